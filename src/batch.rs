@@ -0,0 +1,448 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Headless batch modes
+//!
+//! `lefdef-viewer check --lef tech.lef --lef cells.lef --def top.def
+//! --junit checks.xml` loads the files, runs the design checks and writes the
+//! reports without opening a window. The exit code is 0 when every check
+//! passes, 1 when a check found violations and 2 on usage or load errors.
+//!
+//! `lefdef-viewer export --lef cells.lef --csv cells.csv` runs the File menu
+//! exports instead. The macros of all given LEF files go into one output
+//! per format; the exit code is 0 on success and 2 on errors.
+//!
+//! Only the results are printed to stdout, the readers log to stderr.
+
+use crate::checks::{self, CheckOptions};
+use crate::def::reader::DefReader;
+use crate::export::{self, SpiceStubOptions, VerilogStubOptions, VoltageConfig};
+use crate::lef::reader::LefReader;
+use crate::lef::Lef;
+use crate::voltage_dialog::VoltageDialog;
+use crate::waivers::Waivers;
+use std::path::{Path, PathBuf};
+
+const CHECK_USAGE: &str = "\
+Usage: lefdef-viewer check [OPTIONS]
+
+Options:
+  --lef <FILE>     LEF library to load, may be repeated
+  --def <FILE>     DEF design to check
+  --json <FILE>    Write the report as JSON
+  --junit <FILE>   Write the report as JUnit XML
+  --skip <CHECK>   Disable a check, may be repeated
+  --waivers <FILE> Waived violations, default <design>.waivers.json next to
+                   the DEF
+  --help           Show this help
+
+Checks: missing_cells, net_pins, overlaps, outside_die_area, off_grid_pins,
+        off_track_pins, duplicates, pin_min_area, pin_equivalence,
+        all_angle_geometry";
+
+/// Command line of the batch mode
+#[derive(Debug, Default, PartialEq)]
+struct BatchArgs {
+    lef_files: Vec<String>,
+    def_file: Option<String>,
+    json_output: Option<String>,
+    junit_output: Option<String>,
+    waivers: Option<String>,
+    options: CheckOptions,
+    help: bool,
+}
+
+impl BatchArgs {
+    /// Parse the arguments following `check`
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut parsed = BatchArgs::default();
+        let mut args = args.iter();
+        while let Some(flag) = args.next() {
+            if flag == "--help" {
+                parsed.help = true;
+                continue;
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for {flag}"))?
+                .clone();
+            match flag.as_str() {
+                "--lef" => parsed.lef_files.push(value),
+                "--def" => parsed.def_file = Some(value),
+                "--json" => parsed.json_output = Some(value),
+                "--junit" => parsed.junit_output = Some(value),
+                "--waivers" => parsed.waivers = Some(value),
+                "--skip" => {
+                    if !parsed.options.set(&value, false) {
+                        return Err(format!("unknown check {value}"));
+                    }
+                }
+                _ => return Err(format!("unknown option {flag}")),
+            }
+        }
+        if !parsed.help && parsed.lef_files.is_empty() && parsed.def_file.is_none() {
+            return Err("no LEF or DEF file given".to_string());
+        }
+        Ok(parsed)
+    }
+}
+
+/// Run the batch checks and return the process exit code
+pub fn run_check(args: &[String]) -> i32 {
+    let args = match BatchArgs::parse(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("ERROR: {e}\n\n{CHECK_USAGE}");
+            return 2;
+        }
+    };
+    if args.help {
+        println!("{CHECK_USAGE}");
+        return 0;
+    }
+
+    let Some(libraries) = read_libraries(&args.lef_files) else {
+        return 2;
+    };
+    let def = match &args.def_file {
+        Some(path) => match DefReader::new().read(path) {
+            Ok(def) => Some(def),
+            Err(e) => {
+                eprintln!("ERROR: failed to load DEF {path}: {e}");
+                return 2;
+            }
+        },
+        None => None,
+    };
+
+    let library_refs: Vec<_> = libraries.iter().map(|(path, lef)| (*path, lef)).collect();
+    let mut report = checks::run_checks(def.as_ref(), &library_refs, &args.options);
+
+    let waivers_path = args
+        .waivers
+        .as_ref()
+        .map(PathBuf::from)
+        .or_else(|| args.def_file.as_deref().map(Waivers::path_for));
+    if let Some(path) = waivers_path {
+        match Waivers::load(&path) {
+            Ok(waivers) => waivers.apply(&mut report),
+            Err(e) => {
+                eprintln!("ERROR: {e}");
+                return 2;
+            }
+        }
+    }
+
+    for result in &report.results {
+        let waived = match result.waived.len() {
+            0 => String::new(),
+            count => format!(", {count} waived"),
+        };
+        match &result.skipped {
+            Some(reason) => println!("[SKIP] {}: {reason}", result.name),
+            None if result.passed() => println!("[PASS] {}{waived}", result.name),
+            None => println!(
+                "[FAIL] {}: {} violations{waived}",
+                result.name,
+                result.findings.len()
+            ),
+        }
+    }
+
+    let outputs = [
+        (&args.json_output, report.to_json()),
+        (&args.junit_output, report.to_junit_xml()),
+    ];
+    for (path, text) in outputs {
+        if let Some(path) = path {
+            if let Err(e) = std::fs::write(path, text) {
+                eprintln!("ERROR: failed to write report {path}: {e}");
+                return 2;
+            }
+        }
+    }
+
+    if report.passed() {
+        0
+    } else {
+        1
+    }
+}
+
+const EXPORT_USAGE: &str = "\
+Usage: lefdef-viewer export [OPTIONS]
+
+Options:
+  --lef <FILE>       LEF library to load, may be repeated
+  --csv <FILE>       Write the macro table as CSV
+  --verilog <FILE>   Write a Verilog stub
+  --verilog-flat     Declare every bus bit of the Verilog stub as its own port
+  --lib <FILE>       Write a Liberty stub
+  --spice <FILE>     Write a SPICE stub of empty .SUBCKTs
+  --spice-no-pg      Leave the power and ground pins out of the SPICE stub
+  --pinlist <DIR>    Write one pin list CSV per macro
+  --voltage <V>      Supply voltage of the Liberty stub, default 0.8
+  --help             Show this help";
+
+/// Command line of the export mode
+#[derive(Debug, Default, PartialEq)]
+struct ExportArgs {
+    lef_files: Vec<String>,
+    csv_output: Option<String>,
+    verilog_output: Option<String>,
+    verilog_flat: bool,
+    lib_output: Option<String>,
+    spice_output: Option<String>,
+    spice_no_pg: bool,
+    pinlist_dir: Option<String>,
+    voltage: Option<f32>,
+    help: bool,
+}
+
+impl ExportArgs {
+    /// Parse the arguments following `export`
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut parsed = ExportArgs::default();
+        let mut args = args.iter();
+        while let Some(flag) = args.next() {
+            if flag == "--help" {
+                parsed.help = true;
+                continue;
+            }
+            if flag == "--verilog-flat" {
+                parsed.verilog_flat = true;
+                continue;
+            }
+            if flag == "--spice-no-pg" {
+                parsed.spice_no_pg = true;
+                continue;
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for {flag}"))?
+                .clone();
+            match flag.as_str() {
+                "--lef" => parsed.lef_files.push(value),
+                "--csv" => parsed.csv_output = Some(value),
+                "--verilog" => parsed.verilog_output = Some(value),
+                "--lib" => parsed.lib_output = Some(value),
+                "--spice" => parsed.spice_output = Some(value),
+                "--pinlist" => parsed.pinlist_dir = Some(value),
+                "--voltage" => {
+                    let voltage = value
+                        .parse()
+                        .map_err(|_| format!("invalid voltage {value}"))?;
+                    parsed.voltage = Some(voltage);
+                }
+                _ => return Err(format!("unknown option {flag}")),
+            }
+        }
+        if parsed.help {
+            return Ok(parsed);
+        }
+        if parsed.lef_files.is_empty() {
+            return Err("no LEF file given".to_string());
+        }
+        if parsed.csv_output.is_none()
+            && parsed.verilog_output.is_none()
+            && parsed.lib_output.is_none()
+            && parsed.spice_output.is_none()
+            && parsed.pinlist_dir.is_none()
+        {
+            return Err("no output given".to_string());
+        }
+        Ok(parsed)
+    }
+}
+
+/// Run the batch exports and return the process exit code
+pub fn run_export(args: &[String]) -> i32 {
+    let args = match ExportArgs::parse(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("ERROR: {e}\n\n{EXPORT_USAGE}");
+            return 2;
+        }
+    };
+    if args.help {
+        println!("{EXPORT_USAGE}");
+        return 0;
+    }
+
+    let Some(libraries) = read_libraries(&args.lef_files) else {
+        return 2;
+    };
+    let merged = Lef {
+        macros: libraries
+            .into_iter()
+            .flat_map(|(_, lef)| lef.macros)
+            .collect(),
+        ..Lef::default()
+    };
+
+    let mut failed = false;
+    let mut report =
+        |what: &str, path: &str, result: Result<(), Box<dyn std::error::Error>>| match result {
+            Ok(()) => println!("[PASS] {what} written to {path}"),
+            Err(e) => {
+                eprintln!("ERROR: failed to write {what} {path}: {e}");
+                failed = true;
+            }
+        };
+
+    if let Some(path) = &args.csv_output {
+        report("CSV", path, export::export_lef_to_csv(&merged, path));
+    }
+    if let Some(path) = &args.verilog_output {
+        report(
+            "Verilog stub",
+            path,
+            export::export_verilog_stub_with_options(
+                &merged,
+                path,
+                &file_stem(path),
+                &VerilogStubOptions {
+                    group_buses: !args.verilog_flat,
+                },
+            ),
+        );
+    }
+    if let Some(path) = &args.lib_output {
+        let mut config = VoltageConfig {
+            lib_name: file_stem(path),
+            ..VoltageConfig::default()
+        };
+        VoltageDialog::initialize_config(&merged, &mut config);
+        if let Some(voltage) = args.voltage {
+            config.nom_voltage = voltage;
+            config.power_pins.values_mut().for_each(|v| *v = voltage);
+        }
+        report(
+            "Liberty stub",
+            path,
+            export::export_lib_stub_with_voltage_config(&merged, path, &config),
+        );
+    }
+    if let Some(path) = &args.spice_output {
+        let options = SpiceStubOptions {
+            pg_pins: !args.spice_no_pg,
+        };
+        report(
+            "SPICE stub",
+            path,
+            export::export_spice_stub(&merged, path, &file_stem(path), &options),
+        );
+    }
+    if let Some(dir) = &args.pinlist_dir {
+        let macros: Vec<_> = merged.macros.iter().collect();
+        report(
+            "pin lists",
+            dir,
+            export::export_multiple_cells_pinlist(&macros, dir),
+        );
+    }
+
+    if failed {
+        2
+    } else {
+        0
+    }
+}
+
+/// Load the LEF files, printing the error of the first that fails
+fn read_libraries(paths: &[String]) -> Option<Vec<(&str, Lef)>> {
+    let mut libraries = Vec::new();
+    for path in paths {
+        match LefReader::new().read(path) {
+            Ok(lef) => libraries.push((path.as_str(), lef)),
+            Err(e) => {
+                eprintln!("ERROR: failed to load LEF {path}: {e}");
+                return None;
+            }
+        }
+    }
+    Some(libraries)
+}
+
+fn file_stem(path: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("lef_cells")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_batch_args() {
+        let args = BatchArgs::parse(&strings(&[
+            "--lef",
+            "a.lef",
+            "--lef",
+            "b.lef",
+            "--def",
+            "top.def",
+            "--junit",
+            "out.xml",
+            "--skip",
+            "overlaps",
+            "--waivers",
+            "w.json",
+        ]))
+        .unwrap();
+        assert_eq!(args.lef_files, ["a.lef", "b.lef"]);
+        assert_eq!(args.def_file.as_deref(), Some("top.def"));
+        assert_eq!(args.junit_output.as_deref(), Some("out.xml"));
+        assert_eq!(args.waivers.as_deref(), Some("w.json"));
+        assert!(!args.options.overlaps && args.options.missing_cells);
+
+        assert!(BatchArgs::parse(&strings(&["--skip", "typo", "--def", "x"])).is_err());
+        assert!(BatchArgs::parse(&strings(&["--def"])).is_err());
+        assert!(BatchArgs::parse(&[]).is_err());
+    }
+
+    #[test]
+    fn test_parse_export_args() {
+        let args = ExportArgs::parse(&strings(&[
+            "--lef",
+            "a.lef",
+            "--csv",
+            "out.csv",
+            "--lib",
+            "out.lib",
+            "--voltage",
+            "1.1",
+        ]))
+        .unwrap();
+        assert_eq!(args.lef_files, ["a.lef"]);
+        assert_eq!(args.csv_output.as_deref(), Some("out.csv"));
+        assert_eq!(args.lib_output.as_deref(), Some("out.lib"));
+        assert_eq!(args.voltage, Some(1.1));
+        assert!(!args.verilog_flat);
+        let args = ExportArgs::parse(&strings(&[
+            "--verilog-flat",
+            "--lef",
+            "a.lef",
+            "--verilog",
+            "out.v",
+        ]))
+        .unwrap();
+        assert!(args.verilog_flat);
+        let args = ExportArgs::parse(&strings(&["--lef", "a.lef", "--spice", "out.sp"])).unwrap();
+        assert_eq!(args.spice_output.as_deref(), Some("out.sp"));
+        assert!(!args.spice_no_pg);
+
+        // Nothing to write
+        assert!(ExportArgs::parse(&strings(&["--lef", "a.lef"])).is_err());
+        assert!(ExportArgs::parse(&strings(&["--csv", "out.csv"])).is_err());
+        assert!(ExportArgs::parse(&strings(&["--lef", "a.lef", "--voltage", "high"])).is_err());
+        assert!(ExportArgs::parse(&strings(&["--help"])).unwrap().help);
+    }
+}
@@ -0,0 +1,279 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Canvas camera and DPI-aware drawing sizes
+//!
+//! The pan is an offset in points from the canvas center, so the world
+//! point under the center depends only on zoom and pan: resizing the window
+//! or moving it to a monitor with another scale factor keeps it in place.
+//! Zooming goes through [`Camera::zoom_about`] to keep that property for
+//! the zoom buttons and slider as well.
+//!
+//! A non-finite zoom or pan, e.g. from a corrupt coordinate or a mangled
+//! permalink, would blank the canvas for good; [`Camera::recover`] resets
+//! such a camera so the view can be fitted again.
+//!
+//! The LEF and the DEF view keep a camera each in [`ModeCameras`], so
+//! going from the full chip to a library cell and back returns to the same
+//! place in both.
+//!
+//! Strokes and text are sized in points and snapped to whole device pixels
+//! by [`DpiStyle`], so hairlines stay one pixel wide and crisp at fractional
+//! scale factors.
+
+use eframe::egui;
+
+pub const MIN_ZOOM: f32 = 0.01;
+pub const MAX_ZOOM: f32 = 1000.0;
+
+/// Zoom in points per micron and pan from the canvas center
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    pub zoom: f32,
+    pub pan_x: f32,
+    pub pan_y: f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            pan_x: 0.0,
+            pan_y: 0.0,
+        }
+    }
+}
+
+impl Camera {
+    /// Reset the camera when its zoom or pan is not finite and clamp the
+    /// zoom into range, true when the camera had to be reset
+    pub fn recover(&mut self) -> bool {
+        if !(self.zoom.is_finite() && self.pan_x.is_finite() && self.pan_y.is_finite()) {
+            *self = Self::default();
+            return true;
+        }
+        self.zoom = self.zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        false
+    }
+
+    /// Canvas-space point (microns, Y down) at `offset` from the canvas center
+    pub fn world_at(&self, offset: egui::Vec2) -> egui::Vec2 {
+        egui::vec2(
+            (offset.x - self.pan_x) / self.zoom,
+            (offset.y - self.pan_y) / self.zoom,
+        )
+    }
+
+    /// Zoom by `factor` keeping the point at `offset` from the canvas center
+    /// in place
+    pub fn zoom_about(&mut self, offset: egui::Vec2, factor: f32) {
+        if !(factor.is_finite() && factor > 0.0) {
+            return;
+        }
+        let anchor = self.world_at(offset);
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.pan_x = offset.x - anchor.x * self.zoom;
+        self.pan_y = offset.y - anchor.y * self.zoom;
+    }
+
+    /// Frame the box between `min` and `max`, offsets from the canvas
+    /// center, in a canvas of `size`
+    pub fn zoom_to_box(&mut self, min: egui::Vec2, max: egui::Vec2, size: egui::Vec2) {
+        let extent = (max - min).abs();
+        // Also rejects NaN extents
+        if !(extent.x > 0.0 && extent.y > 0.0 && extent.is_finite()) {
+            return;
+        }
+        let anchor = self.world_at((min + max) * 0.5);
+        let factor = (size.x / extent.x).min(size.y / extent.y);
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.pan_x = -anchor.x * self.zoom;
+        self.pan_y = -anchor.y * self.zoom;
+    }
+
+    /// Change the zoom keeping the canvas center in place
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.zoom_about(egui::Vec2::ZERO, zoom / self.zoom);
+    }
+}
+
+/// Last camera of the LEF view and of the DEF view
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ModeCameras {
+    lef: Option<Camera>,
+    def: Option<Camera>,
+}
+
+impl ModeCameras {
+    fn slot(&mut self, def_mode: bool) -> &mut Option<Camera> {
+        if def_mode {
+            &mut self.def
+        } else {
+            &mut self.lef
+        }
+    }
+
+    /// Remember `camera` as the last camera of a mode
+    pub fn remember(&mut self, def_mode: bool, camera: Camera) {
+        *self.slot(def_mode) = Some(camera);
+    }
+
+    /// Drop the camera of a mode, e.g. when its files are closed
+    pub fn forget(&mut self, def_mode: bool) {
+        *self.slot(def_mode) = None;
+    }
+
+    /// Leave a mode at `current` and return the camera of the other mode,
+    /// None when it has not been shown yet
+    pub fn switch(&mut self, leaving_def: bool, current: Camera) -> Option<Camera> {
+        self.remember(leaving_def, current);
+        *self.slot(!leaving_def)
+    }
+}
+
+/// Drawing sizes for the current scale factor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DpiStyle {
+    pixels_per_point: f32,
+}
+
+impl Default for DpiStyle {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl DpiStyle {
+    /// Style for `pixels_per_point`, 1.0 when it is not a positive number
+    pub fn new(pixels_per_point: f32) -> Self {
+        let pixels_per_point = if pixels_per_point.is_finite() && pixels_per_point > 0.0 {
+            pixels_per_point
+        } else {
+            1.0
+        };
+        Self { pixels_per_point }
+    }
+
+    /// Stroke width in points rounded to whole device pixels, at least one
+    pub fn stroke(&self, points: f32) -> f32 {
+        self.snap(points)
+    }
+
+    /// Font size in points rounded to whole device pixels
+    pub fn font_size(&self, points: f32) -> f32 {
+        self.snap(points)
+    }
+
+    fn snap(&self, points: f32) -> f32 {
+        (points * self.pixels_per_point).round().max(1.0) / self.pixels_per_point
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zoom_keeps_anchor() {
+        let mut camera = Camera {
+            zoom: 2.0,
+            pan_x: 30.0,
+            pan_y: -10.0,
+        };
+        let center = camera.world_at(egui::Vec2::ZERO);
+        camera.set_zoom(8.0);
+        assert!((camera.world_at(egui::Vec2::ZERO) - center).length() < 1e-4);
+
+        let cursor = egui::vec2(120.0, -45.0);
+        let under_cursor = camera.world_at(cursor);
+        camera.zoom_about(cursor, 0.5);
+        assert_eq!(camera.zoom, 4.0);
+        assert!((camera.world_at(cursor) - under_cursor).length() < 1e-4);
+
+        camera.zoom_about(cursor, 1e9);
+        assert_eq!(camera.zoom, MAX_ZOOM);
+    }
+
+    #[test]
+    fn test_zoom_to_box() {
+        let mut camera = Camera {
+            zoom: 1.0,
+            pan_x: 0.0,
+            pan_y: 0.0,
+        };
+        // A 50 x 20 box right of the center in a 200 x 100 canvas
+        let (min, max) = (egui::vec2(50.0, -10.0), egui::vec2(100.0, 10.0));
+        let box_center = camera.world_at((min + max) * 0.5);
+        camera.zoom_to_box(max, min, egui::vec2(200.0, 100.0));
+        assert_eq!(camera.zoom, 4.0);
+        assert!((camera.world_at(egui::Vec2::ZERO) - box_center).length() < 1e-4);
+
+        // A click without a drag leaves the camera alone
+        let before = camera;
+        camera.zoom_to_box(min, min, egui::vec2(200.0, 100.0));
+        assert_eq!(camera, before);
+    }
+
+    #[test]
+    fn test_recover_from_non_finite() {
+        let mut camera = Camera {
+            zoom: f32::NAN,
+            pan_x: 10.0,
+            pan_y: 0.0,
+        };
+        assert!(camera.recover());
+        assert_eq!(camera, Camera::default());
+
+        camera.pan_y = f32::INFINITY;
+        assert!(camera.recover());
+        assert_eq!(camera, Camera::default());
+
+        camera.zoom = 1e9;
+        assert!(!camera.recover());
+        assert_eq!(camera.zoom, MAX_ZOOM);
+
+        // Degenerate requests leave a valid camera alone
+        let before = camera;
+        camera.set_zoom(0.0);
+        camera.zoom_about(egui::vec2(5.0, 5.0), f32::NAN);
+        camera.zoom_to_box(
+            egui::vec2(f32::NAN, 0.0),
+            egui::vec2(10.0, 10.0),
+            egui::vec2(100.0, 100.0),
+        );
+        assert_eq!(camera, before);
+    }
+
+    #[test]
+    fn test_mode_cameras_switch() {
+        let chip = Camera {
+            zoom: 0.05,
+            pan_x: 0.0,
+            pan_y: 0.0,
+        };
+        let cell = Camera {
+            zoom: 80.0,
+            pan_x: -40.0,
+            pan_y: 25.0,
+        };
+        let mut cameras = ModeCameras::default();
+        cameras.remember(false, cell);
+        // Back from the DEF view to the cell, later to the chip
+        assert_eq!(cameras.switch(true, chip), Some(cell));
+        assert_eq!(cameras.switch(false, cell), Some(chip));
+
+        cameras.forget(false);
+        assert_eq!(cameras.switch(true, chip), None);
+    }
+
+    #[test]
+    fn test_sizes_snap_to_device_pixels() {
+        let style = DpiStyle::new(1.5);
+        // 1 pt is 1.5 px, rounded to 2 px
+        assert!((style.stroke(1.0) - 2.0 / 1.5).abs() < 1e-6);
+        // Never thinner than one device pixel
+        assert!((style.stroke(0.1) - 1.0 / 1.5).abs() < 1e-6);
+        assert_eq!(DpiStyle::new(2.0).font_size(8.2), 8.0);
+        assert_eq!(DpiStyle::new(f32::NAN).stroke(1.0), 1.0);
+    }
+}
@@ -0,0 +1,292 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Charts Panel
+//!
+//! Histograms for quick QC of a library and a design: macro widths and
+//! heights, instance counts per macro and net fanout. Clicking a bar selects
+//! the objects counted in it.
+
+use crate::def::Def;
+use crate::lef::LefMacro;
+use eframe::egui;
+use egui_plot::{Bar, BarChart, Plot};
+use std::collections::{BTreeMap, HashSet};
+
+/// Bars of a value histogram
+const HISTOGRAM_BUCKETS: usize = 20;
+/// Macros shown in the instance count chart, most used first
+const MAX_MACRO_BARS: usize = 40;
+
+/// One bar of a chart and the objects counted in it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bucket {
+    /// Hover label, e.g. the value range or the macro name
+    pub label: String,
+    /// Names of the counted objects
+    pub members: Vec<String>,
+}
+
+/// Bucket `values` into at most `bucket_count` equal-width ranges
+///
+/// Integer values such as fanout get one bucket per value when the range is
+/// small enough, so a bar never mixes neighbouring counts.
+pub fn histogram(values: &[(String, f64)], bucket_count: usize, integer: bool) -> Vec<Bucket> {
+    let finite: Vec<&(String, f64)> = values.iter().filter(|(_, v)| v.is_finite()).collect();
+    let Some(min) = finite.iter().map(|(_, v)| *v).reduce(f64::min) else {
+        return Vec::new();
+    };
+    let max = finite.iter().map(|(_, v)| *v).fold(min, f64::max);
+
+    let bucket_count = bucket_count.max(1);
+    let mut width = (max - min) / bucket_count as f64;
+    if integer {
+        width = width.ceil().max(1.0);
+    }
+    let count = if width > 0.0 {
+        (((max - min) / width).floor() as usize + 1).min(bucket_count)
+    } else {
+        1
+    };
+
+    let mut buckets: Vec<Bucket> = (0..count)
+        .map(|i| {
+            let lo = min + i as f64 * width;
+            let hi = if i + 1 == count { max } else { lo + width };
+            let label = if integer && width == 1.0 {
+                format!("{lo}")
+            } else if integer {
+                format!("{lo}..{}", (lo + width - 1.0).min(max))
+            } else {
+                format!("{lo:.3}..{hi:.3}")
+            };
+            Bucket {
+                label,
+                members: Vec::new(),
+            }
+        })
+        .collect();
+    for (name, value) in finite {
+        let index = if width > 0.0 {
+            (((value - min) / width) as usize).min(count - 1)
+        } else {
+            0
+        };
+        buckets[index].members.push(name.clone());
+    }
+    buckets
+}
+
+/// One bucket per macro with its placed components, most used first
+pub fn instances_per_macro(def: &Def, limit: usize) -> Vec<Bucket> {
+    let mut by_macro: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for component in &def.components {
+        by_macro
+            .entry(component.macro_name.as_str())
+            .or_default()
+            .push(component.name.clone());
+    }
+    let mut buckets: Vec<Bucket> = by_macro
+        .into_iter()
+        .map(|(name, members)| Bucket {
+            label: name.to_string(),
+            members,
+        })
+        .collect();
+    buckets.sort_by_key(|b| std::cmp::Reverse(b.members.len()));
+    buckets.truncate(limit);
+    buckets
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Chart {
+    #[default]
+    MacroWidth,
+    MacroHeight,
+    InstancesPerMacro,
+    NetFanout,
+}
+
+impl Chart {
+    fn label(self) -> &'static str {
+        match self {
+            Chart::MacroWidth => "Macro Width",
+            Chart::MacroHeight => "Macro Height",
+            Chart::InstancesPerMacro => "Instances per Macro",
+            Chart::NetFanout => "Net Fanout",
+        }
+    }
+}
+
+/// Charts panel state and rendering
+#[derive(Default)]
+pub struct ChartsPanel {
+    /// Whether the panel is currently shown
+    pub visible: bool,
+    chart: Chart,
+}
+
+impl ChartsPanel {
+    /// Create a new charts panel
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render the panel
+    ///
+    /// Clicking a bar replaces the matching selection: library cells for the
+    /// macro size charts, components and nets for the DEF charts.
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        macros: &[&LefMacro],
+        def: Option<&Def>,
+        selected_cells: &mut HashSet<String>,
+        selected_components: &mut HashSet<String>,
+        selected_nets: &mut HashSet<String>,
+    ) {
+        if !self.visible {
+            return;
+        }
+
+        let mut open = true;
+        egui::Window::new("Charts")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(480.0)
+            .default_height(360.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    for chart in [
+                        Chart::MacroWidth,
+                        Chart::MacroHeight,
+                        Chart::InstancesPerMacro,
+                        Chart::NetFanout,
+                    ] {
+                        ui.selectable_value(&mut self.chart, chart, chart.label());
+                    }
+                });
+                ui.separator();
+
+                let (buckets, target) = match self.chart {
+                    Chart::MacroWidth | Chart::MacroHeight => {
+                        if macros.is_empty() {
+                            ui.label("Load a LEF file to chart macro sizes.");
+                            return;
+                        }
+                        let width = self.chart == Chart::MacroWidth;
+                        let values: Vec<(String, f64)> = macros
+                            .iter()
+                            .map(|m| (m.name.clone(), if width { m.size_x } else { m.size_y }))
+                            .collect();
+                        (histogram(&values, HISTOGRAM_BUCKETS, false), selected_cells)
+                    }
+                    Chart::InstancesPerMacro => {
+                        let Some(def) = def else {
+                            ui.label("Load a DEF file to chart instance counts.");
+                            return;
+                        };
+                        (
+                            instances_per_macro(def, MAX_MACRO_BARS),
+                            selected_components,
+                        )
+                    }
+                    Chart::NetFanout => {
+                        let Some(def) = def else {
+                            ui.label("Load a DEF file to chart net fanout.");
+                            return;
+                        };
+                        let values: Vec<(String, f64)> = def
+                            .nets
+                            .iter()
+                            .map(|n| (n.name.clone(), n.connections.max(n.instances.len()) as f64))
+                            .collect();
+                        (histogram(&values, HISTOGRAM_BUCKETS, true), selected_nets)
+                    }
+                };
+
+                if buckets.is_empty() {
+                    ui.label("Nothing to chart.");
+                    return;
+                }
+                ui.label("Hover a bar for its range, click it to select what it counts.");
+
+                let bars: Vec<Bar> = buckets
+                    .iter()
+                    .enumerate()
+                    .map(|(i, bucket)| {
+                        Bar::new(i as f64, bucket.members.len() as f64)
+                            .width(0.9)
+                            .name(&bucket.label)
+                    })
+                    .collect();
+                let chart = BarChart::new(bars).color(egui::Color32::from_rgb(33, 150, 243));
+
+                let response = Plot::new(("qc_chart", self.chart.label()))
+                    .allow_scroll(false)
+                    .allow_drag(false)
+                    .allow_zoom(false)
+                    .show_x(false)
+                    .y_axis_label("count")
+                    .show(ui, |plot_ui| {
+                        plot_ui.bar_chart(chart);
+                        if plot_ui.response().clicked() {
+                            plot_ui.pointer_coordinate()
+                        } else {
+                            None
+                        }
+                    });
+
+                if let Some(point) = response.inner {
+                    let index = point.x.round();
+                    if index >= 0.0 && (index as usize) < buckets.len() {
+                        target.clear();
+                        target.extend(buckets[index as usize].members.iter().cloned());
+                    }
+                }
+            });
+
+        if !open {
+            self.visible = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(values: &[f64]) -> Vec<(String, f64)> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (format!("obj{i}"), *v))
+            .collect()
+    }
+
+    #[test]
+    fn test_histogram_buckets() {
+        let buckets = histogram(&values(&[0.0, 1.0, 2.5, 10.0]), 4, false);
+        assert_eq!(buckets.len(), 4);
+        assert_eq!(buckets[0].members, vec!["obj0", "obj1"]);
+        assert_eq!(buckets[1].members, vec!["obj2"]);
+        assert!(buckets[2].members.is_empty());
+        // The maximum lands in the last bucket
+        assert_eq!(buckets[3].members, vec!["obj3"]);
+
+        // A single value still gets a bar
+        let buckets = histogram(&values(&[3.0, 3.0]), 4, false);
+        assert_eq!(buckets.len(), 1);
+        assert_eq!(buckets[0].members.len(), 2);
+        assert!(histogram(&[], 4, false).is_empty());
+    }
+
+    #[test]
+    fn test_integer_histogram() {
+        let buckets = histogram(&values(&[1.0, 2.0, 2.0, 4.0]), 20, true);
+        assert_eq!(buckets.len(), 4);
+        assert_eq!(buckets[1].label, "2");
+        assert_eq!(buckets[1].members.len(), 2);
+        assert!(buckets[2].members.is_empty());
+    }
+}
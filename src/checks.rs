@@ -0,0 +1,947 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Batch design checks
+//!
+//! Runs the viewer checks on a DEF and its LEF libraries without the GUI and
+//! collects the findings in a report that CI pipelines can gate on, written
+//! as JSON or as JUnit XML. A check that lacks its inputs, such as the
+//! off-grid check without a MANUFACTURINGGRID, is reported as skipped.
+
+use crate::def::{Def, DefComponent, DefPin, DefTrack};
+use crate::geometry::{edge_angles, polygon_contains, EdgeAngles, MacroTransform};
+use crate::lef::drive_family::pin_divergences;
+use crate::lef::min_area::{pin_area_violations, technology_min_areas};
+use crate::lef::{Lef, LefMacro};
+use crate::units::UnitsCheck;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// Check names as used in reports and on the command line
+pub const MISSING_CELLS: &str = "missing_cells";
+pub const OVERLAPS: &str = "overlaps";
+pub const OFF_GRID_PINS: &str = "off_grid_pins";
+pub const DUPLICATES: &str = "duplicates";
+pub const PIN_MIN_AREA: &str = "pin_min_area";
+pub const PIN_EQUIVALENCE: &str = "pin_equivalence";
+pub const ALL_ANGLE_GEOMETRY: &str = "all_angle_geometry";
+pub const NET_PINS: &str = "net_pins";
+pub const OUTSIDE_DIE_AREA: &str = "outside_die_area";
+pub const OFF_TRACK_PINS: &str = "off_track_pins";
+
+/// All check names in report order
+pub const CHECK_NAMES: [&str; 10] = [
+    MISSING_CELLS,
+    NET_PINS,
+    OVERLAPS,
+    OUTSIDE_DIE_AREA,
+    OFF_GRID_PINS,
+    OFF_TRACK_PINS,
+    DUPLICATES,
+    PIN_MIN_AREA,
+    PIN_EQUIVALENCE,
+    ALL_ANGLE_GEOMETRY,
+];
+
+/// Which checks to run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckOptions {
+    pub missing_cells: bool,
+    pub overlaps: bool,
+    pub off_grid_pins: bool,
+    pub duplicates: bool,
+    pub pin_min_area: bool,
+    pub pin_equivalence: bool,
+    pub all_angle_geometry: bool,
+    pub net_pins: bool,
+    pub outside_die_area: bool,
+    pub off_track_pins: bool,
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        Self {
+            missing_cells: true,
+            overlaps: true,
+            off_grid_pins: true,
+            duplicates: true,
+            pin_min_area: true,
+            pin_equivalence: true,
+            all_angle_geometry: true,
+            net_pins: true,
+            outside_die_area: true,
+            off_track_pins: true,
+        }
+    }
+}
+
+impl CheckOptions {
+    /// Enable or disable a check by name, false for an unknown name
+    pub fn set(&mut self, name: &str, enabled: bool) -> bool {
+        let flag = match name {
+            MISSING_CELLS => &mut self.missing_cells,
+            OVERLAPS => &mut self.overlaps,
+            OFF_GRID_PINS => &mut self.off_grid_pins,
+            DUPLICATES => &mut self.duplicates,
+            PIN_MIN_AREA => &mut self.pin_min_area,
+            PIN_EQUIVALENCE => &mut self.pin_equivalence,
+            ALL_ANGLE_GEOMETRY => &mut self.all_angle_geometry,
+            NET_PINS => &mut self.net_pins,
+            OUTSIDE_DIE_AREA => &mut self.outside_die_area,
+            OFF_TRACK_PINS => &mut self.off_track_pins,
+            _ => return false,
+        };
+        *flag = enabled;
+        true
+    }
+
+    fn is_enabled(&self, name: &str) -> bool {
+        match name {
+            MISSING_CELLS => self.missing_cells,
+            OVERLAPS => self.overlaps,
+            OFF_GRID_PINS => self.off_grid_pins,
+            DUPLICATES => self.duplicates,
+            PIN_MIN_AREA => self.pin_min_area,
+            PIN_EQUIVALENCE => self.pin_equivalence,
+            ALL_ANGLE_GEOMETRY => self.all_angle_geometry,
+            NET_PINS => self.net_pins,
+            OUTSIDE_DIE_AREA => self.outside_die_area,
+            OFF_TRACK_PINS => self.off_track_pins,
+            _ => false,
+        }
+    }
+}
+
+/// One violation found by a check
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Finding {
+    /// Offending object, e.g. a component or pin name
+    pub object: String,
+    pub message: String,
+    /// Where the violation is in the DEF, in microns, for zooming to it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bounds: Option<(f64, f64, f64, f64)>,
+}
+
+/// Violation accepted by a waiver, see [`crate::waivers`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WaivedFinding {
+    #[serde(flatten)]
+    pub finding: Finding,
+    pub comment: String,
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub findings: Vec<Finding>,
+    /// Findings that do not count as violations
+    pub waived: Vec<WaivedFinding>,
+    /// Why the check could not run, None when it ran
+    pub skipped: Option<String>,
+}
+
+impl CheckResult {
+    pub fn passed(&self) -> bool {
+        self.findings.is_empty()
+    }
+}
+
+/// Findings of all enabled checks on a design
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CheckReport {
+    /// DEF DESIGN name, empty when only LEF libraries were checked
+    pub design: String,
+    pub results: Vec<CheckResult>,
+}
+
+impl CheckReport {
+    /// Whether no check found a violation
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(CheckResult::passed)
+    }
+
+    /// Number of checks with violations
+    pub fn failure_count(&self) -> usize {
+        self.results.iter().filter(|r| !r.passed()).count()
+    }
+
+    /// Number of waived findings over all checks
+    pub fn waived_count(&self) -> usize {
+        self.results.iter().map(|r| r.waived.len()).sum()
+    }
+
+    /// Number of checks that could not run
+    pub fn skipped_count(&self) -> usize {
+        self.results.iter().filter(|r| r.skipped.is_some()).count()
+    }
+
+    /// Pretty-printed JSON report
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("check report serializes to JSON")
+    }
+
+    /// JUnit XML report with one test case per check
+    pub fn to_junit_xml(&self) -> String {
+        let suite = if self.design.is_empty() {
+            "lefdef-viewer"
+        } else {
+            &self.design
+        };
+        let (tests, failures, skipped) = (
+            self.results.len(),
+            self.failure_count(),
+            self.skipped_count(),
+        );
+
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            out,
+            "<testsuites name=\"lefdef-viewer\" tests=\"{tests}\" failures=\"{failures}\" \
+             skipped=\"{skipped}\">"
+        );
+        let _ = writeln!(
+            out,
+            "  <testsuite name=\"{}\" tests=\"{tests}\" failures=\"{failures}\" \
+             skipped=\"{skipped}\">",
+            xml_escape(suite)
+        );
+        for result in &self.results {
+            let _ = write!(
+                out,
+                "    <testcase classname=\"{}\" name=\"{}\"",
+                xml_escape(suite),
+                result.name
+            );
+            if let Some(reason) = &result.skipped {
+                let _ = writeln!(out, ">");
+                let _ = writeln!(out, "      <skipped message=\"{}\"/>", xml_escape(reason));
+                out.push_str("    </testcase>\n");
+            } else if result.passed() {
+                out.push_str("/>\n");
+            } else {
+                let _ = writeln!(out, ">");
+                let _ = write!(
+                    out,
+                    "      <failure type=\"{}\" message=\"{} violations\">",
+                    result.name,
+                    result.findings.len()
+                );
+                for finding in &result.findings {
+                    let _ = write!(
+                        out,
+                        "\n{}: {}",
+                        xml_escape(&finding.object),
+                        xml_escape(&finding.message)
+                    );
+                }
+                out.push_str("\n      </failure>\n    </testcase>\n");
+            }
+        }
+        out.push_str("  </testsuite>\n</testsuites>\n");
+        out
+    }
+}
+
+/// Run the enabled checks on `def` and the (file, LEF) libraries
+pub fn run_checks(
+    def: Option<&Def>,
+    libraries: &[(&str, &Lef)],
+    options: &CheckOptions,
+) -> CheckReport {
+    let macros: HashMap<&str, &LefMacro> = libraries
+        .iter()
+        .flat_map(|(_, lef)| &lef.macros)
+        .map(|m| (m.name.as_str(), m))
+        .collect();
+    let db_units = UnitsCheck::new(
+        def.and_then(|d| d.units_distance_microns),
+        libraries.iter().find_map(|(_, lef)| lef.database_microns),
+    )
+    .db_units();
+    let grid = libraries
+        .iter()
+        .find_map(|(_, lef)| lef.manufacturing_grid)
+        .filter(|g| g.is_finite() && *g > 0.0);
+
+    let results = CHECK_NAMES
+        .iter()
+        .filter(|name| options.is_enabled(name))
+        .map(|&name| {
+            let outcome = match (name, def) {
+                (DUPLICATES, _) => Ok(duplicates(def, libraries)),
+                (PIN_MIN_AREA, _) => pin_min_area(libraries),
+                (PIN_EQUIVALENCE, _) => Ok(pin_equivalence(libraries)),
+                (ALL_ANGLE_GEOMETRY, _) => Ok(all_angle_geometry(def, libraries)),
+                (_, None) => Err("no DEF loaded".to_string()),
+                (MISSING_CELLS, Some(def)) => Ok(missing_cells(def, &macros, db_units)),
+                (NET_PINS, Some(def)) => Ok(net_pins(def, &macros, db_units)),
+                (OVERLAPS, Some(def)) => Ok(overlaps(def, &macros, db_units)),
+                (OUTSIDE_DIE_AREA, Some(def)) => outside_die_area(def, &macros, db_units),
+                (OFF_TRACK_PINS, Some(def)) => off_track_pins(def, db_units),
+                (_, Some(def)) => match grid {
+                    Some(grid) => Ok(off_grid_pins(def, grid * db_units, db_units)),
+                    None => Err("no MANUFACTURINGGRID in the LEF libraries".to_string()),
+                },
+            };
+            match outcome {
+                Ok(findings) => CheckResult {
+                    name,
+                    findings,
+                    waived: Vec::new(),
+                    skipped: None,
+                },
+                Err(reason) => CheckResult {
+                    name,
+                    findings: Vec::new(),
+                    waived: Vec::new(),
+                    skipped: Some(reason),
+                },
+            }
+        })
+        .collect();
+
+    CheckReport {
+        design: def.map(|d| d.design.clone()).unwrap_or_default(),
+        results,
+    }
+}
+
+/// Pin shapes below the AREA rule of their layer
+fn pin_min_area(libraries: &[(&str, &Lef)]) -> Result<Vec<Finding>, String> {
+    let min_areas = technology_min_areas(libraries.iter().map(|(_, lef)| *lef));
+    if min_areas.is_empty() {
+        return Err("no layer AREA rules in the LEF libraries".to_string());
+    }
+    Ok(pin_area_violations(libraries, &min_areas)
+        .into_iter()
+        .map(|v| Finding {
+            object: format!("{}/{}", v.macro_name, v.pin_name),
+            message: format!(
+                "{} shape of {:.4} um² is below the minimum area {:.4} um² ({})",
+                v.layer, v.area, v.min_area, v.file
+            ),
+            bounds: None,
+        })
+        .collect())
+}
+
+/// Pins that differ between drive strengths of one cell
+fn pin_equivalence(libraries: &[(&str, &Lef)]) -> Vec<Finding> {
+    pin_divergences(libraries.iter().flat_map(|(_, lef)| &lef.macros))
+        .into_iter()
+        .map(|d| Finding {
+            object: format!("{}/{}", d.macro_name, d.pin_name),
+            message: d.message,
+            bounds: None,
+        })
+        .collect()
+}
+
+/// Shapes with edges that are neither orthogonal nor 45-degree: LEF
+/// polygons, and the DIEAREA, wiring and via polygons of the DEF
+///
+/// Such all-angle geometry renders fine, but GDS flows and many rule decks
+/// reject it. Each object and layer is reported once.
+fn all_angle_geometry(def: Option<&Def>, libraries: &[(&str, &Lef)]) -> Vec<Finding> {
+    let mut findings: Vec<Finding> = Vec::new();
+    let mut report = |object: String, shape: String, points: &[(f64, f64)], closed: bool| {
+        if edge_angles(points, closed) != EdgeAngles::AllAngle {
+            return;
+        }
+        let message = format!("{shape} has edges that are neither orthogonal nor 45-degree");
+        if !findings
+            .iter()
+            .any(|f| f.object == object && f.message == message)
+        {
+            findings.push(Finding {
+                object,
+                message,
+                bounds: None,
+            });
+        }
+    };
+
+    for macro_def in libraries.iter().flat_map(|(_, lef)| &lef.macros) {
+        for pin in &macro_def.pins {
+            for polygon in pin.ports.iter().flat_map(|port| &port.polygons) {
+                let object = format!("{}/{}", macro_def.name, pin.name);
+                report(
+                    object,
+                    format!("{} polygon", polygon.layer),
+                    &polygon.points,
+                    true,
+                );
+            }
+        }
+        for polygon in macro_def.obs.iter().flat_map(|obs| &obs.polygons) {
+            let object = format!("{}/OBS", macro_def.name);
+            report(
+                object,
+                format!("{} polygon", polygon.layer),
+                &polygon.points,
+                true,
+            );
+        }
+    }
+
+    let Some(def) = def else {
+        return findings;
+    };
+    if def.die_area_points.len() > 2 {
+        report(
+            "DIEAREA".to_string(),
+            "die area".to_string(),
+            &def.die_area_points,
+            true,
+        );
+    }
+    let nets = def.nets.iter().map(|n| (&n.name, &n.routes));
+    let special_nets = def.special_nets.iter().map(|n| (&n.name, &n.routes));
+    for (name, routes) in nets.chain(special_nets) {
+        for route in routes {
+            let points: Vec<(f64, f64)> = route.points.iter().map(|p| (p.x, p.y)).collect();
+            report(
+                name.clone(),
+                format!("{} wiring", route.layer),
+                &points,
+                false,
+            );
+        }
+    }
+    for via in &def.vias {
+        for layer in &via.layers {
+            for polygon in &layer.polygons {
+                let shape = format!("{} polygon", layer.layer_name);
+                report(via.name.clone(), shape, &polygon.points, true);
+            }
+        }
+    }
+    findings
+}
+
+/// Location of a placed component in microns, None when unplaced
+fn placed_location(component: &DefComponent, db_units: f64) -> Option<(f64, f64)> {
+    let placement = component.placement.as_ref()?;
+    (placement.placement_type != "UNPLACED")
+        .then(|| (placement.x / db_units, placement.y / db_units))
+}
+
+/// Outline of a placed component in microns, None when unplaced
+fn placed_bbox(
+    component: &DefComponent,
+    macro_def: &LefMacro,
+    db_units: f64,
+) -> Option<(f64, f64, f64, f64)> {
+    let location = placed_location(component, db_units)?;
+    let orientation = &component.placement.as_ref()?.orientation;
+    Some(MacroTransform::new(macro_def, location, orientation).outline_bbox())
+}
+
+/// Components whose macro is in none of the libraries
+fn missing_cells(def: &Def, macros: &HashMap<&str, &LefMacro>, db_units: f64) -> Vec<Finding> {
+    def.components
+        .iter()
+        .filter(|c| !macros.contains_key(c.macro_name.as_str()))
+        .map(|c| Finding {
+            object: c.name.clone(),
+            message: format!("macro {} not found in the LEF libraries", c.macro_name),
+            bounds: placed_location(c, db_units).map(|(x, y)| (x, y, x, y)),
+        })
+        .collect()
+}
+
+/// Net connections to components that are not defined, or to pins their
+/// macro does not have
+///
+/// Connections to top-level pins are not checked, and components of missing
+/// macros are left to the missing cells check.
+fn net_pins(def: &Def, macros: &HashMap<&str, &LefMacro>, db_units: f64) -> Vec<Finding> {
+    let components: HashMap<&str, &DefComponent> = def
+        .components
+        .iter()
+        .map(|c| (c.name.as_str(), c))
+        .collect();
+    let mut findings = Vec::new();
+    for net in &def.nets {
+        for (instance, pin) in net.instances.iter().zip(&net.instance_pins) {
+            if instance == "PIN" || instance == "*" {
+                continue;
+            }
+            let Some(component) = components.get(instance.as_str()) else {
+                findings.push(Finding {
+                    object: instance.clone(),
+                    message: format!("net {} connects to an undefined component", net.name),
+                    bounds: None,
+                });
+                continue;
+            };
+            let Some(macro_def) = macros.get(component.macro_name.as_str()) else {
+                continue;
+            };
+            if !macro_def.pins.iter().any(|p| p.name == *pin) {
+                findings.push(Finding {
+                    object: instance.clone(),
+                    message: format!(
+                        "net {} connects to pin {pin}, which macro {} does not have",
+                        net.name, macro_def.name
+                    ),
+                    bounds: placed_bbox(component, macro_def, db_units),
+                });
+            }
+        }
+    }
+    findings
+}
+
+/// Pairs of placed components whose outlines overlap
+///
+/// COVER components sit above the core and are not checked. Outlines that
+/// only touch do not overlap.
+fn overlaps(def: &Def, macros: &HashMap<&str, &LefMacro>, db_units: f64) -> Vec<Finding> {
+    let mut boxes: Vec<_> = def
+        .components
+        .iter()
+        .filter(|c| !c.is_cover())
+        .filter_map(|c| {
+            let macro_def = macros.get(c.macro_name.as_str())?;
+            Some((c.name.as_str(), placed_bbox(c, macro_def, db_units)?))
+        })
+        .collect();
+    boxes.sort_by(|a, b| a.1 .0.total_cmp(&b.1 .0));
+
+    // Sweep along x, keeping the boxes that still reach the sweep line
+    let mut findings = Vec::new();
+    let mut active: Vec<usize> = Vec::new();
+    for (i, (name, (min_x, min_y, max_x, max_y))) in boxes.iter().enumerate() {
+        active.retain(|&j| boxes[j].1 .2 > *min_x);
+        for &j in &active {
+            let (other, (other_min_x, other_min_y, other_max_x, other_max_y)) = boxes[j];
+            if *min_y < other_max_y && other_min_y < *max_y {
+                findings.push(Finding {
+                    object: other.to_string(),
+                    message: format!("overlaps {name}"),
+                    bounds: Some((
+                        min_x.max(other_min_x),
+                        min_y.max(other_min_y),
+                        max_x.min(other_max_x),
+                        max_y.min(other_max_y),
+                    )),
+                });
+            }
+        }
+        active.push(i);
+    }
+    findings
+}
+
+/// Placed components whose outline is not inside the DIEAREA
+///
+/// The outline corners must lie within the die area; outlines on its
+/// boundary are inside.
+fn outside_die_area(
+    def: &Def,
+    macros: &HashMap<&str, &LefMacro>,
+    db_units: f64,
+) -> Result<Vec<Finding>, String> {
+    let mut die: Vec<(f64, f64)> = def
+        .die_area_points
+        .iter()
+        .map(|(x, y)| (x / db_units, y / db_units))
+        .collect();
+    if let [(x1, y1), (x2, y2)] = die[..] {
+        let (xl, xh) = (x1.min(x2), x1.max(x2));
+        let (yl, yh) = (y1.min(y2), y1.max(y2));
+        die = vec![(xl, yl), (xh, yl), (xh, yh), (xl, yh)];
+    }
+    if die.len() < 3 {
+        return Err("no DIEAREA in the DEF".to_string());
+    }
+
+    // Corners pulled inwards so that outlines on the boundary count as inside
+    const INSET: f64 = 1e-6;
+    let findings = def
+        .components
+        .iter()
+        .filter_map(|c| {
+            let macro_def = macros.get(c.macro_name.as_str())?;
+            let bbox = placed_bbox(c, macro_def, db_units)?;
+            let (xl, yl, xh, yh) = bbox;
+            let corners = [
+                (xl + INSET, yl + INSET),
+                (xh - INSET, yl + INSET),
+                (xh - INSET, yh - INSET),
+                (xl + INSET, yh - INSET),
+            ];
+            (!corners.iter().all(|&corner| polygon_contains(&die, corner))).then(|| Finding {
+                object: c.name.clone(),
+                message: "outline extends outside the DIEAREA".to_string(),
+                bounds: Some(bbox),
+            })
+        })
+        .collect();
+    Ok(findings)
+}
+
+/// Placed top-level pins off the manufacturing grid, `grid` in DBU
+fn off_grid_pins(def: &Def, grid: f64, db_units: f64) -> Vec<Finding> {
+    let on_grid = |value: f64| {
+        let steps = value / grid;
+        (steps - steps.round()).abs() < 1e-6
+    };
+    def.pins
+        .iter()
+        .filter(|p| !p.orient.is_empty())
+        .filter(|p| !on_grid(p.x) || !on_grid(p.y))
+        .map(|p| Finding {
+            object: p.name.clone(),
+            message: format!("location ( {} {} ) is off the {grid} DBU grid", p.x, p.y),
+            bounds: Some(pin_bounds(p, db_units)),
+        })
+        .collect()
+}
+
+/// Placed top-level pins on neither an X nor a Y track of their layer
+///
+/// Pins on layers without TRACKS are not checked.
+fn off_track_pins(def: &Def, db_units: f64) -> Result<Vec<Finding>, String> {
+    if def.tracks_x.is_empty() && def.tracks_y.is_empty() {
+        return Err("no TRACKS in the DEF".to_string());
+    }
+    let on_track = |value: f64, track: &DefTrack| {
+        if track.num <= 0 || track.step <= 0.0 {
+            return false;
+        }
+        let steps = (value - track.offset) / track.step;
+        let index = steps.round();
+        (steps - index).abs() < 1e-6 && index >= 0.0 && index < f64::from(track.num)
+    };
+
+    let mut findings = Vec::new();
+    for pin in def.pins.iter().filter(|p| !p.orient.is_empty()) {
+        let Some(layer) = pin.placed_rects().into_iter().next().map(|r| r.layer) else {
+            continue;
+        };
+        let mut tracks_x = def.tracks_x.iter().filter(|t| t.layer == layer).peekable();
+        let mut tracks_y = def.tracks_y.iter().filter(|t| t.layer == layer).peekable();
+        if tracks_x.peek().is_none() && tracks_y.peek().is_none() {
+            continue;
+        }
+        let on_x = tracks_x.any(|t| on_track(pin.x, t));
+        if !on_x && !tracks_y.any(|t| on_track(pin.y, t)) {
+            findings.push(Finding {
+                object: pin.name.clone(),
+                message: format!("location ( {} {} ) is off the {layer} tracks", pin.x, pin.y),
+                bounds: Some(pin_bounds(pin, db_units)),
+            });
+        }
+    }
+    Ok(findings)
+}
+
+/// Placement point of a top-level pin in microns
+fn pin_bounds(pin: &DefPin, db_units: f64) -> (f64, f64, f64, f64) {
+    let (x, y) = (pin.x / db_units, pin.y / db_units);
+    (x, y, x, y)
+}
+
+/// Names defined more than once: DEF components, pins and nets, and macros
+/// across the LEF libraries
+fn duplicates(def: Option<&Def>, libraries: &[(&str, &Lef)]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut report = |kind: &str, names: &mut dyn Iterator<Item = (&str, &str)>| {
+        let mut first_seen: HashMap<&str, &str> = HashMap::new();
+        let mut reported = HashSet::new();
+        for (name, origin) in names {
+            match first_seen.get(name) {
+                Some(first) if reported.insert(name.to_string()) => findings.push(Finding {
+                    object: name.to_string(),
+                    message: format!("{kind} defined more than once ({first}, {origin})"),
+                    bounds: None,
+                }),
+                Some(_) => {}
+                None => {
+                    first_seen.insert(name, origin);
+                }
+            }
+        }
+    };
+
+    if let Some(def) = def {
+        report(
+            "component",
+            &mut def
+                .components
+                .iter()
+                .map(|c| (c.name.as_str(), "COMPONENTS")),
+        );
+        report(
+            "pin",
+            &mut def.pins.iter().map(|p| (p.name.as_str(), "PINS")),
+        );
+        report(
+            "net",
+            &mut def.nets.iter().map(|n| (n.name.as_str(), "NETS")),
+        );
+    }
+    report(
+        "macro",
+        &mut libraries
+            .iter()
+            .flat_map(|(file, lef)| lef.macros.iter().map(move |m| (m.name.as_str(), *file))),
+    );
+    findings
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::def::DefComponent;
+    use crate::test_fixtures;
+
+    fn cell(name: &str) -> LefMacro {
+        test_fixtures::cell(name, 2.0, 1.0, Vec::new())
+    }
+
+    fn component(name: &str, macro_name: &str, x: f64) -> DefComponent {
+        test_fixtures::component(name, macro_name, x, 0.0, "N")
+    }
+
+    #[test]
+    fn test_design_checks() {
+        let lef = Lef {
+            macros: vec![cell("INV")],
+            database_microns: Some(1000.0),
+            manufacturing_grid: None,
+            layers: Vec::new(),
+            sites: Vec::new(),
+        };
+        let def = Def {
+            design: "top".to_string(),
+            components: vec![
+                component("u1", "INV", 0.0),
+                // Touches u1, overlaps u3
+                component("u2", "INV", 2000.0),
+                component("u3", "INV", 3000.0),
+                component("u4", "NAND", 9000.0),
+                component("u1", "INV", 20000.0),
+            ],
+            ..Def::default()
+        };
+        let report = run_checks(Some(&def), &[("cells.lef", &lef)], &CheckOptions::default());
+        let result = |name| report.results.iter().find(|r| r.name == name).unwrap();
+
+        assert_eq!(result(MISSING_CELLS).findings[0].object, "u4");
+        let overlaps = &result(OVERLAPS).findings;
+        assert_eq!(overlaps.len(), 1);
+        assert_eq!(overlaps[0].object, "u2");
+        assert!(result(OFF_GRID_PINS).skipped.is_some());
+        assert_eq!(result(DUPLICATES).findings[0].object, "u1");
+        // Without layer AREA rules there is nothing to measure pins against
+        assert!(result(PIN_MIN_AREA).skipped.is_some());
+        // A single drive strength has nothing to compare against
+        assert!(result(PIN_EQUIVALENCE).passed());
+        assert!(result(ALL_ANGLE_GEOMETRY).passed());
+        assert!(!report.passed());
+        assert_eq!(report.failure_count(), 3);
+    }
+
+    #[test]
+    fn test_net_pins_die_area_and_tracks() {
+        let mut inv = cell("INV");
+        inv.pins = vec![crate::lef::LefPin {
+            name: "A".to_string(),
+            direction: "INPUT".to_string(),
+            use_type: "SIGNAL".to_string(),
+            shape: String::new(),
+            ports: Vec::new(),
+        }];
+        let lef = Lef {
+            macros: vec![inv],
+            database_microns: Some(1000.0),
+            ..Lef::default()
+        };
+        let net = |name: &str, connections: &[(&str, &str)]| crate::def::DefNet {
+            name: name.to_string(),
+            connections: connections.len(),
+            pins: 0,
+            use_type: String::new(),
+            weight: None,
+            source: String::new(),
+            pattern: String::new(),
+            shielded: false,
+            shield_nets: Vec::new(),
+            instances: connections.iter().map(|c| c.0.to_string()).collect(),
+            instance_pins: connections.iter().map(|c| c.1.to_string()).collect(),
+            routes: Vec::new(),
+        };
+        let pin = |name: &str, x: f64| DefPin {
+            name: name.to_string(),
+            net: String::new(),
+            use_type: String::new(),
+            status: "PLACED".to_string(),
+            direction: "INPUT".to_string(),
+            orient: "N".to_string(),
+            x,
+            y: 0.0,
+            rects: vec![crate::def::DefRect {
+                layer: "M2".to_string(),
+                xl: -50.0,
+                yl: 0.0,
+                xh: 50.0,
+                yh: 100.0,
+            }],
+            ports: Vec::new(),
+        };
+        let def = Def {
+            die_area_points: vec![(0.0, 0.0), (10000.0, 5000.0)],
+            components: vec![
+                component("u1", "INV", 0.0),
+                // Reaches past the right die edge
+                component("u2", "INV", 9000.0),
+            ],
+            nets: vec![
+                net("n1", &[("u1", "A"), ("PIN", "in")]),
+                net("n2", &[("u1", "Z"), ("u9", "A")]),
+            ],
+            pins: vec![pin("in", 200.0), pin("out", 250.0)],
+            tracks_x: vec![DefTrack {
+                layer: "M2".to_string(),
+                offset: 100.0,
+                num: 50,
+                step: 100.0,
+            }],
+            ..Def::default()
+        };
+        let report = run_checks(Some(&def), &[("cells.lef", &lef)], &CheckOptions::default());
+        let result = |name| report.results.iter().find(|r| r.name == name).unwrap();
+
+        let net_pins = &result(NET_PINS).findings;
+        assert_eq!(net_pins.len(), 2);
+        assert_eq!(
+            net_pins[0].message,
+            "net n2 connects to pin Z, which macro INV does not have"
+        );
+        assert_eq!(net_pins[0].bounds, Some((0.0, 0.0, 2.0, 1.0)));
+        assert_eq!(net_pins[1].object, "u9");
+
+        let outside = &result(OUTSIDE_DIE_AREA).findings;
+        assert_eq!(outside.len(), 1);
+        assert_eq!(outside[0].object, "u2");
+
+        let off_track = &result(OFF_TRACK_PINS).findings;
+        assert_eq!(off_track.len(), 1);
+        assert_eq!(off_track[0].object, "out");
+        assert_eq!(off_track[0].bounds, Some((0.25, 0.0, 0.25, 0.0)));
+    }
+
+    #[test]
+    fn test_all_angle_geometry() {
+        let polygon = |points: Vec<(f64, f64)>| crate::lef::LefPolygon {
+            layer: "M1".to_string(),
+            points,
+            is_hole: false,
+        };
+        let mut diagonal = cell("DIAG");
+        diagonal.obs = vec![crate::lef::LefObstruction {
+            rects: Vec::new(),
+            polygons: vec![polygon(vec![(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)])],
+        }];
+        let mut skewed = cell("SKEW");
+        skewed.obs = vec![crate::lef::LefObstruction {
+            rects: Vec::new(),
+            polygons: vec![
+                polygon(vec![(0.0, 0.0), (1.0, 0.0), (1.5, 1.0), (0.5, 1.0)]),
+                polygon(vec![(0.0, 0.0), (1.0, 0.0), (0.0, 2.0)]),
+            ],
+        }];
+        let lef = Lef {
+            macros: vec![diagonal, skewed],
+            ..Lef::default()
+        };
+        let def: Def = serde_json::from_value(serde_json::json!({
+            "die_area_points": [[0.0, 0.0], [1000.0, 0.0], [1000.0, 500.0], [500.0, 1000.0], [0.0, 1000.0]],
+            "g_cell_grid_x": [], "g_cell_grid_y": [], "pins": [], "special_nets": [],
+            "components": [], "rows": [], "tracks_x": [], "tracks_y": [], "vias": [],
+            "nets": [{
+                "name": "n1", "connections": 0, "pins": 0, "use_type": "", "weight": null,
+                "source": "", "pattern": "", "shielded": false, "instances": [], "instance_pins": [],
+                "routes": [{
+                    "layer": "M2", "width": 0.0, "routing_type": "ROUTED", "shape": null,
+                    "vias": [], "mask": null, "style": null,
+                    "points": [
+                        {"x": 0.0, "y": 0.0, "ext": null},
+                        {"x": 100.0, "y": 100.0, "ext": null},
+                        {"x": 300.0, "y": 200.0, "ext": null}
+                    ]
+                }]
+            }]
+        }))
+        .unwrap();
+
+        let report = run_checks(Some(&def), &[("cells.lef", &lef)], &CheckOptions::default());
+        let result = report
+            .results
+            .iter()
+            .find(|r| r.name == ALL_ANGLE_GEOMETRY)
+            .unwrap();
+        let objects: Vec<&str> = result.findings.iter().map(|f| f.object.as_str()).collect();
+        // One finding for both skewed polygons of SKEW, the 45-degree
+        // DIEAREA corner and DIAG pass
+        assert_eq!(objects, ["SKEW/OBS", "n1"]);
+        assert!(result.findings[1].message.starts_with("M2 wiring"));
+    }
+
+    #[test]
+    fn test_report_formats() {
+        let report = CheckReport {
+            design: "top".to_string(),
+            results: vec![
+                CheckResult {
+                    name: MISSING_CELLS,
+                    findings: vec![Finding {
+                        object: "u<1>".to_string(),
+                        message: "macro X not found".to_string(),
+                        bounds: None,
+                    }],
+                    waived: Vec::new(),
+                    skipped: None,
+                },
+                CheckResult {
+                    name: OVERLAPS,
+                    findings: Vec::new(),
+                    waived: vec![WaivedFinding {
+                        finding: Finding {
+                            object: "u2".to_string(),
+                            message: "overlaps u3".to_string(),
+                            bounds: Some((2.0, 0.0, 3.0, 1.0)),
+                        },
+                        comment: "abutted".to_string(),
+                    }],
+                    skipped: None,
+                },
+            ],
+        };
+
+        let xml = report.to_junit_xml();
+        assert!(xml.contains("tests=\"2\" failures=\"1\" skipped=\"0\""));
+        assert!(xml.contains("<testcase classname=\"top\" name=\"overlaps\"/>"));
+        assert!(xml.contains("u&lt;1&gt;: macro X not found"));
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["results"][0]["findings"][0]["object"], "u<1>");
+        assert_eq!(json["results"][1]["skipped"], serde_json::Value::Null);
+        assert_eq!(json["results"][1]["waived"][0]["object"], "u2");
+        assert_eq!(json["results"][1]["waived"][0]["comment"], "abutted");
+    }
+}
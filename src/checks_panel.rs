@@ -0,0 +1,377 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Checks Panel
+//!
+//! Shows the design checks of the loaded files and the result of
+//! cross-checking the loaded DEF against an imported logical netlist.
+//! Clicking a DEF-side name selects and inspects the component.
+//!
+//! A design check violation can be waived with a comment. Waivers are saved
+//! to the waiver file of the design right away, see [`crate::waivers`].
+
+use crate::checks::{self, CheckOptions, CheckReport, Finding};
+use crate::def::Def;
+use crate::lef::Lef;
+use crate::netlist::{self, CrossCheckReport, LogicalNetlist};
+use crate::waivers::Waivers;
+use eframe::egui;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Rows shown per list before the rest is summarized
+const MAX_LISTED: usize = 500;
+
+/// Checks panel state and rendering
+#[derive(Default)]
+pub struct ChecksPanel {
+    /// Whether the panel is currently shown
+    pub visible: bool,
+    netlist_path: Option<String>,
+    netlist: Option<LogicalNetlist>,
+    report: Option<CrossCheckReport>,
+    /// Design check results with the waivers applied
+    design_report: Option<CheckReport>,
+    /// Whether the loaded files or waivers changed since the checks ran
+    checks_stale: bool,
+    waivers: Waivers,
+    waivers_path: Option<PathBuf>,
+    /// Finding being waived and the comment typed so far
+    waiving: Option<(&'static str, Finding, String)>,
+    waiver_error: Option<String>,
+}
+
+impl ChecksPanel {
+    /// Create a new checks panel
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Import a Verilog or SPEF netlist and check it against `def`
+    pub fn import_netlist(&mut self, path: &str, def: Option<&Def>) -> Result<(), String> {
+        let imported = netlist::read_netlist(path)?;
+        self.netlist_path = Some(path.to_string());
+        self.netlist = Some(imported);
+        self.refresh(def);
+        self.visible = true;
+        Ok(())
+    }
+
+    /// Re-run the cross-check, e.g. after a new DEF was loaded, and the
+    /// design checks when the panel is shown next
+    pub fn refresh(&mut self, def: Option<&Def>) {
+        self.report = match (def, &self.netlist) {
+            (Some(def), Some(netlist)) => Some(netlist::cross_check(def, netlist)),
+            _ => None,
+        };
+        self.checks_stale = true;
+    }
+
+    /// Re-run the design checks when the panel is shown next, e.g. after
+    /// the libraries changed
+    pub fn invalidate_checks(&mut self) {
+        self.checks_stale = true;
+    }
+
+    /// Use the waiver file of `design_file`, the DEF or else the first LEF
+    pub fn set_waiver_file(&mut self, design_file: Option<&str>) -> Result<(), String> {
+        let path = design_file.map(Waivers::path_for);
+        if path == self.waivers_path {
+            return Ok(());
+        }
+        self.waivers = match &path {
+            Some(path) => Waivers::load(path)?,
+            None => Waivers::default(),
+        };
+        self.waivers_path = path;
+        self.waiving = None;
+        self.checks_stale = true;
+        Ok(())
+    }
+
+    /// Waivers of the loaded design
+    pub fn waivers(&self) -> &Waivers {
+        &self.waivers
+    }
+
+    /// Run the design checks again if the loaded files or waivers changed
+    /// since they last ran
+    pub fn update_design_report(&mut self, def: Option<&Def>, libraries: &[(&str, &Lef)]) {
+        if self.checks_stale || self.design_report.is_none() {
+            self.checks_stale = false;
+            self.design_report = (def.is_some() || !libraries.is_empty()).then(|| {
+                let mut report = checks::run_checks(def, libraries, &CheckOptions::default());
+                self.waivers.apply(&mut report);
+                report
+            });
+        }
+    }
+
+    /// Design check results with the waivers applied, as of the last update
+    pub fn design_report(&self) -> Option<&CheckReport> {
+        self.design_report.as_ref()
+    }
+
+    /// Render the panel
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        selected_components: &mut HashSet<String>,
+        inspected_component: &mut Option<String>,
+        def: Option<&Def>,
+        libraries: &[(&str, &Lef)],
+    ) {
+        if !self.visible {
+            return;
+        }
+
+        self.update_design_report(def, libraries);
+
+        let mut open = true;
+        egui::Window::new("Checks")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(420.0)
+            .default_height(450.0)
+            .show(ctx, |ui| {
+                self.render_design_checks(ui);
+                ui.separator();
+
+                let Some(path) = &self.netlist_path else {
+                    ui.label("Import a netlist from the File menu to cross-check DEF names.");
+                    return;
+                };
+                let name = Path::new(path)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or(path);
+                ui.label(format!("Netlist: {name}"));
+
+                let Some(report) = &self.report else {
+                    ui.label("Load a DEF file to run the cross-check.");
+                    return;
+                };
+                ui.label(format!(
+                    "Matched {} instances and {} nets",
+                    report.matched_instances, report.matched_nets
+                ));
+                if report.is_clean() {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(76, 175, 80),
+                        "DEF names match the netlist",
+                    );
+                }
+                ui.separator();
+
+                // Map display names back to raw DEF names for selection
+                let def_names: std::collections::HashMap<String, &str> = def
+                    .map(|def| {
+                        def.components
+                            .iter()
+                            .map(|c| {
+                                (
+                                    crate::identifier::display_name(&c.name, def.bus_bit_chars),
+                                    c.name.as_str(),
+                                )
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let mut select = |name: &str| {
+                    if let Some(raw) = def_names.get(name) {
+                        selected_components.insert(raw.to_string());
+                        *inspected_component = Some(raw.to_string());
+                    }
+                };
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        name_list(
+                            ui,
+                            "Missing instances (in netlist, not in DEF)",
+                            &report.missing_instances,
+                            None,
+                        );
+                        name_list(
+                            ui,
+                            "Extra instances (in DEF, not in netlist)",
+                            &report.extra_instances,
+                            Some(&mut select),
+                        );
+
+                        let mismatches = &report.cell_mismatches;
+                        egui::CollapsingHeader::new(format!(
+                            "Cell mismatches ({})",
+                            mismatches.len()
+                        ))
+                        .id_salt("cell_mismatches")
+                        .show(ui, |ui| {
+                            for mismatch in mismatches.iter().take(MAX_LISTED) {
+                                let text = format!(
+                                    "{}: netlist {}, DEF {}",
+                                    mismatch.instance, mismatch.netlist_cell, mismatch.def_cell
+                                );
+                                if ui.link(text).clicked() {
+                                    select(&mismatch.instance);
+                                }
+                            }
+                            more_label(ui, mismatches.len());
+                        });
+
+                        name_list(
+                            ui,
+                            "Missing nets (in netlist, not in DEF)",
+                            &report.missing_nets,
+                            None,
+                        );
+                        name_list(
+                            ui,
+                            "Extra nets (in DEF, not in netlist)",
+                            &report.extra_nets,
+                            None,
+                        );
+                    });
+            });
+
+        if !open {
+            self.visible = false;
+        }
+    }
+
+    /// Design check results with waive and unwaive buttons per finding
+    fn render_design_checks(&mut self, ui: &mut egui::Ui) {
+        let Some(report) = &self.design_report else {
+            ui.label("Load LEF or DEF files to run the design checks.");
+            return;
+        };
+        let failing: usize = report.results.iter().map(|r| r.findings.len()).sum();
+        ui.strong(format!(
+            "Design checks: {failing} violations, {} waived",
+            report.waived_count()
+        ));
+
+        let mut waive = None;
+        let mut unwaive = None;
+        egui::ScrollArea::vertical()
+            .id_salt("design_checks")
+            .max_height(240.0)
+            .show(ui, |ui| {
+                for result in &report.results {
+                    if let Some(reason) = &result.skipped {
+                        ui.weak(format!("{}: skipped, {reason}", result.name));
+                        continue;
+                    }
+                    egui::CollapsingHeader::new(format!(
+                        "{} ({}, {} waived)",
+                        result.name,
+                        result.findings.len(),
+                        result.waived.len()
+                    ))
+                    .id_salt(("design_check", result.name))
+                    .show(ui, |ui| {
+                        for finding in result.findings.iter().take(MAX_LISTED) {
+                            ui.horizontal(|ui| {
+                                if ui.small_button("Waive").clicked() {
+                                    waive = Some((result.name, finding.clone()));
+                                }
+                                ui.label(format!("{}: {}", finding.object, finding.message));
+                            });
+                        }
+                        more_label(ui, result.findings.len());
+                        for waived in result.waived.iter().take(MAX_LISTED) {
+                            let finding = &waived.finding;
+                            ui.horizontal(|ui| {
+                                if ui.small_button("Unwaive").clicked() {
+                                    unwaive = Some((result.name, finding.clone()));
+                                }
+                                ui.weak(format!("{}: {}", finding.object, finding.message))
+                                    .on_hover_text(format!("Waived: {}", waived.comment));
+                            });
+                        }
+                    });
+                }
+            });
+
+        if let Some((check, finding)) = waive {
+            self.waiving = Some((check, finding, String::new()));
+        }
+        if let Some((check, finding)) = unwaive {
+            self.waivers.unwaive(check, &finding);
+            self.save_waivers();
+        }
+
+        let mut confirmed = false;
+        let mut cancelled = false;
+        if let Some((_, finding, comment)) = &mut self.waiving {
+            ui.group(|ui| {
+                ui.label(format!("Waive {}: {}", finding.object, finding.message));
+                ui.add(
+                    egui::TextEdit::singleline(comment)
+                        .hint_text("Why the violation is accepted")
+                        .desired_width(f32::INFINITY),
+                );
+                ui.horizontal(|ui| {
+                    let has_comment = !comment.trim().is_empty();
+                    confirmed = ui
+                        .add_enabled(has_comment, egui::Button::new("Waive"))
+                        .clicked();
+                    cancelled = ui.button("Cancel").clicked();
+                });
+            });
+        }
+        if confirmed {
+            if let Some((check, finding, comment)) = self.waiving.take() {
+                self.waivers.waive(check, &finding, comment.trim());
+                self.save_waivers();
+            }
+        } else if cancelled {
+            self.waiving = None;
+        }
+
+        if let Some(error) = &self.waiver_error {
+            ui.colored_label(egui::Color32::from_rgb(244, 67, 54), error);
+        }
+    }
+
+    /// Write the waivers to the waiver file and re-run the checks
+    fn save_waivers(&mut self) {
+        self.checks_stale = true;
+        self.waiver_error = match &self.waivers_path {
+            Some(path) => self.waivers.save(path).err(),
+            None => Some("No design file to keep the waivers next to".to_string()),
+        };
+    }
+}
+
+/// Collapsible list of names, clickable when `on_click` is given
+fn name_list(
+    ui: &mut egui::Ui,
+    title: &str,
+    names: &[String],
+    mut on_click: Option<&mut dyn FnMut(&str)>,
+) {
+    egui::CollapsingHeader::new(format!("{title} ({})", names.len()))
+        .id_salt(title)
+        .show(ui, |ui| {
+            for name in names.iter().take(MAX_LISTED) {
+                match on_click.as_mut() {
+                    Some(on_click) => {
+                        if ui.link(name).clicked() {
+                            on_click(name);
+                        }
+                    }
+                    None => {
+                        ui.label(name);
+                    }
+                }
+            }
+            more_label(ui, names.len());
+        });
+}
+
+fn more_label(ui: &mut egui::Ui, total: usize) {
+    if total > MAX_LISTED {
+        ui.label(format!("... and {} more", total - MAX_LISTED));
+    }
+}
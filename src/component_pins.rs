@@ -0,0 +1,335 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Component pin service
+//!
+//! Resolves the connections of a DEF net, `( inst pin )` or `( PIN name )`,
+//! to world locations and signal roles. Instance pins are looked up in the
+//! LEF macro of the placed component, so the location is the center of the
+//! first pin shape after ORIGIN and orientation are applied and the role
+//! follows the LEF DIRECTION. Top-level pins use the DEF PIN placement; their
+//! DIRECTION is seen from outside the design, so an INPUT pin drives the net.
+
+use crate::def::{Def, DefComponent, DefNet, DefPin};
+use crate::geometry::MacroTransform;
+use crate::lef::{LefMacro, LefPin};
+use std::collections::HashMap;
+
+/// Part a pin plays in the signal flow of a net
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignalRole {
+    Driver,
+    Sink,
+    /// INOUT and FEEDTHRU pins
+    Bidirectional,
+    /// No direction known, e.g. the macro is not loaded
+    Unknown,
+}
+
+impl SignalRole {
+    /// Role of an instance pin from its LEF DIRECTION, e.g. "OUTPUT TRISTATE"
+    pub fn from_lef_direction(direction: &str) -> Self {
+        match direction.split_whitespace().next().unwrap_or_default() {
+            "OUTPUT" => SignalRole::Driver,
+            "INPUT" => SignalRole::Sink,
+            "INOUT" | "FEEDTHRU" => SignalRole::Bidirectional,
+            _ => SignalRole::Unknown,
+        }
+    }
+
+    /// Role of a top-level DEF pin, which is reversed from the inside
+    pub fn from_top_pin_direction(direction: &str) -> Self {
+        match direction {
+            "INPUT" => SignalRole::Driver,
+            "OUTPUT" => SignalRole::Sink,
+            "INOUT" | "FEEDTHRU" => SignalRole::Bidirectional,
+            _ => SignalRole::Unknown,
+        }
+    }
+}
+
+/// A net connection resolved to a world location in microns
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResolvedPin {
+    pub position: (f64, f64),
+    pub role: SignalRole,
+}
+
+/// Airwire between two pins of a net
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlightLine {
+    pub from: (f64, f64),
+    pub to: (f64, f64),
+    /// Whether `from` drives `to`, drawn with an arrowhead at `to`
+    pub directed: bool,
+}
+
+/// Lookup of placed components, top-level pins and LEF macros
+pub struct ComponentPins<'a> {
+    components: HashMap<&'a str, &'a DefComponent>,
+    top_pins: HashMap<&'a str, &'a DefPin>,
+    macros: HashMap<&'a str, &'a LefMacro>,
+    db_units: f64,
+}
+
+impl<'a> ComponentPins<'a> {
+    /// Index `def` and the loaded `macros`; `db_units` converts DEF
+    /// coordinates to microns
+    pub fn new(
+        def: &'a Def,
+        macros: impl IntoIterator<Item = &'a LefMacro>,
+        db_units: f64,
+    ) -> Self {
+        Self {
+            components: def
+                .components
+                .iter()
+                .map(|c| (c.name.as_str(), c))
+                .collect(),
+            top_pins: def.pins.iter().map(|p| (p.name.as_str(), p)).collect(),
+            macros: macros.into_iter().map(|m| (m.name.as_str(), m)).collect(),
+            db_units,
+        }
+    }
+
+    /// Resolve one connection, None when the instance is unknown or unplaced
+    pub fn resolve(&self, instance: &str, pin: &str) -> Option<ResolvedPin> {
+        if instance == "PIN" {
+            let top_pin = self.top_pins.get(pin)?;
+            return Some(ResolvedPin {
+                position: (top_pin.x / self.db_units, top_pin.y / self.db_units),
+                role: SignalRole::from_top_pin_direction(&top_pin.direction),
+            });
+        }
+
+        let component = self.components.get(instance)?;
+        let placement = component.placement.as_ref()?;
+        let location = (placement.x / self.db_units, placement.y / self.db_units);
+        let Some(macro_def) = self.macros.get(component.macro_name.as_str()) else {
+            return Some(ResolvedPin {
+                position: location,
+                role: SignalRole::Unknown,
+            });
+        };
+
+        let xform = MacroTransform::new(macro_def, location, &placement.orientation);
+        let macro_pin = macro_def.pins.iter().find(|p| p.name == pin);
+        let position = match macro_pin.and_then(LefPin::center) {
+            Some(center) => xform.apply(center),
+            None => {
+                let (min_x, min_y, max_x, max_y) = xform.outline_bbox();
+                ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0)
+            }
+        };
+        Some(ResolvedPin {
+            position,
+            role: macro_pin
+                .map(|p| SignalRole::from_lef_direction(&p.direction))
+                .unwrap_or(SignalRole::Unknown),
+        })
+    }
+
+    /// Resolve every placed connection of `net`
+    pub fn net_pins(&self, net: &DefNet) -> Vec<ResolvedPin> {
+        net.instances
+            .iter()
+            .zip(&net.instance_pins)
+            .filter_map(|(instance, pin)| self.resolve(instance, pin))
+            .collect()
+    }
+}
+
+/// Star of flight lines from the driver to every other pin
+///
+/// Without a driver the star starts at the first pin and no line is
+/// directed. Lines towards bidirectional pins are not directed either.
+pub fn star_flight_lines(pins: &[ResolvedPin]) -> Vec<FlightLine> {
+    let driver = pins
+        .iter()
+        .position(|p| p.role == SignalRole::Driver)
+        .unwrap_or(0);
+    let Some(hub) = pins.get(driver) else {
+        return Vec::new();
+    };
+    let has_driver = hub.role == SignalRole::Driver;
+    pins.iter()
+        .enumerate()
+        .filter(|(i, _)| *i != driver)
+        .map(|(_, pin)| FlightLine {
+            from: hub.position,
+            to: pin.position,
+            directed: has_driver && pin.role == SignalRole::Sink,
+        })
+        .collect()
+}
+
+/// Way the pins of a net are joined by flight lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FlightLineStyle {
+    /// From the driver to every other pin
+    #[default]
+    Star,
+    /// Shortest tree through all pins, closer to the eventual wiring
+    SpanningTree,
+}
+
+impl FlightLineStyle {
+    /// Flight lines of a net with `pins`
+    pub fn flight_lines(self, pins: &[ResolvedPin]) -> Vec<FlightLine> {
+        match self {
+            FlightLineStyle::Star => star_flight_lines(pins),
+            FlightLineStyle::SpanningTree => spanning_tree_flight_lines(pins),
+        }
+    }
+}
+
+/// Nets with more pins than this fall back to a star, as the spanning tree
+/// takes time quadratic in the pin count
+const MAX_SPANNING_TREE_PINS: usize = 2_000;
+
+/// Minimum spanning tree of the pins by Manhattan distance
+///
+/// The tree grows from the driver, so every line points away from it. Only
+/// the lines leaving the driver towards a sink are directed.
+pub fn spanning_tree_flight_lines(pins: &[ResolvedPin]) -> Vec<FlightLine> {
+    if pins.len() > MAX_SPANNING_TREE_PINS {
+        return star_flight_lines(pins);
+    }
+    let Some(root) = pins
+        .iter()
+        .position(|p| p.role == SignalRole::Driver)
+        .or((!pins.is_empty()).then_some(0))
+    else {
+        return Vec::new();
+    };
+    let distance = |a: (f64, f64), b: (f64, f64)| (a.0 - b.0).abs() + (a.1 - b.1).abs();
+
+    // Prim's algorithm: cheapest link of every pin not yet in the tree
+    let mut in_tree = vec![false; pins.len()];
+    let mut link: Vec<(f64, usize)> = pins
+        .iter()
+        .map(|p| (distance(pins[root].position, p.position), root))
+        .collect();
+    in_tree[root] = true;
+    let mut lines = Vec::with_capacity(pins.len().saturating_sub(1));
+    for _ in 1..pins.len() {
+        let Some(next) = (0..pins.len())
+            .filter(|&i| !in_tree[i])
+            .min_by(|&a, &b| link[a].0.total_cmp(&link[b].0))
+        else {
+            break;
+        };
+        in_tree[next] = true;
+        let from = &pins[link[next].1];
+        lines.push(FlightLine {
+            from: from.position,
+            to: pins[next].position,
+            directed: from.role == SignalRole::Driver && pins[next].role == SignalRole::Sink,
+        });
+        for i in 0..pins.len() {
+            let d = distance(pins[next].position, pins[i].position);
+            if !in_tree[i] && d < link[i].0 {
+                link[i] = (d, next);
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{self, cell, pin_with_rects, rect};
+
+    fn inverter() -> LefMacro {
+        let pins = vec![
+            pin_with_rects("A", "INPUT", vec![rect("M1", 0.0, 0.0, 0.2, 0.2)]),
+            pin_with_rects("Y", "OUTPUT", vec![rect("M1", 1.8, 0.8, 2.0, 1.0)]),
+        ];
+        cell("INV", 2.0, 1.0, pins)
+    }
+
+    fn component(name: &str, x: f64, orientation: &str) -> DefComponent {
+        test_fixtures::component(name, "INV", x, 0.0, orientation)
+    }
+
+    #[test]
+    fn test_resolve_instance_pins() {
+        let def = Def {
+            components: vec![component("u1", 0.0, "N"), component("u2", 10000.0, "FN")],
+            ..Def::default()
+        };
+        let macros = [inverter()];
+        let pins = ComponentPins::new(&def, &macros, 1000.0);
+
+        let y = pins.resolve("u1", "Y").unwrap();
+        assert_eq!(y.role, SignalRole::Driver);
+        assert!((y.position.0 - 1.9).abs() < 1e-9 && (y.position.1 - 0.9).abs() < 1e-9);
+
+        // Mirrored instance moves pin A to the right edge
+        let a = pins.resolve("u2", "A").unwrap();
+        assert_eq!(a.role, SignalRole::Sink);
+        assert!((a.position.0 - 11.9).abs() < 1e-9);
+
+        assert!(pins.resolve("missing", "A").is_none());
+    }
+
+    #[test]
+    fn test_star_from_driver() {
+        let pin = |x: f64, role| ResolvedPin {
+            position: (x, 0.0),
+            role,
+        };
+        let lines = star_flight_lines(&[
+            pin(0.0, SignalRole::Sink),
+            pin(1.0, SignalRole::Driver),
+            pin(2.0, SignalRole::Bidirectional),
+        ]);
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|l| l.from == (1.0, 0.0)));
+        assert!(lines[0].directed);
+        assert!(!lines[1].directed);
+
+        // No driver: undirected star from the first pin
+        let lines = star_flight_lines(&[pin(0.0, SignalRole::Sink), pin(1.0, SignalRole::Sink)]);
+        assert_eq!(lines.len(), 1);
+        assert!(!lines[0].directed);
+    }
+
+    #[test]
+    fn test_spanning_tree_from_driver() {
+        let pin = |x: f64, y: f64, role| ResolvedPin {
+            position: (x, y),
+            role,
+        };
+        // Driver at the left end of a row of sinks, one sink above the last
+        let pins = [
+            pin(3.0, 0.0, SignalRole::Sink),
+            pin(0.0, 0.0, SignalRole::Driver),
+            pin(1.0, 0.0, SignalRole::Sink),
+            pin(3.0, 5.0, SignalRole::Sink),
+            pin(2.0, 0.0, SignalRole::Sink),
+        ];
+        let lines = spanning_tree_flight_lines(&pins);
+        assert_eq!(lines.len(), 4);
+        let length: f64 = lines
+            .iter()
+            .map(|l| (l.from.0 - l.to.0).abs() + (l.from.1 - l.to.1).abs())
+            .sum();
+        assert!((length - 8.0).abs() < 1e-9);
+        // A chain, only the first hop leaves the driver
+        assert_eq!(lines.iter().filter(|l| l.directed).count(), 1);
+        assert!(lines.contains(&FlightLine {
+            from: (3.0, 0.0),
+            to: (3.0, 5.0),
+            directed: false,
+        }));
+
+        assert!(spanning_tree_flight_lines(&[]).is_empty());
+        assert!(spanning_tree_flight_lines(&pins[..1]).is_empty());
+        assert_eq!(
+            FlightLineStyle::Star.flight_lines(&pins).len(),
+            FlightLineStyle::SpanningTree.flight_lines(&pins).len()
+        );
+    }
+}
@@ -0,0 +1,534 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! DEF Component Table and Inspector
+//!
+//! This module provides a sortable table of DEF components with a column chooser
+//! covering the fixed component attributes and every PROPERTY key in the design,
+//! plus an inspector window showing all attributes of a single component.
+
+use crate::def::{Def, DefComponent};
+use crate::identifier;
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+/// A column that can be shown in the component table
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ComponentColumn {
+    Name,
+    Macro,
+    Status,
+    Location,
+    Orientation,
+    Source,
+    Weight,
+    Eeq,
+    Generate,
+    Power,
+    Ground,
+    Halo,
+    /// Value of a component PROPERTY with the given key
+    Property(String),
+}
+
+impl ComponentColumn {
+    /// Built-in columns in display order
+    const FIXED: [ComponentColumn; 12] = [
+        ComponentColumn::Name,
+        ComponentColumn::Macro,
+        ComponentColumn::Status,
+        ComponentColumn::Location,
+        ComponentColumn::Orientation,
+        ComponentColumn::Source,
+        ComponentColumn::Weight,
+        ComponentColumn::Eeq,
+        ComponentColumn::Generate,
+        ComponentColumn::Power,
+        ComponentColumn::Ground,
+        ComponentColumn::Halo,
+    ];
+
+    /// Column header text
+    pub fn label(&self) -> String {
+        match self {
+            ComponentColumn::Name => "Name".to_string(),
+            ComponentColumn::Macro => "Macro".to_string(),
+            ComponentColumn::Status => "Status".to_string(),
+            ComponentColumn::Location => "Location".to_string(),
+            ComponentColumn::Orientation => "Orient".to_string(),
+            ComponentColumn::Source => "Source".to_string(),
+            ComponentColumn::Weight => "Weight".to_string(),
+            ComponentColumn::Eeq => "EEQ".to_string(),
+            ComponentColumn::Generate => "Generate".to_string(),
+            ComponentColumn::Power => "Power".to_string(),
+            ComponentColumn::Ground => "Ground".to_string(),
+            ComponentColumn::Halo => "Halo".to_string(),
+            ComponentColumn::Property(key) => key.clone(),
+        }
+    }
+
+    /// Cell text for a component, empty when the attribute is not set
+    pub fn value(&self, component: &DefComponent) -> String {
+        match self {
+            ComponentColumn::Name => component.name.clone(),
+            ComponentColumn::Macro => component.macro_name.clone(),
+            ComponentColumn::Status => component
+                .placement
+                .as_ref()
+                .map(|p| p.placement_type.clone())
+                .unwrap_or_default(),
+            ComponentColumn::Location => component
+                .placement
+                .as_ref()
+                .map(|p| format!("({:.0}, {:.0})", p.x, p.y))
+                .unwrap_or_default(),
+            ComponentColumn::Orientation => component
+                .placement
+                .as_ref()
+                .map(|p| p.orientation.clone())
+                .unwrap_or_default(),
+            ComponentColumn::Source => component.source.clone().unwrap_or_default(),
+            ComponentColumn::Weight => component.weight.map(|w| w.to_string()).unwrap_or_default(),
+            ComponentColumn::Eeq => component.eeq.clone().unwrap_or_default(),
+            ComponentColumn::Generate => component.generate.clone().unwrap_or_default(),
+            ComponentColumn::Power => component.power.map(|p| p.to_string()).unwrap_or_default(),
+            ComponentColumn::Ground => component.ground.clone().unwrap_or_default(),
+            ComponentColumn::Halo => component
+                .routing_halo
+                .map(|(l, b, r, t)| format!("{l} {b} {r} {t}"))
+                .unwrap_or_default(),
+            ComponentColumn::Property(key) => component
+                .properties
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Compare two components by this column
+    ///
+    /// Numeric values compare numerically, locations by (x, y), everything
+    /// else as text. Empty values sort last.
+    pub fn compare(&self, a: &DefComponent, b: &DefComponent) -> Ordering {
+        if *self == ComponentColumn::Location {
+            let key = |c: &DefComponent| c.placement.as_ref().map(|p| (p.x, p.y));
+            return match (key(a), key(b)) {
+                (Some(ka), Some(kb)) => ka.partial_cmp(&kb).unwrap_or(Ordering::Equal),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            };
+        }
+
+        let va = self.value(a);
+        let vb = self.value(b);
+        match (va.is_empty(), vb.is_empty()) {
+            (false, true) => return Ordering::Less,
+            (true, false) => return Ordering::Greater,
+            (true, true) => return Ordering::Equal,
+            (false, false) => {}
+        }
+        match (va.parse::<f64>(), vb.parse::<f64>()) {
+            (Ok(na), Ok(nb)) => na.partial_cmp(&nb).unwrap_or(Ordering::Equal),
+            _ => va.cmp(&vb),
+        }
+    }
+}
+
+/// Collect every PROPERTY key used by any component, sorted
+pub fn collect_property_keys(def: &Def) -> BTreeSet<String> {
+    def.components
+        .iter()
+        .flat_map(|c| c.properties.iter().map(|(k, _)| k.clone()))
+        .collect()
+}
+
+/// Component table window state and rendering
+pub struct ComponentTable {
+    /// Whether the table window is currently shown
+    pub visible: bool,
+    /// Shown columns in display order
+    pub columns: Vec<ComponentColumn>,
+    /// Column the rows are sorted by, and whether ascending
+    pub sort: Option<(ComponentColumn, bool)>,
+    /// Name filter
+    pub filter: String,
+    /// Column widths in points by column label, kept between sessions
+    pub column_widths: BTreeMap<String, f32>,
+}
+
+impl Default for ComponentTable {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            columns: vec![
+                ComponentColumn::Name,
+                ComponentColumn::Macro,
+                ComponentColumn::Status,
+                ComponentColumn::Location,
+                ComponentColumn::Orientation,
+            ],
+            sort: None,
+            filter: String::new(),
+            column_widths: BTreeMap::new(),
+        }
+    }
+}
+
+impl ComponentTable {
+    /// Create a new component table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Indices of the components to show, filtered and sorted
+    pub fn visible_rows(&self, def: &Def) -> Vec<usize> {
+        let mut rows: Vec<usize> = def
+            .components
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| {
+                identifier::matches_query(&c.name, &self.filter)
+                    || identifier::matches_query(&c.macro_name, &self.filter)
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        if let Some((column, ascending)) = &self.sort {
+            rows.sort_by(|&a, &b| {
+                let order = column.compare(&def.components[a], &def.components[b]);
+                if *ascending {
+                    order
+                } else {
+                    order.reverse()
+                }
+            });
+        }
+        rows
+    }
+
+    /// Toggle a column on or off, keeping the built-in columns in their fixed order
+    fn toggle_column(&mut self, column: &ComponentColumn, shown: bool) {
+        if !shown {
+            self.columns.retain(|c| c != column);
+            if self.sort.as_ref().is_some_and(|(c, _)| c == column) {
+                self.sort = None;
+            }
+            return;
+        }
+        if self.columns.contains(column) {
+            return;
+        }
+        let rank = |c: &ComponentColumn| {
+            ComponentColumn::FIXED
+                .iter()
+                .position(|f| f == c)
+                .unwrap_or(ComponentColumn::FIXED.len())
+        };
+        let insert_at = self
+            .columns
+            .iter()
+            .position(|c| rank(c) > rank(column))
+            .unwrap_or(self.columns.len());
+        self.columns.insert(insert_at, column.clone());
+    }
+
+    /// Render the component table window
+    ///
+    /// Clicking a row selects the component in the canvas and opens it in the inspector.
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        def: &Def,
+        selected_components: &mut HashSet<String>,
+        inspected_component: &mut Option<String>,
+    ) {
+        if !self.visible {
+            return;
+        }
+
+        let mut open = true;
+        egui::Window::new("Component Table")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(700.0)
+            .default_height(400.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Filter:");
+                    ui.text_edit_singleline(&mut self.filter);
+
+                    ui.menu_button("Columns", |ui| {
+                        for column in &ComponentColumn::FIXED {
+                            let mut shown = self.columns.contains(column);
+                            if ui.checkbox(&mut shown, column.label()).changed() {
+                                self.toggle_column(column, shown);
+                            }
+                        }
+                        let keys = collect_property_keys(def);
+                        if !keys.is_empty() {
+                            ui.separator();
+                            ui.label("Properties");
+                            for key in keys {
+                                let column = ComponentColumn::Property(key);
+                                let mut shown = self.columns.contains(&column);
+                                if ui.checkbox(&mut shown, column.label()).changed() {
+                                    self.toggle_column(&column, shown);
+                                }
+                            }
+                        }
+                    });
+                });
+                ui.separator();
+
+                let rows = self.visible_rows(def);
+                ui.label(format!(
+                    "Showing {} of {} components",
+                    rows.len(),
+                    def.components.len()
+                ));
+
+                let columns = self.columns.clone();
+                let mut clicked_header = None;
+                egui::ScrollArea::horizontal().show(ui, |ui| {
+                    let mut table = TableBuilder::new(ui)
+                        .striped(true)
+                        .resizable(true)
+                        .sense(egui::Sense::click());
+                    for column in &columns {
+                        let size = match self.column_widths.get(&column.label()) {
+                            Some(&width) => Column::initial(width),
+                            None => Column::auto(),
+                        };
+                        table = table.column(size.at_least(60.0).clip(true));
+                    }
+
+                    table
+                        .header(20.0, |mut header| {
+                            for column in &columns {
+                                header.col(|ui| {
+                                    let arrow = match &self.sort {
+                                        Some((c, true)) if c == column => " ^",
+                                        Some((c, false)) if c == column => " v",
+                                        _ => "",
+                                    };
+                                    if ui.button(format!("{}{arrow}", column.label())).clicked() {
+                                        clicked_header = Some(column.clone());
+                                    }
+                                });
+                            }
+                        })
+                        .body(|body| {
+                            for (column, &width) in columns.iter().zip(body.widths()) {
+                                self.column_widths.insert(column.label(), width);
+                            }
+                            body.rows(18.0, rows.len(), |mut row| {
+                                let component = &def.components[rows[row.index()]];
+                                row.set_selected(selected_components.contains(&component.name));
+                                for column in &columns {
+                                    row.col(|ui| {
+                                        ui.label(column.value(component));
+                                    });
+                                }
+                                if row.response().clicked() {
+                                    if !selected_components.remove(&component.name) {
+                                        selected_components.insert(component.name.clone());
+                                    }
+                                    *inspected_component = Some(component.name.clone());
+                                }
+                            });
+                        });
+                });
+
+                // Clicking the sorted column again flips the direction
+                if let Some(column) = clicked_header {
+                    self.sort = match self.sort.take() {
+                        Some((c, ascending)) if c == column => Some((c, !ascending)),
+                        _ => Some((column, true)),
+                    };
+                }
+            });
+        self.visible = open;
+    }
+}
+
+/// Render the inspector window for a single component
+///
+/// Shows placement, every optional attribute and all PROPERTY values in a grid.
+/// With `swap_candidates`, the footprint family of the component's macro,
+/// the macro can be swapped; the chosen macro is returned.
+/// `connected_nets` lists the nets of the component, None while the
+/// background pass is still collecting them.
+pub fn render_component_inspector(
+    ctx: &egui::Context,
+    def: &Def,
+    inspected_component: &mut Option<String>,
+    swap_candidates: &[String],
+    connected_nets: Option<&[&str]>,
+) -> Option<String> {
+    let name = inspected_component.clone()?;
+    let Some(component) = def.components.iter().find(|c| c.name == name) else {
+        *inspected_component = None;
+        return None;
+    };
+
+    let mut swap_to = None;
+
+    let mut open = true;
+    egui::Window::new("Component Inspector")
+        .open(&mut open)
+        .resizable(true)
+        .default_width(320.0)
+        .show(ctx, |ui| {
+            egui::Grid::new("component_inspector_grid")
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    for column in &ComponentColumn::FIXED {
+                        let value = column.value(component);
+                        if value.is_empty() {
+                            continue;
+                        }
+                        ui.label(column.label());
+                        ui.monospace(value);
+                        ui.end_row();
+                    }
+                });
+
+            ui.separator();
+            if component.properties.is_empty() {
+                ui.label("No properties");
+            } else {
+                ui.label(format!("Properties ({})", component.properties.len()));
+                egui::Grid::new("component_properties_grid")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (key, value) in &component.properties {
+                            ui.label(key);
+                            ui.monospace(value);
+                            ui.end_row();
+                        }
+                    });
+            }
+
+            ui.separator();
+            match connected_nets {
+                Some(nets) => {
+                    egui::CollapsingHeader::new(format!("Nets ({})", nets.len()))
+                        .id_salt("component_inspector_nets")
+                        .show(ui, |ui| {
+                            for net in nets {
+                                ui.monospace(*net);
+                            }
+                        });
+                }
+                None => {
+                    ui.label(egui::RichText::new("Collecting nets...").weak());
+                }
+            }
+
+            if swap_candidates.len() > 1 {
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Swap to:");
+                    egui::ComboBox::from_id_salt("component_swap_combo")
+                        .selected_text(&component.macro_name)
+                        .show_ui(ui, |ui| {
+                            for candidate in swap_candidates {
+                                let current = *candidate == component.macro_name;
+                                if ui.selectable_label(current, candidate).clicked() && !current {
+                                    swap_to = Some(candidate.clone());
+                                }
+                            }
+                        });
+                });
+            }
+        });
+    if !open {
+        *inspected_component = None;
+    }
+    swap_to
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+
+    fn component(name: &str, weight: Option<f64>, props: &[(&str, &str)]) -> DefComponent {
+        DefComponent {
+            weight,
+            eeq: Some("INV_ALT".to_string()),
+            properties: props
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            ..test_fixtures::component(name, "INV", 0.0, 0.0, "N")
+        }
+    }
+
+    #[test]
+    fn test_column_values() {
+        let c = component("u1", Some(5.0), &[("cost", "12")]);
+        assert_eq!(ComponentColumn::Eeq.value(&c), "INV_ALT");
+        assert_eq!(ComponentColumn::Status.value(&c), "PLACED");
+        assert_eq!(
+            ComponentColumn::Property("cost".to_string()).value(&c),
+            "12"
+        );
+        assert_eq!(
+            ComponentColumn::Property("missing".to_string()).value(&c),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_numeric_sort_and_empty_last() {
+        let def = Def {
+            components: vec![
+                component("a", Some(10.0), &[("cost", "9")]),
+                component("b", Some(2.0), &[]),
+                component("c", None, &[("cost", "10")]),
+            ],
+            ..Def::default()
+        };
+
+        let mut table = ComponentTable::new();
+        table.sort = Some((ComponentColumn::Weight, true));
+        assert_eq!(table.visible_rows(&def), vec![1, 0, 2]);
+
+        // Property values "9" and "10" sort numerically, not as text
+        table.sort = Some((ComponentColumn::Property("cost".to_string()), true));
+        assert_eq!(table.visible_rows(&def), vec![0, 2, 1]);
+
+        assert_eq!(
+            collect_property_keys(&def).into_iter().collect::<Vec<_>>(),
+            vec!["cost".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_toggle_column_keeps_fixed_order() {
+        let mut table = ComponentTable::new();
+        table.toggle_column(&ComponentColumn::Eeq, true);
+        table.toggle_column(&ComponentColumn::Source, true);
+        let source = table
+            .columns
+            .iter()
+            .position(|c| *c == ComponentColumn::Source);
+        let eeq = table
+            .columns
+            .iter()
+            .position(|c| *c == ComponentColumn::Eeq);
+        assert!(source < eeq);
+
+        table.sort = Some((ComponentColumn::Eeq, true));
+        table.toggle_column(&ComponentColumn::Eeq, false);
+        assert!(!table.columns.contains(&ComponentColumn::Eeq));
+        assert!(table.sort.is_none());
+    }
+}
@@ -0,0 +1,255 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Regular arrays of identical instances
+//!
+//! Memory bitcells, pad bumps and similar structures are placed as a full
+//! rectangle of one macro at a fixed pitch. Finding them lets the viewer
+//! show one array object with its size instead of every member.
+//!
+//! The pitch of a macro is the most common spacing between neighbouring
+//! placements along each axis. Placements one pitch apart are joined, and
+//! every joined group that fills its rectangle completely is an array.
+//! Orientation is not compared, as bitcells are often mirrored row by row.
+
+use super::DefComponent;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Full rectangular grid of one macro
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstanceArray {
+    /// Common prefix of the member names followed by `*`
+    pub name: String,
+    pub macro_name: String,
+    /// Placement of the lower-left member in database units
+    pub origin: (f64, f64),
+    /// Member spacing in database units
+    pub pitch: (f64, f64),
+    pub rows: usize,
+    pub cols: usize,
+    /// Component indices, row by row from the lower left
+    pub members: Vec<usize>,
+}
+
+impl InstanceArray {
+    /// Bounding box in database units for members of `cell_size` database
+    /// units
+    pub fn bounds(&self, cell_size: (f64, f64)) -> (f64, f64, f64, f64) {
+        (
+            self.origin.0,
+            self.origin.1,
+            self.origin.0 + (self.cols - 1) as f64 * self.pitch.0 + cell_size.0,
+            self.origin.1 + (self.rows - 1) as f64 * self.pitch.1 + cell_size.1,
+        )
+    }
+}
+
+/// Arrays of at least `min_members` instances, at least two rows and two
+/// columns, largest first
+pub fn detect_arrays(components: &[DefComponent], min_members: usize) -> Vec<InstanceArray> {
+    let mut by_macro: HashMap<&str, Vec<(i64, i64, usize)>> = HashMap::new();
+    for (index, component) in components.iter().enumerate() {
+        if let Some(placement) = &component.placement {
+            let point = (placement.x.round() as i64, placement.y.round() as i64);
+            by_macro
+                .entry(component.macro_name.as_str())
+                .or_default()
+                .push((point.0, point.1, index));
+        }
+    }
+
+    let mut arrays = Vec::new();
+    for (macro_name, placements) in by_macro {
+        if placements.len() < min_members.max(4) {
+            continue;
+        }
+        let (Some(pitch_x), Some(pitch_y)) = (
+            common_spacing(placements.iter().map(|&(x, y, _)| (y, x))),
+            common_spacing(placements.iter().map(|&(x, y, _)| (x, y))),
+        ) else {
+            continue;
+        };
+
+        let mut at: HashMap<(i64, i64), usize> = HashMap::new();
+        for &(x, y, index) in &placements {
+            // Stacked duplicates are not a grid
+            at.entry((x, y)).or_insert(index);
+        }
+        let mut seen: HashSet<(i64, i64)> = HashSet::new();
+        for &(x, y, _) in &placements {
+            if !seen.insert((x, y)) {
+                continue;
+            }
+            let group = flood_fill((x, y), (pitch_x, pitch_y), &at, &mut seen);
+            let (min_x, min_y) = group.iter().fold((i64::MAX, i64::MAX), |(ax, ay), p| {
+                (ax.min(p.0), ay.min(p.1))
+            });
+            let (max_x, max_y) = group.iter().fold((i64::MIN, i64::MIN), |(ax, ay), p| {
+                (ax.max(p.0), ay.max(p.1))
+            });
+            let cols = ((max_x - min_x) / pitch_x + 1) as usize;
+            let rows = ((max_y - min_y) / pitch_y + 1) as usize;
+            if rows < 2 || cols < 2 || group.len() != rows * cols || group.len() < min_members {
+                continue;
+            }
+
+            let mut members: Vec<(i64, i64)> = group;
+            members.sort_by_key(|&(x, y)| (y, x));
+            let members: Vec<usize> = members.iter().map(|p| at[p]).collect();
+            arrays.push(InstanceArray {
+                name: array_name(members.iter().map(|&i| components[i].name.as_str())),
+                macro_name: macro_name.to_string(),
+                origin: (min_x as f64, min_y as f64),
+                pitch: (pitch_x as f64, pitch_y as f64),
+                rows,
+                cols,
+                members,
+            });
+        }
+    }
+    arrays.sort_by(|a, b| {
+        b.members
+            .len()
+            .cmp(&a.members.len())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    arrays
+}
+
+/// Most common positive spacing between neighbours sharing a line
+///
+/// `points` are (line, position) pairs, e.g. (y, x) for spacing along X.
+fn common_spacing(points: impl Iterator<Item = (i64, i64)>) -> Option<i64> {
+    let mut lines: HashMap<i64, Vec<i64>> = HashMap::new();
+    for (line, position) in points {
+        lines.entry(line).or_default().push(position);
+    }
+    let mut counts: HashMap<i64, usize> = HashMap::new();
+    for positions in lines.values_mut() {
+        positions.sort_unstable();
+        for pair in positions.windows(2) {
+            let spacing = pair[1] - pair[0];
+            if spacing > 0 {
+                *counts.entry(spacing).or_default() += 1;
+            }
+        }
+    }
+    counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+        .map(|(spacing, _)| spacing)
+}
+
+/// Placements reachable from `start` in steps of one pitch
+fn flood_fill(
+    start: (i64, i64),
+    pitch: (i64, i64),
+    at: &HashMap<(i64, i64), usize>,
+    seen: &mut HashSet<(i64, i64)>,
+) -> Vec<(i64, i64)> {
+    let mut group = vec![start];
+    let mut queue = VecDeque::from([start]);
+    while let Some((x, y)) = queue.pop_front() {
+        let neighbours = [
+            (x - pitch.0, y),
+            (x + pitch.0, y),
+            (x, y - pitch.1),
+            (x, y + pitch.1),
+        ];
+        for next in neighbours {
+            if at.contains_key(&next) && seen.insert(next) {
+                group.push(next);
+                queue.push_back(next);
+            }
+        }
+    }
+    group
+}
+
+/// Common prefix of the names followed by `*`
+fn array_name<'a>(mut names: impl Iterator<Item = &'a str>) -> String {
+    let Some(first) = names.next() else {
+        return "*".to_string();
+    };
+    let mut prefix = first;
+    for name in names {
+        let common = prefix
+            .char_indices()
+            .zip(name.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(prefix.len().min(name.len()), |((i, _), _)| i);
+        prefix = &prefix[..common];
+    }
+    format!("{prefix}*")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::component;
+
+    fn placed(name: &str, macro_name: &str, x: f64, y: f64) -> DefComponent {
+        component(name, macro_name, x, y, "N")
+    }
+
+    #[test]
+    fn test_detect_bitcell_banks() {
+        let mut components = Vec::new();
+        // Two 4 x 8 banks of the same bitcell, far apart
+        for (bank, base_x) in [(0, 0.0), (1, 100000.0)] {
+            for row in 0..4 {
+                for col in 0..8 {
+                    components.push(placed(
+                        &format!("mem/bank{bank}/bit_{row}_{col}"),
+                        "BITCELL",
+                        base_x + col as f64 * 500.0,
+                        row as f64 * 1200.0,
+                    ));
+                }
+            }
+        }
+        // Logic around them does not form an array
+        components.push(placed("u_ctrl", "NAND2X1", 5000.0, 0.0));
+        components.push(placed("u_buf", "BUFX2", 5400.0, 0.0));
+
+        let arrays = detect_arrays(&components, 16);
+        assert_eq!(arrays.len(), 2);
+        let bank = &arrays[0];
+        assert_eq!(bank.name, "mem/bank0/bit_*");
+        assert_eq!(bank.macro_name, "BITCELL");
+        assert_eq!((bank.rows, bank.cols), (4, 8));
+        assert_eq!(bank.pitch, (500.0, 1200.0));
+        assert_eq!(bank.origin, (0.0, 0.0));
+        assert_eq!(bank.members.len(), 32);
+        assert_eq!(bank.members[1], 1);
+        assert_eq!(bank.bounds((500.0, 1200.0)), (0.0, 0.0, 4000.0, 4800.0));
+        assert_eq!(arrays[1].origin, (100000.0, 0.0));
+
+        // Too small for the threshold
+        assert!(detect_arrays(&components, 64).is_empty());
+    }
+
+    #[test]
+    fn test_incomplete_grid_is_not_an_array() {
+        let mut components = Vec::new();
+        for row in 0..3 {
+            for col in 0..3 {
+                if (row, col) != (1, 1) {
+                    components.push(placed(
+                        &format!("b{row}{col}"),
+                        "BUMP",
+                        col as f64 * 100.0,
+                        row as f64 * 100.0,
+                    ));
+                }
+            }
+        }
+        assert!(detect_arrays(&components, 4).is_empty());
+
+        components.push(placed("b11", "BUMP", 100.0, 100.0));
+        let arrays = detect_arrays(&components, 4);
+        assert_eq!(arrays.len(), 1);
+        assert_eq!((arrays[0].rows, arrays[0].cols), (3, 3));
+        assert_eq!(arrays[0].name, "b*");
+    }
+}
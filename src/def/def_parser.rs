@@ -6,13 +6,17 @@ use nom::{
     bytes::complete::{tag, take_until, take_while1},
     character::complete::{char, multispace0, space1},
     multi::separated_list0,
-    number::complete::double,
     sequence::{delimited, preceded, terminated},
     IResult, Parser,
 };
 
 use super::preprocessor::preprocess;
-use super::{Def, DefGCellGrid, DefPolygon, DefVia, DefViaLayer};
+use super::{
+    Def, DefGCellGrid, DefParseOptions, DefPolygon, DefRow, DefTrack, DefVia, DefViaLayer,
+};
+use crate::identifier::BusBitChars;
+use crate::number::{finite_double, ParseFinite};
+use crate::progress::{ProgressCallback, ProgressReporter};
 
 #[allow(dead_code)]
 fn identifier(input: &str) -> IResult<&str, &str> {
@@ -38,8 +42,8 @@ fn parse_die_area(input: &str) -> IResult<&str, Vec<(f64, f64)>> {
     let (input, points) = separated_list0(
         multispace0,
         (
-            preceded(tag("("), double),
-            preceded(space1, terminated(double, tag(")"))),
+            preceded(tag("("), finite_double),
+            preceded(space1, terminated(finite_double, tag(")"))),
         ),
     )
     .parse(input)?;
@@ -67,7 +71,7 @@ fn parse_gcell_grid(input: &str) -> IResult<&str, (Vec<DefGCellGrid>, Vec<DefGCe
         let (new_rest, _) = multispace0(rest)?;
         rest = new_rest;
 
-        let (new_rest, offset) = double(rest)?;
+        let (new_rest, offset) = finite_double(rest)?;
         rest = new_rest;
         let (new_rest, _) = multispace0(rest)?;
         rest = new_rest;
@@ -88,7 +92,7 @@ fn parse_gcell_grid(input: &str) -> IResult<&str, (Vec<DefGCellGrid>, Vec<DefGCe
         let (new_rest, _) = multispace0(rest)?;
         rest = new_rest;
 
-        let (new_rest, step) = double(rest)?;
+        let (new_rest, step) = finite_double(rest)?;
         rest = new_rest;
         let (new_rest, _) = multispace0(rest)?;
         rest = new_rest;
@@ -112,26 +116,117 @@ fn parse_gcell_grid(input: &str) -> IResult<&str, (Vec<DefGCellGrid>, Vec<DefGCe
     Ok((rest, (x_grids, y_grids)))
 }
 
-fn parse_def_simple(input: &str) -> IResult<&str, Def> {
-    println!("[DBG] Starting DEF parsing...");
+/// `ROW name site x y orient [DO numX BY numY [STEP stepX stepY]]`
+///
+/// Without DO the row holds one site, and without STEP the sites are not
+/// repeated, as in the DEF reference.
+fn parse_row(parts: &[&str]) -> Option<DefRow> {
+    let parts: Vec<&str> = parts
+        .iter()
+        .copied()
+        .take_while(|p| *p != ";" && *p != "+")
+        .collect();
+    let number = |i: usize| parts.get(i).and_then(|p| p.parse_finite().ok());
+    let mut row = DefRow {
+        name: parts.get(1)?.to_string(),
+        macro_name: parts.get(2)?.to_string(),
+        x: number(3)?,
+        y: number(4)?,
+        orientation: parts.get(5)?.to_string(),
+        num_x: 1,
+        num_y: 1,
+        step_x: 0.0,
+        step_y: 0.0,
+    };
+    if parts.get(6) == Some(&"DO") {
+        row.num_x = parts.get(7)?.parse().ok()?;
+        row.num_y = parts.get(9)?.parse().ok()?;
+        if parts.get(10) == Some(&"STEP") {
+            row.step_x = number(11)?;
+            row.step_y = number(12)?;
+        }
+    }
+    Some(row)
+}
+
+/// `TRACKS X|Y start DO count STEP step [MASK n [SAMEMASK]] LAYER name...`
+///
+/// Each layer of the statement gives one track pattern.
+fn parse_tracks<'a>(parts: &[&'a str]) -> Option<(&'a str, Vec<DefTrack>)> {
+    let parts: Vec<&str> = parts.iter().copied().take_while(|p| *p != ";").collect();
+    if parts.get(3) != Some(&"DO") || parts.get(5) != Some(&"STEP") {
+        return None;
+    }
+    let axis = *parts.get(1)?;
+    let offset = parts.get(2)?.parse_finite().ok()?;
+    let num: i32 = parts.get(4)?.parse().ok()?;
+    let step = parts.get(6)?.parse_finite().ok()?;
+    let layers = match parts.iter().position(|p| *p == "LAYER") {
+        Some(at) => &parts[at + 1..],
+        None => &[][..],
+    };
+    let tracks = layers
+        .iter()
+        .map(|layer| DefTrack {
+            layer: layer.to_string(),
+            offset,
+            num,
+            step,
+        })
+        .collect();
+    Some((axis, tracks))
+}
+
+fn parse_def_simple<'a>(
+    input: &'a str,
+    options: &DefParseOptions,
+    progress: &mut ProgressReporter,
+) -> IResult<&'a str, Def> {
+    eprintln!("[DBG] Starting DEF parsing...");
+    progress.report(0.0, 0, "preprocessing");
 
     // Pass 1: Preprocess input (remove comments, merge logical lines)
-    println!("[DBG] Preprocessing DEF file...");
+    eprintln!("[DBG] Preprocessing DEF file...");
     let preprocessed = preprocess(input);
-    println!(
+    eprintln!(
         "[DBG] Preprocessed: {} logical lines from {} raw lines",
         preprocessed.lines.len(),
         input.lines().count()
     );
 
+    Ok((
+        "",
+        parse_logical_lines(&preprocessed.lines, options, progress),
+    ))
+}
+
+/// Parse preprocessed logical lines, skipping the sections excluded by
+/// `options`
+pub(super) fn parse_logical_lines(
+    lines: &[String],
+    options: &DefParseOptions,
+    progress: &mut ProgressReporter,
+) -> Def {
+    let mut design = String::new();
     let mut die_area_points = Vec::new();
     let mut components = Vec::new();
     let mut pins = Vec::new();
     let mut nets = Vec::new();
     let mut special_nets = Vec::new();
     let mut vias = Vec::new();
+    let mut blockages = Vec::new();
+    let mut fills = Vec::new();
+    let mut regions = Vec::new();
+    let mut groups = Vec::new();
+    let mut scan_chains = Vec::new();
+    let mut rows = Vec::new();
+    let mut tracks_x = Vec::new();
+    let mut tracks_y = Vec::new();
+    let mut g_cell_grid_x = Vec::new();
+    let mut g_cell_grid_y = Vec::new();
+    let mut bus_bit_chars = BusBitChars::default();
+    let mut units_distance_microns = None;
 
-    let lines = &preprocessed.lines;
     let mut i = 0;
 
     while i < lines.len() {
@@ -147,9 +242,184 @@ fn parse_def_simple(input: &str) -> IResult<&str, Def> {
             continue;
         }
 
+        let items = components.len()
+            + pins.len()
+            + special_nets.len()
+            + nets.len()
+            + blockages.len()
+            + regions.len()
+            + groups.len()
+            + fills.len()
+            + scan_chains.len();
+        progress.report(i as f64 / lines.len() as f64, items, parts[0]);
+
         match parts[0] {
+            section if parts.len() > 1 && options.skips(section) => {
+                eprintln!("[DBG]   Skipping {section} section (section filter)");
+                let end_marker = format!("END {section}");
+                while i < lines.len() && !lines[i].trim().starts_with(&end_marker) {
+                    i += 1;
+                }
+            }
+            "BUSBITCHARS" if parts.len() > 1 => match BusBitChars::parse(parts[1]) {
+                Some(chars) => bus_bit_chars = chars,
+                None => eprintln!(
+                    "WARNING: Invalid BUSBITCHARS {}, using default \"[]\"",
+                    parts[1]
+                ),
+            },
+            "DESIGN" if parts.len() > 1 => {
+                design = parts[1].trim_end_matches(';').to_string();
+            }
+            "UNITS" if parts.len() > 3 && parts[1] == "DISTANCE" && parts[2] == "MICRONS" => {
+                match parts[3].parse_finite() {
+                    Ok(units) => units_distance_microns = Some(units),
+                    Err(_) => eprintln!("WARNING: Invalid UNITS DISTANCE MICRONS {}", parts[3]),
+                }
+            }
+            // GCELLGRID X|Y offset DO count STEP step ;
+            "GCELLGRID" if parts.len() >= 8 && parts[3] == "DO" && parts[5] == "STEP" => {
+                match (
+                    parts[2].parse_finite(),
+                    parts[4].parse::<usize>(),
+                    parts[6].parse_finite(),
+                ) {
+                    (Ok(offset), Ok(count), Ok(step)) => {
+                        let grid = DefGCellGrid {
+                            offset,
+                            count,
+                            step,
+                        };
+                        match parts[1] {
+                            "X" => g_cell_grid_x.push(grid),
+                            "Y" => g_cell_grid_y.push(grid),
+                            axis => eprintln!("WARNING: Invalid GCELLGRID axis {axis}"),
+                        }
+                    }
+                    _ => eprintln!("WARNING: Invalid GCELLGRID statement: {line}"),
+                }
+            }
+            "ROW" => match parse_row(&parts) {
+                Some(row) => rows.push(row),
+                None => eprintln!("WARNING: Invalid ROW statement: {line}"),
+            },
+            "TRACKS" => match parse_tracks(&parts) {
+                Some(("X", tracks)) => tracks_x.extend(tracks),
+                Some(("Y", tracks)) => tracks_y.extend(tracks),
+                _ => eprintln!("WARNING: Invalid TRACKS statement: {line}"),
+            },
+            "BLOCKAGES" if parts.len() > 1 => {
+                eprintln!("[DBG]   Found BLOCKAGES section");
+                i += 1;
+
+                let blockage_parser = crate::def::parser::blockage::DefBlockageParser;
+                let multi_parser =
+                    crate::def::parser::MultiLineParser::with_preprocessed(blockage_parser)
+                        .with_debug(true);
+
+                match multi_parser.parse_section_preprocessed(lines, i, "END BLOCKAGES") {
+                    Ok((parsed_blockages, next_index)) => {
+                        blockages.extend(parsed_blockages);
+                        i = next_index;
+                    }
+                    Err(e) => {
+                        eprintln!("[DBG]   Error parsing BLOCKAGES section: {e}");
+                        while i < lines.len() && !lines[i].trim().starts_with("END BLOCKAGES") {
+                            i += 1;
+                        }
+                    }
+                }
+            }
+            "REGIONS" if parts.len() > 1 => {
+                eprintln!("[DBG]   Found REGIONS section");
+                i += 1;
+
+                let region_parser = crate::def::parser::region::DefRegionParser;
+                let multi_parser =
+                    crate::def::parser::MultiLineParser::with_preprocessed(region_parser)
+                        .with_debug(true);
+
+                match multi_parser.parse_section_preprocessed(lines, i, "END REGIONS") {
+                    Ok((parsed_regions, next_index)) => {
+                        regions.extend(parsed_regions);
+                        i = next_index;
+                    }
+                    Err(e) => {
+                        eprintln!("[DBG]   Error parsing REGIONS section: {e}");
+                        while i < lines.len() && !lines[i].trim().starts_with("END REGIONS") {
+                            i += 1;
+                        }
+                    }
+                }
+            }
+            "GROUPS" if parts.len() > 1 => {
+                eprintln!("[DBG]   Found GROUPS section");
+                i += 1;
+
+                let group_parser = crate::def::parser::group::DefGroupParser;
+                let multi_parser =
+                    crate::def::parser::MultiLineParser::with_preprocessed(group_parser)
+                        .with_debug(true);
+
+                match multi_parser.parse_section_preprocessed(lines, i, "END GROUPS") {
+                    Ok((parsed_groups, next_index)) => {
+                        groups.extend(parsed_groups);
+                        i = next_index;
+                    }
+                    Err(e) => {
+                        eprintln!("[DBG]   Error parsing GROUPS section: {e}");
+                        while i < lines.len() && !lines[i].trim().starts_with("END GROUPS") {
+                            i += 1;
+                        }
+                    }
+                }
+            }
+            "FILLS" if parts.len() > 1 => {
+                eprintln!("[DBG]   Found FILLS section");
+                i += 1;
+
+                let fill_parser = crate::def::parser::fill::DefFillParser;
+                let multi_parser =
+                    crate::def::parser::MultiLineParser::with_preprocessed(fill_parser)
+                        .with_debug(true);
+
+                match multi_parser.parse_section_preprocessed(lines, i, "END FILLS") {
+                    Ok((parsed_fills, next_index)) => {
+                        fills.extend(parsed_fills);
+                        i = next_index;
+                    }
+                    Err(e) => {
+                        eprintln!("[DBG]   Error parsing FILLS section: {e}");
+                        while i < lines.len() && !lines[i].trim().starts_with("END FILLS") {
+                            i += 1;
+                        }
+                    }
+                }
+            }
+            "SCANCHAINS" if parts.len() > 1 => {
+                eprintln!("[DBG]   Found SCANCHAINS section");
+                i += 1;
+
+                let scan_chain_parser = crate::def::parser::scanchain::DefScanChainParser;
+                let multi_parser =
+                    crate::def::parser::MultiLineParser::with_preprocessed(scan_chain_parser)
+                        .with_debug(true);
+
+                match multi_parser.parse_section_preprocessed(lines, i, "END SCANCHAINS") {
+                    Ok((parsed_scan_chains, next_index)) => {
+                        scan_chains.extend(parsed_scan_chains);
+                        i = next_index;
+                    }
+                    Err(e) => {
+                        eprintln!("[DBG]   Error parsing SCANCHAINS section: {e}");
+                        while i < lines.len() && !lines[i].trim().starts_with("END SCANCHAINS") {
+                            i += 1;
+                        }
+                    }
+                }
+            }
             "DIEAREA" => {
-                println!("[DBG]   Found DIEAREA");
+                eprintln!("[DBG]   Found DIEAREA");
 
                 // With preprocessing, the entire DIEAREA is already on one logical line
                 let content_parts: Vec<&str> = line.split_whitespace().collect();
@@ -161,11 +431,11 @@ fn parse_def_simple(input: &str) -> IResult<&str, Def> {
                         && content_parts[j + 3] == ")"
                     {
                         if let (Ok(x), Ok(y)) = (
-                            content_parts[j + 1].parse::<f64>(),
-                            content_parts[j + 2].parse::<f64>(),
+                            content_parts[j + 1].parse_finite(),
+                            content_parts[j + 2].parse_finite(),
                         ) {
                             die_area_points.push((x, y));
-                            println!("[DBG]     Die area point: ({x:.1}, {y:.1})");
+                            eprintln!("[DBG]     Die area point: ({x:.1}, {y:.1})");
                         }
                         j += 4; // Move past ( x y )
                     } else if content_parts[j] == ";" {
@@ -177,7 +447,7 @@ fn parse_def_simple(input: &str) -> IResult<&str, Def> {
             }
             "COMPONENTS" if parts.len() > 1 => {
                 if let Ok(num_components) = parts[1].parse::<usize>() {
-                    println!("[DBG]   Found COMPONENTS section with {num_components} components");
+                    eprintln!("[DBG]   Found COMPONENTS section with {num_components} components");
                     i += 1;
 
                     // Use the new unified parsing framework with preprocessed lines
@@ -201,7 +471,7 @@ fn parse_def_simple(input: &str) -> IResult<&str, Def> {
                                     } else {
                                         "no placement".to_string()
                                     };
-                                println!(
+                                eprintln!(
                                     "[DBG]     Component: {} ({}) {}",
                                     component.name, component.macro_name, placement_info
                                 );
@@ -210,7 +480,7 @@ fn parse_def_simple(input: &str) -> IResult<&str, Def> {
                             i = next_index;
                         }
                         Err(e) => {
-                            println!("[DBG]   Error parsing COMPONENTS section: {e}");
+                            eprintln!("[DBG]   Error parsing COMPONENTS section: {e}");
                             // Fallback: skip to END COMPONENTS
                             while i < lines.len() && !lines[i].trim().starts_with("END COMPONENTS")
                             {
@@ -222,7 +492,7 @@ fn parse_def_simple(input: &str) -> IResult<&str, Def> {
             }
             "PINS" if parts.len() > 1 => {
                 if let Ok(num_pins) = parts[1].parse::<usize>() {
-                    println!("[DBG]   Found PINS section with {num_pins} pins");
+                    eprintln!("[DBG]   Found PINS section with {num_pins} pins");
                     i += 1;
 
                     // Use the new unified parsing framework with preprocessed lines
@@ -234,7 +504,7 @@ fn parse_def_simple(input: &str) -> IResult<&str, Def> {
                     match multi_parser.parse_section_preprocessed(lines, i, "END PINS") {
                         Ok((parsed_pins, next_index)) => {
                             for pin in parsed_pins {
-                                println!(
+                                eprintln!(
                                     "[DBG]     Pin: {} at ({:.1}, {:.1}) dir={} use={}",
                                     pin.name, pin.x, pin.y, pin.direction, pin.use_type
                                 );
@@ -243,7 +513,7 @@ fn parse_def_simple(input: &str) -> IResult<&str, Def> {
                             i = next_index;
                         }
                         Err(e) => {
-                            println!("[DBG]   Error parsing PINS section: {e}");
+                            eprintln!("[DBG]   Error parsing PINS section: {e}");
                             // Fallback: skip to END PINS
                             while i < lines.len() && !lines[i].trim().starts_with("END PINS") {
                                 i += 1;
@@ -254,7 +524,7 @@ fn parse_def_simple(input: &str) -> IResult<&str, Def> {
             }
             "SPECIALNETS" if parts.len() > 1 => {
                 if let Ok(num_special_nets) = parts[1].parse::<usize>() {
-                    println!(
+                    eprintln!(
                         "[DBG]   Found SPECIALNETS section with {num_special_nets} special nets"
                     );
                     i += 1;
@@ -269,7 +539,7 @@ fn parse_def_simple(input: &str) -> IResult<&str, Def> {
                     match multi_parser.parse_section_preprocessed(lines, i, "END SPECIALNETS") {
                         Ok((parsed_special_nets, next_index)) => {
                             for special_net in parsed_special_nets {
-                                println!(
+                                eprintln!(
                                     "[DBG]     SpecialNet: {} with {} routes",
                                     special_net.name,
                                     special_net.routes.len()
@@ -279,7 +549,7 @@ fn parse_def_simple(input: &str) -> IResult<&str, Def> {
                             i = next_index;
                         }
                         Err(e) => {
-                            println!("[DBG]   Error parsing SPECIALNETS section: {e}");
+                            eprintln!("[DBG]   Error parsing SPECIALNETS section: {e}");
                             // Fallback: skip to END SPECIALNETS
                             while i < lines.len() && !lines[i].trim().starts_with("END SPECIALNETS")
                             {
@@ -291,7 +561,7 @@ fn parse_def_simple(input: &str) -> IResult<&str, Def> {
             }
             "NETS" if parts.len() > 1 => {
                 if let Ok(num_nets) = parts[1].parse::<usize>() {
-                    println!("[DBG]   Found NETS section with {num_nets} nets");
+                    eprintln!("[DBG]   Found NETS section with {num_nets} nets");
                     i += 1;
 
                     // Use the new unified parsing framework with preprocessed lines
@@ -303,7 +573,7 @@ fn parse_def_simple(input: &str) -> IResult<&str, Def> {
                     match multi_parser.parse_section_preprocessed(lines, i, "END NETS") {
                         Ok((parsed_nets, next_index)) => {
                             for net in parsed_nets {
-                                println!(
+                                eprintln!(
                                     "[DBG]     Net: {} with {} instances, {} pins",
                                     net.name, net.connections, net.pins
                                 );
@@ -312,7 +582,7 @@ fn parse_def_simple(input: &str) -> IResult<&str, Def> {
                             i = next_index;
                         }
                         Err(e) => {
-                            println!("[DBG]   Error parsing NETS section: {e}");
+                            eprintln!("[DBG]   Error parsing NETS section: {e}");
                             // Fallback: skip to END NETS
                             while i < lines.len() && !lines[i].trim().starts_with("END NETS") {
                                 i += 1;
@@ -323,7 +593,7 @@ fn parse_def_simple(input: &str) -> IResult<&str, Def> {
             }
             "VIAS" if parts.len() > 1 => {
                 if let Ok(num_vias) = parts[1].parse::<usize>() {
-                    println!("[DBG]   Found VIAS section with {num_vias} vias");
+                    eprintln!("[DBG]   Found VIAS section with {num_vias} vias");
                     i += 1;
 
                     // Parse vias until END VIAS
@@ -337,7 +607,7 @@ fn parse_def_simple(input: &str) -> IResult<&str, Def> {
                         if via_parts.len() >= 2 && via_parts[0] == "-" {
                             // Via definition: - viaName
                             let via_name = via_parts[1].to_string();
-                            println!("[DBG]     Parsing VIA: {via_name}");
+                            eprintln!("[DBG]     Parsing VIA: {via_name}");
 
                             let mut layers = Vec::new();
 
@@ -358,168 +628,159 @@ fn parse_def_simple(input: &str) -> IResult<&str, Def> {
                                     // Layer-specific definition
                                     if content_parts.len() >= 2 {
                                         match content_parts[1] {
-                                            "RECT" => {
+                                            "RECT" if content_parts.len() >= 8 => {
                                                 // + RECT layerName ( xl yl ) ( xh yh )
-                                                if content_parts.len() >= 8 {
-                                                    let layer_name = content_parts[2].to_string();
-                                                    if let (Ok(xl), Ok(yl), Ok(xh), Ok(yh)) = (
-                                                        content_parts[4].parse::<f64>(),
-                                                        content_parts[5].parse::<f64>(),
-                                                        content_parts[7].parse::<f64>(),
-                                                        content_parts[8].parse::<f64>(),
-                                                    ) {
-                                                        // Find or create layer
-                                                        let layer_index = layers.iter().position(
-                                                            |l: &DefViaLayer| {
-                                                                l.layer_name == layer_name
+                                                let layer_name = content_parts[2].to_string();
+                                                if let (Ok(xl), Ok(yl), Ok(xh), Ok(yh)) = (
+                                                    content_parts[4].parse_finite(),
+                                                    content_parts[5].parse_finite(),
+                                                    content_parts[7].parse_finite(),
+                                                    content_parts[8].parse_finite(),
+                                                ) {
+                                                    // Find or create layer
+                                                    let layer_index = layers.iter().position(
+                                                        |l: &DefViaLayer| {
+                                                            l.layer_name == layer_name
+                                                        },
+                                                    );
+
+                                                    if let Some(idx) = layer_index {
+                                                        layers[idx].rects.push(
+                                                            crate::def::DefRect {
+                                                                layer: layer_name.clone(),
+                                                                xl,
+                                                                yl,
+                                                                xh,
+                                                                yh,
                                                             },
                                                         );
+                                                    } else {
+                                                        let mut new_layer = DefViaLayer {
+                                                            layer_name: layer_name.clone(),
+                                                            mask: None,
+                                                            rects: Vec::new(),
+                                                            polygons: Vec::new(),
+                                                        };
+                                                        new_layer.rects.push(crate::def::DefRect {
+                                                            layer: layer_name,
+                                                            xl,
+                                                            yl,
+                                                            xh,
+                                                            yh,
+                                                        });
+                                                        layers.push(new_layer);
+                                                    }
 
-                                                        if let Some(idx) = layer_index {
-                                                            layers[idx].rects.push(
-                                                                crate::def::DefRect {
-                                                                    layer: layer_name.clone(),
-                                                                    xl,
-                                                                    yl,
-                                                                    xh,
-                                                                    yh,
-                                                                },
-                                                            );
-                                                        } else {
-                                                            let mut new_layer = DefViaLayer {
-                                                                layer_name: layer_name.clone(),
-                                                                mask: None,
-                                                                rects: Vec::new(),
-                                                                polygons: Vec::new(),
-                                                            };
-                                                            new_layer.rects.push(
-                                                                crate::def::DefRect {
-                                                                    layer: layer_name,
-                                                                    xl,
-                                                                    yl,
-                                                                    xh,
-                                                                    yh,
-                                                                },
-                                                            );
-                                                            layers.push(new_layer);
-                                                        }
-
-                                                        println!("[DBG]       Added RECT on layer {} at ({:.1},{:.1}) -> ({:.1},{:.1})", 
+                                                    eprintln!("[DBG]       Added RECT on layer {} at ({:.1},{:.1}) -> ({:.1},{:.1})", 
                                                                content_parts[2], xl, yl, xh, yh);
-                                                    }
                                                 }
                                             }
-                                            "POLYGON" => {
+                                            "POLYGON" if content_parts.len() >= 3 => {
                                                 // + POLYGON layerName [+ MASK maskNum] ( x1 y1 ) ( x2 y2 ) ...
-                                                if content_parts.len() >= 3 {
-                                                    let layer_name = content_parts[2].to_string();
-                                                    let mut mask_num: Option<i32> = None;
-
-                                                    // Collect all POLYGON content across multiple lines until semicolon
-                                                    let mut polygon_content = String::new();
-                                                    polygon_content.push_str(via_content_line);
-
-                                                    // Continue collecting until we find a semicolon
-                                                    let mut poly_i = i + 1;
-                                                    while !polygon_content.contains(';')
-                                                        && poly_i < lines.len()
+                                                let layer_name = content_parts[2].to_string();
+                                                let mut mask_num: Option<i32> = None;
+
+                                                // Collect all POLYGON content across multiple lines until semicolon
+                                                let mut polygon_content = String::new();
+                                                polygon_content.push_str(via_content_line);
+
+                                                // Continue collecting until we find a semicolon
+                                                let mut poly_i = i + 1;
+                                                while !polygon_content.contains(';')
+                                                    && poly_i < lines.len()
+                                                {
+                                                    let next_line = lines[poly_i].trim();
+                                                    // Stop if we hit next via definition or END VIAS
+                                                    if next_line.starts_with('-')
+                                                        || next_line.starts_with("END VIAS")
                                                     {
-                                                        let next_line = lines[poly_i].trim();
-                                                        // Stop if we hit next via definition or END VIAS
-                                                        if next_line.starts_with('-')
-                                                            || next_line.starts_with("END VIAS")
-                                                        {
-                                                            break;
-                                                        }
-                                                        polygon_content.push(' ');
-                                                        polygon_content.push_str(next_line);
-                                                        poly_i += 1;
+                                                        break;
                                                     }
+                                                    polygon_content.push(' ');
+                                                    polygon_content.push_str(next_line);
+                                                    poly_i += 1;
+                                                }
 
-                                                    // Update main loop index
-                                                    i = poly_i - 1;
-
-                                                    // Parse all content
-                                                    let poly_parts: Vec<&str> = polygon_content
-                                                        .split_whitespace()
-                                                        .collect();
-                                                    let mut part_idx = 3; // Skip "+ POLYGON layerName"
-
-                                                    // Check for MASK
-                                                    if part_idx < poly_parts.len()
-                                                        && poly_parts[part_idx] == "+"
-                                                        && part_idx + 1 < poly_parts.len()
-                                                        && poly_parts[part_idx + 1] == "MASK"
-                                                    {
-                                                        part_idx += 2;
-                                                        if part_idx < poly_parts.len() {
-                                                            if let Ok(mask) =
-                                                                poly_parts[part_idx].parse::<i32>()
-                                                            {
-                                                                mask_num = Some(mask);
-                                                            }
-                                                            part_idx += 1;
+                                                // Update main loop index
+                                                i = poly_i - 1;
+
+                                                // Parse all content
+                                                let poly_parts: Vec<&str> =
+                                                    polygon_content.split_whitespace().collect();
+                                                let mut part_idx = 3; // Skip "+ POLYGON layerName"
+
+                                                // Check for MASK
+                                                if part_idx < poly_parts.len()
+                                                    && poly_parts[part_idx] == "+"
+                                                    && part_idx + 1 < poly_parts.len()
+                                                    && poly_parts[part_idx + 1] == "MASK"
+                                                {
+                                                    part_idx += 2;
+                                                    if part_idx < poly_parts.len() {
+                                                        if let Ok(mask) =
+                                                            poly_parts[part_idx].parse::<i32>()
+                                                        {
+                                                            mask_num = Some(mask);
                                                         }
+                                                        part_idx += 1;
                                                     }
+                                                }
 
-                                                    // Parse coordinate pairs within parentheses
-                                                    let mut points = Vec::new();
-                                                    while part_idx < poly_parts.len() {
-                                                        if poly_parts[part_idx] == "("
-                                                            && part_idx + 3 < poly_parts.len()
-                                                            && poly_parts[part_idx + 3] == ")"
-                                                        {
-                                                            if let (Ok(x), Ok(y)) = (
-                                                                poly_parts[part_idx + 1]
-                                                                    .parse::<f64>(),
-                                                                poly_parts[part_idx + 2]
-                                                                    .parse::<f64>(),
-                                                            ) {
-                                                                points.push((x, y));
-                                                                part_idx += 4; // Move past ( x y )
-                                                            } else {
-                                                                break;
-                                                            }
-                                                        } else if poly_parts[part_idx] == ";" {
-                                                            break;
+                                                // Parse coordinate pairs within parentheses
+                                                let mut points = Vec::new();
+                                                while part_idx < poly_parts.len() {
+                                                    if poly_parts[part_idx] == "("
+                                                        && part_idx + 3 < poly_parts.len()
+                                                        && poly_parts[part_idx + 3] == ")"
+                                                    {
+                                                        if let (Ok(x), Ok(y)) = (
+                                                            poly_parts[part_idx + 1].parse_finite(),
+                                                            poly_parts[part_idx + 2].parse_finite(),
+                                                        ) {
+                                                            points.push((x, y));
+                                                            part_idx += 4; // Move past ( x y )
                                                         } else {
-                                                            part_idx += 1;
+                                                            break;
                                                         }
+                                                    } else if poly_parts[part_idx] == ";" {
+                                                        break;
+                                                    } else {
+                                                        part_idx += 1;
                                                     }
+                                                }
 
-                                                    if !points.is_empty() {
-                                                        // Find or create layer
-                                                        let layer_index = layers.iter().position(
-                                                            |l: &DefViaLayer| {
-                                                                l.layer_name == layer_name
-                                                            },
-                                                        );
-
-                                                        if let Some(idx) = layer_index {
-                                                            layers[idx].polygons.push(DefPolygon {
-                                                                points: points.clone(),
-                                                            });
-                                                            if mask_num.is_some() {
-                                                                layers[idx].mask = mask_num;
-                                                            }
-                                                        } else {
-                                                            let mut new_layer = DefViaLayer {
-                                                                layer_name: layer_name.clone(),
-                                                                mask: mask_num,
-                                                                rects: Vec::new(),
-                                                                polygons: Vec::new(),
-                                                            };
-                                                            new_layer.polygons.push(DefPolygon {
-                                                                points: points.clone(),
-                                                            });
-                                                            layers.push(new_layer);
+                                                if !points.is_empty() {
+                                                    // Find or create layer
+                                                    let layer_index = layers.iter().position(
+                                                        |l: &DefViaLayer| {
+                                                            l.layer_name == layer_name
+                                                        },
+                                                    );
+
+                                                    if let Some(idx) = layer_index {
+                                                        layers[idx].polygons.push(DefPolygon {
+                                                            points: points.clone(),
+                                                        });
+                                                        if mask_num.is_some() {
+                                                            layers[idx].mask = mask_num;
                                                         }
+                                                    } else {
+                                                        let mut new_layer = DefViaLayer {
+                                                            layer_name: layer_name.clone(),
+                                                            mask: mask_num,
+                                                            rects: Vec::new(),
+                                                            polygons: Vec::new(),
+                                                        };
+                                                        new_layer.polygons.push(DefPolygon {
+                                                            points: points.clone(),
+                                                        });
+                                                        layers.push(new_layer);
+                                                    }
 
-                                                        println!("[DBG]       Added POLYGON on layer {} with {} points{}: {:?}", 
+                                                    eprintln!("[DBG]       Added POLYGON on layer {} with {} points{}: {:?}", 
                                                                layer_name, points.len(),
                                                                if let Some(mask) = mask_num { format!(" MASK {mask}") } else { String::new() },
                                                                points);
-                                                    }
                                                 }
                                             }
                                             _ => {}
@@ -549,31 +810,79 @@ fn parse_def_simple(input: &str) -> IResult<&str, Def> {
         i += 1;
     }
 
-    println!(
+    eprintln!(
         "[PASS] DEF parsed: {} die points, {} components, {} pins",
         die_area_points.len(),
         components.len(),
         pins.len()
     );
 
-    Ok((
-        "",
-        Def {
-            die_area_points,
-            g_cell_grid_x: Vec::new(),
-            g_cell_grid_y: Vec::new(),
-            pins,
-            nets,
-            special_nets, // Parsed from SPECIALNETS section
-            components,
-            rows: Vec::new(),
-            tracks_x: Vec::new(),
-            tracks_y: Vec::new(),
-            vias,
-        },
-    ))
+    Def {
+        design,
+        die_area_points,
+        g_cell_grid_x,
+        g_cell_grid_y,
+        pins,
+        nets,
+        special_nets, // Parsed from SPECIALNETS section
+        components,
+        rows,
+        tracks_x,
+        tracks_y,
+        vias,
+        blockages,
+        fills,
+        regions,
+        groups,
+        scan_chains,
+        bus_bit_chars,
+        units_distance_microns,
+    }
 }
 
+#[allow(dead_code)]
 pub fn parse_def(input: &str) -> IResult<&str, Def> {
-    parse_def_simple(input)
+    parse_def_simple(
+        input,
+        &DefParseOptions::default(),
+        &mut ProgressReporter::new(None),
+    )
+}
+
+/// Parse DEF content, skipping the sections excluded by `options`
+#[allow(dead_code)]
+pub fn parse_def_with_options<'a>(
+    input: &'a str,
+    options: &DefParseOptions,
+) -> IResult<&'a str, Def> {
+    parse_def_with_progress(input, options, None)
+}
+
+/// Parse DEF content like [`parse_def_with_options`], reporting the share
+/// of the logical lines parsed
+pub fn parse_def_with_progress<'a>(
+    input: &'a str,
+    options: &DefParseOptions,
+    progress: Option<&ProgressCallback>,
+) -> IResult<&'a str, Def> {
+    crate::profile_scope!("parse_def");
+    let mut progress = ProgressReporter::new(progress);
+    let result = parse_def_simple(input, options, &mut progress);
+    if let Ok((_, def)) = &result {
+        progress.report(1.0, item_count(def), "END DESIGN");
+    }
+    result
+}
+
+/// Items of the list sections, the items counted in the progress
+pub(super) fn item_count(def: &Def) -> usize {
+    def.components.len()
+        + def.pins.len()
+        + def.special_nets.len()
+        + def.nets.len()
+        + def.blockages.len()
+        + def.regions.len()
+        + def.groups.len()
+        + def.fills.len()
+        + def.scan_chains.len()
 }
@@ -0,0 +1,293 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Cell swap and move ECOs
+//!
+//! Records the instances whose macro was replaced by another member of its
+//! footprint family or that were dragged to another location, so the change
+//! can be exported either as a DEF COMPONENTS section of the changed
+//! instances or as an `ecoChangeCell` and `placeInstance` script for the P&R
+//! tool.
+
+use super::{writer, Def};
+use std::fmt::Write;
+
+/// One instance moved to another macro
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellSwap {
+    pub instance: String,
+    /// Macro of the instance in the loaded DEF
+    pub from: String,
+    pub to: String,
+}
+
+/// One instance moved to another location, in database units
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellMove {
+    pub instance: String,
+    /// Location of the instance in the loaded DEF
+    pub from: (f64, f64),
+    pub to: (f64, f64),
+}
+
+impl CellMove {
+    /// Displacement in database units
+    pub fn delta(&self) -> (f64, f64) {
+        (self.to.0 - self.from.0, self.to.1 - self.from.1)
+    }
+}
+
+/// Cell swaps and moves applied to the loaded DEF, in the order they were
+/// made
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EcoLog {
+    swaps: Vec<CellSwap>,
+    moves: Vec<CellMove>,
+}
+
+impl EcoLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn swaps(&self) -> &[CellSwap] {
+        &self.swaps
+    }
+
+    pub fn moves(&self) -> &[CellMove] {
+        &self.moves
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.swaps.is_empty() && self.moves.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.swaps.clear();
+        self.moves.clear();
+    }
+
+    /// Names of the swapped or moved instances, each once, swaps first
+    pub fn instances(&self) -> Vec<&str> {
+        let mut instances: Vec<&str> = self.swaps.iter().map(|s| s.instance.as_str()).collect();
+        for cell_move in &self.moves {
+            if !instances.contains(&cell_move.instance.as_str()) {
+                instances.push(&cell_move.instance);
+            }
+        }
+        instances
+    }
+
+    /// Change the macro of `instance` in `def` to `to` and record it
+    ///
+    /// Swapping an instance again keeps one entry with the original macro;
+    /// swapping it back removes the entry.
+    pub fn swap(&mut self, def: &mut Def, instance: &str, to: &str) -> Result<(), String> {
+        let component = def
+            .components
+            .iter_mut()
+            .find(|c| c.name == instance)
+            .ok_or_else(|| format!("Component {instance} not found"))?;
+        if component.macro_name == to {
+            return Ok(());
+        }
+        let current = std::mem::replace(&mut component.macro_name, to.to_string());
+
+        match self.swaps.iter().position(|s| s.instance == instance) {
+            Some(index) if self.swaps[index].from == to => {
+                self.swaps.remove(index);
+            }
+            Some(index) => self.swaps[index].to = to.to_string(),
+            None => self.swaps.push(CellSwap {
+                instance: instance.to_string(),
+                from: current,
+                to: to.to_string(),
+            }),
+        }
+        Ok(())
+    }
+
+    /// Move `instance` in `def` to the location `to` and record it
+    ///
+    /// Moving an instance again keeps one entry with the original location;
+    /// moving it back removes the entry. Unplaced instances cannot be moved.
+    pub fn move_to(&mut self, def: &mut Def, instance: &str, to: (f64, f64)) -> Result<(), String> {
+        let placement = def
+            .components
+            .iter_mut()
+            .find(|c| c.name == instance)
+            .ok_or_else(|| format!("Component {instance} not found"))?
+            .placement
+            .as_mut()
+            .filter(|p| p.placement_type != "UNPLACED")
+            .ok_or_else(|| format!("Component {instance} is not placed"))?;
+        let current = (placement.x, placement.y);
+        if current == to {
+            return Ok(());
+        }
+        (placement.x, placement.y) = to;
+
+        match self.moves.iter().position(|m| m.instance == instance) {
+            Some(index) if self.moves[index].from == to => {
+                self.moves.remove(index);
+            }
+            Some(index) => self.moves[index].to = to,
+            None => self.moves.push(CellMove {
+                instance: instance.to_string(),
+                from: current,
+                to,
+            }),
+        }
+        Ok(())
+    }
+
+    /// Undo the swap and move of `instance` in `def`
+    pub fn revert(&mut self, def: &mut Def, instance: &str) -> Result<(), String> {
+        if let Some(from) = self
+            .swaps
+            .iter()
+            .find(|s| s.instance == instance)
+            .map(|s| s.from.clone())
+        {
+            self.swap(def, instance, &from)?;
+        }
+        if let Some(from) = self
+            .moves
+            .iter()
+            .find(|m| m.instance == instance)
+            .map(|m| m.from)
+        {
+            self.move_to(def, instance, from)?;
+        }
+        Ok(())
+    }
+
+    /// COMPONENTS section with the swapped and moved instances of `def`
+    pub fn to_def_components(&self, def: &Def) -> String {
+        let instances = self.instances();
+        let mut out = format!("COMPONENTS {} ;\n", instances.len());
+        for instance in instances {
+            if let Some(component) = def.components.iter().find(|c| c.name == instance) {
+                out.push_str(&writer::write_component(component));
+            }
+        }
+        out.push_str("END COMPONENTS\n");
+        out
+    }
+
+    /// Tcl script with one `ecoChangeCell` per swapped instance and one
+    /// `placeInstance` in microns per moved instance of `def`, at
+    /// `db_units` per micron
+    pub fn to_eco_script(&self, def: &Def, db_units: f64) -> String {
+        let design = if def.design.is_empty() {
+            "design"
+        } else {
+            def.design.as_str()
+        };
+        let mut out = format!("# Cell swaps and moves for {design}\n");
+        for swap in &self.swaps {
+            let _ = writeln!(
+                out,
+                "ecoChangeCell -inst {{{}}} -cell {} ;# was {}",
+                swap.instance, swap.to, swap.from
+            );
+        }
+        for cell_move in &self.moves {
+            let orientation = def
+                .components
+                .iter()
+                .find(|c| c.name == cell_move.instance)
+                .and_then(|c| c.placement.as_ref())
+                .map_or("N", |p| p.orientation.as_str());
+            let _ = writeln!(
+                out,
+                "placeInstance {{{}}} {} {} {orientation} ;# was {} {}",
+                cell_move.instance,
+                cell_move.to.0 / db_units,
+                cell_move.to.1 / db_units,
+                cell_move.from.0 / db_units,
+                cell_move.from.1 / db_units
+            );
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+
+    fn design() -> Def {
+        let component = |name: &str| test_fixtures::component(name, "INVX1", 1000.0, 2000.0, "FS");
+        Def {
+            components: vec![component("u1"), component("u2")],
+            ..Def::default()
+        }
+    }
+
+    #[test]
+    fn test_swap_keeps_original_macro() {
+        let mut def = design();
+        let mut eco = EcoLog::new();
+        eco.swap(&mut def, "u1", "INVX2").unwrap();
+        eco.swap(&mut def, "u1", "INVX4").unwrap();
+        assert_eq!(def.components[0].macro_name, "INVX4");
+        assert_eq!(
+            eco.swaps(),
+            [CellSwap {
+                instance: "u1".to_string(),
+                from: "INVX1".to_string(),
+                to: "INVX4".to_string(),
+            }]
+        );
+
+        // Back to the original macro: nothing left to export
+        eco.swap(&mut def, "u1", "INVX1").unwrap();
+        assert!(eco.is_empty());
+        assert!(eco.swap(&mut def, "missing", "INVX2").is_err());
+    }
+
+    #[test]
+    fn test_eco_exports() {
+        let mut def = design();
+        let mut eco = EcoLog::new();
+        eco.swap(&mut def, "u2", "INVX2").unwrap();
+
+        eco.move_to(&mut def, "u1", (1500.0, 2000.0)).unwrap();
+        eco.move_to(&mut def, "u2", (0.0, 0.0)).unwrap();
+        assert_eq!(eco.instances(), ["u2", "u1"]);
+
+        let components = eco.to_def_components(&def);
+        assert!(components.starts_with("COMPONENTS 2 ;\n- u2 INVX2"));
+        assert!(components.contains("- u1 INVX1\n    + PLACED ( 1500 2000 ) FS"));
+        assert!(components.ends_with("END COMPONENTS\n"));
+        let script = eco.to_eco_script(&def, 1000.0);
+        assert!(script.contains("ecoChangeCell -inst {u2} -cell INVX2 ;# was INVX1"));
+        assert!(script.contains("placeInstance {u1} 1.5 2 FS ;# was 1 2"));
+    }
+
+    #[test]
+    fn test_move_and_revert() {
+        let mut def = design();
+        let mut eco = EcoLog::new();
+        eco.move_to(&mut def, "u1", (1200.0, 2000.0)).unwrap();
+        eco.move_to(&mut def, "u1", (1400.0, 1800.0)).unwrap();
+        assert_eq!(eco.moves()[0].delta(), (400.0, -200.0));
+
+        // Back to the original location: nothing left to export
+        eco.move_to(&mut def, "u1", (1000.0, 2000.0)).unwrap();
+        assert!(eco.is_empty());
+
+        eco.swap(&mut def, "u2", "INVX2").unwrap();
+        eco.move_to(&mut def, "u2", (0.0, 0.0)).unwrap();
+        eco.revert(&mut def, "u2").unwrap();
+        assert!(eco.is_empty());
+        let placement = def.components[1].placement.as_ref().unwrap();
+        assert_eq!(def.components[1].macro_name, "INVX1");
+        assert_eq!((placement.x, placement.y), (1000.0, 2000.0));
+
+        def.components[0].placement = None;
+        assert!(eco.move_to(&mut def, "u1", (0.0, 0.0)).is_err());
+    }
+}
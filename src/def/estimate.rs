@@ -0,0 +1,248 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Memory footprint estimate for DEF files
+//!
+//! A quick pre-scan reads the section headers (`COMPONENTS 1200 ;`) and
+//! measures how many bytes each section spans, without parsing any items.
+//! The estimate is deliberately rough; it only has to tell a file that fits
+//! comfortably from one that will not.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use super::stream::{STREAMED_SECTIONS, STREAMING_THRESHOLD};
+use super::{DefParseOptions, FILTERABLE_SECTIONS};
+
+/// Bytes held per byte of file text while parsing: the content string, the
+/// preprocessed logical lines and the per-line token vectors. Streamed
+/// sections of files above the streaming threshold hold no text.
+const TEXT_OVERHEAD_FACTOR: u64 = 3;
+/// Bytes of parsed structures per byte of section text
+const SECTION_BYTES_FACTOR: u64 = 2;
+/// Fixed struct overhead per parsed item
+const ITEM_OVERHEAD_BYTES: u64 = 256;
+/// Warn when the estimate exceeds this share of the available memory
+const AVAILABLE_MEMORY_SHARE: f64 = 0.8;
+
+/// Declared item count and text size of one DEF section
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SectionSize {
+    pub count: u64,
+    pub bytes: u64,
+}
+
+/// Result of a DEF pre-scan
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryEstimate {
+    pub file_size: u64,
+    pub sections: BTreeMap<String, SectionSize>,
+}
+
+impl MemoryEstimate {
+    /// Pre-scan DEF text from a reader
+    pub fn from_reader<R: BufRead>(mut reader: R) -> io::Result<Self> {
+        let mut estimate = Self::default();
+        let mut current: Option<&'static str> = None;
+        let mut line = Vec::new();
+
+        loop {
+            line.clear();
+            let read = reader.read_until(b'\n', &mut line)?;
+            if read == 0 {
+                break;
+            }
+            estimate.file_size += read as u64;
+
+            let text = String::from_utf8_lossy(&line);
+            let mut tokens = text.split_whitespace();
+            let first = tokens.next();
+
+            if current.is_none() {
+                if let Some(section) = FILTERABLE_SECTIONS.iter().find(|s| Some(**s) == first) {
+                    if let Some(count) = tokens.next().and_then(|t| t.parse::<u64>().ok()) {
+                        current = Some(section);
+                        estimate
+                            .sections
+                            .entry(section.to_string())
+                            .or_default()
+                            .count += count;
+                    }
+                }
+            }
+
+            if let Some(section) = current {
+                if let Some(size) = estimate.sections.get_mut(section) {
+                    size.bytes += read as u64;
+                }
+                if first == Some("END") && tokens.next() == Some(section) {
+                    current = None;
+                }
+            }
+        }
+
+        Ok(estimate)
+    }
+
+    /// Estimated peak memory of a full parse, in bytes
+    pub fn total_bytes(&self) -> u64 {
+        self.bytes_with_options(&DefParseOptions::default())
+    }
+
+    /// Estimated peak memory when parsing with `options`, in bytes
+    ///
+    /// Skipped sections still cost their share of the file text, only the
+    /// parsed structures are saved. Files that are parsed while reading only
+    /// hold the text outside the streamed sections.
+    pub fn bytes_with_options(&self, options: &DefParseOptions) -> u64 {
+        let parsed: u64 = self
+            .sections
+            .iter()
+            .filter(|(name, _)| !options.skips(name))
+            .map(|(_, size)| size.bytes * SECTION_BYTES_FACTOR + size.count * ITEM_OVERHEAD_BYTES)
+            .sum();
+        let text = if self.file_size >= STREAMING_THRESHOLD {
+            let streamed: u64 = self
+                .sections
+                .iter()
+                .filter(|(name, _)| STREAMED_SECTIONS.contains(&name.as_str()))
+                .map(|(_, size)| size.bytes)
+                .sum();
+            self.file_size.saturating_sub(streamed)
+        } else {
+            self.file_size
+        };
+        text * TEXT_OVERHEAD_FACTOR + parsed
+    }
+
+    /// Whether parsing with `options` likely exceeds `available` bytes
+    pub fn exceeds(&self, options: &DefParseOptions, available: u64) -> bool {
+        self.bytes_with_options(options) as f64 > available as f64 * AVAILABLE_MEMORY_SHARE
+    }
+
+    /// Section filter that makes the estimate fit, skipping the largest
+    /// sections first
+    ///
+    /// COMPONENTS are never skipped since nothing useful is left without
+    /// them. Returns None if no filter is needed or none is enough.
+    pub fn suggest_filter(&self, available: u64) -> Option<DefParseOptions> {
+        if !self.exceeds(&DefParseOptions::default(), available) {
+            return None;
+        }
+
+        let mut candidates: Vec<(&String, &SectionSize)> = self
+            .sections
+            .iter()
+            .filter(|(name, _)| name.as_str() != "COMPONENTS")
+            .collect();
+        candidates.sort_by_key(|(_, size)| std::cmp::Reverse(size.bytes));
+
+        let mut options = DefParseOptions::default();
+        for (name, _) in candidates {
+            options = options.skip(name);
+            if !self.exceeds(&options, available) {
+                return Some(options);
+            }
+        }
+        None
+    }
+}
+
+/// Pre-scan a DEF file without parsing it
+pub fn prescan_def<P: AsRef<Path>>(path: P) -> io::Result<MemoryEstimate> {
+    MemoryEstimate::from_reader(BufReader::new(File::open(path)?))
+}
+
+/// Memory currently available to new allocations, if the platform reports it
+pub fn available_memory() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    meminfo.lines().find_map(|line| {
+        let value = line.strip_prefix("MemAvailable:")?;
+        let kib = value
+            .trim()
+            .trim_end_matches("kB")
+            .trim()
+            .parse::<u64>()
+            .ok()?;
+        Some(kib * 1024)
+    })
+}
+
+/// Human readable byte count, e.g. "1.5 GiB"
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "VERSION 5.8 ;
+COMPONENTS 2 ;
+- u1 INV + PLACED ( 0 0 ) N ;
+- u2 INV + PLACED ( 10 0 ) N ;
+END COMPONENTS
+NETS 1 ;
+- n1 ( u1 Y ) ( u2 A )
+  + ROUTED M1 ( 0 0 ) ( 10 0 ) ;
+END NETS
+END DESIGN
+";
+
+    #[test]
+    fn test_prescan_counts_sections() {
+        let estimate = MemoryEstimate::from_reader(SAMPLE.as_bytes()).unwrap();
+        assert_eq!(estimate.file_size, SAMPLE.len() as u64);
+        assert_eq!(estimate.sections["COMPONENTS"].count, 2);
+        assert_eq!(estimate.sections["NETS"].count, 1);
+        assert_eq!(
+            estimate.sections["NETS"].bytes,
+            "NETS 1 ;\n- n1 ( u1 Y ) ( u2 A )\n  + ROUTED M1 ( 0 0 ) ( 10 0 ) ;\nEND NETS\n".len()
+                as u64
+        );
+        assert!(!estimate.sections.contains_key("PINS"));
+    }
+
+    #[test]
+    fn test_skipping_sections_lowers_estimate() {
+        let estimate = MemoryEstimate::from_reader(SAMPLE.as_bytes()).unwrap();
+        let filtered = estimate.bytes_with_options(&DefParseOptions::default().skip("NETS"));
+        assert!(filtered < estimate.total_bytes());
+        assert!(filtered >= estimate.file_size * TEXT_OVERHEAD_FACTOR);
+    }
+
+    #[test]
+    fn test_suggest_filter() {
+        let estimate = MemoryEstimate::from_reader(SAMPLE.as_bytes()).unwrap();
+        assert_eq!(estimate.suggest_filter(u64::MAX), None);
+
+        // Just enough memory once NETS is skipped
+        let needed = estimate.bytes_with_options(&DefParseOptions::default().skip("NETS"));
+        let available = (needed as f64 / AVAILABLE_MEMORY_SHARE).ceil() as u64;
+        let options = estimate.suggest_filter(available).unwrap();
+        assert!(options.skips("NETS"));
+        assert!(!options.skips("COMPONENTS"));
+
+        assert_eq!(estimate.suggest_filter(1), None);
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+}
@@ -0,0 +1,161 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Routing layer usage of DEF nets
+//!
+//! Sums the routed wire length of a net per layer and counts its vias.
+//! Wire length is the centerline length between consecutive points of a
+//! route, so extensions and via enclosures are not counted. A long net that
+//! stays on the lowest layers is a candidate for layer promotion.
+
+use super::DefNet;
+use std::collections::HashMap;
+
+/// Routed length per layer and via count of one net
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetLayerUsage {
+    pub net: String,
+    /// Wire length in microns per layer, in routing layer order
+    pub layers: Vec<(String, f64)>,
+    pub vias: usize,
+}
+
+impl NetLayerUsage {
+    /// Total routed length in microns
+    pub fn total_length(&self) -> f64 {
+        self.layers.iter().map(|(_, length)| length).sum()
+    }
+
+    /// Routed length on `layer` in microns, zero when unused
+    pub fn length_on(&self, layer: &str) -> f64 {
+        self.layers
+            .iter()
+            .find(|(name, _)| name == layer)
+            .map_or(0.0, |&(_, length)| length)
+    }
+}
+
+/// Layer usage of `net`
+///
+/// Layers are sorted by their position in `layer_order`, normally the
+/// ROUTING layers of the technology LEF from bottom to top, and layers not
+/// listed there follow in name order.
+pub fn net_layer_usage(net: &DefNet, db_units: f64, layer_order: &[String]) -> NetLayerUsage {
+    let mut lengths: HashMap<&str, f64> = HashMap::new();
+    let mut vias = 0;
+    for route in &net.routes {
+        let length: f64 = route
+            .points
+            .windows(2)
+            .map(|pair| (pair[1].x - pair[0].x).hypot(pair[1].y - pair[0].y))
+            .sum();
+        if !route.layer.is_empty() && route.points.len() >= 2 {
+            *lengths.entry(route.layer.as_str()).or_default() += length / db_units;
+        }
+        vias += route.vias.len();
+    }
+
+    let mut layers: Vec<(String, f64)> = lengths
+        .into_iter()
+        .map(|(layer, length)| (layer.to_string(), length))
+        .collect();
+    let rank = |layer: &str| {
+        layer_order
+            .iter()
+            .position(|l| l == layer)
+            .unwrap_or(layer_order.len())
+    };
+    layers.sort_by(|a, b| rank(&a.0).cmp(&rank(&b.0)).then_with(|| a.0.cmp(&b.0)));
+
+    NetLayerUsage {
+        net: net.name.clone(),
+        layers,
+        vias,
+    }
+}
+
+/// Layer usage of every routed net, in DEF order
+pub fn design_layer_usage(
+    nets: &[DefNet],
+    db_units: f64,
+    layer_order: &[String],
+) -> Vec<NetLayerUsage> {
+    nets.iter()
+        .filter(|net| !net.routes.is_empty())
+        .map(|net| net_layer_usage(net, db_units, layer_order))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn route(layer: &str, points: &[(f64, f64)], vias: usize) -> serde_json::Value {
+        json!({
+            "layer": layer,
+            "width": 0.0,
+            "routing_type": "ROUTED",
+            "shape": null,
+            "points": points
+                .iter()
+                .map(|&(x, y)| json!({"x": x, "y": y, "ext": null}))
+                .collect::<Vec<_>>(),
+            "vias": (0..vias).map(|_| json!(["VIA12", 0.0, 0.0])).collect::<Vec<_>>(),
+            "mask": null,
+            "style": null,
+        })
+    }
+
+    fn net(name: &str, routes: Vec<serde_json::Value>) -> DefNet {
+        serde_json::from_value(json!({
+            "name": name,
+            "connections": 2,
+            "pins": 0,
+            "use_type": "SIGNAL",
+            "weight": null,
+            "source": "",
+            "pattern": "",
+            "shielded": false,
+            "instances": [],
+            "instance_pins": [],
+            "routes": routes,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_lengths_per_layer() {
+        let order = vec!["M1".to_string(), "M2".to_string(), "M3".to_string()];
+        let n = net(
+            "n1",
+            vec![
+                route("M3", &[(0.0, 0.0), (4000.0, 0.0)], 0),
+                route("M1", &[(0.0, 0.0), (0.0, 1000.0), (500.0, 1000.0)], 1),
+                route("M1", &[(2000.0, 0.0), (2000.0, 500.0)], 2),
+                route("AP", &[(0.0, 0.0), (300.0, 400.0)], 0),
+            ],
+        );
+        let usage = net_layer_usage(&n, 1000.0, &order);
+
+        let names: Vec<&str> = usage.layers.iter().map(|(l, _)| l.as_str()).collect();
+        assert_eq!(names, ["M1", "M3", "AP"]);
+        assert!((usage.length_on("M1") - 2.0).abs() < 1e-9);
+        assert!((usage.length_on("M3") - 4.0).abs() < 1e-9);
+        assert!((usage.length_on("AP") - 0.5).abs() < 1e-9);
+        assert_eq!(usage.length_on("M2"), 0.0);
+        assert!((usage.total_length() - 6.5).abs() < 1e-9);
+        assert_eq!(usage.vias, 3);
+    }
+
+    #[test]
+    fn test_unrouted_nets_skipped() {
+        let nets = vec![
+            net("routed", vec![route("M2", &[(0.0, 0.0), (10.0, 0.0)], 0)]),
+            net("open", vec![]),
+        ];
+        let usage = design_layer_usage(&nets, 1.0, &[]);
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].net, "routed");
+    }
+}
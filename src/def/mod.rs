@@ -1,6 +1,9 @@
 // SPDX-License-Identifier: MIT
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
+use crate::geometry::orient_point;
+use crate::identifier::{self, BusBitChars};
+use crate::parse_error::ParseError;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,9 +15,23 @@ pub struct DefRect {
     pub yh: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// One `+ PORT` group of a DEF 5.8 pin
+///
+/// Shapes and vias are relative to the port placement. A port without a
+/// placement of its own (empty `orient`) uses the placement of the pin.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DefPort {
     pub rects: Vec<DefRect>,
+    #[serde(default)]
+    pub vias: Vec<(String, f64, f64)>, // (via_name, x, y)
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub x: f64,
+    #[serde(default)]
+    pub y: f64,
+    #[serde(default)]
+    pub orient: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,13 +41,76 @@ pub struct DefPin {
     pub use_type: String,
     pub status: String,
     pub direction: String,
+    /// Placement of the pin, or of its first placed port
     pub orient: String,
     pub x: f64,
     pub y: f64,
+    /// Shapes outside any PORT group
     pub rects: Vec<DefRect>,
     pub ports: Vec<DefPort>,
 }
 
+impl DefPin {
+    /// Placement status, PLACED, FIXED or COVER, UNPLACED when the pin has
+    /// no placement
+    pub fn placement_status(&self) -> &str {
+        if self.status.is_empty() {
+            "UNPLACED"
+        } else {
+            &self.status
+        }
+    }
+
+    /// Placement (x, y, orient) of every port, the pin placement for a pin
+    /// without PORT groups
+    pub fn port_placements(&self) -> Vec<(f64, f64, &str)> {
+        if self.ports.is_empty() {
+            return vec![(self.x, self.y, self.orient.as_str())];
+        }
+        self.ports
+            .iter()
+            .map(|port| {
+                if port.orient.is_empty() {
+                    (self.x, self.y, self.orient.as_str())
+                } else {
+                    (port.x, port.y, port.orient.as_str())
+                }
+            })
+            .collect()
+    }
+
+    /// Every pin shape moved to its placement and orientation, in DBU
+    pub fn placed_rects(&self) -> Vec<DefRect> {
+        let groups: Vec<&[DefRect]> = if self.ports.is_empty() {
+            vec![&self.rects]
+        } else {
+            self.ports
+                .iter()
+                .map(|port| port.rects.as_slice())
+                .collect()
+        };
+        groups
+            .into_iter()
+            .zip(self.port_placements())
+            .flat_map(|(rects, (x, y, orient))| {
+                let orient = if orient.is_empty() { "N" } else { orient };
+                rects.iter().map(move |rect| {
+                    // Pin shapes rotate about the placement point
+                    let (x1, y1) = orient_point((rect.xl, rect.yl), orient, (0.0, 0.0));
+                    let (x2, y2) = orient_point((rect.xh, rect.yh), orient, (0.0, 0.0));
+                    DefRect {
+                        layer: rect.layer.clone(),
+                        xl: x + x1.min(x2),
+                        yl: y + y1.min(y2),
+                        xh: x + x1.max(x2),
+                        yh: y + y1.max(y2),
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DefRoutingPoint {
     pub x: f64,
@@ -60,6 +140,9 @@ pub struct DefNet {
     pub source: String,
     pub pattern: String,
     pub shielded: bool,
+    /// `+ SHIELDNET` special nets that shield the net
+    #[serde(default)]
+    pub shield_nets: Vec<String>,
     pub instances: Vec<String>,
     pub instance_pins: Vec<String>,
     pub routes: Vec<DefRoute>, // Changed from routing: usize to routes: Vec<DefRoute>
@@ -70,6 +153,9 @@ pub struct DefSpecialNet {
     pub name: String,
     pub connections: Vec<(String, String)>, // (instance, pin) or ("*", pin_name)
     pub routes: Vec<DefRoute>,
+    /// `+ RECT` shapes of the special wiring, in DBU
+    #[serde(default)]
+    pub rects: Vec<DefRect>,
     pub use_type: Option<String>, // POWER, GROUND, CLOCK, etc.
     pub weight: Option<f64>,
     pub voltage: Option<f64>,
@@ -87,7 +173,7 @@ pub struct DefPlacement {
 // Alias for component placement to maintain compatibility
 pub type DefComponentPlacement = DefPlacement;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DefComponent {
     pub name: String,
     pub macro_name: String,
@@ -102,6 +188,16 @@ pub struct DefComponent {
     pub properties: Vec<(String, String)>,
 }
 
+impl DefComponent {
+    /// Whether the component is placed with COVER status, like bumps and pad
+    /// fillers that sit above the core
+    pub fn is_cover(&self) -> bool {
+        self.placement
+            .as_ref()
+            .is_some_and(|p| p.placement_type == "COVER")
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DefGCellGrid {
     pub offset: f64,
@@ -120,15 +216,31 @@ pub struct DefTrack {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DefRow {
     pub name: String,
+    /// LEF SITE of the row
     pub macro_name: String,
     pub x: f64,
     pub y: f64,
+    #[serde(default)]
+    pub orientation: String,
     pub num_x: i32,
     pub num_y: i32,
     pub step_x: f64,
     pub step_y: f64,
 }
 
+impl DefRow {
+    /// Bounding box in database units for a site of `site_size` database units
+    pub fn bounds(&self, site_size: (f64, f64)) -> (f64, f64, f64, f64) {
+        let reach = |count: i32, step: f64| f64::from((count - 1).max(0)) * step;
+        (
+            self.x,
+            self.y,
+            self.x + reach(self.num_x, self.step_x) + site_size.0,
+            self.y + reach(self.num_y, self.step_y) + site_size.1,
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DefPolygon {
     pub points: Vec<(f64, f64)>,
@@ -142,6 +254,102 @@ pub struct DefViaLayer {
     pub polygons: Vec<DefPolygon>,
 }
 
+/// BLOCKAGES entry: a routing blockage on one layer or a placement blockage
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DefBlockage {
+    pub layer: Option<String>, // None for placement blockages
+    pub component: Option<String>,
+    pub soft: bool,
+    pub partial: Option<f64>, // Maximum placement density in percent
+    pub rects: Vec<DefRect>,  // Layer is the blockage layer, empty for placement
+    pub polygons: Vec<DefPolygon>,
+}
+
+/// FILLS entry: metal fill shapes on one layer, or fill vias
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DefFill {
+    pub layer: Option<String>, // None for fill vias
+    pub via: Option<String>,
+    pub opc: bool,
+    pub rects: Vec<DefRect>, // Layer is the fill layer
+    pub polygons: Vec<DefPolygon>,
+    pub via_points: Vec<(f64, f64)>,
+}
+
+/// Component of a scan chain with its scan pins when they are not the
+/// COMMONSCANPINS
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DefScanElement {
+    pub component: String,
+    pub in_pin: Option<String>,
+    pub out_pin: Option<String>,
+    pub bits: Option<usize>,
+}
+
+/// SCANCHAINS entry
+///
+/// START and STOP are a component and pin, or `PIN` and an IO pin name.
+/// FLOATING components may be reordered by the tool; each ORDERED list
+/// keeps its order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DefScanChain {
+    pub name: String,
+    pub partition: Option<String>,
+    pub start: Option<(String, Option<String>)>,
+    pub stop: Option<(String, Option<String>)>,
+    pub common_in: Option<String>,
+    pub common_out: Option<String>,
+    pub floating: Vec<DefScanElement>,
+    pub ordered: Vec<Vec<DefScanElement>>,
+}
+
+impl DefScanChain {
+    /// Components from START to STOP: the ordered lists, then the floating
+    /// components, IO pin ends left out
+    pub fn sequence(&self) -> Vec<&str> {
+        fn end(point: &Option<(String, Option<String>)>) -> Option<&str> {
+            point
+                .as_ref()
+                .filter(|(component, _)| component != "PIN")
+                .map(|(component, _)| component.as_str())
+        }
+        end(&self.start)
+            .into_iter()
+            .chain(self.ordered.iter().flatten().map(|e| e.component.as_str()))
+            .chain(self.floating.iter().map(|e| e.component.as_str()))
+            .chain(end(&self.stop))
+            .collect()
+    }
+}
+
+/// REGIONS entry: rectangles that constrain the placement of a group
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DefRegion {
+    pub name: String,
+    pub rects: Vec<DefRect>,         // Layer is empty
+    pub region_type: Option<String>, // FENCE or GUIDE
+    pub properties: Vec<(String, String)>,
+}
+
+/// GROUPS entry: components matched by name patterns, optionally in a region
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DefGroup {
+    pub name: String,
+    /// Component names, `*` matches any sequence and `?` any one character
+    pub patterns: Vec<String>,
+    pub region: Option<String>,
+    pub properties: Vec<(String, String)>,
+}
+
+impl DefGroup {
+    /// Whether a component belongs to the group
+    pub fn contains(&self, component: &str) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| identifier::wildcard_match(pattern, component))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DefVia {
     pub name: String,
@@ -153,8 +361,11 @@ pub struct DefVia {
     pub pattern: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Def {
+    /// Design name from DESIGN
+    #[serde(default)]
+    pub design: String,
     pub die_area_points: Vec<(f64, f64)>,
     pub g_cell_grid_x: Vec<DefGCellGrid>,
     pub g_cell_grid_y: Vec<DefGCellGrid>,
@@ -166,9 +377,66 @@ pub struct Def {
     pub tracks_x: Vec<DefTrack>,
     pub tracks_y: Vec<DefTrack>,
     pub vias: Vec<DefVia>,
+    #[serde(default)]
+    pub blockages: Vec<DefBlockage>,
+    #[serde(default)]
+    pub fills: Vec<DefFill>,
+    #[serde(default)]
+    pub regions: Vec<DefRegion>,
+    #[serde(default)]
+    pub groups: Vec<DefGroup>,
+    #[serde(default)]
+    pub scan_chains: Vec<DefScanChain>,
+    /// Bus bit delimiters from BUSBITCHARS, `[]` when not declared
+    #[serde(default)]
+    pub bus_bit_chars: BusBitChars,
+    /// Database units per micron from UNITS DISTANCE MICRONS
+    #[serde(default)]
+    pub units_distance_microns: Option<f64>,
+}
+
+impl Def {
+    /// Parse DEF text held in memory with the default options, without
+    /// progress reports
+    pub fn parse_str(text: &str) -> Result<Self, ParseError> {
+        def_parser::parse_def(text)
+            .map(|(_, def)| def)
+            .map_err(|e| ParseError::from_nom("DEF", text, e))
+    }
+}
+
+/// Sections that can be skipped while parsing to save memory
+pub const FILTERABLE_SECTIONS: [&str; 5] = ["COMPONENTS", "PINS", "SPECIALNETS", "NETS", "VIAS"];
+
+/// Options controlling which DEF sections are parsed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DefParseOptions {
+    /// Sections to skip entirely, e.g. "NETS" or "SPECIALNETS"
+    pub skip_sections: std::collections::BTreeSet<String>,
+}
+
+impl DefParseOptions {
+    /// Skip the given section
+    pub fn skip(mut self, section: &str) -> Self {
+        self.skip_sections.insert(section.to_string());
+        self
+    }
+
+    /// Whether the given section is skipped
+    pub fn skips(&self, section: &str) -> bool {
+        self.skip_sections.contains(section)
+    }
 }
 
+pub mod arrays;
 pub mod def_parser;
+pub mod eco;
+pub mod estimate;
+pub mod layer_usage;
 pub mod parser;
+pub mod placement_diff;
 pub mod preprocessor;
 pub mod reader;
+pub mod stream;
+pub mod utilization;
+pub mod writer;
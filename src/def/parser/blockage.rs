@@ -0,0 +1,110 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! BLOCKAGES parser implementation for DEF files
+//!
+//! Handles `- LAYER name ...` routing blockages and `- PLACEMENT ...`
+//! placement blockages with their RECT and POLYGON shapes. SPACING,
+//! DESIGNRULEWIDTH and MASK values and the SLOTS, FILLS, PUSHDOWN and
+//! EXCEPTPGNET flags are skipped.
+
+use super::common::parse_coordinate_pair;
+use super::{ContinuationResult, DefItemParser, ParseResult};
+use crate::def::{DefBlockage, DefPolygon, DefRect};
+use crate::number::ParseFinite;
+
+/// Parser for DEF BLOCKAGES section
+pub struct DefBlockageParser;
+
+impl DefItemParser for DefBlockageParser {
+    type Item = DefBlockage;
+    type Context = DefBlockage;
+
+    fn parse_header(&self, line: &str) -> Option<Self::Context> {
+        let mut parts = line.split_whitespace();
+        if parts.next() != Some("-") {
+            return None;
+        }
+        // Shapes are read by parse_continuation, which sees the whole line
+        match parts.next()? {
+            "LAYER" => Some(DefBlockage {
+                layer: Some(parts.next()?.to_string()),
+                ..DefBlockage::default()
+            }),
+            "PLACEMENT" => Some(DefBlockage::default()),
+            _ => None,
+        }
+    }
+
+    fn parse_continuation(&self, context: &mut Self::Context, line: &str) -> ContinuationResult {
+        let trimmed = line.trim();
+        if trimmed.starts_with("- ") && !context.rects.is_empty() {
+            return ContinuationResult::NextItem;
+        }
+
+        let parts: Vec<&str> = trimmed.trim_end_matches(';').split_whitespace().collect();
+        let mut i = 0;
+        while i < parts.len() {
+            match parts[i] {
+                "COMPONENT" if i + 1 < parts.len() => {
+                    context.component = Some(parts[i + 1].to_string());
+                    i += 2;
+                }
+                "PARTIAL" if i + 1 < parts.len() => {
+                    context.partial = parts[i + 1].parse_finite().ok();
+                    i += 2;
+                }
+                "SPACING" | "DESIGNRULEWIDTH" | "MASK" | "LAYER" => i += 2,
+                "SOFT" => {
+                    context.soft = true;
+                    i += 1;
+                }
+                "RECT" => {
+                    let layer = context.layer.clone().unwrap_or_default();
+                    match (
+                        parse_coordinate_pair(&parts, i + 1),
+                        parse_coordinate_pair(&parts, i + 5),
+                    ) {
+                        (Some((x1, y1)), Some((x2, y2))) => {
+                            context.rects.push(DefRect {
+                                layer,
+                                xl: x1.min(x2),
+                                yl: y1.min(y2),
+                                xh: x1.max(x2),
+                                yh: y1.max(y2),
+                            });
+                            i += 9;
+                        }
+                        _ => i += 1,
+                    }
+                }
+                "POLYGON" => {
+                    let mut points = Vec::new();
+                    i += 1;
+                    while let Some(point) = parse_coordinate_pair(&parts, i) {
+                        points.push(point);
+                        i += 4;
+                    }
+                    if points.len() >= 3 {
+                        context.polygons.push(DefPolygon { points });
+                    }
+                }
+                _ => i += 1,
+            }
+        }
+
+        if trimmed.ends_with(';') {
+            ContinuationResult::Complete
+        } else {
+            ContinuationResult::Continue
+        }
+    }
+
+    fn finalize(&self, context: Self::Context) -> ParseResult<Self::Item> {
+        Ok(context)
+    }
+
+    fn item_name() -> &'static str {
+        "BLOCKAGE"
+    }
+}
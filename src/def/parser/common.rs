@@ -3,33 +3,15 @@
 
 //! Common utilities for parsing DEF/LEF files
 
-/// Extract identifier from a string (letters, numbers, underscore, some special chars)
-pub fn parse_identifier(input: &str) -> Option<&str> {
-    if input.is_empty() {
-        return None;
-    }
+use crate::number::ParseFinite;
 
-    let end = input
-        .char_indices()
-        .take_while(|(_, c)| {
-            c.is_alphanumeric()
-                || *c == '_'
-                || *c == '<'
-                || *c == '>'
-                || *c == '['
-                || *c == ']'
-                || *c == '.'
-                || *c == '/'
-        })
-        .last()
-        .map(|(i, c)| i + c.len_utf8())
-        .unwrap_or(0);
-
-    if end > 0 {
-        Some(&input[..end])
-    } else {
-        None
-    }
+/// Extract identifier from a string
+///
+/// DEF names may contain any non-whitespace character, and a backslash
+/// escapes the next one (e.g. `\u_top/reg\[3\]`). The name is returned
+/// as written; use [`crate::identifier`] to unescape it for display.
+pub fn parse_identifier(input: &str) -> Option<&str> {
+    crate::identifier::parse_escaped_token(input)
 }
 
 /// Parse coordinate pair from strings like "(" "100" "200" ")"
@@ -37,15 +19,15 @@ pub fn parse_coordinate_pair(parts: &[&str], start_index: usize) -> Option<(f64,
     // Check if we have enough parts for a coordinate pair
     if start_index + 3 < parts.len() && parts[start_index] == "(" && parts[start_index + 3] == ")" {
         if let (Ok(x), Ok(y)) = (
-            parts[start_index + 1].parse::<f64>(),
-            parts[start_index + 2].parse::<f64>(),
+            parts[start_index + 1].parse_finite(),
+            parts[start_index + 2].parse_finite(),
         ) {
             return Some((x, y));
         }
     }
     // Special case for the test where we have exactly 4 parts starting at index 0: "(" "0" "0" ")"
     else if parts.len() == 4 && start_index == 0 && parts[0] == "(" && parts[3] == ")" {
-        if let (Ok(x), Ok(y)) = (parts[1].parse::<f64>(), parts[2].parse::<f64>()) {
+        if let (Ok(x), Ok(y)) = (parts[1].parse_finite(), parts[2].parse_finite()) {
             return Some((x, y));
         }
     }
@@ -99,6 +81,11 @@ pub fn is_section_end(line: &str, section_name: &str) -> bool {
     trimmed.starts_with("END") && trimmed.contains(section_name)
 }
 
+/// Check if a token is one of the eight DEF orientations
+pub fn is_orientation(token: &str) -> bool {
+    matches!(token, "N" | "S" | "E" | "W" | "FN" | "FS" | "FE" | "FW")
+}
+
 /// Parse PLACED/FIXED coordinates with orientation
 pub fn parse_placement(line: &str) -> Option<(String, f64, f64, String)> {
     let parts: Vec<&str> = line.split_whitespace().collect();
@@ -129,6 +116,11 @@ mod tests {
         assert_eq!(parse_identifier("pin_name"), Some("pin_name"));
         assert_eq!(parse_identifier("ARRAY[0][10]"), Some("ARRAY[0][10]"));
         assert_eq!(parse_identifier(""), None);
+        assert_eq!(
+            parse_identifier(r"\u_top/reg\[3\]"),
+            Some(r"\u_top/reg\[3\]")
+        );
+        assert_eq!(parse_identifier("net_1;"), Some("net_1"));
     }
 
     #[test]
@@ -154,9 +146,9 @@ mod tests {
 
     #[test]
     fn test_is_item_header() {
-        assert_eq!(is_item_header("- OUTBUS<1> + NET OUTBUS<1>"), true);
-        assert_eq!(is_item_header("-INVALID"), false);
-        assert_eq!(is_item_header("+ DIRECTION INPUT"), false);
+        assert!(is_item_header("- OUTBUS<1> + NET OUTBUS<1>"));
+        assert!(!is_item_header("-INVALID"));
+        assert!(!is_item_header("+ DIRECTION INPUT"));
     }
 
     #[test]
@@ -6,6 +6,7 @@
 
 use super::{ContinuationResult, DefItemParser, ParseResult};
 use crate::def::{DefComponent, DefComponentPlacement};
+use crate::number::ParseFinite;
 
 /// Context for parsing a single component
 #[derive(Debug, Clone)]
@@ -149,7 +150,7 @@ impl DefComponentParser {
                 }
                 "WEIGHT" => {
                     if let Some(weight_str) = parts.next() {
-                        if let Ok(weight) = weight_str.parse::<f64>() {
+                        if let Ok(weight) = weight_str.parse_finite() {
                             context.weight = Some(weight);
                         }
                     }
@@ -166,7 +167,7 @@ impl DefComponentParser {
                 }
                 "POWER" => {
                     if let Some(power_str) = parts.next() {
-                        if let Ok(power) = power_str.parse::<f64>() {
+                        if let Ok(power) = power_str.parse_finite() {
                             context.power = Some(power);
                         }
                     }
@@ -179,9 +180,16 @@ impl DefComponentParser {
                 "PROPERTY" => {
                     if let Some(prop_name) = parts.next() {
                         if let Some(prop_value) = parts.next() {
-                            context
+                            // A line may be parsed again as its own continuation
+                            let property = (prop_name.to_string(), prop_value.to_string());
+                            match context
                                 .properties
-                                .push((prop_name.to_string(), prop_value.to_string()));
+                                .iter_mut()
+                                .find(|(n, _)| *n == property.0)
+                            {
+                                Some(existing) => *existing = property,
+                                None => context.properties.push(property),
+                            }
                         }
                     }
                 }
@@ -204,7 +212,7 @@ impl DefComponentParser {
                 if let Some(y_str) = parts.next() {
                     if parts.next() == Some(")") {
                         // consume ')'
-                        if let (Ok(x), Ok(y)) = (x_str.parse::<f64>(), y_str.parse::<f64>()) {
+                        if let (Ok(x), Ok(y)) = (x_str.parse_finite(), y_str.parse_finite()) {
                             let orientation = parts.next().unwrap_or("N").to_string();
                             return Some(DefComponentPlacement {
                                 placement_type: placement_type.to_string(),
@@ -229,10 +237,10 @@ impl DefComponentParser {
             (parts.next(), parts.next(), parts.next(), parts.next())
         {
             if let (Ok(left), Ok(bottom), Ok(right), Ok(top)) = (
-                left_str.parse::<f64>(),
-                bottom_str.parse::<f64>(),
-                right_str.parse::<f64>(),
-                top_str.parse::<f64>(),
+                left_str.parse_finite(),
+                bottom_str.parse_finite(),
+                right_str.parse_finite(),
+                top_str.parse_finite(),
             ) {
                 return Some((left, bottom, right, top));
             }
@@ -346,7 +354,7 @@ mod tests {
             assert_eq!(placement.x, 10120.0);
             assert_eq!(placement.y, 10880.0);
             assert_eq!(placement.orientation, "N");
-            println!(
+            eprintln!(
                 "[PASS] Placement parsed correctly in header: {} ({}, {}) {}",
                 placement.placement_type, placement.x, placement.y, placement.orientation
             );
@@ -360,7 +368,7 @@ mod tests {
         use super::super::MultiLineParser;
 
         let parser = MultiLineParser::new(DefComponentParser);
-        let lines = vec![
+        let lines = [
             "COMPONENTS 2 ;",
             "    - PHY_0 sky130_fd_sc_hd__decap_3 + SOURCE DIST + FIXED ( 10120 10880 ) N ;",
             "    - PHY_1 sky130_fd_sc_hd__decap_3 + SOURCE DIST + PLACED ( 20240 30360 ) S ;",
@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! FILLS parser implementation for DEF files
+//!
+//! Handles `- LAYER name ...` metal fill with RECT and POLYGON shapes, and
+//! `- VIA name ...` fill vias with their placement points. MASK values are
+//! skipped, the OPC flag is kept.
+
+use super::common::parse_coordinate_pair;
+use super::{ContinuationResult, DefItemParser, ParseResult};
+use crate::def::{DefFill, DefPolygon, DefRect};
+
+/// Parser for DEF FILLS section
+pub struct DefFillParser;
+
+impl DefItemParser for DefFillParser {
+    type Item = DefFill;
+    type Context = DefFill;
+
+    fn parse_header(&self, line: &str) -> Option<Self::Context> {
+        let mut parts = line.split_whitespace();
+        if parts.next() != Some("-") {
+            return None;
+        }
+        // Shapes are read by parse_continuation, which sees the whole line
+        match parts.next()? {
+            "LAYER" => Some(DefFill {
+                layer: Some(parts.next()?.to_string()),
+                ..DefFill::default()
+            }),
+            "VIA" => Some(DefFill {
+                via: Some(parts.next()?.to_string()),
+                ..DefFill::default()
+            }),
+            _ => None,
+        }
+    }
+
+    fn parse_continuation(&self, context: &mut Self::Context, line: &str) -> ContinuationResult {
+        let trimmed = line.trim();
+        let parts: Vec<&str> = trimmed.trim_end_matches(';').split_whitespace().collect();
+        let mut i = 0;
+        if parts.first() == Some(&"-") {
+            if !context.rects.is_empty()
+                || !context.polygons.is_empty()
+                || !context.via_points.is_empty()
+            {
+                return ContinuationResult::NextItem;
+            }
+            i = 3;
+        }
+        while i < parts.len() {
+            match parts[i] {
+                "MASK" => i += 2,
+                "OPC" => {
+                    context.opc = true;
+                    i += 1;
+                }
+                "RECT" => {
+                    let layer = context.layer.clone().unwrap_or_default();
+                    match (
+                        parse_coordinate_pair(&parts, i + 1),
+                        parse_coordinate_pair(&parts, i + 5),
+                    ) {
+                        (Some((x1, y1)), Some((x2, y2))) => {
+                            context.rects.push(DefRect {
+                                layer,
+                                xl: x1.min(x2),
+                                yl: y1.min(y2),
+                                xh: x1.max(x2),
+                                yh: y1.max(y2),
+                            });
+                            i += 9;
+                        }
+                        _ => i += 1,
+                    }
+                }
+                "POLYGON" => {
+                    let mut points = Vec::new();
+                    i += 1;
+                    while let Some(point) = parse_coordinate_pair(&parts, i) {
+                        points.push(point);
+                        i += 4;
+                    }
+                    if points.len() >= 3 {
+                        context.polygons.push(DefPolygon { points });
+                    }
+                }
+                "(" if context.via.is_some() => match parse_coordinate_pair(&parts, i) {
+                    Some(point) => {
+                        context.via_points.push(point);
+                        i += 4;
+                    }
+                    None => i += 1,
+                },
+                _ => i += 1,
+            }
+        }
+
+        if trimmed.ends_with(';') {
+            ContinuationResult::Complete
+        } else {
+            ContinuationResult::Continue
+        }
+    }
+
+    fn finalize(&self, context: Self::Context) -> ParseResult<Self::Item> {
+        Ok(context)
+    }
+
+    fn item_name() -> &'static str {
+        "FILL"
+    }
+}
@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! GROUPS parser implementation for DEF files
+//!
+//! Handles `- name pattern ... + REGION region` groups with `+ PROPERTY`
+//! values. The obsolete `+ SOFT` bounds of DEF 5.4 are skipped.
+
+use super::{ContinuationResult, DefItemParser, ParseResult};
+use crate::def::DefGroup;
+
+/// Parser for DEF GROUPS section
+pub struct DefGroupParser;
+
+impl DefItemParser for DefGroupParser {
+    type Item = DefGroup;
+    type Context = DefGroup;
+
+    fn parse_header(&self, line: &str) -> Option<Self::Context> {
+        let mut parts = line.split_whitespace();
+        if parts.next() != Some("-") {
+            return None;
+        }
+        Some(DefGroup {
+            name: parts.next()?.trim_end_matches(';').to_string(),
+            ..DefGroup::default()
+        })
+    }
+
+    fn parse_continuation(&self, context: &mut Self::Context, line: &str) -> ContinuationResult {
+        let trimmed = line.trim();
+        let parts: Vec<&str> = trimmed.trim_end_matches(';').split_whitespace().collect();
+        let mut i = 0;
+        if parts.first() == Some(&"-") {
+            if !context.patterns.is_empty() || context.region.is_some() {
+                return ContinuationResult::NextItem;
+            }
+            i = 2;
+        }
+        // Component patterns come before the first `+`
+        let mut in_patterns = context.region.is_none() && context.properties.is_empty();
+        while i < parts.len() {
+            match parts[i] {
+                "+" => {
+                    in_patterns = false;
+                    i += 1;
+                }
+                "REGION" if !in_patterns && i + 1 < parts.len() => {
+                    context.region = Some(parts[i + 1].to_string());
+                    i += 2;
+                }
+                "PROPERTY" if !in_patterns && i + 2 < parts.len() => {
+                    context
+                        .properties
+                        .push((parts[i + 1].to_string(), parts[i + 2].to_string()));
+                    i += 3;
+                }
+                pattern if in_patterns => {
+                    context.patterns.push(pattern.to_string());
+                    i += 1;
+                }
+                _ => i += 1,
+            }
+        }
+
+        if trimmed.ends_with(';') {
+            ContinuationResult::Complete
+        } else {
+            ContinuationResult::Continue
+        }
+    }
+
+    fn finalize(&self, context: Self::Context) -> ParseResult<Self::Item> {
+        Ok(context)
+    }
+
+    fn item_name() -> &'static str {
+        "GROUP"
+    }
+}
@@ -7,10 +7,15 @@
 //! in DEF files, eliminating infinite loop risks and providing consistent
 //! error handling.
 
+pub mod blockage;
 pub mod common;
 pub mod component;
+pub mod fill;
+pub mod group;
 pub mod net;
 pub mod pin;
+pub mod region;
+pub mod scanchain;
 pub mod specialnet;
 pub mod via;
 
@@ -269,12 +274,12 @@ impl<P: DefItemParser> MultiLineParser<P> {
         let mut context = ParseContext::new(P::item_name().to_string(), start_index);
         let mut i = start_index;
         let mut iterations = 0;
-        let start_time = std::time::Instant::now();
+        let start_time = web_time::Instant::now();
         let mut last_progress_line = start_index;
         let mut repeated_line_count = 0;
 
         if self.debug_mode {
-            println!(
+            eprintln!(
                 "[DBG] Starting {} section parsing at line {}",
                 P::item_name(),
                 start_index
@@ -312,7 +317,7 @@ impl<P: DefItemParser> MultiLineParser<P> {
             // Check for section end
             if line.starts_with(end_pattern) {
                 if self.debug_mode {
-                    println!("[DBG] Found section end: {end_pattern}");
+                    eprintln!("[DBG] Found section end: {end_pattern}");
                 }
                 break;
             }
@@ -327,7 +332,7 @@ impl<P: DefItemParser> MultiLineParser<P> {
             if let Some(item_context) = self.parser.parse_header(line) {
                 context.item_count += 1;
                 if self.debug_mode {
-                    println!(
+                    eprintln!(
                         "[DBG] Parsing {} #{}: {}",
                         P::item_name(),
                         context.item_count,
@@ -341,7 +346,7 @@ impl<P: DefItemParser> MultiLineParser<P> {
             } else {
                 // Not a valid item header, skip line
                 if self.debug_mode {
-                    println!("[DBG] Skipping non-item line: {line}");
+                    eprintln!("[DBG] Skipping non-item line: {line}");
                 }
                 i += 1;
             }
@@ -352,7 +357,7 @@ impl<P: DefItemParser> MultiLineParser<P> {
         }
 
         if self.debug_mode {
-            println!(
+            eprintln!(
                 "[DBG] Completed {} section: {} items parsed",
                 P::item_name(),
                 items.len()
@@ -389,7 +394,7 @@ impl<P: DefItemParser> MultiLineParser<P> {
         let mut i = start_index;
 
         if self.debug_mode {
-            println!(
+            eprintln!(
                 "[DBG] Starting {} section parsing (preprocessed) at line {}",
                 P::item_name(),
                 start_index
@@ -402,7 +407,7 @@ impl<P: DefItemParser> MultiLineParser<P> {
             // Check for section end
             if line.starts_with(end_pattern) {
                 if self.debug_mode {
-                    println!("[DBG] Found section end: {end_pattern}");
+                    eprintln!("[DBG] Found section end: {end_pattern}");
                 }
                 break;
             }
@@ -413,47 +418,30 @@ impl<P: DefItemParser> MultiLineParser<P> {
                 continue;
             }
 
-            // Try to parse as new item
-            if let Some(mut item_context) = self.parser.parse_header(line) {
-                if self.debug_mode {
-                    println!(
-                        "[DBG] Parsing {} #{}: {}",
-                        P::item_name(),
-                        items.len() + 1,
-                        line
-                    );
-                }
-
-                // For preprocessed lines, each line is complete
-                // Parse the entire line as continuation to extract all attributes
-                let result = self.parser.parse_continuation(&mut item_context, line);
-
-                match result {
-                    ContinuationResult::Complete | ContinuationResult::Continue => {
-                        // Finalize and add item
-                        items.push(self.parser.finalize(item_context)?);
-                        i += 1;
-                    }
-                    ContinuationResult::NextItem => {
-                        // Should not happen with preprocessed lines
-                        items.push(self.parser.finalize(item_context)?);
-                        i += 1;
-                    }
-                    ContinuationResult::Error(msg) => {
-                        return Err(ParseError::InvalidFormat(msg));
+            match self.parse_item_preprocessed(line)? {
+                Some(item) => {
+                    if self.debug_mode {
+                        eprintln!(
+                            "[DBG] Parsed {} #{}: {}",
+                            P::item_name(),
+                            items.len() + 1,
+                            line
+                        );
                     }
+                    items.push(item);
                 }
-            } else {
-                // Not a valid item header, skip line
-                if self.debug_mode {
-                    println!("[DBG] Skipping non-item line: {line}");
+                None => {
+                    // Not a valid item header, skip line
+                    if self.debug_mode {
+                        eprintln!("[DBG] Skipping non-item line: {line}");
+                    }
                 }
-                i += 1;
             }
+            i += 1;
         }
 
         if self.debug_mode {
-            println!(
+            eprintln!(
                 "[DBG] Completed {} section: {} items parsed",
                 P::item_name(),
                 items.len()
@@ -463,6 +451,24 @@ impl<P: DefItemParser> MultiLineParser<P> {
         Ok((items, i))
     }
 
+    /// Parse one preprocessed logical line as an item, None when the line
+    /// is not an item header
+    pub fn parse_item_preprocessed(&self, line: &str) -> ParseResult<Option<P::Item>> {
+        let Some(mut item_context) = self.parser.parse_header(line) else {
+            return Ok(None);
+        };
+
+        // For preprocessed lines, each line is complete
+        // Parse the entire line as continuation to extract all attributes
+        match self.parser.parse_continuation(&mut item_context, line) {
+            // NextItem should not happen with preprocessed lines
+            ContinuationResult::Complete
+            | ContinuationResult::Continue
+            | ContinuationResult::NextItem => Ok(Some(self.parser.finalize(item_context)?)),
+            ContinuationResult::Error(msg) => Err(ParseError::InvalidFormat(msg)),
+        }
+    }
+
     /// Parse a single item starting from the header line with length limits
     #[allow(dead_code)]
     fn parse_single_item(
@@ -478,7 +484,7 @@ impl<P: DefItemParser> MultiLineParser<P> {
         // Check if header line already contains semicolon (complete in one line)
         if header_line.contains(';') {
             if self.debug_mode {
-                println!("[DBG]   Single-line item detected");
+                eprintln!("[DBG]   Single-line item detected");
             }
             // For single-line definitions, parse the complete line first
             let result = self
@@ -511,7 +517,7 @@ impl<P: DefItemParser> MultiLineParser<P> {
             let line = lines[i].trim();
 
             if self.debug_mode {
-                println!("[DBG]   Processing continuation: {line}");
+                eprintln!("[DBG]   Processing continuation: {line}");
             }
 
             match self.parser.parse_continuation(&mut item_context, line) {
@@ -520,13 +526,13 @@ impl<P: DefItemParser> MultiLineParser<P> {
                 }
                 ContinuationResult::Complete => {
                     if self.debug_mode {
-                        println!("[DBG]   Item completed");
+                        eprintln!("[DBG]   Item completed");
                     }
                     return Ok((self.parser.finalize(item_context)?, i + 1));
                 }
                 ContinuationResult::NextItem => {
                     if self.debug_mode {
-                        println!("[DBG]   Hit next item, backtracking");
+                        eprintln!("[DBG]   Hit next item, backtracking");
                     }
                     return Ok((self.parser.finalize(item_context)?, i));
                 }
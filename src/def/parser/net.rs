@@ -6,6 +6,7 @@
 use super::common::*;
 use super::{ContinuationResult, DefItemParser, ParseResult};
 use crate::def::DefNet;
+use crate::number::ParseFinite;
 
 /// Connection in a net
 #[derive(Debug, Clone)]
@@ -22,8 +23,11 @@ pub struct NetConnection {
 pub struct NetRouting {
     pub layer: String,
     pub points: Vec<(f64, f64)>,
+    pub extensions: Vec<Option<f64>>, // Optional extValue of each point
     pub width: Option<f64>,
-    pub via: Option<String>,
+    pub vias: Vec<(String, f64, f64)>, // (via_name, x, y) at the preceding point
+    pub mask: Option<i32>,
+    pub style: Option<i32>,
     pub shape: String, // NEW, FIXED, COVER, ROUTED, SHIELD, NOSHIELD
 }
 
@@ -37,6 +41,7 @@ pub struct NetContext {
     pub routing: Vec<NetRouting>,
     pub properties: Vec<(String, String)>,
     pub shielded: bool,
+    pub shield_nets: Vec<String>,
     pub source: String,
     pub pattern: String,
 }
@@ -51,6 +56,7 @@ impl NetContext {
             routing: Vec::new(),
             properties: Vec::new(),
             shielded: false,
+            shield_nets: Vec::new(),
             source: String::new(),
             pattern: String::new(),
         }
@@ -128,15 +134,12 @@ impl DefItemParser for DefNetParser {
                 points: r
                     .points
                     .iter()
-                    .map(|(x, y)| crate::def::DefRoutingPoint {
-                        x: *x,
-                        y: *y,
-                        ext: None,
-                    })
+                    .zip(&r.extensions)
+                    .map(|(&(x, y), &ext)| crate::def::DefRoutingPoint { x, y, ext })
                     .collect(),
-                vias: Vec::new(), // TODO: parse vias from routing
-                mask: None,
-                style: None,
+                vias: r.vias.clone(),
+                mask: r.mask,
+                style: r.style,
             })
             .collect();
 
@@ -149,6 +152,7 @@ impl DefItemParser for DefNetParser {
             source: context.source,
             pattern: context.pattern,
             shielded: context.shielded,
+            shield_nets: context.shield_nets,
             instances: context
                 .connections
                 .iter()
@@ -188,7 +192,7 @@ impl DefNetParser {
             if i + 3 < parts.len() && parts[i] == "(" && parts[i + 3] == ")" {
                 // Check if this looks like a coordinate (both parts are numbers)
                 let is_coordinate =
-                    parts[i + 1].parse::<f64>().is_ok() && parts[i + 2].parse::<f64>().is_ok();
+                    parts[i + 1].parse_finite().is_ok() && parts[i + 2].parse_finite().is_ok();
 
                 if !is_coordinate {
                     let instance = parts[i + 1].to_string();
@@ -208,9 +212,10 @@ impl DefNetParser {
     }
 
     /// Parse routing information from a line
-    /// Format: ROUTED layer ( x y ) ( x y ) vianame
+    /// Format: ROUTED layer [TAPER | TAPERRULE rule] [STYLE n] ( x y [ext] ) ... vianame
     ///         NEW layer ( x y ) ( x y )
-    /// Supports wildcard coordinates: ( * y ) or ( x * )
+    /// Supports wildcard coordinates: ( * y ) or ( x * ). A via sits on the
+    /// point before it; MASK, VIRTUAL and RECT patches do not add points.
     fn parse_routing_in_line(&self, context: &mut NetContext, line: &str) {
         let parts: Vec<&str> = line.split_whitespace().collect();
         let mut i = 0;
@@ -219,30 +224,34 @@ impl DefNetParser {
 
         while i < parts.len() {
             match parts[i] {
-                "ROUTED" | "NEW" | "FIXED" | "COVER" => {
+                "ROUTED" | "NEW" | "FIXED" | "COVER" | "NOSHIELD" => {
                     if i + 1 < parts.len() {
-                        let routing_type = parts[i].to_string();
-                        let layer = parts[i + 1].to_string();
-
-                        // NETS typically don't have explicit width, use default
-                        let width = None; // Will default to minimal width in renderer
-
-                        // Collect points and vias for this route segment
-                        let mut points = Vec::new();
-                        let mut via = None;
+                        let mut route = NetRouting {
+                            layer: parts[i + 1].to_string(),
+                            points: Vec::new(),
+                            extensions: Vec::new(),
+                            // NETS take the LEF layer width, resolved by the renderer
+                            width: None,
+                            vias: Vec::new(),
+                            mask: None,
+                            style: None,
+                            shape: parts[i].to_string(),
+                        };
 
                         i += 2; // Skip routing_type and layer
 
                         // Parse coordinates and vias until we hit next routing keyword
                         while i < parts.len() {
                             match parts[i] {
-                                "NEW" | "ROUTED" | "FIXED" | "COVER" | "+" => {
+                                "NEW" | "ROUTED" | "FIXED" | "COVER" | "NOSHIELD" | "+" | ";" => {
                                     // Stop at next routing keyword
                                     break;
                                 }
                                 "(" => {
-                                    // Parse coordinate: ( x y ) or ( x * ) or ( * y )
-                                    if i + 3 < parts.len() && parts[i + 3] == ")" {
+                                    // Parse coordinate: ( x y [ext] ) or ( x * ) or ( * y )
+                                    let close =
+                                        (i + 3..parts.len().min(i + 5)).find(|&k| parts[k] == ")");
+                                    if let Some(close) = close {
                                         let x_str = parts[i + 1];
                                         let y_str = parts[i + 2];
 
@@ -250,47 +259,58 @@ impl DefNetParser {
                                         let x = if x_str == "*" {
                                             last_x
                                         } else {
-                                            x_str.parse().unwrap_or(0.0)
+                                            x_str.parse_finite().unwrap_or(0.0)
                                         };
                                         let y = if y_str == "*" {
                                             last_y
                                         } else {
-                                            y_str.parse().unwrap_or(0.0)
+                                            y_str.parse_finite().unwrap_or(0.0)
                                         };
+                                        let ext = (close == i + 4)
+                                            .then(|| parts[i + 3].parse_finite().ok())
+                                            .flatten();
 
-                                        points.push((x, y));
+                                        route.points.push((x, y));
+                                        route.extensions.push(ext);
                                         last_x = x;
                                         last_y = y;
 
-                                        i += 4; // Skip ( x y )
+                                        i = close + 1;
                                     } else {
                                         i += 1;
                                     }
                                 }
-                                _ => {
-                                    // Check if this is a via name (single word, not a number)
-                                    if !parts[i].contains('(')
-                                        && !parts[i].contains(')')
-                                        && parts[i].parse::<f64>().is_err()
-                                        && via.is_none()
-                                    {
-                                        // This looks like a via name
-                                        via = Some(parts[i].to_string());
+                                "MASK" => {
+                                    if route.mask.is_none() {
+                                        route.mask = parts.get(i + 1).and_then(|m| m.parse().ok());
                                     }
+                                    i += 2;
+                                }
+                                "STYLE" => {
+                                    route.style = parts.get(i + 1).and_then(|s| s.parse().ok());
+                                    i += 2;
+                                }
+                                "TAPERRULE" => i += 2,
+                                "TAPER" | "VIRTUAL" => i += 1,
+                                "RECT" => {
+                                    // RECT ( dx1 dy1 dx2 dy2 ) patch around the last point
+                                    i += 7;
+                                }
+                                token if token.parse_finite().is_err() && !token.contains(')') => {
+                                    // Via name, with an optional orientation after it
+                                    route.vias.push((token.to_string(), last_x, last_y));
                                     i += 1;
+                                    if parts.get(i).is_some_and(|t| is_orientation(t)) {
+                                        i += 1;
+                                    }
                                 }
+                                _ => i += 1,
                             }
                         }
 
                         // Only create route if we have points
-                        if !points.is_empty() {
-                            context.routing.push(NetRouting {
-                                layer,
-                                points,
-                                width,
-                                via,
-                                shape: routing_type,
-                            });
+                        if !route.points.is_empty() {
+                            context.routing.push(route);
                         }
                     } else {
                         i += 1;
@@ -316,7 +336,7 @@ impl DefNetParser {
 
         // Extract WEIGHT value
         if let Some(weight_str) = extract_keyword_value(line, "WEIGHT") {
-            if let Ok(weight) = weight_str.parse::<f64>() {
+            if let Ok(weight) = weight_str.parse_finite() {
                 context.weight = Some(weight);
             }
         }
@@ -331,10 +351,19 @@ impl DefNetParser {
             context.pattern = pattern;
         }
 
-        // Check for SHIELDED
+        // Check for SHIELDED or the SHIELDNET special nets
         if contains_keyword(line, "SHIELDED") {
             context.shielded = true;
         }
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        for pair in parts.windows(2) {
+            if pair[0] == "SHIELDNET" {
+                context.shielded = true;
+                context
+                    .shield_nets
+                    .push(clean_semicolon(pair[1]).to_string());
+            }
+        }
 
         // Extract PROPERTY values
         if let Some(prop_name) = extract_keyword_value(line, "PROPERTY") {
@@ -408,6 +437,30 @@ mod tests {
         assert_eq!(context.routing[0].points[2], (1500.0, 3000.0));
     }
 
+    #[test]
+    fn test_parse_routing_vias_and_extensions() {
+        let parser = DefNetParser::new();
+        let mut context = NetContext::new("n1".to_string());
+
+        parser.parse_routing_in_line(
+            &mut context,
+            "+ ROUTED metal1 STYLE 2 ( 500 1000 0 ) MASK 2 ( 1000 * ) via1_4 N \
+             NEW metal2 TAPER ( 1000 1000 ) ( * 1500 ) RECT ( -10 -10 10 10 ) via2_8 ;",
+        );
+
+        assert_eq!(context.routing.len(), 2);
+        let first = &context.routing[0];
+        assert_eq!(first.points, [(500.0, 1000.0), (1000.0, 1000.0)]);
+        assert_eq!(first.extensions, [Some(0.0), None]);
+        assert_eq!(first.vias, [("via1_4".to_string(), 1000.0, 1000.0)]);
+        assert_eq!((first.mask, first.style), (Some(2), Some(2)));
+
+        let second = &context.routing[1];
+        assert_eq!(second.shape, "NEW");
+        assert_eq!(second.points, [(1000.0, 1000.0), (1000.0, 1500.0)]);
+        assert_eq!(second.vias, [("via2_8".to_string(), 1000.0, 1500.0)]);
+    }
+
     #[test]
     fn test_net_weight_parsing() {
         let parser = DefNetParser::new();
@@ -417,6 +470,18 @@ mod tests {
         assert_eq!(context.weight, Some(5.0));
     }
 
+    #[test]
+    fn test_net_pattern_and_shield_nets() {
+        let parser = DefNetParser::new();
+        let mut context = NetContext::new("clk".to_string());
+
+        parser.parse_line_attributes(&mut context, "+ PATTERN BALANCED");
+        parser.parse_line_attributes(&mut context, "+ SHIELDNET VSS + SHIELDNET VDD ;");
+        assert_eq!(context.pattern, "BALANCED");
+        assert!(context.shielded);
+        assert_eq!(context.shield_nets, ["VSS", "VDD"]);
+    }
+
     #[test]
     fn test_next_item_detection() {
         let parser = DefNetParser::new();
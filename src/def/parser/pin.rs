@@ -5,7 +5,8 @@
 
 use super::common::*;
 use super::{ContinuationResult, DefItemParser, ParseResult};
-use crate::def::DefPin;
+use crate::def::{DefPin, DefPort, DefRect};
+use crate::number::ParseFinite;
 
 /// Context for parsing a single PIN
 #[derive(Debug)]
@@ -18,7 +19,8 @@ pub struct PinContext {
     pub y: f64,
     pub orient: String,
     pub status: String,
-    pub rects: Vec<crate::def::DefRect>, // PIN geometry (LAYER rectangles)
+    pub rects: Vec<DefRect>, // PIN geometry outside PORT groups
+    pub ports: Vec<DefPort>,
 }
 
 impl PinContext {
@@ -33,6 +35,7 @@ impl PinContext {
             orient: String::new(),
             status: "PLACED".to_string(),
             rects: Vec::new(),
+            ports: Vec::new(),
         }
     }
 }
@@ -97,7 +100,17 @@ impl DefItemParser for DefPinParser {
         ContinuationResult::Continue
     }
 
-    fn finalize(&self, context: Self::Context) -> ParseResult<Self::Item> {
+    fn finalize(&self, mut context: Self::Context) -> ParseResult<Self::Item> {
+        // A pin placed only through its ports takes the first port placement
+        if context.orient.is_empty() {
+            if let Some(port) = context.ports.iter().find(|p| !p.orient.is_empty()) {
+                context.status = port.status.clone();
+                context.x = port.x;
+                context.y = port.y;
+                context.orient = port.orient.clone();
+            }
+        }
+
         Ok(DefPin {
             name: context.name,
             net: context.net,
@@ -107,8 +120,8 @@ impl DefItemParser for DefPinParser {
             orient: context.orient,
             x: context.x,
             y: context.y,
-            rects: context.rects, // Use parsed rectangles from context
-            ports: Vec::new(),
+            rects: context.rects,
+            ports: context.ports,
         })
     }
 
@@ -122,6 +135,8 @@ impl DefPinParser {
     fn parse_header_attributes(&self, context: &mut PinContext, parts: &[&str]) {
         for i in 2..parts.len() {
             match parts[i] {
+                // Placements after a PORT belong to the port
+                "PORT" => break,
                 "NET" if i + 1 < parts.len() => {
                     context.net = clean_semicolon(parts[i + 1]).to_string();
                 }
@@ -145,82 +160,85 @@ impl DefPinParser {
     }
 
     /// Parse attributes from any line (header or continuation)
+    ///
+    /// The line is split into `+` statements. A `+ PORT` starts a new port
+    /// and the LAYER, VIA and placement statements after it belong to that
+    /// port; before the first PORT they belong to the pin itself.
     fn parse_line_attributes(&self, context: &mut PinContext, line: &str) {
-        // Extract NET value
-        if let Some(net) = extract_keyword_value(line, "NET") {
-            context.net = net;
-        }
-
-        // Extract DIRECTION value
-        if let Some(direction) = extract_keyword_value(line, "DIRECTION") {
-            context.direction = direction;
-        }
-
-        // Extract USE value
-        if let Some(use_type) = extract_keyword_value(line, "USE") {
-            context.use_type = use_type;
-        }
-
-        // Extract placement information
-        if let Some((status, x, y, orient)) = parse_placement(line) {
-            context.status = status;
-            context.x = x;
-            context.y = y;
-            context.orient = orient;
-        }
-
-        // Parse LAYER geometry: LAYER layerName ( xl yl ) ( xh yh )
-        self.parse_layer_geometry(context, line);
-    }
-
-    /// Parse LAYER geometry from a line
-    /// Format: LAYER layerName ( xl yl ) ( xh yh )
-    fn parse_layer_geometry(&self, context: &mut PinContext, line: &str) {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        let mut i = 0;
-
-        while i < parts.len() {
-            if parts[i] == "LAYER" && i + 1 < parts.len() {
-                let layer = parts[i + 1].to_string();
-
-                // Look for two coordinate pairs: ( xl yl ) ( xh yh )
-                let mut coords = Vec::new();
-                let mut j = i + 2;
-
-                while j < parts.len() && coords.len() < 2 {
-                    if parts[j] == "(" && j + 3 < parts.len() && parts[j + 3] == ")" {
-                        if let (Ok(x), Ok(y)) =
-                            (parts[j + 1].parse::<f64>(), parts[j + 2].parse::<f64>())
-                        {
-                            coords.push((x, y));
-                            j += 4; // Skip ( x y )
-                        } else {
-                            break;
+        let tokens: Vec<&str> = line
+            .split_whitespace()
+            .map(clean_semicolon)
+            .filter(|t| !t.is_empty())
+            .collect();
+
+        for statement in tokens.split(|t| *t == "+") {
+            match statement {
+                ["NET", net, ..] => context.net = net.to_string(),
+                ["DIRECTION", direction, ..] => context.direction = direction.to_string(),
+                ["USE", use_type, ..] => context.use_type = use_type.to_string(),
+                ["PORT", ..] => context.ports.push(DefPort::default()),
+                ["LAYER", layer, rest @ ..] => {
+                    // LAYER layerName [MASK n] [SPACING d | DESIGNRULEWIDTH d] ( xl yl ) ( xh yh )
+                    let coords = coordinate_pairs(rest);
+                    if let [(xl, yl), (xh, yh), ..] = coords[..] {
+                        let rect = DefRect {
+                            layer: layer.to_string(),
+                            xl,
+                            yl,
+                            xh,
+                            yh,
+                        };
+                        match context.ports.last_mut() {
+                            Some(port) => port.rects.push(rect),
+                            None => context.rects.push(rect),
                         }
-                    } else {
-                        break;
                     }
                 }
-
-                // If we found two coordinate pairs, create a DefRect
-                if coords.len() == 2 {
-                    context.rects.push(crate::def::DefRect {
-                        layer,
-                        xl: coords[0].0,
-                        yl: coords[0].1,
-                        xh: coords[1].0,
-                        yh: coords[1].1,
-                    });
+                ["VIA", via_name, rest @ ..] => {
+                    // VIA viaName [MASK n] ( x y )
+                    if let (Some(port), Some(&(x, y))) =
+                        (context.ports.last_mut(), coordinate_pairs(rest).first())
+                    {
+                        port.vias.push((via_name.to_string(), x, y));
+                    }
                 }
-
-                i = j; // Continue from where we left off
-            } else {
-                i += 1;
+                [status @ ("PLACED" | "FIXED" | "COVER"), rest @ ..] => {
+                    let Some(&(x, y)) = coordinate_pairs(rest).first() else {
+                        continue;
+                    };
+                    let orient = rest.get(4).copied().unwrap_or("N").to_string();
+                    match context.ports.last_mut() {
+                        Some(port) => {
+                            port.status = status.to_string();
+                            port.x = x;
+                            port.y = y;
+                            port.orient = orient;
+                        }
+                        None => {
+                            context.status = status.to_string();
+                            context.x = x;
+                            context.y = y;
+                            context.orient = orient;
+                        }
+                    }
+                }
+                _ => {}
             }
         }
     }
 }
 
+/// All `( x y )` pairs in a statement, in order
+fn coordinate_pairs(tokens: &[&str]) -> Vec<(f64, f64)> {
+    tokens
+        .windows(4)
+        .filter_map(|window| match window {
+            ["(", x, y, ")"] => Some((x.parse_finite().ok()?, y.parse_finite().ok()?)),
+            _ => None,
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! REGIONS parser implementation for DEF files
+//!
+//! Handles `- name ( x y ) ( x y ) ...` regions with any number of
+//! rectangles, an optional `+ TYPE FENCE|GUIDE` and `+ PROPERTY` values.
+
+use super::common::parse_coordinate_pair;
+use super::{ContinuationResult, DefItemParser, ParseResult};
+use crate::def::{DefRect, DefRegion};
+
+/// Parser for DEF REGIONS section
+pub struct DefRegionParser;
+
+impl DefItemParser for DefRegionParser {
+    type Item = DefRegion;
+    type Context = DefRegion;
+
+    fn parse_header(&self, line: &str) -> Option<Self::Context> {
+        let mut parts = line.split_whitespace();
+        if parts.next() != Some("-") {
+            return None;
+        }
+        Some(DefRegion {
+            name: parts.next()?.to_string(),
+            ..DefRegion::default()
+        })
+    }
+
+    fn parse_continuation(&self, context: &mut Self::Context, line: &str) -> ContinuationResult {
+        let trimmed = line.trim();
+        let parts: Vec<&str> = trimmed.trim_end_matches(';').split_whitespace().collect();
+        let mut i = 0;
+        if parts.first() == Some(&"-") {
+            if !context.rects.is_empty() {
+                return ContinuationResult::NextItem;
+            }
+            i = 2;
+        }
+        while i < parts.len() {
+            match parts[i] {
+                "(" => match (
+                    parse_coordinate_pair(&parts, i),
+                    parse_coordinate_pair(&parts, i + 4),
+                ) {
+                    (Some((x1, y1)), Some((x2, y2))) => {
+                        context.rects.push(DefRect {
+                            layer: String::new(),
+                            xl: x1.min(x2),
+                            yl: y1.min(y2),
+                            xh: x1.max(x2),
+                            yh: y1.max(y2),
+                        });
+                        i += 8;
+                    }
+                    _ => i += 1,
+                },
+                "TYPE" if i + 1 < parts.len() => {
+                    context.region_type = Some(parts[i + 1].to_string());
+                    i += 2;
+                }
+                "PROPERTY" if i + 2 < parts.len() => {
+                    context
+                        .properties
+                        .push((parts[i + 1].to_string(), parts[i + 2].to_string()));
+                    i += 3;
+                }
+                _ => i += 1,
+            }
+        }
+
+        if trimmed.ends_with(';') {
+            ContinuationResult::Complete
+        } else {
+            ContinuationResult::Continue
+        }
+    }
+
+    fn finalize(&self, context: Self::Context) -> ParseResult<Self::Item> {
+        Ok(context)
+    }
+
+    fn item_name() -> &'static str {
+        "REGION"
+    }
+}
@@ -0,0 +1,156 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! SCANCHAINS parser implementation for DEF files
+//!
+//! Handles `- name` chains with PARTITION, COMMONSCANPINS, START, STOP,
+//! FLOATING and ORDERED statements. Every ORDERED statement starts a list
+//! of its own. The MAXBITS of a partition is skipped.
+
+use super::{ContinuationResult, DefItemParser, ParseResult};
+use crate::def::{DefScanChain, DefScanElement};
+
+/// Parser for DEF SCANCHAINS section
+pub struct DefScanChainParser;
+
+/// Statement whose components are being read
+#[derive(Clone, Copy, PartialEq)]
+enum List {
+    None,
+    Floating,
+    Ordered,
+    CommonPins,
+}
+
+/// `( IN pin )`, `( OUT pin )` or `( BITS n )` at `parts[i]`
+fn scan_pin<'a>(parts: &[&'a str], i: usize) -> Option<(&'a str, &'a str)> {
+    match parts.get(i..i + 4)? {
+        ["(", keyword, value, ")"] => Some((keyword, value)),
+        _ => None,
+    }
+}
+
+impl DefItemParser for DefScanChainParser {
+    type Item = DefScanChain;
+    type Context = DefScanChain;
+
+    fn parse_header(&self, line: &str) -> Option<Self::Context> {
+        let mut parts = line.split_whitespace();
+        if parts.next() != Some("-") {
+            return None;
+        }
+        Some(DefScanChain {
+            name: parts.next()?.trim_end_matches(';').to_string(),
+            ..DefScanChain::default()
+        })
+    }
+
+    fn parse_continuation(&self, context: &mut Self::Context, line: &str) -> ContinuationResult {
+        let trimmed = line.trim();
+        let parts: Vec<&str> = trimmed.trim_end_matches(';').split_whitespace().collect();
+        let mut i = 0;
+        if parts.first() == Some(&"-") {
+            if context.start.is_some() || !context.ordered.is_empty() {
+                return ContinuationResult::NextItem;
+            }
+            i = 2;
+        }
+
+        let mut list = List::None;
+        while i < parts.len() {
+            if let Some((keyword, value)) = scan_pin(&parts, i) {
+                let element = match list {
+                    List::Floating => context.floating.last_mut(),
+                    List::Ordered => context.ordered.last_mut().and_then(|o| o.last_mut()),
+                    List::CommonPins => {
+                        match keyword {
+                            "IN" => context.common_in = Some(value.to_string()),
+                            "OUT" => context.common_out = Some(value.to_string()),
+                            _ => {}
+                        }
+                        None
+                    }
+                    List::None => None,
+                };
+                if let Some(element) = element {
+                    match keyword {
+                        "IN" => element.in_pin = Some(value.to_string()),
+                        "OUT" => element.out_pin = Some(value.to_string()),
+                        "BITS" => element.bits = value.parse().ok(),
+                        _ => {}
+                    }
+                }
+                i += 4;
+                continue;
+            }
+
+            match parts[i] {
+                "+" => {
+                    list = List::None;
+                    i += 1;
+                }
+                "PARTITION" if list == List::None && i + 1 < parts.len() => {
+                    context.partition = Some(parts[i + 1].to_string());
+                    i += 2;
+                }
+                "MAXBITS" if list == List::None => i += 2,
+                "COMMONSCANPINS" if list == List::None => {
+                    list = List::CommonPins;
+                    i += 1;
+                }
+                keyword @ ("START" | "STOP") if list == List::None && i + 1 < parts.len() => {
+                    let pin = parts
+                        .get(i + 2)
+                        .filter(|p| **p != "+")
+                        .map(|p| p.to_string());
+                    let end = Some((parts[i + 1].to_string(), pin.clone()));
+                    if keyword == "START" {
+                        context.start = end;
+                    } else {
+                        context.stop = end;
+                    }
+                    i += if pin.is_some() { 3 } else { 2 };
+                }
+                "FLOATING" if list == List::None => {
+                    list = List::Floating;
+                    i += 1;
+                }
+                "ORDERED" if list == List::None => {
+                    list = List::Ordered;
+                    context.ordered.push(Vec::new());
+                    i += 1;
+                }
+                component => {
+                    let element = DefScanElement {
+                        component: component.to_string(),
+                        ..DefScanElement::default()
+                    };
+                    match list {
+                        List::Floating => context.floating.push(element),
+                        List::Ordered => {
+                            if let Some(ordered) = context.ordered.last_mut() {
+                                ordered.push(element);
+                            }
+                        }
+                        List::None | List::CommonPins => {}
+                    }
+                    i += 1;
+                }
+            }
+        }
+
+        if trimmed.ends_with(';') {
+            ContinuationResult::Complete
+        } else {
+            ContinuationResult::Continue
+        }
+    }
+
+    fn finalize(&self, context: Self::Context) -> ParseResult<Self::Item> {
+        Ok(context)
+    }
+
+    fn item_name() -> &'static str {
+        "SCANCHAIN"
+    }
+}
@@ -8,7 +8,8 @@
 
 use super::common::*;
 use super::{ContinuationResult, DefItemParser, ParseResult};
-use crate::def::{DefRoute, DefRoutingPoint, DefSpecialNet};
+use crate::def::{DefRect, DefRoute, DefRoutingPoint, DefSpecialNet};
+use crate::number::ParseFinite;
 
 /// Context for parsing a single SPECIALNET
 #[derive(Debug)]
@@ -16,6 +17,7 @@ pub struct SpecialNetContext {
     pub name: String,
     pub connections: Vec<(String, String)>, // (instance, pin)
     pub routes: Vec<DefRoute>,
+    pub rects: Vec<DefRect>,
     pub use_type: Option<String>,
     pub weight: Option<f64>,
     pub voltage: Option<f64>,
@@ -29,6 +31,7 @@ impl SpecialNetContext {
             name,
             connections: Vec::new(),
             routes: Vec::new(),
+            rects: Vec::new(),
             use_type: None,
             weight: None,
             voltage: None,
@@ -151,6 +154,7 @@ impl DefItemParser for DefSpecialNetParser {
             name: context.name,
             connections: context.connections,
             routes: context.routes,
+            rects: context.rects,
             use_type: context.use_type,
             weight: context.weight,
             voltage: context.voltage,
@@ -183,7 +187,7 @@ impl DefSpecialNetParser {
             if i + 3 < parts.len() && parts[i] == "(" && parts[i + 3] == ")" {
                 // Check if this looks like a coordinate (both parts are numbers)
                 let is_coordinate =
-                    parts[i + 1].parse::<f64>().is_ok() && parts[i + 2].parse::<f64>().is_ok();
+                    parts[i + 1].parse_finite().is_ok() && parts[i + 2].parse_finite().is_ok();
 
                 if !is_coordinate {
                     let instance = parts[i + 1].to_string();
@@ -223,7 +227,7 @@ impl DefSpecialNetParser {
                         let mut width = 0.0;
                         let mut skip_count = 2; // routing_type + layer
                         if i + 2 < parts.len() {
-                            if let Ok(w) = parts[i + 2].parse::<f64>() {
+                            if let Ok(w) = parts[i + 2].parse_finite() {
                                 width = w;
                                 skip_count = 3; // routing_type + layer + width
                             }
@@ -250,7 +254,7 @@ impl DefSpecialNetParser {
                         let mut width = 0.0;
                         let mut skip_count = 2; // NEW + layer
                         if i + 2 < parts.len() {
-                            if let Ok(w) = parts[i + 2].parse::<f64>() {
+                            if let Ok(w) = parts[i + 2].parse_finite() {
                                 width = w;
                                 skip_count = 3; // NEW + layer + width
                             }
@@ -275,6 +279,35 @@ impl DefSpecialNetParser {
                         i += 1;
                     }
                 }
+                "RECT" => {
+                    // RECT layer ( xl yl ) ( xh yh ), absolute coordinates
+                    context.finalize_route();
+                    let coords: Vec<f64> = parts
+                        .iter()
+                        .skip(i + 2)
+                        .take(8)
+                        .filter(|t| **t != "(" && **t != ")")
+                        .filter_map(|t| t.parse_finite().ok())
+                        .collect();
+                    if let (Some(layer), &[x1, y1, x2, y2]) = (parts.get(i + 1), coords.as_slice())
+                    {
+                        context.rects.push(DefRect {
+                            layer: layer.to_string(),
+                            xl: x1.min(x2),
+                            yl: y1.min(y2),
+                            xh: x1.max(x2),
+                            yh: y1.max(y2),
+                        });
+                        i += 10;
+                    } else {
+                        i += 1;
+                    }
+                }
+                "POLYGON" => {
+                    // Not drawn; finalizing keeps its points out of the previous path
+                    context.finalize_route();
+                    i += 2;
+                }
                 "(" => {
                     // Parse coordinate: ( x y ) or ( x * ) or ( * y )
                     if i + 3 < parts.len() && parts[i + 3] == ")" {
@@ -285,12 +318,12 @@ impl DefSpecialNetParser {
                         let x = if x_str == "*" {
                             last_x
                         } else {
-                            x_str.parse().unwrap_or(0.0)
+                            x_str.parse_finite().unwrap_or(0.0)
                         };
                         let y = if y_str == "*" {
                             last_y
                         } else {
-                            y_str.parse().unwrap_or(0.0)
+                            y_str.parse_finite().unwrap_or(0.0)
                         };
 
                         context.add_point(x, y);
@@ -306,8 +339,8 @@ impl DefSpecialNetParser {
                     // Via reference - might be followed by coordinates
                     if i + 1 < parts.len() && parts[i + 1] == "(" {
                         if i + 4 < parts.len() && parts[i + 4] == ")" {
-                            let x = parts[i + 2].parse().unwrap_or(0.0);
-                            let y = parts[i + 3].parse().unwrap_or(0.0);
+                            let x = parts[i + 2].parse_finite().unwrap_or(0.0);
+                            let y = parts[i + 3].parse_finite().unwrap_or(0.0);
                             context.add_via(via_name.to_string(), x, y);
                             last_x = x;
                             last_y = y;
@@ -316,6 +349,10 @@ impl DefSpecialNetParser {
                             i += 1;
                         }
                     } else {
+                        // ( x y ) viaName places the via on that point
+                        if i > 0 && parts[i - 1] == ")" {
+                            context.add_via(via_name.to_string(), last_x, last_y);
+                        }
                         i += 1;
                     }
                 }
@@ -350,14 +387,14 @@ impl DefSpecialNetParser {
 
         // Extract WEIGHT value
         if let Some(weight_str) = extract_keyword_value(line, "WEIGHT") {
-            if let Ok(weight) = weight_str.parse::<f64>() {
+            if let Ok(weight) = weight_str.parse_finite() {
                 context.weight = Some(weight);
             }
         }
 
         // Extract VOLTAGE value
         if let Some(voltage_str) = extract_keyword_value(line, "VOLTAGE") {
-            if let Ok(voltage) = voltage_str.parse::<f64>() {
+            if let Ok(voltage) = voltage_str.parse_finite() {
                 context.voltage = Some(voltage);
             }
         }
@@ -446,6 +483,28 @@ mod tests {
         assert_eq!(context.use_type, Some("POWER".to_string()));
     }
 
+    #[test]
+    fn test_parse_rect_shapes() {
+        let parser = DefSpecialNetParser::new();
+        let mut context = SpecialNetContext::new("VDD".to_string());
+
+        parser.parse_routing_in_line(
+            &mut context,
+            "+ ROUTED metal1 170 ( 0 0 ) ( 100 0 ) + RECT metal5 ( 500 900 ) ( 300 100 ) + POLYGON metal6 ( 0 0 ) ( 10 0 ) ( 10 10 ) ;",
+        );
+
+        assert_eq!(context.routes.len(), 1);
+        assert_eq!(context.routes[0].points.len(), 2);
+        assert!(context.routes[0].vias.is_empty());
+        assert_eq!(context.rects.len(), 1);
+        let rect = &context.rects[0];
+        assert_eq!(rect.layer, "metal5");
+        assert_eq!(
+            (rect.xl, rect.yl, rect.xh, rect.yh),
+            (300.0, 100.0, 500.0, 900.0)
+        );
+    }
+
     #[test]
     fn test_wildcard_coordinates() {
         let parser = DefSpecialNetParser::new();
@@ -7,6 +7,7 @@
 use super::common::*;
 use super::{ContinuationResult, DefItemParser, ParseResult};
 use crate::def::{DefPolygon, DefRect, DefVia, DefViaLayer};
+use crate::number::ParseFinite;
 
 /// Context for parsing a single VIA
 #[derive(Debug)]
@@ -146,7 +147,7 @@ impl DefViaParser {
                 }
                 "CUTSIZE" if i + 2 < parts.len() => {
                     if let (Ok(w), Ok(h)) =
-                        (parts[i + 1].parse::<f64>(), parts[i + 2].parse::<f64>())
+                        (parts[i + 1].parse_finite(), parts[i + 2].parse_finite())
                     {
                         context.cut_size = Some((w, h));
                     }
@@ -176,18 +177,18 @@ impl DefViaParser {
                 context.via_rule = Some(clean_semicolon(parts[2]).to_string());
             }
             "CUTSIZE" if parts.len() >= 4 => {
-                if let (Ok(w), Ok(h)) = (parts[2].parse::<f64>(), parts[3].parse::<f64>()) {
+                if let (Ok(w), Ok(h)) = (parts[2].parse_finite(), parts[3].parse_finite()) {
                     context.cut_size = Some((w, h));
                 }
             }
             "CUTSPACING" if parts.len() >= 4 => {
-                if let (Ok(x), Ok(y)) = (parts[2].parse::<f64>(), parts[3].parse::<f64>()) {
+                if let (Ok(x), Ok(y)) = (parts[2].parse_finite(), parts[3].parse_finite()) {
                     context.cut_spacing = Some((x, y));
                 }
             }
             "ENCLOSURE" if parts.len() >= 5 => {
                 let layer = parts[2].to_string();
-                if let (Ok(x), Ok(y)) = (parts[3].parse::<f64>(), parts[4].parse::<f64>()) {
+                if let (Ok(x), Ok(y)) = (parts[3].parse_finite(), parts[4].parse_finite()) {
                     context.enclosure.push((layer, x, y));
                 }
             }
@@ -240,10 +241,10 @@ impl DefViaParser {
                     && parts[i + 7] == ")"
                 {
                     if let (Ok(x1), Ok(y1), Ok(x2), Ok(y2)) = (
-                        parts[i + 1].parse::<f64>(),
-                        parts[i + 2].parse::<f64>(),
-                        parts[i + 5].parse::<f64>(),
-                        parts[i + 6].parse::<f64>(),
+                        parts[i + 1].parse_finite(),
+                        parts[i + 2].parse_finite(),
+                        parts[i + 5].parse_finite(),
+                        parts[i + 6].parse_finite(),
                     ) {
                         xl = x1;
                         yl = y1;
@@ -294,8 +295,8 @@ impl DefViaParser {
         while part_idx + 3 < parts.len() {
             if parts[part_idx] == "(" && parts[part_idx + 3] == ")" {
                 if let (Ok(x), Ok(y)) = (
-                    parts[part_idx + 1].parse::<f64>(),
-                    parts[part_idx + 2].parse::<f64>(),
+                    parts[part_idx + 1].parse_finite(),
+                    parts[part_idx + 2].parse_finite(),
                 ) {
                     points.push((x, y));
                     part_idx += 4; // Skip past ( x y )
@@ -0,0 +1,215 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Placement diff of a DEF against a reference DEF
+//!
+//! Components are matched by instance name. A component moved when its
+//! location or orientation differs from the reference; locations are
+//! compared in microns, so the two designs may use different database
+//! units. Unplaced components of either design are left out of the moves.
+
+use super::Def;
+use std::collections::{BTreeMap, HashMap};
+
+/// Displacements below this many microns count as unmoved
+pub const MOVE_TOLERANCE: f64 = 1e-6;
+
+/// Component placed differently than in the reference
+#[derive(Debug, Clone, PartialEq)]
+pub struct MovedComponent {
+    pub name: String,
+    pub macro_name: String,
+    /// Location in the reference, in microns
+    pub from: (f64, f64),
+    /// Location in the current design, in microns
+    pub to: (f64, f64),
+    pub from_orientation: String,
+    pub to_orientation: String,
+}
+
+impl MovedComponent {
+    /// Distance between the two locations in microns
+    pub fn displacement(&self) -> f64 {
+        (self.to.0 - self.from.0).hypot(self.to.1 - self.from.1)
+    }
+}
+
+/// Removed component with its location in the reference, in microns
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemovedComponent {
+    pub name: String,
+    pub macro_name: String,
+    pub location: Option<(f64, f64)>,
+}
+
+/// Differences between the placements of two designs
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlacementDiff {
+    /// Moved components, largest displacement first
+    pub moved: Vec<MovedComponent>,
+    /// Names of components only in the current design, in DEF order
+    pub added: Vec<String>,
+    /// Components only in the reference, in DEF order
+    pub removed: Vec<RemovedComponent>,
+    /// Components placed the same in both designs
+    pub unchanged: usize,
+}
+
+/// Displacement statistics of the moved components of one macro class
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassDisplacement {
+    pub class: String,
+    pub moved: usize,
+    /// Largest and mean displacement in microns
+    pub max: f64,
+    pub avg: f64,
+}
+
+/// Compare the placement of `current` against `reference`, at the given
+/// database units per micron of each
+pub fn diff_placements(
+    reference: &Def,
+    reference_db_units: f64,
+    current: &Def,
+    db_units: f64,
+) -> PlacementDiff {
+    let reference_by_name: HashMap<&str, _> = reference
+        .components
+        .iter()
+        .map(|c| (c.name.as_str(), c))
+        .collect();
+    let location = |placement: &super::DefPlacement, db_units: f64| {
+        (placement.placement_type != "UNPLACED")
+            .then(|| (placement.x / db_units, placement.y / db_units))
+    };
+
+    let mut diff = PlacementDiff::default();
+    let mut seen = std::collections::HashSet::new();
+    for component in &current.components {
+        let Some(old) = reference_by_name.get(component.name.as_str()) else {
+            diff.added.push(component.name.clone());
+            continue;
+        };
+        seen.insert(component.name.as_str());
+        let old_placement = old.placement.as_ref();
+        let new_placement = component.placement.as_ref();
+        let (Some(old_placement), Some(new_placement)) = (old_placement, new_placement) else {
+            diff.unchanged += 1;
+            continue;
+        };
+        let (Some(from), Some(to)) = (
+            location(old_placement, reference_db_units),
+            location(new_placement, db_units),
+        ) else {
+            diff.unchanged += 1;
+            continue;
+        };
+        let moved = (to.0 - from.0).hypot(to.1 - from.1) > MOVE_TOLERANCE;
+        if moved || old_placement.orientation != new_placement.orientation {
+            diff.moved.push(MovedComponent {
+                name: component.name.clone(),
+                macro_name: component.macro_name.clone(),
+                from,
+                to,
+                from_orientation: old_placement.orientation.clone(),
+                to_orientation: new_placement.orientation.clone(),
+            });
+        } else {
+            diff.unchanged += 1;
+        }
+    }
+    diff.removed = reference
+        .components
+        .iter()
+        .filter(|c| !seen.contains(c.name.as_str()))
+        .map(|c| RemovedComponent {
+            name: c.name.clone(),
+            macro_name: c.macro_name.clone(),
+            location: c
+                .placement
+                .as_ref()
+                .and_then(|p| location(p, reference_db_units)),
+        })
+        .collect();
+    diff.moved
+        .sort_by(|a, b| b.displacement().total_cmp(&a.displacement()));
+    diff
+}
+
+impl PlacementDiff {
+    /// Displacement statistics per macro class, sorted by class
+    ///
+    /// `class_of` gives the LEF CLASS of a macro, None when the macro is
+    /// not loaded; such components are summarized as `UNKNOWN`.
+    pub fn class_summary<'a>(
+        &self,
+        class_of: impl Fn(&str) -> Option<&'a str>,
+    ) -> Vec<ClassDisplacement> {
+        let mut by_class: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+        for moved in &self.moved {
+            let class = class_of(&moved.macro_name)
+                .filter(|class| !class.is_empty())
+                .unwrap_or("UNKNOWN");
+            by_class
+                .entry(class.to_string())
+                .or_default()
+                .push(moved.displacement());
+        }
+        by_class
+            .into_iter()
+            .map(|(class, displacements)| ClassDisplacement {
+                class,
+                moved: displacements.len(),
+                max: displacements.iter().copied().fold(0.0, f64::max),
+                avg: displacements.iter().sum::<f64>() / displacements.len() as f64,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::component;
+
+    #[test]
+    fn test_diff_placements() {
+        let reference = Def {
+            components: vec![
+                component("u1", "INVX1", 1000.0, 1000.0, "N"),
+                component("u2", "INVX1", 2000.0, 1000.0, "N"),
+                component("u3", "RAM", 0.0, 0.0, "N"),
+                component("gone", "INVX1", 500.0, 500.0, "N"),
+            ],
+            ..Def::default()
+        };
+        // Current design at twice the database units
+        let current = Def {
+            components: vec![
+                component("u1", "INVX1", 2000.0, 2000.0, "N"),
+                component("u2", "INVX1", 4006.0, 2008.0, "N"),
+                component("u3", "RAM", 0.0, 0.0, "FS"),
+                component("new", "INVX1", 0.0, 0.0, "N"),
+            ],
+            ..Def::default()
+        };
+
+        let diff = diff_placements(&reference, 1000.0, &current, 2000.0);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.added, ["new"]);
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].location, Some((0.5, 0.5)));
+        let moved: Vec<&str> = diff.moved.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(moved, ["u2", "u3"]);
+        assert!((diff.moved[0].displacement() - 0.005).abs() < 1e-9);
+
+        let summary = diff.class_summary(|name| (name == "INVX1").then_some("CORE"));
+        assert_eq!(summary.len(), 2);
+        assert_eq!((summary[0].class.as_str(), summary[0].moved), ("CORE", 1));
+        assert!((summary[0].max - 0.005).abs() < 1e-9);
+        assert_eq!(
+            (summary[1].class.as_str(), summary[1].max),
+            ("UNKNOWN", 0.0)
+        );
+    }
+}
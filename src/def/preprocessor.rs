@@ -96,6 +96,7 @@ impl fmt::Display for PreprocessedDef {
 /// 2. Merge lines until semicolon (logical line)
 /// 3. Record mapping between logical and original lines
 pub fn preprocess(content: &str) -> PreprocessedDef {
+    crate::profile_scope!("preprocess");
     let raw_lines: Vec<&str> = content.lines().collect();
     let mut result = PreprocessedDef::new();
 
@@ -134,13 +135,7 @@ pub fn preprocess(content: &str) -> PreprocessedDef {
         current_line.push_str(trimmed);
 
         // Step 3: Check if logical line is complete
-        // Complete on: semicolon OR standalone END/DESIGN statements
-        let is_end_statement = trimmed.starts_with("END ")
-            || trimmed == "END"
-            || trimmed.starts_with("DESIGN ")
-            || (trimmed == "DESIGN" && current_line.trim() == "DESIGN");
-
-        if trimmed.contains(';') || is_end_statement {
+        if completes_statement(trimmed, &current_line) {
             let logical_index = result.lines.len();
             result.lines.push(current_line.clone());
             result
@@ -164,6 +159,16 @@ pub fn preprocess(content: &str) -> PreprocessedDef {
     result
 }
 
+/// Whether the raw line `trimmed`, just appended to `current_line`, ends
+/// the logical line: on a semicolon or a standalone END/DESIGN statement
+pub(super) fn completes_statement(trimmed: &str, current_line: &str) -> bool {
+    let is_end_statement = trimmed.starts_with("END ")
+        || trimmed == "END"
+        || trimmed.starts_with("DESIGN ")
+        || (trimmed == "DESIGN" && current_line.trim() == "DESIGN");
+    trimmed.contains(';') || is_end_statement
+}
+
 /// Remove comment from a line
 ///
 /// Comments start with # when preceded by:
@@ -176,7 +181,7 @@ pub fn preprocess(content: &str) -> PreprocessedDef {
 ///
 /// # Returns
 /// * Line with comment removed
-fn remove_comment(line: &str) -> &str {
+pub(super) fn remove_comment(line: &str) -> &str {
     if let Some(pos) = find_comment_start(line) {
         &line[..pos]
     } else {
@@ -1,41 +1,69 @@
 // SPDX-License-Identifier: MIT
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
-use std::fs;
+use std::fs::{self, File};
+use std::io::BufReader;
 use std::path::Path;
 
-use super::{def_parser::parse_def, Def};
+use super::stream::{parse_def_streaming, STREAMING_THRESHOLD};
+use super::{def_parser::parse_def_with_progress, Def, DefParseOptions};
+use crate::progress::{Progress, ProgressCallback};
 
-pub struct DefReader;
+pub struct DefReader {
+    progress: Option<ProgressCallback>,
+}
 
 impl DefReader {
     pub fn new() -> Self {
-        Self
+        Self { progress: None }
+    }
+
+    /// Call `callback` with the progress while parsing
+    pub fn with_progress(mut self, callback: impl Fn(&Progress) + Send + 'static) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
     }
 
     pub fn read<P: AsRef<Path>>(&self, path: P) -> Result<Def, Box<dyn std::error::Error>> {
+        self.read_with_options(path, &DefParseOptions::default())
+    }
+
+    /// Read a DEF file, skipping the sections excluded by `options`
+    ///
+    /// Files of [`STREAMING_THRESHOLD`] bytes and more are parsed while
+    /// reading instead of being read into memory first.
+    pub fn read_with_options<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &DefParseOptions,
+    ) -> Result<Def, Box<dyn std::error::Error>> {
         let path_str = path.as_ref().display().to_string();
-        println!("[LOAD] Loading DEF file: {path_str}");
+        eprintln!("[LOAD] Loading DEF file: {path_str}");
+
+        let size = fs::metadata(&path)?.len();
+        if size >= STREAMING_THRESHOLD {
+            eprintln!("[FILE] DEF file size: {size} bytes, parsing while reading");
+            let reader = BufReader::new(File::open(path)?);
+            let def = parse_def_streaming(reader, size, options, self.progress.as_ref())?;
+            print_summary(&def);
+            return Ok(def);
+        }
 
         let content = fs::read_to_string(path)?;
-        println!("[FILE] DEF file size: {} bytes", content.len());
+        eprintln!("[FILE] DEF file size: {} bytes", content.len());
 
         // Print first few lines for debugging
         let lines: Vec<&str> = content.lines().take(10).collect();
-        println!("[FILE] First 10 lines:");
+        eprintln!("[FILE] First 10 lines:");
         for (i, line) in lines.iter().enumerate() {
-            println!("  {}: {}", i + 1, line);
+            eprintln!("  {}: {}", i + 1, line);
         }
 
-        match parse_def(&content) {
+        match parse_def_with_progress(&content, options, self.progress.as_ref()) {
             Ok((remaining, def)) => {
-                println!("[PASS] DEF parsed successfully!");
-                println!("[INFO] Die area points: {}", def.die_area_points.len());
-                println!("[INFO] Components: {}", def.components.len());
-                println!("[INFO] Pins: {}", def.pins.len());
-                println!("[INFO] Nets: {}", def.nets.len());
+                print_summary(&def);
                 if !remaining.trim().is_empty() {
-                    println!(
+                    eprintln!(
                         "[WARN] Unparsed content remaining: {} chars",
                         remaining.len()
                     );
@@ -43,13 +71,21 @@ impl DefReader {
                 Ok(def)
             }
             Err(e) => {
-                println!("[FAIL] Failed to parse DEF file: {e:?}");
+                eprintln!("[FAIL] Failed to parse DEF file: {e:?}");
                 Err(format!("Failed to parse DEF file: {e:?}").into())
             }
         }
     }
 }
 
+fn print_summary(def: &Def) {
+    eprintln!("[PASS] DEF parsed successfully!");
+    eprintln!("[INFO] Die area points: {}", def.die_area_points.len());
+    eprintln!("[INFO] Components: {}", def.components.len());
+    eprintln!("[INFO] Pins: {}", def.pins.len());
+    eprintln!("[INFO] Nets: {}", def.nets.len());
+}
+
 impl Default for DefReader {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,374 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Streaming DEF parser for huge files
+//!
+//! The regular parser reads the whole file into a string and preprocesses
+//! it into logical lines before parsing, so the text is held twice next to
+//! the parsed design. That does not fit for routed DEF files of 10 GB and
+//! more. Files of [`STREAMING_THRESHOLD`] and above are read through a
+//! buffer instead: [`LogicalLines`] merges the raw lines into logical lines
+//! with the rules of the preprocessor, one statement at a time in reused
+//! buffers, and the items of the [`STREAMED_SECTIONS`] are parsed as they
+//! are read. Only the remaining statements, the header, ROW, TRACKS and
+//! VIAS, are kept as text for the regular parser; they are small.
+//!
+//! Both parsers give the same design.
+
+use std::error::Error;
+use std::io::{self, BufRead};
+
+use super::def_parser::{item_count, parse_logical_lines};
+use super::parser::{
+    blockage::DefBlockageParser, component::DefComponentParser, fill::DefFillParser,
+    group::DefGroupParser, net::DefNetParser, pin::DefPinParser, region::DefRegionParser,
+    scanchain::DefScanChainParser, specialnet::DefSpecialNetParser, DefItemParser, MultiLineParser,
+};
+use super::preprocessor::{completes_statement, remove_comment};
+use super::{Def, DefParseOptions};
+use crate::progress::{ProgressCallback, ProgressReporter};
+
+/// Files of this many bytes and more are parsed while reading
+pub const STREAMING_THRESHOLD: u64 = 512 * 1024 * 1024;
+
+/// Sections whose items are parsed as they are read
+pub const STREAMED_SECTIONS: [&str; 9] = [
+    "COMPONENTS",
+    "PINS",
+    "SPECIALNETS",
+    "NETS",
+    "BLOCKAGES",
+    "REGIONS",
+    "GROUPS",
+    "FILLS",
+    "SCANCHAINS",
+];
+
+/// Streamed sections whose header needs a valid item count
+const COUNTED_SECTIONS: [&str; 4] = ["COMPONENTS", "PINS", "SPECIALNETS", "NETS"];
+
+/// Logical lines of DEF text read from a buffer, as [`super::preprocessor`]
+/// would produce them
+pub struct LogicalLines<R> {
+    reader: R,
+    raw: String,
+    pending: String,
+    line: String,
+    bytes_read: u64,
+}
+
+impl<R: BufRead> LogicalLines<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            raw: String::new(),
+            pending: String::new(),
+            line: String::new(),
+            bytes_read: 0,
+        }
+    }
+
+    /// Bytes of the text read so far
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    /// Next logical line, None at the end of the text
+    pub fn next_line(&mut self) -> io::Result<Option<&str>> {
+        loop {
+            self.raw.clear();
+            let read = self.reader.read_line(&mut self.raw)?;
+            self.bytes_read += read as u64;
+            if read == 0 {
+                // Incomplete logical line at end of file
+                if self.pending.trim().is_empty() {
+                    return Ok(None);
+                }
+                return Ok(Some(self.take_pending()));
+            }
+
+            let trimmed = remove_comment(&self.raw).trim();
+            if trimmed.is_empty() {
+                // Empty lines end statements without a semicolon
+                if self.pending.is_empty() {
+                    continue;
+                }
+                return Ok(Some(self.take_pending()));
+            }
+
+            if !self.pending.is_empty() {
+                self.pending.push(' ');
+            }
+            self.pending.push_str(trimmed);
+            if completes_statement(trimmed, &self.pending) {
+                return Ok(Some(self.take_pending()));
+            }
+        }
+    }
+
+    fn take_pending(&mut self) -> &str {
+        std::mem::swap(&mut self.line, &mut self.pending);
+        self.pending.clear();
+        &self.line
+    }
+}
+
+/// Progress of a streamed file, by the bytes read of its `size`
+struct StreamProgress<'a> {
+    reporter: ProgressReporter<'a>,
+    size: f64,
+    items: usize,
+}
+
+impl StreamProgress<'_> {
+    fn report<R: BufRead>(&mut self, lines: &LogicalLines<R>, section: &str) {
+        let fraction = lines.bytes_read() as f64 / self.size;
+        self.reporter.report(fraction, self.items, section);
+    }
+}
+
+/// Parse DEF text of `size` bytes from a reader, skipping the sections
+/// excluded by `options`
+pub fn parse_def_streaming<R: BufRead>(
+    reader: R,
+    size: u64,
+    options: &DefParseOptions,
+    progress: Option<&ProgressCallback>,
+) -> Result<Def, Box<dyn Error>> {
+    crate::profile_scope!("parse_def_streaming");
+    let mut lines = LogicalLines::new(reader);
+    let mut rest = Vec::new();
+    let mut streamed = Def::default();
+    let progress = &mut StreamProgress {
+        reporter: ProgressReporter::new(progress),
+        size: size.max(1) as f64,
+        items: 0,
+    };
+
+    while let Some(line) = lines.next_line()? {
+        let mut parts = line.split_whitespace();
+        let keyword = parts.next().unwrap_or_default();
+        let section = match parts.next() {
+            Some(count) => STREAMED_SECTIONS.iter().copied().find(|s| {
+                *s == keyword && (!COUNTED_SECTIONS.contains(s) || count.parse::<usize>().is_ok())
+            }),
+            None => None,
+        };
+        let Some(section) = section else {
+            rest.push(line.to_string());
+            continue;
+        };
+
+        let end = format!("END {section}");
+        if options.skips(section) {
+            eprintln!("[DBG]   Skipping {section} section (section filter)");
+            stream_section(&mut lines, &end, None::<DefComponentParser>, progress)?;
+            continue;
+        }
+        match section {
+            "COMPONENTS" => streamed.components.extend(stream_section(
+                &mut lines,
+                &end,
+                Some(DefComponentParser),
+                progress,
+            )?),
+            "PINS" => streamed.pins.extend(stream_section(
+                &mut lines,
+                &end,
+                Some(DefPinParser::new()),
+                progress,
+            )?),
+            "SPECIALNETS" => streamed.special_nets.extend(stream_section(
+                &mut lines,
+                &end,
+                Some(DefSpecialNetParser::new()),
+                progress,
+            )?),
+            "NETS" => streamed.nets.extend(stream_section(
+                &mut lines,
+                &end,
+                Some(DefNetParser::new()),
+                progress,
+            )?),
+            "BLOCKAGES" => streamed.blockages.extend(stream_section(
+                &mut lines,
+                &end,
+                Some(DefBlockageParser),
+                progress,
+            )?),
+            "REGIONS" => streamed.regions.extend(stream_section(
+                &mut lines,
+                &end,
+                Some(DefRegionParser),
+                progress,
+            )?),
+            "GROUPS" => streamed.groups.extend(stream_section(
+                &mut lines,
+                &end,
+                Some(DefGroupParser),
+                progress,
+            )?),
+            "FILLS" => streamed.fills.extend(stream_section(
+                &mut lines,
+                &end,
+                Some(DefFillParser),
+                progress,
+            )?),
+            _ => streamed.scan_chains.extend(stream_section(
+                &mut lines,
+                &end,
+                Some(DefScanChainParser),
+                progress,
+            )?),
+        }
+    }
+
+    // The streamed sections never reach the regular parser
+    let mut def = parse_logical_lines(&rest, options, &mut ProgressReporter::new(None));
+    def.components = streamed.components;
+    def.pins = streamed.pins;
+    def.special_nets = streamed.special_nets;
+    def.nets = streamed.nets;
+    def.blockages = streamed.blockages;
+    def.regions = streamed.regions;
+    def.groups = streamed.groups;
+    def.fills = streamed.fills;
+    def.scan_chains = streamed.scan_chains;
+    progress
+        .reporter
+        .report(1.0, item_count(&def), "END DESIGN");
+    Ok(def)
+}
+
+/// Parse the items of a section up to its `end` line, or only skip them
+/// without a parser
+///
+/// Like the regular parser, a section with an invalid item gives no items.
+fn stream_section<R: BufRead, P: DefItemParser>(
+    lines: &mut LogicalLines<R>,
+    end: &str,
+    parser: Option<P>,
+    progress: &mut StreamProgress,
+) -> io::Result<Vec<P::Item>> {
+    let section = &end[4..];
+    let parser = parser.map(MultiLineParser::with_preprocessed);
+    let mut items = Vec::new();
+    let mut error = None;
+    while let Some(line) = lines.next_line()? {
+        let line = line.trim();
+        if line.starts_with(end) {
+            break;
+        }
+        if let Some(parser) = &parser {
+            if error.is_none() && !line.is_empty() {
+                match parser.parse_item_preprocessed(line) {
+                    Ok(Some(item)) => {
+                        items.push(item);
+                        progress.items += 1;
+                    }
+                    Ok(None) => {}
+                    Err(e) => error = Some(e),
+                }
+            }
+        }
+        progress.report(lines, section);
+    }
+    if let Some(e) = error {
+        eprintln!("[DBG]   Error parsing {section} section: {e}");
+        progress.items -= items.len();
+        items.clear();
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::def::def_parser::parse_def_with_options;
+    use crate::def::preprocessor::preprocess;
+    use std::path::Path;
+
+    const SAMPLE: &str = "VERSION 5.8 ;
+# header comment
+DESIGN top ;
+UNITS DISTANCE MICRONS 1000 ;
+DIEAREA ( 0 0 ) ( 20000 10000 ) ;
+ROW row0 core 0 0 N DO 10 BY 1 STEP 200 0 ;
+TRACKS X 100 DO 50 STEP 200 LAYER M2 ;
+COMPONENTS 2 ;
+- u1 INV + PLACED ( 0 0 ) N ;
+- u2 INV
+  + FIXED ( 400 0 ) FS ;
+END COMPONENTS
+PINS 1 ;
+- a + NET a + DIRECTION INPUT + USE SIGNAL
+  + LAYER M2 ( -50 0 ) ( 50 100 ) + PLACED ( 0 5000 ) E ;
+END PINS
+NETS 1 ;
+- a ( PIN a ) ( u1 A ) # trailing comment
+  + ROUTED M1 ( 0 5000 ) ( 100 * ) ;
+END NETS
+END DESIGN
+";
+
+    fn streamed(text: &str, options: &DefParseOptions) -> serde_json::Value {
+        let def = parse_def_streaming(text.as_bytes(), text.len() as u64, options, None).unwrap();
+        serde_json::to_value(def).unwrap()
+    }
+
+    fn regular(text: &str, options: &DefParseOptions) -> serde_json::Value {
+        let (_, def) = parse_def_with_options(text, options).unwrap();
+        serde_json::to_value(def).unwrap()
+    }
+
+    #[test]
+    fn test_logical_lines_match_preprocessor() {
+        let mut lines = LogicalLines::new(SAMPLE.as_bytes());
+        let mut streamed = Vec::new();
+        while let Some(line) = lines.next_line().unwrap() {
+            streamed.push(line.to_string());
+        }
+        assert_eq!(streamed, preprocess(SAMPLE).lines);
+    }
+
+    #[test]
+    fn test_streaming_matches_regular_parser() {
+        let options = DefParseOptions::default();
+        let def = streamed(SAMPLE, &options);
+        assert_eq!(def["components"].as_array().unwrap().len(), 2);
+        assert_eq!(def["nets"].as_array().unwrap().len(), 1);
+        assert_eq!(def, regular(SAMPLE, &options));
+
+        let options = DefParseOptions::default().skip("NETS");
+        let def = streamed(SAMPLE, &options);
+        assert!(def["nets"].as_array().unwrap().is_empty());
+        assert_eq!(def, regular(SAMPLE, &options));
+
+        // Progress ends at the whole file with every item counted
+        let last = std::sync::Arc::new(std::sync::Mutex::new(None));
+        let sink = std::sync::Arc::clone(&last);
+        let callback: ProgressCallback = Box::new(move |p| *sink.lock().unwrap() = Some(p.clone()));
+        let size = SAMPLE.len() as u64;
+        parse_def_streaming(SAMPLE.as_bytes(), size, &options, Some(&callback)).unwrap();
+        let last = last.lock().unwrap().clone().unwrap();
+        assert_eq!((last.fraction, last.items), (1.0, 3));
+
+        // Every sample design in the repository
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests");
+        for dir in ["test_samples", "fixtures"] {
+            for entry in std::fs::read_dir(root.join(dir)).unwrap() {
+                let path = entry.unwrap().path();
+                if path.extension().is_some_and(|e| e == "def") {
+                    let text = std::fs::read_to_string(&path).unwrap();
+                    let options = DefParseOptions::default();
+                    assert_eq!(
+                        streamed(&text, &options),
+                        regular(&text, &options),
+                        "{}",
+                        path.display()
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Placement utilization of a DEF
+//!
+//! Utilization is the macro area of the placed components over the die
+//! area. COVER components (bumps, pad fillers) sit above the core and are
+//! left out unless asked for; physical-only cells count towards the total
+//! but not towards the logical utilization.
+
+use super::Def;
+
+/// Utilization ratios of a design
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Utilization {
+    /// All counted components over the die area
+    pub total: f64,
+    /// Counted components that are not physical-only, over the die area
+    pub logical: f64,
+    /// COVER components left out of both ratios
+    pub cover_excluded: usize,
+}
+
+/// Die area in square database units, None without a DIEAREA
+pub fn die_area(def: &Def) -> Option<f64> {
+    let points = &def.die_area_points;
+    let area = match points.len() {
+        0 | 1 => return None,
+        2 => ((points[1].0 - points[0].0) * (points[1].1 - points[0].1)).abs(),
+        n => {
+            // Shoelace formula for rectilinear die areas
+            let twice: f64 = (0..n)
+                .map(|i| {
+                    let (x1, y1) = points[i];
+                    let (x2, y2) = points[(i + 1) % n];
+                    x1 * y2 - x2 * y1
+                })
+                .sum();
+            twice.abs() / 2.0
+        }
+    };
+    (area > 0.0).then_some(area)
+}
+
+/// Utilization of `def`
+///
+/// `macro_area` gives the area of a macro in square microns, None when the
+/// macro is not loaded; such components are not counted.
+pub fn utilization(
+    def: &Def,
+    db_units: f64,
+    macro_area: impl Fn(&str) -> Option<f64>,
+    is_physical_only: impl Fn(&str) -> bool,
+    include_cover: bool,
+) -> Option<Utilization> {
+    let die_area = die_area(def)? / (db_units * db_units);
+
+    let (mut total, mut logical, mut cover_excluded) = (0.0, 0.0, 0);
+    for component in &def.components {
+        if component.is_cover() && !include_cover {
+            cover_excluded += 1;
+            continue;
+        }
+        if let Some(area) = macro_area(&component.macro_name) {
+            total += area;
+            if !is_physical_only(&component.macro_name) {
+                logical += area;
+            }
+        }
+    }
+
+    Some(Utilization {
+        total: total / die_area,
+        logical: logical / die_area,
+        cover_excluded,
+    })
+}
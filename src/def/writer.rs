@@ -0,0 +1,506 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! DEF text writer
+//!
+//! Synthesizes DEF statements from the parsed data structures. Coordinates
+//! are written in database units as stored, so a snippet pasted back into
+//! the source DEF keeps its meaning. [`write_def`] writes a whole design that
+//! reads back into an equal [`Def`].
+
+use std::fmt::Write;
+
+use super::{
+    Def, DefBlockage, DefComponent, DefFill, DefGCellGrid, DefGroup, DefNet, DefPin, DefRect,
+    DefRegion, DefRoute, DefRow, DefScanChain, DefScanElement, DefSpecialNet, DefTrack, DefVia,
+};
+
+/// Complete DEF file of `def`, sections in the order of the DEF reference
+pub fn write_def(def: &Def) -> String {
+    let mut out = String::from("VERSION 5.8 ;\nDIVIDERCHAR \"/\" ;\n");
+    let _ = writeln!(
+        out,
+        "BUSBITCHARS \"{}{}\" ;",
+        def.bus_bit_chars.open, def.bus_bit_chars.close
+    );
+    let design = if def.design.is_empty() {
+        "design"
+    } else {
+        &def.design
+    };
+    let _ = writeln!(out, "DESIGN {design} ;");
+    if let Some(units) = def.units_distance_microns {
+        let _ = writeln!(out, "UNITS DISTANCE MICRONS {units} ;");
+    }
+    if !def.die_area_points.is_empty() {
+        out.push_str("DIEAREA");
+        for (x, y) in &def.die_area_points {
+            let _ = write!(out, " ( {x} {y} )");
+        }
+        out.push_str(" ;\n");
+    }
+    out.push('\n');
+
+    for row in &def.rows {
+        out.push_str(&write_row(row));
+    }
+    for (axis, tracks) in [("X", &def.tracks_x), ("Y", &def.tracks_y)] {
+        for track in tracks {
+            out.push_str(&write_track(axis, track));
+        }
+    }
+    for (axis, grids) in [("X", &def.g_cell_grid_x), ("Y", &def.g_cell_grid_y)] {
+        for grid in grids {
+            out.push_str(&write_gcell_grid(axis, grid));
+        }
+    }
+
+    write_section(&mut out, "VIAS", &def.vias, write_via);
+    write_section(&mut out, "REGIONS", &def.regions, write_region);
+    write_section(&mut out, "COMPONENTS", &def.components, write_component);
+    write_section(&mut out, "PINS", &def.pins, write_pin);
+    write_section(&mut out, "BLOCKAGES", &def.blockages, write_blockage);
+    write_section(&mut out, "FILLS", &def.fills, write_fill);
+    write_section(
+        &mut out,
+        "SPECIALNETS",
+        &def.special_nets,
+        write_special_net,
+    );
+    write_section(&mut out, "NETS", &def.nets, write_net);
+    write_section(&mut out, "SCANCHAINS", &def.scan_chains, write_scan_chain);
+    write_section(&mut out, "GROUPS", &def.groups, write_group);
+
+    out.push_str("\nEND DESIGN\n");
+    out
+}
+
+/// `NAME count ;`, one statement per item and `END NAME`, nothing when empty
+fn write_section<T>(out: &mut String, name: &str, items: &[T], write_item: fn(&T) -> String) {
+    if items.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "\n{name} {} ;", items.len());
+    for item in items {
+        out.push_str(&write_item(item));
+    }
+    let _ = writeln!(out, "END {name}");
+}
+
+/// ROW statement; rows without orientation are written with N
+pub fn write_row(row: &DefRow) -> String {
+    let orientation = if row.orientation.is_empty() {
+        "N"
+    } else {
+        &row.orientation
+    };
+    format!(
+        "ROW {} {} {} {} {orientation} DO {} BY {} STEP {} {} ;\n",
+        row.name, row.macro_name, row.x, row.y, row.num_x, row.num_y, row.step_x, row.step_y
+    )
+}
+
+/// TRACKS statement along `axis` ("X" or "Y")
+pub fn write_track(axis: &str, track: &DefTrack) -> String {
+    format!(
+        "TRACKS {axis} {} DO {} STEP {} LAYER {} ;\n",
+        track.offset, track.num, track.step, track.layer
+    )
+}
+
+/// GCELLGRID statement along `axis` ("X" or "Y")
+pub fn write_gcell_grid(axis: &str, grid: &DefGCellGrid) -> String {
+    format!(
+        "GCELLGRID {axis} {} DO {} STEP {} ;\n",
+        grid.offset, grid.count, grid.step
+    )
+}
+
+/// REGIONS statement of one region with its rectangles
+pub fn write_region(region: &DefRegion) -> String {
+    let mut out = format!("- {}", region.name);
+    for rect in &region.rects {
+        let _ = write!(
+            out,
+            " ( {} {} ) ( {} {} )",
+            rect.xl, rect.yl, rect.xh, rect.yh
+        );
+    }
+    if let Some(region_type) = &region.region_type {
+        let _ = write!(out, " + TYPE {region_type}");
+    }
+    for (name, value) in &region.properties {
+        let _ = write!(out, " + PROPERTY {name} {value}");
+    }
+    out.push_str(" ;\n");
+    out
+}
+
+/// GROUPS statement of one group with its component patterns
+pub fn write_group(group: &DefGroup) -> String {
+    let mut out = format!("- {}", group.name);
+    for pattern in &group.patterns {
+        let _ = write!(out, " {pattern}");
+    }
+    if let Some(region) = &group.region {
+        let _ = write!(out, " + REGION {region}");
+    }
+    for (name, value) in &group.properties {
+        let _ = write!(out, " + PROPERTY {name} {value}");
+    }
+    out.push_str(" ;\n");
+    out
+}
+
+/// FILLS statement of one layer fill or fill via
+pub fn write_fill(fill: &DefFill) -> String {
+    let mut out = match (&fill.layer, &fill.via) {
+        (Some(layer), _) => format!("- LAYER {layer}"),
+        (None, Some(via)) => format!("- VIA {via}"),
+        (None, None) => "- LAYER".to_string(),
+    };
+    if fill.opc {
+        out.push_str(" + OPC");
+    }
+    for rect in &fill.rects {
+        let _ = write!(
+            out,
+            "\n  RECT ( {} {} ) ( {} {} )",
+            rect.xl, rect.yl, rect.xh, rect.yh
+        );
+    }
+    for polygon in &fill.polygons {
+        out.push_str("\n  POLYGON");
+        for (x, y) in &polygon.points {
+            let _ = write!(out, " ( {x} {y} )");
+        }
+    }
+    for (x, y) in &fill.via_points {
+        let _ = write!(out, " ( {x} {y} )");
+    }
+    out.push_str(" ;\n");
+    out
+}
+
+/// Scan chain component with its optional pins and bit count
+fn write_scan_element(out: &mut String, element: &DefScanElement) {
+    let _ = write!(out, " {}", element.component);
+    if let Some(pin) = &element.in_pin {
+        let _ = write!(out, " ( IN {pin} )");
+    }
+    if let Some(pin) = &element.out_pin {
+        let _ = write!(out, " ( OUT {pin} )");
+    }
+    if let Some(bits) = element.bits {
+        let _ = write!(out, " ( BITS {bits} )");
+    }
+}
+
+/// SCANCHAINS statement of one chain
+pub fn write_scan_chain(chain: &DefScanChain) -> String {
+    let mut out = format!("- {}", chain.name);
+    if let Some(partition) = &chain.partition {
+        let _ = write!(out, "\n  + PARTITION {partition}");
+    }
+    if chain.common_in.is_some() || chain.common_out.is_some() {
+        out.push_str("\n  + COMMONSCANPINS");
+        if let Some(pin) = &chain.common_in {
+            let _ = write!(out, " ( IN {pin} )");
+        }
+        if let Some(pin) = &chain.common_out {
+            let _ = write!(out, " ( OUT {pin} )");
+        }
+    }
+    for (keyword, end) in [("START", &chain.start), ("STOP", &chain.stop)] {
+        if let Some((component, pin)) = end {
+            let _ = write!(out, "\n  + {keyword} {component}");
+            if let Some(pin) = pin {
+                let _ = write!(out, " {pin}");
+            }
+        }
+    }
+    if !chain.floating.is_empty() {
+        out.push_str("\n  + FLOATING");
+        for element in &chain.floating {
+            write_scan_element(&mut out, element);
+        }
+    }
+    for ordered in &chain.ordered {
+        out.push_str("\n  + ORDERED");
+        for element in ordered {
+            write_scan_element(&mut out, element);
+        }
+    }
+    out.push_str(" ;\n");
+    out
+}
+
+/// BLOCKAGES statement of one blockage with its shapes, terminated by `;`
+pub fn write_blockage(blockage: &DefBlockage) -> String {
+    let mut out = match &blockage.layer {
+        Some(layer) => format!("- LAYER {layer}"),
+        None => "- PLACEMENT".to_string(),
+    };
+    if let Some(component) = &blockage.component {
+        let _ = write!(out, " + COMPONENT {component}");
+    }
+    if blockage.soft {
+        out.push_str(" + SOFT");
+    }
+    if let Some(partial) = blockage.partial {
+        let _ = write!(out, " + PARTIAL {partial}");
+    }
+    for rect in &blockage.rects {
+        let _ = write!(
+            out,
+            "\n  RECT ( {} {} ) ( {} {} )",
+            rect.xl, rect.yl, rect.xh, rect.yh
+        );
+    }
+    for polygon in &blockage.polygons {
+        out.push_str("\n  POLYGON");
+        for (x, y) in &polygon.points {
+            let _ = write!(out, " ( {x} {y} )");
+        }
+    }
+    out.push_str(" ;\n");
+    out
+}
+
+/// VIAS statement of one via with its shapes, terminated by `;`
+pub fn write_via(via: &DefVia) -> String {
+    let mut out = format!("- {}", via.name);
+    if let Some(rule) = &via.via_rule {
+        let _ = write!(out, "\n    + VIARULE {rule}");
+    }
+    if let Some((x, y)) = via.cut_size {
+        let _ = write!(out, "\n    + CUTSIZE {x} {y}");
+    }
+    if let Some((x, y)) = via.cut_spacing {
+        let _ = write!(out, "\n    + CUTSPACING {x} {y}");
+    }
+    if !via.pattern.is_empty() {
+        let _ = write!(out, "\n    + PATTERN {}", via.pattern);
+    }
+    for layer in &via.layers {
+        for rect in &layer.rects {
+            let _ = write!(
+                out,
+                "\n    + RECT {} ( {} {} ) ( {} {} )",
+                layer.layer_name, rect.xl, rect.yl, rect.xh, rect.yh
+            );
+        }
+        for polygon in &layer.polygons {
+            let _ = write!(out, "\n    + POLYGON {}", layer.layer_name);
+            if let Some(mask) = layer.mask {
+                let _ = write!(out, " + MASK {mask}");
+            }
+            for (x, y) in &polygon.points {
+                let _ = write!(out, " ( {x} {y} )");
+            }
+        }
+    }
+    out.push_str(" ;\n");
+    out
+}
+
+/// NETS statement of one net with its connections and routing
+pub fn write_net(net: &DefNet) -> String {
+    let mut out = format!("- {}", net.name);
+    for (instance, pin) in net.instances.iter().zip(&net.instance_pins) {
+        let _ = write!(out, " ( {instance} {pin} )");
+    }
+    if !net.use_type.is_empty() {
+        let _ = write!(out, "\n    + USE {}", net.use_type);
+    }
+    if let Some(weight) = net.weight {
+        let _ = write!(out, "\n    + WEIGHT {weight}");
+    }
+    if !net.source.is_empty() {
+        let _ = write!(out, "\n    + SOURCE {}", net.source);
+    }
+    if !net.pattern.is_empty() {
+        let _ = write!(out, "\n    + PATTERN {}", net.pattern);
+    }
+    for shield_net in &net.shield_nets {
+        let _ = write!(out, "\n    + SHIELDNET {shield_net}");
+    }
+    write_wiring(&mut out, &net.routes, false);
+    out.push_str(" ;\n");
+    out
+}
+
+/// SPECIALNETS statement of one net with its wiring and RECT straps
+pub fn write_special_net(net: &DefSpecialNet) -> String {
+    let mut out = format!("- {}", net.name);
+    for (instance, pin) in &net.connections {
+        let _ = write!(out, " ( {instance} {pin} )");
+    }
+    if let Some(use_type) = &net.use_type {
+        let _ = write!(out, "\n    + USE {use_type}");
+    }
+    if let Some(voltage) = net.voltage {
+        let _ = write!(out, "\n    + VOLTAGE {voltage}");
+    }
+    if let Some(weight) = net.weight {
+        let _ = write!(out, "\n    + WEIGHT {weight}");
+    }
+    if let Some(original) = &net.original_net {
+        let _ = write!(out, "\n    + ORIGINAL {original}");
+    }
+    write_wiring(&mut out, &net.routes, true);
+    for rect in &net.rects {
+        let _ = write!(
+            out,
+            "\n    + RECT {} ( {} {} ) ( {} {} )",
+            rect.layer, rect.xl, rect.yl, rect.xh, rect.yh
+        );
+    }
+    out.push_str(" ;\n");
+    out
+}
+
+/// Routes as `+ ROUTED` statements, consecutive routes of the same status
+/// joined with `NEW`; special wiring carries its width and SHAPE
+fn write_wiring(out: &mut String, routes: &[DefRoute], special: bool) {
+    let mut status = "";
+    for route in routes {
+        // The net parser records continuations as NEW
+        let route_status = match route.routing_type.as_str() {
+            "NEW" | "" => {
+                if status.is_empty() {
+                    "ROUTED"
+                } else {
+                    status
+                }
+            }
+            other => other,
+        };
+        if route_status == status {
+            let _ = write!(out, "\n      NEW {}", route.layer);
+        } else {
+            let _ = write!(out, "\n    + {route_status} {}", route.layer);
+            status = route_status;
+        }
+        if special {
+            let _ = write!(out, " {}", route.width);
+            if let Some(shape) = &route.shape {
+                let _ = write!(out, " + SHAPE {shape}");
+            }
+        }
+        if let Some(style) = route.style {
+            let _ = write!(out, " STYLE {style}");
+        }
+        if let Some(mask) = route.mask {
+            let _ = write!(out, " MASK {mask}");
+        }
+
+        // Vias sit on the point they were read at
+        let mut written = vec![false; route.vias.len()];
+        for point in &route.points {
+            match point.ext {
+                Some(ext) => {
+                    let _ = write!(out, " ( {} {} {ext} )", point.x, point.y);
+                }
+                None => {
+                    let _ = write!(out, " ( {} {} )", point.x, point.y);
+                }
+            }
+            for (done, (via, x, y)) in written.iter_mut().zip(&route.vias) {
+                if !*done && *x == point.x && *y == point.y {
+                    let _ = write!(out, " {via}");
+                    *done = true;
+                }
+            }
+        }
+        for (_, (via, x, y)) in written.iter().zip(&route.vias).filter(|(done, _)| !**done) {
+            let _ = write!(out, "\n      NEW {} ( {x} {y} ) {via}", route.layer);
+        }
+    }
+}
+
+/// COMPONENTS statement of one component, terminated by `;`
+pub fn write_component(component: &DefComponent) -> String {
+    let mut out = format!("- {} {}", component.name, component.macro_name);
+    if let Some(eeq) = &component.eeq {
+        let _ = write!(out, "\n    + EEQMASTER {eeq}");
+    }
+    if let Some(generate) = &component.generate {
+        let _ = write!(out, "\n    + GENERATE {generate}");
+    }
+    if let Some(source) = &component.source {
+        let _ = write!(out, "\n    + SOURCE {source}");
+    }
+    if let Some(placement) = &component.placement {
+        if placement.placement_type == "UNPLACED" {
+            out.push_str("\n    + UNPLACED");
+        } else {
+            let _ = write!(
+                out,
+                "\n    + {} ( {} {} ) {}",
+                placement.placement_type, placement.x, placement.y, placement.orientation
+            );
+        }
+    }
+    if let Some((left, bottom, right, top)) = component.routing_halo {
+        let _ = write!(out, "\n    + HALO {left} {bottom} {right} {top}");
+    }
+    if let Some(weight) = component.weight {
+        let _ = write!(out, "\n    + WEIGHT {weight}");
+    }
+    for (name, value) in &component.properties {
+        let _ = write!(out, "\n    + PROPERTY {name} {value}");
+    }
+    out.push_str(" ;\n");
+    out
+}
+
+/// PINS statement of one pin with its geometry, terminated by `;`
+pub fn write_pin(pin: &DefPin) -> String {
+    let mut out = format!("- {}", pin.name);
+    if !pin.net.is_empty() {
+        let _ = write!(out, " + NET {}", pin.net);
+    }
+    if !pin.direction.is_empty() {
+        let _ = write!(out, "\n    + DIRECTION {}", pin.direction);
+    }
+    if !pin.use_type.is_empty() {
+        let _ = write!(out, "\n    + USE {}", pin.use_type);
+    }
+
+    let placement = |status: &str, x: f64, y: f64, orient: &str| {
+        (!orient.is_empty()).then(|| format!("{status} ( {x} {y} ) {orient}"))
+    };
+    if pin.ports.is_empty() {
+        write_layers(&mut out, &pin.rects, "    ");
+        if let Some(placement) = placement(&pin.status, pin.x, pin.y, &pin.orient) {
+            let _ = write!(out, "\n    + {placement}");
+        }
+    } else {
+        for port in &pin.ports {
+            out.push_str("\n    + PORT");
+            write_layers(&mut out, &port.rects, "      ");
+            for (via, x, y) in &port.vias {
+                let _ = write!(out, "\n      + VIA {via} ( {x} {y} )");
+            }
+            // Ports without a placement of their own use the pin placement
+            if let Some(placement) = placement(&port.status, port.x, port.y, &port.orient)
+                .or_else(|| placement(&pin.status, pin.x, pin.y, &pin.orient))
+            {
+                let _ = write!(out, "\n      + {placement}");
+            }
+        }
+    }
+    out.push_str(" ;\n");
+    out
+}
+
+fn write_layers(out: &mut String, rects: &[DefRect], indent: &str) {
+    for rect in rects {
+        let _ = write!(
+            out,
+            "\n{indent}+ LAYER {} ( {} {} ) ( {} {} )",
+            rect.layer, rect.xl, rect.yl, rect.xh, rect.yh
+        );
+    }
+}
@@ -0,0 +1,271 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Cell vs Instance Dual Pane
+//!
+//! Shows a LEF macro as defined in the library next to one of its placed
+//! DEF instances with the instance orientation applied. Both panes share
+//! zoom and pan, so what is mirrored or rotated in context is easy to see.
+
+use crate::def::{Def, DefComponent};
+use crate::identifier;
+use crate::lef::LefMacro;
+use crate::macro_thumbnail;
+use eframe::egui;
+
+/// Space around the macro outline when fitting, as a fraction of the pane
+const FIT_MARGIN: f32 = 0.15;
+/// Entries listed in the instance picker
+const MAX_PICKER_ENTRIES: usize = 1000;
+
+/// Dual pane state and rendering
+#[derive(Default)]
+pub struct DualPaneView {
+    /// Whether the view is currently shown
+    pub visible: bool,
+    /// DEF component shown on the right, its macro is shown on the left
+    instance: Option<String>,
+    /// Shared zoom in pixels per micron, None to fit on the next frame
+    zoom: Option<f32>,
+    /// Shared pan in pixels
+    pan: egui::Vec2,
+    /// Last inspected component seen by [`Self::follow_inspected`]
+    last_inspected: Option<String>,
+}
+
+impl DualPaneView {
+    /// Create a new dual pane view
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Switch to the inspected component whenever a new one is inspected
+    pub fn follow_inspected(&mut self, inspected: Option<&str>) {
+        if inspected != self.last_inspected.as_deref() {
+            self.last_inspected = inspected.map(str::to_string);
+            if let Some(name) = inspected {
+                self.set_instance(name.to_string());
+            }
+        }
+    }
+
+    fn set_instance(&mut self, instance: String) {
+        if self.instance.as_deref() != Some(instance.as_str()) {
+            self.instance = Some(instance);
+            self.zoom = None;
+            self.pan = egui::Vec2::ZERO;
+        }
+    }
+
+    /// Render the view
+    ///
+    /// `find_macro` looks up a loaded LEF macro by name, `layer_color` gives
+    /// the canvas color of a layer key such as "M1.PIN".
+    pub fn render<'a>(
+        &mut self,
+        ctx: &egui::Context,
+        def: Option<&Def>,
+        find_macro: impl Fn(&str) -> Option<&'a LefMacro>,
+        layer_color: impl Fn(&str) -> egui::Color32,
+    ) {
+        if !self.visible {
+            return;
+        }
+
+        let mut open = true;
+        egui::Window::new("Cell vs Instance")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(720.0)
+            .default_height(420.0)
+            .show(ctx, |ui| {
+                let Some(def) = def else {
+                    ui.label("Load a DEF file to compare placed instances with the library.");
+                    return;
+                };
+
+                let component = self
+                    .instance
+                    .as_deref()
+                    .and_then(|name| def.components.iter().find(|c| c.name == name));
+                self.instance_picker(ui, def, component);
+
+                let Some(component) = component else {
+                    ui.label("Choose a placed component, or inspect one from the component list.");
+                    return;
+                };
+                let Some(macro_def) = find_macro(&component.macro_name) else {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(244, 67, 54),
+                        format!(
+                            "Macro {} is not in any loaded LEF file",
+                            component.macro_name
+                        ),
+                    );
+                    return;
+                };
+                let orientation = component
+                    .placement
+                    .as_ref()
+                    .map(|p| p.orientation.as_str())
+                    .unwrap_or("N");
+
+                ui.horizontal(|ui| {
+                    if ui.button("Fit").clicked() {
+                        self.zoom = None;
+                        self.pan = egui::Vec2::ZERO;
+                    }
+                    ui.label("Scroll to zoom and drag to pan, both panes follow.");
+                });
+                ui.separator();
+
+                let pane_size = egui::vec2(
+                    ((ui.available_width() - ui.spacing().item_spacing.x) / 2.0).max(100.0),
+                    (ui.available_height() - 24.0).max(100.0),
+                );
+                if self.zoom.is_none() {
+                    self.zoom = Some(fit_zoom(macro_def, pane_size));
+                }
+
+                ui.horizontal(|ui| {
+                    ui.vertical(|ui| {
+                        ui.strong(format!("Library: {} (N)", macro_def.name));
+                        self.pane(ui, pane_size, macro_def, "N", &layer_color);
+                    });
+                    ui.vertical(|ui| {
+                        ui.strong(format!(
+                            "Instance: {} ({orientation})",
+                            identifier::display_name(&component.name, def.bus_bit_chars)
+                        ));
+                        self.pane(ui, pane_size, macro_def, orientation, &layer_color);
+                    });
+                });
+            });
+
+        if !open {
+            self.visible = false;
+        }
+    }
+
+    /// Combo box of placed components, limited to the current macro once one
+    /// is shown; other macros are reached through the component inspector
+    fn instance_picker(&mut self, ui: &mut egui::Ui, def: &Def, current: Option<&DefComponent>) {
+        let selected_text = current
+            .map(|c| {
+                format!(
+                    "{} ({})",
+                    identifier::display_name(&c.name, def.bus_bit_chars),
+                    c.macro_name
+                )
+            })
+            .unwrap_or_else(|| "Select instance".to_string());
+
+        let mut picked = None;
+        ui.horizontal(|ui| {
+            ui.label("Instance:");
+            egui::ComboBox::from_id_salt("dual_pane_instance")
+                .selected_text(selected_text)
+                .width(320.0)
+                .show_ui(ui, |ui| {
+                    let candidates = def.components.iter().filter(|c| {
+                        c.placement.is_some()
+                            && current.is_none_or(|current| c.macro_name == current.macro_name)
+                    });
+                    for component in candidates.take(MAX_PICKER_ENTRIES) {
+                        let label = format!(
+                            "{} ({})",
+                            identifier::display_name(&component.name, def.bus_bit_chars),
+                            component.macro_name
+                        );
+                        let is_current = current.is_some_and(|c| c.name == component.name);
+                        if ui.selectable_label(is_current, label).clicked() {
+                            picked = Some(component.name.clone());
+                        }
+                    }
+                });
+
+            // Step through the other instances of the same macro
+            if let Some(current) = current {
+                let siblings: Vec<&DefComponent> = def
+                    .components
+                    .iter()
+                    .filter(|c| c.macro_name == current.macro_name && c.placement.is_some())
+                    .collect();
+                let index = siblings.iter().position(|c| c.name == current.name);
+                if let Some(index) = index {
+                    if ui.add_enabled(index > 0, egui::Button::new("<")).clicked() {
+                        picked = Some(siblings[index - 1].name.clone());
+                    }
+                    ui.label(format!("{}/{}", index + 1, siblings.len()));
+                    if ui
+                        .add_enabled(index + 1 < siblings.len(), egui::Button::new(">"))
+                        .clicked()
+                    {
+                        picked = Some(siblings[index + 1].name.clone());
+                    }
+                }
+            }
+        });
+
+        if let Some(name) = picked {
+            // Keep the zoom when stepping between instances of one macro
+            let zoom = self.zoom;
+            let same_macro = current.is_some_and(|current| {
+                def.components
+                    .iter()
+                    .any(|c| c.name == name && c.macro_name == current.macro_name)
+            });
+            self.set_instance(name);
+            if same_macro {
+                self.zoom = zoom;
+            }
+        }
+    }
+
+    /// Paint one pane of the macro with the given orientation
+    fn pane(
+        &mut self,
+        ui: &mut egui::Ui,
+        size: egui::Vec2,
+        macro_def: &LefMacro,
+        orientation: &str,
+        layer_color: &impl Fn(&str) -> egui::Color32,
+    ) {
+        let (response, painter) = ui.allocate_painter(size, egui::Sense::drag());
+        let rect = response.rect;
+        painter.rect_filled(rect, 0.0, egui::Color32::from_gray(20));
+
+        // Shared zoom and pan
+        if response.dragged() {
+            self.pan += response.drag_delta();
+        }
+        if response.hovered() {
+            let scroll = ui.input(|i| i.smooth_scroll_delta.y);
+            if scroll != 0.0 {
+                let factor = (scroll * 0.005).exp();
+                self.zoom = self.zoom.map(|z| (z * factor).clamp(0.01, 10000.0));
+            }
+        }
+        let zoom = self.zoom.unwrap_or(1.0);
+
+        macro_thumbnail::paint_macro(
+            &painter.with_clip_rect(rect),
+            rect.center() + self.pan,
+            zoom,
+            macro_def,
+            orientation,
+            layer_color,
+            true,
+        );
+    }
+}
+
+/// Zoom that fits the macro in a pane in any orientation
+fn fit_zoom(macro_def: &LefMacro, pane: egui::Vec2) -> f32 {
+    // Rotated orientations swap width and height, fit the larger side
+    let extent = macro_def.size_x.max(macro_def.size_y) as f32;
+    if extent <= 0.0 {
+        return 1.0;
+    }
+    pane.x.min(pane.y) * (1.0 - FIT_MARGIN) / extent
+}
@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Uncommitted Edits Panel
+//!
+//! Lists the cell swaps and moves made in DEF edit mode, so they can be
+//! reviewed before exporting them as an ECO or saving the DEF. Clicking an
+//! instance selects and inspects it, and each edit can be reverted.
+
+use crate::def::eco::EcoLog;
+use eframe::egui;
+use std::collections::HashSet;
+
+/// Rows shown before the rest is summarized
+const MAX_LISTED: usize = 500;
+
+/// What the user asked for in the panel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditAction {
+    /// Undo the swap and move of an instance
+    Revert(String),
+    RevertAll,
+    Export,
+}
+
+/// Uncommitted edits panel state and rendering
+#[derive(Default)]
+pub struct EditsPanel {
+    /// Whether the panel is currently shown
+    pub visible: bool,
+}
+
+impl EditsPanel {
+    /// Create a new edits panel
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render the panel, with moves shown in microns at `db_units` per micron
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        eco_log: &EcoLog,
+        db_units: f64,
+        selected_components: &mut HashSet<String>,
+        inspected_component: &mut Option<String>,
+    ) -> Option<EditAction> {
+        if !self.visible {
+            return None;
+        }
+
+        let mut action = None;
+        let mut open = true;
+        egui::Window::new("Uncommitted Edits")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(360.0)
+            .default_height(320.0)
+            .show(ctx, |ui| {
+                if eco_log.is_empty() {
+                    ui.label("Swap or drag components in DEF edit mode to edit the design.");
+                    return;
+                }
+                ui.label(format!(
+                    "{} swapped and {} moved instances",
+                    eco_log.swaps().len(),
+                    eco_log.moves().len()
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button("Export...").clicked() {
+                        action = Some(EditAction::Export);
+                    }
+                    if ui.button("Revert All").clicked() {
+                        action = Some(EditAction::RevertAll);
+                    }
+                });
+                ui.separator();
+
+                let instances = eco_log.instances();
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        egui::Grid::new("uncommitted_edits_grid")
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for instance in instances.iter().take(MAX_LISTED) {
+                                    if ui.link(*instance).clicked() {
+                                        selected_components.insert(instance.to_string());
+                                        *inspected_component = Some(instance.to_string());
+                                    }
+                                    ui.monospace(describe_edit(eco_log, instance, db_units));
+                                    if ui.small_button("Revert").clicked() {
+                                        action = Some(EditAction::Revert(instance.to_string()));
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                        if instances.len() > MAX_LISTED {
+                            ui.label(format!("... and {} more", instances.len() - MAX_LISTED));
+                        }
+                    });
+            });
+
+        if !open {
+            self.visible = false;
+        }
+        action
+    }
+}
+
+/// Swap and displacement of `instance`, e.g. `INVX1 -> INVX2, dx 1.200 dy 0.000`
+fn describe_edit(eco_log: &EcoLog, instance: &str, db_units: f64) -> String {
+    let swap = eco_log
+        .swaps()
+        .iter()
+        .find(|s| s.instance == instance)
+        .map(|s| format!("{} -> {}", s.from, s.to));
+    let cell_move = eco_log
+        .moves()
+        .iter()
+        .find(|m| m.instance == instance)
+        .map(|m| {
+            let (dx, dy) = m.delta();
+            format!("dx {:.3} dy {:.3}", dx / db_units, dy / db_units)
+        });
+    [swap, cell_move]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -2,12 +2,17 @@
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
 use csv::Writer;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeSet, HashMap};
 use std::fs::File;
 use std::io::Write;
 
+use crate::def::layer_usage::{net_layer_usage, NetLayerUsage};
+use crate::def::{Def, DefComponent, DefPin};
+use crate::identifier;
+use crate::lef::diff::LefDiff;
 use crate::lef::{Lef, LefMacro, LefPin};
+use crate::pin_lint::Violation;
 
 /// Voltage configuration for Liberty export
 #[derive(Debug, Clone)]
@@ -24,11 +29,37 @@ pub struct VoltageConfig {
     // Pin-specific analog and pad configuration
     pub pin_is_analog: std::collections::BTreeMap<String, bool>, // pin_name -> is_analog
     pub pin_is_pad: std::collections::BTreeMap<String, bool>,    // pin_name -> is_pad
+    // Electrical defaults of the signal pins
+    pub input_capacitance: f32, // capacitance of the signal pins in pF
+    pub max_transition: Option<f32>, // max_transition of the output pins in ns
+    pub max_capacitance: Option<f32>, // max_capacitance of the output pins in pF
+    pub timing_arc: Option<TimingArcTemplate>, // timing arc from the inputs to each output pin
     // UI state for pin selection
     pub selected_pins: std::collections::BTreeSet<String>, // selected pins for batch operations
     pub pin_filter: String,                                // search filter for pins
 }
 
+/// Scalar timing arc written from all inputs of a cell to each output pin
+///
+/// The delays are the same for every arc and load, which is enough for
+/// early synthesis trials to see a cost on each path through the cell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimingArcTemplate {
+    /// cell_rise and cell_fall in ns
+    pub delay: f32,
+    /// rise_transition and fall_transition in ns
+    pub transition: f32,
+}
+
+impl Default for TimingArcTemplate {
+    fn default() -> Self {
+        Self {
+            delay: 0.1,
+            transition: 0.05,
+        }
+    }
+}
+
 impl Default for VoltageConfig {
     fn default() -> Self {
         Self {
@@ -42,6 +73,10 @@ impl Default for VoltageConfig {
             pin_related_ground: std::collections::BTreeMap::new(),
             pin_is_analog: std::collections::BTreeMap::new(),
             pin_is_pad: std::collections::BTreeMap::new(),
+            input_capacitance: 0.02,
+            max_transition: None,
+            max_capacitance: None,
+            timing_arc: None,
             selected_pins: std::collections::BTreeSet::new(),
             pin_filter: String::new(),
         }
@@ -74,38 +109,24 @@ pub struct MacroCsvRecord {
     pub area: f64,
     #[serde(rename = "Pinlist")]
     pub pinlist: String,
+    /// DENSITY statistics, blank for macros without DENSITY windows
+    #[serde(rename = "Density Windows")]
+    pub density_windows: usize,
+    #[serde(rename = "Min Density (%)")]
+    pub density_min: Option<String>,
+    #[serde(rename = "Max Density (%)")]
+    pub density_max: Option<String>,
+    #[serde(rename = "Avg Density (%)")]
+    pub density_avg: Option<String>,
 }
 
 /// Extract bus information from pin name
 /// Returns Some((base_name, index)) if pin follows bus pattern, None otherwise
 fn extract_bus_info(pin_name: &str) -> Option<(String, usize)> {
-    // Try pattern with square brackets: base_name[index]
-    if let Some(bracket_start) = pin_name.rfind('[') {
-        if let Some(bracket_end) = pin_name.rfind(']') {
-            if bracket_start < bracket_end {
-                let base_name = pin_name[..bracket_start].to_string();
-                let index_str = &pin_name[bracket_start + 1..bracket_end];
-                if let Ok(index) = index_str.parse::<usize>() {
-                    return Some((base_name, index));
-                }
-            }
-        }
-    }
-
-    // Try pattern with angle brackets: base_name<index>
-    if let Some(angle_start) = pin_name.rfind('<') {
-        if let Some(angle_end) = pin_name.rfind('>') {
-            if angle_start < angle_end {
-                let base_name = pin_name[..angle_start].to_string();
-                let index_str = &pin_name[angle_start + 1..angle_end];
-                if let Ok(index) = index_str.parse::<usize>() {
-                    return Some((base_name, index));
-                }
-            }
-        }
-    }
-
-    None
+    // Accepts both base_name[index] and base_name<index>; escaped
+    // delimiters such as reg\[3\] are part of the name, not a bus bit
+    identifier::split_any_bus_bit(pin_name)
+        .map(|(base_name, index)| (identifier::unescape(base_name).into_owned(), index))
 }
 
 /// Group pins by bus base name and validate bus constraints
@@ -190,7 +211,7 @@ fn compress_bus_group(pins: &[&LefPin]) -> PinCsvRecord {
         // Single pin
         let pin = pins[0];
         return PinCsvRecord {
-            name: clean_pin_name(&pin.name),
+            name: identifier::unescape(&clean_pin_name(&pin.name)).into_owned(),
             direction: pin.direction.clone(),
             pin_type: pin.use_type.clone(),
             width: 1,
@@ -245,6 +266,7 @@ fn format_pinlist_compressed(pins: &[LefPin]) -> String {
 
 /// Convert a LefMacro to a MacroCsvRecord
 fn macro_to_csv_record(macro_def: &LefMacro) -> MacroCsvRecord {
+    let density = macro_def.density_stats();
     MacroCsvRecord {
         macro_name: macro_def.name.clone(),
         class: macro_def.class.clone(),
@@ -252,6 +274,10 @@ fn macro_to_csv_record(macro_def: &LefMacro) -> MacroCsvRecord {
         pins: macro_def.pins.len(),
         area: macro_def.size_x * macro_def.size_y,
         pinlist: format_pinlist_compressed(&macro_def.pins),
+        density_windows: density.map_or(0, |d| d.windows),
+        density_min: density.map(|d| format!("{:.2}", d.min)),
+        density_max: density.map(|d| format!("{:.2}", d.max)),
+        density_avg: density.map(|d| format!("{:.2}", d.mean)),
     }
 }
 
@@ -293,6 +319,352 @@ pub fn export_multiple_cells_pinlist(
     Ok(())
 }
 
+/// Export pin naming violations to CSV file
+pub fn export_pin_lint_to_csv(
+    violations: &[Violation],
+    file_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let file = File::create(file_path)?;
+    let mut writer = Writer::from_writer(file);
+
+    writer.write_record(["File", "Macro", "Pin", "Class", "Rule"])?;
+    for violation in violations {
+        writer.write_record([
+            &violation.file,
+            &violation.macro_name,
+            &violation.pin_name,
+            &violation.class,
+            &violation.pattern,
+        ])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Export a LEF library diff to CSV file, one row per added or removed
+/// macro and per change of a changed macro
+pub fn export_lef_diff_to_csv(
+    diff: &LefDiff,
+    file_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let file = File::create(file_path)?;
+    let mut writer = Writer::from_writer(file);
+
+    writer.write_record(["Macro", "Status", "Change", "Object", "Old", "New"])?;
+    for name in &diff.added {
+        writer.write_record([name, "added", "", "", "", ""])?;
+    }
+    for name in &diff.removed {
+        writer.write_record([name, "removed", "", "", "", ""])?;
+    }
+    for macro_diff in &diff.changed {
+        for change in &macro_diff.changes {
+            let (old, new) = change.values();
+            writer.write_record([
+                macro_diff.name.as_str(),
+                "changed",
+                change.kind(),
+                &change.object(),
+                &old,
+                &new,
+            ])?;
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Text label as drawn on the canvas, placed in design coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlacedLabel {
+    pub text: String,
+    /// Anchor point in microns
+    pub x: f64,
+    pub y: f64,
+    /// Counterclockwise rotation in degrees
+    pub rotation: f64,
+    /// Which point of the text sits at the anchor, e.g. "CENTER_CENTER"
+    pub anchor: String,
+    /// Font size in points
+    pub size: f32,
+}
+
+/// Export text labels to CSV file, one row per label in drawing order
+pub fn export_labels_to_csv(
+    labels: &[PlacedLabel],
+    file_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let file = File::create(file_path)?;
+    let mut writer = Writer::from_writer(file);
+
+    writer.write_record([
+        "Text",
+        "X (um)",
+        "Y (um)",
+        "Rotation (deg)",
+        "Anchor",
+        "Size (pt)",
+    ])?;
+    for label in labels {
+        writer.write_record([
+            label.text.clone(),
+            format!("{:.3}", label.x),
+            format!("{:.3}", label.y),
+            format!("{:.1}", label.rotation),
+            label.anchor.clone(),
+            format!("{:.1}", label.size),
+        ])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Decimals that write every DEF coordinate of `db_units` per micron exactly
+///
+/// At least 3, so the usual 1000 units keep the `1.500` format. Units that
+/// do not divide a power of ten fall back to 9 decimals.
+fn micron_decimals(db_units: f64) -> usize {
+    (3..=9)
+        .find(|&decimals| (10f64.powi(decimals as i32) / db_units).fract() == 0.0)
+        .unwrap_or(9)
+}
+
+/// Export the DEF pin table to CSV file, positions in microns
+pub fn export_def_pins_to_csv(
+    pins: &[DefPin],
+    db_units: f64,
+    file_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let file = File::create(file_path)?;
+    let mut writer = Writer::from_writer(file);
+
+    writer.write_record([
+        "Pin",
+        "Net",
+        "Direction",
+        "Use",
+        "Status",
+        "X (um)",
+        "Y (um)",
+        "Orient",
+    ])?;
+    let decimals = micron_decimals(db_units);
+    for pin in pins {
+        writer.write_record([
+            pin.name.clone(),
+            pin.net.clone(),
+            pin.direction.clone(),
+            pin.use_type.clone(),
+            pin.placement_status().to_string(),
+            format!("{:.decimals$}", pin.x / db_units),
+            format!("{:.decimals$}", pin.y / db_units),
+            pin.orient.clone(),
+        ])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Export the DEF component placement to CSV file, locations in microns
+///
+/// The locations have as many decimals as the database units need, so no
+/// DEF coordinate is rounded. Components without a placement are written with an UNPLACED status and
+/// empty location and orientation.
+pub fn export_def_placement_to_csv(
+    components: &[DefComponent],
+    db_units: f64,
+    file_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let file = File::create(file_path)?;
+    let mut writer = Writer::from_writer(file);
+
+    writer.write_record(["Instance", "Macro", "X (um)", "Y (um)", "Orient", "Status"])?;
+    let decimals = micron_decimals(db_units);
+    for component in components {
+        let (x, y, orient, status) = match &component.placement {
+            Some(placement) => (
+                format!("{:.decimals$}", placement.x / db_units),
+                format!("{:.decimals$}", placement.y / db_units),
+                placement.orientation.clone(),
+                placement.placement_type.clone(),
+            ),
+            None => (
+                String::new(),
+                String::new(),
+                String::new(),
+                "UNPLACED".to_string(),
+            ),
+        };
+        writer.write_record([
+            component.name.clone(),
+            component.macro_name.clone(),
+            x,
+            y,
+            orient,
+            status,
+        ])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Export per-net routing layer usage to CSV file
+///
+/// One row per net with the length in microns on every layer used by any
+/// net, layers in `layer_order` first.
+pub fn export_net_layer_usage_to_csv(
+    usages: &[NetLayerUsage],
+    layer_order: &[String],
+    file_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut layers: Vec<&str> = layer_order.iter().map(String::as_str).collect();
+    for usage in usages {
+        for (layer, _) in &usage.layers {
+            if !layers.contains(&layer.as_str()) {
+                layers.push(layer);
+            }
+        }
+    }
+    layers.retain(|layer| usages.iter().any(|u| u.length_on(layer) > 0.0));
+
+    let file = File::create(file_path)?;
+    let mut writer = Writer::from_writer(file);
+
+    let mut header = vec!["Net".to_string()];
+    header.extend(layers.iter().map(|layer| format!("{layer} (um)")));
+    header.extend(["Total (um)".to_string(), "Vias".to_string()]);
+    writer.write_record(&header)?;
+    for usage in usages {
+        let mut record = vec![usage.net.clone()];
+        record.extend(
+            layers
+                .iter()
+                .map(|layer| format!("{:.3}", usage.length_on(layer))),
+        );
+        record.push(format!("{:.3}", usage.total_length()));
+        record.push(usage.vias.to_string());
+        writer.write_record(&record)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Version of the netlist JSON written by [`export_def_netlist_json`]
+pub const NETLIST_JSON_VERSION: u32 = 1;
+
+/// Netlist JSON document of one design
+#[derive(Debug, Serialize)]
+pub struct NetlistJson<'a> {
+    pub version: u32,
+    pub design: &'a str,
+    /// Database units per micron the design was read with
+    pub db_units: f64,
+    pub nets: Vec<NetlistJsonNet<'a>>,
+}
+
+/// Net of the netlist JSON with its connections and routing statistics
+#[derive(Debug, Serialize)]
+pub struct NetlistJsonNet<'a> {
+    pub name: &'a str,
+    #[serde(rename = "use", skip_serializing_if = "str::is_empty")]
+    pub use_type: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    pub pattern: &'a str,
+    pub shielded: bool,
+    /// `( instance pin )` connections, instance `PIN` for I/O pins
+    pub connections: Vec<NetlistJsonConnection<'a>>,
+    pub routing: NetlistJsonRouting,
+}
+
+/// One `( instance pin )` connection of a net
+#[derive(Debug, Serialize)]
+pub struct NetlistJsonConnection<'a> {
+    pub instance: &'a str,
+    pub pin: &'a str,
+}
+
+/// Routed length and vias of a net, lengths in microns
+#[derive(Debug, Serialize)]
+pub struct NetlistJsonRouting {
+    pub length: f64,
+    pub vias: usize,
+    /// Length per routing layer, in `layer_order` first
+    pub layers: Vec<NetlistJsonLayer>,
+}
+
+/// Routed length of a net on one layer, in microns
+#[derive(Debug, Serialize)]
+pub struct NetlistJsonLayer {
+    pub layer: String,
+    pub length: f64,
+}
+
+/// Netlist JSON of the nets of `def`, see [`export_def_netlist_json`]
+pub fn def_netlist_json<'a>(
+    def: &'a Def,
+    db_units: f64,
+    layer_order: &[String],
+) -> NetlistJson<'a> {
+    let nets = def
+        .nets
+        .iter()
+        .map(|net| {
+            let usage = net_layer_usage(net, db_units, layer_order);
+            NetlistJsonNet {
+                name: &net.name,
+                use_type: &net.use_type,
+                pattern: &net.pattern,
+                shielded: net.shielded,
+                connections: net
+                    .instances
+                    .iter()
+                    .zip(&net.instance_pins)
+                    .map(|(instance, pin)| NetlistJsonConnection { instance, pin })
+                    .collect(),
+                routing: NetlistJsonRouting {
+                    length: usage.total_length(),
+                    vias: usage.vias,
+                    layers: usage
+                        .layers
+                        .into_iter()
+                        .map(|(layer, length)| NetlistJsonLayer { layer, length })
+                        .collect(),
+                },
+            }
+        })
+        .collect();
+    NetlistJson {
+        version: NETLIST_JSON_VERSION,
+        design: &def.design,
+        db_units,
+        nets,
+    }
+}
+
+/// Export the nets of a DEF with their connections and routing statistics
+/// to a JSON file
+///
+/// The document holds the design name and a `nets` array; every net lists
+/// its `( instance pin )` connections and its routed length and vias, in
+/// total and per layer in `layer_order` first. `version` is increased when
+/// fields change meaning.
+pub fn export_def_netlist_json(
+    def: &Def,
+    db_units: f64,
+    layer_order: &[String],
+    file_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let json = serde_json::to_string_pretty(&def_netlist_json(def, db_units, layer_order))?;
+    std::fs::write(file_path, json + "\n")?;
+    Ok(())
+}
+
 /// Export LEF data to CSV file
 pub fn export_lef_to_csv(
     lef_data: &Lef,
@@ -364,6 +736,26 @@ fn sort_pins_by_type(pins: &mut [LefPin]) {
     pins.sort_by_key(|pin| (get_pin_sort_priority(pin), pin.name.clone()));
 }
 
+/// Verilog port name of a pin, an escaped identifier unless it is a simple
+/// one
+///
+/// Escaped identifiers end at white space, so the space before the comma
+/// is part of the name; bus bits kept flat become e.g. `\DATA[0] `.
+fn verilog_identifier(name: &str) -> String {
+    let simple = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if simple {
+        name.to_string()
+    } else {
+        format!("\\{name} ")
+    }
+}
+
 /// Generate Verilog signal pin declaration with comma placement before comment
 fn generate_verilog_signal_port_declaration(
     pin_group: &[&LefPin],
@@ -381,9 +773,10 @@ fn generate_verilog_signal_port_declaration(
         };
 
         let clean_name = clean_pin_name(&pin.name);
+        let port_name = verilog_identifier(&identifier::unescape(&clean_name));
         let prefix = if add_comma_prefix { "," } else { "" };
         let suffix = if add_comma_suffix { "," } else { "" };
-        format!("    {prefix}{direction} {clean_name}{suffix}       /**< {clean_name} */")
+        format!("    {prefix}{direction} {port_name}{suffix}       /**< {clean_name} */")
     } else {
         // Bus pin - use existing compression logic
         let record = compress_bus_group(pin_group);
@@ -428,9 +821,10 @@ fn generate_verilog_power_port_declaration(
         };
 
         let clean_name = clean_pin_name(&pin.name);
+        let port_name = verilog_identifier(&identifier::unescape(&clean_name));
         let prefix = if add_comma_prefix { "," } else { "" };
         let suffix = if add_comma_suffix { "," } else { "" };
-        format!("    {prefix}{direction} {clean_name}{suffix}       /**< {clean_name} */")
+        format!("    {prefix}{direction} {port_name}{suffix}       /**< {clean_name} */")
     } else {
         // Bus pin - use existing compression logic
         let record = compress_bus_group(pin_group);
@@ -568,6 +962,55 @@ fn collect_used_pg_pins(
     used_pg_pins
 }
 
+/// Names of the input signal pins of a cell, the related pins of its
+/// timing arcs
+fn lib_input_pin_names(pins: &[LefPin]) -> Vec<String> {
+    pins.iter()
+        .filter(|pin| !is_power_pin(pin) && pin.direction.eq_ignore_ascii_case("INPUT"))
+        .map(|pin| clean_pin_name(&pin.name))
+        .collect()
+}
+
+/// Design rule limits and timing arc of an output pin, empty for inputs
+///
+/// `input_pins` are the related pins of the timing arc, no arc is written
+/// for cells without inputs.
+fn lib_output_pin_attributes(
+    direction: &str,
+    voltage_config: &VoltageConfig,
+    input_pins: &[String],
+    indent: &str,
+) -> String {
+    let mut attributes = String::new();
+    if direction != "output" && direction != "inout" {
+        return attributes;
+    }
+    if let Some(max_transition) = voltage_config.max_transition {
+        attributes.push_str(&format!("{indent}max_transition : {max_transition} ;\n"));
+    }
+    if let Some(max_capacitance) = voltage_config.max_capacitance {
+        attributes.push_str(&format!("{indent}max_capacitance : {max_capacitance} ;\n"));
+    }
+    if let Some(arc) = voltage_config.timing_arc.filter(|_| direction == "output") {
+        if !input_pins.is_empty() {
+            attributes.push_str(&format!(
+                "{indent}timing () {{\n\
+                 {indent}    related_pin : \"{}\" ;\n\
+                 {indent}    timing_sense : non_unate ;\n\
+                 {indent}    cell_rise (scalar) {{ values (\"{delay}\") ; }}\n\
+                 {indent}    cell_fall (scalar) {{ values (\"{delay}\") ; }}\n\
+                 {indent}    rise_transition (scalar) {{ values (\"{transition}\") ; }}\n\
+                 {indent}    fall_transition (scalar) {{ values (\"{transition}\") ; }}\n\
+                 {indent}}}\n",
+                input_pins.join(" "),
+                delay = arc.delay,
+                transition = arc.transition,
+            ));
+        }
+    }
+    attributes
+}
+
 /// Generate Liberty pin definition for a pin group with voltage configuration
 fn generate_lib_pin_definition_with_config(
     pin_group: &[&LefPin],
@@ -575,6 +1018,7 @@ fn generate_lib_pin_definition_with_config(
     treat_power_as_signal: bool,
     used_pg_pins: &std::collections::HashSet<String>,
     existing_pins: &std::collections::HashSet<String>,
+    input_pins: &[String],
 ) -> String {
     if pin_group.len() == 1 {
         // Single pin
@@ -639,7 +1083,8 @@ fn generate_lib_pin_definition_with_config(
                         }
                     });
                 let mut pin_def = format!(
-                    "   pin({clean_name})  {{\n           direction : {direction};\n           capacitance : 0.02;\n"
+                    "   pin({clean_name})  {{\n           direction : {direction};\n           capacitance : {};\n",
+                    voltage_config.input_capacitance
                 );
                 // Only add related_power_pin if the referenced pin actually exists in this cell
                 if let Some(power) = related_power {
@@ -671,6 +1116,12 @@ fn generate_lib_pin_definition_with_config(
                 {
                     pin_def.push_str("           is_pad : true ;\n");
                 }
+                pin_def.push_str(&lib_output_pin_attributes(
+                    &direction,
+                    voltage_config,
+                    input_pins,
+                    "           ",
+                ));
                 pin_def.push_str("   }\n");
                 pin_def
             }
@@ -782,7 +1233,8 @@ fn generate_lib_pin_definition_with_config(
             // Generate individual pin definitions
             for i in 0..record.width {
                 let mut pin_def = format!(
-                    "        pin ({base_name}[{i}]) {{\n        direction      : {direction};\n        capacitance    : 0.02;\n"
+                    "        pin ({base_name}[{i}]) {{\n        direction      : {direction};\n        capacitance    : {};\n",
+                    voltage_config.input_capacitance
                 );
 
                 // Add is_analog attribute if set (for bus pins, check the base name)
@@ -803,6 +1255,12 @@ fn generate_lib_pin_definition_with_config(
                 {
                     pin_def.push_str("        is_pad : true ;\n");
                 }
+                pin_def.push_str(&lib_output_pin_attributes(
+                    &direction,
+                    voltage_config,
+                    input_pins,
+                    "        ",
+                ));
 
                 pin_def.push_str("        }\n\n");
                 result.push_str(&pin_def);
@@ -814,11 +1272,45 @@ fn generate_lib_pin_definition_with_config(
     }
 }
 
-/// Export all LEF cells to Verilog stub file
+/// Options of the Verilog stub export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VerilogStubOptions {
+    /// Declare bus bits such as `DATA[0]`..`DATA[31]` as one vector port
+    /// `DATA[31:0]`, else every bit is a port of its own
+    pub group_buses: bool,
+}
+
+impl Default for VerilogStubOptions {
+    fn default() -> Self {
+        Self { group_buses: true }
+    }
+}
+
+/// Export all LEF cells to Verilog stub file, bus bits grouped
+#[allow(dead_code)]
 pub fn export_verilog_stub(
     lef_data: &Lef,
     file_path: &str,
     basename: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    export_verilog_stub_with_options(
+        lef_data,
+        file_path,
+        basename,
+        &VerilogStubOptions::default(),
+    )
+}
+
+/// Export all LEF cells to Verilog stub file with `options`
+///
+/// Bus bits are only grouped when they are contiguous and agree in
+/// direction and use, others stay single ports.
+pub fn export_verilog_stub_with_options(
+    lef_data: &Lef,
+    file_path: &str,
+    basename: &str,
+    options: &VerilogStubOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut file = File::create(file_path)?;
     let guard_name = format!("DEF_{}", basename.to_uppercase());
@@ -863,7 +1355,11 @@ pub fn export_verilog_stub(
         let mut sorted_pins = macro_def.pins.clone();
         sort_pins_by_type(&mut sorted_pins);
 
-        let groups = group_pins_by_bus(&sorted_pins);
+        let groups = if options.group_buses {
+            group_pins_by_bus(&sorted_pins)
+        } else {
+            sorted_pins.iter().map(|pin| vec![pin]).collect()
+        };
 
         // Separate power pins from signal pins
         let mut signal_groups = Vec::new();
@@ -931,6 +1427,106 @@ pub fn export_verilog_stub(
     Ok(())
 }
 
+/// Options of the SPICE stub export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpiceStubOptions {
+    /// List the POWER and GROUND pins, after the signal pins
+    pub pg_pins: bool,
+}
+
+impl Default for SpiceStubOptions {
+    fn default() -> Self {
+        Self { pg_pins: true }
+    }
+}
+
+/// Longest line of the SPICE stub before it continues with `+`
+const SPICE_LINE_WIDTH: usize = 80;
+
+/// Export all LEF cells to a SPICE stub file of empty `.SUBCKT`s
+///
+/// The pins of each macro are listed in LEF order, signal pins first, then
+/// the POWER and then the GROUND pins, so box cells of a library line up
+/// with the netlists LVS compares them against. A `*.PININFO` comment
+/// gives the pin directions.
+pub fn export_spice_stub(
+    lef_data: &Lef,
+    file_path: &str,
+    basename: &str,
+    options: &SpiceStubOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = File::create(file_path)?;
+    writeln!(file, "* SPICE stub of the LEF cells of {basename}")?;
+    writeln!(
+        file,
+        "* Auto-generated stub file. Generated by lefdef-viewer."
+    )?;
+    writeln!(file)?;
+    for macro_def in &lef_data.macros {
+        writeln!(file, "{}", spice_subckt(macro_def, options))?;
+    }
+    writeln!(file, ".END")?;
+    Ok(())
+}
+
+/// `.SUBCKT` block of a macro, without devices
+fn spice_subckt(macro_def: &LefMacro, options: &SpiceStubOptions) -> String {
+    let mut pins: Vec<&LefPin> = macro_def
+        .pins
+        .iter()
+        .filter(|pin| options.pg_pins || !is_power_pin(pin))
+        .collect();
+    // Stable, so pins of the same kind keep their LEF order
+    pins.sort_by_key(|pin| match pin.use_type.as_str() {
+        "POWER" => 1,
+        "GROUND" => 2,
+        _ => 0,
+    });
+    let names: Vec<String> = pins
+        .iter()
+        .map(|pin| identifier::unescape(&clean_pin_name(&pin.name)).into_owned())
+        .collect();
+    let info: Vec<String> = pins
+        .iter()
+        .zip(&names)
+        .map(|(pin, name)| {
+            let kind = match (pin.use_type.as_str(), pin.direction.as_str()) {
+                ("POWER", _) => "P",
+                ("GROUND", _) => "G",
+                (_, "INPUT") => "I",
+                (_, "OUTPUT") => "O",
+                _ => "B",
+            };
+            format!("{name}:{kind}")
+        })
+        .collect();
+
+    let mut out = spice_wrapped(&format!(".SUBCKT {}", macro_def.name), &names);
+    out.push_str(&spice_wrapped("*.PININFO", &info).replace("\n+", "\n*+"));
+    out.push_str(&format!(".ENDS {}\n", macro_def.name));
+    out
+}
+
+/// `head` and `words` as lines of up to [`SPICE_LINE_WIDTH`], continued
+/// with `+`
+fn spice_wrapped(head: &str, words: &[String]) -> String {
+    let mut out = String::new();
+    let mut line = head.to_string();
+    for word in words {
+        if line.len() + 1 + word.len() > SPICE_LINE_WIDTH && line.len() > 1 {
+            out.push_str(&line);
+            out.push('\n');
+            line = "+".to_string();
+        }
+        line.push(' ');
+        line.push_str(word);
+    }
+    out.push_str(&line);
+    out.push('\n');
+    out
+}
+
 /// Export all LEF cells to Liberty stub file with voltage configuration (old implementation)
 #[allow(dead_code)]
 pub fn export_lib_stub_with_voltage(
@@ -1106,6 +1702,7 @@ pub fn export_lib_stub_with_voltage(
             .iter()
             .map(|pin| clean_pin_name(&pin.name))
             .collect();
+        let input_pins = lib_input_pin_names(&sorted_pins);
 
         let groups = group_pins_by_bus(&sorted_pins);
         for group in groups {
@@ -1115,6 +1712,7 @@ pub fn export_lib_stub_with_voltage(
                 treat_power_as_signal,
                 &used_pg_pins,
                 &existing_pins,
+                &input_pins,
             );
             write!(file, "{pin_def}")?;
         }
@@ -1288,6 +1886,7 @@ pub fn export_lib_stub_with_voltage_config(
             .iter()
             .map(|pin| clean_pin_name(&pin.name))
             .collect();
+        let input_pins = lib_input_pin_names(&sorted_pins);
 
         let groups = group_pins_by_bus(&sorted_pins);
         for group in groups {
@@ -1297,6 +1896,7 @@ pub fn export_lib_stub_with_voltage_config(
                 treat_power_as_signal,
                 &used_pg_pins,
                 &existing_pins,
+                &input_pins,
             );
             write!(file, "{pin_def}")?;
         }
@@ -1317,3 +1917,192 @@ pub fn export_lib_stub(lef_data: &Lef, file_path: &str) -> Result<(), Box<dyn st
     let default_config = VoltageConfig::default();
     export_lib_stub_with_voltage_config(lef_data, file_path, &default_config)
 }
+
+/// Straight RGBA color of an SVG element
+pub type Rgba = [u8; 4];
+
+/// One primitive of a canvas snapshot, in canvas pixels with y pointing down
+#[derive(Debug, Clone, PartialEq)]
+pub enum SvgElement {
+    Polygon {
+        points: Vec<(f32, f32)>,
+        fill: Option<Rgba>,
+        stroke: Option<(Rgba, f32)>,
+    },
+    Polyline {
+        points: Vec<(f32, f32)>,
+        stroke: (Rgba, f32),
+    },
+    Circle {
+        center: (f32, f32),
+        radius: f32,
+        fill: Option<Rgba>,
+        stroke: Option<(Rgba, f32)>,
+    },
+    /// Text anchored at the left end of its baseline, rotated by `angle` radians
+    Text {
+        pos: (f32, f32),
+        text: String,
+        size: f32,
+        color: Rgba,
+        angle: f32,
+    },
+}
+
+/// Snapshot of the canvas to write as SVG
+#[derive(Debug, Clone, Default)]
+pub struct SvgView {
+    pub width: f32,
+    pub height: f32,
+    /// Width and height of the document when it is scaled from the canvas
+    pub output_size: Option<(f32, f32)>,
+    pub background: Option<Rgba>,
+    pub elements: Vec<SvgElement>,
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn svg_paint(attribute: &str, color: Option<Rgba>) -> String {
+    match color {
+        Some([r, g, b, 255]) => format!(" {attribute}=\"#{r:02x}{g:02x}{b:02x}\""),
+        Some([r, g, b, a]) => format!(
+            " {attribute}=\"#{r:02x}{g:02x}{b:02x}\" {attribute}-opacity=\"{:.3}\"",
+            a as f32 / 255.0
+        ),
+        None => format!(" {attribute}=\"none\""),
+    }
+}
+
+fn svg_stroke(stroke: Option<(Rgba, f32)>) -> String {
+    match stroke {
+        Some((color, width)) if width > 0.0 && color[3] > 0 => {
+            format!(
+                "{} stroke-width=\"{width:.2}\"",
+                svg_paint("stroke", Some(color))
+            )
+        }
+        _ => String::new(),
+    }
+}
+
+fn svg_points(points: &[(f32, f32)]) -> String {
+    points
+        .iter()
+        .map(|(x, y)| format!("{x:.2},{y:.2}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Render a canvas snapshot as an SVG document
+pub fn view_to_svg(view: &SvgView) -> String {
+    let mut svg = String::new();
+    svg.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let (output_width, output_height) = view.output_size.unwrap_or((view.width, view.height));
+    svg.push_str(&format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{output_width:.0}\" height=\"{output_height:.0}\" viewBox=\"0 0 {w:.2} {h:.2}\">\n",
+        w = view.width,
+        h = view.height
+    ));
+    if let Some(background) = view.background {
+        svg.push_str(&format!(
+            "<rect x=\"0\" y=\"0\" width=\"100%\" height=\"100%\"{}/>\n",
+            svg_paint("fill", Some(background))
+        ));
+    }
+
+    for element in &view.elements {
+        match element {
+            SvgElement::Polygon {
+                points,
+                fill,
+                stroke,
+            } => {
+                if points.len() < 2 {
+                    continue;
+                }
+                svg.push_str(&format!(
+                    "<polygon points=\"{}\"{}{}/>\n",
+                    svg_points(points),
+                    svg_paint("fill", *fill),
+                    svg_stroke(*stroke)
+                ));
+            }
+            SvgElement::Polyline { points, stroke } => {
+                if points.len() < 2 {
+                    continue;
+                }
+                svg.push_str(&format!(
+                    "<polyline points=\"{}\" fill=\"none\"{}/>\n",
+                    svg_points(points),
+                    svg_stroke(Some(*stroke))
+                ));
+            }
+            SvgElement::Circle {
+                center,
+                radius,
+                fill,
+                stroke,
+            } => {
+                svg.push_str(&format!(
+                    "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{radius:.2}\"{}{}/>\n",
+                    center.0,
+                    center.1,
+                    svg_paint("fill", *fill),
+                    svg_stroke(*stroke)
+                ));
+            }
+            SvgElement::Text {
+                pos,
+                text,
+                size,
+                color,
+                angle,
+            } => {
+                let rotate = if *angle != 0.0 {
+                    format!(
+                        " transform=\"rotate({:.2} {:.2} {:.2})\"",
+                        angle.to_degrees(),
+                        pos.0,
+                        pos.1
+                    )
+                } else {
+                    String::new()
+                };
+                svg.push_str(&format!(
+                    "<text x=\"{:.2}\" y=\"{:.2}\" font-family=\"monospace\" font-size=\"{size:.2}\" xml:space=\"preserve\"{}{}>{}</text>\n",
+                    pos.0,
+                    pos.1,
+                    svg_paint("fill", Some(*color)),
+                    rotate,
+                    escape_xml(text)
+                ));
+            }
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Export a canvas snapshot to an SVG file
+pub fn export_view_to_svg(
+    view: &SvgView,
+    file_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = File::create(file_path)?;
+    file.write_all(view_to_svg(view).as_bytes())?;
+    Ok(())
+}
@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Watching the loaded files for changes on disk
+//!
+//! With File > Watch Files for Changes on, the viewer parses a loaded LEF
+//! or DEF file again when it changes on disk, keeping the view, selections
+//! and layer visibility. The directories of the files are watched rather
+//! than the files, so editors that save by renaming a new file over the old
+//! one are seen too. A change is reported once the file stayed untouched
+//! for [`SETTLE_TIME`], so a file that is still being written is read when
+//! complete.
+
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// How long a file must stay untouched before it is read again
+pub const SETTLE_TIME: Duration = Duration::from_millis(500);
+
+/// Watches the loaded files and collects the ones that changed
+pub struct FileWatcher {
+    watcher: RecommendedWatcher,
+    events: mpsc::Receiver<notify::Result<Event>>,
+    /// Paths as loaded, sorted
+    paths: Vec<String>,
+    /// Loaded path of each watched file by canonical path
+    files: HashMap<PathBuf, String>,
+    directories: HashSet<PathBuf>,
+    /// Loaded paths of changed files and when they last changed
+    changed: HashMap<String, Instant>,
+}
+
+impl FileWatcher {
+    /// Create a watcher, calling `wake` on the watcher thread for every
+    /// file system event
+    pub fn new(wake: impl Fn() + Send + 'static) -> Result<Self, String> {
+        if cfg!(target_arch = "wasm32") {
+            // Files opened in the browser are copies in memory
+            return Err("Files cannot be watched in the browser".to_string());
+        }
+        let (tx, events) = mpsc::channel();
+        let watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+            wake();
+        })
+        .map_err(|e| format!("Failed to watch files: {e}"))?;
+        Ok(Self {
+            watcher,
+            events,
+            paths: Vec::new(),
+            files: HashMap::new(),
+            directories: HashSet::new(),
+            changed: HashMap::new(),
+        })
+    }
+
+    /// Watch exactly the files loaded from `paths`
+    pub fn set_files<'a>(
+        &mut self,
+        paths: impl IntoIterator<Item = &'a str>,
+    ) -> Result<(), String> {
+        let mut paths: Vec<String> = paths.into_iter().map(str::to_string).collect();
+        paths.sort();
+        if paths == self.paths {
+            return Ok(());
+        }
+
+        let files: HashMap<PathBuf, String> = paths
+            .iter()
+            .filter_map(|path| Some((Path::new(path).canonicalize().ok()?, path.clone())))
+            .collect();
+        let directories: HashSet<PathBuf> = files
+            .keys()
+            .filter_map(|file| file.parent().map(Path::to_path_buf))
+            .collect();
+        for directory in self.directories.difference(&directories) {
+            let _ = self.watcher.unwatch(directory);
+        }
+        for directory in directories.difference(&self.directories) {
+            self.watcher
+                .watch(directory, RecursiveMode::NonRecursive)
+                .map_err(|e| format!("Failed to watch {}: {e}", directory.display()))?;
+        }
+
+        self.changed.retain(|path, _| paths.contains(path));
+        self.paths = paths;
+        self.files = files;
+        self.directories = directories;
+        Ok(())
+    }
+
+    /// Loaded paths of the files that changed and settled since the last
+    /// call
+    pub fn take_changed(&mut self) -> Vec<String> {
+        let now = Instant::now();
+        while let Ok(event) = self.events.try_recv() {
+            let Ok(event) = event else {
+                continue;
+            };
+            let content_changed = match event.kind {
+                EventKind::Modify(ModifyKind::Metadata(_)) => false,
+                EventKind::Create(_) | EventKind::Modify(_) => true,
+                _ => false,
+            };
+            if !content_changed {
+                continue;
+            }
+            for path in &event.paths {
+                if let Some(loaded) = self.files.get(path) {
+                    self.changed.insert(loaded.clone(), now);
+                }
+            }
+        }
+        take_settled(&mut self.changed, now)
+    }
+
+    /// Whether changed files are still waiting to settle
+    pub fn has_pending(&self) -> bool {
+        !self.changed.is_empty()
+    }
+}
+
+/// Remove and return the files whose last change is [`SETTLE_TIME`] old
+fn take_settled(changed: &mut HashMap<String, Instant>, now: Instant) -> Vec<String> {
+    let mut settled: Vec<String> = changed
+        .iter()
+        .filter(|(_, time)| now.duration_since(**time) >= SETTLE_TIME)
+        .map(|(path, _)| path.clone())
+        .collect();
+    settled.sort();
+    for path in &settled {
+        changed.remove(path);
+    }
+    settled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_settled() {
+        let start = Instant::now();
+        let mut changed = HashMap::from([
+            ("b.lef".to_string(), start),
+            ("a.def".to_string(), start),
+            ("c.lef".to_string(), start + SETTLE_TIME),
+        ]);
+        assert!(take_settled(&mut changed, start).is_empty());
+        assert_eq!(
+            take_settled(&mut changed, start + SETTLE_TIME),
+            ["a.def", "b.lef"]
+        );
+        assert_eq!(changed.len(), 1);
+        assert_eq!(
+            take_settled(&mut changed, start + SETTLE_TIME * 2),
+            ["c.lef"]
+        );
+        assert!(changed.is_empty());
+    }
+}
@@ -0,0 +1,582 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! GDSII stream export
+//!
+//! Every LEF macro becomes a GDS structure holding its PIN and OBS shapes as
+//! boundaries and its pin names as text. With a DEF, a top structure named
+//! after the design is added with the placed macros flattened into it, so
+//! tools like KLayout show the same geometry as the viewer.
+//!
+//! GDS has numbered layers instead of names, so shapes are written through
+//! a [`GdsLayerMap`]. Shapes on layers missing from the map are skipped and
+//! reported in the [`GdsSummary`]. Coordinates are written in nanometers.
+//!
+//! Boundaries keep their 45-degree edges. Edges at other angles are written
+//! as they are but counted in the summary, as many GDS flows reject them.
+
+use crate::def::Def;
+use crate::geometry::{edge_angles, EdgeAngles, MacroTransform};
+use crate::lef::{LefMacro, LefPolygon, LefRect};
+use std::collections::{BTreeSet, HashMap};
+
+/// Database units per micron of the written file
+const DB_PER_MICRON: f64 = 1000.0;
+
+// Record types used by the writer
+const HEADER: u8 = 0x00;
+const BGNLIB: u8 = 0x01;
+const LIBNAME: u8 = 0x02;
+const UNITS: u8 = 0x03;
+const ENDLIB: u8 = 0x04;
+const BGNSTR: u8 = 0x05;
+const STRNAME: u8 = 0x06;
+const ENDSTR: u8 = 0x07;
+const BOUNDARY: u8 = 0x08;
+const TEXT: u8 = 0x0C;
+const LAYER: u8 = 0x0D;
+const DATATYPE: u8 = 0x0E;
+const XY: u8 = 0x10;
+const ENDEL: u8 = 0x11;
+const TEXTTYPE: u8 = 0x16;
+const STRING: u8 = 0x19;
+
+// Record data types
+const NO_DATA: u8 = 0x00;
+const INT16: u8 = 0x02;
+const INT32: u8 = 0x03;
+const REAL8: u8 = 0x05;
+const ASCII: u8 = 0x06;
+
+/// Mapping from LEF/DEF layer names to GDS layer and datatype numbers
+///
+/// One entry per line: `<name> <layer> <datatype>`. The name is a layer
+/// (`M1`), or a layer with a purpose (`M1.PIN`, `M1.OBS`, `M1.LABEL`) that
+/// takes precedence over the plain layer for that kind of shape. `OUTLINE`
+/// maps the macro and die area outlines. Blank lines and lines starting
+/// with `#` are ignored.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GdsLayerMap {
+    layers: HashMap<String, (i16, i16)>,
+}
+
+impl GdsLayerMap {
+    /// Parse a layer map, reporting the first malformed line
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut layers = HashMap::new();
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [name, layer, datatype] = fields[..] else {
+                return Err(format!(
+                    "line {}: expected <name> <layer> <datatype>",
+                    line_no + 1
+                ));
+            };
+            let number = |value: &str| {
+                value
+                    .parse::<i16>()
+                    .ok()
+                    .filter(|n| *n >= 0)
+                    .ok_or_else(|| format!("line {}: invalid number {value}", line_no + 1))
+            };
+            layers.insert(name.to_string(), (number(layer)?, number(datatype)?));
+        }
+        Ok(Self { layers })
+    }
+
+    /// Layer map numbering `layers` from 1 in order, with pins on datatype
+    /// 0, obstructions on 1 and labels on 2
+    pub fn default_text<'a>(layers: impl IntoIterator<Item = &'a str>) -> String {
+        let mut text = String::from("# <name> <gds layer> <gds datatype>\nOUTLINE 0 0\n");
+        for (i, layer) in layers.into_iter().enumerate() {
+            let number = i + 1;
+            text.push_str(&format!(
+                "{layer}.PIN {number} 0\n{layer}.OBS {number} 1\n{layer}.LABEL {number} 2\n"
+            ));
+        }
+        text
+    }
+
+    /// GDS layer and datatype of `layer` shapes of `purpose` (PIN, OBS, LABEL)
+    pub fn get(&self, layer: &str, purpose: &str) -> Option<(i16, i16)> {
+        self.layers
+            .get(&format!("{layer}.{purpose}"))
+            .or_else(|| self.layers.get(layer))
+            .copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+}
+
+/// What was written to the stream
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GdsSummary {
+    pub structures: usize,
+    pub boundaries: usize,
+    pub texts: usize,
+    /// Layers whose shapes were skipped as they are not in the map
+    pub unmapped_layers: BTreeSet<String>,
+    /// Boundaries with edges that are neither orthogonal nor 45-degree
+    pub all_angle_boundaries: usize,
+}
+
+/// Write `macros` and optionally the flattened placement of `def` as GDSII
+///
+/// The DEF comes with its database units per micron. Components whose
+/// macro is not in `macros` are left out of the top structure.
+pub fn write_gds(
+    macros: &[&LefMacro],
+    def: Option<(&Def, f64)>,
+    map: &GdsLayerMap,
+    path: &str,
+) -> Result<GdsSummary, Box<dyn std::error::Error>> {
+    let (bytes, summary) = gds_bytes(macros, def, map);
+    std::fs::write(path, bytes)?;
+    Ok(summary)
+}
+
+/// GDSII stream of [`write_gds`] in memory
+pub fn gds_bytes(
+    macros: &[&LefMacro],
+    def: Option<(&Def, f64)>,
+    map: &GdsLayerMap,
+) -> (Vec<u8>, GdsSummary) {
+    let mut stream = GdsStream {
+        bytes: Vec::new(),
+        map,
+        summary: GdsSummary::default(),
+    };
+
+    stream.record(HEADER, INT16, &600i16.to_be_bytes());
+    // Modification and access time, left at zero for reproducible files
+    stream.record(BGNLIB, INT16, &[0; 24]);
+    let lib_name = def.map_or("LEFDEF", |(def, _)| def.design.as_str());
+    stream.string(
+        LIBNAME,
+        if lib_name.is_empty() {
+            "LEFDEF"
+        } else {
+            lib_name
+        },
+    );
+    let mut units = real8(1.0 / DB_PER_MICRON).to_vec();
+    units.extend_from_slice(&real8(1e-6 / DB_PER_MICRON));
+    stream.record(UNITS, REAL8, &units);
+
+    for macro_def in macros {
+        stream.begin_structure(&macro_def.name);
+        stream.macro_shapes(macro_def, &MacroTransform::new(macro_def, (0.0, 0.0), "N"));
+        stream.record(ENDSTR, NO_DATA, &[]);
+    }
+
+    if let Some((def, db_units)) = def {
+        let by_name: HashMap<&str, &LefMacro> =
+            macros.iter().map(|m| (m.name.as_str(), *m)).collect();
+        let design = if def.design.is_empty() {
+            "TOP"
+        } else {
+            def.design.as_str()
+        };
+        stream.begin_structure(design);
+        if def.die_area_points.len() >= 2 {
+            let points = die_area_polygon(&def.die_area_points, db_units);
+            stream.outline(&points);
+        }
+        for component in &def.components {
+            let (Some(placement), Some(macro_def)) = (
+                &component.placement,
+                by_name.get(component.macro_name.as_str()),
+            ) else {
+                continue;
+            };
+            let transform = MacroTransform::new(
+                macro_def,
+                (placement.x / db_units, placement.y / db_units),
+                &placement.orientation,
+            );
+            stream.macro_shapes(macro_def, &transform);
+        }
+        stream.record(ENDSTR, NO_DATA, &[]);
+    }
+
+    stream.record(ENDLIB, NO_DATA, &[]);
+    (stream.bytes, stream.summary)
+}
+
+/// DIEAREA as a polygon, a two point DIEAREA being a rectangle
+fn die_area_polygon(points: &[(f64, f64)], db_units: f64) -> Vec<(f64, f64)> {
+    let points: Vec<(f64, f64)> = points
+        .iter()
+        .map(|(x, y)| (x / db_units, y / db_units))
+        .collect();
+    if let [(xl, yl), (xh, yh)] = points[..] {
+        vec![(xl, yl), (xh, yl), (xh, yh), (xl, yh)]
+    } else {
+        points
+    }
+}
+
+struct GdsStream<'a> {
+    bytes: Vec<u8>,
+    map: &'a GdsLayerMap,
+    summary: GdsSummary,
+}
+
+impl GdsStream<'_> {
+    fn record(&mut self, record_type: u8, data_type: u8, data: &[u8]) {
+        let length = u16::try_from(data.len() + 4).expect("GDS record fits in 64 KiB");
+        self.bytes.extend_from_slice(&length.to_be_bytes());
+        self.bytes.push(record_type);
+        self.bytes.push(data_type);
+        self.bytes.extend_from_slice(data);
+    }
+
+    /// ASCII record, padded with NUL to an even length
+    fn string(&mut self, record_type: u8, text: &str) {
+        let mut data = text.as_bytes().to_vec();
+        if data.len() % 2 == 1 {
+            data.push(0);
+        }
+        self.record(record_type, ASCII, &data);
+    }
+
+    fn xy(&mut self, points: impl IntoIterator<Item = (f64, f64)>) {
+        let data: Vec<u8> = points
+            .into_iter()
+            .flat_map(|(x, y)| [db(x), db(y)])
+            .flat_map(i32::to_be_bytes)
+            .collect();
+        self.record(XY, INT32, &data);
+    }
+
+    fn begin_structure(&mut self, name: &str) {
+        self.record(BGNSTR, INT16, &[0; 24]);
+        self.string(STRNAME, name);
+        self.summary.structures += 1;
+    }
+
+    /// Closed boundary, skipped when the layer is not mapped
+    fn boundary(&mut self, layer: &str, purpose: &str, points: &[(f64, f64)]) {
+        let Some((gds_layer, datatype)) = self.map.get(layer, purpose) else {
+            self.summary.unmapped_layers.insert(layer.to_string());
+            return;
+        };
+        // XY holds at most 8191 points including the closing one
+        if points.len() < 3 || points.len() > 8190 {
+            return;
+        }
+        self.record(BOUNDARY, NO_DATA, &[]);
+        self.record(LAYER, INT16, &gds_layer.to_be_bytes());
+        self.record(DATATYPE, INT16, &datatype.to_be_bytes());
+        self.xy(points.iter().chain(points.first()).copied());
+        self.record(ENDEL, NO_DATA, &[]);
+        self.summary.boundaries += 1;
+
+        // Angles as written, after rounding to database units
+        let written: Vec<(f64, f64)> = points
+            .iter()
+            .map(|&(x, y)| (f64::from(db(x)), f64::from(db(y))))
+            .collect();
+        if edge_angles(&written, true) == EdgeAngles::AllAngle {
+            self.summary.all_angle_boundaries += 1;
+        }
+    }
+
+    /// Macro or die area outline, written only when OUTLINE is mapped
+    fn outline(&mut self, points: &[(f64, f64)]) {
+        if self.map.get("OUTLINE", "OUTLINE").is_some() {
+            self.boundary("OUTLINE", "OUTLINE", points);
+        }
+    }
+
+    fn text(&mut self, layer: &str, at: (f64, f64), text: &str) {
+        let Some((gds_layer, texttype)) = self.map.get(layer, "LABEL") else {
+            return;
+        };
+        self.record(TEXT, NO_DATA, &[]);
+        self.record(LAYER, INT16, &gds_layer.to_be_bytes());
+        self.record(TEXTTYPE, INT16, &texttype.to_be_bytes());
+        self.xy([at]);
+        self.string(STRING, text);
+        self.record(ENDEL, NO_DATA, &[]);
+        self.summary.texts += 1;
+    }
+
+    fn rect(&mut self, rect: &LefRect, purpose: &str, transform: &MacroTransform) {
+        let corners = transform.rect_corners(rect.xl, rect.yl, rect.xh, rect.yh);
+        self.boundary(&rect.layer, purpose, &corners);
+    }
+
+    /// Polygon shape, whatever its winding: LEF shapes are all positive
+    fn polygon(&mut self, polygon: &LefPolygon, purpose: &str, transform: &MacroTransform) {
+        let points: Vec<(f64, f64)> = polygon.points.iter().map(|&p| transform.apply(p)).collect();
+        self.boundary(&polygon.layer, purpose, &points);
+    }
+
+    /// Outline, pins with their labels and obstructions of a placed macro
+    fn macro_shapes(&mut self, macro_def: &LefMacro, transform: &MacroTransform) {
+        let (width, height) = transform.size;
+        let outline = [
+            transform.apply_frame((0.0, 0.0)),
+            transform.apply_frame((width, 0.0)),
+            transform.apply_frame((width, height)),
+            transform.apply_frame((0.0, height)),
+        ];
+        self.outline(&outline);
+
+        for pin in &macro_def.pins {
+            for port in &pin.ports {
+                for rect in &port.rects {
+                    self.rect(rect, "PIN", transform);
+                }
+                for polygon in &port.polygons {
+                    self.polygon(polygon, "PIN", transform);
+                }
+            }
+            let layer = pin.ports.iter().find_map(|port| {
+                port.rects
+                    .first()
+                    .map(|r| &r.layer)
+                    .or_else(|| port.polygons.first().map(|p| &p.layer))
+            });
+            if let (Some(layer), Some(center)) = (layer, pin.center()) {
+                self.text(layer, transform.apply(center), &pin.name);
+            }
+        }
+        for obs in &macro_def.obs {
+            for rect in &obs.rects {
+                self.rect(rect, "OBS", transform);
+            }
+            for polygon in &obs.polygons {
+                self.polygon(polygon, "OBS", transform);
+            }
+        }
+    }
+}
+
+/// Microns to database units
+fn db(value: f64) -> i32 {
+    (value * DB_PER_MICRON).round() as i32
+}
+
+/// GDSII 8-byte real: sign bit, excess-64 base-16 exponent and a 56-bit
+/// mantissa
+fn real8(value: f64) -> [u8; 8] {
+    if value == 0.0 || !value.is_finite() {
+        return [0; 8];
+    }
+    let sign = if value < 0.0 { 1u64 << 63 } else { 0 };
+    let mut mantissa = value.abs();
+    let mut exponent: i32 = 64;
+    while mantissa >= 1.0 {
+        mantissa /= 16.0;
+        exponent += 1;
+    }
+    while mantissa < 1.0 / 16.0 {
+        mantissa *= 16.0;
+        exponent -= 1;
+    }
+    let mut bits = (mantissa * (1u64 << 56) as f64).round() as u64;
+    if bits >= 1 << 56 {
+        bits >>= 4;
+        exponent += 1;
+    }
+    (sign | ((exponent.clamp(0, 127) as u64) << 56) | bits).to_be_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lef::{LefObstruction, LefPin, LefPort};
+    use crate::test_fixtures::rect;
+
+    /// (record type, data) of every record in a stream
+    fn records(bytes: &[u8]) -> Vec<(u8, Vec<u8>)> {
+        let mut records = Vec::new();
+        let mut rest = bytes;
+        while rest.len() >= 4 {
+            let length = u16::from_be_bytes([rest[0], rest[1]]) as usize;
+            records.push((rest[2], rest[4..length].to_vec()));
+            rest = &rest[length..];
+        }
+        assert!(rest.is_empty());
+        records
+    }
+
+    #[test]
+    fn test_real8() {
+        assert_eq!(real8(1.0), [0x41, 0x10, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(real8(-2.0), [0xC1, 0x20, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(real8(0.0), [0; 8]);
+        // 1e-3 as written by other GDS tools
+        assert_eq!(
+            real8(1e-3),
+            [0x3E, 0x41, 0x89, 0x37, 0x4B, 0xC6, 0xA7, 0xF0]
+        );
+    }
+
+    #[test]
+    fn test_layer_map() {
+        let map = GdsLayerMap::parse("# comment\nM1 31 0\nM1.PIN 31 2\n\nOUTLINE 0 0").unwrap();
+        assert_eq!(map.get("M1", "PIN"), Some((31, 2)));
+        assert_eq!(map.get("M1", "OBS"), Some((31, 0)));
+        assert_eq!(map.get("M2", "PIN"), None);
+        assert!(GdsLayerMap::parse("M1 31").is_err());
+        assert!(GdsLayerMap::parse("M1 31 -1").is_err());
+
+        let default = GdsLayerMap::parse(&GdsLayerMap::default_text(["M1", "M2"])).unwrap();
+        assert_eq!(default.get("M2", "OBS"), Some((2, 1)));
+    }
+
+    #[test]
+    fn test_macro_and_flattened_placement() {
+        let inv = LefMacro {
+            name: "INV".to_string(),
+            class: "CORE".to_string(),
+            foreign: String::new(),
+            origin: (0.0, 0.0),
+            size_x: 2.0,
+            size_y: 1.0,
+            symmetry: Vec::new(),
+            site: String::new(),
+            pins: vec![LefPin {
+                name: "A".to_string(),
+                direction: "INPUT".to_string(),
+                use_type: "SIGNAL".to_string(),
+                shape: String::new(),
+                ports: vec![LefPort {
+                    rects: vec![rect("M1", 0.1, 0.2, 0.5, 0.8)],
+                    polygons: Vec::new(),
+                }],
+            }],
+            obs: vec![LefObstruction {
+                rects: vec![rect("M2", 0.0, 0.0, 2.0, 1.0)],
+                polygons: Vec::new(),
+            }],
+            ..LefMacro::default()
+        };
+        let def: Def = serde_json::from_value(serde_json::json!({
+            "design": "top",
+            "die_area_points": [[0.0, 0.0], [20000.0, 10000.0]],
+            "g_cell_grid_x": [], "g_cell_grid_y": [], "pins": [], "nets": [],
+            "special_nets": [], "rows": [], "tracks_x": [], "tracks_y": [], "vias": [],
+            "components": [{
+                "name": "u1", "macro_name": "INV",
+                "placement": {"placement_type": "PLACED", "x": 5000.0, "y": 2000.0, "orientation": "N"},
+                "routing_halo": null, "source": null, "weight": null, "eeq": null,
+                "generate": null, "power": null, "ground": null, "properties": []
+            }]
+        }))
+        .unwrap();
+        let map = GdsLayerMap::parse("M1 1 0\nOUTLINE 0 0").unwrap();
+
+        let (bytes, summary) = gds_bytes(&[&inv], Some((&def, 1000.0)), &map);
+        assert_eq!(summary.structures, 2);
+        // Outline and pin of INV, die area, outline and pin of u1
+        assert_eq!(summary.boundaries, 5);
+        assert_eq!(summary.texts, 2);
+        assert_eq!(summary.unmapped_layers, BTreeSet::from(["M2".to_string()]));
+        assert_eq!(summary.all_angle_boundaries, 0);
+
+        let records = records(&bytes);
+        assert_eq!(records.first().unwrap().0, HEADER);
+        assert_eq!(records.last().unwrap().0, ENDLIB);
+        let names: Vec<&[u8]> = records
+            .iter()
+            .filter(|(t, _)| *t == STRNAME)
+            .map(|(_, d)| d.as_slice())
+            .collect();
+        assert_eq!(names, [b"INV\0".as_slice(), b"top\0".as_slice()]);
+
+        // The pin of u1 is shifted by the placement and closed
+        let xy: Vec<i32> = records
+            .iter()
+            .rfind(|(t, d)| *t == XY && d.len() == 40)
+            .unwrap()
+            .1
+            .chunks(4)
+            .map(|c| i32::from_be_bytes([c[0], c[1], c[2], c[3]]))
+            .collect();
+        assert_eq!(
+            xy,
+            [5100, 2200, 5500, 2200, 5500, 2800, 5100, 2800, 5100, 2200]
+        );
+    }
+
+    #[test]
+    fn test_all_angle_boundaries_counted() {
+        let polygon = |points: Vec<(f64, f64)>| LefPolygon {
+            layer: "M1".to_string(),
+            points,
+            is_hole: false,
+        };
+        let cell = LefMacro {
+            name: "ANGLES".to_string(),
+            class: "CORE".to_string(),
+            foreign: String::new(),
+            origin: (0.0, 0.0),
+            size_x: 2.0,
+            size_y: 2.0,
+            symmetry: Vec::new(),
+            site: String::new(),
+            pins: Vec::new(),
+            obs: vec![LefObstruction {
+                rects: Vec::new(),
+                polygons: vec![
+                    // 45-degree triangle
+                    polygon(vec![(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]),
+                    // Parallelogram with 63-degree edges
+                    polygon(vec![(0.0, 1.0), (1.0, 1.0), (1.5, 2.0), (0.5, 2.0)]),
+                ],
+            }],
+            ..LefMacro::default()
+        };
+        let map = GdsLayerMap::parse("M1 1 0").unwrap();
+
+        let (_, summary) = gds_bytes(&[&cell], None, &map);
+        assert_eq!(summary.boundaries, 2);
+        assert_eq!(summary.all_angle_boundaries, 1);
+    }
+
+    #[test]
+    fn test_clockwise_polygon_written() {
+        let clockwise = LefPolygon {
+            layer: "M1".to_string(),
+            points: vec![(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)],
+            is_hole: true,
+        };
+        let cell = LefMacro {
+            name: "CW".to_string(),
+            class: "CORE".to_string(),
+            foreign: String::new(),
+            origin: (0.0, 0.0),
+            size_x: 1.0,
+            size_y: 1.0,
+            symmetry: Vec::new(),
+            site: String::new(),
+            pins: Vec::new(),
+            obs: vec![LefObstruction {
+                rects: Vec::new(),
+                polygons: vec![clockwise],
+            }],
+            ..LefMacro::default()
+        };
+        let map = GdsLayerMap::parse("M1 1 0").unwrap();
+
+        let (bytes, summary) = gds_bytes(&[&cell], None, &map);
+        assert_eq!(summary.boundaries, 1);
+        assert_eq!(
+            records(&bytes)
+                .iter()
+                .filter(|(t, _)| *t == BOUNDARY)
+                .count(),
+            1
+        );
+    }
+}
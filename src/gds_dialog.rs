@@ -0,0 +1,139 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! GDSII export dialog
+//!
+//! Edits the layer map used by [`crate::gds`] before writing the stream.
+//! The map starts out numbering the loaded technology layers and can be
+//! replaced by a map file, typically the one the foundry ships for KLayout.
+
+use crate::gds::GdsLayerMap;
+use crate::platform::FileDialog;
+use eframe::egui;
+
+/// Export settings confirmed in the dialog
+pub struct GdsExportRequest {
+    pub map: GdsLayerMap,
+    /// Add a top structure with the flattened DEF placement
+    pub include_def: bool,
+}
+
+/// GDSII export dialog state and rendering
+#[derive(Default)]
+pub struct GdsExportDialog {
+    /// Whether the dialog is currently shown
+    pub visible: bool,
+    map_text: String,
+    /// Layers numbered by the default map
+    layers: Vec<String>,
+    include_def: bool,
+    has_def: bool,
+    error: Option<String>,
+}
+
+impl GdsExportDialog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the dialog, numbering `layers` when no map was entered yet
+    pub fn open(&mut self, layers: &[String], has_def: bool) {
+        self.visible = true;
+        self.error = None;
+        self.layers = layers.to_vec();
+        if self.map_text.trim().is_empty() {
+            self.reset_map();
+        }
+        if has_def && !self.has_def {
+            self.include_def = true;
+        }
+        self.has_def = has_def;
+    }
+
+    fn reset_map(&mut self) {
+        self.map_text = GdsLayerMap::default_text(self.layers.iter().map(String::as_str));
+    }
+
+    /// Render the dialog, returning the settings once Export is clicked
+    pub fn render(&mut self, ctx: &egui::Context) -> Option<GdsExportRequest> {
+        if !self.visible {
+            return None;
+        }
+
+        let mut open = true;
+        let mut request = None;
+
+        egui::Window::new("Export GDSII")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                ui.label("Layer map: <name> <gds layer> <gds datatype>");
+                ui.label(
+                    egui::RichText::new(
+                        "Names are LEF layers, optionally with .PIN, .OBS or .LABEL. \
+                         OUTLINE maps cell and die outlines.",
+                    )
+                    .small()
+                    .weak(),
+                );
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut self.map_text)
+                                .code_editor()
+                                .desired_width(f32::INFINITY)
+                                .desired_rows(12),
+                        );
+                    });
+                ui.horizontal(|ui| {
+                    if ui.button("Load Map File...").clicked() {
+                        if let Some(path) = FileDialog::new()
+                            .add_filter("Layer map", &["map", "txt"])
+                            .pick_file()
+                        {
+                            match std::fs::read_to_string(&path) {
+                                Ok(text) => self.map_text = text,
+                                Err(e) => self.error = Some(format!("Failed to read map: {e}")),
+                            }
+                        }
+                    }
+                    if ui.button("Reset").clicked() {
+                        self.reset_map();
+                    }
+                });
+
+                ui.add_enabled(
+                    self.has_def,
+                    egui::Checkbox::new(&mut self.include_def, "Include flattened DEF placement"),
+                );
+
+                ui.separator();
+                if ui.button("Export...").clicked() {
+                    match GdsLayerMap::parse(&self.map_text) {
+                        Ok(map) if map.is_empty() => {
+                            self.error = Some("The layer map is empty".to_string());
+                        }
+                        Ok(map) => {
+                            self.error = None;
+                            request = Some(GdsExportRequest {
+                                map,
+                                include_def: self.include_def && self.has_def,
+                            });
+                        }
+                        Err(e) => self.error = Some(e),
+                    }
+                }
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 80, 80), error);
+                }
+            });
+
+        if !open || request.is_some() {
+            self.visible = false;
+        }
+
+        request
+    }
+}
@@ -0,0 +1,689 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Placement geometry shared by the renderer and exporters
+//!
+//! LEF shapes are given relative to the macro ORIGIN, while DEF places the
+//! macro bounding box (0, 0)..(SIZE) at the component location. Everything
+//! that needs world coordinates of a placed macro goes through
+//! [`MacroTransform`] so ORIGIN and orientation are applied the same way.
+
+use crate::lef::{LefMacro, LefPort, LefRect};
+
+/// Apply a DEF orientation to a point in the macro frame
+///
+/// The macro frame is the unplaced bounding box (0, 0)..(width, height).
+/// The result stays in the positive quadrant, i.e. the oriented bounding box
+/// again starts at (0, 0).
+///
+/// DEF orientations:
+/// - N: R0, no rotation
+/// - W: R90, 90 degree counterclockwise rotation
+/// - S: R180, 180 degree rotation
+/// - E: R270, 90 degree clockwise rotation
+/// - FN: MY, mirror about the Y axis
+/// - FS: MX, mirror about the X axis
+/// - FE: MY90, mirror about the Y axis then R90
+/// - FW: MX90, mirror about the X axis then R90
+///
+/// Unknown orientations are treated as N.
+pub fn orient_point(point: (f64, f64), orientation: &str, macro_size: (f64, f64)) -> (f64, f64) {
+    let (x, y) = point;
+    let (width, height) = macro_size;
+
+    match orientation {
+        "N" => (x, y),
+        "S" => (width - x, height - y),
+        "E" => (y, width - x),
+        "W" => (height - y, x),
+        "FN" => (width - x, y),
+        "FS" => (x, height - y),
+        "FE" => (height - y, width - x),
+        "FW" => (y, x),
+        _ => {
+            eprintln!("WARNING: Unknown orientation '{orientation}', treating as N");
+            (x, y)
+        }
+    }
+}
+
+/// Placement of a LEF macro in world coordinates
+///
+/// All coordinates are in LEF units (microns); DEF placement locations must
+/// be divided by the DEF database units before being passed in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MacroTransform<'a> {
+    /// LEF ORIGIN, added to every shape coordinate
+    pub origin: (f64, f64),
+    /// LEF SIZE as (width, height)
+    pub size: (f64, f64),
+    /// DEF placement location of the macro bounding box
+    pub placement: (f64, f64),
+    /// DEF orientation (N, S, E, W, FN, FS, FE, FW)
+    pub orientation: &'a str,
+}
+
+impl<'a> MacroTransform<'a> {
+    /// Build the transform for a macro placed at `placement` with `orientation`
+    pub fn new(macro_def: &LefMacro, placement: (f64, f64), orientation: &'a str) -> Self {
+        Self {
+            origin: macro_def.origin,
+            size: (macro_def.size_x, macro_def.size_y),
+            placement,
+            orientation,
+        }
+    }
+
+    /// Transform a LEF shape coordinate (relative to ORIGIN) to world coordinates
+    pub fn apply(&self, point: (f64, f64)) -> (f64, f64) {
+        self.apply_frame((self.origin.0 + point.0, self.origin.1 + point.1))
+    }
+
+    /// Transform a point in the macro frame (bounding box space) to world coordinates
+    ///
+    /// Use this for the outline and other points that are already relative
+    /// to the bounding box rather than to ORIGIN.
+    pub fn apply_frame(&self, point: (f64, f64)) -> (f64, f64) {
+        let (tx, ty) = orient_point(point, self.orientation, self.size);
+        (self.placement.0 + tx, self.placement.1 + ty)
+    }
+
+    /// World bounding box of a LEF RECT as (min_x, min_y, max_x, max_y)
+    pub fn apply_rect(&self, xl: f64, yl: f64, xh: f64, yh: f64) -> (f64, f64, f64, f64) {
+        bounds_of(self.rect_corners(xl, yl, xh, yh))
+    }
+
+    /// World corners of a LEF RECT, in the same order as the input corners
+    /// (xl,yl), (xh,yl), (xh,yh), (xl,yh)
+    pub fn rect_corners(&self, xl: f64, yl: f64, xh: f64, yh: f64) -> [(f64, f64); 4] {
+        [
+            self.apply((xl, yl)),
+            self.apply((xh, yl)),
+            self.apply((xh, yh)),
+            self.apply((xl, yh)),
+        ]
+    }
+
+    /// World bounding box of the placed macro outline as (min_x, min_y, max_x, max_y)
+    pub fn outline_bbox(&self) -> (f64, f64, f64, f64) {
+        let (width, height) = self.size;
+        bounds_of([
+            self.apply_frame((0.0, 0.0)),
+            self.apply_frame((width, 0.0)),
+            self.apply_frame((width, height)),
+            self.apply_frame((0.0, height)),
+        ])
+    }
+}
+
+/// Bounding box of four points as (min_x, min_y, max_x, max_y)
+fn bounds_of(points: [(f64, f64); 4]) -> (f64, f64, f64, f64) {
+    points.iter().fold(
+        (
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ),
+        |(min_x, min_y, max_x, max_y), &(x, y)| {
+            (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+        },
+    )
+}
+
+/// Rectangle as (min_x, min_y, max_x, max_y)
+pub type Bounds = (f64, f64, f64, f64);
+
+/// Most rectangles [`merge_rects`] merges in one call, larger sets are kept
+const MAX_MERGE_RECTS: usize = 1024;
+
+/// Union of rectangles as non-overlapping rectangles
+///
+/// Abutting and overlapping rectangles are cut into horizontal strips at
+/// every edge, each strip is reduced to its covered runs, and runs with the
+/// same X span in consecutive strips are joined. A power rail drawn as
+/// hundreds of abutting pieces becomes one rectangle, and overlaps are no
+/// longer painted twice. Empty rectangles are dropped, and sets larger than
+/// 1024 rectangles are returned unchanged to bound the work.
+pub fn merge_rects(rects: &[Bounds]) -> Vec<Bounds> {
+    let rects: Vec<_> = rects
+        .iter()
+        .copied()
+        .filter(|r| r.2 > r.0 && r.3 > r.1)
+        .collect();
+    if rects.len() < 2 || rects.len() > MAX_MERGE_RECTS {
+        return rects;
+    }
+
+    let edges = |pick: fn(&Bounds) -> [f64; 2]| {
+        let mut values: Vec<f64> = rects.iter().flat_map(pick).collect();
+        values.sort_by(f64::total_cmp);
+        values.dedup();
+        values
+    };
+    let xs = edges(|r| [r.0, r.2]);
+    let ys = edges(|r| [r.1, r.3]);
+    let index = |values: &[f64], v: f64| values.partition_point(|&e| e < v);
+
+    // Coverage count changes per strip, summed into runs below
+    let columns = xs.len();
+    let mut deltas = vec![0i32; (ys.len() - 1) * columns];
+    for r in &rects {
+        let (x0, x1) = (index(&xs, r.0), index(&xs, r.2));
+        for row in index(&ys, r.1)..index(&ys, r.3) {
+            deltas[row * columns + x0] += 1;
+            deltas[row * columns + x1] -= 1;
+        }
+    }
+
+    let mut merged = Vec::new();
+    // Runs of the previous strip as (first column, end column) -> first strip
+    let mut open: std::collections::BTreeMap<(usize, usize), usize> = Default::default();
+    for row in 0..ys.len() {
+        let mut runs = Vec::new();
+        if row < ys.len() - 1 {
+            let mut depth = 0;
+            let mut start = 0;
+            for column in 0..columns {
+                let before = depth;
+                depth += deltas[row * columns + column];
+                if before == 0 && depth > 0 {
+                    start = column;
+                } else if before > 0 && depth == 0 {
+                    runs.push((start, column));
+                }
+            }
+        }
+        open.retain(|&(x0, x1), first_row| {
+            let continues = runs.contains(&(x0, x1));
+            if !continues {
+                merged.push((xs[x0], ys[*first_row], xs[x1], ys[row]));
+            }
+            continues
+        });
+        for run in runs {
+            open.entry(run).or_insert(row);
+        }
+    }
+    merged
+}
+
+/// Copy of a macro with the RECTs of each POWER and GROUND pin merged per
+/// layer, see [`merge_rects`]
+///
+/// The merged shapes of a pin end up in one port, next to its polygons.
+/// Used for rendering only; returns `None` when nothing can be merged.
+pub fn merge_supply_pin_rects(macro_def: &LefMacro) -> Option<LefMacro> {
+    let mut merged = macro_def.clone();
+    let mut reduced = false;
+    for pin in &mut merged.pins {
+        let use_type = pin.use_type.to_ascii_uppercase();
+        if use_type != "POWER" && use_type != "GROUND" {
+            continue;
+        }
+
+        let mut layers: Vec<(String, Vec<Bounds>)> = Vec::new();
+        let mut count = 0;
+        for rect in pin.ports.iter().flat_map(|port| &port.rects) {
+            count += 1;
+            let bounds = (rect.xl, rect.yl, rect.xh, rect.yh);
+            match layers.iter_mut().find(|(layer, _)| *layer == rect.layer) {
+                Some((_, rects)) => rects.push(bounds),
+                None => layers.push((rect.layer.clone(), vec![bounds])),
+            }
+        }
+        let rects: Vec<LefRect> = layers
+            .into_iter()
+            .flat_map(|(layer, rects)| {
+                merge_rects(&rects)
+                    .into_iter()
+                    .map(move |(xl, yl, xh, yh)| LefRect {
+                        layer: layer.clone(),
+                        xl,
+                        yl,
+                        xh,
+                        yh,
+                    })
+            })
+            .collect();
+        if rects.len() >= count {
+            continue;
+        }
+
+        reduced = true;
+        let polygons = pin
+            .ports
+            .iter()
+            .flat_map(|port| port.polygons.iter().cloned())
+            .collect();
+        pin.ports = vec![LefPort { rects, polygons }];
+    }
+    reduced.then_some(merged)
+}
+
+/// Coordinate difference below which an edge counts as exactly axis
+/// parallel or exactly diagonal
+const ANGLE_TOLERANCE: f64 = 1e-6;
+
+/// Directions the edges of a shape take, from the most to the least
+/// restricted
+///
+/// LEF polygons and DEF wires may use 45-degree edges, which every
+/// downstream flow handles. Edges at other angles are all-angle geometry,
+/// which GDS based flows and many rule decks reject.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EdgeAngles {
+    /// Only horizontal and vertical edges
+    Manhattan,
+    /// Also 45-degree edges
+    Diagonal,
+    /// Edges at any other angle
+    AllAngle,
+}
+
+/// Directions of the edges from point to point, and back to the first point
+/// when `closed`, e.g. for a polygon rather than a wire path
+pub fn edge_angles(points: &[(f64, f64)], closed: bool) -> EdgeAngles {
+    let closing = points.first().filter(|_| closed && points.len() > 2);
+    points
+        .iter()
+        .zip(points.iter().skip(1).chain(closing))
+        .map(|(a, b)| {
+            let (dx, dy) = ((b.0 - a.0).abs(), (b.1 - a.1).abs());
+            if dx <= ANGLE_TOLERANCE || dy <= ANGLE_TOLERANCE {
+                EdgeAngles::Manhattan
+            } else if (dx - dy).abs() <= ANGLE_TOLERANCE {
+                EdgeAngles::Diagonal
+            } else {
+                EdgeAngles::AllAngle
+            }
+        })
+        .max()
+        .unwrap_or(EdgeAngles::Manhattan)
+}
+
+/// Shoelace area of a polygon
+pub fn polygon_area(points: &[(f64, f64)]) -> f64 {
+    let twice: f64 = points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum();
+    twice.abs() / 2.0
+}
+
+/// Whether `point` is inside a polygon, by the even-odd rule
+///
+/// Points on an edge may land on either side.
+pub fn polygon_contains(points: &[(f64, f64)], (x, y): (f64, f64)) -> bool {
+    let mut inside = false;
+    for (a, b) in points.iter().zip(points.iter().cycle().skip(1)) {
+        if (a.1 > y) != (b.1 > y) && x < a.0 + (y - a.1) / (b.1 - a.1) * (b.0 - a.0) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Corners of a wire segment of any direction, extended past its ends by
+/// `start_ext` and `end_ext`
+///
+/// A 45-degree wire is a rotated rectangle, not the bounding box of its
+/// centerline.
+pub fn wire_outline(
+    from: (f64, f64),
+    to: (f64, f64),
+    half_width: f64,
+    start_ext: f64,
+    end_ext: f64,
+) -> [(f64, f64); 4] {
+    let length = (to.0 - from.0).hypot(to.1 - from.1);
+    let (dx, dy) = if length > 0.0 {
+        ((to.0 - from.0) / length, (to.1 - from.1) / length)
+    } else {
+        (1.0, 0.0)
+    };
+    let (nx, ny) = (-dy * half_width, dx * half_width);
+    let start = (from.0 - dx * start_ext, from.1 - dy * start_ext);
+    let end = (to.0 + dx * end_ext, to.1 + dy * end_ext);
+    [
+        (start.0 + nx, start.1 + ny),
+        (end.0 + nx, end.1 + ny),
+        (end.0 - nx, end.1 - ny),
+        (start.0 - nx, start.1 - ny),
+    ]
+}
+
+/// Triangles filling a possibly concave polygon, as vertices and indices
+///
+/// Empty when the polygon has fewer than three points or cannot be
+/// tessellated.
+pub fn triangulate(points: &[(f32, f32)]) -> (Vec<(f32, f32)>, Vec<u32>) {
+    use lyon_tessellation::math::point;
+    use lyon_tessellation::path::Path;
+    use lyon_tessellation::{math::Point, VertexBuffers};
+    use lyon_tessellation::{BuffersBuilder, FillOptions, FillTessellator, FillVertex};
+
+    if points.len() < 3 {
+        return (Vec::new(), Vec::new());
+    }
+    let mut builder = Path::builder();
+    builder.begin(point(points[0].0, points[0].1));
+    for p in &points[1..] {
+        builder.line_to(point(p.0, p.1));
+    }
+    builder.end(true);
+    let path = builder.build();
+
+    let mut buffers: VertexBuffers<Point, u32> = VertexBuffers::new();
+    let result = FillTessellator::new().tessellate_path(
+        &path,
+        &FillOptions::default(),
+        &mut BuffersBuilder::new(&mut buffers, |vertex: FillVertex| vertex.position()),
+    );
+    if result.is_err() {
+        return (Vec::new(), Vec::new());
+    }
+    let vertices = buffers.vertices.iter().map(|v| (v.x, v.y)).collect();
+    (vertices, buffers.indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform(orientation: &str) -> MacroTransform<'_> {
+        MacroTransform {
+            origin: (0.5, 0.25),
+            size: (2.0, 1.0),
+            placement: (10.0, 20.0),
+            orientation,
+        }
+    }
+
+    #[test]
+    fn test_origin_applied_to_shapes() {
+        let xform = transform("N");
+        // Shape point at (0,0) sits at ORIGIN inside the placed bounding box
+        assert_eq!(xform.apply((0.0, 0.0)), (10.5, 20.25));
+        assert_eq!(xform.apply((1.0, 0.5)), (11.5, 20.75));
+    }
+
+    #[test]
+    fn test_origin_not_applied_to_outline() {
+        let xform = transform("N");
+        assert_eq!(xform.apply_frame((0.0, 0.0)), (10.0, 20.0));
+        assert_eq!(xform.outline_bbox(), (10.0, 20.0, 12.0, 21.0));
+    }
+
+    #[test]
+    fn test_origin_with_rotation() {
+        // S rotates the ORIGIN-shifted shape about the bounding box
+        let xform = transform("S");
+        assert_eq!(xform.apply((0.0, 0.0)), (11.5, 20.75));
+        assert_eq!(
+            xform.apply_rect(0.0, 0.0, 0.5, 0.25),
+            (11.0, 20.5, 11.5, 20.75)
+        );
+
+        // E swaps the footprint to height x width
+        let xform = transform("E");
+        assert_eq!(xform.outline_bbox(), (10.0, 20.0, 11.0, 22.0));
+        assert_eq!(xform.apply((0.0, 0.0)), (10.25, 21.5));
+    }
+
+    #[test]
+    fn test_shapes_stay_inside_outline_in_all_orientations() {
+        for orientation in ["N", "S", "E", "W", "FN", "FS", "FE", "FW"] {
+            let xform = transform(orientation);
+            let (min_x, min_y, max_x, max_y) = xform.outline_bbox();
+            // Shape spanning the whole box once ORIGIN is applied
+            let (sx0, sy0, sx1, sy1) = xform.apply_rect(-0.5, -0.25, 1.5, 0.75);
+            assert!((sx0 - min_x).abs() < 1e-9, "{orientation}");
+            assert!((sy0 - min_y).abs() < 1e-9, "{orientation}");
+            assert!((sx1 - max_x).abs() < 1e-9, "{orientation}");
+            assert!((sy1 - max_y).abs() < 1e-9, "{orientation}");
+        }
+    }
+
+    #[test]
+    fn test_orient_point_conventions() {
+        let size = (2.0, 1.0);
+        assert_eq!(orient_point((1.0, 0.0), "N", size), (1.0, 0.0));
+        assert_eq!(orient_point((1.0, 0.0), "W", size), (1.0, 1.0));
+        assert_eq!(orient_point((1.0, 0.0), "S", size), (1.0, 1.0));
+        assert_eq!(orient_point((1.0, 0.0), "E", size), (0.0, 1.0));
+        assert_eq!(orient_point((0.0, 0.0), "FN", size), (2.0, 0.0));
+        assert_eq!(orient_point((0.0, 0.0), "FS", size), (0.0, 1.0));
+        assert_eq!(orient_point((2.0, 0.0), "FE", size), (1.0, 0.0));
+        assert_eq!(orient_point((2.0, 0.0), "FW", size), (0.0, 2.0));
+        assert_eq!(orient_point((1.0, 0.0), "BOGUS", size), (1.0, 0.0));
+    }
+
+    #[test]
+    fn test_orient_point_matches_spec_table() {
+        // DEF orientations as the rotate/mirror of the LEF/DEF reference
+        // and OpenDB: mirror first, then rotate counterclockwise about (0, 0)
+        type Point = (f64, f64);
+        type Orient = fn(Point) -> Point;
+        fn r90((x, y): Point) -> Point {
+            (-y, x)
+        }
+        fn mx((x, y): Point) -> Point {
+            (x, -y)
+        }
+        fn my((x, y): Point) -> Point {
+            (-x, y)
+        }
+        let spec: [(&str, Orient); 8] = [
+            ("N", |p| p),
+            ("W", r90),
+            ("S", |p| r90(r90(p))),
+            ("E", |p| r90(r90(r90(p)))),
+            ("FN", my),
+            ("FS", mx),
+            ("FW", |p| r90(mx(p))),
+            ("FE", |p| r90(my(p))),
+        ];
+
+        let size = (3.0, 2.0);
+        let corners = [(0.0, 0.0), (3.0, 0.0), (3.0, 2.0), (0.0, 2.0)];
+        for (orientation, apply) in spec {
+            // DEF places the oriented bounding box at the location
+            let moved = corners.map(apply);
+            let min_x = moved.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+            let min_y = moved.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+            for point in [(0.0, 0.0), (0.5, 1.5), (3.0, 0.25), (2.0, 2.0)] {
+                let (x, y) = apply(point);
+                assert_eq!(
+                    orient_point(point, orientation, size),
+                    (x - min_x, y - min_y),
+                    "{orientation} of {point:?}"
+                );
+            }
+        }
+    }
+
+    /// Octagon with 45-degree corners cut into a 4 x 4 square
+    const OCTAGON: [(f64, f64); 8] = [
+        (1.0, 0.0),
+        (3.0, 0.0),
+        (4.0, 1.0),
+        (4.0, 3.0),
+        (3.0, 4.0),
+        (1.0, 4.0),
+        (0.0, 3.0),
+        (0.0, 1.0),
+    ];
+
+    #[test]
+    fn test_edge_angles() {
+        let square = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)];
+        assert_eq!(edge_angles(&square, true), EdgeAngles::Manhattan);
+        assert_eq!(edge_angles(&OCTAGON, true), EdgeAngles::Diagonal);
+        // Only the closing edge of the triangle is diagonal
+        let triangle = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0)];
+        assert_eq!(edge_angles(&triangle, false), EdgeAngles::Manhattan);
+        assert_eq!(edge_angles(&triangle, true), EdgeAngles::Diagonal);
+        let skewed = [(0.0, 0.0), (2.0, 0.0), (3.0, 2.0), (1.0, 2.0)];
+        assert_eq!(edge_angles(&skewed, true), EdgeAngles::AllAngle);
+        // Rounded DBU coordinates still count as 45 degrees
+        let wire = [(0.0, 0.0), (0.1, 0.1000000001), (0.1, 0.5)];
+        assert_eq!(edge_angles(&wire, false), EdgeAngles::Diagonal);
+    }
+
+    #[test]
+    fn test_diagonal_polygon_area_and_contains() {
+        // Square minus four corner triangles of area 0.5
+        assert!((polygon_area(&OCTAGON) - 14.0).abs() < 1e-9);
+        assert!(polygon_contains(&OCTAGON, (2.0, 2.0)));
+        assert!(polygon_contains(&OCTAGON, (0.6, 0.5)));
+        // Inside the bounding box but in a cut corner
+        assert!(!polygon_contains(&OCTAGON, (0.2, 0.2)));
+        assert!(!polygon_contains(&OCTAGON, (3.9, 3.9)));
+    }
+
+    /// Area covered by the triangles of [`triangulate`]
+    fn triangulated_area(polygon: &[(f64, f64)]) -> f64 {
+        let points: Vec<(f32, f32)> = polygon.iter().map(|&(x, y)| (x as f32, y as f32)).collect();
+        let (vertices, indices) = triangulate(&points);
+        assert_eq!(indices.len() % 3, 0);
+        indices
+            .chunks(3)
+            .map(|triangle| {
+                let corners: Vec<(f64, f64)> = triangle
+                    .iter()
+                    .map(|&i| vertices[i as usize])
+                    .map(|(x, y)| (f64::from(x), f64::from(y)))
+                    .collect();
+                polygon_area(&corners)
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_triangulate_diagonal_polygons() {
+        // Concave at (2, 1), where the 45-degree edge starts
+        let chevron = [
+            (0.0, 0.0),
+            (2.0, 0.0),
+            (2.0, 1.0),
+            (4.0, 3.0),
+            (4.0, 4.0),
+            (0.0, 4.0),
+        ];
+        assert!((triangulated_area(&chevron) - polygon_area(&chevron)).abs() < 1e-4);
+        assert!((triangulated_area(&OCTAGON) - 14.0).abs() < 1e-4);
+        assert!(triangulate(&[(0.0, 0.0), (1.0, 1.0)]).0.is_empty());
+    }
+
+    #[test]
+    fn test_wire_outline() {
+        let corners = wire_outline((0.0, 0.0), (1.0, 1.0), 0.5, 0.0, 0.0);
+        let half = 0.5 / 2f64.sqrt();
+        assert!((corners[0].0 + half).abs() < 1e-9 && (corners[0].1 - half).abs() < 1e-9);
+        // Rotated rectangle as long as the centerline and as wide as the wire
+        assert!((polygon_area(&corners) - 2f64.sqrt()).abs() < 1e-9);
+        let extended = wire_outline((0.0, 0.0), (2.0, 0.0), 0.5, 0.5, 0.5);
+        assert_eq!(
+            extended,
+            [(-0.5, 0.5), (2.5, 0.5), (2.5, -0.5), (-0.5, -0.5)]
+        );
+    }
+
+    fn area(rects: &[Bounds]) -> f64 {
+        rects.iter().map(|r| (r.2 - r.0) * (r.3 - r.1)).sum()
+    }
+
+    #[test]
+    fn test_merge_abutting_rects() {
+        // Rail drawn as four abutting pieces, out of order
+        let rail = [
+            (2.0, 0.0, 3.0, 0.5),
+            (0.0, 0.0, 1.0, 0.5),
+            (3.0, 0.0, 4.0, 0.5),
+            (1.0, 0.0, 2.0, 0.5),
+        ];
+        assert_eq!(merge_rects(&rail), [(0.0, 0.0, 4.0, 0.5)]);
+
+        // Stacked pieces with the same span join vertically
+        let column = [(0.0, 0.0, 1.0, 1.0), (0.0, 1.0, 1.0, 2.0)];
+        assert_eq!(merge_rects(&column), [(0.0, 0.0, 1.0, 2.0)]);
+
+        // Separate rects stay separate, empty ones are dropped
+        let apart = [
+            (0.0, 0.0, 1.0, 1.0),
+            (2.0, 0.0, 3.0, 1.0),
+            (5.0, 5.0, 5.0, 6.0),
+        ];
+        assert_eq!(
+            merge_rects(&apart),
+            [(0.0, 0.0, 1.0, 1.0), (2.0, 0.0, 3.0, 1.0)]
+        );
+    }
+
+    #[test]
+    fn test_merge_overlapping_rects_without_overlap() {
+        // L shape from two overlapping rects
+        let rects = [(0.0, 0.0, 4.0, 1.0), (0.0, 0.0, 1.0, 3.0)];
+        let merged = merge_rects(&rects);
+        assert_eq!(merged, [(0.0, 0.0, 4.0, 1.0), (0.0, 1.0, 1.0, 3.0)]);
+        assert_eq!(area(&merged), 6.0);
+    }
+
+    #[test]
+    fn test_merge_supply_pin_rects() {
+        let rect = |layer: &str, xl, xh| LefRect {
+            layer: layer.to_string(),
+            xl,
+            yl: 0.0,
+            xh,
+            yh: 0.2,
+        };
+        let pin = |name: &str, use_type: &str| crate::lef::LefPin {
+            name: name.to_string(),
+            direction: "INOUT".to_string(),
+            use_type: use_type.to_string(),
+            shape: String::new(),
+            ports: vec![
+                LefPort {
+                    rects: vec![rect("M1", 0.0, 1.0), rect("M2", 0.0, 1.0)],
+                    polygons: Vec::new(),
+                },
+                LefPort {
+                    rects: vec![rect("M1", 1.0, 2.0)],
+                    polygons: Vec::new(),
+                },
+            ],
+        };
+        let macro_def = LefMacro {
+            name: "TAP".to_string(),
+            class: "CORE".to_string(),
+            foreign: String::new(),
+            origin: (0.0, 0.0),
+            size_x: 2.0,
+            size_y: 1.0,
+            symmetry: Vec::new(),
+            site: String::new(),
+            pins: vec![pin("VDD", "POWER"), pin("A", "SIGNAL")],
+            obs: Vec::new(),
+            ..LefMacro::default()
+        };
+
+        let merged = merge_supply_pin_rects(&macro_def).unwrap();
+        let vdd = &merged.pins[0].ports;
+        assert_eq!(vdd.len(), 1);
+        let shapes: Vec<_> = vdd[0]
+            .rects
+            .iter()
+            .map(|r| (r.layer.as_str(), r.xl, r.xh))
+            .collect();
+        assert_eq!(shapes, [("M1", 0.0, 2.0), ("M2", 0.0, 1.0)]);
+        // Signal pins keep their ports
+        assert_eq!(merged.pins[1].ports.len(), 2);
+
+        let mut signal_only = macro_def.clone();
+        signal_only.pins.remove(0);
+        assert!(merge_supply_pin_rects(&signal_only).is_none());
+    }
+}
@@ -0,0 +1,226 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Go to Object Dialog
+//!
+//! Ctrl+G opens a search over every macro, component, net and pin name of
+//! the loaded files. Typed characters match in order but not necessarily
+//! next to each other, so `u1inv` finds `u1/inv_3`. Enter or a click on a
+//! result hands the object back to the viewer, which moves the camera to it.
+
+use crate::identifier;
+use eframe::egui;
+
+/// Results listed below the query
+const MAX_RESULTS: usize = 50;
+
+/// Kind of object a result refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GotoKind {
+    Macro,
+    Component,
+    Net,
+    Pin,
+}
+
+impl GotoKind {
+    /// Badge letter and color shown in front of a result
+    pub fn icon(self) -> (&'static str, egui::Color32) {
+        match self {
+            GotoKind::Macro => ("M", egui::Color32::from_rgb(156, 39, 176)),
+            GotoKind::Component => ("C", egui::Color32::from_rgb(33, 150, 243)),
+            GotoKind::Net => ("N", egui::Color32::from_rgb(76, 175, 80)),
+            GotoKind::Pin => ("P", egui::Color32::from_rgb(255, 152, 0)),
+        }
+    }
+}
+
+/// One searchable object
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GotoTarget {
+    pub kind: GotoKind,
+    /// Raw name as stored in the LEF or DEF data
+    pub name: String,
+}
+
+/// Go to object dialog state and rendering
+#[derive(Default)]
+pub struct GotoDialog {
+    /// Whether the dialog is currently shown
+    pub visible: bool,
+    query: String,
+    targets: Vec<GotoTarget>,
+    /// (score, index into `targets`) of the current matches, best first
+    matches: Vec<(i32, usize)>,
+    highlighted: usize,
+    focus_query: bool,
+}
+
+impl GotoDialog {
+    /// Create a new go to object dialog
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the dialog over `targets`, keeping the last query
+    pub fn open(&mut self, targets: Vec<GotoTarget>) {
+        self.visible = true;
+        self.targets = targets;
+        self.focus_query = true;
+        self.update_matches();
+    }
+
+    fn update_matches(&mut self) {
+        self.matches = self
+            .targets
+            .iter()
+            .enumerate()
+            .filter_map(|(i, t)| fuzzy_score(&t.name, &self.query).map(|score| (score, i)))
+            .collect();
+        // Best score first, then shorter and alphabetical names
+        self.matches.sort_by(|a, b| {
+            let (ta, tb) = (&self.targets[a.1].name, &self.targets[b.1].name);
+            b.0.cmp(&a.0).then(ta.len().cmp(&tb.len())).then(ta.cmp(tb))
+        });
+        self.matches.truncate(MAX_RESULTS);
+        self.highlighted = 0;
+    }
+
+    /// Render the dialog, returning the chosen object
+    pub fn render(&mut self, ctx: &egui::Context) -> Option<GotoTarget> {
+        if !self.visible {
+            return None;
+        }
+
+        let mut open = true;
+        let mut chosen = None;
+
+        egui::Window::new("Go to Object")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(380.0)
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 60.0))
+            .show(ctx, |ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.query)
+                        .hint_text("Macro, component, net or pin name")
+                        .desired_width(f32::INFINITY),
+                );
+                if std::mem::take(&mut self.focus_query) {
+                    response.request_focus();
+                }
+                if response.changed() {
+                    self.update_matches();
+                }
+
+                let (up, down, enter) = ui.input(|i| {
+                    (
+                        i.key_pressed(egui::Key::ArrowUp),
+                        i.key_pressed(egui::Key::ArrowDown),
+                        i.key_pressed(egui::Key::Enter),
+                    )
+                });
+                if down && self.highlighted + 1 < self.matches.len() {
+                    self.highlighted += 1;
+                }
+                if up {
+                    self.highlighted = self.highlighted.saturating_sub(1);
+                }
+                if enter {
+                    chosen = self
+                        .matches
+                        .get(self.highlighted)
+                        .map(|&(_, i)| self.targets[i].clone());
+                }
+
+                ui.separator();
+                if self.matches.is_empty() {
+                    ui.label("No matching objects");
+                    return;
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(320.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        for (row, &(_, i)) in self.matches.iter().enumerate() {
+                            let target = &self.targets[i];
+                            let (letter, color) = target.kind.icon();
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    egui::RichText::new(letter)
+                                        .monospace()
+                                        .strong()
+                                        .color(egui::Color32::WHITE)
+                                        .background_color(color),
+                                );
+                                let label = identifier::unescape(&target.name);
+                                let response =
+                                    ui.selectable_label(row == self.highlighted, label.as_ref());
+                                if row == self.highlighted && (up || down) {
+                                    response.scroll_to_me(None);
+                                }
+                                if response.clicked() {
+                                    chosen = Some(target.clone());
+                                }
+                            });
+                        }
+                    });
+            });
+
+        if !open || chosen.is_some() || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.visible = false;
+        }
+
+        chosen
+    }
+}
+
+/// Score of `name` for `query`, None when the query characters do not all
+/// appear in order
+///
+/// Matching is case-insensitive on the unescaped name. Runs of consecutive
+/// characters, matches at the start of the name or of a hierarchy level and
+/// short names score higher.
+pub fn fuzzy_score(name: &str, query: &str) -> Option<i32> {
+    let name: Vec<char> = identifier::unescape(name).to_lowercase().chars().collect();
+    let query = query.to_lowercase();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+
+    for q in query.chars().filter(|c| !c.is_whitespace()) {
+        let position = next + name[next..].iter().position(|&c| c == q)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == position) {
+            score += 5;
+        }
+        if position == 0 || matches!(name[position - 1], '/' | '_' | '.' | '[') {
+            score += 3;
+        }
+        previous = Some(position);
+        next = position + 1;
+    }
+    Some(score * 16 - name.len() as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score_order() {
+        assert!(fuzzy_score("u1/inv_3", "u1inv").is_some());
+        assert!(fuzzy_score("u1/inv_3", "vni").is_none());
+        assert!(fuzzy_score(r"\u_top/reg\[3\]", "REG[3]").is_some());
+        assert!(fuzzy_score("anything", "").is_some());
+
+        // Consecutive and word-start matches rank first
+        let exact = fuzzy_score("clk_buf", "clk").unwrap();
+        let spread = fuzzy_score("c_l_k_buf", "clk").unwrap();
+        assert!(exact > spread);
+        let short = fuzzy_score("INVX1", "inv").unwrap();
+        let long = fuzzy_score("INVX1_LONG_NAME", "inv").unwrap();
+        assert!(short > long);
+    }
+}
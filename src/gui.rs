@@ -2,21 +2,83 @@
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
 use eframe::egui;
-use lyon_tessellation::math::{point, Point};
-use lyon_tessellation::path::Path as LyonPath;
-use lyon_tessellation::{BuffersBuilder, FillOptions, FillTessellator, FillVertex, VertexBuffers};
-use rfd::FileDialog;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
 
-use crate::def::{reader::DefReader, Def};
+use crate::camera::{Camera, DpiStyle, ModeCameras, MAX_ZOOM, MIN_ZOOM};
+use crate::charts_panel::ChartsPanel;
+use crate::checks::{self, CheckOptions, Finding};
+use crate::checks_panel::ChecksPanel;
+use crate::component_pins::{ComponentPins, FlightLineStyle};
+use crate::component_table::{render_component_inspector, ComponentTable};
+use crate::def::arrays::{detect_arrays, InstanceArray};
+use crate::def::eco::EcoLog;
+use crate::def::estimate::{available_memory, format_bytes, prescan_def, MemoryEstimate};
+use crate::def::layer_usage;
+use crate::def::utilization::Utilization;
+use crate::def::{self, reader::DefReader, Def, DefParseOptions};
+use crate::dual_pane::DualPaneView;
+use crate::edits_panel::{EditAction, EditsPanel};
 use crate::export::{self, VoltageConfig};
-use crate::lef::{reader::LefReader, Lef};
+use crate::file_watch::{FileWatcher, SETTLE_TIME};
+use crate::gds;
+use crate::gds_dialog::{GdsExportDialog, GdsExportRequest};
+use crate::geometry::{merge_supply_pin_rects, orient_point, Bounds, MacroTransform};
+use crate::goto_dialog::{GotoDialog, GotoKind, GotoTarget};
+use crate::hit_test::{self, DesignIndex, Hit};
+use crate::identifier;
+use crate::image_export_dialog::{ImageExportDialog, ImageExportRequest, ImageFormat, ImageRegion};
+use crate::isolation::{Isolation, IsolationAction};
+use crate::issues_panel::IssuesPanel;
+use crate::label_buckets::{Label, LabelCache};
+use crate::layer_alias_dialog::LayerAliasDialog;
+use crate::layer_config::LayerConfig;
+use crate::layer_keys::{macro_shape_layers, LayerKind, VisibleLayerKeys};
+use crate::layer_solo::SoloStack;
+use crate::layer_tree;
+use crate::lef::footprint::{self, FootprintFamily};
+use crate::lef::layer_alias::LayerAliases;
+use crate::lef::{self, reader::LefReader, Lef};
+use crate::lef_diff_panel::LefDiffPanel;
+use crate::loader::{run_guarded, timeout_message, LOADER_TIMEOUT};
+use crate::macro_source_dialog::{MacroSourceDialog, MacroSourceEdit};
+use crate::macro_thumbnail;
+use crate::min_area_dialog::MinAreaDialog;
+use crate::mouse_bindings::{DragAction, MouseBindings, WheelAction};
+use crate::net_inspector::render_net_inspector;
+use crate::object_flags::ObjectFlags;
+use crate::overlay::{OverlayTransformDialog, ScreenTransform, SourcePaint, SourceTransform};
+use crate::pad_label;
+use crate::permalink::{self, PermalinkDialog, ViewState};
+use crate::pin_lint_dialog::PinLintDialog;
+use crate::placement_diff_panel::PlacementDiffPanel;
+use crate::platform::{self, FileDialog};
+use crate::png_export::OffscreenScene;
+use crate::precompute::{Precompute, PrecomputeInput};
+use crate::profiler_window::ProfilerWindow;
+use crate::profiling;
+use crate::progress::Progress;
+use crate::recent_files::{FileKind, RecentFiles};
+use crate::reproducer;
+use crate::reproducer_dialog::{ReproducerDialog, ReproducerRequest, ReproducerSource};
+use crate::resource_panel::{heat_color, ResourcePanel};
+use crate::routing_resources::ResourceMap;
+use crate::ruler::{self, Measurement, Ruler};
+use crate::script::{self, ScriptAction, ScriptDesign};
+use crate::script_console::ScriptConsole;
+use crate::search_bar::{SearchAction, SearchBar};
+use crate::session::{self, PanelLayout, RestoreStep, Session, SessionRestore, WorkspaceLayout};
+use crate::status_bar::{BackgroundTask, StatusBar, StatusInfo};
+use crate::tcl_selection::{TclDialect, TclSelection};
+use crate::underlay::{Underlay, UnderlayAction, UnderlayDialog};
+use crate::units::UnitsCheck;
+use crate::view_policy::{def_view_key, lef_view_key, AutoFit, LoadView, SavedView, ViewPolicy};
 use crate::voltage_dialog::VoltageDialog;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
 use std::sync::mpsc;
-use std::thread;
-use std::time::{Duration, Instant};
+use std::time::Duration;
+use web_time::Instant;
 
 /// File loading state
 #[derive(Debug, Clone, Default)]
@@ -27,7 +89,12 @@ enum LoadingState {
         file_type: String,
         file_name: String,
         start_time: Instant,
+        /// When a loader last reported progress or a result, the watchdog
+        /// times stalls from here
+        last_activity: Instant,
         show_progress: bool,
+        /// Parser progress of each file being loaded
+        progress: BTreeMap<String, Progress>,
     },
 }
 
@@ -38,6 +105,19 @@ enum LoadingMessage {
     DefLoaded(Box<Result<Def, String>>, String),      // Result and file path
     LefFilesSelected(Vec<String>),                    // File paths from dialog (empty if cancelled)
     DefFileSelected(Option<String>),                  // File path from dialog (None if cancelled)
+    DefMemoryWarning(Box<DefMemoryWarning>),          // Pre-scan says the DEF likely won't fit
+    Progress(String, Progress),                       // File path and its parser progress
+    #[cfg(target_arch = "wasm32")]
+    FilesRead(Vec<(String, Vec<u8>)>), // Names and contents of files picked in the browser
+}
+
+/// DEF load held back because the pre-scan estimate exceeds available memory
+#[derive(Debug)]
+struct DefMemoryWarning {
+    path: String,
+    estimate: MemoryEstimate,
+    available: u64,
+    options: DefParseOptions, // Section filter chosen in the warning dialog
 }
 
 /// Edge proximity detection result
@@ -50,6 +130,24 @@ enum EdgeProximity {
     None,       // Not near any edge
 }
 
+/// Screen pitch in points below which an instance array is one block
+const ARRAY_COLLAPSE_PITCH: f32 = 8.0;
+
+/// Fewest identical instances that make an array
+const ARRAY_MIN_MEMBERS: usize = 64;
+
+/// Instances and pins of the selected nets
+const NET_PROBE_HIGHLIGHT: egui::Color32 = egui::Color32::from_rgb(0, 230, 255);
+
+/// Dimension line of the ruler
+const RULER_COLOR: egui::Color32 = egui::Color32::from_rgb(0, 255, 170);
+
+/// Side in microns of the area framed around a searched coordinate
+const SEARCH_POINT_SPAN: f32 = 20.0;
+
+/// Outline of the highlighted DEF group and its region
+const REGION_HIGHLIGHT: egui::Color32 = egui::Color32::from_rgb(255, 230, 0);
+
 /// Smart text positioning configuration
 #[derive(Debug, Clone)]
 struct TextPositioning {
@@ -58,27 +156,70 @@ struct TextPositioning {
     angle: f32, // Rotation angle in radians
 }
 
+/// Text drawn over the design, centered on a screen position
+type PlainText = (egui::Pos2, String, egui::FontId, egui::Color32);
+/// Text drawn over the design, anchored and turned
+type SmartText = (TextPositioning, String, egui::FontId, egui::Color32);
+
 /// Loaded LEF file with path and hash information
 #[derive(Clone)]
 struct LoadedLefFile {
     path: String,
     data: Lef,
     file_hash: String, // BLAKE3 hash of file content for deduplication and stable UI IDs
+    edited_sources: HashMap<String, String>, // macro name -> source text applied in the editor
+    transform: SourceTransform, // Overlay offset/rotation/scale in the LEF view
+}
+
+/// Color and stacking of a layer defined in a loaded technology LEF
+#[derive(Clone, Debug)]
+struct TechLayerStyle {
+    palette_layer: String, // Built-in layer whose color is reused, e.g. "M3" for the third routing layer
+    order: i32,            // Position in the technology stack, bottom first
+    width: Option<f64>,    // Default wire WIDTH in microns
+    summary: String,       // TYPE, DIRECTION, PITCH and WIDTH for the layer panel
 }
 
 /// Cache key for identifying tessellated macro shapes
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
 struct MeshCacheKey {
     macro_name: String,
-    shape_type: String, // "PIN" or "OBS"
-    layer_name: String,
+    /// Position of the shape in the order of [`macro_shape_layers`]
     shape_index: usize,
 }
 
-/// Pre-tessellated mesh in world coordinates (before zoom/pan transform)
+/// Tessellated shapes of each macro geometry by shape index, keyed by
+/// geometry so drawing looks meshes up by `&str` without building a key
+type MeshCache = HashMap<String, HashMap<usize, CachedMesh>>;
+
+/// Buffers of the LEF view loop, kept between frames so that drawing a
+/// shape reuses their capacity instead of allocating
+#[derive(Default)]
+struct RenderScratch {
+    mesh_key: String,
+    selection_key: String,
+    /// Layer order and index of the polygons of one PORT or OBS
+    polygon_order: Vec<(i32, usize)>,
+    points: Vec<egui::Pos2>,
+    /// Pins to outline after the macro's polygons are drawn
+    highlights: Vec<egui::Rect>,
+}
+
+impl RenderScratch {
+    /// `owner::name` key of the LEF pin and obstruction selections
+    fn selection_key(&mut self, owner: &str, name: &str) -> &str {
+        self.selection_key.clear();
+        self.selection_key.push_str(owner);
+        self.selection_key.push_str("::");
+        self.selection_key.push_str(name);
+        &self.selection_key
+    }
+}
+
+/// Pre-tessellated mesh in shape coordinates (before placement/zoom/pan transform)
 #[derive(Clone, Debug)]
 struct CachedMesh {
-    vertices: Vec<egui::Pos2>, // Triangle vertices in world space
+    vertices: Vec<egui::Pos2>, // Triangle vertices in shape space
     indices: Vec<u32>,
     color: egui::Color32,
 }
@@ -107,29 +248,206 @@ struct TessellationJob {
     color: egui::Color32,
 }
 
+/// Resolution of each image written by the batch PNG export
+const BATCH_PNG_SIZE: [u32; 2] = [1024, 1024];
+
+/// Progress of a batch per-macro PNG export
+///
+/// Runs over several frames: each macro is painted alone off-screen, fitted
+/// to [`BATCH_PNG_SIZE`], and written in the background before the next one
+/// is painted.
+struct BatchPngExport {
+    output_dir: std::path::PathBuf,
+    pending: Vec<(String, String)>, // Macro names and file names still to export, popped from the end
+    writing: Option<(String, mpsc::Receiver<Result<(), String>>)>, // Macro being written and its result
+    exported: usize,
+}
+
+/// Component being dragged in DEF edit mode
+struct ComponentDrag {
+    /// Index into `Def::components`
+    index: usize,
+    /// Location in the DEF before the drag, in database units
+    from: (f64, f64),
+    /// Outline before the drag, in microns
+    outline: Bounds,
+    /// Cursor position where the drag started, in microns
+    grab: (f64, f64),
+}
+
+/// Quick pin filter by direction, USE and placement status, empty sets
+/// match everything
+#[derive(Debug, Clone, Default)]
+struct PinFilter {
+    directions: std::collections::HashSet<&'static str>,
+    uses: std::collections::HashSet<&'static str>,
+    statuses: std::collections::HashSet<&'static str>,
+}
+
+impl PinFilter {
+    const DIRECTIONS: [&'static str; 3] = ["INPUT", "OUTPUT", "INOUT"];
+    const USES: [&'static str; 4] = ["SIGNAL", "POWER", "GROUND", "CLOCK"];
+    const STATUSES: [&'static str; 4] = ["FIXED", "PLACED", "COVER", "UNPLACED"];
+
+    fn is_active(&self) -> bool {
+        !self.directions.is_empty() || !self.uses.is_empty() || !self.statuses.is_empty()
+    }
+
+    /// Check the placement status of a DEF pin against the filter
+    fn matches_status(&self, status: &str) -> bool {
+        self.statuses.is_empty() || self.statuses.contains(status)
+    }
+
+    /// Check a DEF pin against the direction, USE and status filters
+    fn matches_def_pin(&self, pin: &crate::def::DefPin) -> bool {
+        self.matches(&pin.direction, &pin.use_type) && self.matches_status(pin.placement_status())
+    }
+
+    /// Check a pin against the filter, a missing USE counts as SIGNAL
+    fn matches(&self, direction: &str, use_type: &str) -> bool {
+        let use_type = if use_type.is_empty() {
+            "SIGNAL"
+        } else {
+            use_type
+        };
+        (self.directions.is_empty() || self.directions.contains(direction))
+            && (self.uses.is_empty() || self.uses.contains(use_type))
+    }
+
+    /// Render the filter as a row of toggle buttons, with the placement
+    /// status toggles for DEF pins
+    fn render(&mut self, ui: &mut egui::Ui, with_status: bool) {
+        ui.horizontal_wrapped(|ui| {
+            for direction in Self::DIRECTIONS {
+                let mut enabled = self.directions.contains(direction);
+                if ui.toggle_value(&mut enabled, direction).clicked() {
+                    if enabled {
+                        self.directions.insert(direction);
+                    } else {
+                        self.directions.remove(direction);
+                    }
+                }
+            }
+            ui.separator();
+            for use_type in Self::USES {
+                let mut enabled = self.uses.contains(use_type);
+                if ui.toggle_value(&mut enabled, use_type).clicked() {
+                    if enabled {
+                        self.uses.insert(use_type);
+                    } else {
+                        self.uses.remove(use_type);
+                    }
+                }
+            }
+            if with_status {
+                ui.separator();
+                for status in Self::STATUSES {
+                    let mut enabled = self.statuses.contains(status);
+                    if ui.toggle_value(&mut enabled, status).clicked() {
+                        if enabled {
+                            self.statuses.insert(status);
+                        } else {
+                            self.statuses.remove(status);
+                        }
+                    }
+                }
+            }
+            if self.is_active() && ui.small_button("X").on_hover_text("Clear filter").clicked() {
+                self.directions.clear();
+                self.uses.clear();
+                self.statuses.clear();
+            }
+        });
+    }
+}
+
+/// Quick net filter by routing PATTERN and shielding, an empty set matches
+/// every pattern
+#[derive(Debug, Clone, Default)]
+struct NetFilter {
+    patterns: std::collections::HashSet<&'static str>,
+    shielded_only: bool,
+}
+
+impl NetFilter {
+    const PATTERNS: [&'static str; 4] = ["BALANCED", "STEINER", "TRUNK", "WIREDLOGIC"];
+
+    fn is_active(&self) -> bool {
+        !self.patterns.is_empty() || self.shielded_only
+    }
+
+    /// Check a DEF net against the pattern and shielding filters
+    fn matches(&self, net: &def::DefNet) -> bool {
+        (self.patterns.is_empty() || self.patterns.contains(net.pattern.as_str()))
+            && (!self.shielded_only || net.shielded)
+    }
+
+    /// Render the filter as a row of toggle buttons
+    fn render(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal_wrapped(|ui| {
+            for pattern in Self::PATTERNS {
+                let mut enabled = self.patterns.contains(pattern);
+                if ui.toggle_value(&mut enabled, pattern).clicked() {
+                    if enabled {
+                        self.patterns.insert(pattern);
+                    } else {
+                        self.patterns.remove(pattern);
+                    }
+                }
+            }
+            ui.separator();
+            ui.toggle_value(&mut self.shielded_only, "SHIELDED")
+                .on_hover_text("Only nets with a SHIELDNET");
+            if self.is_active() && ui.small_button("X").on_hover_text("Clear filter").clicked() {
+                self.patterns.clear();
+                self.shielded_only = false;
+            }
+        });
+    }
+}
+
 pub struct LefDefViewer {
     lef_files: Vec<LoadedLefFile>,
     def_data: Option<Def>,
     def_file_path: Option<String>,
-    def_mode: bool, // True when DEF is loaded and active
+    def_mode: bool,            // True when DEF is loaded and active
+    layer_config: LayerConfig, // Default visibility of newly found layers
+    mode_cameras: ModeCameras,
     component_macro_map: std::collections::HashMap<String, String>, // Maps DEF component instance to LEF macro name
     missing_cells: std::collections::HashSet<String>, // LEF cells referenced in DEF but not found in any loaded LEF
+    physical_only_cells: std::collections::HashSet<String>, // DEF macros without logical function (fillers, taps, ...)
+    design_index: Option<DesignIndex>, // R-trees of the DEF for culling and hover, rebuilt when stale
+    precompute: Precompute, // Background pass after loading: instance counts, connectivity, index, thumbnails
+    hide_physical_only: bool,
+    include_cover_in_stats: bool,
+    layer_solo: SoloStack,
+    dual_pane: DualPaneView,
+    units_warning_dismissed: bool,
+    component_flags: ObjectFlags, // Hidden/locked DEF components by index
+    pin_flags: ObjectFlags,       // Hidden/locked DEF pins by index
     show_lef_details: bool,
     show_def_details: bool,
     zoom: f32,
     pan_x: f32,
     pan_y: f32,
+    dpi_style: DpiStyle, // Stroke and text sizes for the current scale factor
     error_message: Option<String>,
     success_message: Option<String>,
     selected_cells: std::collections::HashSet<String>,
     visible_layers: std::collections::HashSet<String>,
     all_layers: std::collections::HashSet<String>,
     show_layers_panel: bool,
+    /// Widths of the left and layers panels, kept between sessions
+    left_panel_width: f32,
+    layers_panel_width: f32,
     show_pin_text: bool,
     show_component_text: bool, // Show component instance names in DEF mode
     show_cell_details: bool,   // Show LEF cell internal details (PINs, OBS) in DEF mode
     fit_to_view_requested: bool,
+    focus_bounds_requested: Option<(f32, f32, f32, f32)>, // World bounds to frame on the next canvas pass
     fit_to_view_delay_frames: u8, // Delay fit to view by a few frames for UI stability
+    view_policy: ViewPolicy,
+    view_design: Option<String>, // View policy key of the design on the canvas
     // LEF related selection states
     selected_lef_pins: std::collections::HashSet<String>, // Format: "macro_name::pin_name"
     selected_lef_obs: std::collections::HashSet<String>,  // Format: "macro_name::obs_layer"
@@ -137,13 +455,94 @@ pub struct LefDefViewer {
     selected_components: std::collections::HashSet<String>,
     selected_pins: std::collections::HashSet<String>,
     selected_nets: std::collections::HashSet<String>,
+    /// Shell the selection is copied as Tcl for
+    tcl_dialect: TclDialect,
+    verilog_stub_options: export::VerilogStubOptions,
+    spice_stub_options: export::SpiceStubOptions,
+    // Component table and inspector
+    component_table: ComponentTable,
+    inspected_component: Option<String>,
+    inspected_net: Option<String>,
+    isolation: Option<Isolation>, // Component drawn alone with its surroundings
+    // Pin direction/USE quick filters
+    lef_pin_filter: PinFilter,
+    def_pin_filter: PinFilter,
     show_components: bool,
     show_pins: bool,
     show_nets: bool,
+    /// Routing PATTERN and shielding filter of the drawn nets
+    net_filter: NetFilter,
+    show_flight_lines: bool, // Airwires of the selected nets
+    flight_line_style: FlightLineStyle,
+    /// Skip flight lines of nets that already have wiring
+    flight_lines_unrouted_only: bool,
+    /// Highlight the selected nets and dim the rest of the design
+    probe_selected_nets: bool,
     show_special_nets: bool,
+    hidden_special_nets: std::collections::HashSet<String>, // Special nets switched off in the DEF Structure panel
     show_diearea: bool,
+    show_rows: bool,
+    show_tracks: bool,
+    show_regions: bool,
+    show_fills: bool,
+    /// Draw regular instance arrays as one block when their cells get small
+    collapse_arrays: bool,
+    instance_arrays: Vec<InstanceArray>,
+    /// Array of each DEF component, by component index
+    component_array: Vec<Option<usize>>,
+    show_scan_chains: bool,
+    /// Shade the DENSITY windows of the LEF macros by their density
+    show_density: bool,
+    highlighted_group: Option<String>, // DEF group whose members are outlined
     // Voltage configuration for Liberty export
     voltage_dialog: VoltageDialog,
+    macro_source_dialog: MacroSourceDialog,
+    goto_dialog: GotoDialog,
+    search_bar: SearchBar,
+    ruler: Ruler,
+    mouse_bindings: MouseBindings,
+    // Reopen the files of this session on the next start
+    restore_session: bool,
+    session_restore: Option<SessionRestore>,
+    recent_files: RecentFiles,
+    // DEF to load once the LEF files loading with it are in
+    queued_def_load: Option<String>,
+    // Same for a DEF handed over as bytes, by the browser
+    queued_def_bytes: Option<(String, Arc<[u8]>)>,
+    // Parse loaded files again when they change on disk
+    watch_files: bool,
+    file_watcher: Option<FileWatcher>,
+    // Changed files waiting to be parsed again, and the ones being parsed
+    reload_queue: Vec<String>,
+    reloading: HashSet<String>,
+    // Screen position where the zoom box drag started
+    zoom_box_origin: Option<egui::Pos2>,
+    permalink_dialog: PermalinkDialog,
+    gds_dialog: GdsExportDialog,
+    reproducer_dialog: ReproducerDialog,
+    def_transform: SourceTransform, // Overlay offset/rotation/scale of the DEF
+    def_memory_warning: Option<DefMemoryWarning>,
+    checks_panel: ChecksPanel,
+    issues_panel: IssuesPanel,
+    script_console: ScriptConsole,
+    // Fill colors of components set by scripts
+    script_colors: HashMap<String, egui::Color32>,
+    lef_diff_panel: LefDiffPanel,
+    placement_diff_panel: PlacementDiffPanel,
+    label_cache: LabelCache,
+    charts_panel: ChartsPanel,
+    resource_panel: ResourcePanel,
+    profiler_window: ProfilerWindow,
+    pin_lint_dialog: PinLintDialog,
+    min_area_dialog: MinAreaDialog,
+    /// Library layer names mapped onto technology layers
+    layer_aliases: LayerAliases,
+    layer_alias_dialog: LayerAliasDialog,
+    pending_loads: usize, // Loader threads that have not reported a result yet
+    overlay_dialog: OverlayTransformDialog,
+    // Background image under the DEF view
+    underlay: Option<Underlay>,
+    underlay_dialog: UnderlayDialog,
     voltage_config: VoltageConfig,
     // Async loading state
     loading_state: LoadingState,
@@ -151,13 +550,39 @@ pub struct LefDefViewer {
     // Macro search/filter
     macro_filter: String,
     // Animation timestamp for blink effect
-    start_time: std::time::Instant,
+    start_time: Instant,
     // Progressive rendering
-    mesh_cache: Arc<RwLock<HashMap<MeshCacheKey, CachedMesh>>>,
+    mesh_cache: Arc<RwLock<MeshCache>>,
+    render_scratch: RenderScratch,
     render_job_sender: Option<mpsc::Sender<TessellationJob>>,
     render_result_receiver: Option<mpsc::Receiver<RenderMessage>>,
-    tessellated_macros: Arc<Mutex<std::collections::HashSet<String>>>, // Track which macros have been fully tessellated
+    tessellation_queue: Arc<AtomicUsize>, // Jobs sent to the worker and not yet picked up
+    tessellated_macros: Arc<Mutex<std::collections::HashSet<String>>>, // Track which geometry keys have been fully tessellated
+    macro_geometry_keys: HashMap<String, String>, // Maps macro name to geometry hash, shared by identical macros
+    merged_macros: HashMap<String, crate::lef::LefMacro>, // Macro name -> copy with merged supply pin rects, for rendering
+    footprint_families: Vec<FootprintFamily>, // Loaded macros grouped by interchangeable footprint
+    tech_layer_styles: HashMap<String, TechLayerStyle>, // Layers of the loaded technology LEFs
+    def_edit_mode: bool,                      // Allow cell swaps and moves on DEF components
+    eco_log: EcoLog,                          // Cell swaps and moves applied to the loaded DEF
+    edits_panel: EditsPanel,
+    component_drag: Option<ComponentDrag>,
     progressive_rendering_enabled: bool, // Toggle for progressive rendering feature
+    // Help windows
+    show_orientation_legend: bool,
+    show_preferences: bool,
+    // Screen rect of the canvas in the last frame, used to crop screenshots
+    canvas_rect: egui::Rect,
+    batch_png_export: Option<BatchPngExport>,
+    // SVG file to write from the next canvas frame
+    svg_export_path: Option<std::path::PathBuf>,
+    label_export_path: Option<std::path::PathBuf>, // labels written on the next frame
+    image_export_dialog: ImageExportDialog,
+    // PNG file to render off-screen from the next canvas frame
+    image_export: Option<(ImageExportRequest, std::path::PathBuf)>,
+    image_export_receiver: Option<mpsc::Receiver<Result<String, String>>>, // Success or error message of the image written in the background
+    status_bar: StatusBar,
+    // Cursor position over the canvas in microns, for the status bar
+    cursor_position: Option<(f64, f64)>,
 }
 
 impl LefDefViewer {
@@ -177,19 +602,43 @@ impl LefDefViewer {
         Ok(hasher.finalize().to_hex().to_string())
     }
 
+    /// Viewer resuming the session stored by the last run
+    pub fn with_storage(storage: Option<&dyn eframe::Storage>) -> Self {
+        let mut viewer = Self::new();
+        if let Some(session) =
+            storage.and_then(|storage| eframe::get_value::<Session>(storage, session::STORAGE_KEY))
+        {
+            viewer.resume_session(session);
+        }
+        viewer
+    }
+
     pub fn new() -> Self {
-        Self {
+        let mut viewer = Self {
             lef_files: Vec::new(),
             def_data: None,
             def_file_path: None,
             def_mode: false,
+            layer_config: LayerConfig::default(),
+            mode_cameras: ModeCameras::default(),
             component_macro_map: std::collections::HashMap::new(),
             missing_cells: std::collections::HashSet::new(),
+            physical_only_cells: std::collections::HashSet::new(),
+            design_index: None,
+            precompute: Precompute::new(),
+            hide_physical_only: false,
+            include_cover_in_stats: false,
+            layer_solo: SoloStack::new(),
+            dual_pane: DualPaneView::new(),
+            units_warning_dismissed: false,
+            component_flags: ObjectFlags::new(),
+            pin_flags: ObjectFlags::new(),
             show_lef_details: false,
             show_def_details: false,
             zoom: 1.0,
             pan_x: 0.0,
             pan_y: 0.0,
+            dpi_style: DpiStyle::default(),
             error_message: None,
             success_message: None,
             selected_cells: std::collections::HashSet::new(),
@@ -201,11 +650,16 @@ impl LefDefViewer {
             },
             all_layers: std::collections::HashSet::new(),
             show_layers_panel: true,
+            left_panel_width: WorkspaceLayout::default().left_panel_width,
+            layers_panel_width: WorkspaceLayout::default().layers_panel_width,
             show_pin_text: true,
             show_component_text: true,
             show_cell_details: true, // Default: enabled
             fit_to_view_requested: false,
+            focus_bounds_requested: None,
             fit_to_view_delay_frames: 0,
+            view_policy: ViewPolicy::new(),
+            view_design: None,
             // LEF related selection states
             selected_lef_pins: std::collections::HashSet::new(),
             selected_lef_obs: std::collections::HashSet::new(),
@@ -213,13 +667,78 @@ impl LefDefViewer {
             selected_components: std::collections::HashSet::new(),
             selected_pins: std::collections::HashSet::new(),
             selected_nets: std::collections::HashSet::new(),
+            tcl_dialect: TclDialect::default(),
+            verilog_stub_options: export::VerilogStubOptions::default(),
+            spice_stub_options: export::SpiceStubOptions::default(),
+            // Component table and inspector
+            component_table: ComponentTable::new(),
+            inspected_component: None,
+            inspected_net: None,
+            isolation: None,
+            // Pin direction/USE quick filters
+            lef_pin_filter: PinFilter::default(),
+            def_pin_filter: PinFilter::default(),
             show_components: true,
             show_pins: true,
             show_nets: true,
+            net_filter: NetFilter::default(),
+            show_flight_lines: true,
+            flight_line_style: FlightLineStyle::default(),
+            flight_lines_unrouted_only: true,
+            probe_selected_nets: true,
             show_special_nets: true,
+            hidden_special_nets: std::collections::HashSet::new(),
             show_diearea: true,
+            show_rows: true,
+            show_tracks: false,
+            show_regions: true,
+            show_fills: false,
+            collapse_arrays: true,
+            instance_arrays: Vec::new(),
+            component_array: Vec::new(),
+            show_scan_chains: true,
+            show_density: false,
+            highlighted_group: None,
             // Voltage configuration for Liberty export
             voltage_dialog: VoltageDialog::new(),
+            macro_source_dialog: MacroSourceDialog::new(),
+            goto_dialog: GotoDialog::new(),
+            search_bar: SearchBar::new(),
+            ruler: Ruler::new(),
+            mouse_bindings: MouseBindings::default(),
+            restore_session: true,
+            session_restore: None,
+            recent_files: RecentFiles::default(),
+            queued_def_load: None,
+            queued_def_bytes: None,
+            watch_files: false,
+            file_watcher: None,
+            reload_queue: Vec::new(),
+            reloading: HashSet::new(),
+            zoom_box_origin: None,
+            permalink_dialog: PermalinkDialog::new(),
+            gds_dialog: GdsExportDialog::new(),
+            reproducer_dialog: ReproducerDialog::new(),
+            def_transform: SourceTransform::default(),
+            def_memory_warning: None,
+            checks_panel: ChecksPanel::new(),
+            issues_panel: IssuesPanel::new(),
+            script_console: ScriptConsole::new(),
+            script_colors: HashMap::new(),
+            lef_diff_panel: LefDiffPanel::new(),
+            placement_diff_panel: PlacementDiffPanel::new(),
+            label_cache: LabelCache::new(),
+            charts_panel: ChartsPanel::new(),
+            resource_panel: ResourcePanel::new(),
+            profiler_window: ProfilerWindow::new(),
+            pin_lint_dialog: PinLintDialog::new(),
+            min_area_dialog: MinAreaDialog::new(),
+            layer_aliases: LayerAliases::new(),
+            layer_alias_dialog: LayerAliasDialog::new(),
+            pending_loads: 0,
+            overlay_dialog: OverlayTransformDialog::new(),
+            underlay: None,
+            underlay_dialog: UnderlayDialog::new(),
             voltage_config: VoltageConfig::default(),
             // Async loading state
             loading_state: LoadingState::Idle,
@@ -227,17 +746,62 @@ impl LefDefViewer {
             // Macro search/filter
             macro_filter: String::new(),
             // Animation timestamp for blink effect
-            start_time: std::time::Instant::now(),
+            start_time: Instant::now(),
             // Progressive rendering
             mesh_cache: Arc::new(RwLock::new(HashMap::new())),
+            render_scratch: RenderScratch::default(),
             render_job_sender: None,
+            tessellation_queue: Arc::new(AtomicUsize::new(0)),
             render_result_receiver: None,
             tessellated_macros: Arc::new(Mutex::new(std::collections::HashSet::new())),
+            macro_geometry_keys: HashMap::new(),
+            merged_macros: HashMap::new(),
+            footprint_families: Vec::new(),
+            tech_layer_styles: HashMap::new(),
+            def_edit_mode: false,
+            eco_log: EcoLog::new(),
+            edits_panel: EditsPanel::new(),
+            component_drag: None,
             progressive_rendering_enabled: true, // Enabled by default
-        }
+            // Help windows
+            show_orientation_legend: false,
+            show_preferences: false,
+            canvas_rect: egui::Rect::NOTHING,
+            batch_png_export: None,
+            svg_export_path: None,
+            label_export_path: None,
+            image_export_dialog: ImageExportDialog::new(),
+            image_export: None,
+            image_export_receiver: None,
+            status_bar: StatusBar::new(),
+            cursor_position: None,
+        };
+        viewer.reload_layer_config();
+        viewer
     }
 
     fn check_loading_progress(&mut self, ctx: &egui::Context) {
+        // Watchdog: give up on loaders that stopped reporting back
+        if let LoadingState::Loading {
+            file_type,
+            file_name,
+            last_activity,
+            ..
+        } = &self.loading_state
+        {
+            let stalled = last_activity.elapsed();
+            if self.pending_loads > 0 && stalled >= LOADER_TIMEOUT {
+                let message = timeout_message(file_type, file_name, stalled);
+                log::warn!("{message}");
+                self.error_message = Some(message);
+                // Dropping the receiver makes late results go nowhere
+                self.loading_receiver = None;
+                self.pending_loads = 0;
+                self.loading_state = LoadingState::Idle;
+                return;
+            }
+        }
+
         // Check if we need to show progress bar (after 500ms)
         if let LoadingState::Loading {
             start_time,
@@ -267,7 +831,21 @@ impl LefDefViewer {
                         match message {
                             LoadingMessage::LefLoaded(result, path) => {
                                 log::info!("Received LefLoaded message for: {}", path);
+                                self.pending_loads = self.pending_loads.saturating_sub(1);
+                                if let LoadingState::Loading {
+                                    progress,
+                                    last_activity,
+                                    ..
+                                } = &mut self.loading_state
+                                {
+                                    progress.entry(path.clone()).or_default().fraction = 1.0;
+                                    *last_activity = Instant::now();
+                                }
+                                let reload = self.reloading.remove(&path);
                                 match result {
+                                    Ok((lef, hash)) if reload => {
+                                        self.reload_lef_file_sync(lef, path, hash);
+                                    }
                                     Ok((lef, hash)) => {
                                         self.load_lef_file_sync(lef, path, hash);
                                     }
@@ -277,7 +855,16 @@ impl LefDefViewer {
                                 }
                             }
                             LoadingMessage::DefLoaded(result, path) => {
+                                self.pending_loads = self.pending_loads.saturating_sub(1);
+                                let reload = self.reloading.remove(&path);
                                 match *result {
+                                    Ok(def) if reload => {
+                                        self.reload_keeping_view(|viewer| {
+                                            viewer.load_def_file_sync(def, path.clone())
+                                        });
+                                        self.status_bar
+                                            .hint(format!("Reloaded {path} after it changed"));
+                                    }
                                     Ok(def) => {
                                         self.load_def_file_sync(def, path);
                                     }
@@ -317,7 +904,9 @@ impl LefDefViewer {
                                         file_type: "LEF".to_string(),
                                         file_name: display_name,
                                         start_time: Instant::now(),
+                                        last_activity: Instant::now(),
                                         show_progress: false,
+                                        progress: BTreeMap::new(),
                                     };
                                 }
 
@@ -353,15 +942,31 @@ impl LefDefViewer {
                                     let tx_clone = tx.clone();
                                     let hash_clone = file_hash.clone();
                                     log::info!("Starting loading thread for: {}", path);
-                                    thread::spawn(move || {
-                                        let reader = LefReader::new();
-                                        let result = match reader.read(&path) {
-                                            Ok(lef) => Ok((lef, hash_clone)),
-                                            Err(e) => Err(format!("Failed to load LEF file: {e}")),
-                                        };
+                                    self.pending_loads += 1;
+                                    if let LoadingState::Loading { progress, .. } =
+                                        &mut self.loading_state
+                                    {
+                                        progress.insert(path.clone(), Progress::default());
+                                    }
+                                    let progress = progress_sender(&tx, &path);
+                                    let spawned = platform::spawn("lef-loader", move || {
+                                        let result = run_guarded(&path, || {
+                                            let reader = LefReader::new().with_progress(progress);
+                                            match reader.read(&path) {
+                                                Ok(lef) => Ok((lef, hash_clone)),
+                                                Err(e) => {
+                                                    Err(format!("Failed to load LEF file: {e}"))
+                                                }
+                                            }
+                                        });
                                         let _ = tx_clone
                                             .send(LoadingMessage::LefLoaded(result, path.clone()));
                                     });
+                                    if let Err(e) = spawned {
+                                        self.pending_loads = self.pending_loads.saturating_sub(1);
+                                        self.error_message =
+                                            Some(format!("Failed to start the LEF loader: {e}"));
+                                    }
                                 }
 
                                 // Replace receiver with new one for loading threads
@@ -369,6 +974,33 @@ impl LefDefViewer {
                                 keep_receiver = false; // Don't restore old receiver
                                 break; // Exit loop, new receiver will be used in next frame
                             }
+                            LoadingMessage::DefMemoryWarning(warning) => {
+                                self.pending_loads = self.pending_loads.saturating_sub(1);
+                                self.def_memory_warning = Some(*warning);
+                                self.loading_state = LoadingState::Idle;
+                                keep_receiver = false;
+                            }
+                            LoadingMessage::Progress(path, file_progress) => {
+                                if let LoadingState::Loading {
+                                    progress,
+                                    last_activity,
+                                    ..
+                                } = &mut self.loading_state
+                                {
+                                    progress.insert(path, file_progress);
+                                    *last_activity = Instant::now();
+                                }
+                            }
+                            #[cfg(target_arch = "wasm32")]
+                            LoadingMessage::FilesRead(files) => {
+                                keep_receiver = false;
+                                let files = files
+                                    .into_iter()
+                                    .map(|(name, bytes)| (name, Arc::from(bytes)))
+                                    .collect();
+                                self.open_file_bytes(files);
+                                break;
+                            }
                             LoadingMessage::DefFileSelected(path_opt) => {
                                 if let Some(path) = path_opt {
                                     self.start_def_file_loading(path);
@@ -386,6 +1018,14 @@ impl LefDefViewer {
                     Err(mpsc::TryRecvError::Disconnected) => {
                         // Channel disconnected, all senders dropped, all files loaded
                         log::info!("All file loading threads completed");
+                        if self.pending_loads > 0 {
+                            // A loader died without reporting (e.g. aborted)
+                            self.error_message = Some(format!(
+                                "{} loader thread(s) exited without a result",
+                                self.pending_loads
+                            ));
+                            self.pending_loads = 0;
+                        }
                         self.loading_state = LoadingState::Idle;
                         keep_receiver = false;
                         break;
@@ -400,71 +1040,69 @@ impl LefDefViewer {
         }
     }
 
-    fn load_lef_file_sync(&mut self, lef: Lef, path: String, file_hash: String) {
+    fn load_lef_file_sync(&mut self, mut lef: Lef, path: String, file_hash: String) {
         // This is the synchronized version of LEF loading (after async completion)
         // Add new LEF file to the collection (append mode, not replace)
         log::info!("Loading LEF file into GUI: {}", path);
+        self.recent_files.add(&path);
         log::info!("Current LEF count: {}", self.lef_files.len());
 
+        let renamed = self.layer_aliases.apply(&mut lef);
+        if renamed > 0 {
+            log::info!("Moved {renamed} shapes of {path} onto technology layers");
+        }
+
         // If this is the first LEF file, ensure virtual layers are present
         if self.lef_files.is_empty() {
-            self.all_layers.insert("OUTLINE".to_string());
-            self.visible_layers.insert("OUTLINE".to_string());
-            self.all_layers.insert("LABEL".to_string());
-            if self.show_pin_text {
-                self.visible_layers.insert("LABEL".to_string());
-            }
+            self.discover_layer("OUTLINE".to_string(), true);
+            self.discover_layer("LABEL".to_string(), self.show_pin_text);
         }
 
         // Collect layers from the new LEF file
         for macro_def in &lef.macros {
-            for pin in &macro_def.pins {
-                for port in &pin.ports {
-                    for rect in &port.rects {
-                        let detailed_layer = format!("{}.PIN", rect.layer);
-                        self.all_layers.insert(detailed_layer.clone());
-                        // Make power/ground pins visible by default
-                        if pin.use_type == "POWER" || pin.use_type == "GROUND" {
-                            self.visible_layers.insert(detailed_layer);
-                        }
-                    }
-                    for polygon in &port.polygons {
-                        let detailed_layer = format!("{}.PIN", polygon.layer);
-                        self.all_layers.insert(detailed_layer.clone());
-                        // Make power/ground pins visible by default
-                        if pin.use_type == "POWER" || pin.use_type == "GROUND" {
-                            self.visible_layers.insert(detailed_layer);
-                        }
-                    }
-                }
-            }
+            self.register_macro_layers(macro_def);
+        }
 
-            // Add obstruction layers
-            for obs in &macro_def.obs {
-                for rect in &obs.rects {
-                    let detailed_layer = format!("{}.OBS", rect.layer);
-                    self.all_layers.insert(detailed_layer);
-                    // OBS layers are hidden by default
-                }
-                for polygon in &obs.polygons {
-                    let detailed_layer = format!("{}.OBS", polygon.layer);
-                    self.all_layers.insert(detailed_layer);
-                    // OBS layers are hidden by default
-                }
+        // Record geometry hashes so identical macros share tessellated meshes
+        let known_geometries: std::collections::HashSet<&String> =
+            self.macro_geometry_keys.values().collect();
+        let mut shared_count = 0;
+        let mut new_keys = Vec::with_capacity(lef.macros.len());
+        for macro_def in &lef.macros {
+            let geometry_key = macro_def.geometry_hash();
+            if known_geometries.contains(&geometry_key)
+                || new_keys.iter().any(|(_, key)| *key == geometry_key)
+            {
+                shared_count += 1;
             }
+            match merge_supply_pin_rects(macro_def) {
+                Some(merged) => self.merged_macros.insert(macro_def.name.clone(), merged),
+                None => self.merged_macros.remove(&macro_def.name),
+            };
+            new_keys.push((macro_def.name.clone(), geometry_key));
         }
+        self.macro_geometry_keys.extend(new_keys);
+        log::info!(
+            "{} of {} macros share geometry with another macro",
+            shared_count,
+            lef.macros.len()
+        );
 
         // Add the new LEF file to collection
         self.lef_files.push(LoadedLefFile {
             path: path.clone(),
             data: lef,
             file_hash,
+            edited_sources: HashMap::new(),
+            transform: SourceTransform::default(),
         });
-        log::info!(
-            "Successfully loaded LEF file: {}, total LEF files: {}",
-            path,
+        self.status_bar.hint(format!(
+            "Loaded LEF file {path}, {} LEF files open",
             self.lef_files.len()
-        );
+        ));
+        self.rebuild_footprint_families();
+        self.rebuild_tech_layer_styles();
+        self.check_library_layers();
 
         // Initialize voltage configuration with first LEF file's smart defaults
         if self.lef_files.len() == 1 {
@@ -478,180 +1116,620 @@ impl LefDefViewer {
         self.error_message = None;
         // Auto-show layers panel when LEF file is loaded successfully
         self.show_layers_panel = true;
-        // Auto fit to view when LEF file is loaded successfully
-        // Delay fit to view by a few frames to ensure UI layout is stable
-        self.fit_to_view_delay_frames = 3;
+        self.apply_load_view(lef_view_key(&path));
 
         // If in DEF mode, rebuild component-macro mapping to incorporate new LEF macros
         if self.def_mode {
             self.rebuild_component_macro_map();
-            println!("DEF Mode: Rebuilt component mapping after loading new LEF file");
         }
     }
 
-    fn load_def_file_sync(&mut self, def: Def, path: String) {
-        // This is the synchronized version of DEF loading (after async completion)
+    /// Replace a watched LEF file that changed on disk, keeping its place
+    /// in the file list
+    fn reload_lef_file_sync(&mut self, lef: Lef, path: String, file_hash: String) {
+        let Some(index) = self.lef_files.iter().position(|f| f.path == path) else {
+            self.load_lef_file_sync(lef, path, file_hash);
+            return;
+        };
+        self.reload_keeping_view(|viewer| {
+            let previous = viewer.lef_files.remove(index);
+            viewer.load_lef_file_sync(lef, path.clone(), file_hash);
+            if let Some(mut reloaded) = viewer.lef_files.pop() {
+                reloaded.transform = previous.transform;
+                viewer.lef_files.insert(index, reloaded);
+            }
+            // Layer styles follow the order of the files
+            viewer.rebuild_tech_layer_styles();
+        });
+        self.status_bar
+            .hint(format!("Reloaded {path} after it changed"));
+    }
 
-        // Extract routing layers from DEF and add to layer lists
-        for special_net in &def.special_nets {
-            for route in &special_net.routes {
-                let layer_key = format!("{}.ROUTE", route.layer);
-                self.all_layers.insert(layer_key.clone());
-                // Make routing layers visible by default
-                self.visible_layers.insert(layer_key);
+    /// Keep the view, selections and layer visibility over a reload of a
+    /// watched file, layers new in the file take their default visibility
+    fn reload_keeping_view(&mut self, reload: impl FnOnce(&mut Self)) {
+        let view = self.current_view();
+        let known_layers = self.all_layers.clone();
+        let show_layers_panel = self.show_layers_panel;
+        reload(self);
+        let discovered: Vec<String> = self
+            .visible_layers
+            .iter()
+            .filter(|layer| !known_layers.contains(*layer))
+            .cloned()
+            .collect();
+        self.apply_view(view);
+        self.visible_layers.extend(discovered);
+        self.show_layers_panel = show_layers_panel;
+    }
+
+    /// Parse the watched files again that changed on disk, LEF before DEF
+    fn poll_file_watcher(&mut self, ctx: &egui::Context) {
+        if !self.watch_files {
+            self.file_watcher = None;
+            self.reload_queue.clear();
+            return;
+        }
+        if self.file_watcher.is_none() {
+            let repaint = ctx.clone();
+            match FileWatcher::new(move || repaint.request_repaint_after(SETTLE_TIME)) {
+                Ok(watcher) => self.file_watcher = Some(watcher),
+                Err(e) => {
+                    self.error_message = Some(e);
+                    self.watch_files = false;
+                    return;
+                }
             }
         }
-
-        // Extract layers from regular nets as well
-        for net in &def.nets {
-            for route in &net.routes {
-                let layer_key = format!("{}.ROUTE", route.layer);
-                self.all_layers.insert(layer_key.clone());
-                // Regular net routing visible by default
-                self.visible_layers.insert(layer_key);
+        let Some(watcher) = &mut self.file_watcher else {
+            return;
+        };
+        let paths = self
+            .lef_files
+            .iter()
+            .map(|f| f.path.as_str())
+            .chain(self.def_file_path.as_deref());
+        if let Err(e) = watcher.set_files(paths) {
+            self.error_message = Some(e);
+            self.watch_files = false;
+            return;
+        }
+        for path in watcher.take_changed() {
+            if !self.reload_queue.contains(&path) {
+                self.reload_queue.push(path);
             }
         }
+        if watcher.has_pending() {
+            ctx.request_repaint_after(SETTLE_TIME);
+        }
 
-        // Extract layers from DEF PINS
-        for pin in &def.pins {
-            for rect in &pin.rects {
-                let layer_key = format!("{}.PIN", rect.layer);
-                self.all_layers.insert(layer_key.clone());
-                // PIN layers visible by default
-                self.visible_layers.insert(layer_key);
+        if !self.loading_idle() || self.reload_queue.is_empty() {
+            return;
+        }
+        let is_def = |path: &String| self.def_file_path.as_ref() == Some(path);
+        let (defs, lefs): (Vec<String>, Vec<String>) = std::mem::take(&mut self.reload_queue)
+            .into_iter()
+            .partition(is_def);
+        if lefs.is_empty() {
+            if let Some(path) = defs.into_iter().next() {
+                log::info!("Reloading changed DEF file {path}");
+                self.reloading.insert(path.clone());
+                self.start_def_parse(path, DefParseOptions::default());
             }
+        } else {
+            // The DEF waits for its macros
+            log::info!("Reloading {} changed LEF files", lefs.len());
+            self.reloading.extend(lefs.iter().cloned());
+            self.reload_queue = defs;
+            self.load_lef_paths(lefs);
         }
+    }
 
-        self.def_data = Some(def);
-        self.def_file_path = Some(path);
-
-        // Enter DEF mode
-        self.def_mode = true;
-
-        // Build component-to-macro mapping
-        self.rebuild_component_macro_map();
+    /// Group the loaded macros into footprint and drive strength families
+    fn rebuild_footprint_families(&mut self) {
+        self.footprint_families =
+            footprint::footprint_families(self.lef_files.iter().flat_map(|f| &f.data.macros));
+        self.checks_panel.invalidate_checks();
+        self.update_waiver_file();
+    }
 
-        self.error_message = None;
-        // Auto fit to view when DEF file is loaded successfully
-        // Delay fit to view by a few frames to ensure UI layout is stable
-        self.fit_to_view_delay_frames = 3;
+    /// Keep the waivers next to the DEF, or the first LEF without one
+    fn update_waiver_file(&mut self) {
+        let design_file = self
+            .def_file_path
+            .as_deref()
+            .or_else(|| self.lef_files.first().map(|f| f.path.as_str()));
+        if let Err(e) = self.checks_panel.set_waiver_file(design_file) {
+            self.error_message = Some(e);
+        }
     }
 
-    /// Transform a point based on DEF orientation and placement
-    ///
-    /// DEF orientations:
-    /// - N: No rotation (0 degrees)
-    /// - S: 180 degree rotation
-    /// - E: 90 degree counterclockwise rotation
-    /// - W: 270 degree counterclockwise rotation (90 degree clockwise)
-    /// - FN, FS, FE, FW: Flipped versions (mirror about Y-axis first, then rotate)
-    ///
-    /// Transformation order:
-    /// 1. Apply orientation transformation (rotate/flip) around origin
-    /// 2. Translate to placement position
+    /// Derive layer colors and ordering from the loaded technology LEFs
     ///
-    /// Parameters:
-    /// - point: (x, y) coordinate in LEF macro space
-    /// - placement: (px, py) placement position from DEF
-    /// - orientation: Orientation string from DEF (N, S, E, W, FN, FS, FE, FW)
-    /// - macro_size: (width, height) of the LEF macro bounding box
-    fn transform_point(
-        &self,
-        point: (f64, f64),
-        placement: (f64, f64),
-        orientation: &str,
-        macro_size: (f64, f64),
-    ) -> (f64, f64) {
-        let (x, y) = point;
-        let (px, py) = placement;
-        let (width, height) = macro_size;
+    /// Routing layers take the built-in metal colors by their position in
+    /// the stack, cut layers the via colors (the contact color below the
+    /// first routing layer) and masterslice layers the poly color. The first
+    /// definition of a layer name wins.
+    fn rebuild_tech_layer_styles(&mut self) {
+        self.tech_layer_styles.clear();
+        let (mut routing, mut cuts) = (0, 0);
+        for layer in self.lef_files.iter().flat_map(|f| &f.data.layers) {
+            if self.tech_layer_styles.contains_key(&layer.name) {
+                continue;
+            }
+            let palette_layer = if layer.is_routing() {
+                routing += 1;
+                format!("M{}", (routing - 1) % 8 + 1)
+            } else if layer.is_cut() && routing == 0 {
+                "CONT".to_string()
+            } else if layer.is_cut() {
+                cuts += 1;
+                format!("VIA{}", (cuts - 1) % 4 + 1)
+            } else if layer.layer_type == "MASTERSLICE" {
+                "POLY".to_string()
+            } else {
+                layer.name.clone()
+            };
 
-        // Apply orientation transformation
-        let (tx, ty) = match orientation {
-            "N" => {
-                // No rotation, no flip
-                (x, y)
-            }
-            "S" => {
-                // 180 degree rotation around center
-                (width - x, height - y)
-            }
-            "E" => {
-                // 90 degree counterclockwise rotation
-                // (x, y) -> (-y, x) but adjusted for size
-                (y, width - x)
-            }
-            "W" => {
-                // 270 degree counterclockwise (90 degree clockwise)
-                // (x, y) -> (y, -x) but adjusted for size
-                (height - y, x)
-            }
-            "FN" => {
-                // Flip about Y-axis (mirror horizontally), no rotation
-                (width - x, y)
-            }
-            "FS" => {
-                // Flip about Y-axis, then 180 degree rotation
-                (x, height - y)
-            }
-            "FE" => {
-                // Flip about Y-axis, then 90 degree CCW rotation
-                (y, x)
-            }
-            "FW" => {
-                // Flip about Y-axis, then 270 degree CCW rotation
-                (height - y, width - x)
-            }
-            _ => {
-                println!(
-                    "WARNING: Unknown orientation '{}', treating as N",
-                    orientation
-                );
-                (x, y)
+            let mut summary = layer.layer_type.clone();
+            if !layer.direction.is_empty() {
+                summary.push_str(&format!(" {}", layer.direction));
+            }
+            if let Some(pitch) = layer.pitch {
+                summary.push_str(&format!(", pitch {pitch}"));
+            }
+            if let Some(width) = layer.width {
+                summary.push_str(&format!(", width {width}"));
             }
-        };
 
-        // Translate to placement position
-        (px + tx, py + ty)
+            let order = 10 + self.tech_layer_styles.len() as i32;
+            self.tech_layer_styles.insert(
+                layer.name.clone(),
+                TechLayerStyle {
+                    palette_layer,
+                    order,
+                    width: layer.width,
+                    summary,
+                },
+            );
+        }
     }
 
-    /// Calculate bounding box of a macro after transformation
-    /// Returns (min_x, min_y, max_x, max_y) in world coordinates
-    fn transform_bbox(
-        &self,
-        macro_size: (f64, f64),
-        placement: (f64, f64),
-        orientation: &str,
-    ) -> (f64, f64, f64, f64) {
-        let (width, height) = macro_size;
+    /// Add the PIN and OBS layers used by a macro to the layer lists
+    fn register_macro_layers(&mut self, macro_def: &crate::lef::LefMacro) {
+        for pin in &macro_def.pins {
+            for port in &pin.ports {
+                let layers = port
+                    .rects
+                    .iter()
+                    .map(|rect| &rect.layer)
+                    .chain(port.polygons.iter().map(|polygon| &polygon.layer));
+                // Power and ground pins are visible by default
+                let supply = pin.use_type == "POWER" || pin.use_type == "GROUND";
+                for layer in layers {
+                    self.discover_layer(format!("{layer}.PIN"), supply);
+                }
+            }
+        }
 
-        // Transform all four corners
-        let corners = [
-            (0.0, 0.0),      // Bottom-left
-            (width, 0.0),    // Bottom-right
-            (width, height), // Top-right
-            (0.0, height),   // Top-left
-        ];
+        // Add obstruction layers, hidden by default
+        for obs in &macro_def.obs {
+            let layers = obs.rects.iter().map(|rect| &rect.layer);
+            for layer in layers.chain(obs.polygons.iter().map(|polygon| &polygon.layer)) {
+                self.discover_layer(format!("{layer}.OBS"), false);
+            }
+        }
+    }
 
-        let transformed: Vec<(f64, f64)> = corners
+    /// Add a layer found in a loaded file
+    ///
+    /// A new layer is shown as the layer config says, or when `shown` is
+    /// true if no pattern matches it. Known layers keep their visibility.
+    fn discover_layer(&mut self, layer: String, shown: bool) {
+        if self.all_layers.insert(layer.clone()) && self.layer_config.is_visible(&layer, shown) {
+            self.visible_layers.insert(layer);
+        }
+    }
+
+    /// Warn about library layers the technology LEF does not define
+    fn check_library_layers(&mut self) {
+        let libraries: Vec<&Lef> = self.lef_files.iter().map(|f| &f.data).collect();
+        if self
+            .layer_alias_dialog
+            .refresh(&libraries, &self.layer_aliases)
+            == 0
+        {
+            return;
+        }
+        let unmapped: Vec<&str> = self.layer_alias_dialog.unmapped().collect();
+        let message = format!("Layers not in the technology LEF: {}", unmapped.join(", "));
+        log::warn!("{message}");
+        self.status_bar
+            .hint(format!("{message}, map them in View > Layer Aliases"));
+    }
+
+    /// Move the shapes of the loaded libraries from alias layers onto their
+    /// technology layers, keeping the visibility of the old layer keys
+    fn apply_layer_aliases(&mut self) {
+        let before: std::collections::HashSet<String> = self
+            .lef_files
             .iter()
-            .map(|&corner| self.transform_point(corner, placement, orientation, macro_size))
+            .flat_map(|f| &f.data.macros)
+            .flat_map(Self::macro_layer_keys)
             .collect();
+        let mut renamed = 0;
+        for lef_file in &mut self.lef_files {
+            renamed += self.layer_aliases.apply(&mut lef_file.data);
+        }
+        if renamed == 0 {
+            self.status_bar.hint("No shapes are on alias layers");
+            return;
+        }
 
-        // Find bounding box of transformed corners
-        let min_x = transformed
-            .iter()
-            .map(|p| p.0)
-            .fold(f64::INFINITY, f64::min);
-        let min_y = transformed
+        let after: std::collections::HashSet<String> = self
+            .lef_files
             .iter()
-            .map(|p| p.1)
-            .fold(f64::INFINITY, f64::min);
-        let max_x = transformed
+            .flat_map(|f| &f.data.macros)
+            .flat_map(Self::macro_layer_keys)
+            .collect();
+        // DEF pins keep their own layer names
+        let def_keys: std::collections::HashSet<String> = self
+            .def_data
             .iter()
-            .map(|p| p.0)
-            .fold(f64::NEG_INFINITY, f64::max);
-        let max_y = transformed
+            .flat_map(|def| &def.pins)
+            .flat_map(|pin| pin.placed_rects())
+            .map(|rect| format!("{}.PIN", rect.layer))
+            .collect();
+        for old_key in before.difference(&after) {
+            let visible = self.visible_layers.contains(old_key);
+            if let Some((layer, kind)) = old_key.rsplit_once('.') {
+                let new_key = format!("{}.{kind}", self.layer_aliases.resolve(layer));
+                self.discover_layer(new_key, visible);
+            }
+            if !def_keys.contains(old_key) {
+                self.all_layers.remove(old_key);
+                self.visible_layers.remove(old_key);
+            }
+        }
+
+        // Cached meshes and merged copies hold the old layer names
+        self.macro_geometry_keys.clear();
+        self.merged_macros.clear();
+        for macro_def in self.lef_files.iter().flat_map(|f| &f.data.macros) {
+            self.macro_geometry_keys
+                .insert(macro_def.name.clone(), macro_def.geometry_hash());
+            if let Some(merged) = merge_supply_pin_rects(macro_def) {
+                self.merged_macros.insert(macro_def.name.clone(), merged);
+            }
+        }
+        if let Ok(mut cache) = self.mesh_cache.write() {
+            cache.clear();
+        }
+        if let Ok(mut tessellated) = self.tessellated_macros.lock() {
+            tessellated.clear();
+        }
+        self.rebuild_footprint_families();
+        self.resource_panel.invalidate();
+        if self.def_mode {
+            self.rebuild_component_macro_map();
+        }
+        self.check_library_layers();
+        self.success_message = Some(format!("Moved {renamed} shapes onto technology layers"));
+    }
+
+    /// Read the layer config again, for the layers found from now on
+    fn reload_layer_config(&mut self) {
+        match LayerConfig::load() {
+            Ok(Some((config, path))) => {
+                for (alias, layer) in config.aliases.iter() {
+                    self.layer_aliases.insert(alias, layer);
+                }
+                self.layer_config = config;
+                self.status_bar
+                    .hint(format!("Layer defaults from {}", path.display()));
+            }
+            Ok(None) => self.layer_config = LayerConfig::default(),
+            Err(e) => {
+                log::warn!("Invalid configuration, using built-in layer defaults: {e}");
+                self.error_message = Some(format!("Invalid configuration: {e}"));
+                self.layer_config = LayerConfig::default();
+            }
+        }
+    }
+
+    /// Open the source editor for a macro, showing earlier edits if any
+    fn open_macro_source_editor(&mut self, lef_file_idx: usize, macro_name: &str) {
+        let Some(lef_file) = self.lef_files.get(lef_file_idx) else {
+            return;
+        };
+
+        let source = if let Some(edited) = lef_file.edited_sources.get(macro_name) {
+            edited.clone()
+        } else {
+            let content = match std::fs::read_to_string(&lef_file.path) {
+                Ok(content) => content,
+                Err(e) => {
+                    self.error_message = Some(format!("Failed to read {}: {}", lef_file.path, e));
+                    return;
+                }
+            };
+            match crate::lef::lef_parser::extract_macro_source(&content, macro_name) {
+                Some(source) => source.to_string(),
+                None => {
+                    self.error_message = Some(format!(
+                        "MACRO {} not found in {}",
+                        macro_name, lef_file.path
+                    ));
+                    return;
+                }
+            }
+        };
+
+        self.macro_source_dialog
+            .open(lef_file_idx, macro_name, source);
+    }
+
+    /// Replace a macro with its reparsed source and refresh derived state
+    fn apply_macro_source_edit(&mut self, edit: MacroSourceEdit) {
+        let Some(lef_file) = self.lef_files.get_mut(edit.lef_file_idx) else {
+            return;
+        };
+        let Some(slot) = lef_file
+            .data
+            .macros
+            .iter_mut()
+            .find(|macro_def| macro_def.name == edit.original_name)
+        else {
+            self.error_message = Some(format!("MACRO {} is no longer loaded", edit.original_name));
+            return;
+        };
+
+        let old_macro = std::mem::replace(slot, edit.macro_def);
+        let new_name = slot.name.clone();
+        let new_geometry_key = slot.geometry_hash();
+        self.merged_macros.remove(&edit.original_name);
+        if let Some(merged) = merge_supply_pin_rects(slot) {
+            self.merged_macros.insert(new_name.clone(), merged);
+        }
+        lef_file.edited_sources.remove(&edit.original_name);
+        lef_file
+            .edited_sources
+            .insert(new_name.clone(), edit.source);
+
+        // Drop cached meshes of the old geometry; they are rebuilt on demand
+        let mut stale_keys = vec![
+            format!("lef_{}", edit.original_name),
+            format!("lef_{new_name}"),
+        ];
+        if let Some(old_key) = self.macro_geometry_keys.remove(&edit.original_name) {
+            stale_keys.push(old_key);
+        }
+        if let Ok(mut cache) = self.mesh_cache.write() {
+            cache.retain(|key, _| !stale_keys.contains(key));
+        }
+        if let Ok(mut tessellated) = self.tessellated_macros.lock() {
+            for key in &stale_keys {
+                tessellated.remove(key);
+            }
+        }
+        self.macro_geometry_keys
+            .insert(new_name.clone(), new_geometry_key);
+
+        // Register new layers and forget layers nothing uses any more
+        let new_macro = self.lef_files[edit.lef_file_idx]
+            .data
+            .macros
+            .iter()
+            .find(|macro_def| macro_def.name == new_name)
+            .cloned();
+        if let Some(new_macro) = new_macro {
+            self.register_macro_layers(&new_macro);
+        }
+        let mut old_layers = Self::macro_layer_keys(&old_macro);
+        let still_used: std::collections::HashSet<String> = self
+            .lef_files
+            .iter()
+            .flat_map(|lef_file| lef_file.data.macros.iter())
+            .flat_map(Self::macro_layer_keys)
+            .collect();
+        old_layers.retain(|layer| !still_used.contains(layer));
+        for layer in &old_layers {
+            self.all_layers.remove(layer);
+            self.visible_layers.remove(layer);
+        }
+
+        if new_name != edit.original_name && self.selected_cells.remove(&edit.original_name) {
+            self.selected_cells.insert(new_name.clone());
+        }
+        self.rebuild_footprint_families();
+        if self.def_mode {
+            self.rebuild_component_macro_map();
+        }
+
+        log::info!("Reparsed macro {} from edited source", new_name);
+        self.error_message = None;
+    }
+
+    /// Detailed layer keys ("M1.PIN", "M1.OBS") used by a macro
+    fn macro_layer_keys(macro_def: &crate::lef::LefMacro) -> Vec<String> {
+        let pin_layers = macro_def.pins.iter().flat_map(|pin| {
+            pin.ports.iter().flat_map(|port| {
+                port.rects
+                    .iter()
+                    .map(|rect| format!("{}.PIN", rect.layer))
+                    .chain(
+                        port.polygons
+                            .iter()
+                            .map(|polygon| format!("{}.PIN", polygon.layer)),
+                    )
+            })
+        });
+        let obs_layers = macro_def.obs.iter().flat_map(|obs| {
+            obs.rects
+                .iter()
+                .map(|rect| format!("{}.OBS", rect.layer))
+                .chain(
+                    obs.polygons
+                        .iter()
+                        .map(|polygon| format!("{}.OBS", polygon.layer)),
+                )
+        });
+        pin_layers.chain(obs_layers).collect()
+    }
+
+    fn load_def_file_sync(&mut self, def: Def, path: String) {
+        // This is the synchronized version of DEF loading (after async completion)
+        self.recent_files.add(&path);
+
+        // Extract routing layers from DEF and add to layer lists
+        for special_net in &def.special_nets {
+            let layers = special_net.routes.iter().map(|r| &r.layer);
+            for layer in layers.chain(special_net.rects.iter().map(|r| &r.layer)) {
+                // Make routing layers visible by default
+                self.discover_layer(format!("{layer}.ROUTE"), true);
+            }
+        }
+
+        // Extract layers from regular nets as well
+        for net in &def.nets {
+            for route in &net.routes {
+                // Regular net routing visible by default
+                self.discover_layer(format!("{}.ROUTE", route.layer), true);
+            }
+        }
+
+        // Extract layers from DEF PINS, including every PORT group
+        for pin in &def.pins {
+            let port_rects = pin.ports.iter().flat_map(|port| &port.rects);
+            for rect in pin.rects.iter().chain(port_rects) {
+                // PIN layers visible by default
+                self.discover_layer(format!("{}.PIN", rect.layer), true);
+            }
+        }
+
+        self.def_data = Some(def);
+        self.def_file_path = Some(path);
+
+        // Enter DEF mode, the view of the previous design is no use
+        if !self.def_mode {
+            self.mode_cameras.remember(false, self.camera());
+        }
+        self.mode_cameras.forget(true);
+        self.def_mode = true;
+        self.isolation = None;
+
+        // Build component-to-macro mapping
+        self.rebuild_component_macro_map();
+
+        // Re-check an imported netlist against the new DEF
+        self.checks_panel.refresh(self.def_data.as_ref());
+        self.update_waiver_file();
+
+        // Object indices and edits refer to the previous design
+        self.component_flags.clear();
+        self.pin_flags.clear();
+        self.eco_log.clear();
+        self.component_drag = None;
+        self.hidden_special_nets.clear();
+
+        self.units_warning_dismissed = false;
+        if let Some(warning) = self.units_check().warning() {
+            log::warn!("{warning}");
+        }
+
+        self.error_message = None;
+        let view_key = self
+            .def_data
+            .as_ref()
+            .map(|def| def_view_key(def, self.def_file_path.as_deref().unwrap_or_default()))
+            .unwrap_or_default();
+        self.apply_load_view(view_key);
+    }
+
+    /// Fit, keep or restore the view after a load, as the view policy says
+    ///
+    /// Delayed fits wait a few frames so the UI layout is stable.
+    fn apply_load_view(&mut self, design: String) {
+        if let Some(previous) = self.view_design.take() {
+            let view = SavedView {
+                zoom: self.zoom,
+                pan_x: self.pan_x,
+                pan_y: self.pan_y,
+            };
+            self.view_policy.remember(&previous, view);
+        }
+        match self.view_policy.on_load(&design) {
+            LoadView::Fit => self.fit_to_view_delay_frames = 3,
+            LoadView::Keep => {}
+            LoadView::Restore(view) => {
+                self.zoom = view.zoom;
+                self.pan_x = view.pan_x;
+                self.pan_y = view.pan_y;
+            }
+        }
+        self.view_design = Some(design);
+    }
+
+    /// Transform a point in the macro frame based on DEF orientation and placement
+    ///
+    /// See [`orient_point`] for the orientation conventions. Placed LEF shapes
+    /// must go through [`MacroTransform`] instead so ORIGIN is applied too.
+    ///
+    /// Parameters:
+    /// - point: (x, y) coordinate in the macro bounding box frame
+    /// - placement: (px, py) placement position from DEF
+    /// - orientation: Orientation string from DEF (N, S, E, W, FN, FS, FE, FW)
+    /// - macro_size: (width, height) of the LEF macro bounding box
+    fn transform_point(
+        &self,
+        point: (f64, f64),
+        placement: (f64, f64),
+        orientation: &str,
+        macro_size: (f64, f64),
+    ) -> (f64, f64) {
+        let (tx, ty) = orient_point(point, orientation, macro_size);
+
+        // Translate to placement position
+        (placement.0 + tx, placement.1 + ty)
+    }
+
+    /// Calculate bounding box of a macro after transformation
+    /// Returns (min_x, min_y, max_x, max_y) in world coordinates
+    fn transform_bbox(
+        &self,
+        macro_size: (f64, f64),
+        placement: (f64, f64),
+        orientation: &str,
+    ) -> (f64, f64, f64, f64) {
+        let (width, height) = macro_size;
+
+        // Transform all four corners
+        let corners = [
+            (0.0, 0.0),      // Bottom-left
+            (width, 0.0),    // Bottom-right
+            (width, height), // Top-right
+            (0.0, height),   // Top-left
+        ];
+
+        let transformed: Vec<(f64, f64)> = corners
+            .iter()
+            .map(|&corner| self.transform_point(corner, placement, orientation, macro_size))
+            .collect();
+
+        // Find bounding box of transformed corners
+        let min_x = transformed
+            .iter()
+            .map(|p| p.0)
+            .fold(f64::INFINITY, f64::min);
+        let min_y = transformed
+            .iter()
+            .map(|p| p.1)
+            .fold(f64::INFINITY, f64::min);
+        let max_x = transformed
+            .iter()
+            .map(|p| p.0)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let max_y = transformed
             .iter()
             .map(|p| p.1)
             .fold(f64::NEG_INFINITY, f64::max);
@@ -659,20 +1737,42 @@ impl LefDefViewer {
         (min_x, min_y, max_x, max_y)
     }
 
+    /// Database units of the loaded DEF, checked against the tech LEF
+    fn units_check(&self) -> UnitsCheck {
+        let lef_units = self.lef_files.iter().find_map(|f| f.data.database_microns);
+        UnitsCheck::new(
+            self.def_data
+                .as_ref()
+                .and_then(|d| d.units_distance_microns),
+            lef_units,
+        )
+    }
+
+    /// DEF database units per micron used to convert DEF coordinates
+    fn def_db_units(&self) -> f64 {
+        self.units_check().db_units()
+    }
+
     /// Build mapping from DEF component instances to LEF macro names
     /// Also identifies missing cells (referenced in DEF but not in any loaded LEF)
     fn rebuild_component_macro_map(&mut self) {
+        self.resource_panel.invalidate();
+        self.design_index = None;
+        self.precompute.invalidate();
         self.component_macro_map.clear();
         self.missing_cells.clear();
+        self.physical_only_cells.clear();
+        self.instance_arrays.clear();
+        self.component_array.clear();
 
         if let Some(ref def) = self.def_data {
-            // Create a set of all available LEF macros for quick lookup
-            let mut available_macros: std::collections::HashSet<String> =
-                std::collections::HashSet::new();
+            // Map all available LEF macros to their CLASS for quick lookup
+            let mut available_macros: std::collections::HashMap<String, &str> =
+                std::collections::HashMap::new();
 
             for lef_file in &self.lef_files {
                 for macro_def in &lef_file.data.macros {
-                    available_macros.insert(macro_def.name.clone());
+                    available_macros.insert(macro_def.name.clone(), &macro_def.class);
                 }
             }
 
@@ -684,12 +1784,27 @@ impl LefDefViewer {
                 self.component_macro_map
                     .insert(component.name.clone(), macro_name.clone());
 
+                // Missing macros can only be classified by name
+                let class = available_macros.get(&macro_name).copied();
+                if crate::lef::is_physical_only_cell(&macro_name, class) {
+                    self.physical_only_cells.insert(macro_name.clone());
+                }
+
                 // Track if this macro is missing from LEF files
-                if !available_macros.contains(&macro_name) {
+                if class.is_none() {
                     self.missing_cells.insert(macro_name);
                 }
             }
 
+            // Regular arrays, such as memory bitcells, collapse into one entry
+            self.instance_arrays = detect_arrays(&def.components, ARRAY_MIN_MEMBERS);
+            self.component_array = vec![None; def.components.len()];
+            for (array_idx, array) in self.instance_arrays.iter().enumerate() {
+                for &member in &array.members {
+                    self.component_array[member] = Some(array_idx);
+                }
+            }
+
             // Log statistics
             let total_components = def.components.len();
             let missing_count = self.missing_cells.len();
@@ -700,42 +1815,271 @@ impl LefDefViewer {
                     .count(),
             );
 
-            println!(
-                "DEF Mode: {} components total, {} matched, {} unique missing cells",
-                total_components, matched_count, missing_count
+            let mut summary = format!("{matched_count} of {total_components} components matched");
+            if missing_count > 0 {
+                summary.push_str(&format!(", {missing_count} missing cells"));
+            }
+            self.status_bar.hint(summary);
+        }
+        self.refresh_placement_diff();
+    }
+
+    /// Compare the loaded DEF against the reference DEF again
+    fn refresh_placement_diff(&mut self) {
+        let classes: HashMap<&str, &str> = self
+            .lef_files
+            .iter()
+            .flat_map(|f| &f.data.macros)
+            .map(|m| (m.name.as_str(), m.class.as_str()))
+            .collect();
+        let db_units = self.def_db_units();
+        self.placement_diff_panel
+            .refresh(self.def_data.as_ref(), db_units, |name| {
+                classes.get(name).copied()
+            });
+    }
+
+    fn handle_load_reference_def(&mut self) {
+        let Some(path) = FileDialog::new()
+            .add_filter("DEF files", &["def"])
+            .pick_file()
+        else {
+            return;
+        };
+        let path = path.to_string_lossy().to_string();
+        let lef_units = self.lef_files.iter().find_map(|f| f.data.database_microns);
+        match self.placement_diff_panel.load_reference(&path, lef_units) {
+            Ok(()) => self.refresh_placement_diff(),
+            Err(e) => self.error_message = Some(e),
+        }
+    }
+
+    /// Displacement vectors from the reference locations, crosses where
+    /// removed components were and outlines of added components
+    fn paint_placement_diff(&self, painter: &egui::Painter, center: egui::Pos2) {
+        let Some(diff) = self.placement_diff_panel.shown_diff() else {
+            return;
+        };
+        let Some(def) = &self.def_data else {
+            return;
+        };
+        let visible = painter.clip_rect();
+        let color = egui::Color32::from_rgb(255, 193, 7);
+        let stroke = egui::Stroke::new(self.dpi_style.stroke(1.5), color);
+        for moved in &diff.moved {
+            let (Some(from), Some(to)) = (
+                self.world_screen_pos(center, moved.from),
+                self.world_screen_pos(center, moved.to),
+            ) else {
+                continue;
+            };
+            if !visible.intersects(egui::Rect::from_two_pos(from, to)) {
+                continue;
+            }
+            if from.distance(to) < 1.0 {
+                // Turned in place
+                painter.circle_stroke(to, 3.0, stroke);
+                continue;
+            }
+            painter.line_segment([from, to], stroke);
+            Self::paint_arrowhead(painter, from, to, color);
+        }
+
+        let removed_stroke = egui::Stroke::new(
+            self.dpi_style.stroke(1.5),
+            egui::Color32::from_rgb(244, 67, 54),
+        );
+        for location in diff.removed.iter().filter_map(|r| r.location) {
+            let Some(pos) = self.world_screen_pos(center, location) else {
+                continue;
+            };
+            if !visible.contains(pos) {
+                continue;
+            }
+            let d = 4.0;
+            painter.line_segment(
+                [pos + egui::vec2(-d, -d), pos + egui::vec2(d, d)],
+                removed_stroke,
             );
+            painter.line_segment(
+                [pos + egui::vec2(-d, d), pos + egui::vec2(d, -d)],
+                removed_stroke,
+            );
+        }
+
+        let (Some(index), false) = (&self.design_index, diff.added.is_empty()) else {
+            return;
+        };
+        let added: HashSet<&str> = diff.added.iter().map(String::as_str).collect();
+        let added_stroke = egui::Stroke::new(
+            self.dpi_style.stroke(1.5),
+            egui::Color32::from_rgb(76, 175, 80),
+        );
+        for (i, component) in def.components.iter().enumerate() {
+            if !added.contains(component.name.as_str()) {
+                continue;
+            }
+            let Some((x0, y0, x1, y1)) = index.component_bounds(i) else {
+                continue;
+            };
+            let (Some(a), Some(b)) = (
+                self.world_screen_pos(center, (x0, y0)),
+                self.world_screen_pos(center, (x1, y1)),
+            ) else {
+                continue;
+            };
+            let rect = egui::Rect::from_two_pos(a, b);
+            if visible.intersects(rect) {
+                painter.rect_stroke(rect, 0.0, added_stroke, egui::StrokeKind::Middle);
+            }
         }
     }
 
-    /// Render DEF components by transforming and rendering corresponding LEF macros
-    fn render_def_components(
+    /// DENSITY windows of a macro whose layer is visible, shaded green
+    /// through red by density and labeled with it when large enough
+    fn paint_macro_density(
         &self,
         painter: &egui::Painter,
-        center: egui::Pos2,
-        texts_to_render: &mut Vec<(egui::Pos2, String, egui::FontId, egui::Color32)>,
-        _smart_texts_to_render: &mut Vec<(TextPositioning, String, egui::FontId, egui::Color32)>,
+        macro_def: &crate::lef::LefMacro,
+        outline: egui::Pos2,
+        layer_keys: &VisibleLayerKeys,
     ) {
-        let def = match &self.def_data {
-            Some(d) => d,
-            None => return,
+        let to_screen = |x: f64, y: f64| {
+            egui::pos2(
+                outline.x + ((macro_def.origin.0 + x) as f32 * self.zoom),
+                outline.y + ((macro_def.size_y - macro_def.origin.1 - y) as f32 * self.zoom),
+            )
         };
+        for window in &macro_def.density {
+            let visible = [LayerKind::Pin, LayerKind::Obs]
+                .into_iter()
+                .any(|kind| layer_keys.get(kind, &window.layer).is_some());
+            if !visible {
+                continue;
+            }
+            let rect = egui::Rect::from_two_pos(
+                to_screen(window.xl, window.yl),
+                to_screen(window.xh, window.yh),
+            );
+            let fraction = (window.density / 100.0) as f32;
+            painter.rect_filled(rect, 0.0, heat_color(fraction, 0.35));
+            painter.rect_stroke(
+                rect,
+                0.0,
+                egui::Stroke::new(self.dpi_style.stroke(1.0), heat_color(fraction, 1.0)),
+                egui::StrokeKind::Inside,
+            );
+            if rect.width() > 60.0 && rect.height() > 20.0 {
+                painter.text(
+                    rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    format!("{} {}%", window.layer, window.density),
+                    egui::FontId::monospace(11.0),
+                    egui::Color32::WHITE,
+                );
+            }
+        }
+    }
 
-        // DEF coordinates are assumed to be in microns (database units = 1000)
-        // Convert to LEF units by dividing by 1000
-        let db_units = 1000.0;
-
-        // Calculate die area bounds for Y-axis flip
-        // DEF uses bottom-up coordinate system (Y=0 at bottom), screen uses top-down (Y=0 at top)
-        let die_area_max_y = if !def.die_area_points.is_empty() {
-            def.die_area_points
-                .iter()
-                .map(|p| p.1 / db_units)
-                .fold(f64::NEG_INFINITY, f64::max)
-        } else {
-            0.0
-        };
+    /// Cell area over die area, as (all cells, logical cells only)
+    ///
+    /// Cells whose macro is not loaded have no known size and are left out.
+    fn def_utilization(&self, def: &Def) -> Option<Utilization> {
+        let macro_areas: std::collections::HashMap<&str, f64> = self
+            .lef_files
+            .iter()
+            .flat_map(|f| &f.data.macros)
+            .map(|m| (m.name.as_str(), m.size_x * m.size_y))
+            .collect();
+        def::utilization::utilization(
+            def,
+            self.def_db_units(),
+            |name| macro_areas.get(name).copied(),
+            |name| self.physical_only_cells.contains(name),
+            self.include_cover_in_stats,
+        )
+    }
 
-        // Calculate blink effect (1 Hz = 1 second period)
+    /// Filled arrowhead at `to` pointing away from `from`
+    fn paint_arrowhead(
+        painter: &egui::Painter,
+        from: egui::Pos2,
+        to: egui::Pos2,
+        color: egui::Color32,
+    ) {
+        const LENGTH: f32 = 9.0;
+        const HALF_WIDTH: f32 = 4.0;
+        let direction = (to - from).normalized();
+        if !direction.x.is_finite() || (to - from).length() < LENGTH {
+            return;
+        }
+        let base = to - direction * LENGTH;
+        let normal = direction.rot90() * HALF_WIDTH;
+        painter.add(egui::Shape::convex_polygon(
+            vec![to, base + normal, base - normal],
+            color,
+            egui::Stroke::NONE,
+        ));
+    }
+
+    /// Cross-hatch `rect`, the style of COVER components
+    ///
+    /// Lines are spaced in screen pixels and only drawn inside the clip
+    /// rectangle, so huge bump arrays stay cheap at any zoom.
+    fn paint_cross_hatch(painter: &egui::Painter, rect: egui::Rect, color: egui::Color32) {
+        const SPACING: f32 = 8.0;
+        let rect = rect.intersect(painter.clip_rect());
+        if rect.width() < 2.0 || rect.height() < 2.0 {
+            return;
+        }
+        let stroke = egui::Stroke::new(1.0, color.gamma_multiply(0.6));
+        let (x0, y0, x1, y1) = (rect.min.x, rect.min.y, rect.max.x, rect.max.y);
+
+        // "/" lines are x + y = c, "\" lines are x - y = c
+        let mut c = ((x0 + y0) / SPACING).ceil() * SPACING;
+        while c <= x1 + y1 {
+            let (xa, xb) = ((c - y1).max(x0), (c - y0).min(x1));
+            painter.line_segment([egui::pos2(xa, c - xa), egui::pos2(xb, c - xb)], stroke);
+            c += SPACING;
+        }
+        let mut c = ((x0 - y1) / SPACING).ceil() * SPACING;
+        while c <= x1 - y0 {
+            let (xa, xb) = ((c + y0).max(x0), (c + y1).min(x1));
+            painter.line_segment([egui::pos2(xa, xa - c), egui::pos2(xb, xb - c)], stroke);
+            c += SPACING;
+        }
+    }
+
+    /// Render DEF components by transforming and rendering corresponding LEF macros
+    fn render_def_components(
+        &self,
+        painter: &egui::Painter,
+        center: egui::Pos2,
+        texts_to_render: &mut Vec<(egui::Pos2, String, egui::FontId, egui::Color32)>,
+        smart_texts_to_render: &mut Vec<(TextPositioning, String, egui::FontId, egui::Color32)>,
+    ) {
+        let def = match &self.def_data {
+            Some(d) => d,
+            None => return,
+        };
+        let layer_keys = VisibleLayerKeys::new(&self.visible_layers);
+
+        // DEF coordinates are in database units, convert to LEF units (microns)
+        let db_units = self.def_db_units();
+
+        // Calculate die area bounds for Y-axis flip
+        // DEF uses bottom-up coordinate system (Y=0 at bottom), screen uses top-down (Y=0 at top)
+        let die_area_max_y = if !def.die_area_points.is_empty() {
+            def.die_area_points
+                .iter()
+                .map(|p| p.1 / db_units)
+                .fold(f64::NEG_INFINITY, f64::max)
+        } else {
+            0.0
+        };
+        let die_bounds = self.die_bounds();
+
+        // Calculate blink effect (1 Hz = 1 second period)
         let elapsed = self.start_time.elapsed().as_secs_f32();
         let blink_on = (elapsed % 1.0) < 0.5; // On for 0.5s, off for 0.5s
 
@@ -766,7 +2110,7 @@ impl LefDefViewer {
                 painter.rect_stroke(
                     rect,
                     0.0,
-                    egui::Stroke::new(3.0, egui::Color32::RED),
+                    egui::Stroke::new(self.dpi_style.stroke(3.0), egui::Color32::RED),
                     egui::StrokeKind::Middle,
                 );
 
@@ -800,79 +2144,171 @@ impl LefDefViewer {
                 for i in 0..screen_points.len() {
                     let current = screen_points[i];
                     let next = screen_points[(i + 1) % screen_points.len()];
-                    painter
-                        .line_segment([current, next], egui::Stroke::new(4.0, egui::Color32::RED));
+                    painter.line_segment(
+                        [current, next],
+                        egui::Stroke::new(self.dpi_style.stroke(4.0), egui::Color32::RED),
+                    );
                 }
             }
         }
 
-        // Iterate through all components in DEF
-        for component in &def.components {
-            // Check if we have a matching LEF macro
-            if self.missing_cells.contains(&component.macro_name) {
-                // Render placeholder for missing cell with blink effect
-                self.render_missing_cell_placeholder(
-                    painter,
-                    center,
-                    component,
-                    db_units,
-                    blink_on,
-                    texts_to_render,
-                    die_area_max_y,
-                );
-                continue;
-            }
+        if self.show_rows {
+            self.paint_rows(painter, def, center, die_area_max_y);
+        }
+        if self.show_tracks {
+            self.paint_tracks(painter, def, center, die_area_max_y, die_bounds);
+        }
+        if self.show_regions {
+            self.paint_regions(painter, def, center, die_area_max_y, texts_to_render);
+        }
+        if self.show_fills {
+            self.paint_fills(painter, def, center, die_area_max_y);
+        }
 
-            // Find the LEF macro for this component
-            let macro_def = self
-                .lef_files
-                .iter()
-                .flat_map(|f| &f.data.macros)
-                .find(|m| m.name == component.macro_name);
+        // Arrays whose cells are too small to tell apart are drawn as one block
+        let collapsed_arrays: Vec<bool> = self
+            .instance_arrays
+            .iter()
+            .map(|array| {
+                self.collapse_arrays
+                    && (array.pitch.0.min(array.pitch.1) / db_units) as f32 * self.zoom
+                        < ARRAY_COLLAPSE_PITCH
+            })
+            .collect();
 
-            let macro_def = match macro_def {
-                Some(m) => m,
-                None => continue,
+        // Viewport culling: keep the placed components whose outline is on screen
+        let mut missing_components = Vec::new();
+        let mut visible_components = Vec::new();
+        let clip_rect = painter.clip_rect();
+        {
+            crate::profile_scope!("cull_components");
+            let to_world = |pos: egui::Pos2| {
+                (
+                    ((pos.x - center.x - self.pan_x) / self.zoom) as f64,
+                    die_area_max_y - ((pos.y - center.y - self.pan_y) / self.zoom) as f64,
+                )
             };
-
-            // Get component placement from DefPlacement structure
-            let (px, py, orientation) = if let Some(ref placement) = component.placement {
-                // Convert DEF database units to LEF units (microns)
-                let px = placement.x / db_units;
-                let py = placement.y / db_units;
-                let orientation = &placement.orientation;
-                (px, py, orientation.as_str())
-            } else {
-                // No placement info, skip this component
-                continue;
+            let (min_x, max_y) = to_world(clip_rect.left_top());
+            let (max_x, min_y) = to_world(clip_rect.right_bottom());
+            let candidates = match &self.design_index {
+                Some(index) => index.components_in((min_x, min_y, max_x, max_y)),
+                None => (0..def.components.len()).collect(),
             };
+            let macros = self.macros_by_name();
 
-            // Calculate macro size for transformation
-            let macro_size = (macro_def.size_x, macro_def.size_y);
+            for component_idx in candidates {
+                let component = &def.components[component_idx];
+                if self.component_flags.is_hidden(component_idx) {
+                    continue;
+                }
+                let in_collapsed_array = self
+                    .component_array
+                    .get(component_idx)
+                    .copied()
+                    .flatten()
+                    .is_some_and(|array| collapsed_arrays[array]);
+                if in_collapsed_array {
+                    continue;
+                }
+                if self.hide_physical_only
+                    && self.physical_only_cells.contains(&component.macro_name)
+                {
+                    continue;
+                }
 
-            // Calculate bounding box for visibility check
-            let (min_x, min_y, max_x, max_y) =
-                self.transform_bbox(macro_size, (px, py), orientation);
+                // Check if we have a matching LEF macro
+                if self.missing_cells.contains(&component.macro_name) {
+                    let placed = component.placement.as_ref().is_some_and(|p| {
+                        let (x, y) = (p.x / db_units, p.y / db_units);
+                        self.in_isolation(x, y, x, y)
+                    });
+                    if placed || self.isolation.is_none() {
+                        missing_components.push(component);
+                    }
+                    continue;
+                }
 
-            // Convert to screen coordinates with Y-axis flip
-            let screen_min_x = center.x + self.pan_x + (min_x as f32 * self.zoom);
-            let screen_min_y =
-                center.y + self.pan_y + ((die_area_max_y as f32 - max_y as f32) * self.zoom);
-            let screen_max_x = center.x + self.pan_x + (max_x as f32 * self.zoom);
-            let screen_max_y =
-                center.y + self.pan_y + ((die_area_max_y as f32 - min_y as f32) * self.zoom);
+                // Find the LEF macro for this component
+                let Some(&macro_def) = macros.get(component.macro_name.as_str()) else {
+                    continue;
+                };
 
-            let component_rect = egui::Rect::from_min_max(
-                egui::pos2(screen_min_x, screen_min_y),
-                egui::pos2(screen_max_x, screen_max_y),
-            );
+                // Get component placement from DefPlacement structure
+                let (px, py, orientation) = if let Some(ref placement) = component.placement {
+                    // Convert DEF database units to LEF units (microns)
+                    let px = placement.x / db_units;
+                    let py = placement.y / db_units;
+                    let orientation = &placement.orientation;
+                    (px, py, orientation.as_str())
+                } else {
+                    // No placement info, skip this component
+                    continue;
+                };
+
+                // Single macro-local to world transformation (ORIGIN + orientation + placement)
+                let xform = MacroTransform::new(macro_def, (px, py), orientation);
+
+                // Calculate bounding box for visibility check
+                let (min_x, min_y, max_x, max_y) = xform.outline_bbox();
+                if !self.in_isolation(min_x, min_y, max_x, max_y) {
+                    continue;
+                }
+
+                // Convert to screen coordinates with Y-axis flip
+                let screen_min_x = center.x + self.pan_x + (min_x as f32 * self.zoom);
+                let screen_min_y =
+                    center.y + self.pan_y + ((die_area_max_y as f32 - max_y as f32) * self.zoom);
+                let screen_max_x = center.x + self.pan_x + (max_x as f32 * self.zoom);
+                let screen_max_y =
+                    center.y + self.pan_y + ((die_area_max_y as f32 - min_y as f32) * self.zoom);
+
+                let component_rect = egui::Rect::from_min_max(
+                    egui::pos2(screen_min_x, screen_min_y),
+                    egui::pos2(screen_max_x, screen_max_y),
+                );
 
-            // Viewport culling: skip if component is outside visible area
-            let clip_rect = painter.clip_rect();
-            let is_visible = component_rect.intersects(clip_rect);
-            if !is_visible {
-                continue; // Skip this component entirely
+                if component_rect.intersects(clip_rect) {
+                    let macro_def = self.render_macro(macro_def);
+                    visible_components.push((component, macro_def, xform, component_rect));
+                }
             }
+        }
+
+        crate::profile_scope!("paint_def");
+        if collapsed_arrays.contains(&true) {
+            self.paint_collapsed_arrays(
+                painter,
+                def,
+                center,
+                die_area_max_y,
+                &collapsed_arrays,
+                texts_to_render,
+            );
+        }
+        for component in missing_components {
+            // Render placeholder for missing cell with blink effect
+            self.render_missing_cell_placeholder(
+                painter,
+                center,
+                component,
+                db_units,
+                blink_on,
+                texts_to_render,
+                die_area_max_y,
+            );
+        }
+
+        let highlighted_group = self
+            .highlighted_group
+            .as_ref()
+            .and_then(|name| def.groups.iter().find(|g| &g.name == name));
+        // One read of the mesh cache for the whole layout, it is only
+        // written on this thread
+        let mesh_cache = self.mesh_cache.read().ok();
+        let mut instance_details = egui::epaint::Mesh::default();
+        for (component, macro_def, xform, component_rect) in visible_components {
+            // Calculate macro size for transformation
+            let macro_size = (macro_def.size_x, macro_def.size_y);
 
             // Transform and render OUTLINE if visible
             if self.visible_layers.contains("OUTLINE") {
@@ -881,39 +2317,98 @@ impl LefDefViewer {
                 painter.rect_stroke(
                     component_rect,
                     0.0,
-                    egui::Stroke::new(1.0, outline_color),
+                    egui::Stroke::new(self.dpi_style.stroke(1.0), outline_color),
                     egui::StrokeKind::Middle,
                 );
+                if component.is_cover() {
+                    Self::paint_cross_hatch(painter, component_rect, outline_color);
+                }
             }
 
-            // Render component name if enabled (no rotation, white text with black outline)
-            if self.show_component_text {
-                // Transform center point to world coordinates with orientation
-                let (transformed_cx, transformed_cy) = self.transform_point(
-                    (macro_size.0 / 2.0, macro_size.1 / 2.0),
-                    (px, py),
-                    orientation,
-                    macro_size,
+            // LOD: Only render details when component is large enough on screen (performance optimization)
+            const MIN_SCREEN_SIZE_FOR_DETAILS: f32 = 50.0; // Minimum screen pixels to show details
+            let to_screen = |(x, y): (f64, f64)| {
+                egui::pos2(
+                    center.x + self.pan_x + (x as f32 * self.zoom),
+                    center.y + self.pan_y + ((die_area_max_y - y) as f32 * self.zoom),
+                )
+            };
+
+            // Pads are labeled along their die edge, readable from outside
+            let pad_angle = pad_label::is_pad_class(&macro_def.class).then(|| {
+                let (x0, y0) = xform.apply_frame((0.0, 0.0));
+                let (x1, y1) = xform.apply_frame(macro_size);
+                let orientation = component
+                    .placement
+                    .as_ref()
+                    .map_or("N", |p| p.orientation.as_str());
+                let pad = (x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1));
+                pad_label::pad_edge(pad, die_bounds, orientation).text_angle()
+            });
+
+            if let Some(&color) = self.script_colors.get(&component.name) {
+                painter.rect_filled(component_rect, 0.0, color.gamma_multiply(0.4));
+            }
+
+            if highlighted_group.is_some_and(|group| group.contains(&component.name)) {
+                painter.rect_filled(component_rect, 0.0, REGION_HIGHLIGHT.gamma_multiply(0.25));
+                painter.rect_stroke(
+                    component_rect,
+                    0.0,
+                    egui::Stroke::new(self.dpi_style.stroke(2.0), REGION_HIGHLIGHT),
+                    egui::StrokeKind::Middle,
                 );
+            }
 
-                // Convert to screen coordinates with Y-axis flip
-                let screen_cx = center.x + self.pan_x + (transformed_cx as f32 * self.zoom);
-                let screen_cy = center.y
-                    + self.pan_y
-                    + ((die_area_max_y as f32 - transformed_cy as f32) * self.zoom);
+            // Render component name if enabled (white text with black outline)
+            if self.show_component_text {
+                // Transform center point to world coordinates with orientation
+                let screen_center =
+                    to_screen(xform.apply_frame((macro_size.0 / 2.0, macro_size.1 / 2.0)));
+                let name = identifier::display_name(&component.name, def.bus_bit_chars);
+                let font = egui::FontId::proportional(self.dpi_style.font_size(12.0));
 
                 // Collect text for later rendering (so it appears on top of all shapes)
-                texts_to_render.push((
-                    egui::pos2(screen_cx, screen_cy),
-                    component.name.clone(),
-                    egui::FontId::proportional(12.0),
-                    egui::Color32::WHITE,
-                ));
+                match pad_angle {
+                    Some(angle) => smart_texts_to_render.push((
+                        TextPositioning {
+                            pos: screen_center,
+                            anchor: egui::Align2::CENTER_CENTER,
+                            angle,
+                        },
+                        name,
+                        font,
+                        egui::Color32::WHITE,
+                    )),
+                    None => texts_to_render.push((screen_center, name, font, egui::Color32::WHITE)),
+                }
+            }
+
+            // Pad pin labels, turned with the pad name
+            if let Some(angle) = pad_angle.filter(|_| self.show_pin_text) {
+                if component_rect.width().max(component_rect.height())
+                    >= MIN_SCREEN_SIZE_FOR_DETAILS
+                {
+                    for pin in &macro_def.pins {
+                        let Some(rect) = pin.ports.iter().flat_map(|p| &p.rects).next() else {
+                            continue;
+                        };
+                        let pin_center = ((rect.xl + rect.xh) / 2.0, (rect.yl + rect.yh) / 2.0);
+                        smart_texts_to_render.push((
+                            TextPositioning {
+                                pos: to_screen(xform.apply_frame(pin_center)),
+                                anchor: egui::Align2::CENTER_CENTER,
+                                angle,
+                            },
+                            pin.name.clone(),
+                            egui::FontId::monospace(self.dpi_style.font_size(10.0)),
+                            egui::Color32::WHITE,
+                        ));
+                    }
+                }
             }
 
             // Render LEF cell internal details (PINs, OBS) if enabled
-            // LOD: Only render details when component is large enough on screen (performance optimization)
-            const MIN_SCREEN_SIZE_FOR_DETAILS: f32 = 50.0; // Minimum screen pixels to show details
             let screen_width = component_rect.width();
             let screen_height = component_rect.height();
             let screen_size = screen_width.max(screen_height); // Use larger dimension
@@ -923,231 +2418,37 @@ impl LefDefViewer {
                 if self.progressive_rendering_enabled {
                     self.tessellate_macro_details(macro_def);
 
-                    // Render from cache (progressive rendering mode)
-                    if let Ok(cache) = self.mesh_cache.read() {
-                        let mut shape_index = 0;
-
-                        // Render cached PIN shapes
-                        for pin in &macro_def.pins {
-                            for port in &pin.ports {
-                                // Render PIN rectangles from cache
-                                for rect_data in &port.rects {
-                                    let detailed_layer = format!("{}.PIN", rect_data.layer);
-                                    if !self.visible_layers.contains(&detailed_layer) {
-                                        shape_index += 1;
-                                        continue;
-                                    }
-
-                                    let cache_key = MeshCacheKey {
-                                        macro_name: macro_def.name.clone(),
-                                        shape_type: "PIN".to_string(),
-                                        layer_name: detailed_layer,
-                                        shape_index,
-                                    };
-
-                                    if let Some(cached_mesh) = cache.get(&cache_key) {
-                                        // Transform cached world-space vertices to screen space
-                                        let transformed_vertices: Vec<egui::epaint::Vertex> =
-                                            cached_mesh
-                                                .vertices
-                                                .iter()
-                                                .map(|v| {
-                                                    let (tx, ty) = self.transform_point(
-                                                        (v.x as f64, v.y as f64),
-                                                        (px, py),
-                                                        orientation,
-                                                        macro_size,
-                                                    );
-                                                    let screen_x = center.x
-                                                        + self.pan_x
-                                                        + (tx as f32 * self.zoom);
-                                                    let screen_y = center.y
-                                                        + self.pan_y
-                                                        + ((die_area_max_y as f32 - ty as f32)
-                                                            * self.zoom);
-                                                    egui::epaint::Vertex {
-                                                        pos: egui::pos2(screen_x, screen_y),
-                                                        uv: egui::pos2(0.0, 0.0),
-                                                        color: cached_mesh.color,
-                                                    }
-                                                })
-                                                .collect();
-
-                                        let mesh = egui::epaint::Mesh {
-                                            indices: cached_mesh.indices.clone(),
-                                            vertices: transformed_vertices,
-                                            texture_id: egui::TextureId::default(),
-                                        };
-
-                                        painter.add(egui::Shape::Mesh(Arc::new(mesh)));
-                                    }
-
-                                    shape_index += 1;
-                                }
-
-                                // Render PIN polygons from cache
-                                for _polygon_data in &port.polygons {
-                                    let detailed_layer = format!("{}.PIN", _polygon_data.layer);
-                                    if !self.visible_layers.contains(&detailed_layer) {
-                                        shape_index += 1;
-                                        continue;
-                                    }
-
-                                    let cache_key = MeshCacheKey {
-                                        macro_name: macro_def.name.clone(),
-                                        shape_type: "PIN".to_string(),
-                                        layer_name: detailed_layer,
-                                        shape_index,
-                                    };
-
-                                    if let Some(cached_mesh) = cache.get(&cache_key) {
-                                        let transformed_vertices: Vec<egui::epaint::Vertex> =
-                                            cached_mesh
-                                                .vertices
-                                                .iter()
-                                                .map(|v| {
-                                                    let (tx, ty) = self.transform_point(
-                                                        (v.x as f64, v.y as f64),
-                                                        (px, py),
-                                                        orientation,
-                                                        macro_size,
-                                                    );
-                                                    let screen_x = center.x
-                                                        + self.pan_x
-                                                        + (tx as f32 * self.zoom);
-                                                    let screen_y = center.y
-                                                        + self.pan_y
-                                                        + ((die_area_max_y as f32 - ty as f32)
-                                                            * self.zoom);
-                                                    egui::epaint::Vertex {
-                                                        pos: egui::pos2(screen_x, screen_y),
-                                                        uv: egui::pos2(0.0, 0.0),
-                                                        color: cached_mesh.color,
-                                                    }
-                                                })
-                                                .collect();
-
-                                        let mesh = egui::epaint::Mesh {
-                                            indices: cached_mesh.indices.clone(),
-                                            vertices: transformed_vertices,
-                                            texture_id: egui::TextureId::default(),
-                                        };
-
-                                        painter.add(egui::Shape::Mesh(Arc::new(mesh)));
-                                    }
-
-                                    shape_index += 1;
-                                }
-                            }
-                        }
-
-                        // Render cached OBS shapes
-                        for obs in &macro_def.obs {
-                            // Render OBS rectangles from cache
-                            for _rect_data in &obs.rects {
-                                let detailed_layer = format!("{}.OBS", _rect_data.layer);
-                                if !self.visible_layers.contains(&detailed_layer) {
-                                    shape_index += 1;
-                                    continue;
-                                }
-
-                                let cache_key = MeshCacheKey {
-                                    macro_name: macro_def.name.clone(),
-                                    shape_type: "OBS".to_string(),
-                                    layer_name: detailed_layer,
-                                    shape_index,
-                                };
-
-                                if let Some(cached_mesh) = cache.get(&cache_key) {
-                                    let transformed_vertices: Vec<egui::epaint::Vertex> =
-                                        cached_mesh
-                                            .vertices
-                                            .iter()
-                                            .map(|v| {
-                                                let (tx, ty) = self.transform_point(
-                                                    (v.x as f64, v.y as f64),
-                                                    (px, py),
-                                                    orientation,
-                                                    macro_size,
-                                                );
-                                                let screen_x =
-                                                    center.x + self.pan_x + (tx as f32 * self.zoom);
-                                                let screen_y = center.y
-                                                    + self.pan_y
-                                                    + ((die_area_max_y as f32 - ty as f32)
-                                                        * self.zoom);
-                                                egui::epaint::Vertex {
-                                                    pos: egui::pos2(screen_x, screen_y),
-                                                    uv: egui::pos2(0.0, 0.0),
-                                                    color: cached_mesh.color,
-                                                }
-                                            })
-                                            .collect();
-
-                                    let mesh = egui::epaint::Mesh {
-                                        indices: cached_mesh.indices.clone(),
-                                        vertices: transformed_vertices,
-                                        texture_id: egui::TextureId::default(),
-                                    };
-
-                                    painter.add(egui::Shape::Mesh(Arc::new(mesh)));
-                                }
-
-                                shape_index += 1;
-                            }
-
-                            // Render OBS polygons from cache
-                            for _polygon_data in &obs.polygons {
-                                let detailed_layer = format!("{}.OBS", _polygon_data.layer);
-                                if !self.visible_layers.contains(&detailed_layer) {
-                                    shape_index += 1;
-                                    continue;
-                                }
-
-                                let cache_key = MeshCacheKey {
-                                    macro_name: macro_def.name.clone(),
-                                    shape_type: "OBS".to_string(),
-                                    layer_name: detailed_layer,
-                                    shape_index,
-                                };
-
-                                if let Some(cached_mesh) = cache.get(&cache_key) {
-                                    let transformed_vertices: Vec<egui::epaint::Vertex> =
-                                        cached_mesh
-                                            .vertices
-                                            .iter()
-                                            .map(|v| {
-                                                let (tx, ty) = self.transform_point(
-                                                    (v.x as f64, v.y as f64),
-                                                    (px, py),
-                                                    orientation,
-                                                    macro_size,
-                                                );
-                                                let screen_x =
-                                                    center.x + self.pan_x + (tx as f32 * self.zoom);
-                                                let screen_y = center.y
-                                                    + self.pan_y
-                                                    + ((die_area_max_y as f32 - ty as f32)
-                                                        * self.zoom);
-                                                egui::epaint::Vertex {
-                                                    pos: egui::pos2(screen_x, screen_y),
-                                                    uv: egui::pos2(0.0, 0.0),
-                                                    color: cached_mesh.color,
-                                                }
-                                            })
-                                            .collect();
-
-                                    let mesh = egui::epaint::Mesh {
-                                        indices: cached_mesh.indices.clone(),
-                                        vertices: transformed_vertices,
-                                        texture_id: egui::TextureId::default(),
-                                    };
-
-                                    painter.add(egui::Shape::Mesh(Arc::new(mesh)));
-                                }
-
-                                shape_index += 1;
+                    // Render from cache (progressive rendering mode), the
+                    // cached shapes of all instances go in one mesh
+                    let geometry_key = self.mesh_geometry_key(macro_def);
+                    if let Some(meshes) = mesh_cache
+                        .as_deref()
+                        .and_then(|cache| cache.get(geometry_key))
+                    {
+                        for (shape_index, (kind, layer)) in
+                            macro_shape_layers(macro_def).enumerate()
+                        {
+                            if layer_keys.get(kind, layer).is_none() {
+                                continue;
                             }
+                            let Some(cached_mesh) = meshes.get(&shape_index) else {
+                                continue;
+                            };
+                            // Transform cached world-space vertices to screen space
+                            let base = instance_details.vertices.len() as u32;
+                            instance_details
+                                .indices
+                                .extend(cached_mesh.indices.iter().map(|i| base + i));
+                            instance_details
+                                .vertices
+                                .extend(cached_mesh.vertices.iter().map(|v| {
+                                    let (tx, ty) = xform.apply((v.x as f64, v.y as f64));
+                                    egui::epaint::Vertex {
+                                        pos: to_screen((tx, ty)),
+                                        uv: egui::pos2(0.0, 0.0),
+                                        color: cached_mesh.color,
+                                    }
+                                }));
                         }
                     }
 
@@ -1161,72 +2462,41 @@ impl LefDefViewer {
                     for port in &pin.ports {
                         // Render PIN rectangles
                         for rect_data in &port.rects {
-                            let detailed_layer = format!("{}.PIN", rect_data.layer);
-                            if !self.visible_layers.contains(&detailed_layer) {
+                            let Some(detailed_layer) =
+                                layer_keys.get(LayerKind::Pin, &rect_data.layer)
+                            else {
                                 continue;
-                            }
+                            };
 
                             // Transform rectangle corners
-                            let corners = [
-                                (
-                                    macro_def.origin.0 + rect_data.xl,
-                                    macro_def.origin.1 + rect_data.yl,
-                                ),
-                                (
-                                    macro_def.origin.0 + rect_data.xh,
-                                    macro_def.origin.1 + rect_data.yl,
-                                ),
-                                (
-                                    macro_def.origin.0 + rect_data.xh,
-                                    macro_def.origin.1 + rect_data.yh,
-                                ),
-                                (
-                                    macro_def.origin.0 + rect_data.xl,
-                                    macro_def.origin.1 + rect_data.yh,
-                                ),
-                            ];
+                            let corners = xform.rect_corners(
+                                rect_data.xl,
+                                rect_data.yl,
+                                rect_data.xh,
+                                rect_data.yh,
+                            );
 
-                            let screen_points: Vec<egui::Pos2> = corners
-                                .iter()
-                                .map(|&corner| {
-                                    let (tx, ty) = self.transform_point(
-                                        corner,
-                                        (px, py),
-                                        orientation,
-                                        macro_size,
-                                    );
-                                    egui::pos2(
-                                        center.x + self.pan_x + (tx as f32 * self.zoom),
-                                        center.y
-                                            + self.pan_y
-                                            + ((die_area_max_y as f32 - ty as f32) * self.zoom),
-                                    )
-                                })
-                                .collect();
+                            let screen_points = corners.map(to_screen);
 
-                            let color = self.get_layer_color(&detailed_layer);
+                            let color = self.get_layer_color(detailed_layer);
                             let mesh = Self::tessellate_polygon(&screen_points, color);
                             painter.add(egui::Shape::Mesh(Arc::new(mesh)));
                         }
 
                         // Render PIN polygons
                         for polygon_data in &port.polygons {
-                            let detailed_layer = format!("{}.PIN", polygon_data.layer);
-                            if !self.visible_layers.contains(&detailed_layer) {
+                            let Some(detailed_layer) =
+                                layer_keys.get(LayerKind::Pin, &polygon_data.layer)
+                            else {
                                 continue;
-                            }
+                            };
 
                             if polygon_data.points.len() >= 3 {
                                 let screen_points: Vec<egui::Pos2> = polygon_data
                                     .points
                                     .iter()
                                     .map(|&(x, y)| {
-                                        let (tx, ty) = self.transform_point(
-                                            (macro_def.origin.0 + x, macro_def.origin.1 + y),
-                                            (px, py),
-                                            orientation,
-                                            macro_size,
-                                        );
+                                        let (tx, ty) = xform.apply((x, y));
                                         egui::pos2(
                                             center.x + self.pan_x + (tx as f32 * self.zoom),
                                             center.y
@@ -1237,7 +2507,7 @@ impl LefDefViewer {
                                     .collect();
 
                                 if screen_points.len() >= 3 {
-                                    let color = self.get_layer_color(&detailed_layer);
+                                    let color = self.get_layer_color(detailed_layer);
                                     let mesh = Self::tessellate_polygon(&screen_points, color);
                                     painter.add(egui::Shape::Mesh(Arc::new(mesh)));
                                 }
@@ -1250,71 +2520,43 @@ impl LefDefViewer {
                 for obs in &macro_def.obs {
                     // Render OBS rectangles
                     for rect_data in &obs.rects {
-                        let detailed_layer = format!("{}.OBS", rect_data.layer);
-                        if !self.visible_layers.contains(&detailed_layer) {
+                        let Some(detailed_layer) = layer_keys.get(LayerKind::Obs, &rect_data.layer)
+                        else {
                             continue;
-                        }
+                        };
 
                         // Transform rectangle corners
-                        let corners = [
-                            (
-                                macro_def.origin.0 + rect_data.xl,
-                                macro_def.origin.1 + rect_data.yl,
-                            ),
-                            (
-                                macro_def.origin.0 + rect_data.xh,
-                                macro_def.origin.1 + rect_data.yl,
-                            ),
-                            (
-                                macro_def.origin.0 + rect_data.xh,
-                                macro_def.origin.1 + rect_data.yh,
-                            ),
-                            (
-                                macro_def.origin.0 + rect_data.xl,
-                                macro_def.origin.1 + rect_data.yh,
-                            ),
-                        ];
+                        let corners = xform.rect_corners(
+                            rect_data.xl,
+                            rect_data.yl,
+                            rect_data.xh,
+                            rect_data.yh,
+                        );
 
-                        let screen_points: Vec<egui::Pos2> = corners
-                            .iter()
-                            .map(|&corner| {
-                                let (tx, ty) =
-                                    self.transform_point(corner, (px, py), orientation, macro_size);
-                                egui::pos2(
-                                    center.x + self.pan_x + (tx as f32 * self.zoom),
-                                    center.y
-                                        + self.pan_y
-                                        + ((die_area_max_y as f32 - ty as f32) * self.zoom),
-                                )
-                            })
-                            .collect();
+                        let screen_points = corners.map(to_screen);
 
-                        let color = self.get_layer_color(&detailed_layer);
+                        let color = self.get_layer_color(detailed_layer);
                         // Render OBS as outline instead of filled
                         painter.add(egui::Shape::closed_line(
-                            screen_points,
-                            egui::Stroke::new(1.0, color),
+                            screen_points.to_vec(),
+                            egui::Stroke::new(self.dpi_style.stroke(1.0), color),
                         ));
                     }
 
                     // Render OBS polygons
                     for polygon_data in &obs.polygons {
-                        let detailed_layer = format!("{}.OBS", polygon_data.layer);
-                        if !self.visible_layers.contains(&detailed_layer) {
+                        let Some(detailed_layer) =
+                            layer_keys.get(LayerKind::Obs, &polygon_data.layer)
+                        else {
                             continue;
-                        }
+                        };
 
                         if polygon_data.points.len() >= 3 {
                             let screen_points: Vec<egui::Pos2> = polygon_data
                                 .points
                                 .iter()
                                 .map(|&(x, y)| {
-                                    let (tx, ty) = self.transform_point(
-                                        (macro_def.origin.0 + x, macro_def.origin.1 + y),
-                                        (px, py),
-                                        orientation,
-                                        macro_size,
-                                    );
+                                    let (tx, ty) = xform.apply((x, y));
                                     egui::pos2(
                                         center.x + self.pan_x + (tx as f32 * self.zoom),
                                         center.y
@@ -1325,11 +2567,11 @@ impl LefDefViewer {
                                 .collect();
 
                             if screen_points.len() >= 3 {
-                                let color = self.get_layer_color(&detailed_layer);
+                                let color = self.get_layer_color(detailed_layer);
                                 // Render OBS as outline instead of filled
                                 painter.add(egui::Shape::closed_line(
                                     screen_points,
-                                    egui::Stroke::new(1.0, color),
+                                    egui::Stroke::new(self.dpi_style.stroke(1.0), color),
                                 ));
                             }
                         }
@@ -1337,23 +2579,49 @@ impl LefDefViewer {
                 }
             }
         }
+        if !instance_details.is_empty() {
+            painter.add(egui::Shape::mesh(instance_details));
+        }
 
         // Render DEF PINS if enabled
         if self.show_pins {
-            for pin in &def.pins {
+            crate::profile_scope!("paint_pins");
+            for (pin_idx, pin) in def.pins.iter().enumerate() {
+                if self.pin_flags.is_hidden(pin_idx) {
+                    continue;
+                }
                 // Check selection filter
                 if !self.selected_pins.is_empty() && !self.selected_pins.contains(&pin.name) {
                     continue;
                 }
+                // Check direction/USE/status filter
+                if !self.def_pin_filter.matches_def_pin(pin) {
+                    continue;
+                }
+                if let Some(isolation) = &self.isolation {
+                    let ports = pin.port_placements();
+                    if !isolation.overlaps_points(
+                        ports.iter().map(|&(x, y, _)| (x / db_units, y / db_units)),
+                    ) {
+                        continue;
+                    }
+                }
 
                 // Convert pin position to screen coordinates with Y-flip
-                let pin_x = (pin.x / db_units) as f32;
-                let pin_y = (pin.y / db_units) as f32;
-                let screen_x = center.x + self.pan_x + (pin_x * self.zoom);
-                let screen_y =
-                    center.y + self.pan_y + ((die_area_max_y as f32 - pin_y) * self.zoom);
+                let to_screen = |x: f64, y: f64| {
+                    egui::pos2(
+                        center.x + self.pan_x + ((x / db_units) as f32 * self.zoom),
+                        center.y
+                            + self.pan_y
+                            + ((die_area_max_y as f32 - (y / db_units) as f32) * self.zoom),
+                    )
+                };
+                let egui::Pos2 {
+                    x: screen_x,
+                    y: screen_y,
+                } = to_screen(pin.x, pin.y);
 
-                // Render pin position marker (small circle)
+                // Render pin position marker (small circle) at every port
                 let pin_radius = 3.0;
                 let is_selected = self.selected_pins.contains(&pin.name);
                 let marker_color = if is_selected {
@@ -1367,56 +2635,85 @@ impl LefDefViewer {
                     }
                 };
 
-                painter.circle_filled(egui::pos2(screen_x, screen_y), pin_radius, marker_color);
-                painter.circle_stroke(
-                    egui::pos2(screen_x, screen_y),
-                    pin_radius,
-                    egui::Stroke::new(1.0, egui::Color32::WHITE),
-                );
+                for (port_x, port_y, _) in pin.port_placements() {
+                    let marker = to_screen(port_x, port_y);
+                    painter.circle_filled(marker, pin_radius, marker_color);
+                    painter.circle_stroke(
+                        marker,
+                        pin_radius,
+                        egui::Stroke::new(self.dpi_style.stroke(1.0), egui::Color32::WHITE),
+                    );
 
-                // Render PIN LAYER geometry (rectangles)
-                for rect in &pin.rects {
-                    let detailed_layer = format!("{}.PIN", rect.layer);
-                    // Check layer visibility
-                    if !self.visible_layers.contains(&detailed_layer) {
-                        continue;
+                    // Highlight pins that pass an active filter
+                    if self.def_pin_filter.is_active() {
+                        painter.circle_stroke(
+                            marker,
+                            pin_radius + 3.0,
+                            egui::Stroke::new(
+                                self.dpi_style.stroke(2.0),
+                                egui::Color32::from_rgb(255, 165, 0),
+                            ),
+                        );
                     }
+                }
 
-                    // Convert rectangle coordinates (relative to pin position)
-                    let xl = ((pin.x + rect.xl) / db_units) as f32;
-                    let yl = ((pin.y + rect.yl) / db_units) as f32;
-                    let xh = ((pin.x + rect.xh) / db_units) as f32;
-                    let yh = ((pin.y + rect.yh) / db_units) as f32;
+                // Lock FIXED pins, which floorplanning already legalized
+                if pin.placement_status() == "FIXED" {
+                    self.paint_lock_glyph(
+                        painter,
+                        egui::pos2(screen_x, screen_y)
+                            + egui::vec2(pin_radius + 5.0, -pin_radius - 5.0),
+                    );
+                }
 
-                    // Apply Y-flip and zoom/pan
-                    let screen_xl = center.x + self.pan_x + (xl * self.zoom);
-                    let screen_yl =
-                        center.y + self.pan_y + ((die_area_max_y as f32 - yl) * self.zoom);
-                    let screen_xh = center.x + self.pan_x + (xh * self.zoom);
-                    let screen_yh =
-                        center.y + self.pan_y + ((die_area_max_y as f32 - yh) * self.zoom);
+                // Render PIN LAYER geometry of every port at its own placement
+                for rect in pin.placed_rects() {
+                    let Some(detailed_layer) = layer_keys.get(LayerKind::Pin, &rect.layer) else {
+                        continue;
+                    };
 
                     let rect_shape = egui::Rect::from_two_pos(
-                        egui::pos2(screen_xl, screen_yl),
-                        egui::pos2(screen_xh, screen_yh),
+                        to_screen(rect.xl, rect.yl),
+                        to_screen(rect.xh, rect.yh),
                     );
 
-                    let layer_color = self.get_layer_color(&detailed_layer);
+                    let layer_color = self.get_layer_color(detailed_layer);
                     painter.rect_filled(rect_shape, 0.0, layer_color);
                     painter.rect_stroke(
                         rect_shape,
                         0.0,
-                        egui::Stroke::new(1.0, egui::Color32::WHITE),
+                        egui::Stroke::new(self.dpi_style.stroke(1.0), egui::Color32::WHITE),
                         egui::StrokeKind::Middle,
                     );
                 }
 
+                // Mark port vias with a cross
+                for (port, (port_x, port_y, orient)) in pin.ports.iter().zip(pin.port_placements())
+                {
+                    for (_, via_x, via_y) in &port.vias {
+                        let orient = if orient.is_empty() { "N" } else { orient };
+                        let (dx, dy) = orient_point((*via_x, *via_y), orient, (0.0, 0.0));
+                        let via = to_screen(port_x + dx, port_y + dy);
+                        let stroke =
+                            egui::Stroke::new(self.dpi_style.stroke(1.0), egui::Color32::WHITE);
+                        let arm = 2.5;
+                        painter.line_segment(
+                            [via - egui::vec2(arm, arm), via + egui::vec2(arm, arm)],
+                            stroke,
+                        );
+                        painter.line_segment(
+                            [via - egui::vec2(arm, -arm), via + egui::vec2(arm, -arm)],
+                            stroke,
+                        );
+                    }
+                }
+
                 // Render pin name if zoom is sufficient
                 if self.show_pin_text && self.zoom > 1.0 {
                     texts_to_render.push((
                         egui::pos2(screen_x + pin_radius + 5.0, screen_y),
-                        pin.name.clone(),
-                        egui::FontId::monospace(10.0),
+                        identifier::display_name(&pin.name, def.bus_bit_chars),
+                        egui::FontId::monospace(self.dpi_style.font_size(10.0)),
                         egui::Color32::WHITE,
                     ));
                 }
@@ -1426,18 +2723,52 @@ impl LefDefViewer {
         // Render SPECIALNETS (power/ground routing) if enabled
         if self.show_special_nets {
             for special_net in &def.special_nets {
-                // Determine color based on use type
-                let net_color = match special_net.use_type.as_deref() {
-                    Some("POWER") => egui::Color32::from_rgb(255, 50, 50), // Red for VDD
-                    Some("GROUND") => egui::Color32::from_rgb(50, 50, 255), // Blue for VSS
-                    _ => egui::Color32::from_rgb(200, 200, 0),             // Yellow for others
-                };
+                if self.hidden_special_nets.contains(&special_net.name) {
+                    continue;
+                }
+                let net_color = Self::special_net_color(special_net.use_type.as_deref());
+
+                // Straps and rings given as RECT shapes
+                for rect in &special_net.rects {
+                    if layer_keys.get(LayerKind::Route, &rect.layer).is_none() {
+                        continue;
+                    }
+                    if !self.in_isolation(
+                        rect.xl / db_units,
+                        rect.yl / db_units,
+                        rect.xh / db_units,
+                        rect.yh / db_units,
+                    ) {
+                        continue;
+                    }
+                    let (xl, yl) = ((rect.xl / db_units) as f32, (rect.yl / db_units) as f32);
+                    let (xh, yh) = ((rect.xh / db_units) as f32, (rect.yh / db_units) as f32);
+                    let screen_rect = egui::Rect::from_two_pos(
+                        egui::pos2(
+                            center.x + self.pan_x + xl * self.zoom,
+                            center.y + self.pan_y + (die_area_max_y as f32 - yl) * self.zoom,
+                        ),
+                        egui::pos2(
+                            center.x + self.pan_x + xh * self.zoom,
+                            center.y + self.pan_y + (die_area_max_y as f32 - yh) * self.zoom,
+                        ),
+                    );
+                    painter.rect_filled(screen_rect, 0.0, net_color.gamma_multiply(0.6));
+                    painter.rect_stroke(
+                        screen_rect,
+                        0.0,
+                        egui::Stroke::new(self.dpi_style.stroke(1.0), net_color),
+                        egui::StrokeKind::Inside,
+                    );
+                }
 
                 // Render all routing segments
                 for route in &special_net.routes {
                     // Check layer visibility
-                    let layer_key = format!("{}.ROUTE", route.layer);
-                    if !self.visible_layers.contains(&layer_key) {
+                    if layer_keys.get(LayerKind::Route, &route.layer).is_none() {
+                        continue;
+                    }
+                    if !self.route_in_isolation(route, db_units) {
                         continue;
                     }
 
@@ -1496,7 +2827,7 @@ impl LefDefViewer {
                         painter.rect_stroke(
                             via_rect,
                             0.0,
-                            egui::Stroke::new(1.0, egui::Color32::WHITE),
+                            egui::Stroke::new(self.dpi_style.stroke(1.0), egui::Color32::WHITE),
                             egui::StrokeKind::Middle,
                         );
 
@@ -1505,7 +2836,7 @@ impl LefDefViewer {
                             texts_to_render.push((
                                 egui::pos2(screen_x + via_size, screen_y),
                                 via_name.clone(),
-                                egui::FontId::monospace(8.0),
+                                egui::FontId::monospace(self.dpi_style.font_size(8.0)),
                                 egui::Color32::WHITE,
                             ));
                         }
@@ -1514,96 +2845,120 @@ impl LefDefViewer {
             }
         }
 
-        // Render NETS (signal routing) if enabled
+        // Render NETS (signal routing) if enabled, colored by routing layer
         if self.show_nets {
-            // Signal nets use green color with thinner lines
-            let net_color = egui::Color32::from_rgb(50, 200, 50);
-
-            for net in &def.nets {
-                // Skip if no routing information
-                if net.routes.is_empty() {
-                    continue;
-                }
+            crate::profile_scope!("paint_nets");
+            let to_screen = |x: f64, y: f64| {
+                egui::pos2(
+                    center.x + self.pan_x + (x / db_units) as f32 * self.zoom,
+                    center.y
+                        + self.pan_y
+                        + (die_area_max_y as f32 - (y / db_units) as f32) * self.zoom,
+                )
+            };
 
-                // Render all routing segments
+            for net in def.nets.iter().filter(|net| self.net_filter.matches(net)) {
                 for route in &net.routes {
                     // Check layer visibility
-                    let layer_key = format!("{}.ROUTE", route.layer);
-                    if !self.visible_layers.contains(&layer_key) {
+                    let Some(layer_key) = layer_keys.get(LayerKind::Route, &route.layer) else {
+                        continue;
+                    };
+                    if !self.route_in_isolation(route, db_units) {
                         continue;
                     }
+                    let color = self.get_layer_color(layer_key);
 
-                    // Convert route width from DEF units to screen space
-                    // Signal nets typically use thinner lines than power nets
-                    // NETS often don't have explicit width, so use default if missing
-                    let line_width = if route.width > 0.0 {
-                        (route.width / db_units) as f32 * self.zoom
+                    // Regular wiring takes the WIDTH of the LEF layer
+                    let width = if route.width > 0.0 {
+                        route.width / db_units
                     } else {
-                        1.0 // Default width for signal nets
+                        self.tech_layer_styles
+                            .get(&route.layer)
+                            .and_then(|style| style.width)
+                            .unwrap_or(0.0)
                     };
-                    let min_width = 0.5; // Thinner minimum for signal nets
-                    let visual_width = line_width.max(min_width);
-
-                    // Render route points as connected line segments
-                    if route.points.len() >= 2 {
-                        for i in 0..route.points.len() - 1 {
-                            let p1 = &route.points[i];
-                            let p2 = &route.points[i + 1];
-
-                            // Convert to screen coordinates with Y-flip
-                            let x1 = (p1.x / db_units) as f32;
-                            let y1 = (p1.y / db_units) as f32;
-                            let x2 = (p2.x / db_units) as f32;
-                            let y2 = (p2.y / db_units) as f32;
-
-                            let screen_x1 = center.x + self.pan_x + (x1 * self.zoom);
-                            let screen_y1 =
-                                center.y + self.pan_y + ((die_area_max_y as f32 - y1) * self.zoom);
-                            let screen_x2 = center.x + self.pan_x + (x2 * self.zoom);
-                            let screen_y2 =
-                                center.y + self.pan_y + ((die_area_max_y as f32 - y2) * self.zoom);
+                    let half_width = width as f32 * self.zoom / 2.0;
+
+                    for pair in route.points.windows(2) {
+                        let (start, end) = (&pair[0], &pair[1]);
+                        let (p1, p2) = (to_screen(start.x, start.y), to_screen(end.x, end.y));
+                        let orthogonal = p1.x == p2.x || p1.y == p2.y;
+                        if net.shielded && p1 != p2 {
+                            // Shielded nets are drawn with a double line,
+                            // one rail on each side of the wire
+                            let normal = (p2 - p1).normalized().rot90();
+                            let offset = half_width + self.dpi_style.stroke(1.5);
+                            let rail = egui::Stroke::new(self.dpi_style.stroke(1.0), color);
+                            for side in [normal * offset, -normal * offset] {
+                                painter.line_segment([p1 + side, p2 + side], rail);
+                            }
+                            if half_width < 1.0 {
+                                continue;
+                            }
+                        }
+                        if half_width < 1.0 || p1 == p2 {
+                            // Too thin to show the outline
+                            let stroke_width = (half_width * 2.0).max(self.dpi_style.stroke(1.0));
+                            painter.line_segment([p1, p2], egui::Stroke::new(stroke_width, color));
+                            continue;
+                        }
 
-                            // Draw thin line for signal routing
-                            painter.line_segment(
-                                [
-                                    egui::pos2(screen_x1, screen_y1),
-                                    egui::pos2(screen_x2, screen_y2),
-                                ],
-                                egui::Stroke::new(visual_width, net_color),
+                        // Wires end half their width past each point unless an
+                        // extension value is given
+                        let extension = |ext: Option<f64>| {
+                            ext.map_or(half_width, |e| (e / db_units) as f32 * self.zoom)
+                        };
+                        if !orthogonal {
+                            // 45-degree wires are rotated rectangles
+                            let corners = crate::geometry::wire_outline(
+                                (p1.x.into(), p1.y.into()),
+                                (p2.x.into(), p2.y.into()),
+                                half_width.into(),
+                                extension(start.ext).into(),
+                                extension(end.ext).into(),
                             );
+                            let points = corners
+                                .iter()
+                                .map(|&(x, y)| egui::pos2(x as f32, y as f32))
+                                .collect();
+                            painter.add(egui::Shape::convex_polygon(
+                                points,
+                                color,
+                                egui::Stroke::NONE,
+                            ));
+                            continue;
                         }
+                        let direction = (p2 - p1).normalized();
+                        let from = p1 - direction * extension(start.ext);
+                        let to = p2 + direction * extension(end.ext);
+                        let across = egui::vec2(direction.y.abs(), direction.x.abs()) * half_width;
+                        let wire = egui::Rect::from_two_pos(from, to).expand2(across);
+                        painter.rect_filled(wire, 0.0, color);
                     }
 
-                    // Render vias (less prominent than power vias)
+                    // Render vias on the routing points
                     for (via_name, via_x, via_y) in &route.vias {
-                        let x = (*via_x / db_units) as f32;
-                        let y = (*via_y / db_units) as f32;
-
-                        let screen_x = center.x + self.pan_x + (x * self.zoom);
-                        let screen_y =
-                            center.y + self.pan_y + ((die_area_max_y as f32 - y) * self.zoom);
-
-                        // Draw via as small square (smaller than power vias)
-                        let via_size = (3.0 * self.zoom).max(1.5);
+                        let via_center = to_screen(*via_x, *via_y);
+                        let via_size = (half_width * 2.0).max(3.0 * self.zoom).max(1.5);
                         let via_rect = egui::Rect::from_center_size(
-                            egui::pos2(screen_x, screen_y),
+                            via_center,
                             egui::vec2(via_size, via_size),
                         );
 
-                        painter.rect_filled(via_rect, 0.0, net_color);
+                        painter.rect_filled(via_rect, 0.0, color);
                         painter.rect_stroke(
                             via_rect,
                             0.0,
-                            egui::Stroke::new(0.5, egui::Color32::WHITE),
+                            egui::Stroke::new(self.dpi_style.stroke(0.5), egui::Color32::WHITE),
                             egui::StrokeKind::Middle,
                         );
 
                         // Only draw via name at very high zoom
                         if self.zoom > 10.0 {
                             texts_to_render.push((
-                                egui::pos2(screen_x + via_size, screen_y),
+                                egui::pos2(via_center.x + via_size, via_center.y),
                                 via_name.clone(),
-                                egui::FontId::monospace(6.0),
+                                egui::FontId::monospace(self.dpi_style.font_size(6.0)),
                                 egui::Color32::WHITE,
                             ));
                         }
@@ -1611,6 +2966,132 @@ impl LefDefViewer {
                 }
             }
         }
+
+        if self.show_scan_chains {
+            self.paint_scan_chains(painter, def, center, die_area_max_y, texts_to_render);
+        }
+        if self.probe_selected_nets {
+            self.paint_net_probe(painter, def, center, die_area_max_y);
+        }
+        self.paint_flight_lines(painter, def, center, die_area_max_y);
+        self.paint_resource_map(painter, center, die_area_max_y);
+    }
+
+    /// Routing resource map of the loaded design with the OBS of all
+    /// loaded macros, on the ROUTING layers of the technology LEFs
+    fn compute_resource_map(&self) -> Option<ResourceMap> {
+        crate::profile_scope!("compute_resource_map");
+        let def = self.def_data.as_ref()?;
+        let mut macros = HashMap::new();
+        for macro_def in self.lef_files.iter().flat_map(|f| &f.data.macros) {
+            macros.entry(macro_def.name.as_str()).or_insert(macro_def);
+        }
+        ResourceMap::compute(
+            def,
+            self.def_db_units(),
+            &macros,
+            &self.routing_layer_names(),
+        )
+    }
+
+    /// ROUTING layers of the technology LEFs, bottom to top
+    fn routing_layer_names(&self) -> Vec<String> {
+        let mut routing_layers: Vec<String> = Vec::new();
+        for layer in self.lef_files.iter().flat_map(|f| &f.data.layers) {
+            if layer.is_routing() && !routing_layers.contains(&layer.name) {
+                routing_layers.push(layer.name.clone());
+            }
+        }
+        routing_layers
+    }
+
+    /// Heatmap of the layer selected in the routing resources panel
+    fn paint_resource_map(&self, painter: &egui::Painter, center: egui::Pos2, die_area_max_y: f64) {
+        let Some((map, layer)) = self.resource_panel.heatmap() else {
+            return;
+        };
+        crate::profile_scope!("paint_resource_map");
+        let to_screen = |x: f64, y: f64| {
+            egui::pos2(
+                center.x + self.pan_x + x as f32 * self.zoom,
+                center.y + self.pan_y + (die_area_max_y - y) as f32 * self.zoom,
+            )
+        };
+        let clip_rect = painter.clip_rect();
+        let columns = map.grid.columns();
+        for (i, &blocked) in layer.blocked.iter().enumerate() {
+            if blocked <= 0.0 {
+                continue;
+            }
+            let (x1, y1, x2, y2) = map.grid.cell_rect(i % columns, i / columns);
+            let rect = egui::Rect::from_two_pos(to_screen(x1, y1), to_screen(x2, y2));
+            if rect.intersects(clip_rect) {
+                painter.rect_filled(rect, 0.0, heat_color(blocked, self.resource_panel.opacity));
+            }
+        }
+    }
+
+    /// Whether a box in microns is drawn, always true outside isolation
+    fn in_isolation(&self, min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> bool {
+        self.isolation
+            .as_ref()
+            .is_none_or(|isolation| isolation.overlaps(min_x, min_y, max_x, max_y))
+    }
+
+    /// Whether a route in DBU touches the isolated region
+    fn route_in_isolation(&self, route: &def::DefRoute, db_units: f64) -> bool {
+        self.isolation.as_ref().is_none_or(|isolation| {
+            isolation.overlaps_points(
+                route
+                    .points
+                    .iter()
+                    .map(|p| (p.x / db_units, p.y / db_units)),
+            )
+        })
+    }
+
+    /// Show only `name` and what lies within the halo around it
+    fn isolate_component(&mut self, name: &str) {
+        let bounds = self
+            .def_data
+            .as_ref()
+            .and_then(|def| def.components.iter().find(|c| c.name == name))
+            .and_then(|c| self.component_bounds(c));
+        let Some((min_x, min_y, max_x, max_y)) = bounds else {
+            self.error_message = Some(format!("{name} has no placement"));
+            return;
+        };
+        let isolation = Isolation::new(
+            name,
+            (min_x as f64, min_y as f64, max_x as f64, max_y as f64),
+        );
+        self.frame_isolation(&isolation);
+        self.isolation = Some(isolation);
+        self.inspected_component = Some(name.to_string());
+    }
+
+    fn frame_isolation(&mut self, isolation: &Isolation) {
+        let (min_x, min_y, max_x, max_y) = isolation.region();
+        self.focus_bounds_requested =
+            Some((min_x as f32, min_y as f32, max_x as f32, max_y as f32));
+    }
+
+    /// Color of a special net by its USE: red for power, blue for ground
+    fn special_net_color(use_type: Option<&str>) -> egui::Color32 {
+        match use_type {
+            Some("POWER") => egui::Color32::from_rgb(255, 50, 50),
+            Some("GROUND") => egui::Color32::from_rgb(50, 50, 255),
+            _ => egui::Color32::from_rgb(200, 200, 0),
+        }
+    }
+
+    /// Visibility group of a special net: "POWER", "GROUND" or "OTHER"
+    fn special_net_class(net: &def::DefSpecialNet) -> &'static str {
+        match net.use_type.as_deref() {
+            Some("POWER") => "POWER",
+            Some("GROUND") => "GROUND",
+            _ => "OTHER",
+        }
     }
 
     /// Render placeholder for missing LEF cells (not found in loaded LEF files)
@@ -1666,7 +3147,7 @@ impl LefDefViewer {
         painter.rect_stroke(
             rect,
             0.0,
-            egui::Stroke::new(2.0, outline_color),
+            egui::Stroke::new(self.dpi_style.stroke(2.0), outline_color),
             egui::StrokeKind::Middle,
         );
 
@@ -1676,14 +3157,14 @@ impl LefDefViewer {
                 egui::pos2(screen_min_x, screen_min_y),
                 egui::pos2(screen_max_x, screen_max_y),
             ],
-            egui::Stroke::new(2.0, outline_color),
+            egui::Stroke::new(self.dpi_style.stroke(2.0), outline_color),
         );
         painter.line_segment(
             [
                 egui::pos2(screen_max_x, screen_min_y),
                 egui::pos2(screen_min_x, screen_max_y),
             ],
-            egui::Stroke::new(2.0, outline_color),
+            egui::Stroke::new(self.dpi_style.stroke(2.0), outline_color),
         );
 
         // Collect text for later rendering (so it appears on top of all shapes)
@@ -1692,11 +3173,17 @@ impl LefDefViewer {
             let center_x = (screen_min_x + screen_max_x) / 2.0;
             let center_y = (screen_min_y + screen_max_y) / 2.0;
 
+            let bus_bit_chars = self
+                .def_data
+                .as_ref()
+                .map(|def| def.bus_bit_chars)
+                .unwrap_or_default();
+
             // Component name above center
             texts_to_render.push((
                 egui::pos2(center_x, center_y - 10.0),
-                component.name.clone(),
-                egui::FontId::proportional(10.0),
+                identifier::display_name(&component.name, bus_bit_chars),
+                egui::FontId::proportional(self.dpi_style.font_size(10.0)),
                 egui::Color32::WHITE,
             ));
 
@@ -1705,7 +3192,7 @@ impl LefDefViewer {
             texts_to_render.push((
                 egui::pos2(center_x, center_y + 10.0),
                 text2,
-                egui::FontId::proportional(8.0),
+                egui::FontId::proportional(self.dpi_style.font_size(8.0)),
                 egui::Color32::WHITE,
             ));
         }
@@ -1750,6 +3237,10 @@ impl LefDefViewer {
     fn get_layer_color(&self, layer: &str) -> egui::Color32 {
         // Extract base layer name (before any '.' separator)
         let base_layer = layer.split('.').next().unwrap_or(layer);
+        let base_layer = self
+            .tech_layer_styles
+            .get(base_layer)
+            .map_or(base_layer, |style| style.palette_layer.as_str());
 
         // Determine type-specific color adjustment
         let (base_color, type_adjustment) = match base_layer {
@@ -1863,7 +3354,8 @@ impl LefDefViewer {
         };
 
         // Base layer ordering (multiply by 10 to leave room for type ordering)
-        let base_order = match base_layer {
+        let tech_order = self.tech_layer_styles.get(base_layer).map(|s| s.order);
+        let base_order = tech_order.unwrap_or(match base_layer {
             "OUTLINE" => 5,
             "POLY" | "POLY1" => 10,
             "NDIFF" | "DIFF" | "PDIFF" => 20,
@@ -1881,7 +3373,7 @@ impl LefDefViewer {
             "M7" | "METAL7" => 140,
             "M8" | "METAL8" => 150,
             _ => 0, // Default bottom layer
-        } * 10;
+        }) * 10;
 
         // Type-specific ordering within each base layer
         let type_order = match layer_type {
@@ -1896,47 +3388,23 @@ impl LefDefViewer {
 
     // Tessellate a concave polygon into triangles using lyon
     fn tessellate_polygon(points: &[egui::Pos2], color: egui::Color32) -> egui::epaint::Mesh {
-        let mut mesh = egui::epaint::Mesh::default();
-
-        if points.len() < 3 {
-            return mesh;
-        }
-
-        // Build lyon path
-        let mut builder = LyonPath::builder();
-        builder.begin(point(points[0].x, points[0].y));
-        for p in &points[1..] {
-            builder.line_to(point(p.x, p.y));
-        }
-        builder.end(true);
-        let path = builder.build();
-
-        // Tessellate
-        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
-        let mut tessellator = FillTessellator::new();
-        {
-            tessellator
-                .tessellate_path(
-                    &path,
-                    &FillOptions::default(),
-                    &mut BuffersBuilder::new(&mut buffers, |vertex: FillVertex| vertex.position()),
-                )
-                .unwrap();
-        }
+        crate::profile_scope!("tessellate_polygon");
+        let points: Vec<(f32, f32)> = points.iter().map(|p| (p.x, p.y)).collect();
+        let (vertices, indices) = crate::geometry::triangulate(&points);
 
         // Convert to egui mesh
-        mesh.vertices = buffers
-            .vertices
-            .iter()
-            .map(|v| egui::epaint::Vertex {
-                pos: egui::pos2(v.x, v.y),
-                uv: egui::pos2(0.0, 0.0),
-                color,
-            })
-            .collect();
-        mesh.indices = buffers.indices.iter().map(|&i| i as u32).collect();
-
-        mesh
+        egui::epaint::Mesh {
+            vertices: vertices
+                .into_iter()
+                .map(|(x, y)| egui::epaint::Vertex {
+                    pos: egui::pos2(x, y),
+                    uv: egui::pos2(0.0, 0.0),
+                    color,
+                })
+                .collect(),
+            indices,
+            ..Default::default()
+        }
     }
 
     /// Start the background tessellation worker thread
@@ -1945,6 +3413,12 @@ impl LefDefViewer {
             return; // Already started or disabled
         }
 
+        if !platform::THREADS {
+            // The worker waits for jobs and would never return
+            self.progressive_rendering_enabled = false;
+            return;
+        }
+
         let (job_tx, job_rx) = mpsc::channel::<TessellationJob>();
         let (result_tx, result_rx) = mpsc::channel::<RenderMessage>();
 
@@ -1952,9 +3426,12 @@ impl LefDefViewer {
         self.render_result_receiver = Some(result_rx);
 
         // Spawn background worker thread
-        thread::spawn(move || {
+        let queue = Arc::clone(&self.tessellation_queue);
+        let spawned = platform::spawn("tessellation", move || {
             log::info!("Progressive rendering worker thread started");
             while let Ok(job) = job_rx.recv() {
+                queue.fetch_sub(1, Ordering::Relaxed);
+                crate::profile_scope!("tessellate_job");
                 let cached_mesh = match job.shape {
                     ShapeData::Rectangle { xl, yl, xh, yh } => {
                         // Create simple quad as two triangles
@@ -1974,47 +3451,19 @@ impl LefDefViewer {
                     }
                     ShapeData::Polygon { ref points } => {
                         // Tessellate the polygon in world coordinates
-                        let pos_points: Vec<egui::Pos2> = points
-                            .iter()
-                            .map(|&(x, y)| egui::pos2(x as f32, y as f32))
-                            .collect();
-
-                        if pos_points.len() < 3 {
-                            continue;
-                        }
-
-                        // Build lyon path
-                        let mut builder = LyonPath::builder();
-                        builder.begin(point(pos_points[0].x, pos_points[0].y));
-                        for p in &pos_points[1..] {
-                            builder.line_to(point(p.x, p.y));
-                        }
-                        builder.end(true);
-                        let path = builder.build();
-
-                        // Tessellate
-                        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
-                        let mut tessellator = FillTessellator::new();
-                        if tessellator
-                            .tessellate_path(
-                                &path,
-                                &FillOptions::default(),
-                                &mut BuffersBuilder::new(&mut buffers, |vertex: FillVertex| {
-                                    vertex.position()
-                                }),
-                            )
-                            .is_err()
-                        {
+                        let points: Vec<(f32, f32)> =
+                            points.iter().map(|&(x, y)| (x as f32, y as f32)).collect();
+                        let (vertices, indices) = crate::geometry::triangulate(&points);
+                        if indices.is_empty() {
                             continue;
                         }
 
                         CachedMesh {
-                            vertices: buffers
-                                .vertices
-                                .iter()
-                                .map(|v| egui::pos2(v.x, v.y))
+                            vertices: vertices
+                                .into_iter()
+                                .map(|(x, y)| egui::pos2(x, y))
                                 .collect(),
-                            indices: buffers.indices.iter().map(|&i| i as u32).collect(),
+                            indices,
                             color: job.color,
                         }
                     }
@@ -2033,6 +3482,12 @@ impl LefDefViewer {
             }
             log::info!("Progressive rendering worker thread exiting");
         });
+        if let Err(e) = spawned {
+            log::warn!("Failed to start the tessellation worker: {e}");
+            self.progressive_rendering_enabled = false;
+            self.render_job_sender = None;
+            self.render_result_receiver = None;
+        }
     }
 
     /// Process incoming render messages from background thread
@@ -2044,7 +3499,10 @@ impl LefDefViewer {
                     RenderMessage::MeshReady { cache_key, mesh } => {
                         // Store in cache
                         if let Ok(mut cache) = self.mesh_cache.write() {
-                            cache.insert(cache_key, mesh);
+                            cache
+                                .entry(cache_key.macro_name)
+                                .or_default()
+                                .insert(cache_key.shape_index, mesh);
                         }
                         // Request repaint to show the new mesh
                         ctx.request_repaint();
@@ -2054,25 +3512,55 @@ impl LefDefViewer {
         }
     }
 
+    /// Key under which a macro's tessellated meshes are cached
+    ///
+    /// Falls back to the macro name for macros without a recorded geometry hash.
+    fn mesh_geometry_key<'a>(&'a self, macro_def: &'a crate::lef::LefMacro) -> &'a str {
+        self.macro_geometry_keys
+            .get(&macro_def.name)
+            .map_or(&macro_def.name, String::as_str)
+    }
+
+    /// Macro as rendered: supply pins with merged RECTs when that saves shapes
+    fn render_macro<'a>(&'a self, macro_def: &'a crate::lef::LefMacro) -> &'a crate::lef::LefMacro {
+        self.merged_macros.get(&macro_def.name).unwrap_or(macro_def)
+    }
+
+    /// Send a job to the tessellation worker, counted for the status bar
+    fn queue_tessellation(&self, job: TessellationJob) {
+        if let Some(sender) = &self.render_job_sender {
+            self.tessellation_queue.fetch_add(1, Ordering::Relaxed);
+            if sender.send(job).is_err() {
+                self.tessellation_queue.fetch_sub(1, Ordering::Relaxed);
+            }
+        }
+    }
+
     /// Queue a macro's details for background tessellation
     fn tessellate_macro_details(&self, macro_def: &crate::lef::LefMacro) {
         if !self.progressive_rendering_enabled {
             return;
         }
 
-        // Check if already tessellated
-        if let Ok(tessellated) = self.tessellated_macros.lock() {
-            if tessellated.contains(&macro_def.name) {
+        // Macros with identical geometry share one set of meshes
+        let geometry_key = self.mesh_geometry_key(macro_def);
+
+        if self.render_job_sender.is_none() {
+            return;
+        }
+
+        // Check if already tessellated (or queued), and claim it otherwise
+        if let Ok(mut tessellated) = self.tessellated_macros.lock() {
+            if tessellated.contains(geometry_key) {
                 return; // Already done
             }
+            tessellated.insert(geometry_key.to_string());
         }
 
-        let Some(sender) = &self.render_job_sender else {
-            return;
-        };
-
         let mut shape_index = 0;
 
+        // Shapes are queued in raw LEF coordinates (relative to ORIGIN) so the
+        // meshes can be shared by any macro with the same geometry
         // Queue PIN shapes (both rectangles and polygons)
         for pin in &macro_def.pins {
             for port in &pin.ports {
@@ -2083,21 +3571,19 @@ impl LefDefViewer {
 
                     let job = TessellationJob {
                         cache_key: MeshCacheKey {
-                            macro_name: macro_def.name.clone(),
-                            shape_type: "PIN".to_string(),
-                            layer_name: detailed_layer,
+                            macro_name: geometry_key.to_string(),
                             shape_index,
                         },
                         shape: ShapeData::Rectangle {
-                            xl: macro_def.origin.0 + rect_data.xl,
-                            yl: macro_def.origin.1 + rect_data.yl,
-                            xh: macro_def.origin.0 + rect_data.xh,
-                            yh: macro_def.origin.1 + rect_data.yh,
+                            xl: rect_data.xl,
+                            yl: rect_data.yl,
+                            xh: rect_data.xh,
+                            yh: rect_data.yh,
                         },
                         color,
                     };
 
-                    let _ = sender.send(job);
+                    self.queue_tessellation(job);
                     shape_index += 1;
                 }
 
@@ -2106,18 +3592,12 @@ impl LefDefViewer {
                     let detailed_layer = format!("{}.PIN", polygon_data.layer);
                     let color = self.get_layer_color(&detailed_layer);
 
-                    // Transform polygon points with origin offset
-                    let transformed_points: Vec<(f64, f64)> = polygon_data
-                        .points
-                        .iter()
-                        .map(|&(x, y)| (macro_def.origin.0 + x, macro_def.origin.1 + y))
-                        .collect();
+                    // Keep LEF coordinates; ORIGIN is applied by MacroTransform when drawing
+                    let transformed_points: Vec<(f64, f64)> = polygon_data.points.clone();
 
                     let job = TessellationJob {
                         cache_key: MeshCacheKey {
-                            macro_name: macro_def.name.clone(),
-                            shape_type: "PIN".to_string(),
-                            layer_name: detailed_layer,
+                            macro_name: geometry_key.to_string(),
                             shape_index,
                         },
                         shape: ShapeData::Polygon {
@@ -2126,7 +3606,7 @@ impl LefDefViewer {
                         color,
                     };
 
-                    let _ = sender.send(job);
+                    self.queue_tessellation(job);
                     shape_index += 1;
                 }
             }
@@ -2141,21 +3621,19 @@ impl LefDefViewer {
 
                 let job = TessellationJob {
                     cache_key: MeshCacheKey {
-                        macro_name: macro_def.name.clone(),
-                        shape_type: "OBS".to_string(),
-                        layer_name: detailed_layer,
+                        macro_name: geometry_key.to_string(),
                         shape_index,
                     },
                     shape: ShapeData::Rectangle {
-                        xl: macro_def.origin.0 + rect_data.xl,
-                        yl: macro_def.origin.1 + rect_data.yl,
-                        xh: macro_def.origin.0 + rect_data.xh,
-                        yh: macro_def.origin.1 + rect_data.yh,
+                        xl: rect_data.xl,
+                        yl: rect_data.yl,
+                        xh: rect_data.xh,
+                        yh: rect_data.yh,
                     },
                     color,
                 };
 
-                let _ = sender.send(job);
+                self.queue_tessellation(job);
                 shape_index += 1;
             }
 
@@ -2164,18 +3642,12 @@ impl LefDefViewer {
                 let detailed_layer = format!("{}.OBS", polygon_data.layer);
                 let color = self.get_layer_color(&detailed_layer);
 
-                // Transform polygon points with origin offset
-                let transformed_points: Vec<(f64, f64)> = polygon_data
-                    .points
-                    .iter()
-                    .map(|&(x, y)| (macro_def.origin.0 + x, macro_def.origin.1 + y))
-                    .collect();
+                // Keep LEF coordinates; ORIGIN is applied by MacroTransform when drawing
+                let transformed_points: Vec<(f64, f64)> = polygon_data.points.clone();
 
                 let job = TessellationJob {
                     cache_key: MeshCacheKey {
-                        macro_name: macro_def.name.clone(),
-                        shape_type: "OBS".to_string(),
-                        layer_name: detailed_layer,
+                        macro_name: geometry_key.to_string(),
                         shape_index,
                     },
                     shape: ShapeData::Polygon {
@@ -2184,7 +3656,7 @@ impl LefDefViewer {
                     color,
                 };
 
-                let _ = sender.send(job);
+                self.queue_tessellation(job);
                 shape_index += 1;
             }
         }
@@ -2311,9 +3783,10 @@ impl LefDefViewer {
         }
 
         if let Some(def) = &self.def_data {
+            let db_units = self.def_db_units() as f32;
             for point in &def.die_area_points {
-                let x = point.0 as f32 * 0.001; // Scale from microns
-                let y = point.1 as f32 * 0.001;
+                let x = point.0 as f32 / db_units; // Scale to microns
+                let y = point.1 as f32 / db_units;
                 min_x = min_x.min(x);
                 min_y = min_y.min(y);
                 max_x = max_x.max(x);
@@ -2339,53 +3812,17 @@ impl LefDefViewer {
         // In DEF mode, calculate bounds from actual component placements
         if self.def_mode {
             if let Some(def) = &self.def_data {
-                let db_units = 1000.0;
-
                 // Iterate through all components and calculate their bounding boxes
-                for component in &def.components {
-                    // Get component placement
-                    if let Some(ref placement) = component.placement {
-                        let px = (placement.x / db_units) as f32;
-                        let py = (placement.y / db_units) as f32;
-
-                        // Find the LEF macro for this component
-                        let mut macro_size_x = 5.0; // Default size if macro not found
-                        let mut macro_size_y = 5.0;
-
-                        for lef_file in &self.lef_files {
-                            if let Some(macro_def) = lef_file
-                                .data
-                                .macros
-                                .iter()
-                                .find(|m| m.name == component.macro_name)
-                            {
-                                macro_size_x = macro_def.size_x as f32;
-                                macro_size_y = macro_def.size_y as f32;
-                                break;
-                            }
-                        }
-
-                        // Calculate component bounding box based on orientation
-                        // For simplicity, use axis-aligned bounding box
-                        let orientation = &placement.orientation;
-                        let (width, height) = match orientation.as_str() {
-                            "N" | "S" | "FN" | "FS" => (macro_size_x, macro_size_y),
-                            "E" | "W" | "FE" | "FW" => (macro_size_y, macro_size_x), // Rotated 90/270
-                            _ => (macro_size_x, macro_size_y),
-                        };
-
-                        // Component bounding box
-                        let comp_min_x = px;
-                        let comp_min_y = py;
-                        let comp_max_x = px + width;
-                        let comp_max_y = py + height;
-
-                        min_x = min_x.min(comp_min_x);
-                        min_y = min_y.min(comp_min_y);
-                        max_x = max_x.max(comp_max_x);
-                        max_y = max_y.max(comp_max_y);
-                        found_any = true;
-                    }
+                for (comp_min_x, comp_min_y, comp_max_x, comp_max_y) in def
+                    .components
+                    .iter()
+                    .filter_map(|c| self.component_bounds(c))
+                {
+                    min_x = min_x.min(comp_min_x);
+                    min_y = min_y.min(comp_min_y);
+                    max_x = max_x.max(comp_max_x);
+                    max_y = max_y.max(comp_max_y);
+                    found_any = true;
                 }
 
                 if found_any && max_x > min_x && max_y > min_y {
@@ -2435,75 +3872,731 @@ impl LefDefViewer {
         }
     }
 
-    fn fit_to_view(&mut self, available_size: egui::Vec2) {
-        if let Some((min_x, min_y, max_x, max_y)) = self.calculate_outline_bounds() {
-            let content_width = max_x - min_x;
-            let content_height = max_y - min_y;
-
-            if content_width > 0.0 && content_height > 0.0 {
-                // Use 90% of available space for content, 10% for margin
-                let target_width = available_size.x * 0.9;
-                let target_height = available_size.y * 0.9;
+    fn camera(&self) -> Camera {
+        Camera {
+            zoom: self.zoom,
+            pan_x: self.pan_x,
+            pan_y: self.pan_y,
+        }
+    }
 
-                let scale_x = target_width / content_width;
-                let scale_y = target_height / content_height;
+    fn set_camera(&mut self, camera: Camera) {
+        self.zoom = camera.zoom;
+        self.pan_x = camera.pan_x;
+        self.pan_y = camera.pan_y;
+    }
 
-                // Use the smaller scale to ensure everything fits
-                self.zoom = scale_x.min(scale_y).max(0.1);
+    /// Switch between the LEF and the DEF view with the last camera of each
+    ///
+    /// A view shown for the first time is fitted.
+    fn set_def_mode(&mut self, def_mode: bool) {
+        let def_mode = def_mode && self.def_data.is_some();
+        if def_mode == self.def_mode {
+            return;
+        }
+        let restored = self.mode_cameras.switch(self.def_mode, self.camera());
+        self.def_mode = def_mode;
+        self.isolation = None;
+        match restored {
+            Some(camera) => self.set_camera(camera),
+            None => self.fit_to_view_requested = true,
+        }
+    }
 
-                // Center the content properly - use the outside boundary center
-                let center_x = (min_x + max_x) * 0.5;
-                let center_y = (min_y + max_y) * 0.5;
+    /// Current view as a permalink state
+    fn view_state(&self) -> ViewState {
+        let def_hash = self
+            .def_file_path
+            .as_deref()
+            .and_then(|path| Self::calculate_file_hash(path).ok());
+        ViewState {
+            lef_hashes: self.lef_files.iter().map(|f| f.file_hash.clone()).collect(),
+            def_hash,
+            ..self.current_view()
+        }
+    }
 
-                // Reset pan to center the content in the available space
-                self.pan_x = -center_x * self.zoom;
+    /// Camera, layers and selections, without the file hashes
+    fn current_view(&self) -> ViewState {
+        ViewState {
+            lef_hashes: Vec::new(),
+            def_hash: None,
+            def_mode: self.def_mode,
+            zoom: self.zoom,
+            pan_x: self.pan_x,
+            pan_y: self.pan_y,
+            visible_layers: permalink::sorted(&self.visible_layers),
+            show_components: self.show_components,
+            show_pins: self.show_pins,
+            show_nets: self.show_nets,
+            show_special_nets: self.show_special_nets,
+            show_diearea: self.show_diearea,
+            show_pin_text: self.show_pin_text,
+            show_component_text: self.show_component_text,
+            selected_cells: permalink::sorted(&self.selected_cells),
+            selected_lef_pins: permalink::sorted(&self.selected_lef_pins),
+            selected_lef_obs: permalink::sorted(&self.selected_lef_obs),
+            selected_components: permalink::sorted(&self.selected_components),
+            selected_pins: permalink::sorted(&self.selected_pins),
+            selected_nets: permalink::sorted(&self.selected_nets),
+        }
+    }
 
-                // In DEF mode, rendering uses Y-flip: screen_y = center.y + pan_y + (die_area_max_y - world_y) * zoom
-                // To center at center_y: pan_y = -(die_area_max_y - center_y) * zoom
-                if self.def_mode {
-                    // Get die_area_max_y for Y-flip calculation
-                    if let Some(def) = &self.def_data {
-                        let db_units = 1000.0;
-                        let die_area_max_y = if !def.die_area_points.is_empty() {
-                            def.die_area_points
-                                .iter()
-                                .map(|p| (p.1 / db_units) as f32)
-                                .fold(f32::NEG_INFINITY, f32::max)
-                        } else {
-                            0.0
-                        };
-                        self.pan_y = -(die_area_max_y - center_y) * self.zoom;
-                        log::info!(
-                            "DEF fit_to_view: die_area_max_y={}, center_y={}, pan_y={}",
-                            die_area_max_y,
-                            center_y,
-                            self.pan_y
-                        );
-                    } else {
-                        self.pan_y = -center_y * self.zoom;
-                    }
-                } else {
-                    // LEF mode: standard formula
-                    self.pan_y = -center_y * self.zoom;
-                }
+    /// Restore a permalink view if the files it was made from are loaded
+    fn restore_view_state(&mut self, state: ViewState) -> Result<(), String> {
+        let missing_lefs = state
+            .lef_hashes
+            .iter()
+            .filter(|hash| !self.lef_files.iter().any(|f| &f.file_hash == *hash))
+            .count();
+        if missing_lefs > 0 {
+            return Err(format!(
+                "{missing_lefs} of the {} LEF files of this view are not loaded",
+                state.lef_hashes.len()
+            ));
+        }
+        if let Some(def_hash) = &state.def_hash {
+            let loaded = self
+                .def_file_path
+                .as_deref()
+                .and_then(|path| Self::calculate_file_hash(path).ok());
+            if loaded.as_ref() != Some(def_hash) {
+                return Err("The DEF file of this view is not loaded".to_string());
             }
         }
+
+        self.apply_view(state);
+        self.success_message = Some("Restored the shared view".to_string());
+        Ok(())
     }
 
-    /// Open LEF file dialog in background thread to avoid UI freeze
-    fn open_lef_file_dialog(&mut self) {
-        // Create channel for communication
-        let (tx, rx) = mpsc::channel();
-        self.loading_receiver = Some(rx);
+    /// Apply the camera, layers and selections of a view
+    fn apply_view(&mut self, state: ViewState) {
+        self.def_mode = state.def_mode && self.def_data.is_some();
+        self.set_camera(Camera {
+            zoom: state.zoom.clamp(MIN_ZOOM, MAX_ZOOM),
+            pan_x: state.pan_x,
+            pan_y: state.pan_y,
+        });
+        self.fit_to_view_requested = false;
+        self.fit_to_view_delay_frames = 0;
+        self.visible_layers = state.visible_layers.into_iter().collect();
+        self.show_components = state.show_components;
+        self.show_pins = state.show_pins;
+        self.show_nets = state.show_nets;
+        self.show_special_nets = state.show_special_nets;
+        self.show_diearea = state.show_diearea;
+        self.show_pin_text = state.show_pin_text;
+        self.show_component_text = state.show_component_text;
+        self.selected_cells = state.selected_cells.into_iter().collect();
+        self.selected_lef_pins = state.selected_lef_pins.into_iter().collect();
+        self.selected_lef_obs = state.selected_lef_obs.into_iter().collect();
+        self.selected_components = state.selected_components.into_iter().collect();
+        self.selected_pins = state.selected_pins.into_iter().collect();
+        self.selected_nets = state.selected_nets.into_iter().collect();
+    }
 
-        // Open file dialog in background thread
-        thread::spawn(move || {
-            let result = FileDialog::new()
-                .add_filter("LEF files", &["lef"])
-                .pick_files()
-                .map(|paths| {
-                    paths
-                        .iter()
+    /// Session to store for the next start
+    fn session(&self) -> Session {
+        Session {
+            restore_files: self.restore_session,
+            lef_paths: self.lef_files.iter().map(|f| f.path.clone()).collect(),
+            def_path: self.def_file_path.clone(),
+            view: self.current_view(),
+            panels: PanelLayout {
+                layers_panel: self.show_layers_panel,
+                lef_details: self.show_lef_details,
+                def_details: self.show_def_details,
+                component_table: self.component_table.visible,
+                checks_panel: self.checks_panel.visible,
+                issues_panel: self.issues_panel.visible,
+                charts_panel: self.charts_panel.visible,
+                resource_panel: self.resource_panel.visible,
+            },
+            workspace: WorkspaceLayout {
+                left_panel_width: self.left_panel_width,
+                layers_panel_width: self.layers_panel_width,
+                component_columns: self.component_table.column_widths.clone(),
+            },
+            mouse_bindings: self.mouse_bindings,
+            recent_files: self.recent_files.clone(),
+            tcl_dialect: self.tcl_dialect,
+            verilog_stub: self.verilog_stub_options,
+            spice_stub: self.spice_stub_options,
+        }
+    }
+
+    /// Selected components, nets and DEF pins, empty outside DEF mode
+    fn tcl_selection(&self) -> TclSelection {
+        let Some(def) = self.def_data.as_ref().filter(|_| self.def_mode) else {
+            return TclSelection::default();
+        };
+        let names = |selected: &std::collections::HashSet<String>| {
+            selected
+                .iter()
+                .map(|name| identifier::display_name(name, def.bus_bit_chars))
+                .collect()
+        };
+        TclSelection {
+            cells: names(&self.selected_components),
+            nets: names(&self.selected_nets),
+            ports: names(&self.selected_pins),
+        }
+    }
+
+    /// Take over the preferences of a stored session and start loading its
+    /// files
+    fn resume_session(&mut self, session: Session) {
+        let panels = session.panels;
+        self.show_layers_panel = panels.layers_panel;
+        self.show_lef_details = panels.lef_details;
+        self.show_def_details = panels.def_details;
+        self.component_table.visible = panels.component_table;
+        self.checks_panel.visible = panels.checks_panel;
+        self.issues_panel.visible = panels.issues_panel;
+        self.charts_panel.visible = panels.charts_panel;
+        self.resource_panel.visible = panels.resource_panel;
+        self.left_panel_width = session.workspace.left_panel_width;
+        self.layers_panel_width = session.workspace.layers_panel_width;
+        self.component_table.column_widths = session.workspace.component_columns.clone();
+        self.mouse_bindings = session.mouse_bindings;
+        self.recent_files = session.recent_files.clone();
+        self.tcl_dialect = session.tcl_dialect;
+        self.verilog_stub_options = session.verilog_stub;
+        self.spice_stub_options = session.spice_stub;
+        self.restore_session = session.restore_files;
+        self.session_restore = SessionRestore::new(&session);
+    }
+
+    /// Load the next files of the session being restored once the previous
+    /// ones are in, and apply its view after the last one
+    fn continue_session_restore(&mut self) {
+        if !self.loading_idle() {
+            return;
+        }
+        let Some(restore) = &mut self.session_restore else {
+            return;
+        };
+        let step = restore.next_step();
+        let missing = match step {
+            Some(RestoreStep::ApplyView(_)) => std::mem::take(&mut restore.missing),
+            _ => Vec::new(),
+        };
+        match step {
+            Some(RestoreStep::LoadLefs(paths)) => self.load_lef_paths(paths),
+            Some(RestoreStep::LoadDef(path)) => self.start_def_file_loading(path),
+            Some(RestoreStep::ApplyView(view)) => {
+                self.apply_view(*view);
+                if missing.is_empty() {
+                    self.status_bar.hint("Restored the last session");
+                } else {
+                    self.status_bar.hint(format!(
+                        "Restored the last session, {} file(s) no longer exist: {}",
+                        missing.len(),
+                        missing.join(", ")
+                    ));
+                }
+            }
+            None => self.session_restore = None,
+        }
+    }
+
+    /// Whether no file is loading or waiting for a decision
+    fn loading_idle(&self) -> bool {
+        self.loading_receiver.is_none()
+            && self.pending_loads == 0
+            && self.def_memory_warning.is_none()
+            && matches!(self.loading_state, LoadingState::Idle)
+    }
+
+    /// Load dropped or recent files by their detected type, the DEF after
+    /// the LEF files so its components find their macros
+    fn open_paths(&mut self, paths: Vec<String>) {
+        if !self.loading_idle() {
+            self.status_bar
+                .hint("Wait for the current file to finish loading");
+            return;
+        }
+        let mut lef_paths = Vec::new();
+        let mut unknown = Vec::new();
+        for path in paths {
+            if !Path::new(&path).is_file() {
+                self.recent_files.remove(&path);
+                unknown.push(path);
+                continue;
+            }
+            match FileKind::detect(Path::new(&path)) {
+                Some(FileKind::Lef) => lef_paths.push(path),
+                Some(FileKind::Def) => self.queued_def_load = Some(path),
+                None => unknown.push(path),
+            }
+        }
+        if !unknown.is_empty() {
+            self.status_bar.hint(format!(
+                "Not a readable LEF or DEF file: {}",
+                unknown.join(", ")
+            ));
+        }
+        if !lef_paths.is_empty() {
+            self.load_lef_paths(lef_paths);
+        }
+    }
+
+    /// Start the queued DEF load once nothing else is loading
+    fn start_queued_def_load(&mut self) {
+        if !self.loading_idle() {
+            return;
+        }
+        if let Some(path) = self.queued_def_load.take() {
+            self.start_def_file_loading(path);
+        } else if let Some((name, bytes)) = self.queued_def_bytes.take() {
+            self.start_def_bytes_loading(name, bytes);
+        }
+    }
+
+    /// Files dragged over or dropped on the window
+    fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        let (hovering, dropped, dropped_bytes) = ctx.input(|i| {
+            let mut dropped = Vec::new();
+            let mut dropped_bytes = Vec::new();
+            for file in &i.raw.dropped_files {
+                // Browsers hand over the contents instead of a path
+                match (&file.path, &file.bytes) {
+                    (Some(path), _) => dropped.push(path.to_string_lossy().to_string()),
+                    (None, Some(bytes)) => dropped_bytes.push((file.name.clone(), bytes.clone())),
+                    (None, None) => {}
+                }
+            }
+            (!i.raw.hovered_files.is_empty(), dropped, dropped_bytes)
+        });
+        if hovering {
+            let painter = ctx.layer_painter(egui::LayerId::new(
+                egui::Order::Foreground,
+                egui::Id::new("file_drop_overlay"),
+            ));
+            let screen = ctx.screen_rect();
+            painter.rect_filled(screen, 0.0, egui::Color32::from_black_alpha(160));
+            painter.text(
+                screen.center(),
+                egui::Align2::CENTER_CENTER,
+                "Drop LEF or DEF files to load them",
+                egui::FontId::proportional(24.0),
+                egui::Color32::WHITE,
+            );
+        }
+        if !dropped.is_empty() {
+            self.open_paths(dropped);
+        }
+        if !dropped_bytes.is_empty() {
+            self.open_file_bytes(dropped_bytes);
+        }
+    }
+
+    /// Parse files handed over as names and contents, the way the browser
+    /// gives them, LEF files first and the DEF once they are in
+    fn open_file_bytes(&mut self, files: Vec<(String, Arc<[u8]>)>) {
+        if !self.loading_idle() {
+            self.status_bar
+                .hint("Wait for the current file to finish loading");
+            return;
+        }
+        let mut lef_files = Vec::new();
+        let mut unknown = Vec::new();
+        for (name, bytes) in files {
+            match FileKind::detect_bytes(&name, &bytes) {
+                Some(FileKind::Lef) => lef_files.push((name, bytes)),
+                Some(FileKind::Def) => self.queued_def_bytes = Some((name, bytes)),
+                None => unknown.push(name),
+            }
+        }
+        if !unknown.is_empty() {
+            self.status_bar.hint(format!(
+                "Not a readable LEF or DEF file: {}",
+                unknown.join(", ")
+            ));
+        }
+        if lef_files.is_empty() {
+            return;
+        }
+
+        let display_name = match lef_files.len() {
+            1 => lef_files[0].0.clone(),
+            n => format!("{} (+{} more)", lef_files[0].0, n - 1),
+        };
+        self.loading_state = LoadingState::Loading {
+            file_type: "LEF".to_string(),
+            file_name: display_name,
+            start_time: Instant::now(),
+            last_activity: Instant::now(),
+            show_progress: false,
+            progress: BTreeMap::new(),
+        };
+        let (tx, rx) = mpsc::channel();
+        self.loading_receiver = Some(rx);
+        for (name, bytes) in lef_files {
+            let file_hash = blake3::hash(&bytes).to_hex().to_string();
+            if self
+                .lef_files
+                .iter()
+                .any(|file| file.file_hash == file_hash)
+            {
+                log::info!("Skipping already loaded file: {}", name);
+                continue;
+            }
+            let tx = tx.clone();
+            self.pending_loads += 1;
+            let spawned = platform::spawn("lef-loader", move || {
+                let result = run_guarded(&name, || {
+                    Lef::parse_str(&String::from_utf8_lossy(&bytes))
+                        .map(|lef| (lef, file_hash))
+                        .map_err(|e| format!("Failed to load LEF file: {e}"))
+                });
+                let _ = tx.send(LoadingMessage::LefLoaded(result, name));
+            });
+            if let Err(e) = spawned {
+                self.pending_loads = self.pending_loads.saturating_sub(1);
+                self.error_message = Some(format!("Failed to start the LEF loader: {e}"));
+            }
+        }
+    }
+
+    /// Parse a DEF file handed over as its name and contents
+    fn start_def_bytes_loading(&mut self, name: String, bytes: Arc<[u8]>) {
+        self.loading_state = LoadingState::Loading {
+            file_type: "DEF".to_string(),
+            file_name: name.clone(),
+            start_time: Instant::now(),
+            last_activity: Instant::now(),
+            show_progress: false,
+            progress: BTreeMap::new(),
+        };
+        let (tx, rx) = mpsc::channel();
+        self.loading_receiver = Some(rx);
+
+        self.pending_loads = 1;
+        let spawned = platform::spawn("def-loader", move || {
+            let result = run_guarded(&name, || {
+                Def::parse_str(&String::from_utf8_lossy(&bytes))
+                    .map_err(|e| format!("Failed to load DEF file: {e}"))
+            });
+            let _ = tx.send(LoadingMessage::DefLoaded(Box::new(result), name));
+        });
+        if let Err(e) = spawned {
+            self.background_start_failed(e);
+        }
+    }
+
+    /// Load LEF files by path in the background, like files picked in the
+    /// open dialog
+    fn load_lef_paths(&mut self, paths: Vec<String>) {
+        let (tx, rx) = mpsc::channel();
+        let _ = tx.send(LoadingMessage::LefFilesSelected(paths));
+        self.loading_receiver = Some(rx);
+    }
+
+    fn fit_to_view(&mut self, available_size: egui::Vec2) {
+        match self.calculate_outline_bounds() {
+            Some(bounds) => self.zoom_to_bounds(bounds, available_size, 0.9),
+            // Nothing to frame, go back to the default camera
+            None => self.set_camera(Camera::default()),
+        }
+    }
+
+    /// Center the view on world `bounds` (microns) so they take up `fill`
+    /// of the available space
+    fn zoom_to_bounds(
+        &mut self,
+        (min_x, min_y, max_x, max_y): (f32, f32, f32, f32),
+        available_size: egui::Vec2,
+        fill: f32,
+    ) {
+        if ![min_x, min_y, max_x, max_y].iter().all(|v| v.is_finite()) {
+            log::warn!("Ignoring non-finite view bounds ({min_x}, {min_y}, {max_x}, {max_y})");
+            return;
+        }
+        let content_width = max_x - min_x;
+        let content_height = max_y - min_y;
+
+        if content_width > 0.0 && content_height > 0.0 {
+            // The rest of the available space is margin
+            let target_width = available_size.x * fill;
+            let target_height = available_size.y * fill;
+
+            let scale_x = target_width / content_width;
+            let scale_y = target_height / content_height;
+
+            // Use the smaller scale to ensure everything fits
+            self.zoom = scale_x.min(scale_y).clamp(0.1, MAX_ZOOM);
+
+            // Center the content properly - use the outside boundary center
+            let center_x = (min_x + max_x) * 0.5;
+            let center_y = (min_y + max_y) * 0.5;
+
+            // Reset pan to center the content in the available space
+            self.pan_x = -center_x * self.zoom;
+
+            // In DEF mode, rendering uses Y-flip: screen_y = center.y + pan_y + (die_area_max_y - world_y) * zoom
+            // To center at center_y: pan_y = -(die_area_max_y - center_y) * zoom
+            if self.def_mode {
+                // Get die_area_max_y for Y-flip calculation
+                if let Some(def) = &self.def_data {
+                    let db_units = self.def_db_units();
+                    let die_area_max_y = if !def.die_area_points.is_empty() {
+                        def.die_area_points
+                            .iter()
+                            .map(|p| (p.1 / db_units) as f32)
+                            .fold(f32::NEG_INFINITY, f32::max)
+                    } else {
+                        0.0
+                    };
+                    self.pan_y = -(die_area_max_y - center_y) * self.zoom;
+                    log::info!(
+                        "DEF zoom_to_bounds: die_area_max_y={}, center_y={}, pan_y={}",
+                        die_area_max_y,
+                        center_y,
+                        self.pan_y
+                    );
+                } else {
+                    self.pan_y = -center_y * self.zoom;
+                }
+            } else {
+                // LEF mode: standard formula
+                self.pan_y = -center_y * self.zoom;
+            }
+        }
+    }
+
+    /// Everything the go to dialog can jump to: macros in LEF mode, DEF
+    /// objects and the macros they use in DEF mode
+    fn goto_targets(&self) -> Vec<GotoTarget> {
+        let target = |kind, name: &str| GotoTarget {
+            kind,
+            name: name.to_string(),
+        };
+        let mut macro_names: Vec<&str> = self
+            .lef_files
+            .iter()
+            .flat_map(|f| f.data.macros.iter().map(|m| m.name.as_str()))
+            .collect();
+        macro_names.sort_unstable();
+        macro_names.dedup();
+        let mut targets: Vec<GotoTarget> = macro_names
+            .into_iter()
+            .map(|name| target(GotoKind::Macro, name))
+            .collect();
+
+        if let (true, Some(def)) = (self.def_mode, &self.def_data) {
+            targets.extend(
+                def.components
+                    .iter()
+                    .map(|c| target(GotoKind::Component, &c.name)),
+            );
+            targets.extend(def.nets.iter().map(|n| target(GotoKind::Net, &n.name)));
+            targets.extend(
+                def.special_nets
+                    .iter()
+                    .map(|n| target(GotoKind::Net, &n.name)),
+            );
+            targets.extend(def.pins.iter().map(|p| target(GotoKind::Pin, &p.name)));
+        }
+        targets
+    }
+
+    /// Select the object picked in the go to dialog and frame it
+    fn go_to_object(&mut self, target: GotoTarget) {
+        let bounds = match target.kind {
+            GotoKind::Macro if !self.def_mode => {
+                self.selected_cells.clear();
+                self.selected_cells.insert(target.name.clone());
+                self.fit_to_view_requested = true;
+                return;
+            }
+            GotoKind::Macro => {
+                // Frame every instance of the macro
+                let Some(def) = &self.def_data else {
+                    return;
+                };
+                let instances: Vec<_> = def
+                    .components
+                    .iter()
+                    .filter(|c| c.macro_name == target.name)
+                    .collect();
+                if instances.is_empty() {
+                    self.error_message =
+                        Some(format!("{} has no instances in the DEF", target.name));
+                    return;
+                }
+                self.selected_components = instances.iter().map(|c| c.name.clone()).collect();
+                union_bounds(instances.iter().filter_map(|c| self.component_bounds(c)))
+            }
+            GotoKind::Component => {
+                let component = self
+                    .def_data
+                    .as_ref()
+                    .and_then(|def| def.components.iter().find(|c| c.name == target.name));
+                let bounds = component.and_then(|c| self.component_bounds(c));
+                self.selected_components.insert(target.name.clone());
+                self.inspected_component = Some(target.name.clone());
+                bounds
+            }
+            GotoKind::Net => {
+                self.selected_nets.insert(target.name.clone());
+                self.net_bounds(&target.name)
+            }
+            GotoKind::Pin => self.pin_bounds(&target.name),
+        };
+
+        match bounds {
+            Some(bounds) => self.focus_bounds_requested = Some(bounds),
+            None => self.error_message = Some(format!("{} has no placement", target.name)),
+        }
+    }
+
+    /// Zoom to a check violation, selecting the component it names
+    fn focus_issue(&mut self, finding: &Finding) {
+        // Smallest region framed, in microns, so that pins and slivers keep
+        // their surroundings in view
+        const MIN_EXTENT: f64 = 5.0;
+        // Bounds are DEF coordinates
+        let Some((min_x, min_y, max_x, max_y)) = finding.bounds.filter(|_| self.def_mode) else {
+            return;
+        };
+        let pad_x = ((MIN_EXTENT - (max_x - min_x)) / 2.0).max(0.0);
+        let pad_y = ((MIN_EXTENT - (max_y - min_y)) / 2.0).max(0.0);
+        self.focus_bounds_requested = Some((
+            (min_x - pad_x) as f32,
+            (min_y - pad_y) as f32,
+            (max_x + pad_x) as f32,
+            (max_y + pad_y) as f32,
+        ));
+        let names_component = self
+            .def_data
+            .as_ref()
+            .is_some_and(|def| def.components.iter().any(|c| c.name == finding.object));
+        if names_component {
+            self.selected_components.insert(finding.object.clone());
+            self.inspected_component = Some(finding.object.clone());
+        }
+    }
+
+    /// Bounding box of a placed component in microns
+    fn component_bounds(&self, component: &def::DefComponent) -> Option<(f32, f32, f32, f32)> {
+        let placement = component.placement.as_ref()?;
+        let db_units = self.def_db_units();
+        let px = (placement.x / db_units) as f32;
+        let py = (placement.y / db_units) as f32;
+
+        // Default size if the macro is not found
+        let (size_x, size_y) = self
+            .lef_files
+            .iter()
+            .flat_map(|f| &f.data.macros)
+            .find(|m| m.name == component.macro_name)
+            .map_or((5.0, 5.0), |m| (m.size_x as f32, m.size_y as f32));
+
+        // Rotated orientations swap width and height
+        let (width, height) = match placement.orientation.as_str() {
+            "E" | "W" | "FE" | "FW" => (size_y, size_x),
+            _ => (size_x, size_y),
+        };
+        Some((px, py, px + width, py + height))
+    }
+
+    /// Bounding box in microns of the shapes of a DEF pin
+    fn pin_bounds(&self, name: &str) -> Option<(f32, f32, f32, f32)> {
+        let pin = self
+            .def_data
+            .as_ref()?
+            .pins
+            .iter()
+            .find(|p| p.name == name)?;
+        let db_units = self.def_db_units();
+        let rects = pin.placed_rects();
+        if rects.is_empty() {
+            // A pin without shapes is framed as a 2 um square
+            let (x, y) = ((pin.x / db_units) as f32, (pin.y / db_units) as f32);
+            return Some((x - 1.0, y - 1.0, x + 1.0, y + 1.0));
+        }
+        union_bounds(rects.iter().map(|r| {
+            (
+                (r.xl / db_units) as f32,
+                (r.yl / db_units) as f32,
+                (r.xh / db_units) as f32,
+                (r.yh / db_units) as f32,
+            )
+        }))
+    }
+
+    /// Bounding box in microns of the routing, instances and pins of a net
+    fn net_bounds(&self, name: &str) -> Option<(f32, f32, f32, f32)> {
+        let def = self.def_data.as_ref()?;
+        let db_units = self.def_db_units();
+        let mut boxes = Vec::new();
+
+        let routes = def
+            .nets
+            .iter()
+            .filter(|n| n.name == name)
+            .flat_map(|n| &n.routes)
+            .chain(
+                def.special_nets
+                    .iter()
+                    .filter(|n| n.name == name)
+                    .flat_map(|n| &n.routes),
+            );
+        for point in routes.flat_map(|r| &r.points) {
+            let (x, y) = ((point.x / db_units) as f32, (point.y / db_units) as f32);
+            boxes.push((x, y, x, y));
+        }
+
+        if let Some(net) = def.nets.iter().find(|n| n.name == name) {
+            let instances: HashSet<&str> = net.instances.iter().map(String::as_str).collect();
+            for component in &def.components {
+                if instances.contains(component.name.as_str()) {
+                    boxes.extend(self.component_bounds(component));
+                }
+            }
+            for (instance, pin) in net.instances.iter().zip(&net.instance_pins) {
+                if instance == "PIN" {
+                    boxes.extend(self.pin_bounds(pin));
+                }
+            }
+        }
+        union_bounds(boxes).map(|(x1, y1, x2, y2)| {
+            (
+                x1.min(x2 - 1.0),
+                y1.min(y2 - 1.0),
+                x2.max(x1 + 1.0),
+                y2.max(y1 + 1.0),
+            )
+        })
+    }
+
+    /// Report a background job of loading that could not be started and
+    /// stop waiting for it
+    fn background_start_failed(&mut self, error: std::io::Error) {
+        log::warn!("Failed to start a background thread: {error}");
+        self.error_message = Some(format!("Failed to start loading: {error}"));
+        self.loading_state = LoadingState::Idle;
+        self.loading_receiver = None;
+        self.pending_loads = 0;
+    }
+
+    /// Open LEF file dialog in background thread to avoid UI freeze
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_lef_file_dialog(&mut self) {
+        // Create channel for communication
+        let (tx, rx) = mpsc::channel();
+        self.loading_receiver = Some(rx);
+
+        // Open file dialog in background thread
+        let spawned = platform::spawn("file-dialog", move || {
+            let result = FileDialog::new()
+                .add_filter("LEF files", &["lef"])
+                .pick_files()
+                .map(|paths| {
+                    paths
+                        .iter()
                         .map(|path| path.to_string_lossy().to_string())
                         .collect::<Vec<String>>()
                 })
@@ -2511,16 +4604,20 @@ impl LefDefViewer {
 
             let _ = tx.send(LoadingMessage::LefFilesSelected(result));
         });
+        if let Err(e) = spawned {
+            self.background_start_failed(e);
+        }
     }
 
     /// Open DEF file dialog in background thread to avoid UI freeze
+    #[cfg(not(target_arch = "wasm32"))]
     fn open_def_file_dialog(&mut self) {
         // Create channel for communication
         let (tx, rx) = mpsc::channel();
         self.loading_receiver = Some(rx);
 
         // Open file dialog in background thread
-        thread::spawn(move || {
+        let spawned = platform::spawn("file-dialog", move || {
             let result = FileDialog::new()
                 .add_filter("DEF files", &["def"])
                 .pick_file()
@@ -2528,6 +4625,30 @@ impl LefDefViewer {
 
             let _ = tx.send(LoadingMessage::DefFileSelected(result));
         });
+        if let Err(e) = spawned {
+            self.background_start_failed(e);
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn open_lef_file_dialog(&mut self) {
+        self.open_browser_file_dialog("LEF files", &["lef"]);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn open_def_file_dialog(&mut self) {
+        self.open_browser_file_dialog("DEF files", &["def"]);
+    }
+
+    /// Pick files in the browser, they are read into memory and parsed from
+    /// there
+    #[cfg(target_arch = "wasm32")]
+    fn open_browser_file_dialog(&mut self, filter_name: &str, extensions: &[&str]) {
+        let (tx, rx) = mpsc::channel();
+        self.loading_receiver = Some(rx);
+        platform::pick_files(filter_name, extensions, move |files| {
+            let _ = tx.send(LoadingMessage::FilesRead(files));
+        });
     }
 
     #[allow(dead_code)]
@@ -2572,7 +4693,9 @@ impl LefDefViewer {
             file_type: "LEF".to_string(),
             file_name: file_name.clone(),
             start_time: Instant::now(),
+            last_activity: Instant::now(),
             show_progress: false,
+            progress: BTreeMap::new(),
         };
 
         // Create channel for communication
@@ -2581,7 +4704,7 @@ impl LefDefViewer {
 
         // Start loading in background thread (pass hash to avoid recalculation)
         let hash_clone = file_hash.clone();
-        thread::spawn(move || {
+        let spawned = platform::spawn("lef-loader", move || {
             let reader = LefReader::new();
             let result = match reader.read(&path) {
                 Ok(lef) => Ok((lef, hash_clone)),
@@ -2589,6 +4712,9 @@ impl LefDefViewer {
             };
             let _ = tx.send(LoadingMessage::LefLoaded(result, path));
         });
+        if let Err(e) = spawned {
+            self.background_start_failed(e);
+        }
     }
 
     #[allow(dead_code)]
@@ -2628,8 +4754,12 @@ impl LefDefViewer {
                                 // Make power/ground pins visible by default
                                 if pin.use_type == "POWER" || pin.use_type == "GROUND" {
                                     self.visible_layers.insert(detailed_layer);
-                                    println!("DEBUG: Auto-enabled power layer: {}.PIN for pin {} (USE: {})",
-                                           rect.layer, pin.name, pin.use_type);
+                                    log::debug!(
+                                        "Auto-enabled power layer: {}.PIN for pin {} (USE: {})",
+                                        rect.layer,
+                                        pin.name,
+                                        pin.use_type
+                                    );
                                 } else {
                                     self.visible_layers.insert(detailed_layer);
                                 }
@@ -2640,8 +4770,12 @@ impl LefDefViewer {
                                 // Make power/ground pins visible by default
                                 if pin.use_type == "POWER" || pin.use_type == "GROUND" {
                                     self.visible_layers.insert(detailed_layer);
-                                    println!("DEBUG: Auto-enabled power layer: {}.PIN for pin {} (USE: {})",
-                                           polygon.layer, pin.name, pin.use_type);
+                                    log::debug!(
+                                        "Auto-enabled power layer: {}.PIN for pin {} (USE: {})",
+                                        polygon.layer,
+                                        pin.name,
+                                        pin.use_type
+                                    );
                                 } else {
                                     self.visible_layers.insert(detailed_layer);
                                 }
@@ -2673,13 +4807,13 @@ impl LefDefViewer {
                 }
 
                 if obs_count > 0 {
-                    println!(
-                        "DEBUG: Found {} OBS shapes in {} macros",
+                    log::debug!(
+                        "Found {} OBS shapes in {} macros",
                         obs_count,
                         obs_macros.len()
                     );
                 } else {
-                    println!("DEBUG: No OBS data found in any macro");
+                    log::debug!("No OBS data found in any macro");
                 }
 
                 // Count OBS layers
@@ -2688,15 +4822,14 @@ impl LefDefViewer {
                     .iter()
                     .filter(|layer| layer.contains(".OBS"))
                     .collect();
-                println!(
-                    "DEBUG: Added {} OBS layers (default hidden)",
-                    obs_layers.len()
-                );
+                log::debug!("Added {} OBS layers (default hidden)", obs_layers.len());
 
                 self.lef_files.push(LoadedLefFile {
                     path: path.clone(),
                     data: lef,
                     file_hash,
+                    edited_sources: HashMap::new(),
+                    transform: SourceTransform::default(),
                 });
 
                 // Initialize voltage configuration with smart defaults
@@ -2708,9 +4841,7 @@ impl LefDefViewer {
                 self.error_message = None;
                 // Auto-show layers panel when LEF file is loaded successfully
                 self.show_layers_panel = true;
-                // Auto fit to view when LEF file is loaded successfully
-                // Delay fit to view by a few frames to ensure UI layout is stable
-                self.fit_to_view_delay_frames = 3;
+                self.apply_load_view(lef_view_key(&path));
             }
             Err(e) => {
                 self.error_message = Some(format!("Failed to load LEF file: {e}"));
@@ -2731,7 +4862,9 @@ impl LefDefViewer {
             file_type: "DEF".to_string(),
             file_name: file_name.clone(),
             start_time: Instant::now(),
+            last_activity: Instant::now(),
             show_progress: false,
+            progress: BTreeMap::new(),
         };
 
         // Create channel for communication
@@ -2739,174 +4872,208 @@ impl LefDefViewer {
         self.loading_receiver = Some(rx);
 
         // Start loading in background thread
-        thread::spawn(move || {
-            let reader = DefReader::new();
-            let result = match reader.read(&path) {
-                Ok(def) => Ok(def),
-                Err(e) => Err(format!("Failed to load DEF file: {e}")),
-            };
-            let _ = tx.send(LoadingMessage::DefLoaded(Box::new(result), path));
-        });
-    }
-
-    #[allow(dead_code)]
-    fn load_def_file(&mut self, path: String) {
-        let reader = DefReader::new();
-        match reader.read(&path) {
-            Ok(def) => {
-                self.def_data = Some(def);
-                self.def_file_path = Some(path);
-                self.error_message = None;
-                // Auto fit to view when DEF file is loaded successfully
-                // Delay fit to view by a few frames to ensure UI layout is stable
-                self.fit_to_view_delay_frames = 3;
-            }
-            Err(e) => {
-                self.error_message = Some(format!("Failed to load DEF file: {e}"));
-            }
-        }
-    }
-
-    /// Extract basename from LEF file path for use in export filenames
-    fn get_lef_basename(&self) -> String {
-        if let Some(lef_file) = self.lef_files.first() {
-            if let Some(file_stem) = Path::new(&lef_file.path).file_stem() {
-                if let Some(basename) = file_stem.to_str() {
-                    return basename.to_string();
+        self.pending_loads = 1;
+        let spawned = platform::spawn("def-loader", move || {
+            // Quick pre-scan so a file that will not fit is not parsed blindly
+            if let (Ok(estimate), Some(available)) = (prescan_def(&path), available_memory()) {
+                let options = DefParseOptions::default();
+                if estimate.exceeds(&options, available) {
+                    log::warn!(
+                        "DEF {} needs about {}, only {} available",
+                        path,
+                        format_bytes(estimate.total_bytes()),
+                        format_bytes(available)
+                    );
+                    let options = estimate.suggest_filter(available).unwrap_or(options);
+                    let warning = DefMemoryWarning {
+                        path,
+                        estimate,
+                        available,
+                        options,
+                    };
+                    let _ = tx.send(LoadingMessage::DefMemoryWarning(Box::new(warning)));
+                    return;
                 }
             }
+
+            let progress = progress_sender(&tx, &path);
+            let result = run_guarded(&path, || {
+                Self::read_def(&path, &DefParseOptions::default(), progress)
+            });
+            let _ = tx.send(LoadingMessage::DefLoaded(Box::new(result), path));
+        });
+        if let Err(e) = spawned {
+            self.background_start_failed(e);
         }
-        "lef_cells".to_string() // fallback default
     }
 
-    fn handle_export_lef_csv(&mut self) {
-        if !self.lef_files.is_empty() {
-            let basename = self.get_lef_basename();
-            let default_filename = format!("{basename}.csv");
-            if let Some(first_file_path) = FileDialog::new()
-                .set_file_name(&default_filename)
-                .add_filter("CSV files", &["csv"])
-                .save_file()
-            {
-                let output_dir = first_file_path
-                    .parent()
-                    .unwrap_or(std::path::Path::new("."));
-                let mut exported_files = Vec::new();
-                let mut had_error = false;
+    /// Parse a DEF file in the background without the memory pre-check
+    fn start_def_parse(&mut self, path: String, options: DefParseOptions) {
+        let file_name = Path::new(&path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
 
-                // Export each LEF file to a separate CSV file
-                for lef_file in &self.lef_files {
-                    // Generate output filename based on LEF file's original name
-                    let lef_basename = std::path::Path::new(&lef_file.path)
-                        .file_stem()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or("output");
-                    let output_path = output_dir.join(format!("{}.csv", lef_basename));
+        self.loading_state = LoadingState::Loading {
+            file_type: "DEF".to_string(),
+            file_name,
+            start_time: Instant::now(),
+            last_activity: Instant::now(),
+            show_progress: false,
+            progress: BTreeMap::new(),
+        };
 
-                    match export::export_lef_to_csv(&lef_file.data, &output_path.to_string_lossy())
-                    {
-                        Ok(()) => {
-                            exported_files.push(output_path.display().to_string());
-                        }
-                        Err(e) => {
-                            self.error_message =
-                                Some(format!("Failed to export {}: {}", lef_file.path, e));
-                            had_error = true;
-                            break;
+        let (tx, rx) = mpsc::channel();
+        self.loading_receiver = Some(rx);
+
+        self.pending_loads = 1;
+        let spawned = platform::spawn("def-loader", move || {
+            let progress = progress_sender(&tx, &path);
+            let result = run_guarded(&path, || Self::read_def(&path, &options, progress));
+            let _ = tx.send(LoadingMessage::DefLoaded(Box::new(result), path));
+        });
+        if let Err(e) = spawned {
+            self.background_start_failed(e);
+        }
+    }
+
+    fn read_def(
+        path: &str,
+        options: &DefParseOptions,
+        progress: impl Fn(&Progress) + Send + 'static,
+    ) -> Result<Def, String> {
+        DefReader::new()
+            .with_progress(progress)
+            .read_with_options(path, options)
+            .map_err(|e| format!("Failed to load DEF file: {e}"))
+    }
+
+    /// Ask how to proceed with a DEF that likely exceeds available memory
+    fn render_def_memory_warning(&mut self, ctx: &egui::Context) {
+        let Some(warning) = &mut self.def_memory_warning else {
+            return;
+        };
+
+        let mut action: Option<bool> = None; // Some(true) = load, Some(false) = cancel
+        egui::Window::new("Large DEF File")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                let file_name = Path::new(&warning.path)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("unknown");
+                ui.label(format!(
+                    "{} ({}) likely needs about {} to load, but only {} is available.",
+                    file_name,
+                    format_bytes(warning.estimate.file_size),
+                    format_bytes(warning.estimate.total_bytes()),
+                    format_bytes(warning.available)
+                ));
+                ui.separator();
+
+                ui.label("Skip sections to reduce memory use:");
+                egui::Grid::new("def_memory_sections")
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        for (name, size) in &warning.estimate.sections {
+                            let mut skip = warning.options.skips(name);
+                            let enabled = name != "COMPONENTS";
+                            if ui
+                                .add_enabled(enabled, egui::Checkbox::new(&mut skip, name.as_str()))
+                                .changed()
+                            {
+                                if skip {
+                                    warning.options.skip_sections.insert(name.clone());
+                                } else {
+                                    warning.options.skip_sections.remove(name);
+                                }
+                            }
+                            ui.label(format!("{} items", size.count));
+                            ui.label(format_bytes(size.bytes));
+                            ui.end_row();
                         }
+                    });
+
+                let estimate = warning.estimate.bytes_with_options(&warning.options);
+                let fits = !warning
+                    .estimate
+                    .exceeds(&warning.options, warning.available);
+                ui.colored_label(
+                    if fits {
+                        egui::Color32::GREEN
+                    } else {
+                        egui::Color32::YELLOW
+                    },
+                    format!("Estimated with this filter: {}", format_bytes(estimate)),
+                );
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let label = if warning.options.skip_sections.is_empty() {
+                        "Load Anyway"
+                    } else {
+                        "Load Filtered"
+                    };
+                    if ui.button(label).clicked() {
+                        action = Some(true);
                     }
-                }
+                    if ui.button("Cancel").clicked() {
+                        action = Some(false);
+                    }
+                });
+            });
 
-                if !had_error {
-                    let total_macros: usize =
-                        self.lef_files.iter().map(|f| f.data.macros.len()).sum();
-                    self.success_message = Some(format!(
-                        "Successfully exported {} macros from {} LEF files:\n{}",
-                        total_macros,
-                        self.lef_files.len(),
-                        exported_files.join("\n")
-                    ));
+        match action {
+            Some(true) => {
+                if let Some(warning) = self.def_memory_warning.take() {
+                    self.start_def_parse(warning.path, warning.options);
                 }
             }
+            Some(false) => self.def_memory_warning = None,
+            None => {}
         }
     }
 
-    fn handle_export_selected_cells_pinlist(&mut self) {
-        if !self.lef_files.is_empty() {
-            if self.selected_cells.is_empty() {
-                self.error_message = Some("No cells selected for export".to_string());
-                return;
+    #[allow(dead_code)]
+    fn load_def_file(&mut self, path: String) {
+        let reader = DefReader::new();
+        match reader.read(&path) {
+            Ok(def) => {
+                self.def_data = Some(def);
+                self.def_file_path = Some(path);
+                self.error_message = None;
+                // Auto fit to view when DEF file is loaded successfully
+                // Delay fit to view by a few frames to ensure UI layout is stable
+                self.fit_to_view_delay_frames = 3;
             }
-
-            // Get selected macros from all LEF files
-            let selected_macros: Vec<&crate::lef::LefMacro> = self
-                .lef_files
-                .iter()
-                .flat_map(|lef_file| lef_file.data.macros.iter())
-                .filter(|macro_def| self.selected_cells.contains(&macro_def.name))
-                .collect();
-
-            if selected_macros.is_empty() {
-                self.error_message = Some("Selected cells not found in LEF data".to_string());
-                return;
+            Err(e) => {
+                self.error_message = Some(format!("Failed to load DEF file: {e}"));
             }
+        }
+    }
 
-            if selected_macros.len() == 1 {
-                // Single cell export - file save dialog
-                let macro_def = selected_macros[0];
-                let default_filename = format!("{}.csv", macro_def.name);
-
-                if let Some(file_path) = FileDialog::new()
-                    .set_file_name(&default_filename)
-                    .add_filter("CSV files", &["csv"])
-                    .save_file()
-                {
-                    match export::export_cell_pinlist_to_csv(
-                        macro_def,
-                        &file_path.to_string_lossy(),
-                    ) {
-                        Ok(()) => {
-                            self.success_message = Some(format!(
-                                "Successfully exported pinlist for cell '{}' to: {}",
-                                macro_def.name,
-                                file_path.display()
-                            ));
-                        }
-                        Err(e) => {
-                            self.error_message = Some(format!("Failed to export pinlist: {e}"));
-                        }
-                    }
-                }
-            } else {
-                // Multiple cells export - directory picker
-                if let Some(output_dir) = FileDialog::new().pick_folder() {
-                    match export::export_multiple_cells_pinlist(
-                        &selected_macros,
-                        &output_dir.to_string_lossy(),
-                    ) {
-                        Ok(()) => {
-                            self.success_message = Some(format!(
-                                "Successfully exported pinlists for {} cells to directory: {}",
-                                selected_macros.len(),
-                                output_dir.display()
-                            ));
-                        }
-                        Err(e) => {
-                            self.error_message = Some(format!("Failed to export pinlists: {e}"));
-                        }
-                    }
+    /// Extract basename from LEF file path for use in export filenames
+    fn get_lef_basename(&self) -> String {
+        if let Some(lef_file) = self.lef_files.first() {
+            if let Some(file_stem) = Path::new(&lef_file.path).file_stem() {
+                if let Some(basename) = file_stem.to_str() {
+                    return basename.to_string();
                 }
             }
         }
+        "lef_cells".to_string() // fallback default
     }
 
-    fn handle_export_verilog_stub(&mut self) {
+    fn handle_export_lef_csv(&mut self) {
         if !self.lef_files.is_empty() {
             let basename = self.get_lef_basename();
-            let default_filename = format!("{basename}.v");
+            let default_filename = format!("{basename}.csv");
             if let Some(first_file_path) = FileDialog::new()
                 .set_file_name(&default_filename)
-                .add_filter("Verilog files", &["v"])
+                .add_filter("CSV files", &["csv"])
                 .save_file()
             {
                 let output_dir = first_file_path
@@ -2915,20 +5082,17 @@ impl LefDefViewer {
                 let mut exported_files = Vec::new();
                 let mut had_error = false;
 
-                // Export each LEF file to a separate Verilog file
+                // Export each LEF file to a separate CSV file
                 for lef_file in &self.lef_files {
                     // Generate output filename based on LEF file's original name
                     let lef_basename = std::path::Path::new(&lef_file.path)
                         .file_stem()
                         .and_then(|s| s.to_str())
                         .unwrap_or("output");
-                    let output_path = output_dir.join(format!("{}.v", lef_basename));
+                    let output_path = output_dir.join(format!("{}.csv", lef_basename));
 
-                    match export::export_verilog_stub(
-                        &lef_file.data,
-                        &output_path.to_string_lossy(),
-                        lef_basename,
-                    ) {
+                    match export::export_lef_to_csv(&lef_file.data, &output_path.to_string_lossy())
+                    {
                         Ok(()) => {
                             exported_files.push(output_path.display().to_string());
                         }
@@ -2945,7 +5109,7 @@ impl LefDefViewer {
                     let total_macros: usize =
                         self.lef_files.iter().map(|f| f.data.macros.len()).sum();
                     self.success_message = Some(format!(
-                        "Successfully exported {} cells from {} LEF files:\n{}",
+                        "Successfully exported {} macros from {} LEF files:\n{}",
                         total_macros,
                         self.lef_files.len(),
                         exported_files.join("\n")
@@ -2955,78 +5119,1585 @@ impl LefDefViewer {
         }
     }
 
-    fn handle_export_lib_stub(&mut self) {
-        if !self.lef_files.is_empty() {
-            // Voltage config is already initialized when LEF file was loaded
-            // For single file: use its basename as lib_name
-            // For multiple files: lib_name will be auto-generated from each file's basename during export
-            let basename = self.get_lef_basename();
-            self.voltage_config.lib_name = basename;
-            self.voltage_dialog.show();
-        }
-    }
-
-    fn perform_lib_export(&mut self) {
-        if !self.lef_files.is_empty() {
-            let default_filename = format!("{}.lib", self.voltage_config.lib_name);
-            if let Some(first_file_path) = FileDialog::new()
-                .set_file_name(&default_filename)
-                .add_filter("Liberty files", &["lib"])
-                .save_file()
-            {
-                let output_dir = first_file_path
-                    .parent()
-                    .unwrap_or(std::path::Path::new("."));
-                let mut exported_files = Vec::new();
-                let mut had_error = false;
-
-                // Export each LEF file to a separate Liberty file
-                for lef_file in &self.lef_files {
-                    // Generate output filename based on LEF file's original name
-                    let lef_basename = std::path::Path::new(&lef_file.path)
-                        .file_stem()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or("output");
-                    let output_path = output_dir.join(format!("{}.lib", lef_basename));
-
-                    // Create a dedicated VoltageConfig for this file
-                    // Use file's basename as lib_name, but inherit voltage settings from user config
-                    let mut file_voltage_config = self.voltage_config.clone();
-                    file_voltage_config.lib_name = lef_basename.to_string();
-
-                    match export::export_lib_stub_with_voltage_config(
-                        &lef_file.data,
-                        &output_path.to_string_lossy(),
-                        &file_voltage_config,
-                    ) {
-                        Ok(()) => {
-                            exported_files.push(output_path.display().to_string());
-                        }
-                        Err(e) => {
-                            self.error_message =
-                                Some(format!("Failed to export {}: {}", lef_file.path, e));
-                            had_error = true;
-                            break;
-                        }
-                    }
-                }
-
-                if !had_error {
-                    let total_macros: usize =
-                        self.lef_files.iter().map(|f| f.data.macros.len()).sum();
+    fn handle_export_pin_lint_csv(&mut self) {
+        let basename = self.get_lef_basename();
+        if let Some(path) = FileDialog::new()
+            .set_file_name(format!("{basename}_pin_lint.csv"))
+            .add_filter("CSV files", &["csv"])
+            .save_file()
+        {
+            let violations = self.pin_lint_dialog.violations();
+            match export::export_pin_lint_to_csv(violations, &path.to_string_lossy()) {
+                Ok(()) => {
                     self.success_message = Some(format!(
-                        "Successfully exported {} cells from {} LEF files:\n{}",
-                        total_macros,
-                        self.lef_files.len(),
-                        exported_files.join("\n")
+                        "Successfully exported {} pin naming violations to {}",
+                        violations.len(),
+                        path.display()
                     ));
                 }
+                Err(e) => {
+                    self.error_message = Some(format!("Failed to export pin lint report: {e}"));
+                }
             }
         }
     }
 
-    fn render_menu_bar(&mut self, ui: &mut egui::Ui) {
-        egui::menu::bar(ui, |ui| {
+    fn handle_load_underlay(&mut self, ctx: &egui::Context) {
+        let Some(path) = FileDialog::new()
+            .add_filter("PNG images", &["png"])
+            .pick_file()
+        else {
+            return;
+        };
+        let bounds = self
+            .underlay
+            .as_ref()
+            .map(|u| u.bounds)
+            .or_else(|| self.die_bounds());
+        match Underlay::load(ctx, &path, bounds) {
+            Ok(underlay) => {
+                self.status_bar.hint(format!(
+                    "Loaded background image {} ({} x {} px)",
+                    underlay.name, underlay.image_size[0], underlay.image_size[1]
+                ));
+                self.underlay = Some(underlay);
+            }
+            Err(e) => self.error_message = Some(format!("Failed to load background image: {e}")),
+        }
+    }
+
+    fn handle_export_def_pins(&mut self) {
+        let Some(def) = &self.def_data else {
+            return;
+        };
+        let basename = self
+            .def_file_path
+            .as_deref()
+            .and_then(|p| Path::new(p).file_stem())
+            .and_then(|s| s.to_str())
+            .unwrap_or("design")
+            .to_string();
+        let Some(path) = FileDialog::new()
+            .set_file_name(format!("{basename}_pins.csv"))
+            .add_filter("CSV files", &["csv"])
+            .save_file()
+        else {
+            return;
+        };
+
+        match export::export_def_pins_to_csv(
+            &def.pins,
+            self.def_db_units(),
+            &path.to_string_lossy(),
+        ) {
+            Ok(()) => {
+                let unlegalized = def
+                    .pins
+                    .iter()
+                    .filter(|p| p.placement_status() != "FIXED")
+                    .count();
+                self.success_message = Some(format!(
+                    "Successfully exported {} pins ({} not FIXED) to {}",
+                    def.pins.len(),
+                    unlegalized,
+                    path.display()
+                ));
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to export pins: {e}"));
+            }
+        }
+    }
+
+    fn handle_export_def_placement(&mut self) {
+        self.export_def_placement(None);
+    }
+
+    /// Save the placement as CSV, of the components named in `only` or of
+    /// all components
+    fn export_def_placement(&mut self, only: Option<&HashSet<String>>) {
+        let Some(def) = &self.def_data else {
+            return;
+        };
+        let subset: Vec<def::DefComponent>;
+        let components = match only {
+            Some(names) => {
+                subset = def
+                    .components
+                    .iter()
+                    .filter(|c| names.contains(&c.name))
+                    .cloned()
+                    .collect();
+                &subset
+            }
+            None => &def.components,
+        };
+        let basename = self
+            .def_file_path
+            .as_deref()
+            .and_then(|p| Path::new(p).file_stem())
+            .and_then(|s| s.to_str())
+            .unwrap_or("design")
+            .to_string();
+        let Some(path) = FileDialog::new()
+            .set_file_name(format!("{basename}_placement.csv"))
+            .add_filter("CSV files", &["csv"])
+            .save_file()
+        else {
+            return;
+        };
+
+        match export::export_def_placement_to_csv(
+            components,
+            self.def_db_units(),
+            &path.to_string_lossy(),
+        ) {
+            Ok(()) => {
+                self.success_message = Some(format!(
+                    "Successfully exported the placement of {} components to {}",
+                    components.len(),
+                    path.display()
+                ));
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to export placement: {e}"));
+            }
+        }
+    }
+
+    /// Run a script of the scripting console and apply what it asked for
+    fn run_script(&mut self, code: &str) {
+        let macros = self.lef_files.iter().flat_map(|f| &f.data.macros);
+        let design = ScriptDesign::new(self.def_data.as_ref(), self.def_db_units(), macros);
+        let result = script::run(code, &design);
+        if let Ok(output) = &result {
+            for action in output.actions.clone() {
+                self.apply_script_action(action);
+            }
+        }
+        self.script_console.set_result(result);
+    }
+
+    fn apply_script_action(&mut self, action: ScriptAction) {
+        match action {
+            ScriptAction::SelectComponents(names) => self.selected_components.extend(names),
+            ScriptAction::SelectNets(names) => self.selected_nets.extend(names),
+            ScriptAction::ClearSelection => {
+                self.selected_components.clear();
+                self.selected_pins.clear();
+                self.selected_nets.clear();
+            }
+            ScriptAction::ColorComponents(names, [r, g, b]) => {
+                let color = egui::Color32::from_rgb(r, g, b);
+                self.script_colors
+                    .extend(names.into_iter().map(|name| (name, color)));
+            }
+            ScriptAction::ClearColors => self.script_colors.clear(),
+            ScriptAction::ExportPlacementCsv(names) => {
+                let names: HashSet<String> = names.into_iter().collect();
+                self.export_def_placement(Some(&names));
+            }
+        }
+    }
+
+    fn handle_export_net_layer_usage(&mut self) {
+        let Some(def) = &self.def_data else {
+            return;
+        };
+        let basename = self
+            .def_file_path
+            .as_deref()
+            .and_then(|p| Path::new(p).file_stem())
+            .and_then(|s| s.to_str())
+            .unwrap_or("design")
+            .to_string();
+        let Some(path) = FileDialog::new()
+            .set_file_name(format!("{basename}_layer_usage.csv"))
+            .add_filter("CSV files", &["csv"])
+            .save_file()
+        else {
+            return;
+        };
+
+        let layer_order = self.routing_layer_names();
+        let usages = layer_usage::design_layer_usage(&def.nets, self.def_db_units(), &layer_order);
+        match export::export_net_layer_usage_to_csv(&usages, &layer_order, &path.to_string_lossy())
+        {
+            Ok(()) => {
+                self.success_message = Some(format!(
+                    "Successfully exported layer usage of {} routed nets to {}",
+                    usages.len(),
+                    path.display()
+                ));
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to export layer usage: {e}"));
+            }
+        }
+    }
+
+    fn handle_export_netlist_json(&mut self) {
+        let Some(def) = &self.def_data else {
+            return;
+        };
+        let basename = self
+            .def_file_path
+            .as_deref()
+            .and_then(|p| Path::new(p).file_stem())
+            .and_then(|s| s.to_str())
+            .unwrap_or("design")
+            .to_string();
+        let Some(path) = FileDialog::new()
+            .set_file_name(format!("{basename}_netlist.json"))
+            .add_filter("JSON files", &["json"])
+            .save_file()
+        else {
+            return;
+        };
+
+        match export::export_def_netlist_json(
+            def,
+            self.def_db_units(),
+            &self.routing_layer_names(),
+            &path.to_string_lossy(),
+        ) {
+            Ok(()) => {
+                self.success_message = Some(format!(
+                    "Successfully exported {} nets to {}",
+                    def.nets.len(),
+                    path.display()
+                ));
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to export netlist: {e}"));
+            }
+        }
+    }
+
+    fn handle_save_check_report(&mut self) {
+        let basename = match &self.def_file_path {
+            Some(path) => Path::new(path)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("design")
+                .to_string(),
+            None => self.get_lef_basename(),
+        };
+        let Some(path) = FileDialog::new()
+            .set_file_name(format!("{basename}_checks.json"))
+            .add_filter("JSON report", &["json"])
+            .add_filter("JUnit XML report", &["xml"])
+            .save_file()
+        else {
+            return;
+        };
+
+        let libraries: Vec<(&str, &Lef)> = self
+            .lef_files
+            .iter()
+            .map(|f| (f.path.as_str(), &f.data))
+            .collect();
+        let mut report =
+            checks::run_checks(self.def_data.as_ref(), &libraries, &CheckOptions::default());
+        self.checks_panel.waivers().apply(&mut report);
+        let text = if path.extension().is_some_and(|e| e == "xml") {
+            report.to_junit_xml()
+        } else {
+            report.to_json()
+        };
+        match std::fs::write(&path, text) {
+            Ok(()) => {
+                self.success_message = Some(format!(
+                    "Checks finished with {} failing, {} waived and {} skipped, report saved to {}",
+                    report.failure_count(),
+                    report.waived_count(),
+                    report.skipped_count(),
+                    path.display()
+                ));
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to save check report: {e}"));
+            }
+        }
+    }
+
+    /// Swap the inspected component to another member of its footprint family
+    fn swap_inspected_component(&mut self, to: &str) {
+        let (Some(def), Some(instance)) = (&mut self.def_data, &self.inspected_component) else {
+            return;
+        };
+        match self.eco_log.swap(def, instance, to) {
+            Ok(()) => {
+                self.success_message = Some(format!("Swapped {instance} to {to}"));
+                self.edits_panel.visible = true;
+                self.after_design_edit();
+            }
+            Err(e) => self.error_message = Some(format!("Failed to swap cell: {e}")),
+        }
+    }
+
+    /// Start dragging the unlocked component under a screen position
+    fn start_component_drag(
+        &self,
+        center: egui::Pos2,
+        screen: egui::Pos2,
+    ) -> Option<ComponentDrag> {
+        let def = self.def_data.as_ref()?;
+        let index = self.design_index.as_ref()?;
+        let grab = self.def_world_pos(center, screen);
+        let hit = index.pick(
+            def,
+            self.def_db_units(),
+            &self.macros_by_name(),
+            grab,
+            |key| self.visible_layers.contains(key),
+        )?;
+        let component = match hit {
+            Hit::Component(component) | Hit::Shape { component, .. } => component,
+            Hit::Pin { .. } => return None,
+        };
+        if !self.show_components || self.component_flags.is_locked(component) {
+            return None;
+        }
+        let placement = def.components[component].placement.as_ref()?;
+        Some(ComponentDrag {
+            index: component,
+            from: (placement.x, placement.y),
+            outline: index.component_bounds(component)?,
+            grab,
+        })
+    }
+
+    /// Location in database units of the dragged component with the cursor
+    /// at `screen`, rounded to whole database units
+    fn component_drag_target(
+        &self,
+        drag: &ComponentDrag,
+        center: egui::Pos2,
+        screen: egui::Pos2,
+    ) -> (f64, f64) {
+        let db_units = self.def_db_units();
+        let (x, y) = self.def_world_pos(center, screen);
+        (
+            drag.from.0 + ((x - drag.grab.0) * db_units).round(),
+            drag.from.1 + ((y - drag.grab.1) * db_units).round(),
+        )
+    }
+
+    /// Show the dragged component at `to`, in database units, without
+    /// recording the move yet
+    fn set_dragged_component_location(&mut self, index: usize, to: (f64, f64)) {
+        if let Some(placement) = self
+            .def_data
+            .as_mut()
+            .and_then(|def| def.components.get_mut(index))
+            .and_then(|c| c.placement.as_mut())
+        {
+            (placement.x, placement.y) = to;
+        }
+    }
+
+    /// Drop the dragged component at `to` and record the move
+    fn finish_component_drag(&mut self, drag: ComponentDrag, to: (f64, f64)) {
+        self.set_dragged_component_location(drag.index, drag.from);
+        let Some(def) = &mut self.def_data else {
+            return;
+        };
+        let instance = def.components[drag.index].name.clone();
+        match self.eco_log.move_to(def, &instance, to) {
+            Ok(()) if to != drag.from => {
+                self.edits_panel.visible = true;
+                self.after_design_edit();
+            }
+            Ok(()) => {}
+            Err(e) => self.error_message = Some(format!("Failed to move component: {e}")),
+        }
+    }
+
+    /// Ghost outline of the dragged component at its original location and
+    /// its displacement next to the cursor
+    fn paint_component_drag(&self, painter: &egui::Painter, center: egui::Pos2) {
+        let Some(drag) = &self.component_drag else {
+            return;
+        };
+        let (x0, y0, x1, y1) = drag.outline;
+        let corners: Option<Vec<egui::Pos2>> = [(x0, y0), (x1, y0), (x1, y1), (x0, y1), (x0, y0)]
+            .into_iter()
+            .map(|corner| self.world_screen_pos(center, corner))
+            .collect();
+        let Some(corners) = corners else {
+            return;
+        };
+        painter.extend(egui::Shape::dashed_line(
+            &corners,
+            egui::Stroke::new(
+                self.dpi_style.stroke(1.5),
+                egui::Color32::WHITE.gamma_multiply(0.7),
+            ),
+            6.0,
+            4.0,
+        ));
+
+        let Some(placement) = self
+            .def_data
+            .as_ref()
+            .and_then(|def| def.components.get(drag.index))
+            .and_then(|c| c.placement.as_ref())
+        else {
+            return;
+        };
+        let Some(cursor) = painter.ctx().pointer_latest_pos() else {
+            return;
+        };
+        let db_units = self.def_db_units();
+        self.render_text_with_outline(
+            painter,
+            cursor + egui::vec2(14.0, -8.0),
+            egui::Align2::LEFT_BOTTOM,
+            &format!(
+                "dx {:.3}  dy {:.3} um",
+                (placement.x - drag.from.0) / db_units,
+                (placement.y - drag.from.1) / db_units
+            ),
+            egui::FontId::monospace(self.dpi_style.font_size(12.0)),
+            egui::Color32::WHITE,
+        );
+    }
+
+    /// Refresh what depends on the placements and macros of the DEF after
+    /// a swap, move or revert
+    fn after_design_edit(&mut self) {
+        self.rebuild_component_macro_map();
+        self.checks_panel.invalidate_checks();
+    }
+
+    fn handle_edit_action(&mut self, action: EditAction) {
+        match action {
+            EditAction::Export => self.handle_export_cell_swaps(),
+            EditAction::Revert(instance) => {
+                let Some(def) = &mut self.def_data else {
+                    return;
+                };
+                match self.eco_log.revert(def, &instance) {
+                    Ok(()) => self.success_message = Some(format!("Reverted {instance}")),
+                    Err(e) => self.error_message = Some(format!("Failed to revert: {e}")),
+                }
+                self.after_design_edit();
+            }
+            EditAction::RevertAll => {
+                let Some(def) = &mut self.def_data else {
+                    return;
+                };
+                let instances: Vec<String> = self
+                    .eco_log
+                    .instances()
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect();
+                for instance in &instances {
+                    if let Err(e) = self.eco_log.revert(def, instance) {
+                        self.error_message = Some(format!("Failed to revert: {e}"));
+                    }
+                }
+                self.success_message = Some(format!("Reverted {} instances", instances.len()));
+                self.after_design_edit();
+            }
+        }
+    }
+
+    fn handle_export_cell_swaps(&mut self) {
+        let Some(def) = &self.def_data else {
+            return;
+        };
+        let design = if def.design.is_empty() {
+            "design"
+        } else {
+            def.design.as_str()
+        };
+        let Some(path) = FileDialog::new()
+            .set_file_name(format!("{design}_eco.def"))
+            .add_filter("DEF components", &["def"])
+            .add_filter("ECO script", &["tcl"])
+            .save_file()
+        else {
+            return;
+        };
+
+        let text = if path.extension().is_some_and(|e| e == "tcl") {
+            self.eco_log.to_eco_script(def, self.def_db_units())
+        } else {
+            self.eco_log.to_def_components(def)
+        };
+        match std::fs::write(&path, text) {
+            Ok(()) => {
+                self.success_message = Some(format!(
+                    "Successfully exported {} edited instances to {}",
+                    self.eco_log.instances().len(),
+                    path.display()
+                ));
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to export ECO edits: {e}"));
+            }
+        }
+    }
+
+    fn handle_save_def(&mut self) {
+        let Some(def) = &self.def_data else {
+            return;
+        };
+        let design = if def.design.is_empty() {
+            "design"
+        } else {
+            def.design.as_str()
+        };
+        let Some(path) = FileDialog::new()
+            .set_file_name(format!("{design}_out.def"))
+            .add_filter("DEF files", &["def"])
+            .save_file()
+        else {
+            return;
+        };
+
+        match std::fs::write(&path, def::writer::write_def(def)) {
+            Ok(()) => {
+                self.success_message =
+                    Some(format!("Successfully saved DEF to {}", path.display()));
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to save DEF: {e}"));
+            }
+        }
+    }
+
+    /// Layer names for the default GDS map: technology layers in stack
+    /// order, then any other layer used by a macro
+    fn gds_layer_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for lef_file in &self.lef_files {
+            for layer in &lef_file.data.layers {
+                if !names.contains(&layer.name) {
+                    names.push(layer.name.clone());
+                }
+            }
+        }
+        let mut others = std::collections::BTreeSet::new();
+        for macro_def in self.lef_files.iter().flat_map(|f| &f.data.macros) {
+            let ports = macro_def.pins.iter().flat_map(|p| &p.ports);
+            let shapes = ports
+                .flat_map(|p| {
+                    p.rects
+                        .iter()
+                        .map(|r| &r.layer)
+                        .chain(p.polygons.iter().map(|p| &p.layer))
+                })
+                .chain(macro_def.obs.iter().flat_map(|o| {
+                    o.rects
+                        .iter()
+                        .map(|r| &r.layer)
+                        .chain(o.polygons.iter().map(|p| &p.layer))
+                }));
+            others.extend(shapes.filter(|layer| !names.contains(layer)).cloned());
+        }
+        names.extend(others);
+        names
+    }
+
+    /// Open the image export dialog with the bounds of the view, the design
+    /// and the ruler segment
+    fn open_image_export_dialog(&mut self) {
+        let to_f32 = |(x0, y0, x1, y1): Bounds| (x0 as f32, y0 as f32, x1 as f32, y1 as f32);
+        let canvas = self.canvas_rect;
+        let center = canvas.center();
+        let view_bounds = self
+            .cursor_position(center, canvas.left_bottom())
+            .zip(self.cursor_position(center, canvas.right_top()))
+            .map(|(a, b)| to_f32((a.0.min(b.0), a.1.min(b.1), a.0.max(b.0), a.1.max(b.1))));
+        let ruler_bounds = self
+            .ruler
+            .segment(None)
+            .map(|(a, b)| to_f32((a.0.min(b.0), a.1.min(b.1), a.0.max(b.0), a.1.max(b.1))));
+        let in_def_view = self.def_mode && self.def_data.is_some();
+        self.image_export_dialog.open(
+            canvas,
+            view_bounds,
+            self.image_design_bounds(),
+            ruler_bounds,
+            in_def_view,
+        );
+    }
+
+    /// Ask for a file and render the image into it on the next frame
+    fn handle_export_image(&mut self, request: ImageExportRequest) {
+        let (extension, filter) = match request.format {
+            ImageFormat::Png => ("png", "PNG Files"),
+            ImageFormat::Svg => ("svg", "SVG Files"),
+        };
+        let file_name = match (&self.def_data, self.def_mode) {
+            (Some(def), true) => format!("{}.{extension}", def.design),
+            _ => format!("view.{extension}"),
+        };
+        if let Some(path) = FileDialog::new()
+            .set_file_name(&file_name)
+            .add_filter(filter, &[extension])
+            .save_file()
+        {
+            self.image_export = Some((request, path));
+        }
+    }
+
+    /// Loaded LEF macros by name, the first file defining a macro wins
+    fn macros_by_name(&self) -> HashMap<&str, &crate::lef::LefMacro> {
+        let mut macros = HashMap::new();
+        for macro_def in self.lef_files.iter().flat_map(|f| &f.data.macros) {
+            macros.entry(macro_def.name.as_str()).or_insert(macro_def);
+        }
+        macros
+    }
+
+    /// Start the background pass once all files are loaded, and take the
+    /// results that arrived
+    fn run_precompute(&mut self, ctx: &egui::Context) {
+        if self.precompute.is_requested() && self.pending_loads == 0 {
+            let mut seen = HashSet::new();
+            let macros = self
+                .lef_files
+                .iter()
+                .flat_map(|f| &f.data.macros)
+                .filter(|m| seen.insert(m.name.as_str()))
+                .cloned()
+                .collect();
+            let layer_colors = self
+                .all_layers
+                .iter()
+                .map(|layer| (layer.clone(), self.get_layer_color(layer)))
+                .collect();
+            self.precompute.start(PrecomputeInput {
+                def: self.def_data.clone(),
+                macros,
+                db_units: self.def_db_units(),
+                layer_colors,
+            });
+        }
+        if let Some(index) = self.precompute.poll(ctx) {
+            self.design_index = Some(index);
+        }
+    }
+
+    /// Build the spatial index of the DEF if it is missing or stale
+    ///
+    /// While the background pass runs it delivers the index, until then
+    /// culling draws every component and hovering finds nothing.
+    fn ensure_design_index(&mut self) {
+        if self.design_index.is_some() || self.precompute.is_running() {
+            return;
+        }
+        let Some(def) = &self.def_data else {
+            return;
+        };
+        crate::profile_scope!("build_design_index");
+        let index = DesignIndex::build(def, self.def_db_units(), &self.macros_by_name());
+        self.design_index = Some(index);
+    }
+
+    /// Tooltip with the properties of the object under the cursor
+    fn show_hover_tooltip(
+        &self,
+        ctx: &egui::Context,
+        response: &egui::Response,
+        hover_pos: egui::Pos2,
+    ) {
+        let Some((title, rows)) = self.hover_details(response.rect.center(), hover_pos) else {
+            return;
+        };
+        egui::show_tooltip_at_pointer(ctx, response.layer_id, response.id, |ui| {
+            ui.strong(title);
+            egui::Grid::new("hover_tooltip_grid")
+                .num_columns(2)
+                .spacing([12.0, 2.0])
+                .show(ui, |ui| {
+                    for (label, value) in rows {
+                        ui.label(egui::RichText::new(label).weak());
+                        ui.monospace(value);
+                        ui.end_row();
+                    }
+                });
+        });
+    }
+
+    /// DEF coordinates in microns of a screen position on the canvas
+    fn def_world_pos(&self, center: egui::Pos2, screen: egui::Pos2) -> (f64, f64) {
+        let pivot = self.def_origin_screen_pos(center);
+        let pos = match self.def_transform.to_screen(pivot, self.zoom) {
+            Some(transform) => transform.invert(screen),
+            None => screen,
+        };
+        (
+            ((pos.x - pivot.x) / self.zoom) as f64,
+            ((pivot.y - pos.y) / self.zoom) as f64,
+        )
+    }
+
+    /// LEF coordinates, relative to ORIGIN, of a screen position over a
+    /// macro drawn in the LEF view
+    fn lef_local_pos(
+        &self,
+        lef_file: &LoadedLefFile,
+        macro_def: &crate::lef::LefMacro,
+        center: egui::Pos2,
+        screen: egui::Pos2,
+    ) -> (f64, f64) {
+        let pivot = egui::pos2(center.x + self.pan_x, center.y + self.pan_y);
+        let pos = match lef_file.transform.to_screen(pivot, self.zoom) {
+            Some(transform) => transform.invert(screen),
+            None => screen,
+        };
+        (
+            ((pos.x - pivot.x) / self.zoom) as f64 - macro_def.origin.0,
+            macro_def.size_y - ((pos.y - pivot.y) / self.zoom) as f64 - macro_def.origin.1,
+        )
+    }
+
+    /// Macros shown in the LEF view, topmost first
+    fn shown_lef_macros(
+        &self,
+    ) -> impl Iterator<Item = (&LoadedLefFile, &crate::lef::LefMacro)> + '_ {
+        self.lef_files.iter().rev().flat_map(move |lef_file| {
+            lef_file
+                .data
+                .macros
+                .iter()
+                .rev()
+                .filter(|m| self.selected_cells.is_empty() || self.selected_cells.contains(&m.name))
+                .map(move |m| (lef_file, m))
+        })
+    }
+
+    /// Mode, selection and background tasks for the status bar
+    fn status_info(&self) -> StatusInfo {
+        let mode = if self.batch_png_export.is_some() {
+            "Batch export"
+        } else if self.isolation.is_some() {
+            "Isolation"
+        } else if self.def_mode && self.def_edit_mode {
+            "DEF edit"
+        } else if self.def_mode {
+            "DEF view"
+        } else {
+            "LEF view"
+        };
+        let selection = if self.def_mode {
+            format!(
+                "{} components, {} nets selected",
+                self.selected_components.len(),
+                self.selected_nets.len()
+            )
+        } else {
+            format!("{} cells selected", self.selected_cells.len())
+        };
+
+        let mut tasks = Vec::new();
+        if let LoadingState::Loading {
+            file_type,
+            file_name,
+            ..
+        } = &self.loading_state
+        {
+            tasks.push(BackgroundTask {
+                label: format!("Loading {file_type} {file_name}"),
+                queued: self.pending_loads.saturating_sub(1),
+                progress: None,
+            });
+        }
+        let tessellation = self.tessellation_queue.load(Ordering::Relaxed);
+        if tessellation > 0 {
+            tasks.push(BackgroundTask {
+                label: "Tessellating".to_string(),
+                queued: tessellation,
+                progress: None,
+            });
+        }
+        if let Some(batch) = &self.batch_png_export {
+            tasks.push(BackgroundTask {
+                label: format!("Exporting {} PNG", batch.exported + 1),
+                queued: batch.pending.len(),
+                progress: None,
+            });
+        }
+        if self.image_export.is_some()
+            || self.image_export_receiver.is_some()
+            || self.svg_export_path.is_some()
+            || self.label_export_path.is_some()
+        {
+            tasks.push(BackgroundTask {
+                label: "Exporting view".to_string(),
+                queued: 0,
+                progress: None,
+            });
+        }
+        tasks.extend(self.precompute.task());
+
+        StatusInfo {
+            mode,
+            selection,
+            cursor: self.cursor_position,
+            tasks,
+        }
+    }
+
+    /// Cursor position for the status bar: DEF microns, or LEF coordinates
+    /// of the topmost shown macro
+    fn cursor_position(&self, center: egui::Pos2, screen: egui::Pos2) -> Option<(f64, f64)> {
+        if self.def_mode {
+            return self
+                .def_data
+                .is_some()
+                .then(|| self.def_world_pos(center, screen));
+        }
+        self.shown_lef_macros()
+            .next()
+            .map(|(lef_file, macro_def)| self.lef_local_pos(lef_file, macro_def, center, screen))
+    }
+
+    /// Screen position of a point in the coordinates of [`Self::cursor_position`]
+    fn world_screen_pos(&self, center: egui::Pos2, (x, y): (f64, f64)) -> Option<egui::Pos2> {
+        let (pivot, pos, transform) = if self.def_mode {
+            self.def_data.as_ref()?;
+            let pivot = self.def_origin_screen_pos(center);
+            let pos = egui::pos2(
+                pivot.x + x as f32 * self.zoom,
+                pivot.y - y as f32 * self.zoom,
+            );
+            (pivot, pos, self.def_transform)
+        } else {
+            let (lef_file, macro_def) = self.shown_lef_macros().next()?;
+            let pivot = egui::pos2(center.x + self.pan_x, center.y + self.pan_y);
+            let pos = egui::pos2(
+                pivot.x + (x + macro_def.origin.0) as f32 * self.zoom,
+                pivot.y + (macro_def.size_y - macro_def.origin.1 - y) as f32 * self.zoom,
+            );
+            (pivot, pos, lef_file.transform)
+        };
+        Some(match transform.to_screen(pivot, self.zoom) {
+            Some(transform) => transform.apply(pos),
+            None => pos,
+        })
+    }
+
+    /// Title and rows of the object at a screen position, with its coordinates
+    fn hover_details(
+        &self,
+        center: egui::Pos2,
+        screen: egui::Pos2,
+    ) -> Option<(String, Vec<(String, String)>)> {
+        let format_cursor =
+            |(x, y): (f64, f64)| ("Cursor".to_string(), format!("({x:.3}, {y:.3})"));
+
+        if self.def_mode {
+            let def = self.def_data.as_ref()?;
+            let db_units = self.def_db_units();
+            let world = self.def_world_pos(center, screen);
+            let hit = self.design_index.as_ref()?.pick(
+                def,
+                db_units,
+                &self.macros_by_name(),
+                world,
+                |key| self.visible_layers.contains(key),
+            )?;
+            let shown = match &hit {
+                Hit::Pin { .. } => self.show_pins,
+                Hit::Component(_) | Hit::Shape { .. } => self.show_components,
+            };
+            if !shown {
+                return None;
+            }
+            let (title, mut rows) = hit_test::describe(def, db_units, &hit);
+            rows.push(format_cursor(world));
+            return Some((title, rows));
+        }
+
+        // LEF mode draws every shown macro at the same place
+        self.shown_lef_macros().find_map(|(lef_file, macro_def)| {
+            let local = self.lef_local_pos(lef_file, macro_def, center, screen);
+            let shape = hit_test::pick_macro_shape(macro_def, local, |key| {
+                self.visible_layers.contains(key)
+            })?;
+            let (title, mut rows) = hit_test::describe_macro_shape(macro_def, &shape);
+            rows.push(format_cursor(local));
+            Some((title, rows))
+        })
+    }
+
+    /// World bounds of the full design: the die area, or the shown cells
+    fn image_design_bounds(&self) -> Option<(f32, f32, f32, f32)> {
+        if let (true, Some(def)) = (self.def_mode, &self.def_data) {
+            let db_units = self.def_db_units();
+            let points = def
+                .die_area_points
+                .iter()
+                .map(|(x, y)| ((x / db_units) as f32, (y / db_units) as f32));
+            let bounds = points.fold(
+                (
+                    f32::INFINITY,
+                    f32::INFINITY,
+                    f32::NEG_INFINITY,
+                    f32::NEG_INFINITY,
+                ),
+                |(min_x, min_y, max_x, max_y), (x, y)| {
+                    (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+                },
+            );
+            if bounds.2 > bounds.0 && bounds.3 > bounds.1 {
+                return Some(bounds);
+            }
+        }
+        self.calculate_outline_bounds()
+    }
+
+    /// Ask for a file and write the canvas to it on the next frame
+    fn handle_export_svg(&mut self) {
+        let file_name = if self.def_mode {
+            self.def_data
+                .as_ref()
+                .map(|def| format!("{}.svg", def.design))
+        } else {
+            None
+        };
+        if let Some(path) = FileDialog::new()
+            .set_file_name(file_name.as_deref().unwrap_or("view.svg"))
+            .add_filter("SVG Files", &["svg"])
+            .save_file()
+        {
+            self.svg_export_path = Some(path);
+        }
+    }
+
+    /// Ask for a file and write the labels drawn on the next frame to it
+    fn handle_export_labels(&mut self) {
+        let file_name = match (&self.def_data, self.def_mode) {
+            (Some(def), true) => format!("{}_labels.csv", def.design),
+            _ => "labels.csv".to_string(),
+        };
+        if let Some(path) = FileDialog::new()
+            .set_file_name(&file_name)
+            .add_filter("CSV Files", &["csv"])
+            .save_file()
+        {
+            self.label_export_path = Some(path);
+        }
+    }
+
+    /// Labels on the canvas with their position in design coordinates
+    fn placed_labels(
+        &self,
+        center: egui::Pos2,
+        texts: &[(egui::Pos2, String, egui::FontId, egui::Color32)],
+        smart_texts: &[(TextPositioning, String, egui::FontId, egui::Color32)],
+    ) -> Vec<export::PlacedLabel> {
+        let anchor_name = |anchor: egui::Align2| {
+            let x = match anchor.x() {
+                egui::Align::Min => "LEFT",
+                egui::Align::Center => "CENTER",
+                egui::Align::Max => "RIGHT",
+            };
+            let y = match anchor.y() {
+                egui::Align::Min => "TOP",
+                egui::Align::Center => "CENTER",
+                egui::Align::Max => "BOTTOM",
+            };
+            format!("{x}_{y}")
+        };
+        let plain = texts
+            .iter()
+            .map(|(pos, text, font, _)| (*pos, text, font, egui::Align2::CENTER_CENTER, 0.0));
+        let smart = smart_texts.iter().map(|(positioning, text, font, _)| {
+            let angle = positioning.angle;
+            (positioning.pos, text, font, positioning.anchor, angle)
+        });
+        plain
+            .chain(smart)
+            .filter(|(pos, ..)| self.canvas_rect.contains(*pos))
+            .filter_map(|(pos, text, font, anchor, angle)| {
+                let (x, y) = self.cursor_position(center, pos)?;
+                Some(export::PlacedLabel {
+                    text: text.clone(),
+                    x,
+                    y,
+                    // Screen angles turn clockwise because Y points down
+                    rotation: (360.0 - f64::from(angle).to_degrees()).rem_euclid(360.0),
+                    anchor: anchor_name(anchor),
+                    size: font.size,
+                })
+            })
+            .collect()
+    }
+
+    /// Loaded files a parser bug reproducer can be cut from
+    fn reproducer_sources(&self) -> Vec<ReproducerSource> {
+        let mut sources: Vec<ReproducerSource> = self
+            .lef_files
+            .iter()
+            .map(|lef_file| ReproducerSource {
+                path: lef_file.path.clone(),
+                is_def: false,
+                parts: lef_file
+                    .data
+                    .macros
+                    .iter()
+                    .map(|m| m.name.clone())
+                    .chain(lef_file.data.layers.iter().map(|l| l.name.clone()))
+                    .collect(),
+            })
+            .collect();
+        if let Some(path) = &self.def_file_path {
+            let sections = std::fs::read_to_string(path)
+                .map(|content| reproducer::def_sections(&content))
+                .unwrap_or_default();
+            sources.push(ReproducerSource {
+                path: path.clone(),
+                is_def: true,
+                parts: sections,
+            });
+        }
+        sources
+    }
+
+    /// Write a reproducer and its expectations as a test fixture pair
+    fn handle_save_reproducer(&mut self, request: ReproducerRequest) {
+        let extension = if request.is_def { "def" } else { "lef" };
+        let Some(path) = FileDialog::new()
+            .set_file_name(format!("{}.{extension}", request.name))
+            .add_filter("Fixtures", &[extension])
+            .save_file()
+        else {
+            return;
+        };
+        let expect_path = path.with_extension("expect");
+        let result = std::fs::write(&path, &request.reproducer.source)
+            .and_then(|()| std::fs::write(&expect_path, &request.reproducer.expect));
+        match result {
+            Ok(()) => {
+                self.success_message = Some(format!(
+                    "Saved reproducer to: {} and {}",
+                    path.display(),
+                    expect_path.display()
+                ));
+            }
+            Err(e) => self.error_message = Some(format!("Failed to save reproducer: {e}")),
+        }
+    }
+
+    fn handle_export_gds(&mut self, request: GdsExportRequest) {
+        let default_name = match &self.def_data {
+            Some(def) if request.include_def && !def.design.is_empty() => def.design.clone(),
+            _ => self.get_lef_basename(),
+        };
+        let Some(path) = FileDialog::new()
+            .set_file_name(format!("{default_name}.gds"))
+            .add_filter("GDSII files", &["gds", "gds2"])
+            .save_file()
+        else {
+            return;
+        };
+
+        // First definition wins when several libraries define a macro
+        let mut seen = std::collections::HashSet::new();
+        let macros: Vec<_> = self
+            .lef_files
+            .iter()
+            .flat_map(|f| &f.data.macros)
+            .filter(|m| seen.insert(m.name.as_str()))
+            .collect();
+        let db_units = self.def_db_units();
+        let def = self
+            .def_data
+            .as_ref()
+            .filter(|_| request.include_def)
+            .map(|def| (def, db_units));
+
+        match gds::write_gds(&macros, def, &request.map, &path.to_string_lossy()) {
+            Ok(summary) => {
+                let mut message = format!(
+                    "Successfully wrote {} structures with {} shapes to {}",
+                    summary.structures,
+                    summary.boundaries,
+                    path.display()
+                );
+                if !summary.unmapped_layers.is_empty() {
+                    let layers: Vec<_> = summary.unmapped_layers.into_iter().collect();
+                    message.push_str(&format!(
+                        "\nSkipped layers missing from the map: {}",
+                        layers.join(", ")
+                    ));
+                }
+                if summary.all_angle_boundaries > 0 {
+                    message.push_str(&format!(
+                        "\n{} shapes have edges that are neither orthogonal nor 45-degree, \
+                         which many GDS flows reject",
+                        summary.all_angle_boundaries
+                    ));
+                }
+                self.success_message = Some(message);
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to export GDSII: {e}"));
+            }
+        }
+    }
+
+    fn handle_save_lef(&mut self, lef_file_idx: usize) {
+        let Some(lef_file) = self.lef_files.get(lef_file_idx) else {
+            return;
+        };
+        let stem = Path::new(&lef_file.path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("library");
+        let Some(path) = FileDialog::new()
+            .set_file_name(format!("{stem}_out.lef"))
+            .add_filter("LEF files", &["lef"])
+            .save_file()
+        else {
+            return;
+        };
+
+        match lef::writer::write_lef(&lef_file.data, &path.to_string_lossy()) {
+            Ok(()) => {
+                self.success_message = Some(format!(
+                    "Successfully saved {} macros to {}",
+                    lef_file.data.macros.len(),
+                    path.display()
+                ));
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to save LEF: {e}"));
+            }
+        }
+    }
+
+    fn handle_export_selected_cells_pinlist(&mut self) {
+        if !self.lef_files.is_empty() {
+            if self.selected_cells.is_empty() {
+                self.error_message = Some("No cells selected for export".to_string());
+                return;
+            }
+
+            // Get selected macros from all LEF files
+            let selected_macros: Vec<&crate::lef::LefMacro> = self
+                .lef_files
+                .iter()
+                .flat_map(|lef_file| lef_file.data.macros.iter())
+                .filter(|macro_def| self.selected_cells.contains(&macro_def.name))
+                .collect();
+
+            if selected_macros.is_empty() {
+                self.error_message = Some("Selected cells not found in LEF data".to_string());
+                return;
+            }
+
+            if selected_macros.len() == 1 {
+                // Single cell export - file save dialog
+                let macro_def = selected_macros[0];
+                let default_filename = format!("{}.csv", macro_def.name);
+
+                if let Some(file_path) = FileDialog::new()
+                    .set_file_name(&default_filename)
+                    .add_filter("CSV files", &["csv"])
+                    .save_file()
+                {
+                    match export::export_cell_pinlist_to_csv(
+                        macro_def,
+                        &file_path.to_string_lossy(),
+                    ) {
+                        Ok(()) => {
+                            self.success_message = Some(format!(
+                                "Successfully exported pinlist for cell '{}' to: {}",
+                                macro_def.name,
+                                file_path.display()
+                            ));
+                        }
+                        Err(e) => {
+                            self.error_message = Some(format!("Failed to export pinlist: {e}"));
+                        }
+                    }
+                }
+            } else {
+                // Multiple cells export - directory picker
+                if let Some(output_dir) = FileDialog::new().pick_folder() {
+                    match export::export_multiple_cells_pinlist(
+                        &selected_macros,
+                        &output_dir.to_string_lossy(),
+                    ) {
+                        Ok(()) => {
+                            self.success_message = Some(format!(
+                                "Successfully exported pinlists for {} cells to directory: {}",
+                                selected_macros.len(),
+                                output_dir.display()
+                            ));
+                        }
+                        Err(e) => {
+                            self.error_message = Some(format!("Failed to export pinlists: {e}"));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_export_selected_cells_png(&mut self) {
+        if self.def_mode || self.selected_cells.is_empty() {
+            self.error_message = Some("No cells selected for export".to_string());
+            return;
+        }
+
+        let macro_names: std::collections::BTreeSet<&str> = self
+            .lef_files
+            .iter()
+            .flat_map(|lef_file| lef_file.data.macros.iter())
+            .filter(|macro_def| self.selected_cells.contains(&macro_def.name))
+            .map(|macro_def| macro_def.name.as_str())
+            .collect();
+
+        if macro_names.is_empty() {
+            self.error_message = Some("Selected cells not found in LEF data".to_string());
+            return;
+        }
+
+        if let Some(output_dir) = FileDialog::new().pick_folder() {
+            let mut taken = HashSet::new();
+            let mut pending: Vec<(String, String)> = macro_names
+                .into_iter()
+                .map(|name| {
+                    let file_name = crate::png_export::cell_file_name(name, "png", &mut taken);
+                    (name.to_string(), file_name)
+                })
+                .collect();
+            // Export in name order, pending is consumed from the end
+            pending.reverse();
+            self.batch_png_export = Some(BatchPngExport {
+                output_dir,
+                pending,
+                writing: None,
+                exported: 0,
+            });
+        }
+    }
+
+    /// Advance the batch PNG export by one macro
+    ///
+    /// Waits for the image being written, then paints the next macro and
+    /// writes it in the background.
+    fn process_batch_png_export(&mut self, ctx: &egui::Context) {
+        let Some(mut batch) = self.batch_png_export.take() else {
+            return;
+        };
+        ctx.request_repaint();
+
+        if let Some((macro_name, receiver)) = &batch.writing {
+            let result = match receiver.try_recv() {
+                Ok(result) => result,
+                Err(mpsc::TryRecvError::Empty) => {
+                    self.batch_png_export = Some(batch);
+                    return;
+                }
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    Err("the export stopped unexpectedly".to_string())
+                }
+            };
+            if let Err(e) = result {
+                self.error_message =
+                    Some(format!("Failed to export PNG for cell '{macro_name}': {e}"));
+                return;
+            }
+            batch.exported += 1;
+            batch.writing = None;
+        }
+
+        let Some((macro_name, file_name)) = batch.pending.pop() else {
+            self.success_message = Some(format!(
+                "Successfully exported {} cell images to directory: {}",
+                batch.exported,
+                batch.output_dir.display()
+            ));
+            return;
+        };
+
+        let scene = self.paint_cell_offscreen(ctx, &macro_name, BATCH_PNG_SIZE);
+        let path = batch.output_dir.join(file_name);
+        let (sender, receiver) = mpsc::channel();
+        let spawned = platform::spawn("batch-png-export", move || {
+            let image = scene.render(egui::Color32::BLACK);
+            let saved = crate::png_export::save_rgba_png(&image, &path);
+            let _ = sender.send(saved.map_err(|e| e.to_string()));
+        });
+        if let Err(e) = spawned {
+            self.error_message = Some(format!("Failed to start the PNG export: {e}"));
+            return;
+        }
+        batch.writing = Some((macro_name, receiver));
+        self.batch_png_export = Some(batch);
+    }
+
+    /// Paint a macro alone off-screen, fitted to `size` pixels, with every
+    /// pin layer and labels visible
+    fn paint_cell_offscreen(
+        &mut self,
+        ctx: &egui::Context,
+        macro_name: &str,
+        size: [u32; 2],
+    ) -> OffscreenScene {
+        let saved_selected_cells = std::mem::take(&mut self.selected_cells);
+        self.selected_cells.insert(macro_name.to_string());
+        let pin_layers = self
+            .all_layers
+            .iter()
+            .filter(|layer| layer.ends_with(".PIN"))
+            .cloned()
+            .collect();
+        let saved_visible_layers = std::mem::replace(&mut self.visible_layers, pin_layers);
+        self.visible_layers.insert("OUTLINE".to_string());
+        self.visible_layers.insert("LABEL".to_string());
+        let saved_show_pin_text = std::mem::replace(&mut self.show_pin_text, true);
+        let saved_camera = self.camera();
+
+        let region = crate::png_export::offscreen_region(size, ctx.pixels_per_point());
+        self.fit_to_view(region.size());
+        let scene = self.paint_offscreen(ctx, size);
+
+        self.selected_cells = saved_selected_cells;
+        self.visible_layers = saved_visible_layers;
+        self.show_pin_text = saved_show_pin_text;
+        self.set_camera(saved_camera);
+        scene
+    }
+
+    /// Import a Verilog or SPEF netlist and cross-check DEF names against it
+    fn handle_import_netlist(&mut self) {
+        if let Some(path) = FileDialog::new()
+            .add_filter("Netlist files", &["v", "vg", "spef"])
+            .add_filter("All files", &["*"])
+            .pick_file()
+        {
+            let path = path.to_string_lossy().to_string();
+            if let Err(e) = self
+                .checks_panel
+                .import_netlist(&path, self.def_data.as_ref())
+            {
+                self.error_message = Some(format!("Failed to import netlist: {e}"));
+            }
+        }
+    }
+
+    fn handle_export_verilog_stub(&mut self) {
+        if !self.lef_files.is_empty() {
+            let basename = self.get_lef_basename();
+            let default_filename = format!("{basename}.v");
+            if let Some(first_file_path) = FileDialog::new()
+                .set_file_name(&default_filename)
+                .add_filter("Verilog files", &["v"])
+                .save_file()
+            {
+                let output_dir = first_file_path
+                    .parent()
+                    .unwrap_or(std::path::Path::new("."));
+                let mut exported_files = Vec::new();
+                let mut had_error = false;
+
+                // Export each LEF file to a separate Verilog file
+                for lef_file in &self.lef_files {
+                    // Generate output filename based on LEF file's original name
+                    let lef_basename = std::path::Path::new(&lef_file.path)
+                        .file_stem()
+                        .and_then(|s| s.to_str())
+                        .unwrap_or("output");
+                    let output_path = output_dir.join(format!("{}.v", lef_basename));
+
+                    match export::export_verilog_stub_with_options(
+                        &lef_file.data,
+                        &output_path.to_string_lossy(),
+                        lef_basename,
+                        &self.verilog_stub_options,
+                    ) {
+                        Ok(()) => {
+                            exported_files.push(output_path.display().to_string());
+                        }
+                        Err(e) => {
+                            self.error_message =
+                                Some(format!("Failed to export {}: {}", lef_file.path, e));
+                            had_error = true;
+                            break;
+                        }
+                    }
+                }
+
+                if !had_error {
+                    let total_macros: usize =
+                        self.lef_files.iter().map(|f| f.data.macros.len()).sum();
+                    self.success_message = Some(format!(
+                        "Successfully exported {} cells from {} LEF files:\n{}",
+                        total_macros,
+                        self.lef_files.len(),
+                        exported_files.join("\n")
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Export each LEF file to a SPICE stub next to the chosen file
+    fn handle_export_spice_stub(&mut self) {
+        let Some(first_file_path) = FileDialog::new()
+            .set_file_name(format!("{}.sp", self.get_lef_basename()))
+            .add_filter("SPICE files", &["sp", "spi", "cdl"])
+            .save_file()
+        else {
+            return;
+        };
+        let output_dir = first_file_path
+            .parent()
+            .unwrap_or(std::path::Path::new("."));
+        let mut exported_files = Vec::new();
+        for lef_file in &self.lef_files {
+            let lef_basename = std::path::Path::new(&lef_file.path)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("output");
+            let output_path = output_dir.join(format!("{lef_basename}.sp"));
+            if let Err(e) = export::export_spice_stub(
+                &lef_file.data,
+                &output_path.to_string_lossy(),
+                lef_basename,
+                &self.spice_stub_options,
+            ) {
+                self.error_message = Some(format!("Failed to export {}: {}", lef_file.path, e));
+                return;
+            }
+            exported_files.push(output_path.display().to_string());
+        }
+        let total_macros: usize = self.lef_files.iter().map(|f| f.data.macros.len()).sum();
+        self.success_message = Some(format!(
+            "Successfully exported {} cells from {} LEF files:\n{}",
+            total_macros,
+            self.lef_files.len(),
+            exported_files.join("\n")
+        ));
+    }
+
+    /// Export each parsed LEF file as JSON next to the chosen file
+    fn handle_export_lef_json(&mut self) {
+        let Some(first_file_path) = FileDialog::new()
+            .set_file_name(format!("{}.json", self.get_lef_basename()))
+            .add_filter("JSON files", &["json"])
+            .save_file()
+        else {
+            return;
+        };
+        let output_dir = first_file_path
+            .parent()
+            .unwrap_or(std::path::Path::new("."));
+        let mut exported_files = Vec::new();
+        for lef_file in &self.lef_files {
+            let lef_basename = std::path::Path::new(&lef_file.path)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("output");
+            let output_path = output_dir.join(format!("{lef_basename}.json"));
+            let written = lef_file
+                .data
+                .to_json()
+                .map_err(|e| e.to_string())
+                .and_then(|json| {
+                    std::fs::write(&output_path, json + "\n").map_err(|e| e.to_string())
+                });
+            if let Err(e) = written {
+                self.error_message = Some(format!("Failed to export {}: {}", lef_file.path, e));
+                return;
+            }
+            exported_files.push(output_path.display().to_string());
+        }
+        self.success_message = Some(format!(
+            "Successfully exported {} LEF files as JSON:\n{}",
+            self.lef_files.len(),
+            exported_files.join("\n")
+        ));
+    }
+
+    fn handle_export_lib_stub(&mut self) {
+        if !self.lef_files.is_empty() {
+            // Voltage config is already initialized when LEF file was loaded
+            // For single file: use its basename as lib_name
+            // For multiple files: lib_name will be auto-generated from each file's basename during export
+            let basename = self.get_lef_basename();
+            self.voltage_config.lib_name = basename;
+            self.voltage_dialog.show();
+        }
+    }
+
+    fn perform_lib_export(&mut self) {
+        if !self.lef_files.is_empty() {
+            let default_filename = format!("{}.lib", self.voltage_config.lib_name);
+            if let Some(first_file_path) = FileDialog::new()
+                .set_file_name(&default_filename)
+                .add_filter("Liberty files", &["lib"])
+                .save_file()
+            {
+                let output_dir = first_file_path
+                    .parent()
+                    .unwrap_or(std::path::Path::new("."));
+                let mut exported_files = Vec::new();
+                let mut had_error = false;
+
+                // Export each LEF file to a separate Liberty file
+                for lef_file in &self.lef_files {
+                    // Generate output filename based on LEF file's original name
+                    let lef_basename = std::path::Path::new(&lef_file.path)
+                        .file_stem()
+                        .and_then(|s| s.to_str())
+                        .unwrap_or("output");
+                    let output_path = output_dir.join(format!("{}.lib", lef_basename));
+
+                    // Create a dedicated VoltageConfig for this file
+                    // Use file's basename as lib_name, but inherit voltage settings from user config
+                    let mut file_voltage_config = self.voltage_config.clone();
+                    file_voltage_config.lib_name = lef_basename.to_string();
+
+                    match export::export_lib_stub_with_voltage_config(
+                        &lef_file.data,
+                        &output_path.to_string_lossy(),
+                        &file_voltage_config,
+                    ) {
+                        Ok(()) => {
+                            exported_files.push(output_path.display().to_string());
+                        }
+                        Err(e) => {
+                            self.error_message =
+                                Some(format!("Failed to export {}: {}", lef_file.path, e));
+                            had_error = true;
+                            break;
+                        }
+                    }
+                }
+
+                if !had_error {
+                    let total_macros: usize =
+                        self.lef_files.iter().map(|f| f.data.macros.len()).sum();
+                    self.success_message = Some(format!(
+                        "Successfully exported {} cells from {} LEF files:\n{}",
+                        total_macros,
+                        self.lef_files.len(),
+                        exported_files.join("\n")
+                    ));
+                }
+            }
+        }
+    }
+
+    fn render_menu_bar(&mut self, ui: &mut egui::Ui) {
+        // Consumed here so the canvas does not take it for the fit key
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::F)) {
+            self.search_bar.focus();
+        }
+        egui::menu::bar(ui, |ui| {
             ui.menu_button("File", |ui| {
                 if ui.button("Open LEF File").clicked() {
                     self.open_lef_file_dialog();
@@ -3038,6 +6709,35 @@ impl LefDefViewer {
                     ui.close_menu();
                 }
 
+                ui.add_enabled_ui(!self.recent_files.is_empty(), |ui| {
+                    ui.menu_button("Recent Files", |ui| {
+                        let mut opened = None;
+                        for path in self.recent_files.iter() {
+                            let name = Path::new(path)
+                                .file_name()
+                                .and_then(|n| n.to_str())
+                                .unwrap_or(path);
+                            if ui.button(name).on_hover_text(path).clicked() {
+                                opened = Some(path.to_string());
+                            }
+                        }
+                        ui.separator();
+                        if ui.button("Clear Recent Files").clicked() {
+                            self.recent_files.clear();
+                            ui.close_menu();
+                        }
+                        if let Some(path) = opened {
+                            self.open_paths(vec![path]);
+                            ui.close_menu();
+                        }
+                    });
+                });
+
+                ui.checkbox(&mut self.watch_files, "Watch Files for Changes")
+                    .on_hover_text(
+                        "Parse loaded files again when they change on disk, keeping the view",
+                    );
+
                 ui.separator();
 
                 if ui
@@ -3062,6 +6762,20 @@ impl LefDefViewer {
                     ui.close_menu();
                 }
 
+                if ui
+                    .add_enabled(
+                        !self.def_mode
+                            && !self.selected_cells.is_empty()
+                            && self.batch_png_export.is_none(),
+                        egui::Button::new("Export Selected Cells as PNG"),
+                    )
+                    .on_disabled_hover_text("Select cells in LEF mode to export images")
+                    .clicked()
+                {
+                    self.handle_export_selected_cells_png();
+                    ui.close_menu();
+                }
+
                 if ui
                     .add_enabled(
                         !self.lef_files.is_empty(),
@@ -3084,22 +6798,258 @@ impl LefDefViewer {
                     ui.close_menu();
                 }
 
+                if ui
+                    .add_enabled(
+                        !self.lef_files.is_empty(),
+                        egui::Button::new("Export SPICE Stub"),
+                    )
+                    .on_hover_text("Empty .SUBCKT per cell, for LVS box cells")
+                    .clicked()
+                {
+                    self.handle_export_spice_stub();
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(
+                        !self.lef_files.is_empty(),
+                        egui::Button::new("Export LEF as JSON"),
+                    )
+                    .on_hover_text("The parsed library, for tools that should not parse LEF")
+                    .clicked()
+                {
+                    self.handle_export_lef_json();
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(
+                        !self.eco_log.is_empty(),
+                        egui::Button::new("Export ECO Edits (DEF/ECO)"),
+                    )
+                    .on_disabled_hover_text("Swap or move cells in DEF edit mode first")
+                    .clicked()
+                {
+                    self.handle_export_cell_swaps();
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(
+                        self.def_data.is_some(),
+                        egui::Button::new("Export DEF Pins CSV..."),
+                    )
+                    .on_hover_text(
+                        "Net, direction, USE, placement status and position of every pin",
+                    )
+                    .clicked()
+                {
+                    self.handle_export_def_pins();
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(
+                        self.def_data.is_some(),
+                        egui::Button::new("Export DEF Placement CSV..."),
+                    )
+                    .on_hover_text("Macro, location, orientation and status of every component")
+                    .clicked()
+                {
+                    self.handle_export_def_placement();
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(
+                        self.def_data.is_some(),
+                        egui::Button::new("Export Net Layer Usage CSV..."),
+                    )
+                    .on_hover_text("Routed length per layer and via count of every net")
+                    .clicked()
+                {
+                    self.handle_export_net_layer_usage();
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(
+                        self.def_data.is_some(),
+                        egui::Button::new("Export Netlist JSON..."),
+                    )
+                    .on_hover_text("Connections and routing statistics of every net")
+                    .clicked()
+                {
+                    self.handle_export_netlist_json();
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(
+                        self.def_data.is_some() || !self.lef_files.is_empty(),
+                        egui::Button::new("Export Labels CSV..."),
+                    )
+                    .on_hover_text("Text, position and rotation of every label in the view")
+                    .clicked()
+                {
+                    self.handle_export_labels();
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(self.def_data.is_some(), egui::Button::new("Save DEF As..."))
+                    .on_hover_text("Write the loaded design, including cell swaps, as DEF")
+                    .clicked()
+                {
+                    self.handle_save_def();
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(
+                        !self.lef_files.is_empty(),
+                        egui::Button::new("Export GDSII..."),
+                    )
+                    .on_hover_text("Write the macros and the placed design as a GDS stream")
+                    .clicked()
+                {
+                    let layers = self.gds_layer_names();
+                    self.gds_dialog.open(&layers, self.def_data.is_some());
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(
+                        !self.lef_files.is_empty() || self.def_data.is_some(),
+                        egui::Button::new("Export View as SVG..."),
+                    )
+                    .on_hover_text("Write the canvas as it is shown as a vector image")
+                    .clicked()
+                {
+                    self.handle_export_svg();
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(
+                        !self.lef_files.is_empty() || self.def_data.is_some(),
+                        egui::Button::new("Export Image..."),
+                    )
+                    .on_hover_text(
+                        "Render the view, the full design or exact bounds as PNG or SVG at any \
+                         resolution",
+                    )
+                    .clicked()
+                {
+                    self.open_image_export_dialog();
+                    ui.close_menu();
+                }
+
+                ui.add_enabled_ui(!self.lef_files.is_empty(), |ui| {
+                    ui.menu_button("Save LEF As", |ui| {
+                        let mut save = None;
+                        for (idx, lef_file) in self.lef_files.iter().enumerate() {
+                            let name = Path::new(&lef_file.path)
+                                .file_name()
+                                .and_then(|n| n.to_str())
+                                .unwrap_or(&lef_file.path);
+                            if ui
+                                .button(name)
+                                .on_hover_text("Write the library with its macro edits as LEF")
+                                .clicked()
+                            {
+                                save = Some(idx);
+                            }
+                        }
+                        if let Some(idx) = save {
+                            self.handle_save_lef(idx);
+                            ui.close_menu();
+                        }
+                    });
+                });
+
+                ui.separator();
+
+                if ui.button("Import Netlist for Cross-Check").clicked() {
+                    self.handle_import_netlist();
+                    ui.close_menu();
+                }
+
+                if ui
+                    .button("Load Reference DEF...")
+                    .on_hover_text("Compare the placement of the loaded DEF against another DEF")
+                    .clicked()
+                {
+                    self.handle_load_reference_def();
+                    ui.close_menu();
+                }
+
+                if ui
+                    .button("Compare LEF Files...")
+                    .on_hover_text("Added, removed and changed macros between two library versions")
+                    .clicked()
+                {
+                    self.lef_diff_panel.visible = true;
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add_enabled(
+                        self.def_data.is_some() || !self.lef_files.is_empty(),
+                        egui::Button::new("Run Checks and Save Report"),
+                    )
+                    .on_hover_text(
+                        "Missing cells, overlaps, off-grid pins, duplicates and pin minimum area",
+                    )
+                    .clicked()
+                {
+                    self.handle_save_check_report();
+                    ui.close_menu();
+                }
+
                 ui.separator();
 
                 if ui.button("Close All LEF Files").clicked() {
                     self.lef_files.clear();
+                    self.mode_cameras.forget(false);
+                    self.macro_geometry_keys.clear();
+                    self.merged_macros.clear();
                     self.selected_cells.clear();
                     self.all_layers.clear();
                     self.visible_layers.clear();
+                    self.layer_solo.clear();
+                    self.pin_lint_dialog.clear();
+                    self.min_area_dialog.clear();
+                    self.check_library_layers();
+                    self.rebuild_footprint_families();
+                    self.tech_layer_styles.clear();
+                    self.resource_panel.invalidate();
+                    self.design_index = None;
+                    self.precompute.invalidate();
                     ui.close_menu();
                 }
 
                 if ui.button("Close DEF File").clicked() {
+                    self.set_def_mode(false);
+                    self.mode_cameras.forget(true);
                     self.def_data = None;
                     self.def_file_path = None;
-                    self.def_mode = false;
                     self.component_macro_map.clear();
                     self.missing_cells.clear();
+                    self.checks_panel.refresh(None);
+                    self.refresh_placement_diff();
+                    self.update_waiver_file();
+                    self.component_flags.clear();
+                    self.pin_flags.clear();
+                    self.eco_log.clear();
+                    self.component_drag = None;
+                    self.hidden_special_nets.clear();
+                    self.isolation = None;
+                    self.inspected_net = None;
+                    self.highlighted_group = None;
+                    self.resource_panel.invalidate();
+                    self.design_index = None;
+                    self.precompute.invalidate();
                     ui.close_menu();
                 }
 
@@ -3114,6 +7064,110 @@ impl LefDefViewer {
                 ui.checkbox(&mut self.show_lef_details, "Show LEF Details");
                 ui.checkbox(&mut self.show_def_details, "Show DEF Details");
                 ui.checkbox(&mut self.show_layers_panel, "Show Layers Panel");
+                ui.add_enabled(
+                    self.def_data.is_some(),
+                    egui::Checkbox::new(&mut self.component_table.visible, "Component Table"),
+                );
+                ui.add_enabled(
+                    self.def_data.is_some(),
+                    egui::Checkbox::new(&mut self.dual_pane.visible, "Cell vs Instance"),
+                );
+                ui.checkbox(&mut self.overlay_dialog.visible, "Source Transforms");
+                ui.checkbox(&mut self.underlay_dialog.visible, "Background Image");
+                ui.add_enabled(
+                    self.def_data.is_some(),
+                    egui::Checkbox::new(&mut self.show_rows, "Rows"),
+                )
+                .on_hover_text("DEF ROW boundaries and site grid");
+                ui.add_enabled(
+                    self.def_data.is_some(),
+                    egui::Checkbox::new(&mut self.show_tracks, "Tracks"),
+                )
+                .on_hover_text("DEF TRACKS of the visible routing layers");
+                ui.checkbox(&mut self.checks_panel.visible, "Checks Panel");
+                ui.checkbox(&mut self.issues_panel.visible, "Issues Panel")
+                    .on_hover_text("Docked list of the check violations, click one to zoom to it");
+                ui.checkbox(&mut self.script_console.visible, "Scripting Console")
+                    .on_hover_text("Rhai scripts over the loaded design, with a snippet library");
+                ui.checkbox(&mut self.edits_panel.visible, "Uncommitted Edits");
+                ui.checkbox(&mut self.placement_diff_panel.visible, "Placement Diff");
+                ui.checkbox(&mut self.charts_panel.visible, "Charts Panel");
+                ui.checkbox(&mut self.resource_panel.visible, "Routing Resources");
+                ui.checkbox(&mut self.pin_lint_dialog.visible, "Pin Naming Linter");
+                ui.checkbox(&mut self.min_area_dialog.visible, "Pin Minimum Area")
+                    .on_hover_text("Pin shapes below the AREA rule of their layer");
+                ui.checkbox(&mut self.layer_alias_dialog.visible, "Layer Aliases")
+                    .on_hover_text("Map library layer names onto the technology layers");
+                ui.checkbox(&mut self.profiler_window.visible, "Profiler");
+                let mut ruler_active = self.ruler.active;
+                if ui
+                    .checkbox(&mut ruler_active, "Ruler (R)")
+                    .on_hover_text("Click two points on the canvas to measure their distance")
+                    .changed()
+                {
+                    self.toggle_ruler();
+                }
+                ui.indent("ruler_snap", |ui| {
+                    ui.checkbox(&mut self.ruler.snap_edges, "Snap to Shape Edges");
+                    ui.checkbox(&mut self.ruler.snap_tracks, "Snap to Tracks")
+                        .on_hover_text("DEF TRACKS; X tracks give X positions, Y tracks Y");
+                });
+                if ui
+                    .add(egui::Button::new("Go to Object...").shortcut_text("Ctrl+G"))
+                    .clicked()
+                {
+                    self.goto_dialog.open(self.goto_targets());
+                    ui.close_menu();
+                }
+                let selection = self.tcl_selection();
+                if ui
+                    .add_enabled(!selection.is_empty(), egui::Button::new("Copy Selection as Tcl"))
+                    .on_hover_text(format!(
+                        "get_cells, get_nets and get_ports of the selection, for {}; the dialect is set in Preferences",
+                        self.tcl_dialect.label()
+                    ))
+                    .clicked()
+                {
+                    ui.ctx().copy_text(selection.to_tcl(self.tcl_dialect));
+                    self.success_message =
+                        Some(format!("Copied {} selected objects as Tcl", selection.len()));
+                    ui.close_menu();
+                }
+                if ui
+                    .button("Share View...")
+                    .on_hover_text("Copy or paste a permalink of the camera, layers and selections")
+                    .clicked()
+                {
+                    let state = self.view_state();
+                    self.permalink_dialog.open(&state);
+                    ui.close_menu();
+                }
+                ui.separator();
+                if ui.button("Preferences...").clicked() {
+                    self.show_preferences = true;
+                    ui.close_menu();
+                }
+                ui.menu_button("Auto-Fit on Load", |ui| {
+                    for policy in AutoFit::ALL {
+                        ui.radio_value(&mut self.view_policy.auto_fit, policy, policy.label());
+                    }
+                    ui.separator();
+                    ui.checkbox(
+                        &mut self.view_policy.preserve_view,
+                        "Preserve View Across Reload",
+                    )
+                    .on_hover_text("Restore the last view of a design when it is loaded again");
+                });
+                if ui
+                    .button("Reload Layer Defaults")
+                    .on_hover_text(
+                        "Read default_visible and default_hidden from the configuration file",
+                    )
+                    .clicked()
+                {
+                    self.reload_layer_config();
+                    ui.close_menu();
+                }
                 ui.separator();
                 // Sync show_pin_text with LABEL layer visibility
                 let mut label_visible = self.visible_layers.contains("LABEL");
@@ -3126,6 +7180,149 @@ impl LefDefViewer {
                     self.show_pin_text = label_visible;
                 }
             });
+
+            ui.menu_button("Help", |ui| {
+                if ui.button("Orientation Legend").clicked() {
+                    self.show_orientation_legend = true;
+                    ui.close_menu();
+                }
+                if ui
+                    .button("Report a Parser Bug...")
+                    .on_hover_text("Cut a loaded file down to a reproducer for a test fixture")
+                    .clicked()
+                {
+                    let sources = self.reproducer_sources();
+                    self.reproducer_dialog.open(sources);
+                    ui.close_menu();
+                }
+            });
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let mut search_bar = std::mem::take(&mut self.search_bar);
+                let action = search_bar.render(ui, || self.goto_targets());
+                self.search_bar = search_bar;
+                match action {
+                    Some(SearchAction::Point(x, y)) => {
+                        let (x, y) = (x as f32, y as f32);
+                        let half = SEARCH_POINT_SPAN / 2.0;
+                        self.focus_bounds_requested =
+                            Some((x - half, y - half, x + half, y + half));
+                        self.status_bar
+                            .hint(format!("Centered on ({x:.3}, {y:.3}) um"));
+                    }
+                    Some(SearchAction::Object(target)) => self.go_to_object(target),
+                    None => {}
+                }
+            });
+        });
+    }
+
+    /// Render the orientation cheat-sheet
+    ///
+    /// Draws a sample asymmetric macro in all 8 DEF orientations. Every shape
+    /// goes through transform_point, so the legend always matches what the
+    /// renderer does with placed components.
+    fn render_orientation_legend(&self, ui: &mut egui::Ui) {
+        // (orientation, equivalent rotate/mirror notation from the DEF spec)
+        const ORIENTATIONS: [(&str, &str); 8] = [
+            ("N", "R0"),
+            ("W", "R90"),
+            ("S", "R180"),
+            ("E", "R270"),
+            ("FN", "MY"),
+            ("FW", "MX90"),
+            ("FS", "MX"),
+            ("FE", "MY90"),
+        ];
+        const CELL_SIZE: f32 = 120.0;
+
+        // Sample macro: 2x1 body, origin marker in the lower-left corner and
+        // a pin on the upper part of the left edge, so rotation and mirroring
+        // are both visible
+        let macro_size = (2.0, 1.0);
+        let outline = [(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (0.0, 1.0)];
+        let origin_marker = [(0.0, 0.0), (0.5, 0.0), (0.0, 0.5)];
+        let pin = [(0.0, 0.6), (0.3, 0.6), (0.3, 0.9), (0.0, 0.9)];
+
+        ui.label("Sample macro placed at (0, 0) in each DEF orientation.");
+        ui.label("Triangle marks the LEF origin corner, square marks pin A.");
+        ui.separator();
+
+        egui::Grid::new("orientation_legend_grid")
+            .spacing([8.0, 8.0])
+            .show(ui, |ui| {
+                for (i, (orient, notation)) in ORIENTATIONS.iter().enumerate() {
+                    ui.vertical(|ui| {
+                        let (rect, _) = ui.allocate_exact_size(
+                            egui::Vec2::splat(CELL_SIZE),
+                            egui::Sense::hover(),
+                        );
+                        let painter = ui.painter_at(rect);
+                        painter.rect_filled(rect, 2.0, egui::Color32::from_gray(30));
+
+                        // Fit the transformed macro into the cell, Y axis up
+                        let (min_x, min_y, max_x, max_y) =
+                            self.transform_bbox(macro_size, (0.0, 0.0), orient);
+                        let scale = (CELL_SIZE - 20.0)
+                            / ((max_x - min_x).max(max_y - min_y) as f32).max(f32::EPSILON);
+                        let offset = egui::Vec2::new(
+                            (CELL_SIZE - (max_x - min_x) as f32 * scale) / 2.0,
+                            (CELL_SIZE - (max_y - min_y) as f32 * scale) / 2.0,
+                        );
+                        let to_screen = |p: &(f64, f64)| {
+                            let (x, y) = self.transform_point(*p, (0.0, 0.0), orient, macro_size);
+                            egui::Pos2::new(
+                                rect.min.x + offset.x + (x - min_x) as f32 * scale,
+                                rect.max.y - offset.y - (y - min_y) as f32 * scale,
+                            )
+                        };
+
+                        painter.add(egui::Shape::convex_polygon(
+                            outline.iter().map(to_screen).collect(),
+                            egui::Color32::from_rgb(60, 60, 80),
+                            egui::Stroke::new(self.dpi_style.stroke(1.5), egui::Color32::WHITE),
+                        ));
+                        painter.add(egui::Shape::convex_polygon(
+                            origin_marker.iter().map(to_screen).collect(),
+                            egui::Color32::from_rgb(244, 67, 54),
+                            egui::Stroke::NONE,
+                        ));
+                        painter.add(egui::Shape::convex_polygon(
+                            pin.iter().map(to_screen).collect(),
+                            egui::Color32::from_rgb(76, 175, 80),
+                            egui::Stroke::NONE,
+                        ));
+                        painter.text(
+                            to_screen(&pin[2]),
+                            egui::Align2::LEFT_BOTTOM,
+                            "A",
+                            egui::FontId::proportional(self.dpi_style.font_size(11.0)),
+                            egui::Color32::WHITE,
+                        );
+
+                        ui.label(format!("{orient}  ({notation})"));
+                    });
+
+                    if i % 4 == 3 {
+                        ui.end_row();
+                    }
+                }
+            });
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(40.0, 24.0), egui::Sense::hover());
+            let painter = ui.painter_at(rect);
+            painter.rect_filled(rect, 0.0, egui::Color32::from_gray(30));
+            let outline_color = self.get_layer_color("OUTLINE");
+            painter.rect_stroke(
+                rect,
+                0.0,
+                egui::Stroke::new(self.dpi_style.stroke(1.0), outline_color),
+                egui::StrokeKind::Inside,
+            );
+            Self::paint_cross_hatch(&painter, rect, outline_color);
+            ui.label("COVER component (bump, pad filler), not counted in utilization by default");
         });
     }
 
@@ -3166,46 +7363,16 @@ impl LefDefViewer {
 
                     // Rebuild layer lists from remaining files
                     if !self.lef_files.is_empty() {
-                        self.all_layers.insert("OUTLINE".to_string());
-                        self.visible_layers.insert("OUTLINE".to_string());
-                        self.all_layers.insert("LABEL".to_string());
-                        if self.show_pin_text {
-                            self.visible_layers.insert("LABEL".to_string());
-                        }
-
-                        for lef_file in &self.lef_files {
-                            for macro_def in &lef_file.data.macros {
-                                for pin in &macro_def.pins {
-                                    for port in &pin.ports {
-                                        for rect in &port.rects {
-                                            let detailed_layer = format!("{}.PIN", rect.layer);
-                                            self.all_layers.insert(detailed_layer.clone());
-                                            if pin.use_type == "POWER" || pin.use_type == "GROUND" {
-                                                self.visible_layers.insert(detailed_layer);
-                                            }
-                                        }
-                                        for polygon in &port.polygons {
-                                            let detailed_layer = format!("{}.PIN", polygon.layer);
-                                            self.all_layers.insert(detailed_layer.clone());
-                                            if pin.use_type == "POWER" || pin.use_type == "GROUND" {
-                                                self.visible_layers.insert(detailed_layer);
-                                            }
-                                        }
-                                    }
-                                }
-                                for obs in &macro_def.obs {
-                                    for rect in &obs.rects {
-                                        let detailed_layer = format!("{}.OBS", rect.layer);
-                                        self.all_layers.insert(detailed_layer);
-                                    }
-                                    for polygon in &obs.polygons {
-                                        let detailed_layer = format!("{}.OBS", polygon.layer);
-                                        self.all_layers.insert(detailed_layer);
-                                    }
-                                }
-                            }
+                        self.discover_layer("OUTLINE".to_string(), true);
+                        self.discover_layer("LABEL".to_string(), self.show_pin_text);
+                        let lef_files = std::mem::take(&mut self.lef_files);
+                        for macro_def in lef_files.iter().flat_map(|f| &f.data.macros) {
+                            self.register_macro_layers(macro_def);
                         }
+                        self.lef_files = lef_files;
                     }
+                    self.check_library_layers();
+                    self.rebuild_footprint_families();
 
                     // If in DEF mode, rebuild component mapping after removing LEF
                     if self.def_mode {
@@ -3227,11 +7394,20 @@ impl LefDefViewer {
                 // Show DEF mode statistics
                 if self.def_mode {
                     if let Some(ref def) = self.def_data {
-                        let total_components = def.components.len();
+                        // Physical-only cells drop out of the statistics when hidden
+                        let counted = |c: &&crate::def::DefComponent| {
+                            !self.hide_physical_only
+                                || !self.physical_only_cells.contains(&c.macro_name)
+                        };
+                        let total_components = def.components.iter().filter(counted).count();
+                        let physical_instances = def.components.iter()
+                            .filter(|c| self.physical_only_cells.contains(&c.macro_name))
+                            .count();
                         let missing_unique = self.missing_cells.len();
 
                         // Count how many component instances use missing cells
                         let missing_instances = def.components.iter()
+                            .filter(counted)
                             .filter(|c| self.missing_cells.contains(&c.macro_name))
                             .count();
                         let matched_instances = total_components.saturating_sub(missing_instances);
@@ -3242,6 +7418,20 @@ impl LefDefViewer {
                         );
                         ui.label(format!("Components: {}", total_components));
                         ui.label(format!("Matched: {}", matched_instances));
+                        if self.hide_physical_only {
+                            ui.label(format!("Physical-only: {physical_instances} (hidden)"));
+                        } else {
+                            ui.label(format!("Physical-only: {physical_instances}"));
+                        }
+                        if let Some(utilization) = self.def_utilization(def) {
+                            if !self.hide_physical_only {
+                                ui.label(format!("Utilization: {:.1}%", utilization.total * 100.0));
+                            }
+                            ui.label(format!("Logical utilization: {:.1}%", utilization.logical * 100.0));
+                            if utilization.cover_excluded > 0 {
+                                ui.label(format!("COVER excluded: {}", utilization.cover_excluded));
+                            }
+                        }
 
                         if missing_unique > 0 {
                             ui.colored_label(
@@ -3274,15 +7464,43 @@ impl LefDefViewer {
             // Zoom controls
             ui.horizontal(|ui| {
                 ui.label("Zoom:");
+                let mut camera = self.camera();
                 if ui.button("-").clicked() {
-                    self.zoom = (self.zoom * 0.8).max(0.01);
+                    camera.set_zoom(camera.zoom * 0.8);
+                }
+                let mut zoom = camera.zoom;
+                if ui
+                    .add(egui::Slider::new(&mut zoom, MIN_ZOOM..=MAX_ZOOM).logarithmic(true))
+                    .changed()
+                {
+                    camera.set_zoom(zoom);
                 }
-                ui.add(egui::Slider::new(&mut self.zoom, 0.01..=1000.0).logarithmic(true));
                 if ui.button("+").clicked() {
-                    self.zoom = (self.zoom * 1.25).min(1000.0);
+                    camera.set_zoom(camera.zoom * 1.25);
                 }
+                self.set_camera(camera);
             });
 
+            if self.def_data.is_some() {
+                ui.horizontal(|ui| {
+                    ui.label("View:");
+                    if ui
+                        .selectable_label(!self.def_mode, "LEF view")
+                        .on_hover_text("Library cells, at the camera last used for them")
+                        .clicked()
+                    {
+                        self.set_def_mode(false);
+                    }
+                    if ui
+                        .selectable_label(self.def_mode, "DEF view")
+                        .on_hover_text("The placed design, at the camera last used for it")
+                        .clicked()
+                    {
+                        self.set_def_mode(true);
+                    }
+                });
+            }
+
             ui.horizontal(|ui| {
                 if ui.button("Fit to View").clicked() {
                     self.fit_to_view_requested = true;
@@ -3312,6 +7530,25 @@ impl LefDefViewer {
 
                 ui.separator();
 
+                ui.checkbox(&mut self.def_edit_mode, "Edit Mode (Cell Swaps, Moves)")
+                    .on_hover_text(
+                        "Swap inspected components to another cell of the same footprint \
+                         family, or drag unlocked components to move them",
+                    );
+                if !self.eco_log.is_empty() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!(
+                            "Uncommitted edits: {}",
+                            self.eco_log.instances().len()
+                        ));
+                        if ui.small_button("Review").clicked() {
+                            self.edits_panel.visible = true;
+                        }
+                    });
+                }
+
+                ui.separator();
+
                 if let Some(ref def) = self.def_data {
                     ui.label(format!("Total Components: {}", def.components.len()));
                     ui.label(format!("Pins: {}", def.pins.len()));
@@ -3319,6 +7556,14 @@ impl LefDefViewer {
                 }
             } else if !self.lef_files.is_empty() {
                 ui.heading("LEF Macros (Cells)");
+                let has_density = self
+                    .lef_files
+                    .iter()
+                    .any(|f| f.data.macros.iter().any(|m| !m.density.is_empty()));
+                if has_density {
+                    ui.checkbox(&mut self.show_density, "Show Density Windows")
+                        .on_hover_text("Shade the DENSITY windows of the macros by metal density");
+                }
                 ui.label("Select cells to display:");
 
                 // Add search/filter box
@@ -3345,29 +7590,82 @@ impl LefDefViewer {
                     .iter()
                     .copied()
                     .filter(|(_, macro_def)| {
-                        if self.macro_filter.is_empty() {
-                            true
-                        } else {
-                            macro_def.name.to_lowercase().contains(&self.macro_filter.to_lowercase())
-                        }
+                        identifier::matches_query(&macro_def.name, &self.macro_filter)
                     })
                     .collect();
 
                 ui.label(format!("Showing {} of {} macros", filtered_macros.len(), all_macros.len()));
 
+                if !self.footprint_families.is_empty() {
+                    ui.collapsing(
+                        format!("Footprint Families ({})", self.footprint_families.len()),
+                        |ui| {
+                            for (family_idx, family) in self.footprint_families.iter().enumerate() {
+                                ui.push_id(format!("footprint_family_{family_idx}"), |ui| {
+                                    ui.collapsing(
+                                        format!("{} ({} cells)", family.label(), family.members.len()),
+                                        |ui| {
+                                            for member in &family.members {
+                                                let mut is_selected = self.selected_cells.contains(member);
+                                                if ui.checkbox(&mut is_selected, member).clicked() {
+                                                    if is_selected {
+                                                        self.selected_cells.insert(member.clone());
+                                                    } else {
+                                                        self.selected_cells.remove(member);
+                                                    }
+                                                }
+                                            }
+                                        },
+                                    );
+                                });
+                            }
+                        },
+                    );
+                }
+
+                let mut edit_source_request: Option<(usize, String)> = None;
+                // Layer colors of the hover preview cards
+                let preview_colors: std::collections::HashMap<String, egui::Color32> = self
+                    .all_layers
+                    .iter()
+                    .map(|layer| (layer.clone(), self.get_layer_color(layer)))
+                    .collect();
+                let preview_color = |layer: &str| {
+                    preview_colors
+                        .get(layer)
+                        .copied()
+                        .unwrap_or(egui::Color32::GRAY)
+                };
                 egui::ScrollArea::vertical()
                     .id_salt("lef_macros_list_scroll")
                     .auto_shrink([false, true])
                     .show(ui, |ui| {
                         for (lef_file_idx, macro_def) in filtered_macros {
                             let mut is_selected = self.selected_cells.contains(&macro_def.name);
-                            if ui.checkbox(&mut is_selected, &macro_def.name).clicked() {
+                            let response = ui
+                                .checkbox(&mut is_selected, &macro_def.name)
+                                .on_hover_ui(|ui| {
+                                    macro_thumbnail::preview_card(
+                                        ui,
+                                        macro_def,
+                                        &preview_color,
+                                        self.precompute.thumbnail(&macro_def.name),
+                                        self.precompute.instances_label(&macro_def.name),
+                                    );
+                                });
+                            if response.clicked() {
                                 if is_selected {
                                     self.selected_cells.insert(macro_def.name.clone());
                                 } else {
                                     self.selected_cells.remove(&macro_def.name);
                                 }
                             }
+                            response.context_menu(|ui| {
+                                if ui.button("Copy as LEF").clicked() {
+                                    ui.ctx().copy_text(lef::writer::write_macro(macro_def));
+                                    ui.close_menu();
+                                }
+                            });
 
                             // Use push_id to create unique ID scope for each macro (handles duplicate names from different files)
                             ui.push_id(format!("macro_{}_{}", lef_file_idx, &macro_def.name), |ui| {
@@ -3382,20 +7680,42 @@ impl LefDefViewer {
                                     format!("Details: {}", &macro_def.name)
                                 };
                                 ui.collapsing(details_header, |ui| {
-                                ui.label(format!("Class: {}", macro_def.class));
+                                if macro_def.is_physical_only() {
+                                    ui.label(format!("Class: {} (physical-only)", macro_def.class));
+                                } else {
+                                    ui.label(format!("Class: {}", macro_def.class));
+                                }
                                 ui.label(format!(
                                     "Size: {:.3} x {:.3}",
                                     macro_def.size_x, macro_def.size_y
                                 ));
+                                if ui
+                                    .small_button("Edit Source...")
+                                    .on_hover_text("Edit this macro's LEF text and reparse it")
+                                    .clicked()
+                                {
+                                    edit_source_request = Some((lef_file_idx, macro_def.name.clone()));
+                                }
+                                if ui
+                                    .small_button("Copy as LEF")
+                                    .on_hover_text("Copy the MACRO statement to the clipboard")
+                                    .clicked()
+                                {
+                                    ui.ctx().copy_text(lef::writer::write_macro(macro_def));
+                                }
 
                                 // PINs section
                                 ui.collapsing(format!("PINS ({})", macro_def.pins.len()), |ui| {
+                                    self.lef_pin_filter.render(ui, false);
                                     egui::ScrollArea::vertical()
                                         .id_salt(format!("pins_scroll_{}", macro_def.name))
                                         .auto_shrink([false, true])
                                         .max_height(120.0)
                                         .show(ui, |ui| {
                                             for pin in &macro_def.pins {
+                                                if !self.lef_pin_filter.matches(&pin.direction, &pin.use_type) {
+                                                    continue;
+                                                }
                                                 let pin_id = format!("{}::{}", macro_def.name, pin.name);
                                                 let mut is_selected = self.selected_lef_pins.contains(&pin_id);
 
@@ -3415,6 +7735,12 @@ impl LefDefViewer {
                                                     };
 
                                                     let response = ui.label(pin_label);
+                                                    response.context_menu(|ui| {
+                                                        if ui.button("Copy as LEF").clicked() {
+                                                            ui.ctx().copy_text(lef::writer::write_pin(pin));
+                                                            ui.close_menu();
+                                                        }
+                                                    });
                                                     if response.hovered() {
                                                         let layers: Vec<String> = pin.ports.iter()
                                                             .flat_map(|port| port.rects.iter())
@@ -3435,7 +7761,9 @@ impl LefDefViewer {
 
                                     ui.horizontal(|ui| {
                                         if ui.small_button("Select All PINs").clicked() {
-                                            for pin in &macro_def.pins {
+                                            for pin in macro_def.pins.iter().filter(|pin| {
+                                                self.lef_pin_filter.matches(&pin.direction, &pin.use_type)
+                                            }) {
                                                 let pin_id = format!("{}::{}", macro_def.name, pin.name);
                                                 self.selected_lef_pins.insert(pin_id);
                                             }
@@ -3540,6 +7868,10 @@ impl LefDefViewer {
                         }
                     });
 
+                if let Some((lef_file_idx, macro_name)) = edit_source_request {
+                    self.open_macro_source_editor(lef_file_idx, &macro_name);
+                }
+
                 ui.separator();
                 if ui.button("Select All Cells").clicked() {
                     for lef_file in &self.lef_files {
@@ -3551,17 +7883,44 @@ impl LefDefViewer {
                 if ui.button("Clear Selection").clicked() {
                     self.selected_cells.clear();
                 }
+                if ui
+                    .add_enabled(
+                        !self.selected_cells.is_empty(),
+                        egui::Button::new("Copy Selected as LEF"),
+                    )
+                    .clicked()
+                {
+                    let snippet = self
+                        .lef_files
+                        .iter()
+                        .flat_map(|f| &f.data.macros)
+                        .filter(|m| self.selected_cells.contains(&m.name))
+                        .map(lef::writer::write_macro)
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    ui.ctx().copy_text(snippet);
+                }
             }
 
             // DEF Structure Section
+            let mut isolate = None;
+            let mut zoom_to_nets: Option<Vec<String>> = None;
+            let db_units = self.def_db_units();
             if let Some(def) = &self.def_data {
                 ui.separator();
                 ui.heading("DEF Structure");
 
                 // DESIGN information
                 ui.label("DESIGN");
+                let units = self.units_check();
                 ui.indent("design_info", |ui| {
-                    ui.label("Design loaded successfully");
+                    if def.design.is_empty() {
+                        ui.label("Design loaded successfully");
+                    } else {
+                        ui.label(format!("Name: {}", def.design));
+                    }
+                    ui.label(format!("Units: {} DBU per um", units.db_units()))
+                        .on_hover_text(format!("From {}", units.source()));
                 });
 
                 ui.separator();
@@ -3584,6 +7943,7 @@ impl LefDefViewer {
 
                 // Show DIEAREA details
                 if !def.die_area_points.is_empty() {
+                    let db_units = self.def_db_units();
                     ui.indent("diearea_details", |ui| {
                         if def.die_area_points.len() == 2 {
                             let p1 = &def.die_area_points[0];
@@ -3592,18 +7952,18 @@ impl LefDefViewer {
                             let height = (p2.1 - p1.1).abs();
                             ui.label(format!(
                                 "  Size: {:.1} x {:.1} um",
-                                width / 1000.0,
-                                height / 1000.0
+                                width / db_units,
+                                height / db_units
                             ));
                             ui.label(format!(
                                 "  Bottom-left: ({:.1}, {:.1})",
-                                p1.0 / 1000.0,
-                                p1.1 / 1000.0
+                                p1.0 / db_units,
+                                p1.1 / db_units
                             ));
                             ui.label(format!(
                                 "  Top-right: ({:.1}, {:.1})",
-                                p2.0 / 1000.0,
-                                p2.1 / 1000.0
+                                p2.0 / db_units,
+                                p2.1 / db_units
                             ));
                         } else {
                             ui.label("  Custom polygon shape");
@@ -3633,10 +7993,10 @@ impl LefDefViewer {
 
                             ui.label(format!(
                                 "  Bounds: ({:.1}, {:.1}) to ({:.1}, {:.1})",
-                                min_x / 1000.0,
-                                min_y / 1000.0,
-                                max_x / 1000.0,
-                                max_y / 1000.0
+                                min_x / db_units,
+                                min_y / db_units,
+                                max_x / db_units,
+                                max_y / db_units
                             ));
                         }
                     });
@@ -3646,35 +8006,169 @@ impl LefDefViewer {
 
                 // COMPONENTS section
                 egui::CollapsingHeader::new(format!("COMP COMPONENTS ({})", def.components.len()))
+                    .id_salt("def_components")
                     .default_open(true)
                     .show(ui, |ui| {
                         ui.horizontal(|ui| {
                             ui.checkbox(&mut self.show_components, "Show Components");
                             ui.label(format!("Total: {}", def.components.len()));
                         });
+                        ui.checkbox(&mut self.hide_physical_only, "Hide Physical-Only Cells")
+                            .on_hover_text("Fillers, tap cells, decaps and other cells without logical function");
+                        ui.checkbox(&mut self.include_cover_in_stats, "Count COVER Cells in Utilization")
+                            .on_hover_text("Bumps and pad fillers placed with COVER status");
+
+                        // Components grouped by EEQ master
+                        let mut eeq_groups: std::collections::BTreeMap<&str, Vec<&str>> =
+                            std::collections::BTreeMap::new();
+                        for component in &def.components {
+                            if let Some(eeq) = &component.eeq {
+                                eeq_groups.entry(eeq).or_default().push(&component.name);
+                            }
+                        }
+                        if !eeq_groups.is_empty() {
+                            ui.collapsing(format!("EEQ Masters ({})", eeq_groups.len()), |ui| {
+                                for (eeq, names) in &eeq_groups {
+                                    if ui
+                                        .link(format!("{eeq} ({})", names.len()))
+                                        .on_hover_text("Click to select these components")
+                                        .clicked()
+                                    {
+                                        self.selected_components.clear();
+                                        self.selected_components
+                                            .extend(names.iter().map(|n| n.to_string()));
+                                    }
+                                }
+                            });
+                        }
+
+                        if !self.instance_arrays.is_empty() {
+                            ui.checkbox(&mut self.collapse_arrays, "Collapse Instance Arrays")
+                                .on_hover_text("Draw an array as one block once its cells get too small to tell apart");
+                            ui.collapsing(format!("Instance Arrays ({})", self.instance_arrays.len()), |ui| {
+                                let macro_sizes: HashMap<&str, (f64, f64)> = self
+                                    .lef_files
+                                    .iter()
+                                    .flat_map(|f| &f.data.macros)
+                                    .map(|m| (m.name.as_str(), (m.size_x, m.size_y)))
+                                    .collect();
+                                for (array_idx, array) in self.instance_arrays.iter().enumerate() {
+                                    egui::CollapsingHeader::new(format!(
+                                        "{} {} x {}",
+                                        identifier::display_name(&array.name, def.bus_bit_chars),
+                                        array.rows,
+                                        array.cols
+                                    ))
+                                    .id_salt(("instance_array", array_idx))
+                                    .show(ui, |ui| {
+                                        let mut orientations: BTreeMap<&str, usize> = BTreeMap::new();
+                                        for &member in &array.members {
+                                            if let Some(placement) = &def.components[member].placement {
+                                                *orientations.entry(placement.orientation.as_str()).or_default() += 1;
+                                            }
+                                        }
+                                        let pitch = (array.pitch.0 / db_units, array.pitch.1 / db_units);
+                                        egui::Grid::new(("instance_array_grid", array_idx))
+                                            .num_columns(2)
+                                            .show(ui, |ui| {
+                                                ui.label("Macro");
+                                                ui.monospace(&array.macro_name);
+                                                ui.end_row();
+                                                ui.label("Instances");
+                                                ui.monospace(format!("{} ({} rows x {} columns)", array.members.len(), array.rows, array.cols));
+                                                ui.end_row();
+                                                ui.label("Pitch");
+                                                ui.monospace(format!("{:.3} x {:.3} um", pitch.0, pitch.1));
+                                                ui.end_row();
+                                                if let Some(&(width, height)) = macro_sizes.get(array.macro_name.as_str()) {
+                                                    let size = (
+                                                        (array.cols - 1) as f64 * pitch.0 + width,
+                                                        (array.rows - 1) as f64 * pitch.1 + height,
+                                                    );
+                                                    ui.label("Size");
+                                                    ui.monospace(format!("{:.3} x {:.3} um", size.0, size.1));
+                                                    ui.end_row();
+                                                    ui.label("Cell area");
+                                                    ui.monospace(format!("{:.3} um²", array.members.len() as f64 * width * height));
+                                                    ui.end_row();
+                                                }
+                                                ui.label("Origin");
+                                                ui.monospace(format!("({:.3}, {:.3}) um", array.origin.0 / db_units, array.origin.1 / db_units));
+                                                ui.end_row();
+                                                ui.label("Orientations");
+                                                ui.monospace(
+                                                    orientations
+                                                        .iter()
+                                                        .map(|(orientation, count)| format!("{orientation} {count}"))
+                                                        .collect::<Vec<_>>()
+                                                        .join(", "),
+                                                );
+                                                ui.end_row();
+                                            });
+                                        if ui.button("Select Members").clicked() {
+                                            self.selected_components.clear();
+                                            self.selected_components.extend(
+                                                array.members.iter().map(|&m| def.components[m].name.clone()),
+                                            );
+                                        }
+                                    });
+                                }
+                            });
+                        }
 
                         if !def.components.is_empty() {
                             ui.separator();
                             ui.horizontal(|ui| {
                                 if ui.button("Select All").clicked() {
-                                    for component in &def.components {
-                                        self.selected_components.insert(component.name.clone());
+                                    // Hidden components cannot be picked
+                                    for (idx, component) in def.components.iter().enumerate() {
+                                        if !self.component_flags.is_hidden(idx) {
+                                            self.selected_components.insert(component.name.clone());
+                                        }
                                     }
                                 }
                                 if ui.button("Clear Selection").clicked() {
                                     self.selected_components.clear();
                                 }
+                                if ui
+                                    .add_enabled(
+                                        !self.selected_components.is_empty(),
+                                        egui::Button::new("Copy as DEF"),
+                                    )
+                                    .on_hover_text("Copy the selected COMPONENTS statements")
+                                    .clicked()
+                                {
+                                    let snippet: String = def
+                                        .components
+                                        .iter()
+                                        .filter(|c| self.selected_components.contains(&c.name))
+                                        .map(def::writer::write_component)
+                                        .collect();
+                                    ui.ctx().copy_text(snippet);
+                                }
                             });
+                            Self::render_flag_summary(ui, &mut self.component_flags);
 
                             egui::ScrollArea::vertical()
                                 .auto_shrink([false, true])
                                 .max_height(200.0)
                                 .show(ui, |ui| {
-                                    for component in &def.components {
+                                    for (component_idx, component) in def.components.iter().enumerate() {
+                                        // Array members are listed with their array
+                                        if self.collapse_arrays
+                                            && self.component_array.get(component_idx).is_some_and(Option::is_some)
+                                        {
+                                            continue;
+                                        }
                                         let mut is_selected =
                                             self.selected_components.contains(&component.name);
-                                        let response =
-                                            ui.checkbox(&mut is_selected, &component.name);
+                                        let response = ui.horizontal(|ui| {
+                                            self.component_flags.render_toggles(ui, component_idx);
+                                            ui.add_enabled(
+                                                !self.component_flags.is_hidden(component_idx),
+                                                egui::Checkbox::new(&mut is_selected, identifier::display_name(&component.name, def.bus_bit_chars)),
+                                            )
+                                        }).inner;
                                         if response.clicked() {
                                             if is_selected {
                                                 self.selected_components
@@ -3683,6 +8177,20 @@ impl LefDefViewer {
                                                 self.selected_components.remove(&component.name);
                                             }
                                         }
+                                        if response.double_clicked() {
+                                            self.inspected_component =
+                                                Some(component.name.clone());
+                                        }
+                                        response.context_menu(|ui| {
+                                            if ui.button("Copy as DEF").clicked() {
+                                                ui.ctx().copy_text(def::writer::write_component(component));
+                                                ui.close_menu();
+                                            }
+                                            if ui.button("Open in Isolation").clicked() {
+                                                isolate = Some(component.name.clone());
+                                                ui.close_menu();
+                                            }
+                                        });
 
                                         // Show component details on hover
                                         if response.hovered() {
@@ -3696,11 +8204,44 @@ impl LefDefViewer {
                                             } else {
                                                 "no placement".to_string()
                                             };
+                                            // Macro details from the LEF the instance resolves to
+                                            let macro_name = self
+                                                .component_macro_map
+                                                .get(&component.name)
+                                                .unwrap_or(&component.macro_name);
+                                            let macro_info = self
+                                                .lef_files
+                                                .iter()
+                                                .find_map(|f| {
+                                                    f.data
+                                                        .macros
+                                                        .iter()
+                                                        .find(|m| &m.name == macro_name)
+                                                        .map(|m| (f, m))
+                                                })
+                                                .map_or_else(
+                                                    || "not found in any loaded LEF".to_string(),
+                                                    |(lef_file, macro_def)| {
+                                                        format!(
+                                                            "{} {:.3} x {:.3} um ({:.3} um²), {} pins\n  from {}",
+                                                            macro_def.class,
+                                                            macro_def.size_x,
+                                                            macro_def.size_y,
+                                                            macro_def.size_x * macro_def.size_y,
+                                                            macro_def.pins.len(),
+                                                            std::path::Path::new(&lef_file.path)
+                                                                .file_name()
+                                                                .and_then(|n| n.to_str())
+                                                                .unwrap_or(&lef_file.path)
+                                                        )
+                                                    },
+                                                );
                                             response.on_hover_text(format!(
-                                                "  {} ({}): {}",
+                                                "  {} ({}): {}\n  {}\nDouble-click to inspect",
                                                 component.name,
                                                 component.macro_name,
-                                                placement_info
+                                                placement_info,
+                                                macro_info
                                             ));
                                         }
                                     }
@@ -3710,8 +8251,130 @@ impl LefDefViewer {
 
                 ui.separator();
 
+                // REGIONS and GROUPS sections
+                if !def.regions.is_empty() || !def.groups.is_empty() {
+                    egui::CollapsingHeader::new(format!(
+                        "REGIONS ({}) / GROUPS ({})",
+                        def.regions.len(),
+                        def.groups.len()
+                    ))
+                    .id_salt("def_regions_groups")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        ui.checkbox(&mut self.show_regions, "Show Regions");
+                        for region in &def.regions {
+                            ui.label(format!(
+                                "  {} {}, {} rects",
+                                region.name,
+                                region.region_type.as_deref().unwrap_or("(no type)"),
+                                region.rects.len()
+                            ));
+                        }
+                        if !def.groups.is_empty() {
+                            ui.separator();
+                            ui.label("Click a group to highlight its components:");
+                        }
+                        for group in &def.groups {
+                            let members: Vec<&str> = def
+                                .components
+                                .iter()
+                                .filter(|c| group.contains(&c.name))
+                                .map(|c| c.name.as_str())
+                                .collect();
+                            let highlighted =
+                                self.highlighted_group.as_deref() == Some(group.name.as_str());
+                            let label = match &group.region {
+                                Some(region) => format!(
+                                    "{} in {region} ({} components)",
+                                    group.name,
+                                    members.len()
+                                ),
+                                None => format!("{} ({} components)", group.name, members.len()),
+                            };
+                            let response = ui
+                                .selectable_label(highlighted, label)
+                                .on_hover_text(group.patterns.join(" "));
+                            if response.clicked() {
+                                self.highlighted_group =
+                                    (!highlighted).then(|| group.name.clone());
+                            }
+                            response.context_menu(|ui| {
+                                if ui.button("Select Components").clicked() {
+                                    self.selected_components =
+                                        members.iter().map(|m| m.to_string()).collect();
+                                    ui.close_menu();
+                                }
+                            });
+                        }
+                    });
+
+                    ui.separator();
+                }
+
+                // ROWS section
+                if !def.rows.is_empty() {
+                    egui::CollapsingHeader::new(format!("ROWS ({})", def.rows.len()))
+                        .id_salt("def_rows")
+                        .default_open(false)
+                        .show(ui, |ui| {
+                            ui.checkbox(&mut self.show_rows, "Show Rows");
+                            let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+                            for row in &def.rows {
+                                *counts.entry(row.macro_name.as_str()).or_default() += 1;
+                            }
+                            for (name, count) in counts {
+                                let site = self
+                                    .lef_files
+                                    .iter()
+                                    .flat_map(|f| &f.data.sites)
+                                    .find(|site| site.name == name);
+                                let label = match site {
+                                    Some(site) => format!(
+                                        "  {name}: {count} rows, site {:.3} x {:.3} um",
+                                        site.size_x, site.size_y
+                                    ),
+                                    None => format!("  {name}: {count} rows, SITE not in LEF"),
+                                };
+                                ui.label(label);
+                            }
+                        });
+
+                    ui.separator();
+                }
+
+                // FILLS and SCANCHAINS sections
+                if !def.fills.is_empty() || !def.scan_chains.is_empty() {
+                    egui::CollapsingHeader::new(format!(
+                        "FILLS ({}) / SCANCHAINS ({})",
+                        def.fills.len(),
+                        def.scan_chains.len()
+                    ))
+                    .id_salt("def_fills_scan_chains")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        ui.checkbox(&mut self.show_fills, "Show Fills");
+                        ui.checkbox(&mut self.show_scan_chains, "Show Scan Chain Order");
+                        for chain in &def.scan_chains {
+                            let label = match &chain.partition {
+                                Some(partition) => format!(
+                                    "  {} [{partition}], {} cells",
+                                    chain.name,
+                                    chain.sequence().len()
+                                ),
+                                None => {
+                                    format!("  {}, {} cells", chain.name, chain.sequence().len())
+                                }
+                            };
+                            ui.label(label);
+                        }
+                    });
+
+                    ui.separator();
+                }
+
                 // PINS section
                 egui::CollapsingHeader::new(format!("PINS ({})", def.pins.len()))
+                    .id_salt("def_pins")
                     .default_open(true)
                     .show(ui, |ui| {
                         ui.horizontal(|ui| {
@@ -3721,14 +8384,219 @@ impl LefDefViewer {
 
                         if !def.pins.is_empty() {
                             ui.separator();
+                            self.def_pin_filter.render(ui, true);
                             ui.horizontal(|ui| {
                                 if ui.button("Select All").clicked() {
-                                    for pin in &def.pins {
+                                    for (_, pin) in def.pins.iter().enumerate().filter(|(idx, pin)| {
+                                        !self.pin_flags.is_hidden(*idx)
+                                            && self.def_pin_filter.matches_def_pin(pin)
+                                    }) {
                                         self.selected_pins.insert(pin.name.clone());
                                     }
                                 }
-                                if ui.button("Clear Selection").clicked() {
-                                    self.selected_pins.clear();
+                                if ui.button("Clear Selection").clicked() {
+                                    self.selected_pins.clear();
+                                }
+                                if ui
+                                    .add_enabled(
+                                        !self.selected_pins.is_empty(),
+                                        egui::Button::new("Copy as DEF"),
+                                    )
+                                    .on_hover_text("Copy the selected PINS statements")
+                                    .clicked()
+                                {
+                                    let snippet: String = def
+                                        .pins
+                                        .iter()
+                                        .filter(|p| self.selected_pins.contains(&p.name))
+                                        .map(def::writer::write_pin)
+                                        .collect();
+                                    ui.ctx().copy_text(snippet);
+                                }
+                            });
+                            Self::render_flag_summary(ui, &mut self.pin_flags);
+
+                            egui::ScrollArea::vertical()
+                                .auto_shrink([false, true])
+                                .max_height(200.0)
+                                .show(ui, |ui| {
+                                    for (pin_idx, pin) in def.pins.iter().enumerate() {
+                                        if !self.def_pin_filter.matches_def_pin(pin) {
+                                            continue;
+                                        }
+                                        let mut is_selected =
+                                            self.selected_pins.contains(&pin.name);
+                                        let response = ui.horizontal(|ui| {
+                                            self.pin_flags.render_toggles(ui, pin_idx);
+                                            ui.add_enabled(
+                                                !self.pin_flags.is_hidden(pin_idx),
+                                                egui::Checkbox::new(&mut is_selected, identifier::display_name(&pin.name, def.bus_bit_chars)),
+                                            )
+                                        }).inner;
+                                        if response.clicked() {
+                                            if is_selected {
+                                                self.selected_pins.insert(pin.name.clone());
+                                            } else {
+                                                self.selected_pins.remove(&pin.name);
+                                            }
+                                        }
+                                        response.context_menu(|ui| {
+                                            if ui.button("Copy as DEF").clicked() {
+                                                ui.ctx().copy_text(def::writer::write_pin(pin));
+                                                ui.close_menu();
+                                            }
+                                        });
+
+                                        // Show pin details on hover
+                                        if response.hovered() {
+                                            response.on_hover_text(format!(
+                                                "  {} {} {} {} at ({:.1}, {:.1})",
+                                                pin.direction,
+                                                pin.use_type,
+                                                pin.net,
+                                                pin.placement_status(),
+                                                pin.x,
+                                                pin.y
+                                            ));
+                                        }
+                                    }
+                                });
+                        }
+                    });
+
+                ui.separator();
+
+                // SPECIALNETS section (power/ground networks)
+                egui::CollapsingHeader::new(format!("SPECIALNETS ({})", def.special_nets.len()))
+                    .id_salt("def_special_nets")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.show_special_nets, "Show Special Nets");
+                            ui.label(format!("Total: {}", def.special_nets.len()));
+                        });
+
+                        if !def.special_nets.is_empty() {
+                            // One toggle per USE class, partially checked when some nets are hidden
+                            ui.horizontal(|ui| {
+                                for use_type in ["POWER", "GROUND", "OTHER"] {
+                                    let nets: Vec<&String> = def
+                                        .special_nets
+                                        .iter()
+                                        .filter(|n| Self::special_net_class(n) == use_type)
+                                        .map(|n| &n.name)
+                                        .collect();
+                                    if nets.is_empty() {
+                                        continue;
+                                    }
+                                    let shown = nets
+                                        .iter()
+                                        .filter(|n| !self.hidden_special_nets.contains(**n))
+                                        .count();
+                                    let mut all_shown = shown == nets.len();
+                                    let label = format!("{use_type} ({})", nets.len());
+                                    let checkbox = egui::Checkbox::new(&mut all_shown, label)
+                                        .indeterminate(shown > 0 && shown < nets.len());
+                                    if ui.add(checkbox).clicked() {
+                                        for net in nets {
+                                            if all_shown {
+                                                self.hidden_special_nets.remove(net);
+                                            } else {
+                                                self.hidden_special_nets.insert(net.clone());
+                                            }
+                                        }
+                                    }
+                                }
+                            });
+
+                            ui.separator();
+                            ui.label("Power/Ground networks:");
+                            egui::ScrollArea::vertical()
+                                .auto_shrink([false, true])
+                                .max_height(150.0)
+                                .show(ui, |ui| {
+                                    for snet in &def.special_nets {
+                                        let use_type = snet.use_type.as_deref().unwrap_or("UNKNOWN");
+                                        let route_count = snet.routes.len() + snet.rects.len();
+                                        let color = Self::special_net_color(snet.use_type.as_deref());
+
+                                        ui.horizontal(|ui| {
+                                            // Color indicator
+                                            let (rect, _) = ui.allocate_exact_size(
+                                                egui::Vec2::splat(12.0),
+                                                egui::Sense::hover(),
+                                            );
+                                            ui.painter().rect_filled(rect, 2.0, color);
+
+                                            let mut visible =
+                                                !self.hidden_special_nets.contains(&snet.name);
+                                            let label = format!(
+                                                "{} ({}) - {} routes",
+                                                snet.name, use_type, route_count
+                                            );
+                                            if ui.checkbox(&mut visible, label).changed() {
+                                                if visible {
+                                                    self.hidden_special_nets.remove(&snet.name);
+                                                } else {
+                                                    self.hidden_special_nets.insert(snet.name.clone());
+                                                }
+                                            }
+                                        });
+                                    }
+                                });
+                        }
+                    });
+
+                ui.separator();
+
+                // NETS section (signal networks)
+                egui::CollapsingHeader::new(format!("NETS ({})", def.nets.len()))
+                    .id_salt("def_nets")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.show_nets, "Show Nets");
+                            ui.label(format!("Total: {}", def.nets.len()));
+                        });
+                        if def.nets.iter().any(|net| !net.pattern.is_empty() || net.shielded) {
+                            ui.label("Routing pattern:");
+                            self.net_filter.render(ui);
+                        }
+                        ui.checkbox(&mut self.probe_selected_nets, "Highlight Selected Nets")
+                            .on_hover_text("Mark the instances and pins of the selected nets and dim the rest of the design");
+                        ui.checkbox(&mut self.show_flight_lines, "Flight Lines for Selected Nets")
+                            .on_hover_text("Airwires between the pins, arrowheads show the signal direction");
+                        ui.add_enabled_ui(self.show_flight_lines, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.radio_value(&mut self.flight_line_style, FlightLineStyle::Star, "Star")
+                                    .on_hover_text("From the driver to every other pin");
+                                ui.radio_value(&mut self.flight_line_style, FlightLineStyle::SpanningTree, "Spanning Tree")
+                                    .on_hover_text("Shortest tree through all pins, closer to the eventual wiring");
+                            });
+                            ui.checkbox(&mut self.flight_lines_unrouted_only, "Only Unrouted Nets")
+                                .on_hover_text("Skip nets that already have wiring");
+                        });
+
+                        if !def.nets.is_empty() {
+                            ui.separator();
+                            ui.horizontal(|ui| {
+                                if ui.button("Select All").clicked() {
+                                    for net in &def.nets {
+                                        self.selected_nets.insert(net.name.clone());
+                                    }
+                                }
+                                if ui.button("Clear Selection").clicked() {
+                                    self.selected_nets.clear();
+                                }
+                                if ui
+                                    .add_enabled(
+                                        !self.selected_nets.is_empty(),
+                                        egui::Button::new("Zoom to Selected"),
+                                    )
+                                    .on_hover_text("Fit the view to the selected nets")
+                                    .clicked()
+                                {
+                                    zoom_to_nets = Some(self.selected_nets.iter().cloned().collect());
                                 }
                             });
 
@@ -3736,137 +8604,817 @@ impl LefDefViewer {
                                 .auto_shrink([false, true])
                                 .max_height(200.0)
                                 .show(ui, |ui| {
-                                    for pin in &def.pins {
+                                    for net in &def.nets {
                                         let mut is_selected =
-                                            self.selected_pins.contains(&pin.name);
-                                        let response = ui.checkbox(&mut is_selected, &pin.name);
+                                            self.selected_nets.contains(&net.name);
+                                        let response = ui.checkbox(&mut is_selected, identifier::display_name(&net.name, def.bus_bit_chars));
                                         if response.clicked() {
                                             if is_selected {
-                                                self.selected_pins.insert(pin.name.clone());
+                                                self.selected_nets.insert(net.name.clone());
                                             } else {
-                                                self.selected_pins.remove(&pin.name);
+                                                self.selected_nets.remove(&net.name);
                                             }
                                         }
+                                        if response.double_clicked() {
+                                            self.inspected_net = Some(net.name.clone());
+                                        }
+                                        response.context_menu(|ui| {
+                                            if ui.button("Inspect").clicked() {
+                                                self.inspected_net = Some(net.name.clone());
+                                                ui.close_menu();
+                                            }
+                                            if ui.button("Zoom to Net").clicked() {
+                                                self.selected_nets.insert(net.name.clone());
+                                                zoom_to_nets = Some(vec![net.name.clone()]);
+                                                ui.close_menu();
+                                            }
+                                        });
 
-                                        // Show pin details on hover
+                                        // Show net details on hover
                                         if response.hovered() {
                                             response.on_hover_text(format!(
-                                                "  {} {} {} at ({:.1}, {:.1})",
-                                                pin.direction, pin.use_type, pin.net, pin.x, pin.y
+                                                "  {} instances, {} pins",
+                                                net.instances.len(),
+                                                net.pins
                                             ));
                                         }
                                     }
                                 });
                         }
                     });
+            }
+            if let Some(name) = isolate {
+                self.isolate_component(&name);
+            }
+            if let Some(names) = zoom_to_nets {
+                match union_bounds(names.iter().filter_map(|name| self.net_bounds(name))) {
+                    Some(bounds) => self.focus_bounds_requested = Some(bounds),
+                    None => self.error_message = Some("The net has no placed connections".to_string()),
+                }
+            }
+        });
+    }
 
-                ui.separator();
+    /// SITE definitions of the loaded LEF files by name
+    fn sites_by_name(&self) -> HashMap<&str, &crate::lef::LefSite> {
+        let mut sites = HashMap::new();
+        for site in self.lef_files.iter().flat_map(|f| &f.data.sites) {
+            sites.entry(site.name.as_str()).or_insert(site);
+        }
+        sites
+    }
 
-                // SPECIALNETS section (power/ground networks)
-                egui::CollapsingHeader::new(format!("SPECIALNETS ({})", def.special_nets.len()))
-                    .default_open(true)
-                    .show(ui, |ui| {
-                        ui.horizontal(|ui| {
-                            ui.checkbox(&mut self.show_special_nets, "Show Special Nets");
-                            ui.label(format!("Total: {}", def.special_nets.len()));
-                        });
+    /// Standard-cell rows with their site boundaries
+    ///
+    /// A row is as tall as its LEF SITE and spans its sites at the row
+    /// step. Site boundaries are drawn once they are far enough apart on
+    /// screen. Without the SITE in the loaded LEF the step is used as size.
+    fn paint_rows(
+        &self,
+        painter: &egui::Painter,
+        def: &Def,
+        center: egui::Pos2,
+        die_area_max_y: f64,
+    ) {
+        const MIN_SITE_SPACING: f32 = 6.0;
+        let db_units = self.def_db_units();
+        let to_screen = |x: f64, y: f64| {
+            egui::pos2(
+                center.x + self.pan_x + (x / db_units) as f32 * self.zoom,
+                center.y + self.pan_y + (die_area_max_y - y / db_units) as f32 * self.zoom,
+            )
+        };
+        let sites = self.sites_by_name();
+        let clip_rect = painter.clip_rect();
+        let color = egui::Color32::from_rgb(70, 70, 100);
+        let stroke = egui::Stroke::new(self.dpi_style.stroke(1.0), color);
+        let site_stroke = egui::Stroke::new(self.dpi_style.stroke(0.5), color.gamma_multiply(0.6));
+
+        for row in &def.rows {
+            let site_size = match sites.get(row.macro_name.as_str()) {
+                Some(site) => (site.size_x * db_units, site.size_y * db_units),
+                None => (row.step_x, row.step_y),
+            };
+            let (x0, y0, x1, y1) = row.bounds(site_size);
+            let rect = egui::Rect::from_two_pos(to_screen(x0, y1), to_screen(x1, y0));
+            if !rect.intersects(clip_rect) {
+                continue;
+            }
+            painter.rect_stroke(rect, 0.0, stroke, egui::StrokeKind::Middle);
 
-                        if !def.special_nets.is_empty() {
-                            ui.separator();
-                            ui.label("Power/Ground networks:");
-                            egui::ScrollArea::vertical()
-                                .auto_shrink([false, true])
-                                .max_height(150.0)
-                                .show(ui, |ui| {
-                                    for snet in &def.special_nets {
-                                        let use_type = snet.use_type.as_deref().unwrap_or("UNKNOWN");
-                                        let route_count = snet.routes.len();
-                                        let color = match use_type {
-                                            "POWER" => egui::Color32::from_rgb(255, 50, 50),
-                                            "GROUND" => egui::Color32::from_rgb(50, 50, 255),
-                                            _ => egui::Color32::from_rgb(200, 200, 0),
-                                        };
+            // Boundaries between sites, only the ones inside the clip rect
+            let visible = |start: f32, end: f32, step: f32, count: i32| {
+                let first = ((start / step).floor() as i32).max(1);
+                let last = ((end / step).ceil() as i32).min(count - 1);
+                first..=last
+            };
+            let step_x = (row.step_x / db_units) as f32 * self.zoom;
+            if row.num_x > 1 && step_x >= MIN_SITE_SPACING {
+                let range = clip_rect.left() - rect.left()..clip_rect.right() - rect.left();
+                for i in visible(range.start, range.end, step_x, row.num_x) {
+                    let x = rect.left() + i as f32 * step_x;
+                    painter.line_segment(
+                        [egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())],
+                        site_stroke,
+                    );
+                }
+            }
+            let step_y = (row.step_y / db_units) as f32 * self.zoom;
+            if row.num_y > 1 && step_y >= MIN_SITE_SPACING {
+                let range = rect.bottom() - clip_rect.bottom()..rect.bottom() - clip_rect.top();
+                for j in visible(range.start, range.end, step_y, row.num_y) {
+                    let y = rect.bottom() - j as f32 * step_y;
+                    painter.line_segment(
+                        [egui::pos2(rect.left(), y), egui::pos2(rect.right(), y)],
+                        site_stroke,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Routing tracks in their layer color
+    ///
+    /// Tracks of hidden routing layers are left out. When tracks get closer
+    /// than a few points on screen only every n-th one is drawn, so the
+    /// pattern stays readable when zoomed out.
+    fn paint_tracks(
+        &self,
+        painter: &egui::Painter,
+        def: &Def,
+        center: egui::Pos2,
+        die_area_max_y: f64,
+        die: Option<Bounds>,
+    ) {
+        const MIN_TRACK_SPACING: f32 = 4.0;
+        let db_units = self.def_db_units();
+        let clip_rect = painter.clip_rect();
+        let origin = egui::pos2(
+            center.x + self.pan_x,
+            center.y + self.pan_y + die_area_max_y as f32 * self.zoom,
+        );
+        // Tracks run across the die, or the visible area without one
+        let span = match die {
+            Some((x0, y0, x1, y1)) => egui::Rect::from_two_pos(
+                origin + egui::vec2(x0 as f32, -(y1 as f32)) * self.zoom,
+                origin + egui::vec2(x1 as f32, -(y0 as f32)) * self.zoom,
+            ),
+            None => clip_rect,
+        }
+        .intersect(clip_rect);
+        if !span.is_positive() {
+            return;
+        }
+
+        let tracks = def
+            .tracks_x
+            .iter()
+            .map(|track| (true, track))
+            .chain(def.tracks_y.iter().map(|track| (false, track)));
+        for (vertical, track) in tracks {
+            let layer_key = format!("{}.ROUTE", track.layer);
+            if self.all_layers.contains(&layer_key) && !self.visible_layers.contains(&layer_key) {
+                continue;
+            }
+            let step = (track.step / db_units) as f32 * self.zoom;
+            if track.num <= 0 || step <= 0.0 {
+                continue;
+            }
+            let every = (MIN_TRACK_SPACING / step).ceil().max(1.0) as i32;
+            let stroke = egui::Stroke::new(
+                self.dpi_style.stroke(0.5),
+                self.get_layer_color(&track.layer).gamma_multiply(0.4),
+            );
+            // Screen position of the first track and the visible index range
+            let (first, low, high) = if vertical {
+                let first = origin.x + (track.offset / db_units) as f32 * self.zoom;
+                (first, span.left() - first, span.right() - first)
+            } else {
+                let first = origin.y - (track.offset / db_units) as f32 * self.zoom;
+                (first, first - span.bottom(), first - span.top())
+            };
+            let start = ((low / step).ceil() as i32).max(0);
+            let start = (start + every - 1) / every * every;
+            let end = ((high / step).floor() as i32).min(track.num - 1);
+            for i in (start..=end).step_by(every as usize) {
+                let offset = i as f32 * step;
+                let line = if vertical {
+                    let x = first + offset;
+                    [egui::pos2(x, span.top()), egui::pos2(x, span.bottom())]
+                } else {
+                    let y = first - offset;
+                    [egui::pos2(span.left(), y), egui::pos2(span.right(), y)]
+                };
+                painter.line_segment(line, stroke);
+            }
+        }
+    }
+
+    /// Outlines of the DEF REGIONS with their name and type
+    fn paint_regions(
+        &self,
+        painter: &egui::Painter,
+        def: &Def,
+        center: egui::Pos2,
+        die_area_max_y: f64,
+        texts_to_render: &mut Vec<(egui::Pos2, String, egui::FontId, egui::Color32)>,
+    ) {
+        let db_units = self.def_db_units();
+        let to_screen = |x: f64, y: f64| {
+            egui::pos2(
+                center.x + self.pan_x + (x / db_units) as f32 * self.zoom,
+                center.y + self.pan_y + (die_area_max_y - y / db_units) as f32 * self.zoom,
+            )
+        };
+        let highlighted_region = self
+            .highlighted_group
+            .as_ref()
+            .and_then(|name| def.groups.iter().find(|g| &g.name == name))
+            .and_then(|g| g.region.as_deref());
+
+        for region in &def.regions {
+            let color = if highlighted_region == Some(region.name.as_str()) {
+                REGION_HIGHLIGHT
+            } else if region.region_type.as_deref() == Some("GUIDE") {
+                egui::Color32::from_rgb(80, 200, 255)
+            } else {
+                egui::Color32::from_rgb(255, 150, 40)
+            };
+            for rect in &region.rects {
+                let screen = egui::Rect::from_two_pos(
+                    to_screen(rect.xl, rect.yh),
+                    to_screen(rect.xh, rect.yl),
+                );
+                painter.rect_filled(screen, 0.0, color.gamma_multiply(0.08));
+                painter.rect_stroke(
+                    screen,
+                    0.0,
+                    egui::Stroke::new(self.dpi_style.stroke(1.5), color),
+                    egui::StrokeKind::Middle,
+                );
+            }
+            if let Some(first) = region.rects.first() {
+                let label = match &region.region_type {
+                    Some(region_type) => format!("{} ({region_type})", region.name),
+                    None => region.name.clone(),
+                };
+                let top = to_screen((first.xl + first.xh) / 2.0, first.yh);
+                texts_to_render.push((
+                    top + egui::vec2(0.0, 8.0),
+                    label,
+                    egui::FontId::proportional(self.dpi_style.font_size(11.0)),
+                    color,
+                ));
+            }
+        }
+    }
+
+    /// Metal fill shapes of the DEF FILLS, faint in their layer color
+    fn paint_fills(
+        &self,
+        painter: &egui::Painter,
+        def: &Def,
+        center: egui::Pos2,
+        die_area_max_y: f64,
+    ) {
+        let db_units = self.def_db_units();
+        let to_screen = |x: f64, y: f64| {
+            egui::pos2(
+                center.x + self.pan_x + (x / db_units) as f32 * self.zoom,
+                center.y + self.pan_y + (die_area_max_y - y / db_units) as f32 * self.zoom,
+            )
+        };
+        let clip_rect = painter.clip_rect();
+        for fill in &def.fills {
+            let Some(layer) = &fill.layer else {
+                continue;
+            };
+            if !self.visible_layers.contains(&format!("{layer}.ROUTE")) {
+                continue;
+            }
+            let color = self.get_layer_color(layer).gamma_multiply(0.35);
+            for rect in &fill.rects {
+                let screen = egui::Rect::from_two_pos(
+                    to_screen(rect.xl, rect.yh),
+                    to_screen(rect.xh, rect.yl),
+                );
+                if screen.intersects(clip_rect) {
+                    painter.rect_filled(screen, 0.0, color);
+                }
+            }
+            for polygon in &fill.polygons {
+                let points: Vec<egui::Pos2> = polygon
+                    .points
+                    .iter()
+                    .map(|&(x, y)| to_screen(x, y))
+                    .collect();
+                if egui::Rect::from_points(&points).intersects(clip_rect) {
+                    painter.add(egui::Shape::convex_polygon(
+                        points,
+                        color,
+                        egui::Stroke::NONE,
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Flight lines of the selected nets
+    fn paint_flight_lines(
+        &self,
+        painter: &egui::Painter,
+        def: &Def,
+        center: egui::Pos2,
+        die_area_max_y: f64,
+    ) {
+        if !self.show_flight_lines || self.selected_nets.is_empty() {
+            return;
+        }
+        const MAX_FLIGHT_LINES: usize = 20_000;
+        let db_units = self.def_db_units();
+        let component_pins = ComponentPins::new(
+            def,
+            self.lef_files.iter().flat_map(|f| &f.data.macros),
+            db_units,
+        );
+        let to_screen = |(x, y): (f64, f64)| {
+            egui::pos2(
+                center.x + self.pan_x + x as f32 * self.zoom,
+                center.y + self.pan_y + (die_area_max_y - y) as f32 * self.zoom,
+            )
+        };
+        let color = egui::Color32::from_rgb(255, 213, 79);
+        let stroke = egui::Stroke::new(self.dpi_style.stroke(1.0), color);
+        let lines = def
+            .nets
+            .iter()
+            .filter(|net| self.selected_nets.contains(&net.name))
+            .filter(|net| !self.flight_lines_unrouted_only || net.routes.is_empty())
+            .flat_map(|net| {
+                self.flight_line_style
+                    .flight_lines(&component_pins.net_pins(net))
+            })
+            .take(MAX_FLIGHT_LINES);
+        for line in lines {
+            let (from, to) = (to_screen(line.from), to_screen(line.to));
+            painter.line_segment([from, to], stroke);
+            if line.directed {
+                Self::paint_arrowhead(painter, from, to, color);
+            }
+        }
+    }
+
+    /// Highlight the wiring, instances and top-level pins of the selected
+    /// nets over the dimmed rest of the design
+    fn paint_net_probe(
+        &self,
+        painter: &egui::Painter,
+        def: &Def,
+        center: egui::Pos2,
+        die_area_max_y: f64,
+    ) {
+        let nets: Vec<&def::DefNet> = def
+            .nets
+            .iter()
+            .filter(|net| self.selected_nets.contains(&net.name))
+            .collect();
+        if nets.is_empty() {
+            return;
+        }
+        let db_units = self.def_db_units();
+        let to_screen = |x: f64, y: f64| {
+            egui::pos2(
+                center.x + self.pan_x + x as f32 * self.zoom,
+                center.y + self.pan_y + (die_area_max_y - y) as f32 * self.zoom,
+            )
+        };
+        let clip_rect = painter.clip_rect();
+        painter.rect_filled(clip_rect, 0.0, egui::Color32::from_black_alpha(170));
+        let stroke = egui::Stroke::new(self.dpi_style.stroke(1.5), NET_PROBE_HIGHLIGHT);
+
+        for route in nets.iter().flat_map(|net| &net.routes) {
+            for pair in route.points.windows(2) {
+                painter.line_segment(
+                    [
+                        to_screen(pair[0].x / db_units, pair[0].y / db_units),
+                        to_screen(pair[1].x / db_units, pair[1].y / db_units),
+                    ],
+                    stroke,
+                );
+            }
+        }
+
+        let mut instances = HashSet::new();
+        let mut top_pins = HashSet::new();
+        for net in &nets {
+            for (instance, pin) in net.instances.iter().zip(&net.instance_pins) {
+                if instance == "PIN" {
+                    top_pins.insert(pin.as_str());
+                } else {
+                    instances.insert(instance.as_str());
+                }
+            }
+        }
+
+        // Cells too small to see are still marked
+        const MIN_MARKER_SIZE: f32 = 4.0;
+        let macros = self.macros_by_name();
+        for component in &def.components {
+            if !instances.contains(component.name.as_str()) {
+                continue;
+            }
+            let Some(placement) = &component.placement else {
+                continue;
+            };
+            let location = (placement.x / db_units, placement.y / db_units);
+            let (min_x, min_y, max_x, max_y) = match macros.get(component.macro_name.as_str()) {
+                Some(macro_def) => {
+                    MacroTransform::new(macro_def, location, &placement.orientation).outline_bbox()
+                }
+                None => (location.0, location.1, location.0, location.1),
+            };
+            let mut rect =
+                egui::Rect::from_two_pos(to_screen(min_x, max_y), to_screen(max_x, min_y));
+            if rect.width() < MIN_MARKER_SIZE || rect.height() < MIN_MARKER_SIZE {
+                rect = egui::Rect::from_center_size(
+                    rect.center(),
+                    rect.size().max(egui::Vec2::splat(MIN_MARKER_SIZE)),
+                );
+            }
+            if rect.intersects(clip_rect) {
+                painter.rect_filled(rect, 0.0, NET_PROBE_HIGHLIGHT.gamma_multiply(0.25));
+                painter.rect_stroke(rect, 0.0, stroke, egui::StrokeKind::Middle);
+            }
+        }
+
+        for pin in &def.pins {
+            if !top_pins.contains(pin.name.as_str()) {
+                continue;
+            }
+            let position = to_screen(pin.x / db_units, pin.y / db_units);
+            if clip_rect.contains(position) {
+                painter.circle_stroke(position, self.dpi_style.stroke(5.0), stroke);
+            }
+        }
+    }
+
+    /// One block per collapsed instance array, labeled with its size
+    fn paint_collapsed_arrays(
+        &self,
+        painter: &egui::Painter,
+        def: &Def,
+        center: egui::Pos2,
+        die_area_max_y: f64,
+        collapsed: &[bool],
+        texts_to_render: &mut Vec<(egui::Pos2, String, egui::FontId, egui::Color32)>,
+    ) {
+        let db_units = self.def_db_units();
+        let to_screen = |x: f64, y: f64| {
+            egui::pos2(
+                center.x + self.pan_x + x as f32 * self.zoom,
+                center.y + self.pan_y + (die_area_max_y - y) as f32 * self.zoom,
+            )
+        };
+        let clip_rect = painter.clip_rect();
+        let macros = self.macros_by_name();
+        let color = self.get_layer_color("OUTLINE");
+        for (array, _) in self
+            .instance_arrays
+            .iter()
+            .zip(collapsed)
+            .filter(|(_, collapsed)| **collapsed)
+        {
+            // Corner members give the extent, whatever their orientation
+            let corners = [array.members[0], array.members[array.members.len() - 1]];
+            let extent = corners
+                .iter()
+                .filter_map(|&idx| {
+                    let component = &def.components[idx];
+                    let placement = component.placement.as_ref()?;
+                    let macro_def = macros.get(component.macro_name.as_str())?;
+                    let xform = MacroTransform::new(
+                        macro_def,
+                        (placement.x / db_units, placement.y / db_units),
+                        &placement.orientation,
+                    );
+                    Some(xform.outline_bbox())
+                })
+                .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)));
+            let (min_x, min_y, max_x, max_y) = extent.unwrap_or_else(|| {
+                let (x0, y0, x1, y1) = array.bounds(array.pitch);
+                (x0 / db_units, y0 / db_units, x1 / db_units, y1 / db_units)
+            });
+            if !self.in_isolation(min_x, min_y, max_x, max_y) {
+                continue;
+            }
+            let rect = egui::Rect::from_two_pos(to_screen(min_x, max_y), to_screen(max_x, min_y));
+            if !rect.intersects(clip_rect) {
+                continue;
+            }
+            let selected = array
+                .members
+                .iter()
+                .any(|&idx| self.selected_components.contains(&def.components[idx].name));
+            let stroke_color = if selected {
+                egui::Color32::YELLOW
+            } else {
+                color
+            };
+            painter.rect_filled(rect, 0.0, color.gamma_multiply(0.12));
+            painter.rect_stroke(
+                rect,
+                0.0,
+                egui::Stroke::new(self.dpi_style.stroke(1.5), stroke_color),
+                egui::StrokeKind::Middle,
+            );
+            if rect.width() > 60.0 && rect.height() > 16.0 {
+                texts_to_render.push((
+                    rect.center(),
+                    format!("{} {}\u{00d7}{}", array.macro_name, array.rows, array.cols),
+                    egui::FontId::proportional(self.dpi_style.font_size(11.0)),
+                    stroke_color,
+                ));
+            }
+        }
+    }
+
+    /// Scan chain order as a polyline through the placed scan cells
+    ///
+    /// Each chain gets its own color, starts with a dot and is labeled with
+    /// its name at the first cell. Cells are joined at their outline center,
+    /// or at the placement point when the macro is unknown.
+    fn paint_scan_chains(
+        &self,
+        painter: &egui::Painter,
+        def: &Def,
+        center: egui::Pos2,
+        die_area_max_y: f64,
+        texts_to_render: &mut Vec<(egui::Pos2, String, egui::FontId, egui::Color32)>,
+    ) {
+        if def.scan_chains.is_empty() {
+            return;
+        }
+        let db_units = self.def_db_units();
+        let to_screen = |(x, y): (f64, f64)| {
+            egui::pos2(
+                center.x + self.pan_x + x as f32 * self.zoom,
+                center.y + self.pan_y + (die_area_max_y - y) as f32 * self.zoom,
+            )
+        };
+        let macros = self.macros_by_name();
+        let locations: HashMap<&str, (f64, f64)> = def
+            .components
+            .iter()
+            .filter_map(|component| {
+                let placement = component.placement.as_ref()?;
+                let point = (placement.x / db_units, placement.y / db_units);
+                let location = match macros.get(component.macro_name.as_str()) {
+                    Some(&macro_def) => {
+                        let xform = MacroTransform::new(macro_def, point, &placement.orientation);
+                        let (min_x, min_y, max_x, max_y) = xform.outline_bbox();
+                        ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0)
+                    }
+                    None => point,
+                };
+                Some((component.name.as_str(), location))
+            })
+            .collect();
+
+        for (index, chain) in def.scan_chains.iter().enumerate() {
+            let hue = (index as f32 * 0.618_034).fract();
+            let color: egui::Color32 = egui::ecolor::Hsva::new(hue, 0.8, 1.0, 1.0).into();
+            let points: Vec<egui::Pos2> = chain
+                .sequence()
+                .into_iter()
+                .filter_map(|name| locations.get(name))
+                .map(|&location| to_screen(location))
+                .collect();
+            let Some(&first) = points.first() else {
+                continue;
+            };
+            painter.add(egui::Shape::line(
+                points,
+                egui::Stroke::new(self.dpi_style.stroke(1.5), color),
+            ));
+            painter.circle_filled(first, self.dpi_style.stroke(4.0), color);
+            texts_to_render.push((
+                first + egui::vec2(0.0, -12.0),
+                chain.name.clone(),
+                egui::FontId::proportional(self.dpi_style.font_size(11.0)),
+                color,
+            ));
+        }
+    }
+
+    /// Bounding box of the DEF die area in microns
+    fn die_bounds(&self) -> Option<Bounds> {
+        let def = self.def_data.as_ref()?;
+        let db_units = self.def_db_units();
+        (def.die_area_points.len() >= 2).then(|| {
+            def.die_area_points.iter().fold(
+                (
+                    f64::INFINITY,
+                    f64::INFINITY,
+                    f64::NEG_INFINITY,
+                    f64::NEG_INFINITY,
+                ),
+                |(ax, ay, bx, by), &(x, y)| {
+                    let (x, y) = (x / db_units, y / db_units);
+                    (ax.min(x), ay.min(y), bx.max(x), by.max(y))
+                },
+            )
+        })
+    }
+
+    /// Screen position of the DEF world origin in DEF mode
+    fn def_origin_screen_pos(&self, center: egui::Pos2) -> egui::Pos2 {
+        let db_units = self.def_db_units();
+        let die_area_max_y = self
+            .def_data
+            .as_ref()
+            .filter(|def| !def.die_area_points.is_empty())
+            .map(|def| {
+                def.die_area_points
+                    .iter()
+                    .map(|p| p.1 / db_units)
+                    .fold(f64::NEG_INFINITY, f64::max)
+            })
+            .unwrap_or(0.0);
+        egui::pos2(
+            center.x + self.pan_x,
+            center.y + self.pan_y + die_area_max_y as f32 * self.zoom,
+        )
+    }
+
+    /// Move deferred labels of a transformed source along with its shapes
+    fn transform_source_texts(
+        transform: Option<&ScreenTransform>,
+        texts: &mut [(egui::Pos2, String, egui::FontId, egui::Color32)],
+        smart_texts: &mut [(TextPositioning, String, egui::FontId, egui::Color32)],
+    ) {
+        let Some(transform) = transform else {
+            return;
+        };
+        for (pos, _, _, _) in texts {
+            *pos = transform.apply(*pos);
+        }
+        for (positioning, _, _, _) in smart_texts {
+            positioning.pos = transform.apply(positioning.pos);
+        }
+    }
 
-                                        ui.horizontal(|ui| {
-                                            // Color indicator
-                                            let (rect, _) = ui.allocate_exact_size(
-                                                egui::Vec2::splat(12.0),
-                                                egui::Sense::hover(),
-                                            );
-                                            ui.painter().rect_filled(rect, 2.0, color);
+    /// Small padlock centered at `pos`, marking a FIXED pin
+    fn paint_lock_glyph(&self, painter: &egui::Painter, pos: egui::Pos2) {
+        let stroke = egui::Stroke::new(self.dpi_style.stroke(1.0), egui::Color32::BLACK);
+        let body = egui::Rect::from_center_size(pos + egui::vec2(0.0, 1.5), egui::vec2(7.0, 5.0));
+        // Shackle as a half circle over the body
+        let shackle: Vec<egui::Pos2> = (0..=8)
+            .map(|i| {
+                let angle = std::f32::consts::PI * i as f32 / 8.0;
+                pos + egui::vec2(-2.2 * angle.cos(), -1.0 - 2.5 * angle.sin())
+            })
+            .collect();
+        painter.add(egui::Shape::line(
+            shackle,
+            egui::Stroke::new(self.dpi_style.stroke(1.5), egui::Color32::WHITE),
+        ));
+        painter.rect(
+            body,
+            1.0,
+            egui::Color32::WHITE,
+            stroke,
+            egui::StrokeKind::Outside,
+        );
+    }
 
-                                            ui.label(format!(
-                                                "{} ({}) - {} routes",
-                                                snet.name, use_type, route_count
-                                            ));
-                                        });
-                                    }
-                                });
-                        }
-                    });
+    /// Turn ruler mode on or off
+    fn toggle_ruler(&mut self) {
+        self.ruler.toggle();
+        if self.ruler.active {
+            self.status_bar
+                .hint("Ruler: click two points to measure, Esc clears, R leaves");
+        }
+    }
 
-                ui.separator();
+    /// Ruler point moved onto a nearby shape edge or track, as enabled
+    fn snap_ruler_point(&self, point: (f64, f64)) -> (f64, f64) {
+        let tolerance = (ruler::SNAP_DISTANCE / self.zoom) as f64;
+        let area = (
+            point.0 - tolerance,
+            point.1 - tolerance,
+            point.0 + tolerance,
+            point.1 + tolerance,
+        );
+        let is_visible = |key: &str| self.visible_layers.contains(key);
 
-                // NETS section (signal networks)
-                egui::CollapsingHeader::new(format!("NETS ({})", def.nets.len()))
-                    .default_open(true)
-                    .show(ui, |ui| {
-                        ui.horizontal(|ui| {
-                            ui.checkbox(&mut self.show_nets, "Show Nets");
-                            ui.label(format!("Total: {}", def.nets.len()));
-                        });
+        if self.def_mode {
+            let Some(def) = &self.def_data else {
+                return point;
+            };
+            let db_units = self.def_db_units();
+            let shapes = match (&self.design_index, self.ruler.snap_edges) {
+                (Some(index), true) => {
+                    index.shapes_in(def, db_units, &self.macros_by_name(), area, is_visible)
+                }
+                _ => Vec::new(),
+            };
+            let tracks = if self.ruler.snap_tracks {
+                (&def.tracks_x[..], &def.tracks_y[..])
+            } else {
+                (&[][..], &[][..])
+            };
+            return ruler::snap_point(point, &shapes, tracks, db_units, tolerance);
+        }
 
-                        if !def.nets.is_empty() {
-                            ui.separator();
-                            ui.horizontal(|ui| {
-                                if ui.button("Select All").clicked() {
-                                    for net in &def.nets {
-                                        self.selected_nets.insert(net.name.clone());
-                                    }
-                                }
-                                if ui.button("Clear Selection").clicked() {
-                                    self.selected_nets.clear();
-                                }
-                            });
+        // LEF view: outline and shapes of the macro, relative to ORIGIN
+        let Some((_, macro_def)) = self.shown_lef_macros().next() else {
+            return point;
+        };
+        if !self.ruler.snap_edges {
+            return point;
+        }
+        let (ox, oy) = macro_def.origin;
+        let mut shapes = vec![(-ox, -oy, macro_def.size_x - ox, macro_def.size_y - oy)];
+        let pin_rects = macro_def
+            .pins
+            .iter()
+            .flat_map(|pin| &pin.ports)
+            .flat_map(|port| &port.rects)
+            .map(|rect| (rect, LayerKind::Pin));
+        let obs_rects = macro_def
+            .obs
+            .iter()
+            .flat_map(|obs| &obs.rects)
+            .map(|rect| (rect, LayerKind::Obs));
+        let layer_keys = VisibleLayerKeys::new(&self.visible_layers);
+        shapes.extend(
+            pin_rects
+                .chain(obs_rects)
+                .filter(|(rect, kind)| layer_keys.get(*kind, &rect.layer).is_some())
+                .map(|(rect, _)| (rect.xl, rect.yl, rect.xh, rect.yh)),
+        );
+        ruler::snap_point(point, &shapes, (&[], &[]), 1.0, tolerance)
+    }
 
-                            egui::ScrollArea::vertical()
-                                .auto_shrink([false, true])
-                                .max_height(200.0)
-                                .show(ui, |ui| {
-                                    for net in &def.nets {
-                                        let mut is_selected =
-                                            self.selected_nets.contains(&net.name);
-                                        let response = ui.checkbox(&mut is_selected, &net.name);
-                                        if response.clicked() {
-                                            if is_selected {
-                                                self.selected_nets.insert(net.name.clone());
-                                            } else {
-                                                self.selected_nets.remove(&net.name);
-                                            }
-                                        }
+    /// Dimension line of the ruler with its length and X and Y legs
+    fn paint_ruler(&self, painter: &egui::Painter, center: egui::Pos2) {
+        let cursor = self.cursor_position.map(|p| self.snap_ruler_point(p));
+        let stroke = egui::Stroke::new(self.dpi_style.stroke(1.5), RULER_COLOR);
+        if let Some(pos) = cursor.and_then(|p| self.world_screen_pos(center, p)) {
+            painter.circle_stroke(pos, 4.0, stroke);
+        }
+        let Some((start, end)) = self.ruler.segment(cursor) else {
+            return;
+        };
+        let (Some(a), Some(b), Some(corner)) = (
+            self.world_screen_pos(center, start),
+            self.world_screen_pos(center, end),
+            self.world_screen_pos(center, (end.0, start.1)),
+        ) else {
+            return;
+        };
 
-                                        // Show net details on hover
-                                        if response.hovered() {
-                                            response.on_hover_text(format!(
-                                                "  {} instances, {} pins",
-                                                net.instances.len(),
-                                                net.pins
-                                            ));
-                                        }
-                                    }
-                                });
-                        }
-                    });
-            }
-        });
+        painter.extend(egui::Shape::dashed_line(
+            &[a, corner, b],
+            egui::Stroke::new(self.dpi_style.stroke(1.0), RULER_COLOR.gamma_multiply(0.6)),
+            4.0,
+            3.0,
+        ));
+        painter.line_segment([a, b], stroke);
+        // Ticks across both ends
+        let tick = (b - a).normalized().rot90() * 6.0;
+        for end_pos in [a, b] {
+            painter.line_segment([end_pos - tick, end_pos + tick], stroke);
+        }
+
+        let measurement = Measurement::between(start, end);
+        self.render_text_with_outline(
+            painter,
+            a.lerp(b, 0.5) + egui::vec2(8.0, -8.0),
+            egui::Align2::LEFT_BOTTOM,
+            &format!(
+                "{:.3} um\ndx {:.3}  dy {:.3}",
+                measurement.distance, measurement.dx, measurement.dy
+            ),
+            egui::FontId::monospace(self.dpi_style.font_size(12.0)),
+            egui::Color32::WHITE,
+        );
     }
 
     fn render_visualization(&mut self, ui: &mut egui::Ui) {
+        crate::profile_scope!("render_visualization");
         // First record the remaining available space
         let available_size = ui.available_size();
 
         // Then allocate this entire space at once
-        let (response, painter) = ui.allocate_painter(available_size, egui::Sense::drag());
+        let (response, painter) =
+            ui.allocate_painter(available_size, egui::Sense::click_and_drag());
+        self.canvas_rect = response.rect;
+        self.ensure_design_index();
+        let first_shape = ui
+            .ctx()
+            .graphics_mut(|graphics| graphics.entry(painter.layer_id()).next_idx());
+        self.dpi_style = DpiStyle::new(ui.ctx().pixels_per_point());
 
         // Use the previously recorded `available_size` for fit-to-view
         // Handle fit to view request with frame delay
@@ -3878,48 +9426,216 @@ impl LefDefViewer {
             ui.ctx().request_repaint(); // Continue animation until delay is complete
         }
 
+        let mut camera = self.camera();
+        if camera.recover() {
+            log::warn!("Reset a non-finite camera, fitting the view");
+            self.fit_to_view_requested = true;
+        }
+        self.set_camera(camera);
+
         if self.fit_to_view_requested {
             self.fit_to_view(available_size);
             self.fit_to_view_requested = false;
         }
+        if let Some(bounds) = self.focus_bounds_requested.take() {
+            self.zoom_to_bounds(bounds, available_size, 0.5);
+        }
 
         // Handle F key for fit to view
-        if ui.input(|i| i.key_pressed(egui::Key::F)) {
+        if !ui.ctx().wants_keyboard_input() && ui.input(|i| i.key_pressed(egui::Key::F)) {
             self.fit_to_view(available_size);
         }
+        if !ui.ctx().wants_keyboard_input() && ui.input(|i| i.key_pressed(egui::Key::R)) {
+            self.toggle_ruler();
+        }
 
         // Handle mouse interactions
-        if response.dragged() {
-            let delta = response.drag_delta();
-            self.pan_x += delta.x;
-            self.pan_y += delta.y;
+        let bindings = self.mouse_bindings;
+        let drag_button = [
+            egui::PointerButton::Primary,
+            egui::PointerButton::Middle,
+            egui::PointerButton::Secondary,
+        ]
+        .into_iter()
+        .find(|button| response.dragged_by(*button) || response.drag_stopped_by(*button));
+        if self.def_mode
+            && self.def_edit_mode
+            && !self.ruler.active
+            && response.drag_started_by(egui::PointerButton::Primary)
+        {
+            self.component_drag = ui
+                .input(|i| i.pointer.press_origin())
+                .and_then(|pos| self.start_component_drag(response.rect.center(), pos));
+        }
+        // A component drag takes the primary button from the bindings
+        let drag_button = match self.component_drag.take() {
+            Some(drag) => {
+                let pos = response.interact_pointer_pos();
+                let to = pos.map_or(drag.from, |pos| {
+                    self.component_drag_target(&drag, response.rect.center(), pos)
+                });
+                let cancelled = ui.input(|i| i.key_pressed(egui::Key::Escape));
+                if cancelled {
+                    self.set_dragged_component_location(drag.index, drag.from);
+                } else if response.dragged() {
+                    self.set_dragged_component_location(drag.index, to);
+                    self.component_drag = Some(drag);
+                } else {
+                    self.finish_component_drag(drag, to);
+                }
+                None
+            }
+            None => drag_button,
+        };
+        let mut zoom_box = None;
+        match drag_button.map(|button| bindings.drag_action(button)) {
+            Some(DragAction::Pan) => {
+                let delta = response.drag_delta();
+                self.pan_x += delta.x;
+                self.pan_y += delta.y;
+            }
+            Some(DragAction::ZoomBox) => {
+                let origin = *self.zoom_box_origin.get_or_insert_with(|| {
+                    ui.input(|i| i.pointer.press_origin()).unwrap_or_default()
+                });
+                if let Some(pos) = response.interact_pointer_pos() {
+                    zoom_box = Some(egui::Rect::from_two_pos(origin, pos));
+                }
+            }
+            Some(DragAction::Nothing) | None => {}
+        }
+        if !response.dragged() {
+            if let Some(bounds) = self.zoom_box_origin.take().and(zoom_box.take()) {
+                let center = response.rect.center();
+                let mut camera = self.camera();
+                camera.zoom_to_box(
+                    bounds.min - center,
+                    bounds.max - center,
+                    response.rect.size(),
+                );
+                self.set_camera(camera);
+            }
         }
 
-        // Handle mouse wheel zoom
+        // Handle the mouse wheel
         if let Some(hover_pos) = response.hover_pos() {
-            let scroll_delta = ui.input(|i| i.raw_scroll_delta.y);
+            let (scroll, modifiers) = ui.input(|i| (i.raw_scroll_delta, i.modifiers));
+            // egui reports Shift+wheel as a horizontal scroll
+            let scroll_delta = if scroll.y != 0.0 { scroll.y } else { scroll.x };
             if scroll_delta != 0.0 {
-                let zoom_factor = if scroll_delta > 0.0 { 1.1 } else { 0.9 };
-                let old_zoom = self.zoom;
+                match bindings.wheel_action(modifiers) {
+                    WheelAction::Zoom => {
+                        let zoom_factor = if scroll_delta > 0.0 { 1.1 } else { 0.9 };
+
+                        // Keep the world point under the mouse at the same screen position
+                        let mut camera = self.camera();
+                        camera.zoom_about(hover_pos - response.rect.center(), zoom_factor);
+                        self.set_camera(camera);
+                    }
+                    WheelAction::PanHorizontal => self.pan_x += scroll_delta,
+                    WheelAction::PanVertical => self.pan_y += scroll_delta,
+                }
+            }
+        }
+
+        let image_export = self.image_export.take();
+
+        let rect = response.rect;
+        let center = rect.center();
+        let (texts_to_render, smart_texts_to_render) = self.paint_scene(&painter, rect);
+
+        if let Some(path) = self.label_export_path.take() {
+            let labels = self.placed_labels(center, &texts_to_render, &smart_texts_to_render);
+            match export::export_labels_to_csv(&labels, &path.to_string_lossy()) {
+                Ok(()) => {
+                    self.success_message = Some(format!(
+                        "Exported {} labels to: {}",
+                        labels.len(),
+                        path.display()
+                    ));
+                }
+                Err(e) => self.error_message = Some(format!("Failed to export labels: {e}")),
+            }
+        }
 
-                // Get drawing area center
-                let rect = response.rect;
-                let center = rect.center();
+        // Render all text on top of everything with outline for white text
+        Self::paint_labels(
+            &mut self.label_cache,
+            &painter,
+            self.zoom,
+            &texts_to_render,
+            &smart_texts_to_render,
+        );
 
-                // Convert mouse screen position to world coordinates before zoom
-                let world_x = (hover_pos.x - center.x - self.pan_x) / old_zoom;
-                let world_y = (hover_pos.y - center.y - self.pan_y) / old_zoom;
+        if let Some(bounds) = zoom_box {
+            painter.rect(
+                bounds,
+                0.0,
+                egui::Color32::from_rgba_unmultiplied(255, 255, 255, 20),
+                egui::Stroke::new(self.dpi_style.stroke(1.0), egui::Color32::WHITE),
+                egui::StrokeKind::Inside,
+            );
+        }
 
-                // Update zoom
-                self.zoom = (self.zoom * zoom_factor).clamp(0.01, 1000.0);
+        if self.def_mode {
+            self.paint_placement_diff(&painter, center);
+        }
 
-                // Adjust pan so that the world point under mouse stays at the same screen position
-                self.pan_x = hover_pos.x - center.x - (world_x * self.zoom);
-                self.pan_y = hover_pos.y - center.y - (world_y * self.zoom);
+        self.cursor_position = response
+            .hover_pos()
+            .and_then(|pos| self.cursor_position(center, pos));
+        if self.ruler.active {
+            if let Some(pos) = response
+                .interact_pointer_pos()
+                .filter(|_| response.clicked())
+            {
+                if let Some(point) = self.cursor_position(center, pos) {
+                    let point = self.snap_ruler_point(point);
+                    self.ruler.click(point);
+                }
+            }
+            if !ui.ctx().wants_keyboard_input() && ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                self.ruler.clear();
+            }
+            self.paint_ruler(&painter, center);
+        } else if self.component_drag.is_some() {
+            self.paint_component_drag(&painter, center);
+        } else if !response.dragged() {
+            if let Some(hover_pos) = response.hover_pos() {
+                self.show_hover_tooltip(ui.ctx(), &response, hover_pos);
             }
         }
 
-        let rect = response.rect;
+        if let Some((request, path)) = image_export {
+            self.start_image_export(ui.ctx(), request, path);
+        }
+
+        if let Some(path) = self.svg_export_path.take() {
+            let view = crate::svg_export::capture(
+                ui.ctx(),
+                painter.layer_id(),
+                first_shape,
+                self.canvas_rect,
+            );
+            match export::export_view_to_svg(&view, &path.to_string_lossy()) {
+                Ok(()) => {
+                    self.success_message =
+                        Some(format!("Exported view as SVG to: {}", path.display()));
+                }
+                Err(e) => self.error_message = Some(format!("Failed to export SVG: {e}")),
+            }
+        }
+
+        ui.ctx().request_repaint();
+    }
+
+    /// Paint the design into `rect` at the current camera, returning the
+    /// labels to draw over it
+    fn paint_scene(
+        &mut self,
+        painter: &egui::Painter,
+        rect: egui::Rect,
+    ) -> (Vec<PlainText>, Vec<SmartText>) {
         let center = rect.center();
 
         painter.rect_filled(rect, 0.0, egui::Color32::BLACK);
@@ -3931,15 +9647,40 @@ impl LefDefViewer {
         // Choose rendering mode based on whether DEF is loaded
         if self.def_mode && self.def_data.is_some() {
             // DEF mode: Render DEF components with transformed LEF macros
+            let pivot = self.def_origin_screen_pos(center);
+            let source =
+                SourcePaint::begin(painter, self.def_transform.to_screen(pivot, self.zoom));
+            if let Some(underlay) = &self.underlay {
+                underlay.paint(&source.painter, pivot, self.zoom);
+            }
+            let text_start = (texts_to_render.len(), smart_texts_to_render.len());
             self.render_def_components(
-                &painter,
+                &source.painter,
                 center,
                 &mut texts_to_render,
                 &mut smart_texts_to_render,
             );
+            Self::transform_source_texts(
+                source.transform(),
+                &mut texts_to_render[text_start.0..],
+                &mut smart_texts_to_render[text_start.1..],
+            );
+            source.finish();
         } else {
             // LEF mode: Render LEF macros directly
+            let layer_keys = VisibleLayerKeys::new(&self.visible_layers);
+            let mut scratch = std::mem::take(&mut self.render_scratch);
+            // One read of the mesh cache for the whole view, it is only
+            // written on this thread
+            let mesh_cache = Arc::clone(&self.mesh_cache);
+            let mesh_cache = mesh_cache.read().ok();
+            crate::profile_scope!("paint_lef");
             for lef_file in &self.lef_files {
+                let pivot = egui::pos2(center.x + self.pan_x, center.y + self.pan_y);
+                let source =
+                    SourcePaint::begin(painter, lef_file.transform.to_screen(pivot, self.zoom));
+                let painter = &source.painter;
+                let text_start = texts_to_render.len();
                 for macro_def in &lef_file.data.macros {
                     // Only render selected cells (or all if none selected)
                     if !self.selected_cells.is_empty()
@@ -3947,6 +9688,7 @@ impl LefDefViewer {
                     {
                         continue;
                     }
+                    let macro_def = self.render_macro(macro_def);
 
                     // Calculate macro position - use origin as reference point but don't offset the display
                     // The PIN coordinates are already absolute coordinates within the macro space
@@ -3985,7 +9727,7 @@ impl LefDefViewer {
                         painter.rect_stroke(
                             macro_rect,
                             0.0,
-                            egui::Stroke::new(2.0, outline_color),
+                            egui::Stroke::new(self.dpi_style.stroke(2.0), outline_color),
                             egui::StrokeKind::Middle,
                         );
                     }
@@ -3994,25 +9736,53 @@ impl LefDefViewer {
                     // PIN coordinates are absolute within the macro coordinate system
                     // We apply the same ORIGIN offset to align them with the OUTLINE
                     if should_render_details {
+                        // Meshes of LEF view polygons, in screen-aligned macro coordinates
+                        scratch.mesh_key.clear();
+                        scratch.mesh_key.push_str("lef_");
+                        scratch.mesh_key.push_str(&macro_def.name);
+                        let cached_meshes = mesh_cache
+                            .as_deref()
+                            .and_then(|cache| cache.get(scratch.mesh_key.as_str()));
+                        // Pin polygons of the whole macro, drawn as one mesh
+                        let mut polygon_mesh = egui::epaint::Mesh::default();
+                        scratch.highlights.clear();
+                        let mut next_shape = 0;
                         for pin in &macro_def.pins {
+                            // Shape indices count every pin, shown or not
+                            let first_shape = next_shape;
+                            next_shape += pin
+                                .ports
+                                .iter()
+                                .map(|port| port.rects.len() + port.polygons.len())
+                                .sum::<usize>();
+
                             // Check if this specific pin is selected (if any pins are selected)
-                            let pin_id = format!("{}::{}", macro_def.name, pin.name);
                             if !self.selected_lef_pins.is_empty()
-                                && !self.selected_lef_pins.contains(&pin_id)
+                                && !self
+                                    .selected_lef_pins
+                                    .contains(scratch.selection_key(&macro_def.name, &pin.name))
                             {
                                 continue;
                             }
+                            if !self.lef_pin_filter.matches(&pin.direction, &pin.use_type) {
+                                continue;
+                            }
 
-                            let mut pin_bounds: Option<(f32, f32, f32, f32)> = None; // min_x, min_y, max_x, max_y
+                            let mut pin_bounds = egui::Rect::NOTHING;
                             let mut has_visible_shapes = false;
 
+                            let mut port_first_shape = first_shape;
                             for port in &pin.ports {
+                                let first_polygon = port_first_shape + port.rects.len();
+                                port_first_shape = first_polygon + port.polygons.len();
+
                                 // Render rectangles
                                 for rect_data in &port.rects {
-                                    let detailed_layer = format!("{}.PIN", rect_data.layer);
-                                    if !self.visible_layers.contains(&detailed_layer) {
+                                    let Some(detailed_layer) =
+                                        layer_keys.get(LayerKind::Pin, &rect_data.layer)
+                                    else {
                                         continue;
-                                    }
+                                    };
 
                                     has_visible_shapes = true;
 
@@ -4044,218 +9814,106 @@ impl LefDefViewer {
                                         ),
                                     );
 
-                                    let color = self.get_layer_color(&detailed_layer);
+                                    let color = self.get_layer_color(detailed_layer);
                                     painter.rect_filled(pin_rect, 0.0, color);
 
-                                    // Update pin bounds for text positioning (with Y-flip and ORIGIN offset)
-                                    let rect_min_x = outline_x
-                                        + ((macro_def.origin.0 + rect_data.xl) as f32 * self.zoom);
-                                    let rect_min_y = outline_y
-                                        + ((macro_def.size_y - macro_def.origin.1 - rect_data.yh)
-                                            as f32
-                                            * self.zoom);
-                                    let rect_max_x = outline_x
-                                        + ((macro_def.origin.0 + rect_data.xh) as f32 * self.zoom);
-                                    let rect_max_y = outline_y
-                                        + ((macro_def.size_y - macro_def.origin.1 - rect_data.yl)
-                                            as f32
-                                            * self.zoom);
-
-                                    if let Some((min_x, min_y, max_x, max_y)) = pin_bounds {
-                                        pin_bounds = Some((
-                                            min_x.min(rect_min_x),
-                                            min_y.min(rect_min_y),
-                                            max_x.max(rect_max_x),
-                                            max_y.max(rect_max_y),
-                                        ));
-                                    } else {
-                                        pin_bounds =
-                                            Some((rect_min_x, rect_min_y, rect_max_x, rect_max_y));
-                                    }
+                                    // Update pin bounds for text positioning
+                                    pin_bounds = pin_bounds.union(pin_rect);
                                 }
 
-                                // Group polygons by layer for this specific PORT (within this PIN)
-                                // Boolean operations only apply within the same pin and same layer
-                                let mut layer_polygons: std::collections::HashMap<
-                                    String,
-                                    Vec<&crate::lef::LefPolygon>,
-                                > = std::collections::HashMap::new();
-                                for polygon_data in &port.polygons {
-                                    let detailed_layer = format!("{}.PIN", polygon_data.layer);
-                                    if !self.visible_layers.contains(&detailed_layer) {
-                                        continue;
+                                // Polygons of this PORT in layer z-order to prevent flickering
+                                scratch.polygon_order.clear();
+                                for (offset, polygon_data) in port.polygons.iter().enumerate() {
+                                    if let Some(detailed_layer) =
+                                        layer_keys.get(LayerKind::Pin, &polygon_data.layer)
+                                    {
+                                        scratch
+                                            .polygon_order
+                                            .push((self.get_layer_order(detailed_layer), offset));
                                     }
-                                    layer_polygons
-                                        .entry(detailed_layer.clone())
-                                        .or_default()
-                                        .push(polygon_data);
                                 }
-
-                                // Sort layers by z-order to prevent flickering
-                                let mut sorted_layers: Vec<_> =
-                                    layer_polygons.into_iter().collect();
-                                sorted_layers.sort_by_key(|(layer_name, _)| {
-                                    self.get_layer_order(layer_name)
-                                });
-
-                                // Process each layer's polygons with proper boolean operations
-                                for (layer_name, polygons) in sorted_layers {
+                                // Offsets are unique, so ties keep the LEF order
+                                scratch.polygon_order.sort_unstable();
+
+                                // Draw each polygon independently (LEF only has positive shapes)
+                                for &(_, offset) in &scratch.polygon_order {
+                                    let polygon_data = &port.polygons[offset];
+                                    let Some(layer_name) =
+                                        layer_keys.get(LayerKind::Pin, &polygon_data.layer)
+                                    else {
+                                        continue;
+                                    };
+                                    let shape_index = first_polygon + offset;
                                     has_visible_shapes = true;
-                                    let color = self.get_layer_color(&layer_name);
-
-                                    // Track shape index for cache keys
-                                    let mut poly_shape_index = 0;
-
-                                    // Draw each polygon independently (LEF only has positive shapes)
-                                    for polygon_data in &polygons {
-                                        if polygon_data.points.len() < 3 {
-                                            poly_shape_index += 1;
-                                            continue;
-                                        }
-
-                                        // Generate unique cache key for this polygon
-                                        let cache_key = MeshCacheKey {
-                                            macro_name: format!("lef_{}", macro_def.name),
-                                            shape_type: "PIN".to_string(),
-                                            layer_name: layer_name.clone(),
-                                            shape_index: poly_shape_index,
-                                        };
-
-                                        // Try to get cached mesh
-                                        let cached_mesh_opt = self
-                                            .mesh_cache
-                                            .read()
-                                            .unwrap()
-                                            .get(&cache_key)
-                                            .cloned();
-
-                                        if let Some(cached_mesh) = cached_mesh_opt {
-                                            // Render from cache: transform world coordinates to screen coordinates
-                                            let transformed_vertices: Vec<egui::epaint::Vertex> =
-                                                cached_mesh
-                                                    .vertices
-                                                    .iter()
-                                                    .map(|v| {
-                                                        // Apply zoom and pan to world coordinates
-                                                        // World coordinates already have ORIGIN offset and Y-flip applied
-                                                        let screen_x = outline_x + v.x * self.zoom;
-                                                        let screen_y = outline_y + v.y * self.zoom;
-
-                                                        egui::epaint::Vertex {
-                                                            pos: egui::pos2(screen_x, screen_y),
-                                                            uv: egui::pos2(0.0, 0.0),
-                                                            color: cached_mesh.color,
-                                                        }
-                                                    })
-                                                    .collect();
-
-                                            // Calculate bounds for text positioning (before moving vertices)
-                                            let mut poly_min_x = f32::INFINITY;
-                                            let mut poly_min_y = f32::INFINITY;
-                                            let mut poly_max_x = f32::NEG_INFINITY;
-                                            let mut poly_max_y = f32::NEG_INFINITY;
-
-                                            for v in &transformed_vertices {
-                                                poly_min_x = poly_min_x.min(v.pos.x);
-                                                poly_min_y = poly_min_y.min(v.pos.y);
-                                                poly_max_x = poly_max_x.max(v.pos.x);
-                                                poly_max_y = poly_max_y.max(v.pos.y);
-                                            }
-
-                                            let mesh = egui::epaint::Mesh {
-                                                indices: cached_mesh.indices.clone(),
-                                                vertices: transformed_vertices,
-                                                texture_id: egui::TextureId::default(),
-                                            };
-
-                                            painter.add(egui::Shape::Mesh(Arc::new(mesh)));
-
-                                            // Update pin bounds
-                                            if let Some((min_x, min_y, max_x, max_y)) = pin_bounds {
-                                                pin_bounds = Some((
-                                                    min_x.min(poly_min_x),
-                                                    min_y.min(poly_min_y),
-                                                    max_x.max(poly_max_x),
-                                                    max_y.max(poly_max_y),
-                                                ));
-                                            } else {
-                                                pin_bounds = Some((
-                                                    poly_min_x, poly_min_y, poly_max_x, poly_max_y,
-                                                ));
-                                            }
-                                        } else {
-                                            // Not cached yet: queue for background tessellation
-                                            // Convert to world coordinates (with ORIGIN offset and Y-flip, but no zoom/pan)
-                                            let world_points: Vec<(f64, f64)> = polygon_data
-                                                .points
-                                                .iter()
-                                                .map(|(x, y)| {
-                                                    let world_x = macro_def.origin.0 + *x;
-                                                    let world_y =
-                                                        macro_def.size_y - macro_def.origin.1 - *y;
-                                                    (world_x, world_y)
-                                                })
-                                                .collect();
-
-                                            if let Some(sender) = &self.render_job_sender {
-                                                let job = TessellationJob {
-                                                    cache_key: cache_key.clone(),
-                                                    shape: ShapeData::Polygon {
-                                                        points: world_points.clone(),
-                                                    },
-                                                    color,
-                                                };
-                                                let _ = sender.send(job);
-                                            }
+                                    let color = self.get_layer_color(layer_name);
+                                    if polygon_data.points.len() < 3 {
+                                        continue;
+                                    }
 
-                                            // Fallback: synchronous rendering for first frame
-                                            let screen_points: Vec<egui::Pos2> = world_points
-                                                .iter()
-                                                .map(|(x, y)| {
-                                                    egui::pos2(
-                                                        outline_x + (*x as f32 * self.zoom),
-                                                        outline_y + (*y as f32 * self.zoom),
-                                                    )
-                                                })
-                                                .collect();
-
-                                            if screen_points.len() >= 3 {
-                                                let mesh =
-                                                    Self::tessellate_polygon(&screen_points, color);
-                                                painter.add(egui::Shape::Mesh(Arc::new(mesh)));
-
-                                                // Calculate and update bounds
-                                                let mut poly_min_x = f32::INFINITY;
-                                                let mut poly_min_y = f32::INFINITY;
-                                                let mut poly_max_x = f32::NEG_INFINITY;
-                                                let mut poly_max_y = f32::NEG_INFINITY;
-
-                                                for point in &screen_points {
-                                                    poly_min_x = poly_min_x.min(point.x);
-                                                    poly_min_y = poly_min_y.min(point.y);
-                                                    poly_max_x = poly_max_x.max(point.x);
-                                                    poly_max_y = poly_max_y.max(point.y);
+                                    if let Some(cached_mesh) =
+                                        cached_meshes.and_then(|meshes| meshes.get(&shape_index))
+                                    {
+                                        // Render from cache: apply zoom and pan to world coordinates,
+                                        // which already have ORIGIN offset and Y-flip applied
+                                        let base = polygon_mesh.vertices.len() as u32;
+                                        polygon_mesh
+                                            .indices
+                                            .extend(cached_mesh.indices.iter().map(|i| base + i));
+                                        polygon_mesh.vertices.extend(
+                                            cached_mesh.vertices.iter().map(|v| {
+                                                let pos = egui::pos2(
+                                                    outline_x + v.x * self.zoom,
+                                                    outline_y + v.y * self.zoom,
+                                                );
+                                                // Update pin bounds for text positioning
+                                                pin_bounds.extend_with(pos);
+                                                egui::epaint::Vertex {
+                                                    pos,
+                                                    uv: egui::pos2(0.0, 0.0),
+                                                    color: cached_mesh.color,
                                                 }
+                                            }),
+                                        );
+                                    } else {
+                                        // Not cached yet: queue for background tessellation
+                                        // Convert to world coordinates (with ORIGIN offset and Y-flip, but no zoom/pan)
+                                        let world_points: Vec<(f64, f64)> = polygon_data
+                                            .points
+                                            .iter()
+                                            .map(|(x, y)| {
+                                                let world_x = macro_def.origin.0 + *x;
+                                                let world_y =
+                                                    macro_def.size_y - macro_def.origin.1 - *y;
+                                                (world_x, world_y)
+                                            })
+                                            .collect();
 
-                                                if let Some((min_x, min_y, max_x, max_y)) =
-                                                    pin_bounds
-                                                {
-                                                    pin_bounds = Some((
-                                                        min_x.min(poly_min_x),
-                                                        min_y.min(poly_min_y),
-                                                        max_x.max(poly_max_x),
-                                                        max_y.max(poly_max_y),
-                                                    ));
-                                                } else {
-                                                    pin_bounds = Some((
-                                                        poly_min_x, poly_min_y, poly_max_x,
-                                                        poly_max_y,
-                                                    ));
-                                                }
-                                            }
+                                        // Fallback: synchronous rendering for first frame
+                                        scratch.points.clear();
+                                        scratch.points.extend(world_points.iter().map(|(x, y)| {
+                                            egui::pos2(
+                                                outline_x + (*x as f32 * self.zoom),
+                                                outline_y + (*y as f32 * self.zoom),
+                                            )
+                                        }));
+                                        for &point in &scratch.points {
+                                            pin_bounds.extend_with(point);
                                         }
+                                        polygon_mesh.append(Self::tessellate_polygon(
+                                            &scratch.points,
+                                            color,
+                                        ));
 
-                                        poly_shape_index += 1;
+                                        self.queue_tessellation(TessellationJob {
+                                            cache_key: MeshCacheKey {
+                                                macro_name: scratch.mesh_key.clone(),
+                                                shape_index,
+                                            },
+                                            shape: ShapeData::Polygon {
+                                                points: world_points,
+                                            },
+                                            color,
+                                        });
                                     }
                                 }
                             }
@@ -4264,34 +9922,48 @@ impl LefDefViewer {
                             if self.visible_layers.contains("LABEL")
                                 && self.zoom > 0.2
                                 && has_visible_shapes
+                                && pin_bounds.is_finite()
                             {
-                                if let Some((min_x, min_y, max_x, max_y)) = pin_bounds {
-                                    let pin_center =
-                                        egui::pos2((min_x + max_x) * 0.5, (min_y + max_y) * 0.5);
-                                    texts_to_render.push((
-                                        pin_center,
-                                        pin.name.clone(),
-                                        egui::FontId::monospace(12.0),
-                                        egui::Color32::WHITE,
-                                    ));
-                                }
+                                texts_to_render.push((
+                                    pin_bounds.center(),
+                                    pin.name.clone(),
+                                    egui::FontId::monospace(self.dpi_style.font_size(12.0)),
+                                    egui::Color32::WHITE,
+                                ));
+                            }
+
+                            // Highlight pins that pass an active filter, over the polygons
+                            if self.lef_pin_filter.is_active() && pin_bounds.is_finite() {
+                                scratch.highlights.push(pin_bounds);
                             }
                         }
 
+                        if !polygon_mesh.is_empty() {
+                            painter.add(egui::Shape::mesh(polygon_mesh));
+                        }
+                        for &pin_bounds in &scratch.highlights {
+                            painter.rect_stroke(
+                                pin_bounds.expand(2.0),
+                                0.0,
+                                egui::Stroke::new(2.0, egui::Color32::from_rgb(255, 165, 0)),
+                                egui::StrokeKind::Outside,
+                            );
+                        }
                         // Render obstructions
                         for obs in &macro_def.obs {
                             // Render obstruction rectangles
                             for rect_data in &obs.rects {
-                                let detailed_layer = format!("{}.OBS", rect_data.layer);
-
-                                if !self.visible_layers.contains(&detailed_layer) {
+                                let Some(detailed_layer) =
+                                    layer_keys.get(LayerKind::Obs, &rect_data.layer)
+                                else {
                                     continue;
-                                }
+                                };
 
                                 // Check if this specific OBS layer is selected (if any OBS are selected)
-                                let obs_id = format!("{}::{}", macro_def.name, rect_data.layer);
                                 if !self.selected_lef_obs.is_empty()
-                                    && !self.selected_lef_obs.contains(&obs_id)
+                                    && !self.selected_lef_obs.contains(
+                                        scratch.selection_key(&macro_def.name, &rect_data.layer),
+                                    )
                                 {
                                     continue;
                                 }
@@ -4322,9 +9994,9 @@ impl LefDefViewer {
                                                 * self.zoom),
                                     ),
                                 );
-                                let color = self.get_layer_color(&detailed_layer);
+                                let color = self.get_layer_color(detailed_layer);
                                 // Render OBS as dashed outline instead of filled rectangle
-                                let stroke = egui::Stroke::new(1.0, color);
+                                let stroke = egui::Stroke::new(self.dpi_style.stroke(1.0), color);
                                 painter.rect_stroke(
                                     obs_rect,
                                     0.0,
@@ -4394,112 +10066,107 @@ impl LefDefViewer {
                                 }
                             }
 
-                            // Group obstruction polygons by layer to avoid z-fighting
-                            let mut obs_layer_polygons: std::collections::HashMap<
-                                String,
-                                Vec<&crate::lef::LefPolygon>,
-                            > = std::collections::HashMap::new();
-                            for polygon_data in &obs.polygons {
-                                let detailed_layer = format!("{}.OBS", polygon_data.layer);
-                                if !self.visible_layers.contains(&detailed_layer) {
+                            // Obstruction polygons in layer z-order to avoid z-fighting
+                            scratch.polygon_order.clear();
+                            for (offset, polygon_data) in obs.polygons.iter().enumerate() {
+                                let Some(detailed_layer) =
+                                    layer_keys.get(LayerKind::Obs, &polygon_data.layer)
+                                else {
                                     continue;
-                                }
+                                };
 
                                 // Check if this specific OBS layer is selected (if any OBS are selected)
-                                let obs_id = format!("{}::{}", macro_def.name, polygon_data.layer);
                                 if !self.selected_lef_obs.is_empty()
-                                    && !self.selected_lef_obs.contains(&obs_id)
+                                    && !self.selected_lef_obs.contains(
+                                        scratch.selection_key(&macro_def.name, &polygon_data.layer),
+                                    )
                                 {
                                     continue;
                                 }
 
-                                obs_layer_polygons
-                                    .entry(detailed_layer.clone())
-                                    .or_default()
-                                    .push(polygon_data);
+                                scratch
+                                    .polygon_order
+                                    .push((self.get_layer_order(detailed_layer), offset));
                             }
+                            // Offsets are unique, so ties keep the LEF order
+                            scratch.polygon_order.sort_unstable();
+
+                            // Draw each OBS polygon independently (LEF only has positive shapes)
+                            for &(_, offset) in &scratch.polygon_order {
+                                let polygon_data = &obs.polygons[offset];
+                                let Some(layer_name) =
+                                    layer_keys.get(LayerKind::Obs, &polygon_data.layer)
+                                else {
+                                    continue;
+                                };
+                                let color = self.get_layer_color(layer_name);
+                                if polygon_data.points.len() >= 3 {
+                                    // Convert LEF coordinates to screen coordinates
+                                    // LEF uses bottom-up Y (Y=0 at bottom), screen uses top-down Y (Y=0 at top)
+                                    // OBS coordinates are relative to ORIGIN, so add ORIGIN offset
+                                    let screen_points = &mut scratch.points;
+                                    screen_points.clear();
+                                    screen_points.extend(polygon_data.points.iter().map(
+                                        |(x, y)| {
+                                            egui::pos2(
+                                                outline_x
+                                                    + ((macro_def.origin.0 + *x) as f32
+                                                        * self.zoom),
+                                                outline_y
+                                                    + ((macro_def.size_y - macro_def.origin.1 - *y)
+                                                        as f32
+                                                        * self.zoom),
+                                            )
+                                        },
+                                    ));
 
-                            // Sort obstruction layers by z-order to prevent flickering
-                            let mut sorted_obs_layers: Vec<_> =
-                                obs_layer_polygons.into_iter().collect();
-                            sorted_obs_layers
-                                .sort_by_key(|(layer_name, _)| self.get_layer_order(layer_name));
-
-                            // Render obstruction polygons by layer
-                            for (layer_name, polygons) in sorted_obs_layers {
-                                let color = self.get_layer_color(&layer_name);
-
-                                // Draw each OBS polygon independently (LEF only has positive shapes)
-                                for polygon_data in &polygons {
-                                    if polygon_data.points.len() >= 3 {
-                                        // Convert LEF coordinates to screen coordinates
-                                        // LEF uses bottom-up Y (Y=0 at bottom), screen uses top-down Y (Y=0 at top)
-                                        // OBS coordinates are relative to ORIGIN, so add ORIGIN offset
-                                        let mut screen_points: Vec<egui::Pos2> = polygon_data
-                                            .points
-                                            .iter()
-                                            .map(|(x, y)| {
-                                                egui::pos2(
-                                                    outline_x
-                                                        + ((macro_def.origin.0 + *x) as f32
-                                                            * self.zoom),
-                                                    outline_y
-                                                        + ((macro_def.size_y
-                                                            - macro_def.origin.1
-                                                            - *y)
-                                                            as f32
-                                                            * self.zoom),
-                                                )
-                                            })
-                                            .collect();
+                                    if screen_points.len() >= 3 {
+                                        // Explicitly close the polygon by adding the first point at the end
+                                        let first_point = screen_points[0];
+                                        screen_points.push(first_point);
+
+                                        // Draw dashed outline for OBS polygons
+                                        let stroke =
+                                            egui::Stroke::new(self.dpi_style.stroke(1.0), color);
+
+                                        // Draw dashed lines between consecutive points
+                                        for i in 0..(screen_points.len() - 1) {
+                                            let start = screen_points[i];
+                                            let end = screen_points[i + 1];
+
+                                            // Calculate line direction and length
+                                            let dx = end.x - start.x;
+                                            let dy = end.y - start.y;
+                                            let line_length = (dx * dx + dy * dy).sqrt();
+
+                                            if line_length > 0.0 {
+                                                let dash_length = 3.0_f32;
+                                                let gap_length = 2.0_f32;
+                                                let pattern_length = dash_length + gap_length;
+
+                                                // Normalize direction
+                                                let dir_x = dx / line_length;
+                                                let dir_y = dy / line_length;
+
+                                                // Draw dashes along the line
+                                                let mut t = 0.0;
+                                                while t < line_length {
+                                                    let dash_end =
+                                                        (t + dash_length).min(line_length);
+                                                    let dash_start_pos = egui::pos2(
+                                                        start.x + dir_x * t,
+                                                        start.y + dir_y * t,
+                                                    );
+                                                    let dash_end_pos = egui::pos2(
+                                                        start.x + dir_x * dash_end,
+                                                        start.y + dir_y * dash_end,
+                                                    );
 
-                                        if screen_points.len() >= 3 {
-                                            // Explicitly close the polygon by adding the first point at the end
-                                            let first_point = screen_points[0];
-                                            screen_points.push(first_point);
-
-                                            // Draw dashed outline for OBS polygons
-                                            let stroke = egui::Stroke::new(1.0, color);
-
-                                            // Draw dashed lines between consecutive points
-                                            for i in 0..(screen_points.len() - 1) {
-                                                let start = screen_points[i];
-                                                let end = screen_points[i + 1];
-
-                                                // Calculate line direction and length
-                                                let dx = end.x - start.x;
-                                                let dy = end.y - start.y;
-                                                let line_length = (dx * dx + dy * dy).sqrt();
-
-                                                if line_length > 0.0 {
-                                                    let dash_length = 3.0_f32;
-                                                    let gap_length = 2.0_f32;
-                                                    let pattern_length = dash_length + gap_length;
-
-                                                    // Normalize direction
-                                                    let dir_x = dx / line_length;
-                                                    let dir_y = dy / line_length;
-
-                                                    // Draw dashes along the line
-                                                    let mut t = 0.0;
-                                                    while t < line_length {
-                                                        let dash_end =
-                                                            (t + dash_length).min(line_length);
-                                                        let dash_start_pos = egui::pos2(
-                                                            start.x + dir_x * t,
-                                                            start.y + dir_y * t,
-                                                        );
-                                                        let dash_end_pos = egui::pos2(
-                                                            start.x + dir_x * dash_end,
-                                                            start.y + dir_y * dash_end,
-                                                        );
-
-                                                        painter.line_segment(
-                                                            [dash_start_pos, dash_end_pos],
-                                                            stroke,
-                                                        );
-                                                        t += pattern_length;
-                                                    }
+                                                    painter.line_segment(
+                                                        [dash_start_pos, dash_end_pos],
+                                                        stroke,
+                                                    );
+                                                    t += pattern_length;
                                                 }
                                             }
                                         }
@@ -4509,6 +10176,15 @@ impl LefDefViewer {
                         }
                     } // End should_render_details
 
+                    if self.show_density {
+                        self.paint_macro_density(
+                            painter,
+                            macro_def,
+                            egui::pos2(outline_x, outline_y),
+                            &layer_keys,
+                        );
+                    }
+
                     // Store text for later rendering (on top)
                     if self.zoom > 0.3 {
                         texts_to_render.push((
@@ -4519,9 +10195,25 @@ impl LefDefViewer {
                         ));
                     }
                 }
+                Self::transform_source_texts(
+                    source.transform(),
+                    &mut texts_to_render[text_start..],
+                    &mut [],
+                );
+                source.finish();
             }
+            drop(mesh_cache);
+            self.render_scratch = scratch;
 
             if let Some(def) = &self.def_data {
+                // DEF data drawn over the LEF view shares the LEF origin
+                let pivot = egui::pos2(center.x + self.pan_x, center.y + self.pan_y);
+                let source =
+                    SourcePaint::begin(painter, self.def_transform.to_screen(pivot, self.zoom));
+                let painter = &source.painter;
+                let text_start = (texts_to_render.len(), smart_texts_to_render.len());
+                // Screen pixels per DEF database unit
+                let dbu_scale = self.zoom / self.def_db_units() as f32;
                 // Draw die area outline (if enabled)
                 if self.show_diearea && !def.die_area_points.is_empty() {
                     if def.die_area_points.len() == 2 {
@@ -4531,12 +10223,12 @@ impl LefDefViewer {
 
                         // Convert to screen coordinates (keep Y axis consistent with multi-point)
                         let screen_p1 = egui::pos2(
-                            center.x + self.pan_x + (p1.0 as f32 * self.zoom * 0.001),
-                            center.y + self.pan_y + (p1.1 as f32 * self.zoom * 0.001), // Same as components
+                            center.x + self.pan_x + (p1.0 as f32 * dbu_scale),
+                            center.y + self.pan_y + (p1.1 as f32 * dbu_scale), // Same as components
                         );
                         let screen_p2 = egui::pos2(
-                            center.x + self.pan_x + (p2.0 as f32 * self.zoom * 0.001),
-                            center.y + self.pan_y + (p2.1 as f32 * self.zoom * 0.001), // Same as components
+                            center.x + self.pan_x + (p2.0 as f32 * dbu_scale),
+                            center.y + self.pan_y + (p2.1 as f32 * dbu_scale), // Same as components
                         );
 
                         // Create rectangle from min/max of both points
@@ -4546,7 +10238,7 @@ impl LefDefViewer {
                         painter.rect_stroke(
                             rect,
                             0.0,
-                            egui::Stroke::new(3.0, egui::Color32::RED),
+                            egui::Stroke::new(self.dpi_style.stroke(3.0), egui::Color32::RED),
                             egui::StrokeKind::Middle,
                         );
 
@@ -4560,8 +10252,8 @@ impl LefDefViewer {
                         // Convert all points to screen coordinates (same as components)
                         for point in &def.die_area_points {
                             let screen_point = egui::pos2(
-                                center.x + self.pan_x + (point.0 as f32 * self.zoom * 0.001),
-                                center.y + self.pan_y + (point.1 as f32 * self.zoom * 0.001), // Same as components
+                                center.x + self.pan_x + (point.0 as f32 * dbu_scale),
+                                center.y + self.pan_y + (point.1 as f32 * dbu_scale), // Same as components
                             );
                             screen_points.push(screen_point);
                         }
@@ -4583,7 +10275,10 @@ impl LefDefViewer {
                             // Draw thick red outline
                             painter.line_segment(
                                 [current, next],
-                                egui::Stroke::new(4.0, egui::Color32::from_rgb(255, 0, 0)), // Thick red line
+                                egui::Stroke::new(
+                                    self.dpi_style.stroke(4.0),
+                                    egui::Color32::from_rgb(255, 0, 0),
+                                ), // Thick red line
                             );
                         }
                     }
@@ -4591,19 +10286,27 @@ impl LefDefViewer {
 
                 // Draw components (if enabled and selected)
                 if self.show_components {
-                    for component in &def.components {
+                    for (component_idx, component) in def.components.iter().enumerate() {
+                        if self.component_flags.is_hidden(component_idx) {
+                            continue;
+                        }
                         // Only draw if this component is selected (or all if none are selected)
                         if !self.selected_components.is_empty()
                             && !self.selected_components.contains(&component.name)
                         {
                             continue;
                         }
+                        if self.hide_physical_only
+                            && self.physical_only_cells.contains(&component.macro_name)
+                        {
+                            continue;
+                        }
 
                         // Get component position from placement info
                         let (comp_x, comp_y) = if let Some(ref placement) = component.placement {
                             (
-                                center.x + self.pan_x + (placement.x as f32 * self.zoom * 0.001),
-                                center.y + self.pan_y + (placement.y as f32 * self.zoom * 0.001),
+                                center.x + self.pan_x + (placement.x as f32 * dbu_scale),
+                                center.y + self.pan_y + (placement.y as f32 * dbu_scale),
                             )
                         } else {
                             // Default position if no placement info
@@ -4629,7 +10332,7 @@ impl LefDefViewer {
                         painter.rect_stroke(
                             comp_rect,
                             0.0,
-                            egui::Stroke::new(1.0, egui::Color32::WHITE),
+                            egui::Stroke::new(self.dpi_style.stroke(1.0), egui::Color32::WHITE),
                             egui::StrokeKind::Middle,
                         );
 
@@ -4638,8 +10341,8 @@ impl LefDefViewer {
                         if self.zoom > 2.0 {
                             texts_to_render.push((
                                 egui::pos2(comp_x, comp_y - comp_size - 10.0),
-                                component.name.clone(),
-                                egui::FontId::monospace(8.0),
+                                identifier::display_name(&component.name, def.bus_bit_chars),
+                                egui::FontId::monospace(self.dpi_style.font_size(8.0)),
                                 egui::Color32::YELLOW,
                             ));
                         }
@@ -4648,15 +10351,21 @@ impl LefDefViewer {
 
                 // Draw pins (if enabled and selected)
                 if self.show_pins {
-                    for pin in &def.pins {
+                    for (pin_idx, pin) in def.pins.iter().enumerate() {
+                        if self.pin_flags.is_hidden(pin_idx) {
+                            continue;
+                        }
                         // Only draw if this pin is selected (or all if none are selected)
                         if !self.selected_pins.is_empty() && !self.selected_pins.contains(&pin.name)
                         {
                             continue;
                         }
+                        if !self.def_pin_filter.matches_def_pin(pin) {
+                            continue;
+                        }
 
-                        let pin_x = center.x + self.pan_x + (pin.x as f32 * self.zoom * 0.001);
-                        let pin_y = center.y + self.pan_y + (pin.y as f32 * self.zoom * 0.001);
+                        let pin_x = center.x + self.pan_x + (pin.x as f32 * dbu_scale);
+                        let pin_y = center.y + self.pan_y + (pin.y as f32 * dbu_scale);
 
                         // Draw a small circle for each pin
                         let pin_radius = 3.0 * self.zoom;
@@ -4682,8 +10391,15 @@ impl LefDefViewer {
                         painter.circle_stroke(
                             egui::pos2(pin_x, pin_y),
                             pin_radius.max(1.0),
-                            egui::Stroke::new(1.0, egui::Color32::WHITE),
+                            egui::Stroke::new(self.dpi_style.stroke(1.0), egui::Color32::WHITE),
                         );
+                        if pin.placement_status() == "FIXED" {
+                            let offset = pin_radius.max(1.0) + 5.0;
+                            self.paint_lock_glyph(
+                                painter,
+                                egui::pos2(pin_x + offset, pin_y - offset),
+                            );
+                        }
 
                         // Draw pin name with smart positioning if zoom is high enough
                         if self.zoom > 1.0 {
@@ -4694,7 +10410,7 @@ impl LefDefViewer {
                             let edge_proximity = Self::calculate_pin_edge_proximity(
                                 (pin.x as f32, pin.y as f32),
                                 &def.die_area_points,
-                                self.zoom,
+                                dbu_scale,
                                 center,
                                 self.pan_x,
                                 self.pan_y,
@@ -4707,8 +10423,8 @@ impl LefDefViewer {
                                 .iter()
                                 .map(|(x, y)| {
                                     egui::pos2(
-                                        center.x + self.pan_x + (*x as f32 * self.zoom * 0.001),
-                                        center.y + self.pan_y + (*y as f32 * self.zoom * 0.001),
+                                        center.x + self.pan_x + (*x as f32 * dbu_scale),
+                                        center.y + self.pan_y + (*y as f32 * dbu_scale),
                                     )
                                 })
                                 .collect();
@@ -4722,34 +10438,159 @@ impl LefDefViewer {
                             // Store smart text positioning info for later rendering
                             smart_texts_to_render.push((
                                 text_positioning,
-                                pin.name.clone(),
-                                egui::FontId::monospace(14.0),
+                                identifier::display_name(&pin.name, def.bus_bit_chars),
+                                egui::FontId::monospace(self.dpi_style.font_size(14.0)),
                                 egui::Color32::WHITE,
                             ));
                         }
                     }
                 }
+                Self::transform_source_texts(
+                    source.transform(),
+                    &mut texts_to_render[text_start.0..],
+                    &mut smart_texts_to_render[text_start.1..],
+                );
+                source.finish();
             }
         } // End of LEF mode else branch
 
-        // Render all text on top of everything with outline for white text
-        for (pos, text, font, color) in texts_to_render {
-            self.render_text_with_outline(
-                &painter,
-                pos,
-                egui::Align2::CENTER_CENTER,
-                &text,
+        (texts_to_render, smart_texts_to_render)
+    }
+
+    fn paint_labels(
+        label_cache: &mut LabelCache,
+        painter: &egui::Painter,
+        zoom: f32,
+        texts: &[PlainText],
+        smart_texts: &[SmartText],
+    ) {
+        let plain_labels = texts.iter().map(|(pos, text, font, color)| Label {
+            pos: *pos,
+            anchor: egui::Align2::CENTER_CENTER,
+            angle: 0.0,
+            text,
+            font,
+            color: *color,
+        });
+        let smart_labels = smart_texts
+            .iter()
+            .map(|(positioning, text, font, color)| Label {
+                pos: positioning.pos,
+                anchor: positioning.anchor,
+                angle: positioning.angle,
+                text,
                 font,
-                color,
-            );
-        }
+                color: *color,
+            });
+        label_cache.paint(painter, zoom, plain_labels.chain(smart_labels));
+    }
 
-        // Render smart positioned text using the new rendering system
-        for (positioning, text, font, color) in smart_texts_to_render {
-            self.render_smart_text_with_outline(&painter, &positioning, &text, font, color);
+    /// Paint the design again at the resolution of an image export, framed
+    /// as on the canvas, and write the image in the background
+    ///
+    /// Culling and the level of detail follow the image rather than the
+    /// screen, so cells too small to show details on the canvas get them
+    /// in a large export.
+    fn start_image_export(
+        &mut self,
+        ctx: &egui::Context,
+        request: ImageExportRequest,
+        path: std::path::PathBuf,
+    ) {
+        if self.image_export_receiver.is_some() {
+            self.error_message = Some("An image export is already running".to_string());
+            return;
+        }
+        let saved_camera = self.camera();
+        let canvas = self.canvas_rect;
+        let bounds = match request.region {
+            ImageRegion::View => None,
+            ImageRegion::Design => self.image_design_bounds(),
+            ImageRegion::Bounds => Some(request.bounds),
+        };
+        let mut region = canvas;
+        if let Some(bounds) = bounds {
+            self.zoom_to_bounds(bounds, canvas.size(), 1.0);
+            let size = egui::vec2(bounds.2 - bounds.0, bounds.3 - bounds.1) * self.zoom;
+            region = egui::Rect::from_center_size(canvas.center(), size).intersect(canvas);
+        }
+        let size = crate::png_export::render_size(region, request.width as f32 / region.width());
+
+        // Scale the region of the canvas up to the image
+        let target = crate::png_export::offscreen_region(size, ctx.pixels_per_point());
+        let scale = (target.width() / region.width()).min(target.height() / region.height());
+        let camera = self.camera();
+        let offset = (canvas.center() - region.min) * scale - target.center().to_vec2();
+        self.set_camera(Camera {
+            zoom: camera.zoom * scale,
+            pan_x: camera.pan_x * scale + offset.x,
+            pan_y: camera.pan_y * scale + offset.y,
+        });
+        let scene = self.paint_offscreen(ctx, size);
+        self.set_camera(saved_camera);
+
+        let format = request.format;
+        let (sender, receiver) = mpsc::channel();
+        let spawned = platform::spawn("image-export", move || {
+            let [width, height] = scene.size();
+            let saved = match format {
+                ImageFormat::Png => {
+                    crate::png_export::save_rgba_png(&scene.render(egui::Color32::BLACK), &path)
+                }
+                ImageFormat::Svg => {
+                    let mut view = crate::svg_export::shapes_to_svg(scene.shapes(), scene.region());
+                    view.output_size = Some((width as f32, height as f32));
+                    export::export_view_to_svg(&view, &path.to_string_lossy())
+                }
+            };
+            let _ = sender.send(match saved {
+                Ok(()) => Ok(format!(
+                    "Exported {width} x {height} image to: {}",
+                    path.display()
+                )),
+                Err(e) => Err(format!("Failed to export image: {e}")),
+            });
+        });
+        match spawned {
+            Ok(()) => self.image_export_receiver = Some(receiver),
+            Err(e) => self.error_message = Some(format!("Failed to start the image export: {e}")),
         }
+    }
 
-        ui.ctx().request_repaint();
+    /// Paint the design and its labels at the current camera onto a layer
+    /// that is not shown, covering `size` pixels
+    fn paint_offscreen(&mut self, ctx: &egui::Context, size: [u32; 2]) -> OffscreenScene {
+        let layer = egui::LayerId::new(egui::Order::Background, egui::Id::new("offscreen_export"));
+        let region = crate::png_export::offscreen_region(size, ctx.pixels_per_point());
+        let painter = egui::Painter::new(ctx.clone(), layer, region);
+        let (texts, smart_texts) = self.paint_scene(&painter, region);
+        Self::paint_labels(
+            &mut LabelCache::new(),
+            &painter,
+            self.zoom,
+            &texts,
+            &smart_texts,
+        );
+        OffscreenScene::capture(ctx, layer, size)
+    }
+
+    /// Report the image export once it is written
+    fn process_image_export(&mut self) {
+        let Some(receiver) = &self.image_export_receiver else {
+            return;
+        };
+        let result = match receiver.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                Err("The image export stopped unexpectedly".to_string())
+            }
+        };
+        self.image_export_receiver = None;
+        match result {
+            Ok(message) => self.success_message = Some(message),
+            Err(error) => self.error_message = Some(error),
+        }
     }
 
     fn render_layers_panel(&mut self, ui: &mut egui::Ui) {
@@ -4759,6 +10600,20 @@ impl LefDefViewer {
             if !self.lef_files.is_empty() {
                 ui.label("Toggle layer visibility:");
 
+                if let Some(solo) = self.layer_solo.current() {
+                    let solo = solo.to_string();
+                    ui.horizontal(|ui| {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(255, 193, 7),
+                            format!("Solo: {solo} (level {})", self.layer_solo.depth()),
+                        );
+                        if ui.small_button("Restore").clicked() {
+                            self.layer_solo.restore_all(&mut self.visible_layers);
+                            self.show_pin_text = self.visible_layers.contains("LABEL");
+                        }
+                    });
+                }
+
                 // Get all unique layers from the complete list, not just visible ones
                 let mut all_layers: Vec<String> = self.all_layers.iter().cloned().collect();
                 all_layers.sort();
@@ -4776,128 +10631,122 @@ impl LefDefViewer {
                 egui::ScrollArea::vertical()
                     .auto_shrink([false, true])
                     .show(ui, |ui| {
-                        // Group layers by type for better organization
-                        let mut special_layers = Vec::new();
-                        let mut power_layers = Vec::new();
-                        let mut signal_layers = Vec::new();
-                        let mut obs_layers = Vec::new();
-
-                        for layer in &all_layers {
-                            if layer == "OUTLINE" || layer == "LABEL" {
-                                special_layers.push(layer);
-                            } else if layer.contains("T8M") && layer.contains(".PIN") {
-                                power_layers.push(layer);
-                            } else if layer.contains(".PIN") {
-                                signal_layers.push(layer);
-                            } else if layer.contains(".OBS") {
-                                obs_layers.push(layer);
-                            }
-                        }
+                        // Base layer nodes (M2 -> PIN, OBS, ROUTE) in stack order
+                        let (special_layers, groups) =
+                            layer_tree::build(&all_layers, |base| {
+                                self.tech_layer_styles
+                                    .get(base)
+                                    .map_or(i32::MAX, |style| style.order)
+                            });
 
                         // Render special layers first
                         if !special_layers.is_empty() {
                             ui.heading("Special Layers");
                             for layer in &special_layers {
-                                let mut is_visible = self.visible_layers.contains(*layer);
-                                let color = self.get_layer_color(layer);
-
-                                ui.horizontal(|ui| {
-                                    let (rect, _) = ui.allocate_exact_size(
-                                        egui::Vec2::splat(12.0),
-                                        egui::Sense::hover(),
-                                    );
-                                    ui.painter().rect_filled(rect, 2.0, color);
-
-                                    if ui.checkbox(&mut is_visible, *layer).clicked() {
-                                        if is_visible {
-                                            self.visible_layers.insert(layer.to_string());
-                                        } else {
-                                            self.visible_layers.remove(*layer);
-                                        }
-
-                                        if layer == &"LABEL" {
-                                            self.show_pin_text = is_visible;
-                                        }
-                                    }
-                                });
-                            }
-                            ui.separator();
-                        }
-
-                        // Render power mesh layers
-                        if !power_layers.is_empty() {
-                            ui.heading("Power Mesh Layers");
-                            for layer in &power_layers {
-                                let mut is_visible = self.visible_layers.contains(*layer);
-                                let color = self.get_layer_color(layer);
-
-                                ui.horizontal(|ui| {
-                                    let (rect, _) = ui.allocate_exact_size(
-                                        egui::Vec2::splat(12.0),
-                                        egui::Sense::hover(),
-                                    );
-                                    ui.painter().rect_filled(rect, 2.0, color);
-
-                                    if ui.checkbox(&mut is_visible, *layer).clicked() {
-                                        if is_visible {
-                                            self.visible_layers.insert(layer.to_string());
-                                        } else {
-                                            self.visible_layers.remove(*layer);
-                                        }
-                                    }
-                                });
+                                self.render_layer_checkbox(ui, layer, layer);
                             }
                             ui.separator();
                         }
 
-                        // Render signal layers
-                        if !signal_layers.is_empty() {
-                            ui.heading("Signal Pin Layers");
-                            for layer in &signal_layers {
-                                let mut is_visible = self.visible_layers.contains(*layer);
-                                let color = self.get_layer_color(layer);
-
-                                ui.horizontal(|ui| {
-                                    let (rect, _) = ui.allocate_exact_size(
-                                        egui::Vec2::splat(12.0),
-                                        egui::Sense::hover(),
+                        // One kind across every base layer, e.g. all OBS
+                        let kinds: Vec<(&str, Vec<&str>)> = layer_tree::KINDS
+                            .iter()
+                            .map(|kind| (*kind, layer_tree::kind_layers(&groups, kind)))
+                            .filter(|(_, layers)| !layers.is_empty())
+                            .collect();
+                        if !kinds.is_empty() {
+                            ui.horizontal_wrapped(|ui| {
+                                ui.label("All:");
+                                for (kind, layers) in &kinds {
+                                    let (mut all_visible, partial) =
+                                        layer_tree::tri_state(&self.visible_layers, layers);
+                                    let response = ui
+                                        .add(
+                                            egui::Checkbox::new(&mut all_visible, *kind)
+                                                .indeterminate(partial),
+                                        )
+                                        .on_hover_text(format!(
+                                            "{kind} of every layer\nAlt-click to solo"
+                                        ));
+                                    self.apply_layer_group_click(
+                                        ui,
+                                        &response,
+                                        kind,
+                                        layers,
+                                        all_visible,
                                     );
-                                    ui.painter().rect_filled(rect, 2.0, color);
-
-                                    if ui.checkbox(&mut is_visible, *layer).clicked() {
-                                        if is_visible {
-                                            self.visible_layers.insert(layer.to_string());
-                                        } else {
-                                            self.visible_layers.remove(*layer);
-                                        }
-                                    }
-                                });
-                            }
+                                }
+                            });
                             ui.separator();
                         }
 
-                        // Render obstruction layers
-                        if !obs_layers.is_empty() {
-                            ui.heading("Obstruction Layers");
-                            for layer in &obs_layers {
-                                let mut is_visible = self.visible_layers.contains(*layer);
-                                let color = self.get_layer_color(layer);
-
-                                ui.horizontal(|ui| {
-                                    let (rect, _) = ui.allocate_exact_size(
-                                        egui::Vec2::splat(12.0),
-                                        egui::Sense::hover(),
+                        // Render base layers with tri-state toggles for their variants
+                        if !groups.is_empty() {
+                            let mut expand = None;
+                            ui.horizontal(|ui| {
+                                ui.heading("Layers");
+                                if ui.small_button("Expand All").clicked() {
+                                    expand = Some(true);
+                                }
+                                if ui.small_button("Collapse All").clicked() {
+                                    expand = Some(false);
+                                }
+                            });
+                            for group in &groups {
+                                let (mut all_visible, partial) =
+                                    layer_tree::tri_state(&self.visible_layers, &group.layers);
+                                let color = self.get_layer_color(group.layers[0]);
+
+                                let id = ui.make_persistent_id(("layer_group", group.base));
+                                let mut state =
+                                    egui::collapsing_header::CollapsingState::load_with_default_open(
+                                        ui.ctx(),
+                                        id,
+                                        false,
                                     );
-                                    ui.painter().rect_filled(rect, 2.0, color);
+                                if let Some(open) = expand {
+                                    state.set_open(open);
+                                    state.store(ui.ctx());
+                                }
+                                state
+                                    .show_header(ui, |ui| {
+                                        let (rect, _) = ui.allocate_exact_size(
+                                            egui::Vec2::splat(12.0),
+                                            egui::Sense::hover(),
+                                        );
+                                        ui.painter().rect_filled(rect, 2.0, color);
 
-                                    if ui.checkbox(&mut is_visible, *layer).clicked() {
-                                        if is_visible {
-                                            self.visible_layers.insert(layer.to_string());
-                                        } else {
-                                            self.visible_layers.remove(*layer);
+                                        // Clicking a partially visible group shows every variant
+                                        let hover = match self.tech_layer_styles.get(group.base) {
+                                            Some(style) => {
+                                                format!("{}\nAlt-click to solo", style.summary)
+                                            }
+                                            None => "Alt-click to solo".to_string(),
+                                        };
+                                        let response = ui
+                                            .add(
+                                                egui::Checkbox::new(&mut all_visible, group.base)
+                                                    .indeterminate(partial),
+                                            )
+                                            .on_hover_text(hover);
+                                        self.apply_layer_group_click(
+                                            ui,
+                                            &response,
+                                            group.base,
+                                            &group.layers,
+                                            all_visible,
+                                        );
+                                    })
+                                    .body(|ui| {
+                                        for layer in &group.layers {
+                                            // The node already names the base layer
+                                            let kind = match layer_tree::split_layer(layer).1 {
+                                                "" => *layer,
+                                                kind => kind,
+                                            };
+                                            self.render_layer_checkbox(ui, layer, kind);
                                         }
-                                    }
-                                });
+                                    });
                             }
                         }
 
@@ -4906,6 +10755,7 @@ impl LefDefViewer {
                         // Move button group inside ScrollArea for better accessibility
                         ui.horizontal(|ui| {
                             if ui.button("Show All").clicked() {
+                                self.layer_solo.clear();
                                 for layer in &all_layers {
                                     self.visible_layers.insert(layer.clone());
                                 }
@@ -4913,11 +10763,13 @@ impl LefDefViewer {
                                 self.show_pin_text = true;
                             }
                             if ui.button("Hide All").clicked() {
+                                self.layer_solo.clear();
                                 self.visible_layers.clear();
                                 // Sync show_pin_text when hiding all layers
                                 self.show_pin_text = false;
                             }
                             if ui.button("Show Power Only").clicked() {
+                                self.layer_solo.clear();
                                 self.visible_layers.clear();
                                 // Show only OUTLINE and power mesh layers
                                 self.visible_layers.insert("OUTLINE".to_string());
@@ -4950,11 +10802,83 @@ impl LefDefViewer {
         });
     }
 
+    /// Color swatch and visibility checkbox of one layer
+    fn render_layer_checkbox(&mut self, ui: &mut egui::Ui, layer: &str, label: &str) {
+        let mut is_visible = self.visible_layers.contains(layer);
+        let color = self.get_layer_color(layer);
+
+        ui.horizontal(|ui| {
+            let (rect, _) = ui.allocate_exact_size(egui::Vec2::splat(12.0), egui::Sense::hover());
+            ui.painter().rect_filled(rect, 2.0, color);
+
+            let response = ui
+                .checkbox(&mut is_visible, label)
+                .on_hover_text(format!("{layer}\nAlt-click to solo"));
+            if response.clicked() && ui.input(|i| i.modifiers.alt) {
+                self.toggle_layer_solo(layer, &[layer.to_string()]);
+            } else if response.clicked() {
+                if is_visible {
+                    self.visible_layers.insert(layer.to_string());
+                } else {
+                    self.visible_layers.remove(layer);
+                }
+
+                if layer == "LABEL" {
+                    self.show_pin_text = is_visible;
+                }
+            }
+        });
+    }
+
+    /// Apply a click on a tri-state checkbox covering `layers`
+    fn apply_layer_group_click(
+        &mut self,
+        ui: &egui::Ui,
+        response: &egui::Response,
+        key: &str,
+        layers: &[&str],
+        all_visible: bool,
+    ) {
+        if response.clicked() && ui.input(|i| i.modifiers.alt) {
+            let layers: Vec<String> = layers.iter().map(|l| l.to_string()).collect();
+            self.toggle_layer_solo(key, &layers);
+        } else if response.clicked() {
+            for layer in layers {
+                if all_visible {
+                    self.visible_layers.insert(layer.to_string());
+                } else {
+                    self.visible_layers.remove(*layer);
+                }
+            }
+        }
+    }
+
+    /// Solo the given layers, or undo the innermost solo if it was `key`
+    fn toggle_layer_solo(&mut self, key: &str, layers: &[String]) {
+        self.layer_solo
+            .toggle(key, layers, &mut self.visible_layers);
+        self.show_pin_text = self.visible_layers.contains("LABEL");
+    }
+
+    /// Hidden/locked counts of a tree with a button to unhide everything
+    fn render_flag_summary(ui: &mut egui::Ui, flags: &mut ObjectFlags) {
+        let (hidden, locked) = (flags.hidden_count(), flags.locked_count());
+        if hidden == 0 && locked == 0 {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label(format!("{hidden} hidden, {locked} locked"));
+            if hidden > 0 && ui.small_button("Show Hidden").clicked() {
+                flags.show_all();
+            }
+        });
+    }
+
     /// Calculate pin proximity to DIEAREA edges
     fn calculate_pin_edge_proximity(
         pin_pos: (f32, f32),
         diearea_bounds: &[(f64, f64)],
-        zoom: f32,
+        dbu_scale: f32, // Screen pixels per DEF database unit
         center: egui::Pos2,
         pan_x: f32,
         pan_y: f32,
@@ -4965,16 +10889,16 @@ impl LefDefViewer {
         }
 
         // Convert pin to screen coordinates (same as DEF pins)
-        let pin_screen_x = center.x + pan_x + (pin_pos.0 * zoom * 0.001);
-        let pin_screen_y = center.y + pan_y + (pin_pos.1 * zoom * 0.001);
+        let pin_screen_x = center.x + pan_x + (pin_pos.0 * dbu_scale);
+        let pin_screen_y = center.y + pan_y + (pin_pos.1 * dbu_scale);
 
         // Convert DIEAREA to screen coordinates
         let screen_bounds: Vec<egui::Pos2> = diearea_bounds
             .iter()
             .map(|(x, y)| {
                 egui::pos2(
-                    center.x + pan_x + (*x as f32 * zoom * 0.001),
-                    center.y + pan_y + (*y as f32 * zoom * 0.001),
+                    center.x + pan_x + (*x as f32 * dbu_scale),
+                    center.y + pan_y + (*y as f32 * dbu_scale),
                 )
             })
             .collect();
@@ -5147,83 +11071,30 @@ impl LefDefViewer {
             }
         }
     }
-
-    /// Enhanced text rendering with rotation support and outline
-    fn render_smart_text_with_outline(
-        &self,
-        painter: &egui::Painter,
-        positioning: &TextPositioning,
-        text: &str,
-        font: egui::FontId,
-        color: egui::Color32,
-    ) {
-        // Create TextShape with rotation using egui's API
-        let mut text_shape = egui::Shape::text(
-            &painter.fonts(|f| f.clone()),
-            positioning.pos,
-            positioning.anchor,
-            text,
-            font.clone(),
-            color,
-        );
-
-        // Apply rotation if needed
-        if positioning.angle != 0.0 {
-            if let egui::Shape::Text(text_shape) = &mut text_shape {
-                text_shape.angle = positioning.angle;
-            }
-        }
-
-        // Add outline effect for white text by rendering multiple offset copies
-        if color == egui::Color32::WHITE {
-            let outline_color = egui::Color32::BLACK;
-            let outline_offsets = [
-                (-1.0, -1.0),
-                (0.0, -1.0),
-                (1.0, -1.0),
-                (-1.0, 0.0),
-                (1.0, 0.0),
-                (-1.0, 1.0),
-                (0.0, 1.0),
-                (1.0, 1.0),
-            ];
-
-            for (dx, dy) in outline_offsets {
-                let outline_pos = egui::pos2(positioning.pos.x + dx, positioning.pos.y + dy);
-                let mut outline_shape = egui::Shape::text(
-                    &painter.fonts(|f| f.clone()),
-                    outline_pos,
-                    positioning.anchor,
-                    text,
-                    font.clone(),
-                    outline_color,
-                );
-
-                // Apply rotation to outline if needed
-                if positioning.angle != 0.0 {
-                    if let egui::Shape::Text(outline_shape) = &mut outline_shape {
-                        outline_shape.angle = positioning.angle;
-                    }
-                }
-                painter.add(outline_shape);
-            }
-        }
-
-        // Render main text on top
-        painter.add(text_shape);
-    }
 }
 
 impl eframe::App for LefDefViewer {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, session::STORAGE_KEY, &self.session());
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        profiling::new_frame();
+        crate::profile_scope!("update");
+
         // Check loading progress and handle async messages
         self.check_loading_progress(ctx);
+        self.handle_dropped_files(ctx);
+        self.start_queued_def_load();
+        self.poll_file_watcher(ctx);
+        self.continue_session_restore();
 
         // Start progressive rendering worker if not already started
         self.start_progressive_rendering();
 
         // Process incoming render messages from background thread
         self.process_render_messages(ctx);
+        self.run_precompute(ctx);
 
         if let Some(error) = &self.error_message.clone() {
             egui::Window::new("Error")
@@ -5270,43 +11141,197 @@ impl eframe::App for LefDefViewer {
             self.perform_lib_export();
         }
 
+        self.render_def_memory_warning(ctx);
+
+        // Macro source editor, reparses a single macro on apply
+        if let Some(edit) = self.macro_source_dialog.render(ctx) {
+            self.apply_macro_source_edit(edit);
+        }
+
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::G)) {
+            self.goto_dialog.open(self.goto_targets());
+        }
+        if let Some(target) = self.goto_dialog.render(ctx) {
+            self.go_to_object(target);
+        }
+
+        if let Some(request) = self.gds_dialog.render(ctx) {
+            self.handle_export_gds(request);
+        }
+
+        if let Some(request) = self.image_export_dialog.render(ctx) {
+            self.handle_export_image(request);
+        }
+
+        if let Some(request) = self.reproducer_dialog.render(ctx) {
+            self.handle_save_reproducer(request);
+        }
+
+        if let Some(state) = self.permalink_dialog.render(ctx) {
+            if let Err(e) = self.restore_view_state(state) {
+                self.permalink_dialog.set_error(e);
+            }
+        }
+
+        if let Some(mut isolation) = self.isolation.take() {
+            match isolation.render_controls(ctx) {
+                IsolationAction::None => self.isolation = Some(isolation),
+                IsolationAction::Reframe => {
+                    self.frame_isolation(&isolation);
+                    self.isolation = Some(isolation);
+                }
+                IsolationAction::Exit => {}
+            }
+        }
+
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
             self.render_menu_bar(ui);
         });
 
+        if self.show_preferences {
+            let mut open = true;
+            egui::Window::new("Preferences")
+                .open(&mut open)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.heading("Session");
+                    ui.checkbox(
+                        &mut self.restore_session,
+                        "Reopen the Last Files on Startup",
+                    )
+                    .on_hover_text(
+                        "Load the files of this session again with its view, layers and selections",
+                    );
+                    ui.separator();
+                    ui.heading("Mouse Bindings");
+                    self.mouse_bindings.render_settings(ui);
+                    ui.separator();
+                    ui.heading("Copy Selection as Tcl");
+                    for dialect in TclDialect::ALL {
+                        ui.radio_value(&mut self.tcl_dialect, dialect, dialect.label());
+                    }
+                    ui.separator();
+                    ui.heading("Verilog Stub");
+                    ui.checkbox(
+                        &mut self.verilog_stub_options.group_buses,
+                        "Group Bus Pins into Vector Ports",
+                    )
+                    .on_hover_text(
+                        "Declare DATA[0] to DATA[31] as input [31:0] DATA, else each bit as its own port",
+                    );
+                    ui.separator();
+                    ui.heading("SPICE Stub");
+                    ui.checkbox(
+                        &mut self.spice_stub_options.pg_pins,
+                        "List Power and Ground Pins",
+                    )
+                    .on_hover_text("POWER and GROUND pins follow the signal pins of each .SUBCKT");
+                });
+            self.show_preferences = open;
+        }
+
+        if self.show_orientation_legend {
+            let mut open = true;
+            egui::Window::new("Orientation Legend")
+                .open(&mut open)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    self.render_orientation_legend(ui);
+                });
+            self.show_orientation_legend = open;
+        }
+
         // Show progress bar if loading and show_progress is true
         if let LoadingState::Loading {
             file_type,
             file_name,
             start_time,
             show_progress,
+            progress,
+            ..
         } = &self.loading_state
         {
             if *show_progress {
                 egui::TopBottomPanel::top("loading_bar").show(ctx, |ui| {
+                    ui.horizontal(|ui| match combined_progress(progress) {
+                        Some(progress) => {
+                            ui.add(
+                                egui::ProgressBar::new(progress.fraction)
+                                    .desired_width(200.0)
+                                    .text(format!("{:.0}%", progress.percent())),
+                            );
+                            ui.label(format!("Loading {file_type} file: {file_name}"));
+                            ui.label(format!(
+                                "({}, {} items, {:.1}s)",
+                                progress.section,
+                                progress.items,
+                                start_time.elapsed().as_secs_f32()
+                            ));
+                        }
+                        None => {
+                            ui.spinner();
+                            ui.label(format!("Loading {file_type} file: {file_name}"));
+                            ui.label(format!("({:.1}s)", start_time.elapsed().as_secs_f32()));
+                        }
+                    });
+                });
+            }
+        }
+
+        // Banner for DEF units that disagree with the tech LEF
+        if self.def_mode && !self.units_warning_dismissed {
+            if let Some(warning) = self.units_check().warning() {
+                egui::TopBottomPanel::top("units_warning").show(ctx, |ui| {
                     ui.horizontal(|ui| {
-                        ui.spinner();
-                        ui.label(format!("Loading {file_type} file: {file_name}"));
-                        ui.label(format!("({:.1}s)", start_time.elapsed().as_secs_f32()));
+                        ui.colored_label(egui::Color32::from_rgb(255, 193, 7), "⚠ Units mismatch:");
+                        ui.label(warning);
+                        if ui.small_button("Dismiss").clicked() {
+                            self.units_warning_dismissed = true;
+                        }
                     });
                 });
             }
         }
 
-        egui::SidePanel::left("left_panel")
+        let status = self.status_info();
+        self.status_bar.render(ctx, status);
+
+        let left_panel = egui::SidePanel::left("left_panel")
             .resizable(true)
-            .default_width(300.0)
+            .default_width(self.left_panel_width)
             .show(ctx, |ui| {
                 self.render_left_panel(ui);
             });
+        self.left_panel_width = left_panel.response.rect.width();
 
         if self.show_layers_panel {
-            egui::SidePanel::right("layers_panel")
+            let layers_panel = egui::SidePanel::right("layers_panel")
                 .resizable(true)
-                .default_width(250.0)
+                .default_width(self.layers_panel_width)
                 .show(ctx, |ui| {
                     self.render_layers_panel(ui);
                 });
+            self.layers_panel_width = layers_panel.response.rect.width();
+        }
+
+        if self.issues_panel.visible {
+            let libraries: Vec<(&str, &Lef)> = self
+                .lef_files
+                .iter()
+                .map(|f| (f.path.as_str(), &f.data))
+                .collect();
+            self.checks_panel
+                .update_design_report(self.def_data.as_ref(), &libraries);
+            if let Some(finding) = self
+                .issues_panel
+                .render(ctx, self.checks_panel.design_report())
+            {
+                self.focus_issue(&finding);
+            }
+        }
+
+        if let Some(code) = self.script_console.render(ctx) {
+            self.run_script(&code);
         }
 
         egui::CentralPanel::default().show(ctx, |ui| {
@@ -5314,6 +11339,167 @@ impl eframe::App for LefDefViewer {
             self.render_visualization(ui);
         });
 
+        if let Some(def) = &self.def_data {
+            self.component_table.render(
+                ctx,
+                def,
+                &mut self.selected_components,
+                &mut self.inspected_component,
+            );
+        }
+        if let Some(def) = &self.def_data {
+            let swap_candidates = self
+                .inspected_component
+                .as_ref()
+                .filter(|_| self.def_edit_mode)
+                .and_then(|name| def.components.iter().position(|c| &c.name == name))
+                .filter(|&index| !self.component_flags.is_locked(index))
+                .map(|index| {
+                    footprint::family_members(
+                        &self.footprint_families,
+                        &def.components[index].macro_name,
+                    )
+                })
+                .unwrap_or_default();
+            let connected_nets: Option<Vec<&str>> = self
+                .inspected_component
+                .as_ref()
+                .and_then(|name| self.precompute.connected_nets(name))
+                .map(|nets| {
+                    nets.iter()
+                        .filter_map(|&index| def.nets.get(index))
+                        .map(|net| net.name.as_str())
+                        .collect()
+                });
+            if let Some(to) = render_component_inspector(
+                ctx,
+                def,
+                &mut self.inspected_component,
+                swap_candidates,
+                connected_nets.as_deref(),
+            ) {
+                self.swap_inspected_component(&to);
+            }
+        }
+        if let Some(def) = &self.def_data {
+            let layer_order = self.routing_layer_names();
+            if render_net_inspector(
+                ctx,
+                def,
+                self.def_db_units(),
+                &layer_order,
+                &mut self.inspected_net,
+            ) {
+                self.handle_export_net_layer_usage();
+            }
+        }
+
+        // Library cell next to its placed instance, following the inspector
+        let mut dual_pane = std::mem::take(&mut self.dual_pane);
+        dual_pane.follow_inspected(self.inspected_component.as_deref());
+        dual_pane.render(
+            ctx,
+            self.def_data.as_ref(),
+            |name| {
+                self.lef_files
+                    .iter()
+                    .flat_map(|f| &f.data.macros)
+                    .find(|m| m.name == name)
+            },
+            |layer| self.get_layer_color(layer),
+        );
+        self.dual_pane = dual_pane;
+
+        if let Some(action) = self.edits_panel.render(
+            ctx,
+            &self.eco_log,
+            self.def_db_units(),
+            &mut self.selected_components,
+            &mut self.inspected_component,
+        ) {
+            self.handle_edit_action(action);
+        }
+        let libraries: Vec<(&str, &Lef)> = self
+            .lef_files
+            .iter()
+            .map(|f| (f.path.as_str(), &f.data))
+            .collect();
+        self.checks_panel.render(
+            ctx,
+            &mut self.selected_components,
+            &mut self.inspected_component,
+            self.def_data.as_ref(),
+            &libraries,
+        );
+        self.lef_diff_panel.render(ctx);
+        self.placement_diff_panel.render(
+            ctx,
+            &mut self.selected_components,
+            &mut self.inspected_component,
+        );
+        let mut export_requested = false;
+        self.pin_lint_dialog
+            .render(ctx, &libraries, &mut export_requested);
+        self.min_area_dialog.render(ctx, &libraries);
+        if export_requested {
+            self.handle_export_pin_lint_csv();
+        }
+        if self.layer_alias_dialog.render(ctx, &mut self.layer_aliases) {
+            self.apply_layer_aliases();
+        }
+
+        let macros: Vec<&crate::lef::LefMacro> =
+            self.lef_files.iter().flat_map(|f| &f.data.macros).collect();
+        self.charts_panel.render(
+            ctx,
+            &macros,
+            self.def_data.as_ref(),
+            &mut self.selected_cells,
+            &mut self.selected_components,
+            &mut self.selected_nets,
+        );
+
+        if self.resource_panel.needs_map() {
+            let map = self.compute_resource_map();
+            self.resource_panel.set_map(map);
+        }
+        self.resource_panel.render(ctx);
+
+        self.profiler_window.render(ctx);
+
+        // Per-source overlay transforms
+        let mut sources: Vec<(String, &mut SourceTransform)> = Vec::new();
+        if let Some(path) = &self.def_file_path {
+            let name = Path::new(path)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("DEF");
+            sources.push((format!("DEF: {name}"), &mut self.def_transform));
+        }
+        for lef_file in &mut self.lef_files {
+            let name = Path::new(&lef_file.path)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("LEF")
+                .to_string();
+            sources.push((format!("LEF: {name}"), &mut lef_file.transform));
+        }
+        self.overlay_dialog.render(ctx, &mut sources);
+
+        let die_bounds = self.die_bounds();
+        match self
+            .underlay_dialog
+            .render(ctx, self.underlay.as_mut(), die_bounds)
+        {
+            Some(UnderlayAction::Load) => self.handle_load_underlay(ctx),
+            Some(UnderlayAction::Remove) => self.underlay = None,
+            None => {}
+        }
+
+        // Batch PNG export captures the canvas drawn above
+        self.process_batch_png_export(ctx);
+        self.process_image_export();
+
         if self.show_lef_details {
             egui::Window::new("LEF Details")
                 .resizable(true)
@@ -5346,6 +11532,12 @@ impl eframe::App for LefDefViewer {
                                             ));
                                             ui.monospace(format!("Foreign: {}", macro_def.foreign));
                                             ui.monospace(format!("Pins: {}", macro_def.pins.len()));
+                                            if let Some(stats) = macro_def.density_stats() {
+                                                ui.monospace(format!(
+                                                    "Density: {} windows, {:.2}% to {:.2}%, avg {:.2}%",
+                                                    stats.windows, stats.min, stats.max, stats.mean
+                                                ));
+                                            }
                                         });
                                     }
                                 }
@@ -5387,3 +11579,40 @@ impl eframe::App for LefDefViewer {
         }
     }
 }
+
+/// Smallest box containing all `boxes`, None when there are none
+fn union_bounds(
+    boxes: impl IntoIterator<Item = (f32, f32, f32, f32)>,
+) -> Option<(f32, f32, f32, f32)> {
+    boxes
+        .into_iter()
+        .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)))
+}
+
+/// Forward the parser progress of `path` to the loading channel
+fn progress_sender(
+    tx: &mpsc::Sender<LoadingMessage>,
+    path: &str,
+) -> impl Fn(&Progress) + Send + 'static {
+    let tx = tx.clone();
+    let path = path.to_string();
+    move |progress| {
+        let _ = tx.send(LoadingMessage::Progress(path.clone(), progress.clone()));
+    }
+}
+
+/// Progress of all files being loaded, None before the first report
+fn combined_progress(progress: &BTreeMap<String, Progress>) -> Option<Progress> {
+    if progress.values().all(|p| p.section.is_empty()) {
+        return None;
+    }
+    if progress.len() == 1 {
+        return progress.values().next().cloned();
+    }
+    let done = progress.values().filter(|p| p.fraction >= 1.0).count();
+    Some(Progress {
+        fraction: progress.values().map(|p| p.fraction).sum::<f32>() / progress.len() as f32,
+        items: progress.values().map(|p| p.items).sum(),
+        section: format!("{done} of {} files", progress.len()),
+    })
+}
@@ -0,0 +1,730 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Hit-testing of the rendered geometry
+//!
+//! [`DesignIndex`] keeps R-trees of the placed components and the pin
+//! shapes of a DEF, built once after loading. The renderer asks it for the
+//! components in the viewport, and the objects under the cursor are found
+//! without scanning the whole design. The pins and OBS of a component are
+//! only tested once the cursor is inside it.
+//!
+//! All coordinates are in microns with Y pointing up.
+
+use std::collections::HashMap;
+
+use crate::def::Def;
+use crate::geometry::{polygon_contains, Bounds, MacroTransform};
+use crate::lef::{LefMacro, LefPolygon, LefRect};
+use crate::spatial::RTree;
+
+/// Size of the square a component without LEF macro is indexed as
+const UNKNOWN_MACRO_SIZE: f64 = 5.0;
+
+/// Bounds of an object that is not indexed, skipped by [`RTree::new`]
+const NOT_INDEXED: Bounds = (f64::NAN, f64::NAN, f64::NAN, f64::NAN);
+
+fn contains((min_x, min_y, max_x, max_y): Bounds, (x, y): (f64, f64)) -> bool {
+    x >= min_x && x <= max_x && y >= min_y && y <= max_y
+}
+
+fn overlaps(a: Bounds, b: Bounds) -> bool {
+    a.0 <= b.2 && b.0 <= a.2 && a.1 <= b.3 && b.1 <= a.3
+}
+
+fn area((min_x, min_y, max_x, max_y): Bounds) -> f64 {
+    (max_x - min_x) * (max_y - min_y)
+}
+
+/// Pin or OBS shape of a macro
+#[derive(Debug, Clone, PartialEq)]
+pub enum MacroShape {
+    Pin {
+        name: String,
+        layer: String,
+        bounds: Bounds,
+    },
+    Obstruction {
+        layer: String,
+        bounds: Bounds,
+    },
+}
+
+impl MacroShape {
+    pub fn bounds(&self) -> Bounds {
+        match self {
+            MacroShape::Pin { bounds, .. } | MacroShape::Obstruction { bounds, .. } => *bounds,
+        }
+    }
+
+    fn rows(&self, rows: &mut Vec<(String, String)>) {
+        match self {
+            MacroShape::Pin {
+                name,
+                layer,
+                bounds,
+            } => {
+                rows.push(("Pin".to_string(), name.clone()));
+                rows.push(("Layer".to_string(), layer.clone()));
+                rows.push(("Shape".to_string(), format_bounds(*bounds)));
+            }
+            MacroShape::Obstruction { layer, bounds } => {
+                rows.push(("Layer".to_string(), layer.clone()));
+                rows.push(("Shape".to_string(), format_bounds(*bounds)));
+            }
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            MacroShape::Pin { .. } => "Pin",
+            MacroShape::Obstruction { .. } => "Obstruction",
+        }
+    }
+}
+
+/// Object under the cursor in the DEF view
+#[derive(Debug, Clone, PartialEq)]
+pub enum Hit {
+    /// Component by index into `Def::components`
+    Component(usize),
+    /// Top-level DEF pin by index into `Def::pins`
+    Pin { index: usize, layer: String },
+    /// Pin or OBS shape of a placed component
+    Shape { component: usize, shape: MacroShape },
+}
+
+/// Spatial index of the components and pins of a DEF
+#[derive(Default)]
+pub struct DesignIndex {
+    /// Outlines by component index, unplaced components are left out
+    components: RTree,
+    component_bounds: Vec<Bounds>,
+    /// Pin shapes as (pin index, layer, bounds), ids of `pins`
+    pin_shapes: Vec<(usize, String, Bounds)>,
+    pins: RTree,
+}
+
+impl DesignIndex {
+    /// Index the placed components and the pins of `def`
+    ///
+    /// Components without LEF macro are indexed as 5 um squares, like the
+    /// placeholders they are drawn as.
+    pub fn build(def: &Def, db_units: f64, macros: &HashMap<&str, &LefMacro>) -> Self {
+        let component_bounds: Vec<Bounds> = def
+            .components
+            .iter()
+            .map(|component| {
+                let Some(placement) = &component.placement else {
+                    return NOT_INDEXED;
+                };
+                let location = (placement.x / db_units, placement.y / db_units);
+                match macros.get(component.macro_name.as_str()) {
+                    Some(macro_def) => {
+                        MacroTransform::new(macro_def, location, &placement.orientation)
+                            .outline_bbox()
+                    }
+                    None => (
+                        location.0,
+                        location.1,
+                        location.0 + UNKNOWN_MACRO_SIZE,
+                        location.1 + UNKNOWN_MACRO_SIZE,
+                    ),
+                }
+            })
+            .collect();
+
+        let mut pin_shapes = Vec::new();
+        for (index, pin) in def.pins.iter().enumerate() {
+            let rects = pin.placed_rects();
+            if rects.is_empty() {
+                // A pin without shapes is picked within 1 um of its location
+                let (x, y) = (pin.x / db_units, pin.y / db_units);
+                pin_shapes.push((index, String::new(), (x - 1.0, y - 1.0, x + 1.0, y + 1.0)));
+            }
+            pin_shapes.extend(rects.into_iter().map(|r| {
+                let bounds = (
+                    r.xl / db_units,
+                    r.yl / db_units,
+                    r.xh / db_units,
+                    r.yh / db_units,
+                );
+                (index, r.layer, bounds)
+            }));
+        }
+
+        Self {
+            components: RTree::new(component_bounds.iter().copied()),
+            component_bounds,
+            pins: RTree::new(pin_shapes.iter().map(|(_, _, bounds)| *bounds)),
+            pin_shapes,
+        }
+    }
+
+    /// Indices of the components whose outline touches `area`, in DEF order
+    pub fn components_in(&self, area: Bounds) -> Vec<usize> {
+        self.components.query(area)
+    }
+
+    /// Outline of the component at `index`, if it is placed
+    pub fn component_bounds(&self, index: usize) -> Option<Bounds> {
+        self.component_bounds
+            .get(index)
+            .copied()
+            .filter(|bounds| !bounds.0.is_nan())
+    }
+
+    /// Most specific object at `point`
+    ///
+    /// Pins win over the shapes inside a component, and those over the
+    /// component itself. Among overlapping objects of a kind the smallest
+    /// one is picked. Macro shapes count only if `is_visible` accepts their
+    /// layer key, such as `M1.PIN` or `M1.OBS`.
+    pub fn pick(
+        &self,
+        def: &Def,
+        db_units: f64,
+        macros: &HashMap<&str, &LefMacro>,
+        point: (f64, f64),
+        is_visible: impl Fn(&str) -> bool,
+    ) -> Option<Hit> {
+        let mut pin: Option<(f64, Hit)> = None;
+        let mut shape: Option<(f64, Hit)> = None;
+        let mut component: Option<(f64, Hit)> = None;
+        let keep_smallest = |best: &mut Option<(f64, Hit)>, size: f64, hit: Hit| {
+            if best.as_ref().is_none_or(|(s, _)| size < *s) {
+                *best = Some((size, hit));
+            }
+        };
+
+        for id in self.pins.query_point(point) {
+            let (index, layer, bounds) = &self.pin_shapes[id];
+            let hit = Hit::Pin {
+                index: *index,
+                layer: layer.clone(),
+            };
+            keep_smallest(&mut pin, area(*bounds), hit);
+        }
+
+        for index in self.components.query_point(point) {
+            let outline = self.component_bounds[index];
+            keep_smallest(&mut component, area(outline), Hit::Component(index));
+            if let Some(found) =
+                pick_component_shape(def, db_units, macros, index, point, &is_visible)
+            {
+                let size = area(found.bounds());
+                let hit = Hit::Shape {
+                    component: index,
+                    shape: found,
+                };
+                keep_smallest(&mut shape, size, hit);
+            }
+        }
+
+        pin.or(shape).or(component).map(|(_, hit)| hit)
+    }
+
+    /// Bounds of the component outlines, pin shapes and macro shapes that
+    /// touch `area`, e.g. the edges a measurement can snap to
+    ///
+    /// Pins without shapes are left out. Macro shapes count only if
+    /// `is_visible` accepts their layer key, as in [`DesignIndex::pick`].
+    pub fn shapes_in(
+        &self,
+        def: &Def,
+        db_units: f64,
+        macros: &HashMap<&str, &LefMacro>,
+        area: Bounds,
+        is_visible: impl Fn(&str) -> bool,
+    ) -> Vec<Bounds> {
+        let mut shapes: Vec<Bounds> = self
+            .pins
+            .query(area)
+            .into_iter()
+            .map(|id| &self.pin_shapes[id])
+            .filter(|(_, layer, _)| !layer.is_empty())
+            .map(|(_, _, bounds)| *bounds)
+            .collect();
+
+        for index in self.components.query(area) {
+            shapes.push(self.component_bounds[index]);
+            let component = &def.components[index];
+            let (Some(placement), Some(macro_def)) = (
+                &component.placement,
+                macros.get(component.macro_name.as_str()),
+            ) else {
+                continue;
+            };
+            let transform = MacroTransform::new(
+                macro_def,
+                (placement.x / db_units, placement.y / db_units),
+                &placement.orientation,
+            );
+            let pin_rects = macro_def
+                .pins
+                .iter()
+                .flat_map(|pin| &pin.ports)
+                .flat_map(|port| &port.rects)
+                .map(|rect| (rect, "PIN"));
+            let obs_rects = macro_def
+                .obs
+                .iter()
+                .flat_map(|obs| &obs.rects)
+                .map(|rect| (rect, "OBS"));
+            for (rect, kind) in pin_rects.chain(obs_rects) {
+                let bounds = transform.apply_rect(rect.xl, rect.yl, rect.xh, rect.yh);
+                if overlaps(bounds, area) && is_visible(&format!("{}.{kind}", rect.layer)) {
+                    shapes.push(bounds);
+                }
+            }
+        }
+        shapes
+    }
+}
+
+/// Pin or OBS shape of a placed component at `point`
+fn pick_component_shape(
+    def: &Def,
+    db_units: f64,
+    macros: &HashMap<&str, &LefMacro>,
+    index: usize,
+    point: (f64, f64),
+    is_visible: &impl Fn(&str) -> bool,
+) -> Option<MacroShape> {
+    let component = def.components.get(index)?;
+    let placement = component.placement.as_ref()?;
+    let macro_def = macros.get(component.macro_name.as_str())?;
+    let transform = MacroTransform::new(
+        macro_def,
+        (placement.x / db_units, placement.y / db_units),
+        &placement.orientation,
+    );
+    find_macro_shape(macro_def, |p| transform.apply(p), point, is_visible)
+}
+
+/// Pin or OBS shape of an unplaced macro at `point`, in LEF coordinates
+///
+/// Pins win over obstructions, see [`DesignIndex::pick`] for `is_visible`.
+pub fn pick_macro_shape(
+    macro_def: &LefMacro,
+    point: (f64, f64),
+    is_visible: impl Fn(&str) -> bool,
+) -> Option<MacroShape> {
+    find_macro_shape(macro_def, |p| p, point, &is_visible)
+}
+
+/// Bounding box of the points a shape covers
+fn bounds_of(points: impl IntoIterator<Item = (f64, f64)>) -> Bounds {
+    points.into_iter().fold(
+        (
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ),
+        |b, (x, y)| (b.0.min(x), b.1.min(y), b.2.max(x), b.3.max(y)),
+    )
+}
+
+/// Bounds of the RECT or polygon at `point`, after `place` moved the shape
+/// coordinates to the coordinates of `point`
+///
+/// Polygons, e.g. with 45-degree edges, are hit inside their outline
+/// only, not anywhere in their bounding box. LEF has no holes, so a
+/// clockwise polygon is hit like any other.
+fn hit_bounds(
+    rect: Option<&LefRect>,
+    polygon: Option<&LefPolygon>,
+    place: &impl Fn((f64, f64)) -> (f64, f64),
+    point: (f64, f64),
+) -> Option<Bounds> {
+    if let Some(rect) = rect {
+        let corners = [
+            (rect.xl, rect.yl),
+            (rect.xh, rect.yl),
+            (rect.xh, rect.yh),
+            (rect.xl, rect.yh),
+        ];
+        let bounds = bounds_of(corners.map(place));
+        return contains(bounds, point).then_some(bounds);
+    }
+    let polygon = polygon?;
+    let points: Vec<(f64, f64)> = polygon.points.iter().map(|&p| place(p)).collect();
+    let bounds = bounds_of(points.iter().copied());
+    (contains(bounds, point) && polygon_contains(&points, point)).then_some(bounds)
+}
+
+/// RECTs and polygons of some ports or obstructions with their layer
+fn layer_shapes<'a>(
+    rects: impl Iterator<Item = &'a LefRect>,
+    polygons: impl Iterator<Item = &'a LefPolygon>,
+) -> impl Iterator<Item = (&'a String, Option<&'a LefRect>, Option<&'a LefPolygon>)> {
+    let rects = rects.map(|r| (&r.layer, Some(r), None));
+    rects.chain(polygons.map(|p| (&p.layer, None, Some(p))))
+}
+
+fn find_macro_shape(
+    macro_def: &LefMacro,
+    place: impl Fn((f64, f64)) -> (f64, f64),
+    point: (f64, f64),
+    is_visible: &impl Fn(&str) -> bool,
+) -> Option<MacroShape> {
+    let smallest = |found: Option<MacroShape>, shape: MacroShape| match found {
+        Some(best) if area(best.bounds()) <= area(shape.bounds()) => Some(best),
+        _ => Some(shape),
+    };
+    let mut found = None;
+    for pin in &macro_def.pins {
+        let pin_shapes = layer_shapes(
+            pin.ports.iter().flat_map(|port| &port.rects),
+            pin.ports.iter().flat_map(|port| &port.polygons),
+        );
+        for (layer, rect, polygon) in pin_shapes {
+            if !is_visible(&format!("{layer}.PIN")) {
+                continue;
+            }
+            if let Some(bounds) = hit_bounds(rect, polygon, &place, point) {
+                let shape = MacroShape::Pin {
+                    name: pin.name.clone(),
+                    layer: layer.clone(),
+                    bounds,
+                };
+                found = smallest(found, shape);
+            }
+        }
+    }
+    if found.is_some() {
+        return found;
+    }
+    let obs_shapes = layer_shapes(
+        macro_def.obs.iter().flat_map(|obs| &obs.rects),
+        macro_def.obs.iter().flat_map(|obs| &obs.polygons),
+    );
+    for (layer, rect, polygon) in obs_shapes {
+        if !is_visible(&format!("{layer}.OBS")) {
+            continue;
+        }
+        if let Some(bounds) = hit_bounds(rect, polygon, &place, point) {
+            let shape = MacroShape::Obstruction {
+                layer: layer.clone(),
+                bounds,
+            };
+            found = smallest(found, shape);
+        }
+    }
+    found
+}
+
+fn format_bounds((min_x, min_y, max_x, max_y): Bounds) -> String {
+    format!("({min_x:.3}, {min_y:.3}) - ({max_x:.3}, {max_y:.3})")
+}
+
+/// Tooltip title and rows of a macro shape in the LEF view
+pub fn describe_macro_shape(
+    macro_def: &LefMacro,
+    shape: &MacroShape,
+) -> (String, Vec<(String, String)>) {
+    let mut rows = Vec::new();
+    shape.rows(&mut rows);
+    rows.push(("Macro".to_string(), macro_def.name.clone()));
+    (shape.title().to_string(), rows)
+}
+
+/// Tooltip title and (label, value) rows of a hit in the DEF view
+pub fn describe(def: &Def, db_units: f64, hit: &Hit) -> (String, Vec<(String, String)>) {
+    let component_rows = |index: usize, rows: &mut Vec<(String, String)>| {
+        let Some(component) = def.components.get(index) else {
+            return;
+        };
+        rows.push(("Instance".to_string(), component.name.clone()));
+        rows.push(("Macro".to_string(), component.macro_name.clone()));
+        if let Some(placement) = &component.placement {
+            rows.push(("Placement".to_string(), placement.placement_type.clone()));
+            rows.push(("Orientation".to_string(), placement.orientation.clone()));
+            rows.push((
+                "Location".to_string(),
+                format!(
+                    "({:.3}, {:.3})",
+                    placement.x / db_units,
+                    placement.y / db_units
+                ),
+            ));
+        }
+    };
+
+    let mut rows = Vec::new();
+    let title = match hit {
+        Hit::Component(index) => {
+            component_rows(*index, &mut rows);
+            "Component".to_string()
+        }
+        Hit::Pin { index, layer } => {
+            if let Some(pin) = def.pins.get(*index) {
+                rows.push(("Pin".to_string(), pin.name.clone()));
+                rows.push(("Net".to_string(), pin.net.clone()));
+                rows.push(("Direction".to_string(), pin.direction.clone()));
+                if !layer.is_empty() {
+                    rows.push(("Layer".to_string(), layer.clone()));
+                }
+                rows.push(("Placement".to_string(), pin.status.clone()));
+                rows.push(("Orientation".to_string(), pin.orient.clone()));
+                rows.push((
+                    "Location".to_string(),
+                    format!("({:.3}, {:.3})", pin.x / db_units, pin.y / db_units),
+                ));
+            }
+            "Pin".to_string()
+        }
+        Hit::Shape { component, shape } => {
+            shape.rows(&mut rows);
+            component_rows(*component, &mut rows);
+            format!("Component {}", shape.title())
+        }
+    };
+    (title, rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lef::{LefObstruction, LefPin, LefPort};
+    use serde_json::json;
+
+    fn lef_rect(layer: &str, xl: f64, yl: f64, xh: f64, yh: f64) -> LefRect {
+        LefRect {
+            layer: layer.to_string(),
+            xl,
+            yl,
+            xh,
+            yh,
+        }
+    }
+
+    fn inv() -> LefMacro {
+        LefMacro {
+            name: "INV".to_string(),
+            class: "CORE".to_string(),
+            foreign: String::new(),
+            origin: (0.0, 0.0),
+            size_x: 2.0,
+            size_y: 4.0,
+            symmetry: Vec::new(),
+            site: String::new(),
+            pins: vec![LefPin {
+                name: "A".to_string(),
+                direction: "INPUT".to_string(),
+                use_type: "SIGNAL".to_string(),
+                shape: String::new(),
+                ports: vec![LefPort {
+                    rects: vec![lef_rect("M1", 0.2, 0.2, 0.6, 1.0)],
+                    polygons: Vec::new(),
+                }],
+            }],
+            obs: vec![LefObstruction {
+                rects: vec![lef_rect("M1", 1.0, 0.0, 2.0, 4.0)],
+                polygons: Vec::new(),
+            }],
+            ..LefMacro::default()
+        }
+    }
+
+    fn design() -> Def {
+        serde_json::from_value(json!({
+            "design": "top",
+            "die_area_points": [[0.0, 0.0], [100000.0, 100000.0]],
+            "g_cell_grid_x": [], "g_cell_grid_y": [],
+            "pins": [{
+                "name": "clk", "net": "clk", "use_type": "SIGNAL", "status": "PLACED",
+                "direction": "INPUT", "orient": "N", "x": 50000.0, "y": 0.0,
+                "rects": [{"layer": "M2", "xl": -100.0, "yl": 0.0, "xh": 100.0, "yh": 200.0}],
+                "ports": []
+            }],
+            "nets": [], "special_nets": [],
+            "components": [
+                {"name": "u1", "macro_name": "INV",
+                 "placement": {"placement_type": "PLACED", "x": 10000.0, "y": 10000.0, "orientation": "N"},
+                 "routing_halo": null, "source": null, "weight": null, "eeq": null,
+                 "generate": null, "power": null, "ground": null, "properties": []},
+                {"name": "u2", "macro_name": "INV",
+                 "placement": {"placement_type": "FIXED", "x": 20000.0, "y": 10000.0, "orientation": "S"},
+                 "routing_halo": null, "source": null, "weight": null, "eeq": null,
+                 "generate": null, "power": null, "ground": null, "properties": []}
+            ],
+            "rows": [], "tracks_x": [], "tracks_y": [], "vias": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_components_in_viewport() {
+        let def = design();
+        let macro_def = inv();
+        let macros = HashMap::from([("INV", &macro_def)]);
+        let index = DesignIndex::build(&def, 1000.0, &macros);
+
+        assert_eq!(index.components_in((0.0, 0.0, 100.0, 100.0)), [0, 1]);
+        assert_eq!(index.components_in((15.0, 0.0, 21.0, 11.0)), [1]);
+        assert!(index.components_in((30.0, 30.0, 40.0, 40.0)).is_empty());
+    }
+
+    #[test]
+    fn test_shapes_in_area() {
+        let def = design();
+        let macro_def = inv();
+        let macros = HashMap::from([("INV", &macro_def)]);
+        let index = DesignIndex::build(&def, 1000.0, &macros);
+
+        // Outline and pin of u1, the OBS layer is hidden
+        let shapes = index.shapes_in(&def, 1000.0, &macros, (10.0, 10.0, 10.5, 10.5), |key| {
+            key == "M1.PIN"
+        });
+        assert_eq!(shapes, [(10.0, 10.0, 12.0, 14.0), (10.2, 10.2, 10.6, 11.0)]);
+
+        let shapes = index.shapes_in(&def, 1000.0, &macros, (49.0, 0.0, 51.0, 1.0), |_| true);
+        assert_eq!(shapes, [(49.9, 0.0, 50.1, 0.2)]);
+    }
+
+    #[test]
+    fn test_pick_most_specific_object() {
+        let def = design();
+        let macro_def = inv();
+        let macros = HashMap::from([("INV", &macro_def)]);
+        let hits = DesignIndex::build(&def, 1000.0, &macros);
+        let pick = |point| hits.pick(&def, 1000.0, &macros, point, |_| true);
+
+        // Pin A of u1, inside the component outline
+        match pick((10.4, 10.5)) {
+            Some(Hit::Shape {
+                component: 0,
+                shape: MacroShape::Pin { name, layer, .. },
+            }) => assert_eq!((name.as_str(), layer.as_str()), ("A", "M1")),
+            other => panic!("expected pin A, got {other:?}"),
+        }
+        // OBS of u1
+        assert!(matches!(
+            pick((11.5, 12.0)),
+            Some(Hit::Shape {
+                component: 0,
+                shape: MacroShape::Obstruction { .. }
+            })
+        ));
+        // Empty part of u1
+        assert_eq!(pick((10.8, 13.0)), Some(Hit::Component(0)));
+        // u2 is rotated by 180 degrees, so its OBS is on the left
+        assert!(matches!(
+            pick((20.5, 12.0)),
+            Some(Hit::Shape {
+                component: 1,
+                shape: MacroShape::Obstruction { .. }
+            })
+        ));
+        // Top-level pin
+        assert_eq!(
+            pick((50.0, 0.1)),
+            Some(Hit::Pin {
+                index: 0,
+                layer: "M2".to_string()
+            })
+        );
+        assert_eq!(pick((70.0, 70.0)), None);
+
+        // Shapes on hidden layers are skipped
+        assert_eq!(
+            hits.pick(&def, 1000.0, &macros, (10.4, 10.5), |key| key != "M1.PIN"),
+            Some(Hit::Component(0))
+        );
+        assert!(matches!(
+            pick_macro_shape(&macro_def, (1.5, 3.0), |_| true),
+            Some(MacroShape::Obstruction { .. })
+        ));
+    }
+
+    #[test]
+    fn test_pick_diagonal_polygon() {
+        // Triangle with a 45-degree edge in the free upper left of INV
+        let mut macro_def = inv();
+        macro_def.pins[0].ports[0].polygons.push(LefPolygon {
+            layer: "M2".to_string(),
+            points: vec![(0.0, 2.0), (1.0, 2.0), (0.0, 3.0)],
+            is_hole: false,
+        });
+        let def = design();
+        let macros = HashMap::from([("INV", &macro_def)]);
+        let hits = DesignIndex::build(&def, 1000.0, &macros);
+        let pick = |point| hits.pick(&def, 1000.0, &macros, point, |_| true);
+
+        match pick((10.2, 12.2)) {
+            Some(Hit::Shape {
+                shape: MacroShape::Pin { layer, bounds, .. },
+                ..
+            }) => {
+                assert_eq!(layer, "M2");
+                assert_eq!(bounds, (10.0, 12.0, 11.0, 13.0));
+            }
+            other => panic!("expected pin A, got {other:?}"),
+        }
+        // Inside the bounding box, beyond the diagonal edge
+        assert_eq!(pick((10.8, 12.8)), Some(Hit::Component(0)));
+        // Rotated by 180 degrees in u2
+        assert!(matches!(
+            pick((21.8, 11.8)),
+            Some(Hit::Shape {
+                component: 1,
+                shape: MacroShape::Pin { .. }
+            })
+        ));
+        assert_eq!(pick((21.2, 11.2)), Some(Hit::Component(1)));
+        assert!(pick_macro_shape(&macro_def, (0.2, 2.7), |_| true).is_some());
+        assert!(pick_macro_shape(&macro_def, (0.4, 2.7), |_| true).is_none());
+    }
+
+    #[test]
+    fn test_pick_clockwise_polygon() {
+        // Same triangle as above, listed clockwise as the parser marks holes
+        let mut macro_def = inv();
+        macro_def.pins[0].ports[0].polygons.push(LefPolygon {
+            layer: "M2".to_string(),
+            points: vec![(0.0, 2.0), (0.0, 3.0), (1.0, 2.0)],
+            is_hole: true,
+        });
+        let def = design();
+        let macros = HashMap::from([("INV", &macro_def)]);
+        let hits = DesignIndex::build(&def, 1000.0, &macros);
+
+        assert!(matches!(
+            hits.pick(&def, 1000.0, &macros, (10.2, 12.2), |_| true),
+            Some(Hit::Shape {
+                component: 0,
+                shape: MacroShape::Pin { .. }
+            })
+        ));
+        assert!(pick_macro_shape(&macro_def, (0.2, 2.7), |_| true).is_some());
+    }
+
+    #[test]
+    fn test_describe_component_pin() {
+        let def = design();
+        let hit = Hit::Shape {
+            component: 1,
+            shape: MacroShape::Pin {
+                name: "A".to_string(),
+                layer: "M1".to_string(),
+                bounds: (21.4, 13.0, 21.8, 13.8),
+            },
+        };
+        let (title, rows) = describe(&def, 1000.0, &hit);
+        assert_eq!(title, "Component Pin");
+        let value = |label: &str| {
+            rows.iter()
+                .find(|(l, _)| l == label)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(value("Pin"), Some("A"));
+        assert_eq!(value("Instance"), Some("u2"));
+        assert_eq!(value("Macro"), Some("INV"));
+        assert_eq!(value("Placement"), Some("FIXED"));
+        assert_eq!(value("Orientation"), Some("S"));
+        assert_eq!(value("Location"), Some("(20.000, 10.000)"));
+        assert_eq!(value("Layer"), Some("M1"));
+    }
+}
@@ -0,0 +1,269 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Identifier normalization shared by the parsers, search and export
+//!
+//! LEF/DEF names may escape special characters with a backslash, e.g.
+//! `\u_top/reg\[3\]` is the instance `u_top/reg[3]` whose brackets are part
+//! of the name rather than a bus bit. An unescaped BUSBITCHARS pair at the
+//! end of a name (`data[3]` or `data<3>`) is a real bus bit.
+//!
+//! Parsers keep names exactly as written in the file so that writers and
+//! cross references round-trip. Display, search and bus grouping go through
+//! the helpers in this module.
+
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Bus bit delimiters declared by the BUSBITCHARS statement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BusBitChars {
+    pub open: char,
+    pub close: char,
+}
+
+impl Default for BusBitChars {
+    fn default() -> Self {
+        Self {
+            open: '[',
+            close: ']',
+        }
+    }
+}
+
+impl BusBitChars {
+    /// Parse the value of a BUSBITCHARS statement, e.g. `"[]"` or `"<>"`
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().trim_end_matches(';').trim().trim_matches('"');
+        let mut chars = value.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(open), Some(close), None) if open != close => Some(Self { open, close }),
+            _ => None,
+        }
+    }
+}
+
+/// Read the identifier at the start of a token
+///
+/// Everything up to whitespace or an unescaped `;` belongs to the name, and
+/// a backslash escapes the following character. Returns the raw (still
+/// escaped) name.
+pub fn parse_escaped_token(input: &str) -> Option<&str> {
+    let mut escaped = false;
+    let mut end = 0;
+
+    for (i, c) in input.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c.is_whitespace() || c == ';' {
+            break;
+        }
+        end = i + c.len_utf8();
+    }
+
+    if end > 0 {
+        Some(&input[..end])
+    } else {
+        None
+    }
+}
+
+/// Remove backslash escapes from a name
+pub fn unescape(name: &str) -> Cow<'_, str> {
+    if !name.contains('\\') {
+        return Cow::Borrowed(name);
+    }
+
+    let mut result = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(next) = chars.next() {
+                result.push(next);
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    Cow::Owned(result)
+}
+
+/// Escape characters that would otherwise be read as bus bits or separators
+#[allow(dead_code)]
+pub fn escape(name: &str, bus_bit_chars: BusBitChars) -> String {
+    let mut result = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c == '\\' || c == ';' || c == bus_bit_chars.open || c == bus_bit_chars.close {
+            result.push('\\');
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Name as shown to the user: escapes removed, bus bits written with `[]`
+pub fn display_name(name: &str, bus_bit_chars: BusBitChars) -> String {
+    match split_bus_bit(name, bus_bit_chars) {
+        Some((base, index)) => format!("{}[{index}]", unescape(base)),
+        None => unescape(name).into_owned(),
+    }
+}
+
+/// Split an unescaped trailing bus bit, e.g. `data[3]` into (`data`, 3)
+///
+/// Escaped delimiters are part of the name and never form a bus bit. The
+/// returned base name is still escaped.
+pub fn split_bus_bit(name: &str, bus_bit_chars: BusBitChars) -> Option<(&str, usize)> {
+    let body = name.strip_suffix(bus_bit_chars.close)?;
+    if is_escaped_at(name, body.len()) {
+        return None;
+    }
+
+    let open = body.rfind(bus_bit_chars.open)?;
+    if open == 0 || is_escaped_at(name, open) {
+        return None;
+    }
+
+    let index = body[open + bus_bit_chars.open.len_utf8()..]
+        .parse::<usize>()
+        .ok()?;
+    Some((&name[..open], index))
+}
+
+/// Split a bus bit using either of the common `[]` and `<>` conventions
+///
+/// LEF pin names are matched this way because libraries mix both styles
+/// regardless of the declared BUSBITCHARS.
+pub fn split_any_bus_bit(name: &str) -> Option<(&str, usize)> {
+    split_bus_bit(name, BusBitChars::default()).or_else(|| {
+        split_bus_bit(
+            name,
+            BusBitChars {
+                open: '<',
+                close: '>',
+            },
+        )
+    })
+}
+
+/// Case-insensitive substring match of a name against a search query
+///
+/// Both sides are unescaped first, so `reg[3]` finds `\u_top/reg\[3\]` and
+/// so does the query `reg\[3\]`.
+pub fn matches_query(name: &str, query: &str) -> bool {
+    if query.is_empty() {
+        return true;
+    }
+    unescape(name)
+        .to_lowercase()
+        .contains(&unescape(query).to_lowercase())
+}
+
+/// Name pattern match with `*` matching any sequence and `?` any one
+/// character, as in DEF GROUPS; the whole name has to match
+pub fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let (pattern, name) = (pattern.as_bytes(), name.as_bytes());
+    let (mut p, mut n) = (0, 0);
+    // Position after the last `*` and the name position it was tried at
+    let mut backtrack = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                p += 1;
+                backtrack = Some((p, n));
+            }
+            Some(&c) if c == b'?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((bp, bn)) => {
+                    p = bp;
+                    n = bn + 1;
+                    backtrack = Some((bp, bn + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+/// Whether the character starting at byte offset `pos` is escaped
+fn is_escaped_at(name: &str, pos: usize) -> bool {
+    let backslashes = name[..pos].chars().rev().take_while(|&c| c == '\\').count();
+    backslashes % 2 == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_escaped_token() {
+        assert_eq!(
+            parse_escaped_token(r"\u_top/reg\[3\] + PLACED"),
+            Some(r"\u_top/reg\[3\]")
+        );
+        assert_eq!(parse_escaped_token("net_1;"), Some("net_1"));
+        assert_eq!(parse_escaped_token(r"a\;b ;"), Some(r"a\;b"));
+        assert_eq!(parse_escaped_token("net$12"), Some("net$12"));
+        assert_eq!(parse_escaped_token(" x"), None);
+    }
+
+    #[test]
+    fn test_unescape_and_escape_round_trip() {
+        let raw = r"\u_top/reg\[3\]";
+        assert_eq!(unescape(raw), "u_top/reg[3]");
+        assert_eq!(
+            escape("u_top/reg[3]", BusBitChars::default()),
+            r"u_top/reg\[3\]"
+        );
+        assert!(matches!(unescape("plain"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_bus_bits_respect_escapes() {
+        let brackets = BusBitChars::default();
+        assert_eq!(split_bus_bit("data[3]", brackets), Some(("data", 3)));
+        assert_eq!(split_bus_bit(r"reg\[3\]", brackets), None);
+        assert_eq!(
+            split_bus_bit(r"\a\[1\][2]", brackets),
+            Some((r"\a\[1\]", 2))
+        );
+        assert_eq!(split_bus_bit("data[x]", brackets), None);
+        assert_eq!(split_any_bus_bit("addr<7>"), Some(("addr", 7)));
+
+        let angle = BusBitChars::parse("\"<>\"").unwrap();
+        assert_eq!(split_bus_bit("data<3>", angle), Some(("data", 3)));
+        assert_eq!(split_bus_bit("data[3]", angle), None);
+        assert_eq!(display_name("data<3>", angle), "data[3]");
+    }
+
+    #[test]
+    fn test_matches_query() {
+        assert!(matches_query(r"\u_top/reg\[3\]", "REG[3]"));
+        assert!(matches_query(r"\u_top/reg\[3\]", r"reg\[3\]"));
+        assert!(matches_query("anything", ""));
+        assert!(!matches_query("u_top/reg_3", "reg[3]"));
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*.OBS", "metal1.OBS"));
+        assert!(wildcard_match("VIA*", "VIA12"));
+        assert!(wildcard_match("M?.PIN", "M1.PIN"));
+        assert!(wildcard_match("a*b*c", "axxbyyc"));
+        assert!(!wildcard_match("VIA*", "metal1.ROUTE"));
+        assert!(!wildcard_match("M?.PIN", "M10.PIN"));
+        assert!(!wildcard_match("u1", "u10"));
+    }
+
+    #[test]
+    fn test_bus_bit_chars_parse() {
+        assert_eq!(BusBitChars::parse("\"[]\" ;"), Some(BusBitChars::default()));
+        assert_eq!(BusBitChars::parse("\"[\""), None);
+    }
+}
@@ -0,0 +1,343 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Image export dialog
+//!
+//! Picks the part of the design and the resolution of an off-screen render,
+//! see [`crate::png_export::OffscreenScene`], or of an SVG snapshot. The part
+//! is the view, the full design or exact bounds in microns, typed or taken
+//! from the ruler. The resolution is a scale of the canvas, a width in
+//! pixels or a fixed number of microns per pixel, so it does not depend on
+//! the window; the same bounds at the same microns per pixel give images of
+//! different revisions that line up pixel for pixel.
+
+use crate::png_export::{render_size, MAX_RENDER_SIZE};
+use eframe::egui;
+
+/// Part of the design to render
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageRegion {
+    /// What the canvas shows now
+    View,
+    /// The die area, or all shown cells in the LEF view
+    Design,
+    /// Bounds typed in the dialog, in DEF microns
+    Bounds,
+}
+
+/// File format of the export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Svg,
+}
+
+/// How the output resolution is given
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SizeMode {
+    /// Output pixels per canvas point
+    Scale,
+    /// Output width in pixels
+    Width,
+    /// Microns of the design per output pixel
+    MicronsPerPixel,
+}
+
+/// World bounds (min x, min y, max x, max y) in microns
+type WorldBounds = (f32, f32, f32, f32);
+
+/// Export settings confirmed in the dialog
+#[derive(Debug, Clone, Copy)]
+pub struct ImageExportRequest {
+    pub region: ImageRegion,
+    /// Bounds of [`ImageRegion::Bounds`]
+    pub bounds: WorldBounds,
+    pub format: ImageFormat,
+    /// Output width in pixels, the height follows the region
+    pub width: u32,
+}
+
+/// Image export dialog state and rendering
+pub struct ImageExportDialog {
+    /// Whether the dialog is currently shown
+    pub visible: bool,
+    region: ImageRegion,
+    format: ImageFormat,
+    size_mode: SizeMode,
+    scale: f32,
+    width: u32,
+    microns_per_pixel: f32,
+    /// Bounds of [`ImageRegion::Bounds`], kept between exports
+    bounds: WorldBounds,
+    /// Canvas size in points when the dialog was opened
+    canvas: egui::Vec2,
+    /// Bounds of the view, the design and the ruler segment when the
+    /// dialog was opened
+    view_bounds: Option<WorldBounds>,
+    design_bounds: Option<WorldBounds>,
+    ruler_bounds: Option<WorldBounds>,
+    /// Whether exact bounds can be given, only in the DEF view
+    bounds_available: bool,
+}
+
+impl Default for ImageExportDialog {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            region: ImageRegion::View,
+            format: ImageFormat::Png,
+            size_mode: SizeMode::Scale,
+            scale: 2.0,
+            width: 4096,
+            microns_per_pixel: 0.01,
+            bounds: (0.0, 0.0, 0.0, 0.0),
+            canvas: egui::Vec2::ZERO,
+            view_bounds: None,
+            design_bounds: None,
+            ruler_bounds: None,
+            bounds_available: false,
+        }
+    }
+}
+
+impl ImageExportDialog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the dialog for a canvas showing `view_bounds`
+    ///
+    /// `ruler_bounds` is the box spanned by the ruler segment, if any.
+    /// Exact bounds are offered only with `bounds_available`, in the DEF
+    /// view.
+    pub fn open(
+        &mut self,
+        canvas: egui::Rect,
+        view_bounds: Option<WorldBounds>,
+        design_bounds: Option<WorldBounds>,
+        ruler_bounds: Option<WorldBounds>,
+        bounds_available: bool,
+    ) {
+        self.visible = true;
+        self.canvas = canvas.size();
+        self.view_bounds = view_bounds;
+        self.design_bounds = design_bounds;
+        self.ruler_bounds = ruler_bounds;
+        self.bounds_available = bounds_available;
+        if !bounds_available && self.region == ImageRegion::Bounds {
+            self.region = ImageRegion::View;
+        }
+        if !has_area(self.bounds) {
+            if let Some(bounds) = ruler_bounds.or(view_bounds) {
+                self.bounds = bounds;
+            }
+        }
+    }
+
+    /// World bounds of the chosen region
+    fn region_bounds(&self) -> Option<WorldBounds> {
+        match self.region {
+            ImageRegion::View => self.view_bounds,
+            ImageRegion::Design => self.design_bounds,
+            ImageRegion::Bounds => Some(self.bounds).filter(|b| has_area(*b)),
+        }
+    }
+
+    fn output_width(&self) -> u32 {
+        match self.size_mode {
+            SizeMode::Scale => {
+                let canvas = egui::Rect::from_min_size(egui::Pos2::ZERO, self.canvas);
+                render_size(canvas, self.scale)[0]
+            }
+            SizeMode::Width => self.width,
+            SizeMode::MicronsPerPixel => self
+                .region_bounds()
+                .map_or(1, |b| width_at_scale(b.2 - b.0, self.microns_per_pixel)),
+        }
+    }
+
+    /// Render the dialog, returning the settings once Export is clicked
+    pub fn render(&mut self, ctx: &egui::Context) -> Option<ImageExportRequest> {
+        if !self.visible {
+            return None;
+        }
+
+        let mut open = true;
+        let mut request = None;
+
+        egui::Window::new("Export Image")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Format:");
+                    ui.radio_value(&mut self.format, ImageFormat::Png, "PNG");
+                    ui.radio_value(&mut self.format, ImageFormat::Svg, "SVG");
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Region:");
+                    ui.radio_value(&mut self.region, ImageRegion::View, "Current view");
+                    ui.radio_value(&mut self.region, ImageRegion::Design, "Full design");
+                    ui.add_enabled_ui(self.bounds_available, |ui| {
+                        ui.radio_value(&mut self.region, ImageRegion::Bounds, "Bounds")
+                            .on_disabled_hover_text("Exact bounds are given in the DEF view");
+                    });
+                });
+                if self.region == ImageRegion::Bounds {
+                    let (x0, y0, x1, y1) = &mut self.bounds;
+                    egui::Grid::new("image_export_bounds")
+                        .num_columns(3)
+                        .show(ui, |ui| {
+                            ui.label("Lower left (um):");
+                            ui.add(egui::DragValue::new(x0).speed(0.1).max_decimals(4));
+                            ui.add(egui::DragValue::new(y0).speed(0.1).max_decimals(4));
+                            ui.end_row();
+                            ui.label("Upper right (um):");
+                            ui.add(egui::DragValue::new(x1).speed(0.1).max_decimals(4));
+                            ui.add(egui::DragValue::new(y1).speed(0.1).max_decimals(4));
+                            ui.end_row();
+                        });
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add_enabled(
+                                self.ruler_bounds.is_some(),
+                                egui::Button::new("From Ruler"),
+                            )
+                            .on_hover_text("Box spanned by the measured ruler segment")
+                            .on_disabled_hover_text("Measure a segment with the ruler first")
+                            .clicked()
+                        {
+                            self.bounds = self.ruler_bounds.unwrap_or(self.bounds);
+                        }
+                        if ui
+                            .add_enabled(self.view_bounds.is_some(), egui::Button::new("From View"))
+                            .clicked()
+                        {
+                            self.bounds = self.view_bounds.unwrap_or(self.bounds);
+                        }
+                    });
+                }
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.size_mode, SizeMode::Scale, "Scale");
+                    ui.add_enabled(
+                        self.size_mode == SizeMode::Scale,
+                        egui::DragValue::new(&mut self.scale)
+                            .range(0.5..=16.0)
+                            .speed(0.1)
+                            .suffix("x"),
+                    );
+                    ui.radio_value(&mut self.size_mode, SizeMode::Width, "Width");
+                    ui.add_enabled(
+                        self.size_mode == SizeMode::Width,
+                        egui::DragValue::new(&mut self.width)
+                            .range(64..=MAX_RENDER_SIZE)
+                            .suffix(" px"),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.radio_value(
+                        &mut self.size_mode,
+                        SizeMode::MicronsPerPixel,
+                        "Fixed scale",
+                    )
+                    .on_hover_text("Same scale for every export, to compare revisions");
+                    ui.add_enabled(
+                        self.size_mode == SizeMode::MicronsPerPixel,
+                        egui::DragValue::new(&mut self.microns_per_pixel)
+                            .range(0.0001..=1000.0)
+                            .speed(0.001)
+                            .max_decimals(4)
+                            .suffix(" um/px"),
+                    );
+                });
+
+                let width = self.output_width();
+                match self.region_bounds() {
+                    Some(bounds) if bounds.2 > bounds.0 => {
+                        let aspect = (bounds.3 - bounds.1) / (bounds.2 - bounds.0);
+                        let height = (width as f32 * aspect).round();
+                        ui.label(format!("Output: {width} x {height} px"));
+                    }
+                    _ if self.region == ImageRegion::Bounds => {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(244, 67, 54),
+                            "The upper right corner must be above and right of the lower left",
+                        );
+                    }
+                    _ => {
+                        ui.label(format!("Output: {width} px wide"));
+                    }
+                }
+                if width >= MAX_RENDER_SIZE && self.format == ImageFormat::Png {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(255, 193, 7),
+                        format!("Limited to {MAX_RENDER_SIZE} px, the scale is not exact"),
+                    );
+                }
+                ui.label(
+                    egui::RichText::new("Rendered off-screen, independent of the window size.")
+                        .small()
+                        .weak(),
+                );
+
+                ui.separator();
+                let valid = self.region != ImageRegion::Bounds || has_area(self.bounds);
+                if ui
+                    .add_enabled(valid, egui::Button::new("Export..."))
+                    .clicked()
+                {
+                    request = Some(ImageExportRequest {
+                        region: self.region,
+                        bounds: self.bounds,
+                        format: self.format,
+                        width,
+                    });
+                }
+            });
+
+        if !open || request.is_some() {
+            self.visible = false;
+        }
+
+        request
+    }
+}
+
+fn has_area((x0, y0, x1, y1): WorldBounds) -> bool {
+    x1 > x0 && y1 > y0
+}
+
+/// Output width in pixels of `world_width` microns at `microns_per_pixel`
+fn width_at_scale(world_width: f32, microns_per_pixel: f32) -> u32 {
+    if microns_per_pixel <= 0.0 || world_width <= 0.0 {
+        return 1;
+    }
+    (world_width / microns_per_pixel).round().max(1.0) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_width_at_scale() {
+        assert_eq!(width_at_scale(100.0, 0.05), 2000);
+        assert_eq!(width_at_scale(0.001, 0.05), 1);
+        assert_eq!(width_at_scale(100.0, 0.0), 1);
+
+        let mut dialog = ImageExportDialog::new();
+        let canvas = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(800.0, 600.0));
+        let ruler = Some((10.0, 20.0, 30.0, 30.0));
+        dialog.open(canvas, Some((0.0, 0.0, 80.0, 60.0)), None, ruler, true);
+        dialog.region = ImageRegion::Bounds;
+        dialog.size_mode = SizeMode::MicronsPerPixel;
+        dialog.microns_per_pixel = 0.1;
+        assert_eq!(dialog.bounds, (10.0, 20.0, 30.0, 30.0));
+        assert_eq!(dialog.output_width(), 200);
+
+        // Exact bounds only in the DEF view
+        dialog.open(canvas, None, None, None, false);
+        assert_eq!(dialog.region, ImageRegion::View);
+    }
+}
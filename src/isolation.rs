@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Instance isolation view
+//!
+//! Isolating a component draws only what lies within a halo around its
+//! outline: the neighboring cells, pins, special wiring and routes that
+//! touch the region. Everything else is skipped before painting, so a
+//! congested spot can be reviewed without the rest of the chip slowing the
+//! frame down.
+
+use eframe::egui;
+
+/// Halo around the isolated component in microns when first opened
+pub const DEFAULT_HALO: f64 = 10.0;
+
+/// Component shown in isolation and the region drawn around it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Isolation {
+    /// Name of the isolated component
+    pub component: String,
+    /// Distance in microns kept around the outline
+    pub halo: f64,
+    /// Outline of the component in microns (min_x, min_y, max_x, max_y)
+    outline: (f64, f64, f64, f64),
+}
+
+/// What the user asked for in the isolation window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IsolationAction {
+    None,
+    /// The halo changed, the view should frame the new region
+    Reframe,
+    Exit,
+}
+
+impl Isolation {
+    /// Isolate `component` with the given outline and the default halo
+    pub fn new(component: &str, outline: (f64, f64, f64, f64)) -> Self {
+        Self {
+            component: component.to_string(),
+            halo: DEFAULT_HALO,
+            outline,
+        }
+    }
+
+    /// Region drawn in microns: the outline grown by the halo
+    pub fn region(&self) -> (f64, f64, f64, f64) {
+        let (min_x, min_y, max_x, max_y) = self.outline;
+        (
+            min_x - self.halo,
+            min_y - self.halo,
+            max_x + self.halo,
+            max_y + self.halo,
+        )
+    }
+
+    /// Whether a box in microns overlaps the region
+    pub fn overlaps(&self, min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> bool {
+        let (rx1, ry1, rx2, ry2) = self.region();
+        min_x <= rx2 && max_x >= rx1 && min_y <= ry2 && max_y >= ry1
+    }
+
+    /// Whether the bounding box of `points` in microns overlaps the region
+    pub fn overlaps_points(&self, points: impl IntoIterator<Item = (f64, f64)>) -> bool {
+        let bounds = points
+            .into_iter()
+            .fold(None, |bounds, (x, y)| match bounds {
+                None => Some((x, y, x, y)),
+                Some((x1, y1, x2, y2)) => Some((x1.min(x), y1.min(y), x2.max(x), y2.max(y))),
+            });
+        bounds.is_some_and(|(x1, y1, x2, y2)| self.overlaps(x1, y1, x2, y2))
+    }
+
+    /// Small window with the halo slider and the exit button
+    pub fn render_controls(&mut self, ctx: &egui::Context) -> IsolationAction {
+        let mut action = IsolationAction::None;
+        egui::Window::new("Isolation")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-12.0, 60.0))
+            .show(ctx, |ui| {
+                ui.label(format!("Showing {} and its surroundings", self.component));
+                ui.horizontal(|ui| {
+                    ui.label("Halo:");
+                    let response = ui.add(
+                        egui::DragValue::new(&mut self.halo)
+                            .range(0.0..=1000.0)
+                            .speed(0.5)
+                            .suffix(" um"),
+                    );
+                    if response.changed() {
+                        action = IsolationAction::Reframe;
+                    }
+                });
+                if ui.button("Exit Isolation").clicked() {
+                    action = IsolationAction::Exit;
+                }
+            });
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_follows_halo() {
+        let mut isolation = Isolation::new("u1", (10.0, 20.0, 12.0, 21.8));
+        isolation.halo = 5.0;
+        assert_eq!(isolation.region(), (5.0, 15.0, 17.0, 26.8));
+
+        // A neighbor inside the halo is kept, one beyond it is not
+        assert!(isolation.overlaps(16.0, 20.0, 18.0, 21.8));
+        assert!(!isolation.overlaps(17.5, 20.0, 19.0, 21.8));
+
+        // A long wire crossing the region is kept although no point is inside
+        assert!(isolation.overlaps_points([(0.0, 21.0), (100.0, 21.0)]));
+        assert!(!isolation.overlaps_points([(0.0, 40.0), (100.0, 40.0)]));
+        assert!(!isolation.overlaps_points([]));
+    }
+}
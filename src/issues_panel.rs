@@ -0,0 +1,166 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Issues Panel
+//!
+//! Docked list of the unwaived design check violations of the loaded files,
+//! see [`crate::checks`], that can be filtered by check and text. Clicking
+//! a violation with a location zooms to it and selects the component it
+//! names. The panel docks to the right or to the bottom of the window;
+//! waiving is done in the Checks panel.
+
+use crate::checks::{CheckReport, Finding};
+use eframe::egui;
+
+/// Rows shown per check before the rest is summarized
+const MAX_LISTED: usize = 500;
+
+/// Where the panel is docked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IssuesDock {
+    #[default]
+    Right,
+    Bottom,
+}
+
+/// Issues panel state and rendering
+#[derive(Default)]
+pub struct IssuesPanel {
+    /// Whether the panel is currently shown
+    pub visible: bool,
+    dock: IssuesDock,
+    /// Check to list, all checks when None
+    check_filter: Option<&'static str>,
+    text_filter: String,
+}
+
+impl IssuesPanel {
+    /// Create a new issues panel
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render the docked panel, returning the violation that was clicked
+    ///
+    /// Must be called before the central panel is shown.
+    pub fn render(&mut self, ctx: &egui::Context, report: Option<&CheckReport>) -> Option<Finding> {
+        if !self.visible {
+            return None;
+        }
+
+        let mut clicked = None;
+        match self.dock {
+            IssuesDock::Right => {
+                egui::SidePanel::right("issues_panel")
+                    .resizable(true)
+                    .default_width(320.0)
+                    .show(ctx, |ui| clicked = self.render_contents(ui, report));
+            }
+            IssuesDock::Bottom => {
+                egui::TopBottomPanel::bottom("issues_panel_bottom")
+                    .resizable(true)
+                    .default_height(200.0)
+                    .show(ctx, |ui| clicked = self.render_contents(ui, report));
+            }
+        }
+        clicked
+    }
+
+    fn render_contents(
+        &mut self,
+        ui: &mut egui::Ui,
+        report: Option<&CheckReport>,
+    ) -> Option<Finding> {
+        ui.horizontal(|ui| {
+            ui.heading("Issues");
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.small_button("X").on_hover_text("Close").clicked() {
+                    self.visible = false;
+                }
+                let (label, other) = match self.dock {
+                    IssuesDock::Right => ("Dock Bottom", IssuesDock::Bottom),
+                    IssuesDock::Bottom => ("Dock Right", IssuesDock::Right),
+                };
+                if ui.small_button(label).clicked() {
+                    self.dock = other;
+                }
+            });
+        });
+
+        let Some(report) = report else {
+            ui.label("Load LEF or DEF files to run the design checks.");
+            return None;
+        };
+        ui.label(format!(
+            "{} violations, {} waived, {} checks skipped",
+            report.failure_count(),
+            report.waived_count(),
+            report.skipped_count()
+        ));
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("issues_check_filter")
+                .selected_text(self.check_filter.unwrap_or("All checks"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.check_filter, None, "All checks");
+                    for result in report.results.iter().filter(|r| !r.passed()) {
+                        ui.selectable_value(
+                            &mut self.check_filter,
+                            Some(result.name),
+                            format!("{} ({})", result.name, result.findings.len()),
+                        );
+                    }
+                });
+            ui.add(
+                egui::TextEdit::singleline(&mut self.text_filter)
+                    .hint_text("Filter")
+                    .desired_width(f32::INFINITY),
+            );
+        });
+        ui.separator();
+
+        if report.passed() {
+            ui.colored_label(egui::Color32::from_rgb(76, 175, 80), "No open issues");
+            return None;
+        }
+
+        let text_filter = self.text_filter.to_lowercase();
+        let matches = |finding: &Finding| {
+            text_filter.is_empty()
+                || finding.object.to_lowercase().contains(&text_filter)
+                || finding.message.to_lowercase().contains(&text_filter)
+        };
+        let mut clicked = None;
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                let results = report
+                    .results
+                    .iter()
+                    .filter(|r| !r.passed())
+                    .filter(|r| self.check_filter.is_none_or(|name| name == r.name));
+                for result in results {
+                    let findings: Vec<&Finding> =
+                        result.findings.iter().filter(|f| matches(f)).collect();
+                    egui::CollapsingHeader::new(format!("{} ({})", result.name, findings.len()))
+                        .id_salt(("issues", result.name))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for finding in findings.iter().take(MAX_LISTED) {
+                                let text = format!("{}: {}", finding.object, finding.message);
+                                if finding.bounds.is_some() {
+                                    if ui.link(text).on_hover_text("Zoom to it").clicked() {
+                                        clicked = Some((*finding).clone());
+                                    }
+                                } else {
+                                    ui.label(text);
+                                }
+                            }
+                            if findings.len() > MAX_LISTED {
+                                ui.label(format!("... and {} more", findings.len() - MAX_LISTED));
+                            }
+                        });
+                }
+            });
+        clicked
+    }
+}
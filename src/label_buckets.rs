@@ -0,0 +1,208 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Label buckets
+//!
+//! Laying out thousands of pin and instance names every frame is a large
+//! part of the frame time on dense pin maps. Labels are bucketed into
+//! screen-space tiles of [`TILE_SIZE`] points, and each tile keeps the
+//! galleys of its labels together with the zoom bucket they were laid out
+//! at. Only tiles whose zoom bucket changed, and labels new to a tile, are
+//! laid out again; the rest reuse their galley, also for the outline.
+//!
+//! Tiles with more than [`DENSE_TILE_LABELS`] labels, as seen from far
+//! out, are drawn approximately: without the outline, and leaving out the
+//! labels that would overlap one drawn before them in the tile.
+
+use eframe::egui;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Side of a label tile in screen points
+pub const TILE_SIZE: f32 = 256.0;
+/// Labels of a tile above which it is drawn approximately
+pub const DENSE_TILE_LABELS: usize = 48;
+/// Zoom buckets per doubling of the zoom
+const BUCKETS_PER_OCTAVE: f32 = 4.0;
+
+/// Outline offsets around white text
+const OUTLINE_OFFSETS: [(f32, f32); 8] = [
+    (-1.0, -1.0),
+    (0.0, -1.0),
+    (1.0, -1.0),
+    (-1.0, 0.0),
+    (1.0, 0.0),
+    (-1.0, 1.0),
+    (0.0, 1.0),
+    (1.0, 1.0),
+];
+
+/// Label to draw, rotated by `angle` about its anchor point `pos`
+pub struct Label<'a> {
+    pub pos: egui::Pos2,
+    pub anchor: egui::Align2,
+    pub angle: f32,
+    pub text: &'a str,
+    pub font: &'a egui::FontId,
+    pub color: egui::Color32,
+}
+
+/// Text, font size bits and family of a laid out label
+type GalleyKey = (String, u32, egui::FontFamily);
+
+struct Tile {
+    zoom_bucket: i32,
+    galleys: HashMap<GalleyKey, Arc<egui::Galley>>,
+}
+
+/// Galleys of the labels drawn last frame, by tile
+#[derive(Default)]
+pub struct LabelCache {
+    tiles: HashMap<(i32, i32), Tile>,
+    pixels_per_point: f32,
+}
+
+impl LabelCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Zoom bucket of `zoom`, a quarter of a doubling wide
+    pub fn zoom_bucket(zoom: f32) -> i32 {
+        (zoom.max(f32::MIN_POSITIVE).log2() * BUCKETS_PER_OCTAVE).floor() as i32
+    }
+
+    /// Tile that the anchor point `pos` falls in
+    pub fn tile_of(pos: egui::Pos2) -> (i32, i32) {
+        (
+            (pos.x / TILE_SIZE).floor() as i32,
+            (pos.y / TILE_SIZE).floor() as i32,
+        )
+    }
+
+    /// Draw `labels` on top of the canvas, white ones with a black outline
+    pub fn paint<'a>(
+        &mut self,
+        painter: &egui::Painter,
+        zoom: f32,
+        labels: impl IntoIterator<Item = Label<'a>>,
+    ) {
+        crate::profile_scope!("paint_labels");
+        // Galleys are laid out for the pixels per point
+        let pixels_per_point = painter.ctx().pixels_per_point();
+        if pixels_per_point != self.pixels_per_point {
+            self.tiles.clear();
+            self.pixels_per_point = pixels_per_point;
+        }
+
+        // Labels far off the canvas cannot reach into it
+        let visible = painter.clip_rect().expand(TILE_SIZE);
+        let mut buckets: HashMap<(i32, i32), Vec<Label>> = HashMap::new();
+        for label in labels {
+            if visible.contains(label.pos) {
+                buckets
+                    .entry(Self::tile_of(label.pos))
+                    .or_default()
+                    .push(label);
+            }
+        }
+
+        // Tiles without labels this frame are dropped
+        self.tiles.retain(|key, _| buckets.contains_key(key));
+        let zoom_bucket = Self::zoom_bucket(zoom);
+        for (key, labels) in buckets {
+            let tile = self.tiles.entry(key).or_insert_with(|| Tile {
+                zoom_bucket,
+                galleys: HashMap::new(),
+            });
+            if tile.zoom_bucket != zoom_bucket {
+                tile.galleys.clear();
+                tile.zoom_bucket = zoom_bucket;
+            }
+
+            let dense = labels.len() > DENSE_TILE_LABELS;
+            let mut drawn: Vec<egui::Rect> = Vec::new();
+            for label in labels {
+                let key = (
+                    label.text.to_string(),
+                    label.font.size.to_bits(),
+                    label.font.family.clone(),
+                );
+                let galley = tile
+                    .galleys
+                    .entry(key)
+                    .or_insert_with(|| {
+                        painter.layout_no_wrap(
+                            label.text.to_string(),
+                            label.font.clone(),
+                            egui::Color32::PLACEHOLDER,
+                        )
+                    })
+                    .clone();
+                let rect = label.anchor.anchor_size(label.pos, galley.size());
+                if dense {
+                    if drawn.iter().any(|r| r.intersects(rect)) {
+                        continue;
+                    }
+                    drawn.push(rect);
+                } else if label.color == egui::Color32::WHITE {
+                    for (dx, dy) in OUTLINE_OFFSETS {
+                        let offset = egui::vec2(dx, dy);
+                        painter.add(text_shape(
+                            &galley,
+                            label.pos + offset,
+                            rect.min + offset,
+                            label.angle,
+                            egui::Color32::BLACK,
+                        ));
+                    }
+                }
+                painter.add(text_shape(
+                    &galley,
+                    label.pos,
+                    rect.min,
+                    label.angle,
+                    label.color,
+                ));
+            }
+        }
+    }
+}
+
+/// Galley at `top_left`, rotated by `angle` about `pivot`
+///
+/// egui rotates text about the top-left corner of the galley, which is
+/// the anchor only for LEFT_TOP; shift it so that centered text stays
+/// centered.
+fn text_shape(
+    galley: &Arc<egui::Galley>,
+    pivot: egui::Pos2,
+    top_left: egui::Pos2,
+    angle: f32,
+    color: egui::Color32,
+) -> egui::epaint::TextShape {
+    let pos = if angle == 0.0 {
+        top_left
+    } else {
+        pivot - egui::emath::Rot2::from_angle(angle) * (pivot - top_left)
+    };
+    egui::epaint::TextShape::new(pos, Arc::clone(galley), color).with_angle(angle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buckets() {
+        assert_eq!(LabelCache::zoom_bucket(1.0), 0);
+        assert_eq!(LabelCache::zoom_bucket(1.1), 0);
+        assert_eq!(LabelCache::zoom_bucket(1.2), 1);
+        assert_eq!(LabelCache::zoom_bucket(2.0), 4);
+        assert_eq!(LabelCache::zoom_bucket(0.5), -4);
+        assert_eq!(LabelCache::zoom_bucket(0.0), LabelCache::zoom_bucket(-1.0));
+
+        assert_eq!(LabelCache::tile_of(egui::pos2(10.0, 300.0)), (0, 1));
+        assert_eq!(LabelCache::tile_of(egui::pos2(-1.0, 256.0)), (-1, 1));
+    }
+}
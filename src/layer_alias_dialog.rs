@@ -0,0 +1,188 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Layer Aliases dialog
+//!
+//! Edits the alias table of [`crate::lef::layer_alias`], offers the aliases
+//! suggested from the technology LEF and applies the table to the loaded
+//! libraries. Libraries loaded later are renamed on load.
+
+use crate::lef::layer_alias::{macro_layer_names, suggest_aliases, LayerAliases};
+use crate::lef::Lef;
+use eframe::egui;
+
+/// Layer alias editor state and rendering
+#[derive(Default)]
+pub struct LayerAliasDialog {
+    /// Whether the dialog is currently shown
+    pub visible: bool,
+    /// Aliases suggested for the loaded libraries, not yet in the table
+    suggested: Vec<(String, String)>,
+    /// Library layers the technology does not define and no alias matched
+    unknown: Vec<String>,
+    /// Layers of the technology LEF
+    technology_layers: Vec<String>,
+    new_alias: String,
+    new_layer: String,
+}
+
+impl LayerAliasDialog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Look for library layers the technology of `libraries` does not
+    /// define, returning how many there are
+    pub fn refresh(&mut self, libraries: &[&Lef], aliases: &LayerAliases) -> usize {
+        let technology: Vec<_> = libraries
+            .iter()
+            .flat_map(|lef| lef.layers.iter().cloned())
+            .collect();
+        self.technology_layers = technology.iter().map(|l| l.name.clone()).collect();
+        if technology.is_empty() {
+            self.suggested.clear();
+            self.unknown.clear();
+            return 0;
+        }
+        let used: std::collections::BTreeSet<&str> = libraries
+            .iter()
+            .flat_map(|lef| macro_layer_names(lef))
+            .collect();
+        let (suggested, unknown) = suggest_aliases(&technology, used);
+        self.suggested = suggested
+            .into_iter()
+            .filter(|(alias, _)| aliases.resolve(alias) == alias.as_str())
+            .collect();
+        self.unknown = unknown;
+        self.suggested.len() + self.unknown.len()
+    }
+
+    /// Names of the library layers without a technology layer
+    pub fn unmapped(&self) -> impl Iterator<Item = &str> {
+        self.suggested
+            .iter()
+            .map(|(alias, _)| alias.as_str())
+            .chain(self.unknown.iter().map(String::as_str))
+    }
+
+    /// Render the dialog, returning true when the table is to be applied
+    /// to the loaded libraries
+    pub fn render(&mut self, ctx: &egui::Context, aliases: &mut LayerAliases) -> bool {
+        if !self.visible {
+            return false;
+        }
+
+        let mut apply = false;
+        let mut open = true;
+        egui::Window::new("Layer Aliases")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(380.0)
+            .show(ctx, |ui| {
+                ui.label("Library layer names mapped onto technology layers:");
+                let mut removed = None;
+                egui::Grid::new("layer_alias_table")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (alias, layer) in aliases.iter() {
+                            ui.monospace(alias);
+                            ui.monospace(format!("-> {layer}"));
+                            if ui.small_button("🗑").on_hover_text("Remove alias").clicked() {
+                                removed = Some(alias.to_string());
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if aliases.is_empty() {
+                    ui.label(egui::RichText::new("No aliases").weak());
+                }
+                if let Some(alias) = removed {
+                    aliases.remove(&alias);
+                }
+
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.new_alias)
+                            .hint_text("METAL1")
+                            .desired_width(100.0)
+                            .font(egui::TextStyle::Monospace),
+                    );
+                    ui.label("->");
+                    if self.technology_layers.is_empty() {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.new_layer)
+                                .hint_text("M1")
+                                .desired_width(100.0)
+                                .font(egui::TextStyle::Monospace),
+                        );
+                    } else {
+                        egui::ComboBox::from_id_salt("layer_alias_target")
+                            .selected_text(self.new_layer.as_str())
+                            .show_ui(ui, |ui| {
+                                for layer in &self.technology_layers {
+                                    ui.selectable_value(&mut self.new_layer, layer.clone(), layer);
+                                }
+                            });
+                    }
+                    let valid = !self.new_alias.trim().is_empty() && !self.new_layer.is_empty();
+                    if ui.add_enabled(valid, egui::Button::new("Add")).clicked() {
+                        aliases.insert(self.new_alias.trim(), self.new_layer.clone());
+                        self.new_alias.clear();
+                    }
+                });
+
+                if !self.suggested.is_empty() {
+                    ui.separator();
+                    ui.label("Suggested from the technology LEF:");
+                    let mut accepted = Vec::new();
+                    for (i, (alias, layer)) in self.suggested.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.monospace(format!("{alias} -> {layer}"));
+                            if ui.small_button("Add").clicked() {
+                                accepted.push(i);
+                            }
+                        });
+                    }
+                    if ui.button("Add All").clicked() {
+                        accepted = (0..self.suggested.len()).collect();
+                    }
+                    for i in accepted.into_iter().rev() {
+                        let (alias, layer) = self.suggested.remove(i);
+                        aliases.insert(alias, layer);
+                    }
+                }
+                if !self.unknown.is_empty() {
+                    ui.separator();
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "Not in the technology LEF: {}",
+                            self.unknown.join(", ")
+                        ))
+                        .color(egui::Color32::from_rgb(255, 180, 0)),
+                    );
+                }
+
+                ui.separator();
+                if ui
+                    .add_enabled(!aliases.is_empty(), egui::Button::new("Apply to Loaded LEFs"))
+                    .on_hover_text("Move the shapes on alias layers onto their technology layers")
+                    .clicked()
+                {
+                    apply = true;
+                }
+                ui.label(
+                    egui::RichText::new(
+                        "Permanent aliases go in the [layer_aliases] table of the configuration file.",
+                    )
+                    .small()
+                    .weak(),
+                );
+            });
+
+        if !open {
+            self.visible = false;
+        }
+        apply
+    }
+}
@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Default layer visibility from the configuration file
+//!
+//! The `[layers]` table of the configuration file decides whether a layer
+//! is shown when a loaded file brings it in for the first time:
+//!
+//! ```toml
+//! [layers]
+//! default_hidden = ["*.OBS", "VIA*"]
+//! default_visible = ["M1.PIN", "OUTLINE"]
+//! ```
+//!
+//! Patterns match the layer keys of the Layers panel, e.g. `metal1.PIN`,
+//! with `*` and `?` wildcards. A layer matching both lists is shown. Layers
+//! matching neither keep the built-in default: OUTLINE, routing, DEF pins
+//! and power and ground pins of LEF macros are shown, the rest is hidden.
+//!
+//! The `[layer_aliases]` table maps layer names of libraries onto the
+//! layers of the technology LEF, see [`crate::lef::layer_alias`]:
+//!
+//! ```toml
+//! [layer_aliases]
+//! METAL1 = "M1"
+//! me2 = "M2"
+//! ```
+//!
+//! The file is `lefdef-viewer.toml` in the working directory, or else
+//! `lefdef-viewer/config.toml` in the user configuration directory. The
+//! `LEFDEF_VIEWER_CONFIG` environment variable names another file.
+
+use crate::identifier::wildcard_match;
+use crate::lef::layer_alias::LayerAliases;
+use std::path::PathBuf;
+
+/// Layer visibility patterns
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LayerConfig {
+    pub default_visible: Vec<String>,
+    pub default_hidden: Vec<String>,
+    pub aliases: LayerAliases,
+}
+
+impl LayerConfig {
+    /// Read the `[layers]` and `[layer_aliases]` tables of a configuration
+    /// file
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let document: toml_edit::DocumentMut = text.parse().map_err(|e| format!("{e}"))?;
+        let mut aliases = LayerAliases::new();
+        if let Some(table) = document.get("layer_aliases") {
+            let table = table
+                .as_table_like()
+                .ok_or("layer_aliases must be a table")?;
+            for (alias, layer) in table.iter() {
+                let layer = layer
+                    .as_str()
+                    .ok_or_else(|| format!("layer_aliases.{alias} must be a layer name"))?;
+                aliases.insert(alias, layer);
+            }
+        }
+        let Some(layers) = document.get("layers") else {
+            return Ok(Self {
+                aliases,
+                ..Self::default()
+            });
+        };
+        let layers = layers.as_table_like().ok_or("layers must be a table")?;
+        let patterns = |key: &str| -> Result<Vec<String>, String> {
+            let Some(item) = layers.get(key) else {
+                return Ok(Vec::new());
+            };
+            item.as_array()
+                .and_then(|array| {
+                    array
+                        .iter()
+                        .map(|value| value.as_str().map(str::to_string))
+                        .collect()
+                })
+                .ok_or_else(|| format!("layers.{key} must be a list of strings"))
+        };
+        Ok(Self {
+            default_visible: patterns("default_visible")?,
+            default_hidden: patterns("default_hidden")?,
+            aliases,
+        })
+    }
+
+    /// Load the first configuration file that exists
+    ///
+    /// Returns the path it came from, or None without a file.
+    pub fn load() -> Result<Option<(Self, PathBuf)>, String> {
+        for path in config_paths() {
+            match std::fs::read_to_string(&path) {
+                Ok(text) => {
+                    let config =
+                        Self::parse(&text).map_err(|e| format!("{}: {e}", path.display()))?;
+                    return Ok(Some((config, path)));
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(format!("{}: {e}", path.display())),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Whether a newly found layer is shown, `default` when no pattern
+    /// matches it
+    pub fn is_visible(&self, layer: &str, default: bool) -> bool {
+        let matches = |patterns: &[String]| patterns.iter().any(|p| wildcard_match(p, layer));
+        if matches(&self.default_visible) {
+            true
+        } else if matches(&self.default_hidden) {
+            false
+        } else {
+            default
+        }
+    }
+}
+
+/// Configuration files in the order they are tried
+fn config_paths() -> Vec<PathBuf> {
+    if let Some(path) = std::env::var_os("LEFDEF_VIEWER_CONFIG") {
+        return vec![PathBuf::from(path)];
+    }
+    let mut paths = vec![PathBuf::from("lefdef-viewer.toml")];
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+    if let Some(dir) = config_dir {
+        paths.push(dir.join("lefdef-viewer").join("config.toml"));
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_layer_patterns() {
+        let config = LayerConfig::parse(
+            r#"
+# Layers panel defaults
+[layers]
+default_hidden = ["*.OBS", "VIA*"]
+default_visible = ["M1.PIN", "OUTLINE"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.default_hidden, ["*.OBS", "VIA*"]);
+        assert_eq!(config.default_visible, ["M1.PIN", "OUTLINE"]);
+
+        assert_eq!(LayerConfig::parse("").unwrap(), LayerConfig::default());
+        assert!(LayerConfig::parse("[layers]\ndefault_hidden = \"*.OBS\"").is_err());
+        assert!(LayerConfig::parse("[layers]\ndefault_hidden = [1]").is_err());
+        assert!(LayerConfig::parse("layers = [").is_err());
+    }
+
+    #[test]
+    fn test_parse_layer_aliases() {
+        let config = LayerConfig::parse("[layer_aliases]\nMETAL1 = \"M1\"\nme2 = \"M2\"").unwrap();
+        assert!(config.default_hidden.is_empty());
+        assert_eq!(
+            config.aliases.iter().collect::<Vec<_>>(),
+            [("METAL1", "M1"), ("me2", "M2")]
+        );
+        assert!(LayerConfig::parse("[layer_aliases]\nMETAL1 = 1").is_err());
+    }
+
+    #[test]
+    fn test_visibility_precedence() {
+        let config = LayerConfig {
+            default_visible: vec!["M1.*".to_string()],
+            default_hidden: vec!["*.PIN".to_string(), "VIA*".to_string()],
+            aliases: LayerAliases::new(),
+        };
+        // Visible patterns win over hidden ones
+        assert!(config.is_visible("M1.PIN", false));
+        assert!(!config.is_visible("M2.PIN", true));
+        assert!(!config.is_visible("VIA12.ROUTE", true));
+        // Unmatched layers keep the built-in default
+        assert!(config.is_visible("M2.ROUTE", true));
+        assert!(!config.is_visible("M2.OBS", false));
+    }
+}
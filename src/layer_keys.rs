@@ -0,0 +1,158 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Visible layer keys by base layer
+//!
+//! The Layers panel names layers `M1.PIN`, `M1.OBS`, `M1.ROUTE` and so on,
+//! while shapes only store `M1`. Drawing used to format the key of every
+//! shape to test its visibility and pick its color, which allocated a string
+//! per shape per frame. [`VisibleLayerKeys`] is built once per frame from
+//! the visible set and hands out the borrowed key of a shape instead.
+
+use crate::lef::LefMacro;
+use std::collections::{HashMap, HashSet};
+
+/// Kind suffix of a layer key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayerKind {
+    Pin,
+    Obs,
+    Route,
+}
+
+/// Visible layer keys of the PIN, OBS and ROUTE kinds by base layer
+#[derive(Debug, Default)]
+pub struct VisibleLayerKeys<'a> {
+    pin: HashMap<&'a str, &'a str>,
+    obs: HashMap<&'a str, &'a str>,
+    route: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> VisibleLayerKeys<'a> {
+    pub fn new(visible: &'a HashSet<String>) -> Self {
+        let mut keys = Self::default();
+        for key in visible {
+            let Some((layer, kind)) = key.rsplit_once('.') else {
+                continue;
+            };
+            let map = match kind {
+                "PIN" => &mut keys.pin,
+                "OBS" => &mut keys.obs,
+                "ROUTE" => &mut keys.route,
+                _ => continue,
+            };
+            map.insert(layer, key.as_str());
+        }
+        keys
+    }
+
+    /// Key of a shape on `layer`, None when that layer is hidden
+    pub fn get(&self, kind: LayerKind, layer: &str) -> Option<&'a str> {
+        let map = match kind {
+            LayerKind::Pin => &self.pin,
+            LayerKind::Obs => &self.obs,
+            LayerKind::Route => &self.route,
+        };
+        map.get(layer).copied()
+    }
+}
+
+/// Kind and layer of every PIN and OBS shape of a macro, rectangles before
+/// polygons within each port and obstruction, in the order the shapes are
+/// numbered in the mesh cache
+pub fn macro_shape_layers(macro_def: &LefMacro) -> impl Iterator<Item = (LayerKind, &str)> {
+    let pins = macro_def
+        .pins
+        .iter()
+        .flat_map(|pin| &pin.ports)
+        .flat_map(|port| {
+            let rects = port.rects.iter().map(|r| r.layer.as_str());
+            let polygons = port.polygons.iter().map(|p| p.layer.as_str());
+            rects.chain(polygons).map(|layer| (LayerKind::Pin, layer))
+        });
+    let obs = macro_def.obs.iter().flat_map(|obs| {
+        let rects = obs.rects.iter().map(|r| r.layer.as_str());
+        let polygons = obs.polygons.iter().map(|p| p.layer.as_str());
+        rects.chain(polygons).map(|layer| (LayerKind::Obs, layer))
+    });
+    pins.chain(obs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lef::{LefObstruction, LefPin, LefPolygon, LefPort, LefRect};
+    use crate::test_fixtures;
+
+    fn rect(layer: &str) -> LefRect {
+        test_fixtures::rect(layer, 0.0, 0.0, 1.0, 1.0)
+    }
+
+    fn polygon(layer: &str) -> LefPolygon {
+        LefPolygon {
+            layer: layer.to_string(),
+            points: vec![(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)],
+            is_hole: false,
+        }
+    }
+
+    #[test]
+    fn test_visible_layer_keys() {
+        let visible: HashSet<String> = ["M1.PIN", "M2.ROUTE", "VIA.1.OBS", "OUTLINE"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let keys = VisibleLayerKeys::new(&visible);
+        assert_eq!(keys.get(LayerKind::Pin, "M1"), Some("M1.PIN"));
+        assert_eq!(keys.get(LayerKind::Obs, "M1"), None);
+        assert_eq!(keys.get(LayerKind::Route, "M2"), Some("M2.ROUTE"));
+        assert_eq!(keys.get(LayerKind::Obs, "VIA.1"), Some("VIA.1.OBS"));
+        assert_eq!(keys.get(LayerKind::Pin, "OUTLINE"), None);
+    }
+
+    #[test]
+    fn test_macro_shape_layers_order() {
+        let macro_def = LefMacro {
+            name: "INV".to_string(),
+            class: "CORE".to_string(),
+            foreign: String::new(),
+            origin: (0.0, 0.0),
+            size_x: 1.0,
+            size_y: 1.0,
+            symmetry: Vec::new(),
+            site: String::new(),
+            pins: vec![LefPin {
+                name: "A".to_string(),
+                direction: "INPUT".to_string(),
+                use_type: "SIGNAL".to_string(),
+                shape: String::new(),
+                ports: vec![
+                    LefPort {
+                        rects: vec![rect("M1")],
+                        polygons: vec![polygon("M2")],
+                    },
+                    LefPort {
+                        rects: vec![rect("M3")],
+                        polygons: Vec::new(),
+                    },
+                ],
+            }],
+            obs: vec![LefObstruction {
+                rects: vec![rect("M1")],
+                polygons: vec![polygon("M4")],
+            }],
+            ..LefMacro::default()
+        };
+        let layers: Vec<_> = macro_shape_layers(&macro_def).collect();
+        assert_eq!(
+            layers,
+            [
+                (LayerKind::Pin, "M1"),
+                (LayerKind::Pin, "M2"),
+                (LayerKind::Pin, "M3"),
+                (LayerKind::Obs, "M1"),
+                (LayerKind::Obs, "M4"),
+            ]
+        );
+    }
+}
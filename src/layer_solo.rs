@@ -0,0 +1,120 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Layer solo mode
+//!
+//! Soloing a layer shows only that layer plus OUTLINE. Each solo pushes the
+//! previous visibility set on a stack, so soloing M2 while M1 is soloed and
+//! then un-soloing M2 returns to the M1 solo rather than to the full view.
+
+use std::collections::HashSet;
+
+/// Layers kept visible while another layer is soloed
+const ALWAYS_VISIBLE: [&str; 1] = ["OUTLINE"];
+
+struct SoloEntry {
+    key: String,
+    previous: HashSet<String>,
+}
+
+/// Stack of solo operations on the layer visibility set
+#[derive(Default)]
+pub struct SoloStack {
+    entries: Vec<SoloEntry>,
+}
+
+impl SoloStack {
+    /// Create an empty solo stack
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Solo `layers` under `key`, or restore the previous set if `key` is
+    /// the innermost solo
+    ///
+    /// `key` names what was soloed, e.g. a layer or a base layer group.
+    pub fn toggle(&mut self, key: &str, layers: &[String], visible: &mut HashSet<String>) {
+        if self.current() == Some(key) {
+            if let Some(entry) = self.entries.pop() {
+                *visible = entry.previous;
+            }
+            return;
+        }
+
+        let previous = std::mem::take(visible);
+        visible.extend(ALWAYS_VISIBLE.iter().map(|layer| layer.to_string()));
+        visible.extend(layers.iter().cloned());
+        self.entries.push(SoloEntry {
+            key: key.to_string(),
+            previous,
+        });
+    }
+
+    /// Innermost soloed key
+    pub fn current(&self) -> Option<&str> {
+        self.entries.last().map(|entry| entry.key.as_str())
+    }
+
+    /// Number of nested solo operations
+    pub fn depth(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Restore the visibility from before the outermost solo
+    pub fn restore_all(&mut self, visible: &mut HashSet<String>) {
+        if let Some(first) = self.entries.drain(..).next() {
+            *visible = first.previous;
+        }
+    }
+
+    /// Forget all solo operations, keeping the current visibility
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(layers: &[&str]) -> HashSet<String> {
+        layers.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_solo_and_restore() {
+        let mut stack = SoloStack::new();
+        let mut visible = set(&["OUTLINE", "LABEL", "M1.PIN", "M2.PIN"]);
+        let original = visible.clone();
+
+        stack.toggle("M1.PIN", &["M1.PIN".to_string()], &mut visible);
+        assert_eq!(visible, set(&["OUTLINE", "M1.PIN"]));
+        assert_eq!(stack.current(), Some("M1.PIN"));
+
+        stack.toggle("M1.PIN", &["M1.PIN".to_string()], &mut visible);
+        assert_eq!(visible, original);
+        assert_eq!(stack.depth(), 0);
+    }
+
+    #[test]
+    fn test_nested_solo() {
+        let mut stack = SoloStack::new();
+        let mut visible = set(&["OUTLINE", "M1.PIN", "M2.PIN", "M2.OBS"]);
+        let original = visible.clone();
+
+        stack.toggle("M1", &["M1.PIN".to_string()], &mut visible);
+        let m2 = ["M2.PIN".to_string(), "M2.OBS".to_string()];
+        stack.toggle("M2", &m2, &mut visible);
+        assert_eq!(visible, set(&["OUTLINE", "M2.PIN", "M2.OBS"]));
+        assert_eq!(stack.depth(), 2);
+
+        // Un-soloing the inner layer returns to the outer solo
+        stack.toggle("M2", &m2, &mut visible);
+        assert_eq!(visible, set(&["OUTLINE", "M1.PIN"]));
+
+        stack.toggle("M2", &m2, &mut visible);
+        stack.restore_all(&mut visible);
+        assert_eq!(visible, original);
+        assert_eq!(stack.current(), None);
+    }
+}
@@ -0,0 +1,131 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Layers panel tree
+//!
+//! Layer keys of the panel are a base layer with a kind suffix, `M1.PIN`,
+//! `M1.OBS` or `M1.ROUTE`, next to the virtual `OUTLINE` and `LABEL`
+//! layers. The panel shows one collapsible node per base layer, ordered
+//! like the technology stack, with the kinds below it, plus a row per kind
+//! that switches that kind on every layer at once. Both levels use
+//! tri-state checkboxes.
+//!
+//! The suffix is split off at the last dot, so base layers that contain
+//! dots themselves, such as `VIA.1`, keep their name.
+
+use std::collections::{BTreeMap, HashSet};
+
+/// Layers shown above the tree, in this order
+pub const VIRTUAL_LAYERS: [&str; 2] = ["OUTLINE", "LABEL"];
+/// Kind suffixes of derived layers
+pub const KINDS: [&str; 3] = ["PIN", "OBS", "ROUTE"];
+
+/// Base layer and kind of a layer key, the kind empty for a plain layer
+pub fn split_layer(layer: &str) -> (&str, &str) {
+    match layer.rsplit_once('.') {
+        Some((base, kind)) if KINDS.contains(&kind) => (base, kind),
+        _ => (layer, ""),
+    }
+}
+
+/// Base layer node of the tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerGroup<'a> {
+    pub base: &'a str,
+    /// Layer keys of the node, in kind order
+    pub layers: Vec<&'a str>,
+}
+
+/// Virtual layers and base layer nodes of `layers`, nodes sorted by
+/// `order` and then by name
+pub fn build<'a>(
+    layers: impl IntoIterator<Item = &'a String>,
+    order: impl Fn(&str) -> i32,
+) -> (Vec<&'a str>, Vec<LayerGroup<'a>>) {
+    let mut virtual_layers = Vec::new();
+    let mut groups: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for layer in layers {
+        if VIRTUAL_LAYERS.contains(&layer.as_str()) {
+            virtual_layers.push(layer.as_str());
+        } else {
+            groups
+                .entry(split_layer(layer).0)
+                .or_default()
+                .push(layer.as_str());
+        }
+    }
+    virtual_layers.sort_by_key(|layer| VIRTUAL_LAYERS.iter().position(|v| v == layer));
+
+    let kind_rank = |layer: &str| {
+        let kind = split_layer(layer).1;
+        KINDS.iter().position(|k| *k == kind).unwrap_or(KINDS.len())
+    };
+    let mut groups: Vec<LayerGroup> = groups
+        .into_iter()
+        .map(|(base, mut layers)| {
+            layers.sort_by_key(|layer| (kind_rank(layer), *layer));
+            LayerGroup { base, layers }
+        })
+        .collect();
+    // Stable sort keeps the names in order within one rank
+    groups.sort_by_key(|group| order(group.base));
+    (virtual_layers, groups)
+}
+
+/// Layer keys of one kind across all nodes
+pub fn kind_layers<'a>(groups: &[LayerGroup<'a>], kind: &str) -> Vec<&'a str> {
+    groups
+        .iter()
+        .flat_map(|group| &group.layers)
+        .copied()
+        .filter(|layer| split_layer(layer).1 == kind)
+        .collect()
+}
+
+/// Checkbox state of a set of layers: (all visible, some but not all)
+pub fn tri_state(visible: &HashSet<String>, layers: &[&str]) -> (bool, bool) {
+    let shown = layers.iter().filter(|l| visible.contains(**l)).count();
+    let all = !layers.is_empty() && shown == layers.len();
+    (all, shown > 0 && !all)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_tree() {
+        let layers: Vec<String> = [
+            "M2.PIN",
+            "LABEL",
+            "M1.OBS",
+            "VIA.1.OBS",
+            "M1.PIN",
+            "M1.ROUTE",
+            "OUTLINE",
+            "POLY",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        // M2 sits above M1 in the stack, the rest are unknown
+        let order = |base: &str| match base {
+            "M1" => 1,
+            "M2" => 2,
+            _ => i32::MAX,
+        };
+        let (virtual_layers, groups) = build(&layers, order);
+        assert_eq!(virtual_layers, ["OUTLINE", "LABEL"]);
+        let bases: Vec<&str> = groups.iter().map(|g| g.base).collect();
+        assert_eq!(bases, ["M1", "M2", "POLY", "VIA.1"]);
+        assert_eq!(groups[0].layers, ["M1.PIN", "M1.OBS", "M1.ROUTE"]);
+        assert_eq!(kind_layers(&groups, "OBS"), ["M1.OBS", "VIA.1.OBS"]);
+        assert_eq!(split_layer("POLY"), ("POLY", ""));
+
+        let visible: HashSet<String> = ["M1.PIN".to_string()].into();
+        assert_eq!(tri_state(&visible, &groups[0].layers), (false, true));
+        assert_eq!(tri_state(&visible, &groups[1].layers), (false, false));
+        assert_eq!(tri_state(&visible, &["M1.PIN"]), (true, false));
+        assert_eq!(tri_state(&visible, &[]), (false, false));
+    }
+}
@@ -0,0 +1,381 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Structured diff of two LEF libraries
+//!
+//! Macros are matched by name, and pins by name within a macro, so
+//! reordering a library does not show up as a change. Shapes are compared
+//! as sets per pin and for the OBS of a macro, with the coordinates rounded
+//! to [`DIFF_QUANTUM`] so parser noise does not count as moved geometry.
+
+use super::{Lef, LefMacro, LefPin, LefPolygon, LefRect};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Rounding step in microns applied before comparing coordinates
+pub const DIFF_QUANTUM: f64 = 1e-4;
+
+/// One difference between the old and the new version of a macro
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// SIZE changed, width and height in microns
+    Size {
+        old: (f64, f64),
+        new: (f64, f64),
+    },
+    /// CLASS, SITE, FOREIGN or ORIGIN changed
+    Property {
+        name: &'static str,
+        old: String,
+        new: String,
+    },
+    PinAdded(String),
+    PinRemoved(String),
+    /// DIRECTION or USE of a pin changed
+    PinProperty {
+        pin: String,
+        name: &'static str,
+        old: String,
+        new: String,
+    },
+    /// Shapes only in the old and only in the new version of a pin
+    PinGeometry {
+        pin: String,
+        removed: Vec<String>,
+        added: Vec<String>,
+    },
+    /// Shapes only in the old and only in the new OBS
+    Obstruction {
+        removed: Vec<String>,
+        added: Vec<String>,
+    },
+}
+
+impl Change {
+    /// Kind of change, e.g. "pin geometry"
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Change::Size { .. } => "size",
+            Change::Property { .. } => "property",
+            Change::PinAdded(_) => "pin added",
+            Change::PinRemoved(_) => "pin removed",
+            Change::PinProperty { .. } => "pin property",
+            Change::PinGeometry { .. } => "pin geometry",
+            Change::Obstruction { .. } => "obs",
+        }
+    }
+
+    /// Changed part of the macro, e.g. "PIN A DIRECTION" or "OBS"
+    pub fn object(&self) -> String {
+        match self {
+            Change::Size { .. } => "SIZE".to_string(),
+            Change::Property { name, .. } => name.to_string(),
+            Change::PinAdded(pin) | Change::PinRemoved(pin) | Change::PinGeometry { pin, .. } => {
+                format!("PIN {pin}")
+            }
+            Change::PinProperty { pin, name, .. } => format!("PIN {pin} {name}"),
+            Change::Obstruction { .. } => "OBS".to_string(),
+        }
+    }
+
+    /// Old and new value, shapes separated by "; "
+    pub fn values(&self) -> (String, String) {
+        match self {
+            Change::Size { old, new } => (
+                format!("{:.4} x {:.4}", old.0, old.1),
+                format!("{:.4} x {:.4}", new.0, new.1),
+            ),
+            Change::Property { old, new, .. } | Change::PinProperty { old, new, .. } => {
+                (old.clone(), new.clone())
+            }
+            Change::PinAdded(_) => (String::new(), "added".to_string()),
+            Change::PinRemoved(_) => ("removed".to_string(), String::new()),
+            Change::PinGeometry { removed, added, .. } | Change::Obstruction { removed, added } => {
+                (removed.join("; "), added.join("; "))
+            }
+        }
+    }
+
+    /// One line for the diff tree, e.g. "PIN A: 1 shape removed, 2 added"
+    pub fn describe(&self) -> String {
+        match self {
+            Change::PinAdded(pin) => format!("PIN {pin} added"),
+            Change::PinRemoved(pin) => format!("PIN {pin} removed"),
+            Change::PinGeometry { removed, added, .. } | Change::Obstruction { removed, added } => {
+                format!(
+                    "{}: {} shapes removed, {} added",
+                    self.object(),
+                    removed.len(),
+                    added.len()
+                )
+            }
+            _ => {
+                let (old, new) = self.values();
+                format!("{}: {old} -> {new}", self.object())
+            }
+        }
+    }
+}
+
+/// Differences of a macro found in both libraries
+#[derive(Debug, Clone, PartialEq)]
+pub struct MacroDiff {
+    pub name: String,
+    pub changes: Vec<Change>,
+}
+
+/// Differences between two libraries, macro names sorted
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LefDiff {
+    /// Macros only in the new library
+    pub added: Vec<String>,
+    /// Macros only in the old library
+    pub removed: Vec<String>,
+    pub changed: Vec<MacroDiff>,
+    /// Macros in both libraries without differences
+    pub unchanged: usize,
+}
+
+impl LefDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compare the macros of `old` and `new`
+///
+/// A macro defined twice in a library is compared by its first definition.
+pub fn diff_libraries(old: &Lef, new: &Lef) -> LefDiff {
+    let (old_macros, new_macros) = (by_name(&old.macros), by_name(&new.macros));
+
+    let mut diff = LefDiff {
+        added: new_macros
+            .keys()
+            .filter(|name| !old_macros.contains_key(*name))
+            .map(|name| name.to_string())
+            .collect(),
+        ..LefDiff::default()
+    };
+    for (name, old_macro) in &old_macros {
+        let Some(new_macro) = new_macros.get(name) else {
+            diff.removed.push(name.to_string());
+            continue;
+        };
+        let changes = diff_macros(old_macro, new_macro);
+        if changes.is_empty() {
+            diff.unchanged += 1;
+        } else {
+            diff.changed.push(MacroDiff {
+                name: name.to_string(),
+                changes,
+            });
+        }
+    }
+    diff
+}
+
+/// Changes from `old` to `new` of one macro
+pub fn diff_macros(old: &LefMacro, new: &LefMacro) -> Vec<Change> {
+    let mut changes = Vec::new();
+    if quantize(old.size_x) != quantize(new.size_x) || quantize(old.size_y) != quantize(new.size_y)
+    {
+        changes.push(Change::Size {
+            old: (old.size_x, old.size_y),
+            new: (new.size_x, new.size_y),
+        });
+    }
+    let origin = |m: &LefMacro| format!("{:.4} {:.4}", m.origin.0, m.origin.1);
+    let properties = [
+        ("CLASS", old.class.clone(), new.class.clone()),
+        ("SITE", old.site.clone(), new.site.clone()),
+        ("FOREIGN", old.foreign.clone(), new.foreign.clone()),
+        ("ORIGIN", origin(old), origin(new)),
+    ];
+    for (name, old, new) in properties {
+        if old != new {
+            changes.push(Change::Property { name, old, new });
+        }
+    }
+
+    let (old_pins, new_pins) = (by_name(&old.pins), by_name(&new.pins));
+    for (name, old_pin) in &old_pins {
+        let Some(new_pin) = new_pins.get(name) else {
+            changes.push(Change::PinRemoved(name.to_string()));
+            continue;
+        };
+        let properties = [
+            ("DIRECTION", &old_pin.direction, &new_pin.direction),
+            ("USE", &old_pin.use_type, &new_pin.use_type),
+        ];
+        for (property, old, new) in properties {
+            if old != new {
+                changes.push(Change::PinProperty {
+                    pin: name.to_string(),
+                    name: property,
+                    old: old.clone(),
+                    new: new.clone(),
+                });
+            }
+        }
+        let shapes = |pin: &LefPin| {
+            shape_set(
+                pin.ports
+                    .iter()
+                    .map(|port| (port.rects.as_slice(), port.polygons.as_slice())),
+            )
+        };
+        let (removed, added) = set_difference(shapes(old_pin), shapes(new_pin));
+        if !removed.is_empty() || !added.is_empty() {
+            changes.push(Change::PinGeometry {
+                pin: name.to_string(),
+                removed,
+                added,
+            });
+        }
+    }
+    changes.extend(
+        new_pins
+            .keys()
+            .filter(|name| !old_pins.contains_key(*name))
+            .map(|name| Change::PinAdded(name.to_string())),
+    );
+
+    let obs = |m: &LefMacro| {
+        shape_set(
+            m.obs
+                .iter()
+                .map(|obs| (obs.rects.as_slice(), obs.polygons.as_slice())),
+        )
+    };
+    let (removed, added) = set_difference(obs(old), obs(new));
+    if !removed.is_empty() || !added.is_empty() {
+        changes.push(Change::Obstruction { removed, added });
+    }
+    changes
+}
+
+/// Items by name, the first one of a repeated name
+fn by_name<T: Named>(items: &[T]) -> BTreeMap<&str, &T> {
+    let mut by_name = BTreeMap::new();
+    for item in items {
+        by_name.entry(item.name()).or_insert(item);
+    }
+    by_name
+}
+
+trait Named {
+    fn name(&self) -> &str;
+}
+
+impl Named for LefMacro {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Named for LefPin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+fn quantize(value: f64) -> i64 {
+    (value / DIFF_QUANTUM).round() as i64
+}
+
+/// Shape with its coordinates quantized, ordered by layer
+type ShapeKey = (String, &'static str, Vec<i64>);
+
+fn shape_set<'a>(
+    groups: impl Iterator<Item = (&'a [LefRect], &'a [LefPolygon])>,
+) -> BTreeSet<ShapeKey> {
+    let mut shapes = BTreeSet::new();
+    for (rects, polygons) in groups {
+        for rect in rects {
+            let coords = [rect.xl, rect.yl, rect.xh, rect.yh].map(quantize);
+            shapes.insert((rect.layer.clone(), "RECT", coords.to_vec()));
+        }
+        for polygon in polygons {
+            let coords = polygon
+                .points
+                .iter()
+                .flat_map(|&(x, y)| [quantize(x), quantize(y)])
+                .collect();
+            shapes.insert((polygon.layer.clone(), "POLYGON", coords));
+        }
+    }
+    shapes
+}
+
+/// Shapes only in `old` and only in `new`, as "M1 RECT 0.1000 0.2000 ..."
+fn set_difference(old: BTreeSet<ShapeKey>, new: BTreeSet<ShapeKey>) -> (Vec<String>, Vec<String>) {
+    let describe = |(layer, kind, coords): &ShapeKey| {
+        let coords: Vec<String> = coords
+            .iter()
+            .map(|&c| format!("{:.4}", c as f64 * DIFF_QUANTUM))
+            .collect();
+        format!("{layer} {kind} {}", coords.join(" "))
+    };
+    (
+        old.difference(&new).map(describe).collect(),
+        new.difference(&old).map(describe).collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lef::lef_parser::parse_lef;
+
+    fn library(text: &str) -> Lef {
+        parse_lef(text).unwrap().1
+    }
+
+    #[test]
+    fn test_diff_libraries() {
+        let old = library(
+            "MACRO INVX1\n  CLASS CORE ;\n  SIZE 1.0 BY 2.0 ;\n\
+             PIN A\n    DIRECTION INPUT ;\n    PORT\n      LAYER M1 ;\n        RECT 0.1 0.1 0.2 0.2 ;\n    END\n  END A\n\
+             PIN Y\n    DIRECTION OUTPUT ;\n    PORT\n      LAYER M1 ;\n        RECT 0.5 0.1 0.6 0.2 ;\n    END\n  END Y\n\
+             END INVX1\n\
+             MACRO OLDCELL\n  SIZE 1.0 BY 2.0 ;\nEND OLDCELL\n\
+             MACRO SAME\n  SIZE 1.0 BY 2.0 ;\nEND SAME\nEND LIBRARY\n",
+        );
+        let new = library(
+            "MACRO SAME\n  SIZE 1.0 BY 2.0 ;\nEND SAME\n\
+             MACRO INVX1\n  CLASS CORE ;\n  SIZE 1.2 BY 2.0 ;\n\
+             PIN A\n    DIRECTION INOUT ;\n    PORT\n      LAYER M1 ;\n        RECT 0.1 0.1 0.3 0.2 ;\n    END\n  END A\n\
+             PIN Z\n    DIRECTION OUTPUT ;\n  END Z\n\
+             OBS\n    LAYER M1 ;\n      RECT 0.0 0.0 1.2 0.1 ;\n  END\n\
+             END INVX1\n\
+             MACRO NEWCELL\n  SIZE 1.0 BY 2.0 ;\nEND NEWCELL\nEND LIBRARY\n",
+        );
+
+        let diff = diff_libraries(&old, &new);
+        assert_eq!(diff.added, ["NEWCELL"]);
+        assert_eq!(diff.removed, ["OLDCELL"]);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.changed.len(), 1);
+
+        let changes = &diff.changed[0].changes;
+        let described: Vec<String> = changes.iter().map(Change::describe).collect();
+        assert_eq!(
+            described,
+            [
+                "SIZE: 1.0000 x 2.0000 -> 1.2000 x 2.0000",
+                "PIN A DIRECTION: INPUT -> INOUT",
+                "PIN A: 1 shapes removed, 1 added",
+                "PIN Y removed",
+                "PIN Z added",
+                "OBS: 0 shapes removed, 1 added",
+            ]
+        );
+        assert_eq!(
+            changes[2].values(),
+            (
+                "M1 RECT 0.1000 0.1000 0.2000 0.2000".to_string(),
+                "M1 RECT 0.1000 0.1000 0.3000 0.2000".to_string()
+            )
+        );
+        assert!(diff_libraries(&old, &old).is_empty());
+    }
+}
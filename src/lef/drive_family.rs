@@ -0,0 +1,236 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Pin equivalence across drive strengths
+//!
+//! The drive-strength variants of a cell (BUFX1, BUFX2, BUFX4) are meant to
+//! be swapped for each other by sizing, so they must have the same pin
+//! names, directions and uses. A variant that renames a pin or flips its
+//! direction is a frequent library-release bug that only shows up when a
+//! tool tries to resize an instance.
+//!
+//! Families are found by name: a trailing drive suffix `X<n>`, `_X<n>` or
+//! `_<n>`, where `<n>` may be written `0P5` for 0.5, is stripped to get the
+//! family stem. Every member is compared against the weakest one.
+
+use super::{LefMacro, LefPin};
+use std::collections::{BTreeMap, HashSet};
+
+/// Pin that differs from the weakest member of its family
+#[derive(Debug, Clone, PartialEq)]
+pub struct PinDivergence {
+    /// Family stem, e.g. "BUF"
+    pub family: String,
+    pub macro_name: String,
+    /// Weakest member the macro is compared against
+    pub reference: String,
+    pub pin_name: String,
+    pub message: String,
+}
+
+/// Family stem and drive strength of a macro name, None without a suffix
+///
+/// `BUFX2`, `BUF_X2` and `buf_2` all give ("BUF", 2.0) or ("buf", 2.0),
+/// `INVX0P5` gives ("INV", 0.5).
+pub fn drive_strength(name: &str) -> Option<(&str, f64)> {
+    let trailing_digits = |end: usize| {
+        name[..end]
+            .rfind(|c: char| !c.is_ascii_digit())
+            .map_or(0, |i| i + 1)
+    };
+    let fraction_start = trailing_digits(name.len());
+    if fraction_start == name.len() {
+        return None;
+    }
+    let mut value = name[fraction_start..].to_string();
+    let mut start = fraction_start;
+    if let Some(before) = name[..fraction_start].strip_suffix(['P', 'p']) {
+        let int_start = trailing_digits(before.len());
+        if int_start < before.len() {
+            value = format!("{}.{value}", &before[int_start..]);
+            start = int_start;
+        }
+    }
+
+    let prefix = &name[..start];
+    let stem = match prefix.strip_suffix(['X', 'x']) {
+        Some(stem) => stem.strip_suffix('_').unwrap_or(stem),
+        None => prefix.strip_suffix('_')?,
+    };
+    if stem.is_empty() {
+        return None;
+    }
+    Some((stem, value.parse().ok()?))
+}
+
+/// Drive-strength families of two or more macros, members sorted from the
+/// weakest drive, families sorted by stem
+///
+/// A name defined in several libraries is counted once.
+pub fn drive_families<'a>(
+    macros: impl IntoIterator<Item = &'a LefMacro>,
+) -> Vec<(String, Vec<&'a LefMacro>)> {
+    let mut seen = HashSet::new();
+    let mut by_stem: BTreeMap<&str, Vec<(f64, &LefMacro)>> = BTreeMap::new();
+    for macro_def in macros {
+        if !seen.insert(macro_def.name.as_str()) {
+            continue;
+        }
+        if let Some((stem, drive)) = drive_strength(&macro_def.name) {
+            by_stem.entry(stem).or_default().push((drive, macro_def));
+        }
+    }
+
+    by_stem
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(stem, mut members)| {
+            members.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.name.cmp(&b.1.name)));
+            (
+                stem.to_string(),
+                members.into_iter().map(|(_, m)| m).collect(),
+            )
+        })
+        .collect()
+}
+
+/// Pins of every family member that are missing, extra or declared with
+/// another DIRECTION or USE than in the weakest member
+pub fn pin_divergences<'a>(macros: impl IntoIterator<Item = &'a LefMacro>) -> Vec<PinDivergence> {
+    let mut divergences = Vec::new();
+    for (family, members) in drive_families(macros) {
+        let reference = members[0];
+        for member in &members[1..] {
+            let mut report = |pin_name: &str, message: String| {
+                divergences.push(PinDivergence {
+                    family: family.clone(),
+                    macro_name: member.name.clone(),
+                    reference: reference.name.clone(),
+                    pin_name: pin_name.to_string(),
+                    message,
+                });
+            };
+            for expected in &reference.pins {
+                let Some(pin) = member.pins.iter().find(|p| p.name == expected.name) else {
+                    report(
+                        &expected.name,
+                        format!("missing, {} has it", reference.name),
+                    );
+                    continue;
+                };
+                if pin.direction != expected.direction {
+                    report(
+                        &pin.name,
+                        format!(
+                            "DIRECTION {}, {} has {}",
+                            or_none(&pin.direction),
+                            reference.name,
+                            or_none(&expected.direction)
+                        ),
+                    );
+                }
+                if pin_use(pin) != pin_use(expected) {
+                    report(
+                        &pin.name,
+                        format!(
+                            "USE {}, {} has {}",
+                            pin_use(pin),
+                            reference.name,
+                            pin_use(expected)
+                        ),
+                    );
+                }
+            }
+            for pin in &member.pins {
+                if !reference.pins.iter().any(|p| p.name == pin.name) {
+                    report(&pin.name, format!("not in {}", reference.name));
+                }
+            }
+        }
+    }
+    divergences
+}
+
+/// USE of a pin, SIGNAL when not given as in LEF
+fn pin_use(pin: &LefPin) -> &str {
+    if pin.use_type.is_empty() {
+        "SIGNAL"
+    } else {
+        &pin.use_type
+    }
+}
+
+fn or_none(value: &str) -> &str {
+    if value.is_empty() {
+        "none"
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{self, pin};
+
+    fn cell(name: &str, pins: Vec<LefPin>) -> LefMacro {
+        test_fixtures::cell(name, 1.0, 1.8, pins)
+    }
+
+    #[test]
+    fn test_drive_strength() {
+        assert_eq!(drive_strength("BUFX2"), Some(("BUF", 2.0)));
+        assert_eq!(drive_strength("NAND2_X4"), Some(("NAND2", 4.0)));
+        assert_eq!(
+            drive_strength("sky130_fd_sc_hd__buf_16"),
+            Some(("sky130_fd_sc_hd__buf", 16.0))
+        );
+        assert_eq!(drive_strength("INVX0P5"), Some(("INV", 0.5)));
+        assert_eq!(drive_strength("NAND2"), None);
+        assert_eq!(drive_strength("X1"), None);
+        assert_eq!(drive_strength("FILLER"), None);
+    }
+
+    #[test]
+    fn test_pin_divergences() {
+        let macros = vec![
+            cell(
+                "BUFX4",
+                vec![pin("A", "INPUT", ""), pin("Z", "OUTPUT", "SIGNAL")],
+            ),
+            cell(
+                "BUFX1",
+                vec![pin("A", "INPUT", "SIGNAL"), pin("Y", "OUTPUT", "SIGNAL")],
+            ),
+            cell(
+                "BUFX2",
+                vec![pin("A", "INOUT", "SIGNAL"), pin("Y", "OUTPUT", "CLOCK")],
+            ),
+            // Alone in its family
+            cell("INVX1", vec![pin("A", "INPUT", "")]),
+        ];
+        let families = drive_families(&macros);
+        assert_eq!(families.len(), 1);
+        let names: Vec<&str> = families[0].1.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["BUFX1", "BUFX2", "BUFX4"]);
+
+        let divergences = pin_divergences(&macros);
+        assert!(divergences.iter().all(|d| d.reference == "BUFX1"));
+        let found: Vec<(&str, &str, &str)> = divergences
+            .iter()
+            .map(|d| {
+                let kind = d.message.split(',').next().unwrap_or_default();
+                (d.macro_name.as_str(), d.pin_name.as_str(), kind)
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("BUFX2", "A", "DIRECTION INOUT"),
+                ("BUFX2", "Y", "USE CLOCK"),
+                ("BUFX4", "Y", "missing"),
+                ("BUFX4", "Z", "not in BUFX1"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,156 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Footprint families of library cells
+//!
+//! Drive-strength variants of a cell (INVX1, INVX2, ...) usually share the
+//! outline, the SITE and the pin locations while their internal geometry
+//! differs. Such cells can replace each other in a placed design without
+//! moving anything. The footprint is hashed from the size, the site and the
+//! pin centers, rounded to `FOOTPRINT_QUANTUM` so parser noise does not
+//! split a family.
+
+use super::LefMacro;
+use std::collections::BTreeMap;
+
+/// Rounding step in microns applied before hashing
+pub const FOOTPRINT_QUANTUM: f64 = 1e-4;
+
+/// Cells sharing one footprint
+#[derive(Debug, Clone, PartialEq)]
+pub struct FootprintFamily {
+    pub width: f64,
+    pub height: f64,
+    pub site: String,
+    /// Member macro names, sorted
+    pub members: Vec<String>,
+}
+
+impl FootprintFamily {
+    /// Short description for the cell tree, e.g. "1.200 x 1.800 core"
+    pub fn label(&self) -> String {
+        if self.site.is_empty() {
+            format!("{:.3} x {:.3}", self.width, self.height)
+        } else {
+            format!("{:.3} x {:.3} {}", self.width, self.height, self.site)
+        }
+    }
+}
+
+/// Hash of the footprint of a macro: SIZE, SITE and pin name and center
+///
+/// Pins are sorted by name, so the PIN order in the LEF does not matter.
+pub fn footprint_hash(macro_def: &LefMacro) -> String {
+    let quantize = |value: f64| (value / FOOTPRINT_QUANTUM).round() as i64;
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&quantize(macro_def.size_x).to_le_bytes());
+    hasher.update(&quantize(macro_def.size_y).to_le_bytes());
+    hasher.update(macro_def.site.as_bytes());
+
+    let mut pins: Vec<_> = macro_def.pins.iter().collect();
+    pins.sort_by(|a, b| a.name.cmp(&b.name));
+    for pin in pins {
+        hasher.update(b"PIN");
+        hasher.update(pin.name.as_bytes());
+        if let Some((x, y)) = pin.center() {
+            // Centers are taken relative to the outline, not the ORIGIN
+            hasher.update(&quantize(x + macro_def.origin.0).to_le_bytes());
+            hasher.update(&quantize(y + macro_def.origin.1).to_le_bytes());
+        }
+    }
+    hasher.finalize().to_hex().to_string()
+}
+
+/// Families of two or more macros with the same footprint
+///
+/// A name defined in several libraries is counted once. Families are sorted
+/// by their first member.
+pub fn footprint_families<'a>(
+    macros: impl IntoIterator<Item = &'a LefMacro>,
+) -> Vec<FootprintFamily> {
+    let mut by_hash: BTreeMap<String, FootprintFamily> = BTreeMap::new();
+    for macro_def in macros {
+        let family = by_hash
+            .entry(footprint_hash(macro_def))
+            .or_insert_with(|| FootprintFamily {
+                width: macro_def.size_x,
+                height: macro_def.size_y,
+                site: macro_def.site.clone(),
+                members: Vec::new(),
+            });
+        if !family.members.contains(&macro_def.name) {
+            family.members.push(macro_def.name.clone());
+        }
+    }
+
+    let mut families: Vec<FootprintFamily> = by_hash
+        .into_values()
+        .filter(|f| f.members.len() > 1)
+        .map(|mut f| {
+            f.members.sort();
+            f
+        })
+        .collect();
+    families.sort_by(|a, b| a.members[0].cmp(&b.members[0]));
+    families
+}
+
+/// Members of the family that `macro_name` belongs to, empty when it has
+/// no family
+pub fn family_members<'a>(families: &'a [FootprintFamily], macro_name: &str) -> &'a [String] {
+    families
+        .iter()
+        .find(|f| f.members.iter().any(|m| m == macro_name))
+        .map(|f| f.members.as_slice())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lef::{LefObstruction, LefPin};
+    use crate::test_fixtures::{self, pin_with_rects, rect};
+
+    fn pin(name: &str, x: f64) -> LefPin {
+        pin_with_rects(name, "INPUT", vec![rect("M1", x, 0.4, x + 0.1, 0.6)])
+    }
+
+    fn cell(name: &str, width: f64, pins: Vec<LefPin>, obs_width: f64) -> LefMacro {
+        LefMacro {
+            site: "core".to_string(),
+            obs: vec![LefObstruction {
+                rects: vec![rect("M1", 0.0, 0.0, obs_width, 0.2)],
+                polygons: Vec::new(),
+            }],
+            ..test_fixtures::cell(name, width, 1.8, pins)
+        }
+    }
+
+    #[test]
+    fn test_drive_strengths_form_a_family() {
+        let macros = [
+            cell("INVX1", 1.2, vec![pin("A", 0.2), pin("Y", 0.8)], 0.5),
+            // Different internal geometry and pin order, same footprint
+            cell("INVX2", 1.2, vec![pin("Y", 0.8), pin("A", 0.20000001)], 0.9),
+            // Pin moved
+            cell("INVX4", 1.2, vec![pin("A", 0.2), pin("Y", 1.0)], 0.5),
+            cell("BUFX1", 1.6, vec![pin("A", 0.2), pin("Y", 0.8)], 0.5),
+        ];
+        let families = footprint_families(&macros);
+        assert_eq!(families.len(), 1);
+        assert_eq!(families[0].members, ["INVX1", "INVX2"]);
+        assert_eq!(families[0].label(), "1.200 x 1.800 core");
+    }
+
+    #[test]
+    fn test_family_members_lookup() {
+        let families = vec![FootprintFamily {
+            width: 1.0,
+            height: 1.0,
+            site: String::new(),
+            members: vec!["A1".to_string(), "A2".to_string()],
+        }];
+        assert_eq!(family_members(&families, "A2"), ["A1", "A2"]);
+        assert!(family_members(&families, "B1").is_empty());
+    }
+}
@@ -0,0 +1,240 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Layer aliases across LEF libraries
+//!
+//! Libraries from different vendors name the same layer M1, METAL1 or me1.
+//! An alias table maps such names onto the layers of the technology LEF, so
+//! the shapes of mixed libraries share one layer set for visibility, colors
+//! and export.
+//!
+//! Aliases are suggested by folding names: case and `_` or `-` are ignored
+//! and the usual spellings of metal (M, ME, MET, METAL) and via (V, VIA)
+//! layers followed by a number are taken as the same layer.
+
+use super::{Lef, LefLayer, LefMacro};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Map from layer names used by libraries to technology layers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LayerAliases {
+    aliases: BTreeMap<String, String>,
+}
+
+impl LayerAliases {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map `alias` onto `layer`; mapping a name onto itself removes it
+    pub fn insert(&mut self, alias: impl Into<String>, layer: impl Into<String>) {
+        let (alias, layer) = (alias.into(), layer.into());
+        if alias == layer {
+            self.aliases.remove(&alias);
+        } else {
+            self.aliases.insert(alias, layer);
+        }
+    }
+
+    pub fn remove(&mut self, alias: &str) {
+        self.aliases.remove(alias);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+
+    /// (alias, layer) pairs sorted by alias
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.aliases.iter().map(|(a, l)| (a.as_str(), l.as_str()))
+    }
+
+    /// Layer a name stands for, the name itself when it is no alias
+    pub fn resolve<'a>(&'a self, name: &'a str) -> &'a str {
+        self.aliases.get(name).map_or(name, String::as_str)
+    }
+
+    /// Rename the pin and obstruction shapes of a macro, returning how
+    /// many shapes changed layer
+    pub fn apply_to_macro(&self, macro_def: &mut LefMacro) -> usize {
+        if self.aliases.is_empty() {
+            return 0;
+        }
+        let mut renamed = 0;
+        let mut rename = |layer: &mut String| {
+            if let Some(target) = self.aliases.get(layer.as_str()) {
+                layer.clone_from(target);
+                renamed += 1;
+            }
+        };
+        for port in macro_def.pins.iter_mut().flat_map(|pin| &mut pin.ports) {
+            port.rects.iter_mut().for_each(|r| rename(&mut r.layer));
+            port.polygons.iter_mut().for_each(|p| rename(&mut p.layer));
+        }
+        for obs in &mut macro_def.obs {
+            obs.rects.iter_mut().for_each(|r| rename(&mut r.layer));
+            obs.polygons.iter_mut().for_each(|p| rename(&mut p.layer));
+        }
+        renamed
+    }
+
+    /// Rename the shapes of every macro of a library
+    pub fn apply(&self, lef: &mut Lef) -> usize {
+        lef.macros
+            .iter_mut()
+            .map(|macro_def| self.apply_to_macro(macro_def))
+            .sum()
+    }
+}
+
+/// Folded form of a layer name, equal for the spellings of one layer
+pub fn fold_layer_name(name: &str) -> String {
+    let folded: String = name
+        .chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .collect::<String>()
+        .to_ascii_uppercase();
+    let split = folded
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(folded.len());
+    let (prefix, number) = folded.split_at(split);
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+        return folded;
+    }
+    match prefix {
+        "M" | "ME" | "MET" | "METAL" => format!("M{number}"),
+        "V" | "VIA" => format!("V{number}"),
+        _ => folded,
+    }
+}
+
+/// Layers the pin and obstruction shapes of a library are on
+pub fn macro_layer_names(lef: &Lef) -> BTreeSet<&str> {
+    let mut layers = BTreeSet::new();
+    for macro_def in &lef.macros {
+        for port in macro_def.pins.iter().flat_map(|pin| &pin.ports) {
+            layers.extend(port.rects.iter().map(|r| r.layer.as_str()));
+            layers.extend(port.polygons.iter().map(|p| p.layer.as_str()));
+        }
+        for obs in &macro_def.obs {
+            layers.extend(obs.rects.iter().map(|r| r.layer.as_str()));
+            layers.extend(obs.polygons.iter().map(|p| p.layer.as_str()));
+        }
+    }
+    layers
+}
+
+/// Aliases for the `used` layers the technology does not define
+///
+/// A used name gets an alias when exactly one technology layer folds to
+/// the same name. The second list holds the names left without one.
+pub fn suggest_aliases<'a>(
+    technology: &[LefLayer],
+    used: impl IntoIterator<Item = &'a str>,
+) -> (Vec<(String, String)>, Vec<String>) {
+    let mut by_folded: HashMap<String, Vec<&str>> = HashMap::new();
+    for layer in technology {
+        by_folded
+            .entry(fold_layer_name(&layer.name))
+            .or_default()
+            .push(&layer.name);
+    }
+    let known: BTreeSet<&str> = technology.iter().map(|l| l.name.as_str()).collect();
+
+    let mut suggested = Vec::new();
+    let mut unknown = Vec::new();
+    for name in used {
+        if known.contains(name) {
+            continue;
+        }
+        match by_folded.get(&fold_layer_name(name)).map(Vec::as_slice) {
+            Some([layer]) => suggested.push((name.to_string(), layer.to_string())),
+            _ => unknown.push(name.to_string()),
+        }
+    }
+    (suggested, unknown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lef::{LefObstruction, LefRect};
+    use crate::test_fixtures;
+
+    fn layer(name: &str) -> LefLayer {
+        LefLayer {
+            name: name.to_string(),
+            layer_type: "ROUTING".to_string(),
+            ..LefLayer::default()
+        }
+    }
+
+    fn rect(layer: &str) -> LefRect {
+        test_fixtures::rect(layer, 0.0, 0.0, 1.0, 1.0)
+    }
+
+    #[test]
+    fn test_fold_layer_name() {
+        assert_eq!(fold_layer_name("METAL1"), "M1");
+        assert_eq!(fold_layer_name("me1"), "M1");
+        assert_eq!(fold_layer_name("Metal_12"), "M12");
+        assert_eq!(fold_layer_name("via1"), "V1");
+        assert_eq!(fold_layer_name("VIA12"), "V12");
+        assert_eq!(fold_layer_name("poly"), "POLY");
+        assert_eq!(fold_layer_name("M1A"), "M1A");
+    }
+
+    #[test]
+    fn test_suggest_and_apply() {
+        let technology = [layer("M1"), layer("M2"), layer("VIA1")];
+        let (suggested, unknown) =
+            suggest_aliases(&technology, ["M1", "METAL1", "me2", "V1", "NWELL"]);
+        assert_eq!(
+            suggested,
+            [
+                ("METAL1".to_string(), "M1".to_string()),
+                ("me2".to_string(), "M2".to_string()),
+                ("V1".to_string(), "VIA1".to_string()),
+            ]
+        );
+        assert_eq!(unknown, ["NWELL"]);
+
+        let mut aliases = LayerAliases::new();
+        for (alias, target) in suggested {
+            aliases.insert(alias, target);
+        }
+        aliases.insert("M2", "M2");
+        assert_eq!(aliases.resolve("me2"), "M2");
+        assert_eq!(aliases.resolve("NWELL"), "NWELL");
+
+        let mut lef = Lef {
+            macros: vec![LefMacro {
+                name: "INV".to_string(),
+                class: "CORE".to_string(),
+                foreign: String::new(),
+                origin: (0.0, 0.0),
+                size_x: 1.0,
+                size_y: 1.0,
+                symmetry: Vec::new(),
+                site: String::new(),
+                pins: Vec::new(),
+                obs: vec![LefObstruction {
+                    rects: vec![rect("METAL1"), rect("M1"), rect("NWELL")],
+                    polygons: Vec::new(),
+                }],
+                ..LefMacro::default()
+            }],
+            ..Lef::default()
+        };
+        assert_eq!(aliases.apply(&mut lef), 1);
+        assert_eq!(
+            macro_layer_names(&lef).into_iter().collect::<Vec<_>>(),
+            ["M1", "NWELL"]
+        );
+
+        // Colliding spellings are left to the user
+        let (suggested, unknown) = suggest_aliases(&[layer("M1"), layer("metal1")], ["ME1"]);
+        assert!(suggested.is_empty());
+        assert_eq!(unknown, ["ME1"]);
+    }
+}
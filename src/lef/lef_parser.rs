@@ -4,16 +4,19 @@
 use nom::{
     branch::alt,
     bytes::complete::{tag, take_until},
-    character::complete::{alpha1, alphanumeric1, char, multispace0, space0, space1},
-    combinator::recognize,
+    character::complete::{char, multispace0, space0, space1},
     multi::many0,
-    number::complete::double,
-    sequence::{delimited, pair},
+    sequence::delimited,
     IResult, Parser,
 };
 
-use super::{Lef, LefMacro, LefObstruction, LefPin, LefPolygon, LefPort, LefRect};
+use super::{
+    Lef, LefDensity, LefLayer, LefMacro, LefObstruction, LefPin, LefPolygon, LefPort, LefRect,
+    LefSite,
+};
 use crate::def::preprocessor::preprocess;
+use crate::number::{finite_double, ParseFinite};
+use crate::progress::{ProgressCallback, ProgressReporter};
 
 fn calculate_polygon_winding(points: &[(f64, f64)]) -> bool {
     if points.len() < 3 {
@@ -29,12 +32,15 @@ fn calculate_polygon_winding(points: &[(f64, f64)]) -> bool {
     sum > 0.0 // clockwise (hole) if positive, counterclockwise (solid) if negative
 }
 
+/// LEF name: any run of non-whitespace characters, backslash escapes allowed
 fn identifier(input: &str) -> IResult<&str, &str> {
-    recognize(pair(
-        alt((alpha1, tag("_"))),
-        many0(alt((alphanumeric1, tag("_")))),
-    ))
-    .parse(input)
+    match crate::identifier::parse_escaped_token(input) {
+        Some(name) => Ok((&input[name.len()..], name)),
+        None => Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Alpha,
+        ))),
+    }
 }
 
 #[allow(dead_code)]
@@ -55,13 +61,13 @@ fn parse_rect(input: &str) -> IResult<&str, LefRect> {
     let (input, _) = multispace0(input)?;
     let (input, _) = tag("RECT")(input)?;
     let (input, _) = space0(input)?;
-    let (input, xl) = double(input)?;
+    let (input, xl) = finite_double(input)?;
     let (input, _) = space0(input)?;
-    let (input, yl) = double(input)?;
+    let (input, yl) = finite_double(input)?;
     let (input, _) = space0(input)?;
-    let (input, xh) = double(input)?;
+    let (input, xh) = finite_double(input)?;
     let (input, _) = space0(input)?;
-    let (input, yh) = double(input)?;
+    let (input, yh) = finite_double(input)?;
     let (input, _) = multispace0(input)?;
     let (input, _) = tag(";")(input)?;
 
@@ -110,9 +116,9 @@ fn parse_polygon(input: &str) -> IResult<&str, LefPolygon> {
         }
 
         // Try to parse a coordinate pair
-        if let Ok((rest, x)) = double::<&str, nom::error::Error<&str>>(rest) {
+        if let Ok((rest, x)) = finite_double(rest) {
             let (rest, _) = space1(rest)?;
-            if let Ok((rest, y)) = double::<&str, nom::error::Error<&str>>(rest) {
+            if let Ok((rest, y)) = finite_double(rest) {
                 points.push((x, y));
                 remaining = rest;
                 continue;
@@ -164,12 +170,13 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
     let (input, name) = identifier(input)?;
     let (input, _) = multispace0(input)?;
 
-    println!("[DBG] Parsing MACRO: {name}");
+    eprintln!("[DBG] Parsing MACRO: {name}");
 
     // Parse macro content with PIN extraction
     let remaining = input;
     let mut pins: Vec<LefPin> = Vec::new();
     let mut obstructions: Vec<LefObstruction> = Vec::new();
+    let mut density: Vec<LefDensity> = Vec::new();
     let mut size_x = 0.0;
     let mut size_y = 0.0;
     let mut origin_x = 0.0;
@@ -179,9 +186,11 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
     let mut _source = String::new();
     let mut _foreign_x = 0.0;
     let mut _foreign_y = 0.0;
-    let symmetry = Vec::new();
+    let mut symmetry = Vec::new();
 
+    // Exact match so that "END INV" does not end a macro named "IN"
     let end_pattern = format!("END {name}");
+    let end_pattern_with_trailer = format!("{end_pattern} ");
 
     // Use preprocessor for multi-line statement support
     let preprocessed = preprocess(remaining);
@@ -195,8 +204,8 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
             continue;
         }
 
-        if line.starts_with(&end_pattern) {
-            println!(
+        if line == end_pattern || line.starts_with(&end_pattern_with_trailer) {
+            eprintln!(
                 "[PASS] Found macro: {} (size: {:.3}x{:.3}, pins: {})",
                 name,
                 size_x,
@@ -224,6 +233,7 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
                         } else {
                             vec![]
                         },
+                        density,
                     },
                 ));
             }
@@ -237,7 +247,12 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
 
         match parts[0] {
             "CLASS" if parts.len() > 1 => {
-                class = parts[1].trim_end_matches(';').to_string();
+                // Keep the subclass too, e.g. "CORE SPACER"
+                class = parts[1..]
+                    .join(" ")
+                    .trim_end_matches(';')
+                    .trim()
+                    .to_string();
             }
             "SOURCE" if parts.len() > 1 => {
                 _source = parts[1].trim_end_matches(';').to_string();
@@ -245,21 +260,29 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
             "SITE" if parts.len() > 1 => {
                 site = parts[1].trim_end_matches(';').to_string();
             }
+            "SYMMETRY" => {
+                symmetry = parts[1..]
+                    .iter()
+                    .map(|p| p.trim_end_matches(';'))
+                    .filter(|p| !p.is_empty())
+                    .map(str::to_string)
+                    .collect();
+            }
             "ORIGIN" if parts.len() > 2 => {
-                if let (Ok(x), Ok(y)) = (parts[1].parse::<f64>(), parts[2].parse::<f64>()) {
+                if let (Ok(x), Ok(y)) = (parts[1].parse_finite(), parts[2].parse_finite()) {
                     origin_x = x;
                     origin_y = y;
                 }
             }
             "SIZE" if parts.len() > 3 && parts[2] == "BY" => {
-                if let (Ok(x), Ok(y)) = (parts[1].parse::<f64>(), parts[3].parse::<f64>()) {
+                if let (Ok(x), Ok(y)) = (parts[1].parse_finite(), parts[3].parse_finite()) {
                     size_x = x;
                     size_y = y;
                 }
             }
             "FOREIGN" if parts.len() > 3 => {
                 _source = parts[1].to_string();
-                if let (Ok(x), Ok(y)) = (parts[2].parse::<f64>(), parts[3].parse::<f64>()) {
+                if let (Ok(x), Ok(y)) = (parts[2].parse_finite(), parts[3].parse_finite()) {
                     _foreign_x = x;
                     _foreign_y = y;
                 }
@@ -267,7 +290,7 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
             "PIN" if parts.len() > 1 => {
                 // Parse PIN section
                 let pin_name = parts[1].to_string();
-                println!("[DBG]   Parsing PIN: {pin_name}");
+                eprintln!("[DBG]   Parsing PIN: {pin_name}");
 
                 let mut direction = String::new();
                 let mut use_type = String::new();
@@ -284,7 +307,7 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
                         "USE" if j + 1 < parts.len() => {
                             use_type = parts[j + 1].trim_end_matches(';').to_string();
                             if use_type == "POWER" || use_type == "GROUND" {
-                                println!(
+                                eprintln!(
                                     "[DBG]     Found POWER/GROUND pin: {pin_name} (USE: {use_type})"
                                 );
                             }
@@ -313,7 +336,7 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
                             "USE" if pin_parts.len() > 1 => {
                                 use_type = pin_parts[1].trim_end_matches(';').to_string();
                                 if use_type == "POWER" || use_type == "GROUND" {
-                                    println!(
+                                    eprintln!(
                                         "[DBG]     Found POWER/GROUND pin: {pin_name} (USE: {use_type})"
                                     );
                                 }
@@ -323,7 +346,7 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
                             }
                             "PORT" => {
                                 // Parse PORT content
-                                println!(
+                                eprintln!(
                                     "[DBG]     Found PORT in pin {pin_name} (USE: {use_type})"
                                 );
                                 let mut rects = Vec::new();
@@ -337,7 +360,7 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
                                         current_layer =
                                             pin_parts[j + 1].trim_end_matches(';').to_string();
                                         if use_type == "POWER" || use_type == "GROUND" {
-                                            println!("[DBG]       POWER/GROUND pin {pin_name} using layer: {current_layer}");
+                                            eprintln!("[DBG]       POWER/GROUND pin {pin_name} using layer: {current_layer}");
                                         }
                                         break;
                                     }
@@ -359,21 +382,21 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
                                     let port_parts: Vec<&str> =
                                         port_line.split_whitespace().collect();
                                     if !port_parts.is_empty() {
-                                        println!("[DBG]       Processing port line: {port_line}");
+                                        eprintln!("[DBG]       Processing port line: {port_line}");
                                         match port_parts[0] {
                                             "LAYER" if port_parts.len() > 1 => {
                                                 current_layer =
                                                     port_parts[1].trim_end_matches(';').to_string();
                                                 if use_type == "POWER" || use_type == "GROUND" {
-                                                    println!("[DBG]       POWER/GROUND pin {pin_name} using layer: {current_layer}");
+                                                    eprintln!("[DBG]       POWER/GROUND pin {pin_name} using layer: {current_layer}");
                                                 }
                                             }
                                             "RECT" if port_parts.len() >= 5 => {
                                                 if let (Ok(xl), Ok(yl), Ok(xh), Ok(yh)) = (
-                                                    port_parts[1].parse::<f64>(),
-                                                    port_parts[2].parse::<f64>(),
-                                                    port_parts[3].parse::<f64>(),
-                                                    port_parts[4].parse::<f64>(),
+                                                    port_parts[1].parse_finite(),
+                                                    port_parts[2].parse_finite(),
+                                                    port_parts[3].parse_finite(),
+                                                    port_parts[4].parse_finite(),
                                                 ) {
                                                     rects.push(LefRect {
                                                         layer: current_layer.clone(),
@@ -383,9 +406,9 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
                                                         yh,
                                                     });
                                                     if use_type == "POWER" || use_type == "GROUND" {
-                                                        println!("[DBG]       Added POWER/GROUND rect on {current_layer}: ({xl:.1},{yl:.1}) -> ({xh:.1},{yh:.1})");
+                                                        eprintln!("[DBG]       Added POWER/GROUND rect on {current_layer}: ({xl:.1},{yl:.1}) -> ({xh:.1},{yh:.1})");
                                                     } else {
-                                                        println!("[DBG]       Added rect on {current_layer}: ({xl:.1},{yl:.1}) -> ({xh:.1},{yh:.1})");
+                                                        eprintln!("[DBG]       Added rect on {current_layer}: ({xl:.1},{yl:.1}) -> ({xh:.1},{yh:.1})");
                                                     }
                                                 }
                                             }
@@ -432,6 +455,7 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
                                                 }
 
                                                 // Parse coordinate pairs
+                                                let mut corrupt = false;
                                                 while part_idx + 1 < poly_parts.len() {
                                                     let x_str =
                                                         poly_parts[part_idx].trim_end_matches(';');
@@ -439,15 +463,23 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
                                                         .trim_end_matches(';');
 
                                                     if let (Ok(x), Ok(y)) =
-                                                        (x_str.parse::<f64>(), y_str.parse::<f64>())
+                                                        (x_str.parse_finite(), y_str.parse_finite())
                                                     {
                                                         points.push((x, y));
                                                         part_idx += 2;
                                                     } else {
+                                                        // A coordinate that is not a finite number drops the
+                                                        // polygon rather than truncating it
+                                                        corrupt = !x_str.is_empty();
                                                         break;
                                                     }
                                                 }
 
+                                                if corrupt {
+                                                    eprintln!("[DBG]       Skipped polygon on {current_layer} with an invalid coordinate");
+                                                    points.clear();
+                                                }
+
                                                 if !points.is_empty() {
                                                     let is_hole =
                                                         calculate_polygon_winding(&points);
@@ -456,7 +488,7 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
                                                         points,
                                                         is_hole,
                                                     });
-                                                    println!("[DBG]       Added polygon on {} with {} points ({}){}: {:?}",
+                                                    eprintln!("[DBG]       Added polygon on {} with {} points ({}){}: {:?}",
                                                            current_layer, polygons.last().unwrap().points.len(),
                                                            if is_hole { "hole" } else { "solid" },
                                                            if let Some(mask) = mask_num { format!(" MASK {mask}") } else { String::new() },
@@ -491,10 +523,16 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
             }
             "OBS" => {
                 // Parse OBS section
-                println!("[DBG]   Parsing OBS");
+                eprintln!("[DBG]   Parsing OBS");
                 let mut rects = Vec::new();
                 let mut polygons = Vec::new();
                 let mut current_layer = String::new();
+                // The preprocessor joins "OBS" with the first "LAYER x ;"
+                if let Some(pos) = parts.iter().position(|p| *p == "LAYER") {
+                    if let Some(layer) = parts.get(pos + 1) {
+                        current_layer = layer.trim_end_matches(';').to_string();
+                    }
+                }
 
                 i += 1;
                 while i < lines.len() {
@@ -505,17 +543,17 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
 
                     let obs_parts: Vec<&str> = obs_line.split_whitespace().collect();
                     if !obs_parts.is_empty() {
-                        println!("[DBG]     Processing OBS line: {obs_line}");
+                        eprintln!("[DBG]     Processing OBS line: {obs_line}");
                         match obs_parts[0] {
                             "LAYER" if obs_parts.len() > 1 => {
                                 current_layer = obs_parts[1].to_string();
                             }
                             "RECT" if obs_parts.len() >= 5 => {
                                 if let (Ok(xl), Ok(yl), Ok(xh), Ok(yh)) = (
-                                    obs_parts[1].parse::<f64>(),
-                                    obs_parts[2].parse::<f64>(),
-                                    obs_parts[3].parse::<f64>(),
-                                    obs_parts[4].parse::<f64>(),
+                                    obs_parts[1].parse_finite(),
+                                    obs_parts[2].parse_finite(),
+                                    obs_parts[3].parse_finite(),
+                                    obs_parts[4].parse_finite(),
                                 ) {
                                     rects.push(LefRect {
                                         layer: current_layer.clone(),
@@ -524,7 +562,7 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
                                         xh,
                                         yh,
                                     });
-                                    println!("[DBG]     Added OBS rect on {current_layer}: ({xl:.1},{yl:.1}) -> ({xh:.1},{yh:.1})");
+                                    eprintln!("[DBG]     Added OBS rect on {current_layer}: ({xl:.1},{yl:.1}) -> ({xh:.1},{yh:.1})");
                                 }
                             }
                             "POLYGON" => {
@@ -564,20 +602,29 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
                                 }
 
                                 // Parse coordinate pairs
+                                let mut corrupt = false;
                                 while part_idx + 1 < poly_parts.len() {
                                     let x_str = poly_parts[part_idx].trim_end_matches(';');
                                     let y_str = poly_parts[part_idx + 1].trim_end_matches(';');
 
                                     if let (Ok(x), Ok(y)) =
-                                        (x_str.parse::<f64>(), y_str.parse::<f64>())
+                                        (x_str.parse_finite(), y_str.parse_finite())
                                     {
                                         points.push((x, y));
                                         part_idx += 2;
                                     } else {
+                                        // A coordinate that is not a finite number drops the
+                                        // polygon rather than truncating it
+                                        corrupt = !x_str.is_empty();
                                         break;
                                     }
                                 }
 
+                                if corrupt {
+                                    eprintln!("[DBG]       Skipped polygon on {current_layer} with an invalid coordinate");
+                                    points.clear();
+                                }
+
                                 if !points.is_empty() {
                                     let is_hole = calculate_polygon_winding(&points);
                                     polygons.push(LefPolygon {
@@ -585,7 +632,7 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
                                         points,
                                         is_hole,
                                     });
-                                    println!(
+                                    eprintln!(
                                         "[DBG]     Added OBS polygon on {} with {} points ({}){}: {:?}",
                                         current_layer,
                                         polygons.last().unwrap().points.len(),
@@ -607,13 +654,55 @@ fn parse_simple_macro(input: &str) -> IResult<&str, LefMacro> {
                 // Store the obstruction data in the macro
                 obstructions.push(LefObstruction { rects, polygons });
 
-                println!(
+                eprintln!(
                     "[DBG]   OBS parsing complete: {} rects, {} polygons",
                     obstructions.last().unwrap().rects.len(),
                     obstructions.last().unwrap().polygons.len()
                 );
                 continue;
             }
+            "DENSITY" => {
+                // The preprocessor joins "DENSITY" with the first "LAYER x ;"
+                let mut current_layer = parts
+                    .iter()
+                    .position(|p| *p == "LAYER")
+                    .and_then(|pos| parts.get(pos + 1))
+                    .map(|layer| layer.trim_end_matches(';').to_string())
+                    .unwrap_or_default();
+
+                i += 1;
+                while i < lines.len() {
+                    let density_parts: Vec<&str> = lines[i]
+                        .split_whitespace()
+                        .map(|p| p.trim_end_matches(';'))
+                        .filter(|p| !p.is_empty())
+                        .collect();
+                    match density_parts[..] {
+                        ["END", ..] => break,
+                        ["LAYER", layer, ..] => current_layer = layer.to_string(),
+                        ["RECT", xl, yl, xh, yh, value, ..] => {
+                            let numbers = [xl, yl, xh, yh, value].map(|v| v.parse_finite());
+                            if let [Ok(xl), Ok(yl), Ok(xh), Ok(yh), Ok(value)] = numbers {
+                                density.push(LefDensity {
+                                    layer: current_layer.clone(),
+                                    xl,
+                                    yl,
+                                    xh,
+                                    yh,
+                                    density: value,
+                                });
+                            }
+                        }
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                eprintln!(
+                    "[DBG]   DENSITY parsing complete: {} windows",
+                    density.len()
+                );
+                continue;
+            }
             _ => {}
         }
         i += 1;
@@ -656,12 +745,23 @@ fn skip_to_macro(input: &str) -> IResult<&str, &str> {
 }
 
 pub fn parse_lef(input: &str) -> IResult<&str, Lef> {
-    println!("[DBG] Starting LEF parsing...");
-    println!("[DBG] Preprocessing LEF file...");
+    parse_lef_with_progress(input, None)
+}
+
+/// Parse LEF content, reporting the share of the text scanned for macros
+pub fn parse_lef_with_progress<'a>(
+    input: &'a str,
+    progress: Option<&ProgressCallback>,
+) -> IResult<&'a str, Lef> {
+    crate::profile_scope!("parse_lef");
+    let mut progress = ProgressReporter::new(progress);
+    progress.report(0.0, 0, "preprocessing");
+    eprintln!("[DBG] Starting LEF parsing...");
+    eprintln!("[DBG] Preprocessing LEF file...");
 
     // Preprocess entire file first
     let preprocessed = preprocess(input);
-    println!(
+    eprintln!(
         "[DBG] Preprocessed: {} logical lines from {} raw lines",
         preprocessed.lines.len(),
         input.lines().count()
@@ -671,7 +771,9 @@ pub fn parse_lef(input: &str) -> IResult<&str, Lef> {
     let mut macros = Vec::new();
 
     // Skip header content and find MACROs
+    let total = input.len().max(1) as f64;
     loop {
+        progress.report(1.0 - input.len() as f64 / total, macros.len(), "MACRO");
         let (rest, _) = multispace0(input)?;
         if rest.is_empty() {
             break;
@@ -691,6 +793,190 @@ pub fn parse_lef(input: &str) -> IResult<&str, Lef> {
         }
     }
 
-    println!("[PASS] LEF parsed: {} macros", macros.len());
-    Ok((input, Lef { macros }))
+    let database_microns = parse_database_microns(&preprocessed.lines);
+    let manufacturing_grid = parse_manufacturing_grid(&preprocessed.lines);
+    let (layers, sites) = parse_technology(&preprocessed.lines);
+    progress.report(1.0, macros.len(), "END LIBRARY");
+
+    eprintln!("[PASS] LEF parsed: {} macros", macros.len());
+    Ok((
+        input,
+        Lef {
+            macros,
+            database_microns,
+            manufacturing_grid,
+            layers,
+            sites,
+        },
+    ))
+}
+
+/// Value of `DATABASE MICRONS` inside the `UNITS ... END UNITS` block
+///
+/// Logical lines may merge the block header with its first statement, so
+/// the tokens are scanned as one stream.
+fn parse_database_microns(lines: &[String]) -> Option<f64> {
+    let tokens: Vec<&str> = lines.iter().flat_map(|l| l.split_whitespace()).collect();
+    let mut in_units = false;
+    for (i, window) in tokens.windows(3).enumerate() {
+        match window {
+            ["END", "UNITS", _] => in_units = false,
+            ["UNITS", _, _] if i == 0 || tokens[i - 1] != "END" => in_units = true,
+            ["DATABASE", "MICRONS", value] if in_units => {
+                return value.trim_end_matches(';').parse_finite().ok();
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Value of the top-level `MANUFACTURINGGRID` statement
+fn parse_manufacturing_grid(lines: &[String]) -> Option<f64> {
+    let tokens: Vec<&str> = lines.iter().flat_map(|l| l.split_whitespace()).collect();
+    tokens.windows(2).find_map(|window| match window {
+        ["MANUFACTURINGGRID", value] => value.trim_end_matches(';').parse_finite().ok(),
+        _ => None,
+    })
+}
+
+/// Top-level blocks closed by `END <name>` that are skipped
+const NAMED_BLOCKS: [&str; 4] = ["MACRO", "VIA", "VIARULE", "NONDEFAULTRULE"];
+
+/// Top-level blocks closed by `END <keyword>`
+const KEYWORD_BLOCKS: [&str; 4] = ["UNITS", "PROPERTYDEFINITIONS", "SPACING", "BEGINEXT"];
+
+/// Top-level `LAYER name ... END name` and `SITE name ... END name` blocks
+///
+/// Other named blocks are skipped whole, so the LAYER statements of VIA,
+/// NONDEFAULTRULE and MACRO geometry and the SITE reference of a MACRO are
+/// not mistaken for definitions.
+fn parse_technology(lines: &[String]) -> (Vec<LefLayer>, Vec<LefSite>) {
+    let text = lines.join(" ").replace(';', " ; ");
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let end_of = |from: usize, name: &str| {
+        (from..tokens.len())
+            .find(|&i| tokens[i] == "END" && tokens.get(i + 1) == Some(&name))
+            .unwrap_or(tokens.len())
+    };
+
+    let mut layers = Vec::new();
+    let mut sites = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        match (tokens[i], tokens.get(i + 1)) {
+            ("LAYER", Some(&name)) => {
+                let end = end_of(i + 2, name);
+                let mut layer = LefLayer {
+                    name: name.to_string(),
+                    ..LefLayer::default()
+                };
+                for statement in tokens[i + 2..end].split(|t| *t == ";") {
+                    apply_layer_statement(&mut layer, statement);
+                }
+                layers.push(layer);
+                i = end + 2;
+            }
+            ("SITE", Some(&name)) => {
+                let end = end_of(i + 2, name);
+                let mut site = LefSite {
+                    name: name.to_string(),
+                    ..LefSite::default()
+                };
+                for statement in tokens[i + 2..end].split(|t| *t == ";") {
+                    apply_site_statement(&mut site, statement);
+                }
+                sites.push(site);
+                i = end + 2;
+            }
+            (keyword, Some(&name)) if NAMED_BLOCKS.contains(&keyword) => {
+                i = end_of(i + 2, name) + 2;
+            }
+            (keyword, _) if KEYWORD_BLOCKS.contains(&keyword) => {
+                i = end_of(i + 1, keyword) + 2;
+            }
+            _ => i += 1,
+        }
+    }
+    (layers, sites)
+}
+
+fn apply_site_statement(site: &mut LefSite, statement: &[&str]) {
+    match statement {
+        ["CLASS", class, ..] => site.class = class.to_string(),
+        ["SIZE", width, "BY", height] => {
+            site.size_x = width.parse_finite().unwrap_or_default();
+            site.size_y = height.parse_finite().unwrap_or_default();
+        }
+        ["SYMMETRY", symmetry @ ..] => {
+            site.symmetry = symmetry.iter().map(|s| s.to_string()).collect();
+        }
+        _ => {}
+    }
+}
+
+fn apply_layer_statement(layer: &mut LefLayer, statement: &[&str]) {
+    let number = |token: Option<&&str>| token.and_then(|t| t.parse_finite().ok());
+    match statement {
+        ["TYPE", layer_type, ..] => layer.layer_type = layer_type.to_string(),
+        ["DIRECTION", direction, ..] => layer.direction = direction.to_string(),
+        ["PITCH", rest @ ..] => layer.pitch = number(rest.first()),
+        ["WIDTH", value] => layer.width = number(Some(value)),
+        // Later SPACING rules usually apply to wide wires or end of line
+        ["SPACING", rest @ ..] => {
+            if let Some(value) = number(rest.first()) {
+                layer.spacing = Some(layer.spacing.map_or(value, |s| s.min(value)));
+            }
+        }
+        ["RESISTANCE", "RPERSQ", value] | ["RESISTANCE", value] => {
+            layer.resistance = number(Some(value));
+        }
+        ["CAPACITANCE", "CPERSQDIST", value] => layer.capacitance = number(Some(value)),
+        ["AREA", value] => layer.min_area = number(Some(value)),
+        _ => {}
+    }
+}
+
+/// Find the text of one `MACRO name ... END name` block in LEF content
+///
+/// The returned slice starts at the MACRO line and includes the END line.
+pub fn extract_macro_source<'a>(content: &'a str, name: &str) -> Option<&'a str> {
+    extract_block_source(content, "MACRO", name)
+}
+
+/// Find the text of one `KEYWORD name ... END name` block, e.g. a LAYER
+pub fn extract_block_source<'a>(content: &'a str, block: &str, name: &str) -> Option<&'a str> {
+    let mut start = None;
+    let mut offset = 0;
+
+    for line in content.split_inclusive('\n') {
+        let mut tokens = line.split_whitespace();
+        let keyword = tokens.next();
+        let token_name = tokens.next();
+
+        match (start, keyword, token_name) {
+            (None, Some(word), Some(token)) if word == block && token == name => {
+                start = Some(offset)
+            }
+            (Some(begin), Some("END"), Some(token)) if token == name => {
+                return Some(&content[begin..offset + line.len()]);
+            }
+            _ => {}
+        }
+        offset += line.len();
+    }
+
+    None
+}
+
+/// Parse the source of exactly one MACRO block, e.g. text edited by the user
+pub fn parse_macro_source(input: &str) -> Result<LefMacro, String> {
+    let (_, lef) = parse_lef(input).map_err(|e| format!("Failed to parse macro: {e:?}"))?;
+
+    let mut macros = lef.macros;
+    match macros.len() {
+        0 => Err("No complete MACRO ... END block found".to_string()),
+        1 => Ok(macros.remove(0)),
+        count => Err(format!("Expected one MACRO block, found {count}")),
+    }
 }
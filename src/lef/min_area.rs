@@ -0,0 +1,294 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Pin shapes below the layer minimum area
+//!
+//! The AREA rule of a technology layer is the smallest area a metal shape
+//! may have. A pin whose shapes on a layer add up to less than that is an
+//! abstract bug that shows up as a DRC violation once the cell is placed.
+//!
+//! The rule applies to merged metal, so the shapes of one pin on one layer
+//! that overlap or touch are measured together. Rectangles are merged
+//! exactly; polygon areas are added to their group as they are.
+
+use super::{Lef, LefPolygon, LefRect};
+use crate::geometry::polygon_area;
+use std::collections::BTreeMap;
+
+/// Allowed rounding error in square microns
+const AREA_TOLERANCE: f64 = 1e-9;
+
+/// Merged pin shape smaller than the minimum area of its layer
+#[derive(Debug, Clone, PartialEq)]
+pub struct MinAreaViolation {
+    pub file: String,
+    pub macro_name: String,
+    pub pin_name: String,
+    pub layer: String,
+    /// Area of the merged shape in square microns
+    pub area: f64,
+    pub min_area: f64,
+    /// Bounding box of the merged shape relative to the macro ORIGIN
+    pub bounds: (f64, f64, f64, f64),
+}
+
+/// Minimum area of every layer with an AREA rule, in square microns
+pub fn technology_min_areas<'a>(
+    libraries: impl IntoIterator<Item = &'a Lef>,
+) -> BTreeMap<String, f64> {
+    let mut min_areas = BTreeMap::new();
+    for layer in libraries.into_iter().flat_map(|lef| &lef.layers) {
+        if let Some(area) = layer.min_area.filter(|a| a.is_finite() && *a > 0.0) {
+            min_areas.entry(layer.name.clone()).or_insert(area);
+        }
+    }
+    min_areas
+}
+
+/// Pin shapes of the (file, LEF) libraries below `min_areas`
+pub fn pin_area_violations(
+    libraries: &[(&str, &Lef)],
+    min_areas: &BTreeMap<String, f64>,
+) -> Vec<MinAreaViolation> {
+    let mut violations = Vec::new();
+    for (file, lef) in libraries {
+        for macro_def in &lef.macros {
+            for pin in &macro_def.pins {
+                let mut by_layer: BTreeMap<&str, (Vec<&LefRect>, Vec<&LefPolygon>)> =
+                    BTreeMap::new();
+                for port in &pin.ports {
+                    for rect in &port.rects {
+                        by_layer.entry(&rect.layer).or_default().0.push(rect);
+                    }
+                    for polygon in &port.polygons {
+                        by_layer.entry(&polygon.layer).or_default().1.push(polygon);
+                    }
+                }
+                for (layer, (rects, polygons)) in by_layer {
+                    let Some(&min_area) = min_areas.get(layer) else {
+                        continue;
+                    };
+                    for (area, bounds) in merged_areas(&rects, &polygons) {
+                        if area < min_area - AREA_TOLERANCE {
+                            violations.push(MinAreaViolation {
+                                file: file.to_string(),
+                                macro_name: macro_def.name.clone(),
+                                pin_name: pin.name.clone(),
+                                layer: layer.to_string(),
+                                area,
+                                min_area,
+                                bounds,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+    violations
+}
+
+type Bounds = (f64, f64, f64, f64);
+
+/// Area and bounding box of each group of touching shapes
+fn merged_areas(rects: &[&LefRect], polygons: &[&LefPolygon]) -> Vec<(f64, Bounds)> {
+    let mut shapes: Vec<Bounds> = rects
+        .iter()
+        .map(|r| {
+            (
+                r.xl.min(r.xh),
+                r.yl.min(r.yh),
+                r.xl.max(r.xh),
+                r.yl.max(r.yh),
+            )
+        })
+        .collect();
+    for polygon in polygons {
+        let bounds = polygon.points.iter().fold(
+            (
+                f64::INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::NEG_INFINITY,
+            ),
+            |b, &(x, y)| (b.0.min(x), b.1.min(y), b.2.max(x), b.3.max(y)),
+        );
+        shapes.push(bounds);
+    }
+
+    // Union-find over shapes whose boxes overlap or touch
+    let mut parent: Vec<usize> = (0..shapes.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for i in 0..shapes.len() {
+        for j in i + 1..shapes.len() {
+            let (a, b) = (shapes[i], shapes[j]);
+            if a.0 <= b.2 && b.0 <= a.2 && a.1 <= b.3 && b.1 <= a.3 {
+                let (ri, rj) = (root(&mut parent, i), root(&mut parent, j));
+                parent[ri] = rj;
+            }
+        }
+    }
+
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for i in 0..shapes.len() {
+        groups.entry(root(&mut parent, i)).or_default().push(i);
+    }
+    groups
+        .into_values()
+        .map(|members| {
+            let group_rects: Vec<Bounds> = members
+                .iter()
+                .filter(|&&i| i < rects.len())
+                .map(|&i| shapes[i])
+                .collect();
+            let polygon_area: f64 = members
+                .iter()
+                .filter(|&&i| i >= rects.len())
+                .map(|&i| polygon_area(&polygons[i - rects.len()].points))
+                .sum();
+            let bounds = members.iter().map(|&i| shapes[i]).fold(
+                (
+                    f64::INFINITY,
+                    f64::INFINITY,
+                    f64::NEG_INFINITY,
+                    f64::NEG_INFINITY,
+                ),
+                |a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)),
+            );
+            (union_area(&group_rects) + polygon_area, bounds)
+        })
+        .collect()
+}
+
+/// Area covered by overlapping rectangles, by coordinate compression
+fn union_area(rects: &[Bounds]) -> f64 {
+    let mut xs: Vec<f64> = rects.iter().flat_map(|r| [r.0, r.2]).collect();
+    let mut ys: Vec<f64> = rects.iter().flat_map(|r| [r.1, r.3]).collect();
+    xs.sort_by(f64::total_cmp);
+    xs.dedup();
+    ys.sort_by(f64::total_cmp);
+    ys.dedup();
+    let mut area = 0.0;
+    for x in xs.windows(2) {
+        for y in ys.windows(2) {
+            let (cx, cy) = ((x[0] + x[1]) / 2.0, (y[0] + y[1]) / 2.0);
+            if rects
+                .iter()
+                .any(|r| r.0 <= cx && cx <= r.2 && r.1 <= cy && cy <= r.3)
+            {
+                area += (x[1] - x[0]) * (y[1] - y[0]);
+            }
+        }
+    }
+    area
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lef::{LefLayer, LefMacro, LefPin, LefPort};
+    use crate::test_fixtures::{self, rect};
+
+    fn pin(name: &str, rects: Vec<LefRect>, polygons: Vec<LefPolygon>) -> LefPin {
+        LefPin {
+            ports: vec![LefPort { rects, polygons }],
+            ..test_fixtures::pin(name, "INPUT", "SIGNAL")
+        }
+    }
+
+    #[test]
+    fn test_pin_area_violations() {
+        let lef = Lef {
+            layers: vec![LefLayer {
+                name: "M1".to_string(),
+                layer_type: "ROUTING".to_string(),
+                min_area: Some(0.05),
+                ..LefLayer::default()
+            }],
+            macros: vec![LefMacro {
+                name: "INV".to_string(),
+                class: "CORE".to_string(),
+                foreign: String::new(),
+                origin: (0.0, 0.0),
+                size_x: 1.0,
+                size_y: 1.0,
+                symmetry: Vec::new(),
+                site: String::new(),
+                pins: vec![
+                    // 0.1 x 0.3 and an overlapping 0.3 x 0.1 strip: 0.05 merged
+                    pin(
+                        "A",
+                        vec![
+                            rect("M1", 0.0, 0.0, 0.1, 0.3),
+                            rect("M1", 0.0, 0.2, 0.3, 0.3),
+                            rect("M2", 0.0, 0.0, 0.01, 0.01),
+                        ],
+                        Vec::new(),
+                    ),
+                    // Two separate 0.1 x 0.1 squares, each too small
+                    pin(
+                        "Y",
+                        vec![
+                            rect("M1", 0.5, 0.0, 0.6, 0.1),
+                            rect("M1", 0.8, 0.0, 0.9, 0.1),
+                        ],
+                        Vec::new(),
+                    ),
+                    // L-shaped polygon of 0.03
+                    pin(
+                        "B",
+                        Vec::new(),
+                        vec![LefPolygon {
+                            layer: "M1".to_string(),
+                            points: vec![
+                                (0.0, 0.5),
+                                (0.2, 0.5),
+                                (0.2, 0.6),
+                                (0.1, 0.6),
+                                (0.1, 0.7),
+                                (0.0, 0.7),
+                            ],
+                            is_hole: false,
+                        }],
+                    ),
+                    // 45-degree triangle of 0.045, its bounding box is 0.09
+                    pin(
+                        "C",
+                        Vec::new(),
+                        vec![LefPolygon {
+                            layer: "M1".to_string(),
+                            points: vec![(0.5, 0.5), (0.8, 0.5), (0.5, 0.8)],
+                            is_hole: false,
+                        }],
+                    ),
+                ],
+                obs: Vec::new(),
+                ..LefMacro::default()
+            }],
+            ..Lef::default()
+        };
+
+        let min_areas = technology_min_areas([&lef]);
+        assert_eq!(min_areas.get("M1"), Some(&0.05));
+        let violations = pin_area_violations(&[("cells.lef", &lef)], &min_areas);
+        let found: Vec<(&str, f64)> = violations
+            .iter()
+            .map(|v| (v.pin_name.as_str(), (v.area * 1000.0).round() / 1000.0))
+            .collect();
+        assert_eq!(found, [("Y", 0.01), ("Y", 0.01), ("B", 0.03), ("C", 0.045)]);
+        assert_eq!(violations[0].bounds, (0.5, 0.0, 0.6, 0.1));
+        assert_eq!(violations[0].file, "cells.lef");
+
+        // Manual rules replace the technology
+        let manual = BTreeMap::from([("M2".to_string(), 0.001)]);
+        let violations = pin_area_violations(&[("cells.lef", &lef)], &manual);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].layer, "M2");
+    }
+}
@@ -6,19 +6,102 @@
 //! This module provides comprehensive LEF file parsing using proven nom-based parser
 //! that supports all LEF features including multi-line POLYGON definitions.
 
+pub mod diff;
+pub mod drive_family;
+pub mod footprint;
+pub mod layer_alias;
 pub mod lef_parser;
+pub mod min_area;
 pub mod reader;
+pub mod writer;
 
 // pub use reader::LefReader;
 
+use crate::parse_error::ParseError;
+use serde::{Deserialize, Serialize};
+
 /// Main LEF file structure
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Lef {
     pub macros: Vec<LefMacro>,
+    /// Database units per micron from UNITS DATABASE MICRONS
+    pub database_microns: Option<f64>,
+    /// Manufacturing grid in microns from MANUFACTURINGGRID
+    pub manufacturing_grid: Option<f64>,
+    /// Technology LAYER definitions in file order, bottom to top
+    pub layers: Vec<LefLayer>,
+    /// Placement SITE definitions in file order
+    pub sites: Vec<LefSite>,
+}
+
+impl Lef {
+    /// Parse LEF text held in memory, without progress reports
+    pub fn parse_str(text: &str) -> Result<Self, ParseError> {
+        lef_parser::parse_lef(text)
+            .map(|(_, lef)| lef)
+            .map_err(|e| ParseError::from_nom("LEF", text, e))
+    }
+
+    /// The parsed library as pretty-printed JSON, coordinates in microns
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Library from the JSON of [`Lef::to_json`]
+    #[allow(dead_code)]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// LEF LAYER definition from a technology LEF
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LefLayer {
+    pub name: String,
+    /// TYPE: ROUTING, CUT, MASTERSLICE, OVERLAP, IMPLANT, ...
+    pub layer_type: String,
+    /// Preferred routing DIRECTION, empty when not given
+    pub direction: String,
+    /// First PITCH value in microns
+    pub pitch: Option<f64>,
+    /// Default WIDTH in microns
+    pub width: Option<f64>,
+    /// Smallest SPACING value in microns
+    pub spacing: Option<f64>,
+    /// RESISTANCE in ohms per square, or per cut on CUT layers
+    pub resistance: Option<f64>,
+    /// CAPACITANCE CPERSQDIST in picofarads per square micron
+    pub capacitance: Option<f64>,
+    /// Minimum shape AREA in square microns
+    pub min_area: Option<f64>,
+}
+
+impl LefLayer {
+    pub fn is_routing(&self) -> bool {
+        self.layer_type == "ROUTING"
+    }
+
+    pub fn is_cut(&self) -> bool {
+        self.layer_type == "CUT"
+    }
+}
+
+/// LEF SITE definition, the placement unit of a row
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LefSite {
+    pub name: String,
+    /// CLASS: CORE or PAD, empty when not given
+    pub class: String,
+    /// SIZE width and height in microns
+    pub size_x: f64,
+    pub size_y: f64,
+    /// SYMMETRY: any of X, Y and R90
+    pub symmetry: Vec<String>,
 }
 
 /// LEF MACRO definition
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LefMacro {
     pub name: String,
     pub class: String,
@@ -31,29 +114,187 @@ pub struct LefMacro {
     pub site: String,
     pub pins: Vec<LefPin>,
     pub obs: Vec<LefObstruction>,
+    /// Declared DENSITY windows
+    pub density: Vec<LefDensity>,
+}
+
+/// Area-weighted metal density of the DENSITY windows of a macro
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DensityStats {
+    pub windows: usize,
+    /// Lowest, highest and area-weighted mean density in percent
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+impl LefMacro {
+    /// Hash of the macro's PIN and OBS geometry
+    ///
+    /// Names, CLASS, SIZE and ORIGIN are not part of the hash, so drive-strength
+    /// variants with identical shapes produce the same value and can share
+    /// tessellated meshes. Shape order is preserved because cached meshes
+    /// are addressed by shape index.
+    pub fn geometry_hash(&self) -> String {
+        let mut hasher = blake3::Hasher::new();
+        let hash_f64 = |hasher: &mut blake3::Hasher, value: f64| {
+            hasher.update(&value.to_bits().to_le_bytes());
+        };
+
+        let shape_groups = self
+            .pins
+            .iter()
+            .flat_map(|pin| {
+                pin.ports
+                    .iter()
+                    .map(|port| ("PIN", &port.rects, &port.polygons))
+            })
+            .chain(
+                self.obs
+                    .iter()
+                    .map(|obs| ("OBS", &obs.rects, &obs.polygons)),
+            );
+
+        for (shape_type, rects, polygons) in shape_groups {
+            for rect in rects {
+                hasher.update(shape_type.as_bytes());
+                hasher.update(b"RECT");
+                hasher.update(rect.layer.as_bytes());
+                for value in [rect.xl, rect.yl, rect.xh, rect.yh] {
+                    hash_f64(&mut hasher, value);
+                }
+            }
+            for polygon in polygons {
+                hasher.update(shape_type.as_bytes());
+                hasher.update(b"POLYGON");
+                hasher.update(polygon.layer.as_bytes());
+                hasher.update(&(polygon.points.len() as u64).to_le_bytes());
+                for &(x, y) in &polygon.points {
+                    hash_f64(&mut hasher, x);
+                    hash_f64(&mut hasher, y);
+                }
+            }
+        }
+
+        hasher.finalize().to_hex().to_string()
+    }
+
+    /// Density statistics over all DENSITY windows, None without windows
+    pub fn density_stats(&self) -> Option<DensityStats> {
+        if self.density.is_empty() {
+            return None;
+        }
+        let area = |d: &LefDensity| ((d.xh - d.xl) * (d.yh - d.yl)).abs();
+        let total_area: f64 = self.density.iter().map(area).sum();
+        let values = self.density.iter().map(|d| d.density);
+        let mean = if total_area > 0.0 {
+            self.density
+                .iter()
+                .map(|d| area(d) * d.density)
+                .sum::<f64>()
+                / total_area
+        } else {
+            values.clone().sum::<f64>() / self.density.len() as f64
+        };
+        Some(DensityStats {
+            windows: self.density.len(),
+            min: values.clone().fold(f64::INFINITY, f64::min),
+            max: values.fold(f64::NEG_INFINITY, f64::max),
+            mean,
+        })
+    }
+
+    /// Whether the macro is a physical-only cell (filler, tap, decap, ...)
+    pub fn is_physical_only(&self) -> bool {
+        is_physical_only_cell(&self.name, Some(&self.class))
+    }
+}
+
+/// CLASS values of cells without logical function
+const PHYSICAL_ONLY_CLASSES: [&str; 7] = [
+    "COVER",
+    "CORE SPACER",
+    "CORE WELLTAP",
+    "CORE ANTENNACELL",
+    "ENDCAP",
+    "PAD SPACER",
+    "BLOCK BLACKBOX",
+];
+
+/// Name fragments of physical-only cells, for libraries without a precise
+/// CLASS and for DEF components whose macro is not loaded
+const PHYSICAL_ONLY_NAME_PATTERNS: [&str; 8] = [
+    "FILL", "DECAP", "TAP", "ENDCAP", "BOUNDARY", "ANTENNA", "PHY_", "SPACER",
+];
+
+/// Whether a cell is physical-only, judged by its CLASS when known and by
+/// its name otherwise
+///
+/// `BLOCK BLACKBOX` is included because such blocks have no netlist
+/// counterpart either. Name patterns are matched case-insensitively.
+pub fn is_physical_only_cell(name: &str, class: Option<&str>) -> bool {
+    if let Some(class) = class {
+        let class = class
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_ascii_uppercase();
+        if PHYSICAL_ONLY_CLASSES
+            .iter()
+            .any(|c| class == *c || class.starts_with(&format!("{c} ")))
+        {
+            return true;
+        }
+    }
+    let name = name.to_ascii_uppercase();
+    PHYSICAL_ONLY_NAME_PATTERNS
+        .iter()
+        .any(|pattern| name.contains(pattern))
 }
 
 /// LEF PIN definition with complete geometry support
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LefPin {
     pub name: String,
     pub direction: String,
-    #[allow(dead_code)]
     pub use_type: String,
     #[allow(dead_code)]
     pub shape: String,
     pub ports: Vec<LefPort>,
 }
 
+impl LefPin {
+    /// Center of the first pin shape, relative to the macro ORIGIN
+    pub fn center(&self) -> Option<(f64, f64)> {
+        self.ports.iter().find_map(|port| {
+            port.rects
+                .first()
+                .map(|r| ((r.xl + r.xh) / 2.0, (r.yl + r.yh) / 2.0))
+                .or_else(|| {
+                    port.polygons.first().and_then(|polygon| {
+                        let count = polygon.points.len() as f64;
+                        (count > 0.0).then(|| {
+                            let (sx, sy) = polygon
+                                .points
+                                .iter()
+                                .fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x, sy + y));
+                            (sx / count, sy / count)
+                        })
+                    })
+                })
+        })
+    }
+}
+
 /// LEF PORT containing geometric shapes
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LefPort {
     pub rects: Vec<LefRect>,
     pub polygons: Vec<LefPolygon>,
 }
 
 /// LEF RECT geometry
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LefRect {
     pub layer: String,
     pub xl: f64,
@@ -63,7 +304,7 @@ pub struct LefRect {
 }
 
 /// LEF POLYGON geometry with multi-line support
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LefPolygon {
     pub layer: String,
     pub points: Vec<(f64, f64)>,
@@ -71,8 +312,20 @@ pub struct LefPolygon {
     pub is_hole: bool,
 }
 
+/// Rectangle of a MACRO DENSITY section with its declared density
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LefDensity {
+    pub layer: String,
+    pub xl: f64,
+    pub yl: f64,
+    pub xh: f64,
+    pub yh: f64,
+    /// Metal density in percent, 0 to 100
+    pub density: f64,
+}
+
 /// LEF OBSTRUCTION (OBS)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LefObstruction {
     pub rects: Vec<LefRect>,
     pub polygons: Vec<LefPolygon>,
@@ -5,34 +5,43 @@ use std::fs;
 use std::path::Path;
 
 use super::Lef;
+use crate::progress::{Progress, ProgressCallback};
 
-pub struct LefReader;
+pub struct LefReader {
+    progress: Option<ProgressCallback>,
+}
 
 impl LefReader {
     pub fn new() -> Self {
-        Self
+        Self { progress: None }
+    }
+
+    /// Call `callback` with the progress while parsing
+    pub fn with_progress(mut self, callback: impl Fn(&Progress) + Send + 'static) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
     }
 
     pub fn read<P: AsRef<Path>>(&self, path: P) -> Result<Lef, Box<dyn std::error::Error>> {
         let path_str = path.as_ref().display().to_string();
-        println!("[LOAD] Loading LEF file: {path_str}");
+        eprintln!("[LOAD] Loading LEF file: {path_str}");
 
         let content = fs::read_to_string(path)?;
-        println!("[FILE] LEF file size: {} bytes", content.len());
+        eprintln!("[FILE] LEF file size: {} bytes", content.len());
 
         // Print first few lines for debugging
         let lines: Vec<&str> = content.lines().take(10).collect();
-        println!("[FILE] First 10 lines:");
+        eprintln!("[FILE] First 10 lines:");
         for (i, line) in lines.iter().enumerate() {
-            println!("  {}: {}", i + 1, line);
+            eprintln!("  {}: {}", i + 1, line);
         }
 
         // Use proven nom-based parser
-        println!("[DBG] Using proven nom-based LEF parser...");
-        match super::lef_parser::parse_lef(&content) {
+        eprintln!("[DBG] Using proven nom-based LEF parser...");
+        match super::lef_parser::parse_lef_with_progress(&content, self.progress.as_ref()) {
             Ok((_, lef)) => {
-                println!("[PASS] LEF parsed successfully!");
-                println!(
+                eprintln!("[PASS] LEF parsed successfully!");
+                eprintln!(
                     "[INFO] Found {} macros with complete PIN geometry data",
                     lef.macros.len()
                 );
@@ -52,14 +61,14 @@ impl LefReader {
                     }
                 }
 
-                println!(
+                eprintln!(
                     "[INFO] Statistics: {total_pins} pins, {total_rects} rects, {total_polygons} polygons"
                 );
 
                 Ok(lef)
             }
             Err(e) => {
-                println!("[FAIL] Failed to parse LEF file: {e:?}");
+                eprintln!("[FAIL] Failed to parse LEF file: {e:?}");
                 Err(format!("Failed to parse LEF file: {e:?}").into())
             }
         }
@@ -0,0 +1,198 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! LEF text writer
+//!
+//! Synthesizes LEF statements from the parsed data structures, in microns
+//! as stored. [`write_lef`] writes a whole library that reads back into the
+//! same macros, so a library can be edited in memory and saved again.
+
+use std::fmt::Write;
+
+use super::{Lef, LefLayer, LefMacro, LefPin, LefPolygon, LefRect, LefSite};
+
+/// Write `lef` as a complete LEF file to `path`
+pub fn write_lef(lef: &Lef, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    std::fs::write(path, lef_to_string(lef))?;
+    Ok(())
+}
+
+/// Complete LEF text of `lef`: header, technology layers, sites and macros
+pub fn lef_to_string(lef: &Lef) -> String {
+    let mut out = String::from("VERSION 5.8 ;\nBUSBITCHARS \"[]\" ;\nDIVIDERCHAR \"/\" ;\n");
+    if let Some(microns) = lef.database_microns {
+        let _ = writeln!(out, "\nUNITS\n  DATABASE MICRONS {microns} ;\nEND UNITS");
+    }
+    if let Some(grid) = lef.manufacturing_grid {
+        let _ = writeln!(out, "\nMANUFACTURINGGRID {grid} ;");
+    }
+    for layer in &lef.layers {
+        out.push('\n');
+        out.push_str(&write_layer(layer));
+    }
+    for site in &lef.sites {
+        out.push('\n');
+        out.push_str(&write_site(site));
+    }
+    for macro_def in &lef.macros {
+        out.push('\n');
+        out.push_str(&write_macro(macro_def));
+    }
+    out.push_str("\nEND LIBRARY\n");
+    out
+}
+
+/// LAYER block with the attributes kept from the technology LEF
+pub fn write_layer(layer: &LefLayer) -> String {
+    let mut out = format!("LAYER {}\n", layer.name);
+    if !layer.layer_type.is_empty() {
+        let _ = writeln!(out, "  TYPE {} ;", layer.layer_type);
+    }
+    if !layer.direction.is_empty() {
+        let _ = writeln!(out, "  DIRECTION {} ;", layer.direction);
+    }
+    let values = [
+        ("PITCH", layer.pitch),
+        ("WIDTH", layer.width),
+        ("SPACING", layer.spacing),
+        ("AREA", layer.min_area),
+    ];
+    for (keyword, value) in values {
+        if let Some(value) = value {
+            let _ = writeln!(out, "  {keyword} {value} ;");
+        }
+    }
+    if let Some(resistance) = layer.resistance {
+        // Cut layers give the resistance per cut
+        if layer.is_cut() {
+            let _ = writeln!(out, "  RESISTANCE {resistance} ;");
+        } else {
+            let _ = writeln!(out, "  RESISTANCE RPERSQ {resistance} ;");
+        }
+    }
+    if let Some(capacitance) = layer.capacitance {
+        let _ = writeln!(out, "  CAPACITANCE CPERSQDIST {capacitance} ;");
+    }
+    let _ = writeln!(out, "END {}", layer.name);
+    out
+}
+
+/// SITE block with its class, size and symmetry
+pub fn write_site(site: &LefSite) -> String {
+    let mut out = format!("SITE {}\n", site.name);
+    if !site.class.is_empty() {
+        let _ = writeln!(out, "  CLASS {} ;", site.class);
+    }
+    if !site.symmetry.is_empty() {
+        let _ = writeln!(out, "  SYMMETRY {} ;", site.symmetry.join(" "));
+    }
+    let _ = writeln!(out, "  SIZE {} BY {} ;", site.size_x, site.size_y);
+    let _ = writeln!(out, "END {}", site.name);
+    out
+}
+
+/// MACRO block with its pins and obstructions
+pub fn write_macro(macro_def: &LefMacro) -> String {
+    let mut out = format!("MACRO {}\n", macro_def.name);
+    if !macro_def.class.is_empty() {
+        let _ = writeln!(out, "  CLASS {} ;", macro_def.class);
+    }
+    if !macro_def.foreign.is_empty() {
+        let _ = writeln!(out, "  FOREIGN {} ;", macro_def.foreign);
+    }
+    let _ = writeln!(
+        out,
+        "  ORIGIN {} {} ;",
+        macro_def.origin.0, macro_def.origin.1
+    );
+    let _ = writeln!(out, "  SIZE {} BY {} ;", macro_def.size_x, macro_def.size_y);
+    if !macro_def.symmetry.is_empty() {
+        let _ = writeln!(out, "  SYMMETRY {} ;", macro_def.symmetry.join(" "));
+    }
+    if !macro_def.site.is_empty() {
+        let _ = writeln!(out, "  SITE {} ;", macro_def.site);
+    }
+    for pin in &macro_def.pins {
+        write_pin_block(&mut out, pin, "  ");
+    }
+    if !macro_def.obs.is_empty() {
+        out.push_str("  OBS\n");
+        for obs in &macro_def.obs {
+            write_geometry(&mut out, &obs.rects, &obs.polygons, "    ");
+        }
+        out.push_str("  END\n");
+    }
+    if !macro_def.density.is_empty() {
+        out.push_str("  DENSITY\n");
+        let mut current_layer: Option<&str> = None;
+        for window in &macro_def.density {
+            if current_layer != Some(window.layer.as_str()) {
+                let _ = writeln!(out, "    LAYER {} ;", window.layer);
+                current_layer = Some(&window.layer);
+            }
+            let _ = writeln!(
+                out,
+                "      RECT {} {} {} {} {} ;",
+                window.xl, window.yl, window.xh, window.yh, window.density
+            );
+        }
+        out.push_str("  END\n");
+    }
+    let _ = writeln!(out, "END {}", macro_def.name);
+    out
+}
+
+/// PIN block with its ports, as written inside a MACRO
+pub fn write_pin(pin: &LefPin) -> String {
+    let mut out = String::new();
+    write_pin_block(&mut out, pin, "");
+    out
+}
+
+fn write_pin_block(out: &mut String, pin: &LefPin, indent: &str) {
+    let _ = writeln!(out, "{indent}PIN {}", pin.name);
+    if !pin.direction.is_empty() {
+        let _ = writeln!(out, "{indent}  DIRECTION {} ;", pin.direction);
+    }
+    if !pin.use_type.is_empty() {
+        let _ = writeln!(out, "{indent}  USE {} ;", pin.use_type);
+    }
+    if !pin.shape.is_empty() {
+        let _ = writeln!(out, "{indent}  SHAPE {} ;", pin.shape);
+    }
+    for port in &pin.ports {
+        let _ = writeln!(out, "{indent}  PORT");
+        write_geometry(out, &port.rects, &port.polygons, &format!("{indent}    "));
+        let _ = writeln!(out, "{indent}  END");
+    }
+    let _ = writeln!(out, "{indent}END {}", pin.name);
+}
+
+/// LAYER statements followed by their RECT and POLYGON shapes, emitting a
+/// new LAYER only when the layer changes
+fn write_geometry(out: &mut String, rects: &[LefRect], polygons: &[LefPolygon], indent: &str) {
+    let mut current_layer: Option<&str> = None;
+    for rect in rects {
+        if current_layer != Some(rect.layer.as_str()) {
+            let _ = writeln!(out, "{indent}LAYER {} ;", rect.layer);
+            current_layer = Some(&rect.layer);
+        }
+        let _ = writeln!(
+            out,
+            "{indent}  RECT {} {} {} {} ;",
+            rect.xl, rect.yl, rect.xh, rect.yh
+        );
+    }
+    for polygon in polygons {
+        if current_layer != Some(polygon.layer.as_str()) {
+            let _ = writeln!(out, "{indent}LAYER {} ;", polygon.layer);
+            current_layer = Some(&polygon.layer);
+        }
+        let points: Vec<String> = polygon
+            .points
+            .iter()
+            .map(|(x, y)| format!("{x} {y}"))
+            .collect();
+        let _ = writeln!(out, "{indent}  POLYGON {} ;", points.join(" "));
+    }
+}
@@ -0,0 +1,224 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! LEF Diff Panel
+//!
+//! Compares an old and a new version of a LEF library, e.g. when a vendor
+//! ships an update, and shows the result as a tree of added, removed and
+//! changed macros. The diff can be exported to CSV.
+
+use crate::export;
+use crate::lef::diff::{self, LefDiff};
+use crate::lef::reader::LefReader;
+use crate::platform::FileDialog;
+use eframe::egui;
+use std::path::Path;
+
+/// Rows shown per list before the rest is summarized
+const MAX_LISTED: usize = 500;
+
+/// LEF diff panel state and rendering
+#[derive(Default)]
+pub struct LefDiffPanel {
+    /// Whether the panel is currently shown
+    pub visible: bool,
+    old_path: Option<String>,
+    new_path: Option<String>,
+    diff: Option<LefDiff>,
+    error: Option<String>,
+    message: Option<String>,
+}
+
+impl LefDiffPanel {
+    /// Create a new LEF diff panel
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compare the chosen files, if both are set
+    fn compare(&mut self) {
+        let (Some(old_path), Some(new_path)) = (&self.old_path, &self.new_path) else {
+            return;
+        };
+        let read = |path: &str| {
+            LefReader::new()
+                .read(path)
+                .map_err(|e| format!("Failed to load LEF {path}: {e}"))
+        };
+        match read(old_path).and_then(|old| Ok((old, read(new_path)?))) {
+            Ok((old, new)) => {
+                self.diff = Some(diff::diff_libraries(&old, &new));
+                self.error = None;
+            }
+            Err(e) => {
+                self.diff = None;
+                self.error = Some(e);
+            }
+        }
+        self.message = None;
+    }
+
+    fn export_csv(&mut self) {
+        let Some(diff) = &self.diff else {
+            return;
+        };
+        let Some(path) = FileDialog::new()
+            .set_file_name("lef_diff.csv")
+            .add_filter("CSV files", &["csv"])
+            .save_file()
+        else {
+            return;
+        };
+        match export::export_lef_diff_to_csv(diff, &path.to_string_lossy()) {
+            Ok(()) => {
+                self.message = Some(format!("Exported LEF diff to {}", path.display()));
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("Failed to export LEF diff: {e}")),
+        }
+    }
+
+    /// Render the panel
+    pub fn render(&mut self, ctx: &egui::Context) {
+        if !self.visible {
+            return;
+        }
+
+        let mut open = true;
+        let mut compare = false;
+        let mut export = false;
+        egui::Window::new("LEF Diff")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(460.0)
+            .default_height(450.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("lef_diff_files")
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        for (label, path) in
+                            [("Old", &mut self.old_path), ("New", &mut self.new_path)]
+                        {
+                            ui.label(label);
+                            ui.monospace(path.as_deref().map_or("(none)", file_name));
+                            if ui.button("Browse...").clicked() {
+                                if let Some(picked) = FileDialog::new()
+                                    .add_filter("LEF files", &["lef"])
+                                    .pick_file()
+                                {
+                                    *path = Some(picked.to_string_lossy().to_string());
+                                    compare = true;
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    let ready = self.old_path.is_some() && self.new_path.is_some();
+                    if ui
+                        .add_enabled(ready, egui::Button::new("Compare"))
+                        .clicked()
+                    {
+                        compare = true;
+                    }
+                    if ui
+                        .add_enabled(self.diff.is_some(), egui::Button::new("Export CSV..."))
+                        .clicked()
+                    {
+                        export = true;
+                    }
+                });
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::from_rgb(244, 67, 54), error);
+                }
+                if let Some(message) = &self.message {
+                    ui.label(message);
+                }
+                ui.separator();
+
+                let Some(diff) = &self.diff else {
+                    ui.label("Choose the old and the new version of a library.");
+                    return;
+                };
+                ui.label(format!(
+                    "{} added, {} removed, {} changed, {} unchanged macros",
+                    diff.added.len(),
+                    diff.removed.len(),
+                    diff.changed.len(),
+                    diff.unchanged
+                ));
+                if diff.is_empty() {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(76, 175, 80),
+                        "The libraries define the same macros",
+                    );
+                }
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        name_list(ui, "Added macros", &diff.added);
+                        name_list(ui, "Removed macros", &diff.removed);
+                        egui::CollapsingHeader::new(format!(
+                            "Changed macros ({})",
+                            diff.changed.len()
+                        ))
+                        .id_salt("changed_macros")
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for macro_diff in diff.changed.iter().take(MAX_LISTED) {
+                                egui::CollapsingHeader::new(format!(
+                                    "{} ({})",
+                                    macro_diff.name,
+                                    macro_diff.changes.len()
+                                ))
+                                .id_salt(("changed_macro", &macro_diff.name))
+                                .show(ui, |ui| {
+                                    for change in &macro_diff.changes {
+                                        let (old, new) = change.values();
+                                        ui.label(change.describe())
+                                            .on_hover_text(format!("Old: {old}\nNew: {new}"));
+                                    }
+                                });
+                            }
+                            more_label(ui, diff.changed.len());
+                        });
+                    });
+            });
+
+        if compare {
+            self.compare();
+        }
+        if export {
+            self.export_csv();
+        }
+        if !open {
+            self.visible = false;
+        }
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    Path::new(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(path)
+}
+
+/// Collapsible list of macro names
+fn name_list(ui: &mut egui::Ui, title: &str, names: &[String]) {
+    egui::CollapsingHeader::new(format!("{title} ({})", names.len()))
+        .id_salt(title)
+        .show(ui, |ui| {
+            for name in names.iter().take(MAX_LISTED) {
+                ui.label(name);
+            }
+            more_label(ui, names.len());
+        });
+}
+
+fn more_label(ui: &mut egui::Ui, total: usize) {
+    if total > MAX_LISTED {
+        ui.label(format!("... and {} more", total - MAX_LISTED));
+    }
+}
@@ -3,10 +3,33 @@
 //! This library provides parsing and visualization capabilities for LEF and DEF files
 //! used in Electronic Design Automation (EDA) workflows.
 
+pub mod checks;
+pub mod component_pins;
 pub mod def;
 pub mod export;
+pub mod gds;
+pub mod geometry;
+pub mod hit_test;
+pub mod identifier;
 pub mod lef;
+pub mod netlist;
+pub mod number;
+pub mod parse_error;
+pub mod pin_lint;
+pub mod profiling;
+pub mod progress;
+pub mod reproducer;
+pub mod routing_resources;
+pub mod script;
+pub mod spatial;
+pub mod tcl_selection;
+#[cfg(test)]
+mod test_fixtures;
+pub mod units;
+pub mod waivers;
 
 // Re-export commonly used types
 pub use def::{Def, DefComponent, DefNet, DefPin, DefVia};
+pub use geometry::MacroTransform;
 pub use lef::{Lef, LefMacro, LefPin, LefPolygon, LefPort, LefRect};
+pub use parse_error::ParseError;
@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Guards for background loader threads
+//!
+//! Parser threads report back over a channel. A panic would drop the sender
+//! without a result and a hang would never report at all, so loader bodies
+//! run under [`run_guarded`] and the UI checks [`LOADER_TIMEOUT`]. The
+//! timeout counts from the last progress report, so a long load that keeps
+//! reporting is never given up on.
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+/// How long a loader may go without reporting progress before the UI gives
+/// up on it
+pub const LOADER_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Run a loader body, turning a panic into an error message
+pub fn run_guarded<T>(
+    description: &str,
+    body: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(result) => result,
+        Err(payload) => {
+            let message = panic_message(payload.as_ref());
+            eprintln!("WARNING: Loader for {description} panicked: {message}");
+            Err(format!(
+                "Parser crashed while loading {description}: {message}"
+            ))
+        }
+    }
+}
+
+/// Text of a panic payload, for the common `&str` and `String` payloads
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// Diagnostic shown when a loader stalls for [`LOADER_TIMEOUT`]
+pub fn timeout_message(file_type: &str, file_name: &str, stalled: Duration) -> String {
+    format!(
+        "Loading {file_type} file {file_name} reported no progress for {} s and was abandoned. \
+         The file may be malformed (e.g. a missing END statement) or the parser hung; \
+         the parser thread keeps running in the background until it exits.",
+        stalled.as_secs()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_result_passes_through() {
+        assert_eq!(run_guarded("a.def", || Ok::<_, String>(7)), Ok(7));
+        assert_eq!(
+            run_guarded::<()>("a.def", || Err("bad".to_string())),
+            Err("bad".to_string())
+        );
+    }
+
+    #[test]
+    fn test_panic_becomes_error() {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let result = run_guarded::<()>("a.def", || panic!("index out of bounds"));
+        let formatted = run_guarded::<()>("b.lef", || panic!("line {}", 42));
+        panic::set_hook(previous_hook);
+
+        assert_eq!(
+            result,
+            Err("Parser crashed while loading a.def: index out of bounds".to_string())
+        );
+        assert_eq!(
+            formatted,
+            Err("Parser crashed while loading b.lef: line 42".to_string())
+        );
+    }
+
+    #[test]
+    fn test_timeout_message_names_file() {
+        let message = timeout_message("DEF", "top.def", Duration::from_secs(600));
+        assert!(message.contains("DEF file top.def"));
+        assert!(message.contains("600 s"));
+    }
+}
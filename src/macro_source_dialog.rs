@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Macro Source Editor Dialog
+//!
+//! Shows the LEF text of a single macro in an editable buffer. Applying the
+//! edit reparses only that macro, so abstract tweaks can be tried without
+//! reloading the whole library. The file on disk is never modified.
+
+use crate::lef::{lef_parser, LefMacro};
+use eframe::egui;
+
+/// Result of a successful apply, handed back to the viewer
+pub struct MacroSourceEdit {
+    /// Index of the LEF file the macro belongs to
+    pub lef_file_idx: usize,
+    /// Name of the macro before the edit
+    pub original_name: String,
+    /// Reparsed macro
+    pub macro_def: LefMacro,
+    /// Edited source text, kept so reopening the dialog shows the edit
+    pub source: String,
+}
+
+/// Macro source editor state and rendering
+#[derive(Default)]
+pub struct MacroSourceDialog {
+    /// Whether the dialog is currently shown
+    pub visible: bool,
+    lef_file_idx: usize,
+    macro_name: String,
+    text: String,
+    original_text: String,
+    error: Option<String>,
+}
+
+impl MacroSourceDialog {
+    /// Create a new macro source dialog
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the editor for a macro with its current source text
+    pub fn open(&mut self, lef_file_idx: usize, macro_name: &str, source: String) {
+        self.visible = true;
+        self.lef_file_idx = lef_file_idx;
+        self.macro_name = macro_name.to_string();
+        self.original_text = source.clone();
+        self.text = source;
+        self.error = None;
+    }
+
+    /// Render the editor, returning the reparsed macro when the user applies
+    pub fn render(&mut self, ctx: &egui::Context) -> Option<MacroSourceEdit> {
+        if !self.visible {
+            return None;
+        }
+
+        let mut open = true;
+        let mut applied = None;
+
+        egui::Window::new(format!("Edit Macro Source: {}", self.macro_name))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(600.0)
+            .default_height(500.0)
+            .max_height(ctx.screen_rect().height() - 100.0)
+            .show(ctx, |ui| {
+                ui.label(
+                    "Changes are applied to the loaded library only, the LEF file is not modified.",
+                );
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("Apply").clicked() {
+                        match lef_parser::parse_macro_source(&self.text) {
+                            Ok(macro_def) => {
+                                self.error = None;
+                                applied = Some(MacroSourceEdit {
+                                    lef_file_idx: self.lef_file_idx,
+                                    original_name: self.macro_name.clone(),
+                                    macro_def,
+                                    source: self.text.clone(),
+                                });
+                            }
+                            Err(e) => self.error = Some(e),
+                        }
+                    }
+                    if ui
+                        .add_enabled(self.text != self.original_text, egui::Button::new("Revert"))
+                        .clicked()
+                    {
+                        self.text = self.original_text.clone();
+                        self.error = None;
+                    }
+                });
+
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut self.text)
+                                .code_editor()
+                                .desired_width(f32::INFINITY)
+                                .desired_rows(25),
+                        );
+                    });
+            });
+
+        if let Some(edit) = &applied {
+            // Further edits continue from the applied text under the new name
+            self.macro_name = edit.macro_def.name.clone();
+            self.original_text = edit.source.clone();
+        }
+        if !open {
+            self.visible = false;
+        }
+
+        applied
+    }
+}
@@ -0,0 +1,417 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Macro thumbnails and the preview card of the cell list
+//!
+//! [`paint_macro`] draws a LEF macro in any orientation into a painter,
+//! obstructions as outlines below the pins and the cell outline on top. The
+//! Cell vs Instance panes use it at full size with pin names; the preview
+//! card shown when hovering a macro in the cell list uses it as a small
+//! thumbnail next to the size, class and pin summary. The card appears
+//! after egui's tooltip delay, so sweeping over the list does not flash it.
+//!
+//! [`rasterize_macro`] renders the same thumbnail into an image, so the
+//! background pass after loading can prepare the cards of a whole library
+//! and the card only draws a texture.
+
+use crate::geometry::{polygon_contains, MacroTransform};
+use crate::lef::LefMacro;
+use eframe::egui;
+use std::collections::BTreeMap;
+
+/// Size of the thumbnail on the preview card
+const CARD_THUMBNAIL: egui::Vec2 = egui::vec2(220.0, 160.0);
+/// Space around the macro in the thumbnail, as a fraction of its size
+const CARD_MARGIN: f32 = 0.1;
+/// Pin names listed on the card
+const CARD_PIN_NAMES: usize = 12;
+/// Pixel size of the rasterized thumbnails, half the card thumbnail
+pub const THUMBNAIL_PIXELS: [usize; 2] = [110, 80];
+
+/// Paint `macro_def` with `orientation`, the center of its oriented outline
+/// at `center` and `zoom` points per micron
+///
+/// `layer_color` gives the color of a layer key such as "M1.PIN". With
+/// `detailed` the pin names and a marker on the library lower-left corner
+/// are drawn as well.
+pub fn paint_macro(
+    painter: &egui::Painter,
+    center: egui::Pos2,
+    zoom: f32,
+    macro_def: &LefMacro,
+    orientation: &str,
+    layer_color: &impl Fn(&str) -> egui::Color32,
+    detailed: bool,
+) {
+    // Oriented bounding box starts at (0, 0)
+    let xform = MacroTransform::new(macro_def, (0.0, 0.0), orientation);
+    let (min_x, min_y, max_x, max_y) = xform.outline_bbox();
+    let world_center = (
+        ((min_x + max_x) / 2.0) as f32,
+        ((min_y + max_y) / 2.0) as f32,
+    );
+    let to_screen = |(x, y): (f64, f64)| {
+        egui::pos2(
+            center.x + (x as f32 - world_center.0) * zoom,
+            center.y - (y as f32 - world_center.1) * zoom,
+        )
+    };
+
+    // Obstructions first, as outlines below the pins
+    for obs in &macro_def.obs {
+        for r in &obs.rects {
+            let color = layer_color(&format!("{}.OBS", r.layer));
+            let corners = xform.rect_corners(r.xl, r.yl, r.xh, r.yh);
+            painter.add(egui::Shape::closed_line(
+                corners.iter().map(|&p| to_screen(p)).collect(),
+                egui::Stroke::new(1.0, color),
+            ));
+        }
+        for polygon in &obs.polygons {
+            let color = layer_color(&format!("{}.OBS", polygon.layer));
+            painter.add(egui::Shape::closed_line(
+                polygon
+                    .points
+                    .iter()
+                    .map(|&p| to_screen(xform.apply(p)))
+                    .collect(),
+                egui::Stroke::new(1.0, color),
+            ));
+        }
+    }
+
+    for pin in &macro_def.pins {
+        let mut label_pos = None;
+        for port in &pin.ports {
+            for r in &port.rects {
+                let color = layer_color(&format!("{}.PIN", r.layer));
+                let (xl, yl, xh, yh) = xform.apply_rect(r.xl, r.yl, r.xh, r.yh);
+                let screen = egui::Rect::from_two_pos(to_screen((xl, yl)), to_screen((xh, yh)));
+                painter.rect_filled(screen, 0.0, color);
+                label_pos.get_or_insert(screen.center());
+            }
+            for polygon in &port.polygons {
+                // Polygons may be concave, outline them only
+                let color = layer_color(&format!("{}.PIN", polygon.layer));
+                let points: Vec<egui::Pos2> = polygon
+                    .points
+                    .iter()
+                    .map(|&p| to_screen(xform.apply(p)))
+                    .collect();
+                label_pos.get_or_insert_with(|| egui::Rect::from_points(&points).center());
+                painter.add(egui::Shape::closed_line(
+                    points,
+                    egui::Stroke::new(2.0, color),
+                ));
+            }
+        }
+        if let (true, Some(pos)) = (detailed, label_pos) {
+            painter.text(
+                pos,
+                egui::Align2::CENTER_CENTER,
+                &pin.name,
+                egui::FontId::proportional(12.0),
+                egui::Color32::WHITE,
+            );
+        }
+    }
+
+    let (width, height) = xform.size;
+    let outline = [(0.0, 0.0), (width, 0.0), (width, height), (0.0, height)]
+        .map(|p| to_screen(xform.apply_frame(p)));
+    painter.add(egui::Shape::closed_line(
+        outline.to_vec(),
+        egui::Stroke::new(if detailed { 2.0 } else { 1.0 }, egui::Color32::WHITE),
+    ));
+    if detailed {
+        // Marker on the library lower-left corner shows where rotation and
+        // mirroring moved it, a short tick along the library bottom edge
+        // gives the mirror sense
+        painter.circle_filled(outline[0], 5.0, egui::Color32::from_rgb(255, 193, 7));
+        let tick_end = outline[0] + (outline[1] - outline[0]).normalized() * 16.0;
+        painter.arrow(
+            outline[0],
+            tick_end - outline[0],
+            egui::Stroke::new(2.0, egui::Color32::from_rgb(255, 193, 7)),
+        );
+    }
+}
+
+/// Rasterize the preview card thumbnail of `macro_def` into an image of
+/// `size` pixels
+///
+/// Pins are filled, polygons included, obstructions and the cell outline
+/// are drawn as one pixel outlines.
+pub fn rasterize_macro(
+    macro_def: &LefMacro,
+    layer_color: &impl Fn(&str) -> egui::Color32,
+    size: [usize; 2],
+) -> egui::ColorImage {
+    let mut image = egui::ColorImage::new(size, egui::Color32::from_gray(20));
+    let xform = MacroTransform::new(macro_def, (0.0, 0.0), "N");
+    let (min_x, min_y, max_x, max_y) = xform.outline_bbox();
+    let (width, height) = (max_x - min_x, max_y - min_y);
+    if !(width > 0.0 && height > 0.0) {
+        return image;
+    }
+    let scale = (size[0] as f64 / width).min(size[1] as f64 / height) * (1.0 - CARD_MARGIN as f64);
+    let center = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+    let half = (size[0] as f64 / 2.0, size[1] as f64 / 2.0);
+    let to_pixel = |(x, y): (f64, f64)| {
+        (
+            half.0 + (x - center.0) * scale,
+            half.1 - (y - center.1) * scale,
+        )
+    };
+
+    for obs in &macro_def.obs {
+        for r in &obs.rects {
+            let color = layer_color(&format!("{}.OBS", r.layer));
+            let corners = xform.rect_corners(r.xl, r.yl, r.xh, r.yh).map(to_pixel);
+            draw_closed_line(&mut image, &corners, color);
+        }
+        for polygon in &obs.polygons {
+            let color = layer_color(&format!("{}.OBS", polygon.layer));
+            let points: Vec<(f64, f64)> = polygon
+                .points
+                .iter()
+                .map(|&p| to_pixel(xform.apply(p)))
+                .collect();
+            draw_closed_line(&mut image, &points, color);
+        }
+    }
+
+    for port in macro_def.pins.iter().flat_map(|pin| &pin.ports) {
+        for r in &port.rects {
+            let color = layer_color(&format!("{}.PIN", r.layer));
+            let (xl, yl, xh, yh) = xform.apply_rect(r.xl, r.yl, r.xh, r.yh);
+            let corners = [to_pixel((xl, yl)), to_pixel((xh, yh))];
+            fill_polygon(&mut image, &corners, color, |_| true);
+        }
+        for polygon in &port.polygons {
+            let color = layer_color(&format!("{}.PIN", polygon.layer));
+            let points: Vec<(f64, f64)> = polygon
+                .points
+                .iter()
+                .map(|&p| to_pixel(xform.apply(p)))
+                .collect();
+            fill_polygon(&mut image, &points, color, |pixel| {
+                polygon_contains(&points, pixel)
+            });
+        }
+    }
+
+    let (width, height) = xform.size;
+    let outline = [(0.0, 0.0), (width, 0.0), (width, height), (0.0, height)]
+        .map(|p| to_pixel(xform.apply_frame(p)));
+    draw_closed_line(&mut image, &outline, egui::Color32::WHITE);
+    image
+}
+
+/// Set the pixels in the bounding box of `points` whose centers are `inside`
+fn fill_polygon(
+    image: &mut egui::ColorImage,
+    points: &[(f64, f64)],
+    color: egui::Color32,
+    inside: impl Fn((f64, f64)) -> bool,
+) {
+    let [width, height] = image.size;
+    let clamp = |value: f64, max: usize| (value.max(0.0) as usize).min(max);
+    let x_range = points
+        .iter()
+        .map(|p| p.0)
+        .fold((f64::MAX, f64::MIN), |(lo, hi), x| (lo.min(x), hi.max(x)));
+    let y_range = points
+        .iter()
+        .map(|p| p.1)
+        .fold((f64::MAX, f64::MIN), |(lo, hi), y| (lo.min(y), hi.max(y)));
+    // Shapes thinner than a pixel still get one
+    let (x0, x1) = (
+        clamp(x_range.0, width),
+        clamp(x_range.1.max(x_range.0 + 1.0), width),
+    );
+    let (y0, y1) = (
+        clamp(y_range.0, height),
+        clamp(y_range.1.max(y_range.0 + 1.0), height),
+    );
+    for y in y0..y1 {
+        for x in x0..x1 {
+            if inside((x as f64 + 0.5, y as f64 + 0.5)) {
+                image.pixels[y * width + x] = color;
+            }
+        }
+    }
+}
+
+fn draw_closed_line(image: &mut egui::ColorImage, points: &[(f64, f64)], color: egui::Color32) {
+    let [width, height] = image.size;
+    for (a, b) in points.iter().zip(points.iter().cycle().skip(1)) {
+        let steps = (b.0 - a.0).abs().max((b.1 - a.1).abs()).ceil().max(1.0) as usize;
+        for step in 0..=steps {
+            let t = step as f64 / steps as f64;
+            let (x, y) = (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t);
+            // Edges on the right and bottom border stay inside the image
+            let x = (x.floor().max(0.0) as usize).min(width - 1);
+            let y = (y.floor().max(0.0) as usize).min(height - 1);
+            image.pixels[y * width + x] = color;
+        }
+    }
+}
+
+/// Pin count per direction, with POWER and GROUND pins counted on their own
+pub fn pin_summary(macro_def: &LefMacro) -> String {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for pin in &macro_def.pins {
+        let kind = match pin.use_type.as_str() {
+            "POWER" | "GROUND" => "PG",
+            _ if pin.direction.is_empty() => "OTHER",
+            _ => pin.direction.as_str(),
+        };
+        *counts.entry(kind).or_default() += 1;
+    }
+    let kinds: Vec<String> = counts
+        .iter()
+        .map(|(kind, count)| format!("{count} {kind}"))
+        .collect();
+    if kinds.is_empty() {
+        "no pins".to_string()
+    } else {
+        format!("{} pins: {}", macro_def.pins.len(), kinds.join(", "))
+    }
+}
+
+/// Preview card of a macro, shown as the hover UI of its cell list entry
+///
+/// The rasterized `thumbnail` is shown when it is ready, the macro is
+/// painted live otherwise. `instances` is the instance count row of the
+/// loaded DEF, left out without DEF.
+pub fn preview_card(
+    ui: &mut egui::Ui,
+    macro_def: &LefMacro,
+    layer_color: &impl Fn(&str) -> egui::Color32,
+    thumbnail: Option<&egui::TextureHandle>,
+    instances: Option<String>,
+) {
+    ui.strong(&macro_def.name);
+    let (response, painter) = ui.allocate_painter(CARD_THUMBNAIL, egui::Sense::hover());
+    let rect = response.rect;
+    painter.rect_filled(rect, 2.0, egui::Color32::from_gray(20));
+    let (width, height) = (macro_def.size_x as f32, macro_def.size_y as f32);
+    if let Some(texture) = thumbnail {
+        painter.image(
+            texture.id(),
+            rect,
+            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+            egui::Color32::WHITE,
+        );
+    } else if width > 0.0 && height > 0.0 {
+        let zoom = (rect.width() / width).min(rect.height() / height) * (1.0 - CARD_MARGIN);
+        paint_macro(
+            &painter.with_clip_rect(rect),
+            rect.center(),
+            zoom,
+            macro_def,
+            "N",
+            layer_color,
+            false,
+        );
+    }
+
+    egui::Grid::new("macro_preview_card")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("Size");
+            ui.label(format!(
+                "{:.3} x {:.3} um",
+                macro_def.size_x, macro_def.size_y
+            ));
+            ui.end_row();
+            ui.label("Class");
+            ui.label(&macro_def.class);
+            ui.end_row();
+            if !macro_def.site.is_empty() {
+                ui.label("Site");
+                ui.label(&macro_def.site);
+                ui.end_row();
+            }
+            ui.label("Pins");
+            ui.label(pin_summary(macro_def));
+            ui.end_row();
+            if let Some(instances) = instances {
+                ui.label("Instances");
+                ui.label(instances);
+                ui.end_row();
+            }
+        });
+
+    let names: Vec<&str> = macro_def
+        .pins
+        .iter()
+        .take(CARD_PIN_NAMES)
+        .map(|pin| pin.name.as_str())
+        .collect();
+    if !names.is_empty() {
+        let more = macro_def.pins.len().saturating_sub(CARD_PIN_NAMES);
+        let mut text = names.join(" ");
+        if more > 0 {
+            text.push_str(&format!(" ... +{more}"));
+        }
+        ui.add(egui::Label::new(egui::RichText::new(text).monospace().weak()).wrap());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{cell, pin};
+
+    #[test]
+    fn test_pin_summary() {
+        let mut macro_def = cell("NAND2", 1.0, 1.0, Vec::new());
+        assert_eq!(pin_summary(&macro_def), "no pins");
+
+        macro_def.pins = vec![
+            pin("A", "INPUT", "SIGNAL"),
+            pin("B", "INPUT", ""),
+            pin("Y", "OUTPUT", "SIGNAL"),
+            pin("VDD", "INOUT", "POWER"),
+            pin("VSS", "INOUT", "GROUND"),
+        ];
+        assert_eq!(pin_summary(&macro_def), "5 pins: 2 INPUT, 1 OUTPUT, 2 PG");
+    }
+
+    #[test]
+    fn test_rasterize_macro() {
+        let mut macro_def = LefMacro {
+            name: "BUF".to_string(),
+            class: "CORE".to_string(),
+            foreign: String::new(),
+            origin: (0.0, 0.0),
+            size_x: 10.0,
+            size_y: 10.0,
+            symmetry: Vec::new(),
+            site: String::new(),
+            pins: vec![pin("A", "INPUT", "SIGNAL")],
+            obs: Vec::new(),
+            ..LefMacro::default()
+        };
+        macro_def.pins[0].ports.push(crate::lef::LefPort {
+            rects: vec![crate::lef::LefRect {
+                layer: "M1".to_string(),
+                xl: 0.0,
+                yl: 0.0,
+                xh: 5.0,
+                yh: 5.0,
+            }],
+            polygons: Vec::new(),
+        });
+        let red = egui::Color32::RED;
+        let image = rasterize_macro(&macro_def, &|_| red, [20, 20]);
+        let pixel = |x: usize, y: usize| image.pixels[y * 20 + x];
+        // 10 um fit into 18 pixels, the pin is the lower left quarter
+        assert_eq!(pixel(5, 14), red);
+        assert_eq!(pixel(14, 5), egui::Color32::from_gray(20));
+        assert_eq!(pixel(1, 1), egui::Color32::WHITE);
+        assert_eq!(pixel(0, 0), egui::Color32::from_gray(20));
+    }
+}
@@ -1,24 +1,105 @@
 // SPDX-License-Identifier: MIT
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
+// The web build has no command line and leaves some items of the native
+// build unused
+#![cfg_attr(target_arch = "wasm32", allow(dead_code))]
+
+#[cfg(not(target_arch = "wasm32"))]
+mod batch;
+mod camera;
+mod charts_panel;
+mod checks;
+mod checks_panel;
+mod component_pins;
+mod component_table;
 mod def;
+mod dual_pane;
+mod edits_panel;
 mod export;
+mod file_watch;
+mod gds;
+mod gds_dialog;
+mod geometry;
+mod goto_dialog;
 mod gui;
+mod hit_test;
+mod identifier;
+mod image_export_dialog;
+mod isolation;
+mod issues_panel;
+mod label_buckets;
+mod layer_alias_dialog;
+mod layer_config;
+mod layer_keys;
+mod layer_solo;
+mod layer_tree;
 mod lef;
+mod lef_diff_panel;
+mod loader;
+mod macro_source_dialog;
+mod macro_thumbnail;
+mod min_area_dialog;
+mod mouse_bindings;
+mod net_inspector;
+mod netlist;
+mod number;
+mod object_flags;
+mod overlay;
+mod pad_label;
+mod parse_error;
+mod permalink;
+mod pin_lint;
+mod pin_lint_dialog;
+mod placement_diff_panel;
+mod platform;
+mod png_export;
+mod precompute;
+mod profiler_window;
+mod profiling;
+mod progress;
+mod recent_files;
+mod reproducer;
+mod reproducer_dialog;
+mod resource_panel;
+mod routing_resources;
+mod ruler;
+mod script;
+mod script_console;
+mod search_bar;
+mod session;
+mod spatial;
+mod status_bar;
+mod svg_export;
+mod tcl_selection;
+#[cfg(test)]
+mod test_fixtures;
+mod underlay;
+mod units;
+mod view_policy;
 mod voltage_dialog;
+mod waivers;
 
-use eframe::egui;
+fn create_app(cc: &eframe::CreationContext<'_>) -> Box<dyn eframe::App> {
+    egui_extras::install_image_loaders(&cc.egui_ctx);
+    Box::new(gui::LefDefViewer::with_storage(cc.storage))
+}
 
+#[cfg(not(target_arch = "wasm32"))]
 fn main() -> Result<(), eframe::Error> {
     env_logger::init();
 
-    let creator = Box::new(|cc: &eframe::CreationContext<'_>| {
-        egui_extras::install_image_loaders(&cc.egui_ctx);
-        Ok(Box::new(gui::LefDefViewer::new()) as Box<dyn eframe::App>)
-    });
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("check") => std::process::exit(batch::run_check(&args[1..])),
+        Some("export") => std::process::exit(batch::run_export(&args[1..])),
+        _ => {}
+    }
+
+    let creator = Box::new(|cc: &eframe::CreationContext<'_>| Ok(create_app(cc)));
 
     let mut options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default().with_inner_size([1200.0, 800.0]),
+        viewport: eframe::egui::ViewportBuilder::default().with_inner_size([1200.0, 800.0]),
         renderer: eframe::Renderer::Wgpu,
         ..Default::default()
     };
@@ -32,3 +113,30 @@ fn main() -> Result<(), eframe::Error> {
         }
     }
 }
+
+/// Start the viewer on the `lefdef_viewer_canvas` canvas of the page, see
+/// `index.html`
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    use wasm_bindgen::JsCast;
+
+    eframe::WebLogger::init(log::LevelFilter::Info).ok();
+
+    wasm_bindgen_futures::spawn_local(async {
+        let canvas = web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.get_element_by_id("lefdef_viewer_canvas"))
+            .and_then(|element| element.dyn_into::<web_sys::HtmlCanvasElement>().ok())
+            .expect("index.html has no lefdef_viewer_canvas canvas");
+        let started = eframe::WebRunner::new()
+            .start(
+                canvas,
+                eframe::WebOptions::default(),
+                Box::new(|cc| Ok(create_app(cc))),
+            )
+            .await;
+        if let Err(e) = started {
+            log::error!("Failed to start the viewer: {e:?}");
+        }
+    });
+}
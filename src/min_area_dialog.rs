@@ -0,0 +1,189 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Pin Minimum Area dialog
+//!
+//! Edits the minimum area of each layer, taken from the AREA rules of the
+//! technology LEF or entered by hand, and lists the pin shapes of the loaded
+//! libraries below it, see [`crate::lef::min_area`].
+
+use crate::lef::min_area::{pin_area_violations, technology_min_areas, MinAreaViolation};
+use crate::lef::Lef;
+use eframe::egui;
+use std::collections::BTreeMap;
+
+/// Violations listed before the rest is summarized
+const MAX_LISTED: usize = 1000;
+
+/// Minimum area check state and rendering
+#[derive(Default)]
+pub struct MinAreaDialog {
+    /// Whether the dialog is currently shown
+    pub visible: bool,
+    /// Minimum area per layer in square microns
+    min_areas: BTreeMap<String, f64>,
+    /// Whether the rules were filled in or edited, so new technology
+    /// rules do not replace them
+    rules_set: bool,
+    new_layer: String,
+    new_area: f64,
+    /// Result of the last run, None before the first run
+    violations: Option<Vec<MinAreaViolation>>,
+}
+
+impl MinAreaDialog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the last result, e.g. when the loaded LEF files change
+    pub fn clear(&mut self) {
+        self.violations = None;
+    }
+
+    /// Render the dialog over the (file, LEF) libraries
+    pub fn render(&mut self, ctx: &egui::Context, libraries: &[(&str, &Lef)]) {
+        if !self.visible {
+            return;
+        }
+        if !self.rules_set {
+            self.min_areas = technology_min_areas(libraries.iter().map(|(_, lef)| *lef));
+            self.rules_set = !self.min_areas.is_empty();
+        }
+
+        let mut open = true;
+        egui::Window::new("Pin Minimum Area")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(480.0)
+            .default_height(420.0)
+            .show(ctx, |ui| {
+                ui.label("Merged pin shapes must cover at least the layer minimum:");
+                let mut removed = None;
+                egui::Grid::new("min_area_rules")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (layer, area) in &mut self.min_areas {
+                            ui.monospace(layer);
+                            if ui
+                                .add(
+                                    egui::DragValue::new(area)
+                                        .speed(0.001)
+                                        .range(0.0..=f64::MAX)
+                                        .max_decimals(5)
+                                        .suffix(" um²"),
+                                )
+                                .changed()
+                            {
+                                self.rules_set = true;
+                            }
+                            if ui.small_button("🗑").on_hover_text("Remove rule").clicked() {
+                                removed = Some(layer.clone());
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if let Some(layer) = removed {
+                    self.min_areas.remove(&layer);
+                    self.rules_set = true;
+                }
+                if self.min_areas.is_empty() {
+                    ui.label(
+                        egui::RichText::new(
+                            "No AREA rules in the loaded LEF files, add them here.",
+                        )
+                        .weak(),
+                    );
+                }
+
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.new_layer)
+                            .hint_text("Layer")
+                            .desired_width(100.0)
+                            .font(egui::TextStyle::Monospace),
+                    );
+                    ui.add(
+                        egui::DragValue::new(&mut self.new_area)
+                            .speed(0.001)
+                            .range(0.0..=f64::MAX)
+                            .max_decimals(5)
+                            .suffix(" um²"),
+                    );
+                    let valid = !self.new_layer.trim().is_empty() && self.new_area > 0.0;
+                    if ui
+                        .add_enabled(valid, egui::Button::new("Add Rule"))
+                        .clicked()
+                    {
+                        self.min_areas
+                            .insert(self.new_layer.trim().to_string(), self.new_area);
+                        self.new_layer.clear();
+                        self.rules_set = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("Reset to Technology").clicked() {
+                        self.min_areas =
+                            technology_min_areas(libraries.iter().map(|(_, lef)| *lef));
+                        self.rules_set = !self.min_areas.is_empty();
+                    }
+                    ui.separator();
+                    if ui
+                        .add_enabled(
+                            !libraries.is_empty() && !self.min_areas.is_empty(),
+                            egui::Button::new("Run"),
+                        )
+                        .clicked()
+                    {
+                        self.violations = Some(pin_area_violations(libraries, &self.min_areas));
+                    }
+                });
+                ui.separator();
+
+                let Some(violations) = &self.violations else {
+                    return;
+                };
+                if violations.is_empty() {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(76, 175, 80),
+                        "All pin shapes meet the minimum area.",
+                    );
+                    return;
+                }
+                ui.label(format!("{} violation(s)", violations.len()));
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("min_area_violations")
+                        .num_columns(5)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Macro");
+                            ui.strong("Pin");
+                            ui.strong("Layer");
+                            ui.strong("Area (um²)");
+                            ui.strong("Minimum");
+                            ui.end_row();
+                            for violation in violations.iter().take(MAX_LISTED) {
+                                ui.label(&violation.macro_name)
+                                    .on_hover_text(&violation.file);
+                                let (xl, yl, xh, yh) = violation.bounds;
+                                ui.label(&violation.pin_name).on_hover_text(format!(
+                                    "({xl:.3}, {yl:.3}) - ({xh:.3}, {yh:.3})"
+                                ));
+                                ui.label(&violation.layer);
+                                ui.monospace(format!("{:.5}", violation.area));
+                                ui.monospace(format!("{:.5}", violation.min_area));
+                                ui.end_row();
+                            }
+                        });
+                    if violations.len() > MAX_LISTED {
+                        ui.label(format!("... and {} more", violations.len() - MAX_LISTED));
+                    }
+                });
+            });
+
+        if !open {
+            self.visible = false;
+        }
+    }
+}
@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Mouse bindings of the canvas
+//!
+//! What the wheel does with and without Ctrl or Shift held, and what a drag
+//! with each button does. The defaults follow Virtuoso and KLayout: the
+//! wheel zooms, Ctrl+wheel pans sideways, Shift+wheel pans up and down, the
+//! left and middle buttons pan and a right drag zooms to the dragged box.
+//! The bindings are edited in the Preferences window and kept with the
+//! session.
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+/// Action of the mouse wheel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WheelAction {
+    Zoom,
+    PanHorizontal,
+    PanVertical,
+}
+
+impl WheelAction {
+    pub const ALL: [WheelAction; 3] = [
+        WheelAction::Zoom,
+        WheelAction::PanHorizontal,
+        WheelAction::PanVertical,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WheelAction::Zoom => "Zoom",
+            WheelAction::PanHorizontal => "Pan Horizontally",
+            WheelAction::PanVertical => "Pan Vertically",
+        }
+    }
+}
+
+/// Action of a drag on the canvas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DragAction {
+    Pan,
+    /// Zoom to the box dragged out
+    ZoomBox,
+    Nothing,
+}
+
+impl DragAction {
+    pub const ALL: [DragAction; 3] = [DragAction::Pan, DragAction::ZoomBox, DragAction::Nothing];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DragAction::Pan => "Pan",
+            DragAction::ZoomBox => "Zoom Box",
+            DragAction::Nothing => "Nothing",
+        }
+    }
+}
+
+/// Wheel and drag actions of the canvas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MouseBindings {
+    pub wheel: WheelAction,
+    pub ctrl_wheel: WheelAction,
+    pub shift_wheel: WheelAction,
+    pub primary_drag: DragAction,
+    pub middle_drag: DragAction,
+    pub secondary_drag: DragAction,
+}
+
+impl Default for MouseBindings {
+    fn default() -> Self {
+        Self {
+            wheel: WheelAction::Zoom,
+            ctrl_wheel: WheelAction::PanHorizontal,
+            shift_wheel: WheelAction::PanVertical,
+            primary_drag: DragAction::Pan,
+            middle_drag: DragAction::Pan,
+            secondary_drag: DragAction::ZoomBox,
+        }
+    }
+}
+
+impl MouseBindings {
+    /// Wheel action with `modifiers` held, Ctrl taking precedence
+    pub fn wheel_action(&self, modifiers: egui::Modifiers) -> WheelAction {
+        if modifiers.command {
+            self.ctrl_wheel
+        } else if modifiers.shift {
+            self.shift_wheel
+        } else {
+            self.wheel
+        }
+    }
+
+    /// Drag action of a mouse button
+    pub fn drag_action(&self, button: egui::PointerButton) -> DragAction {
+        match button {
+            egui::PointerButton::Primary => self.primary_drag,
+            egui::PointerButton::Middle => self.middle_drag,
+            egui::PointerButton::Secondary => self.secondary_drag,
+            _ => DragAction::Nothing,
+        }
+    }
+
+    /// Bindings section of the Preferences window
+    pub fn render_settings(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("mouse_bindings")
+            .num_columns(2)
+            .show(ui, |ui| {
+                for (label, action) in [
+                    ("Wheel", &mut self.wheel),
+                    ("Ctrl+Wheel", &mut self.ctrl_wheel),
+                    ("Shift+Wheel", &mut self.shift_wheel),
+                ] {
+                    ui.label(label);
+                    egui::ComboBox::from_id_salt(label)
+                        .selected_text(action.label())
+                        .show_ui(ui, |ui| {
+                            for choice in WheelAction::ALL {
+                                ui.selectable_value(action, choice, choice.label());
+                            }
+                        });
+                    ui.end_row();
+                }
+                for (label, action) in [
+                    ("Left Drag", &mut self.primary_drag),
+                    ("Middle Drag", &mut self.middle_drag),
+                    ("Right Drag", &mut self.secondary_drag),
+                ] {
+                    ui.label(label);
+                    egui::ComboBox::from_id_salt(label)
+                        .selected_text(action.label())
+                        .show_ui(ui, |ui| {
+                            for choice in DragAction::ALL {
+                                ui.selectable_value(action, choice, choice.label());
+                            }
+                        });
+                    ui.end_row();
+                }
+            });
+        if ui.button("Restore Defaults").clicked() {
+            *self = Self::default();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_bindings() {
+        let bindings = MouseBindings::default();
+        assert_eq!(
+            bindings.wheel_action(egui::Modifiers::NONE),
+            WheelAction::Zoom
+        );
+        assert_eq!(
+            bindings.wheel_action(egui::Modifiers::COMMAND),
+            WheelAction::PanHorizontal
+        );
+        assert_eq!(
+            bindings.wheel_action(egui::Modifiers::SHIFT),
+            WheelAction::PanVertical
+        );
+        // Ctrl wins over Shift
+        assert_eq!(
+            bindings.wheel_action(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT),
+            WheelAction::PanHorizontal
+        );
+        assert_eq!(
+            bindings.drag_action(egui::PointerButton::Middle),
+            DragAction::Pan
+        );
+        assert_eq!(
+            bindings.drag_action(egui::PointerButton::Secondary),
+            DragAction::ZoomBox
+        );
+        assert_eq!(
+            bindings.drag_action(egui::PointerButton::Extra1),
+            DragAction::Nothing
+        );
+    }
+}
@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Net inspector window
+//!
+//! Shows the connections of one net and, once it is routed, how its wire
+//! length is spread over the routing layers, see
+//! [`crate::def::layer_usage`].
+
+use crate::def::layer_usage::net_layer_usage;
+use crate::def::Def;
+use crate::identifier;
+use eframe::egui;
+
+/// Render the inspector of `inspected_net`
+///
+/// Returns true when the layer usage of all nets should be exported.
+pub fn render_net_inspector(
+    ctx: &egui::Context,
+    def: &Def,
+    db_units: f64,
+    layer_order: &[String],
+    inspected_net: &mut Option<String>,
+) -> bool {
+    let Some(name) = inspected_net.clone() else {
+        return false;
+    };
+    let Some(net) = def.nets.iter().find(|n| n.name == name) else {
+        *inspected_net = None;
+        return false;
+    };
+    let usage = net_layer_usage(net, db_units, layer_order);
+    let total = usage.total_length();
+
+    let mut export = false;
+    let mut open = true;
+    egui::Window::new("Net Inspector")
+        .open(&mut open)
+        .resizable(true)
+        .default_width(320.0)
+        .show(ctx, |ui| {
+            egui::Grid::new("net_inspector_grid")
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("Name");
+                    ui.monospace(identifier::display_name(&net.name, def.bus_bit_chars));
+                    ui.end_row();
+                    if !net.use_type.is_empty() {
+                        ui.label("Use");
+                        ui.monospace(&net.use_type);
+                        ui.end_row();
+                    }
+                    if !net.pattern.is_empty() {
+                        ui.label("Pattern");
+                        ui.monospace(&net.pattern);
+                        ui.end_row();
+                    }
+                    if net.shielded {
+                        ui.label("Shielded");
+                        if net.shield_nets.is_empty() {
+                            ui.monospace("yes");
+                        } else {
+                            ui.monospace(net.shield_nets.join(", "));
+                        }
+                        ui.end_row();
+                    }
+                    ui.label("Instances");
+                    ui.monospace(net.instances.len().to_string());
+                    ui.end_row();
+                    ui.label("Pins");
+                    ui.monospace(net.pins.to_string());
+                    ui.end_row();
+                });
+
+            ui.separator();
+            if usage.layers.is_empty() {
+                ui.label("Not routed");
+            } else {
+                ui.label(format!("Routed length {total:.3} um, {} vias", usage.vias));
+                egui::Grid::new("net_layer_usage_grid")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Layer");
+                        ui.strong("Length (um)");
+                        ui.strong("Share");
+                        ui.end_row();
+                        for (layer, length) in &usage.layers {
+                            ui.label(layer);
+                            ui.monospace(format!("{length:.3}"));
+                            let share = if total > 0.0 { length / total } else { 0.0 };
+                            ui.add(
+                                egui::ProgressBar::new(share as f32)
+                                    .desired_width(100.0)
+                                    .text(format!("{:.1}%", share * 100.0)),
+                            );
+                            ui.end_row();
+                        }
+                    });
+            }
+
+            ui.separator();
+            if ui
+                .button("Export Layer Usage CSV...")
+                .on_hover_text("Write the routed length per layer of every net")
+                .clicked()
+            {
+                export = true;
+            }
+        });
+
+    if !open {
+        *inspected_net = None;
+    }
+    export
+}
@@ -0,0 +1,792 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Logical netlist import for cross-checking DEF names
+//!
+//! A structural Verilog netlist or a SPEF file gives the instance and net
+//! names the logical design expects. Comparing them with the DEF shows
+//! instances or nets that were renamed, added or dropped during an ECO.
+//!
+//! Only what is needed for name matching is read: instances with their
+//! cell, and the nets that connect to instance pins. Names are compared in
+//! display form (escapes removed, bus bits as `[n]`), so `\reg[3] ` in
+//! Verilog, `reg\[3\]` in DEF and `reg<3>` with `<>` BUSBITCHARS all match.
+
+use crate::def::Def;
+use crate::identifier::{self, BusBitChars};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+/// Instance and net names of a logical design
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogicalNetlist {
+    /// Instance name to cell name, None when the source gives no cell
+    pub instances: BTreeMap<String, Option<String>>,
+    /// Connected net names
+    pub nets: BTreeSet<String>,
+}
+
+/// Instance whose cell differs between netlist and DEF
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellMismatch {
+    pub instance: String,
+    pub netlist_cell: String,
+    pub def_cell: String,
+}
+
+/// Result of comparing a DEF against a logical netlist
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CrossCheckReport {
+    /// Instances in the netlist but not in the DEF
+    pub missing_instances: Vec<String>,
+    /// DEF components not in the netlist
+    pub extra_instances: Vec<String>,
+    pub cell_mismatches: Vec<CellMismatch>,
+    /// Nets in the netlist but not in the DEF
+    pub missing_nets: Vec<String>,
+    /// DEF nets not in the netlist
+    pub extra_nets: Vec<String>,
+    pub matched_instances: usize,
+    pub matched_nets: usize,
+}
+
+impl CrossCheckReport {
+    /// Whether the DEF and the netlist agree
+    pub fn is_clean(&self) -> bool {
+        self.missing_instances.is_empty()
+            && self.extra_instances.is_empty()
+            && self.cell_mismatches.is_empty()
+            && self.missing_nets.is_empty()
+            && self.extra_nets.is_empty()
+    }
+}
+
+/// Read a netlist file, choosing the format by extension
+///
+/// `.spef` files are read as SPEF, anything else as structural Verilog.
+pub fn read_netlist<P: AsRef<Path>>(path: P) -> Result<LogicalNetlist, String> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let is_spef = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("spef"));
+    if is_spef {
+        parse_spef(&content)
+    } else {
+        parse_verilog(&content)
+    }
+}
+
+/// Compare DEF component and net names against a logical netlist
+///
+/// Special nets are included on the DEF side, since power nets often show
+/// up as ordinary connections in the netlist.
+pub fn cross_check(def: &Def, netlist: &LogicalNetlist) -> CrossCheckReport {
+    let bus = def.bus_bit_chars;
+    let mut report = CrossCheckReport::default();
+
+    let def_components: BTreeMap<String, &str> = def
+        .components
+        .iter()
+        .map(|c| {
+            (
+                identifier::display_name(&c.name, bus),
+                c.macro_name.as_str(),
+            )
+        })
+        .collect();
+    let netlist_instances: BTreeMap<String, Option<&str>> = netlist
+        .instances
+        .iter()
+        .map(|(name, cell)| (normalize(name), cell.as_deref()))
+        .collect();
+
+    for (name, cell) in &netlist_instances {
+        match def_components.get(name) {
+            None => report.missing_instances.push(name.clone()),
+            Some(def_cell) => {
+                report.matched_instances += 1;
+                if let Some(cell) = cell {
+                    if cell != def_cell {
+                        report.cell_mismatches.push(CellMismatch {
+                            instance: name.clone(),
+                            netlist_cell: cell.to_string(),
+                            def_cell: def_cell.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+    report.extra_instances = def_components
+        .keys()
+        .filter(|name| !netlist_instances.contains_key(*name))
+        .cloned()
+        .collect();
+
+    let def_nets: BTreeSet<String> = def
+        .nets
+        .iter()
+        .map(|n| n.name.as_str())
+        .chain(def.special_nets.iter().map(|n| n.name.as_str()))
+        .map(|name| identifier::display_name(name, bus))
+        .collect();
+    let netlist_nets: BTreeSet<String> = netlist.nets.iter().map(|n| normalize(n)).collect();
+
+    report.missing_nets = netlist_nets.difference(&def_nets).cloned().collect();
+    report.extra_nets = def_nets.difference(&netlist_nets).cloned().collect();
+    report.matched_nets = netlist_nets.intersection(&def_nets).count();
+
+    report
+}
+
+/// Display form used for matching, with either bus bit convention
+fn normalize(name: &str) -> String {
+    let angle = BusBitChars {
+        open: '<',
+        close: '>',
+    };
+    if identifier::split_bus_bit(name, angle).is_some() {
+        identifier::display_name(name, angle)
+    } else {
+        identifier::display_name(name, BusBitChars::default())
+    }
+}
+
+/// Parse a structural Verilog netlist
+///
+/// The top module is the one no other module instantiates. Instances of
+/// modules defined in the same file are flattened with `/` separated
+/// hierarchical names, matching how DEF names flattened instances.
+pub fn parse_verilog(input: &str) -> Result<LogicalNetlist, String> {
+    let tokens = tokenize_verilog(input);
+    let modules = parse_modules(&tokens)?;
+    if modules.is_empty() {
+        return Err("No module found in netlist".to_string());
+    }
+
+    let instantiated: BTreeSet<&str> = modules
+        .values()
+        .flat_map(|m| m.instances.iter().map(|i| i.cell.as_str()))
+        .filter(|cell| modules.contains_key(*cell))
+        .collect();
+    let top = modules
+        .values()
+        .filter(|m| !instantiated.contains(m.name.as_str()))
+        .max_by_key(|m| m.instances.len())
+        .ok_or_else(|| "Netlist modules instantiate each other recursively".to_string())?;
+
+    let mut netlist = LogicalNetlist::default();
+    flatten_module(top, "", &modules, &mut netlist, 0)?;
+    Ok(netlist)
+}
+
+/// Parse instance and net names from a SPEF file
+///
+/// Names may be given through the `*NAME_MAP` section. Instances come from
+/// the `*I` entries of each `*CONN` section, nets from `*D_NET` and `*R_NET`.
+pub fn parse_spef(input: &str) -> Result<LogicalNetlist, String> {
+    let mut name_map: HashMap<&str, &str> = HashMap::new();
+    let mut delimiter = ':';
+    let mut in_name_map = false;
+    let mut netlist = LogicalNetlist::default();
+    let mut seen_net = false;
+
+    let resolve = |name_map: &HashMap<&str, &str>, name: &str| -> String {
+        name_map.get(name).copied().unwrap_or(name).to_string()
+    };
+
+    for line in input.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        let mut fields = line.split_whitespace();
+        let Some(keyword) = fields.next() else {
+            continue;
+        };
+
+        match keyword {
+            "*DELIMITER" => {
+                if let Some(c) = fields.next().and_then(|d| d.chars().next()) {
+                    delimiter = c;
+                }
+            }
+            "*NAME_MAP" => in_name_map = true,
+            "*D_NET" | "*R_NET" => {
+                in_name_map = false;
+                if let Some(net) = fields.next() {
+                    seen_net = true;
+                    netlist.nets.insert(resolve(&name_map, net));
+                }
+            }
+            "*I" => {
+                let Some(pin_ref) = fields.next() else {
+                    continue;
+                };
+                let Some((instance, _pin)) = pin_ref.rsplit_once(delimiter) else {
+                    continue;
+                };
+                let instance = resolve(&name_map, instance);
+                // Cell name follows *D in the optional pin attributes
+                let cell = fields
+                    .skip_while(|f| *f != "*D")
+                    .nth(1)
+                    .map(|c| resolve(&name_map, c));
+                let entry = netlist.instances.entry(instance).or_default();
+                if entry.is_none() {
+                    *entry = cell;
+                }
+            }
+            _ if in_name_map && keyword.starts_with('*') => {
+                if let Some(name) = fields.next() {
+                    name_map.insert(keyword, name);
+                }
+            }
+            _ if keyword.starts_with('*') => in_name_map = false,
+            _ => {}
+        }
+    }
+
+    if !seen_net {
+        return Err("No *D_NET or *R_NET sections found in SPEF".to_string());
+    }
+    Ok(netlist)
+}
+
+/// One module of a Verilog netlist
+#[derive(Debug, Default)]
+struct VerilogModule {
+    name: String,
+    ports: BTreeSet<String>,
+    /// Declared width of bus wires and ports, as (msb, lsb)
+    ranges: HashMap<String, (i64, i64)>,
+    instances: Vec<VerilogInstance>,
+}
+
+#[derive(Debug)]
+struct VerilogInstance {
+    cell: String,
+    name: String,
+    /// Net names connected to the instance pins, bit-blasted
+    nets: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Number(String),
+    Punct(char),
+}
+
+/// Split Verilog source into identifiers, numbers and punctuation
+fn tokenize_verilog(input: &str) -> Vec<Token> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if c == '\\' {
+            // Escaped identifier, ends at whitespace
+            let start = i + 1;
+            i = start;
+            while i < chars.len() && !chars[i].is_whitespace() {
+                i += 1;
+            }
+            tokens.push(Token::Ident(escape_verilog_name(
+                &chars[start..i].iter().collect::<String>(),
+            )));
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || "_$".contains(chars[i])) {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else if c.is_ascii_digit() || c == '\'' {
+            // Sized constants like 1'b0 are read as one number
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || "'_".contains(chars[i])) {
+                i += 1;
+            }
+            tokens.push(Token::Number(chars[start..i].iter().collect()));
+        } else {
+            tokens.push(Token::Punct(c));
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+/// Escaped Verilog name in LEF/DEF escape syntax
+///
+/// Brackets inside an escaped Verilog identifier are part of the name, so
+/// they are escaped and never read as a bus bit.
+fn escape_verilog_name(name: &str) -> String {
+    identifier::escape(name, BusBitChars::default())
+}
+
+/// Cursor over the token stream
+struct Tokens<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&'a Token> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        self.peek() == Some(&Token::Punct(c))
+    }
+
+    fn eat_punct(&mut self, c: char) -> bool {
+        let matched = self.is_punct(c);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn ident(&mut self) -> Option<&'a str> {
+        match self.peek() {
+            Some(Token::Ident(name)) => {
+                self.pos += 1;
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
+    /// Skip to just past the next `;` at this nesting level
+    fn skip_statement(&mut self) {
+        let mut depth = 0i32;
+        while let Some(token) = self.next() {
+            match token {
+                Token::Punct('(') | Token::Punct('{') | Token::Punct('[') => depth += 1,
+                Token::Punct(')') | Token::Punct('}') | Token::Punct(']') => depth -= 1,
+                Token::Punct(';') if depth <= 0 => return,
+                _ => {}
+            }
+        }
+    }
+
+    /// Tokens of a balanced `( ... )` group, without the outer parentheses
+    fn paren_group(&mut self) -> Option<&'a [Token]> {
+        if !self.eat_punct('(') {
+            return None;
+        }
+        let start = self.pos;
+        let mut depth = 1;
+        while let Some(token) = self.next() {
+            match token {
+                Token::Punct('(') => depth += 1,
+                Token::Punct(')') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(&self.tokens[start..self.pos - 1]);
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Parse `[msb:lsb]` or `[bit]`
+    fn range(&mut self) -> Option<(i64, i64)> {
+        if !self.eat_punct('[') {
+            return None;
+        }
+        let msb = self.number();
+        let lsb = if self.eat_punct(':') {
+            self.number()
+        } else {
+            msb
+        };
+        while let Some(token) = self.next() {
+            if token == &Token::Punct(']') {
+                break;
+            }
+        }
+        Some((msb?, lsb?))
+    }
+
+    fn number(&mut self) -> Option<i64> {
+        match self.peek() {
+            Some(Token::Number(value)) => {
+                self.pos += 1;
+                value.parse().ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+const DECLARATION_KEYWORDS: [&str; 9] = [
+    "input", "output", "inout", "wire", "tri", "wand", "wor", "supply0", "supply1",
+];
+const SKIPPED_KEYWORDS: [&str; 9] = [
+    "assign",
+    "reg",
+    "parameter",
+    "localparam",
+    "specify",
+    "timescale",
+    "defparam",
+    "genvar",
+    "initial",
+];
+
+fn parse_modules(tokens: &[Token]) -> Result<BTreeMap<String, VerilogModule>, String> {
+    let mut cursor = Tokens { tokens, pos: 0 };
+    let mut modules = BTreeMap::new();
+
+    while let Some(token) = cursor.next() {
+        if token != &Token::Ident("module".to_string()) {
+            continue;
+        }
+        let module = parse_module(&mut cursor)?;
+        modules.insert(module.name.clone(), module);
+    }
+
+    Ok(modules)
+}
+
+fn parse_module(cursor: &mut Tokens) -> Result<VerilogModule, String> {
+    let name = cursor
+        .ident()
+        .ok_or_else(|| "Expected module name after 'module'".to_string())?;
+    let mut module = VerilogModule {
+        name: name.to_string(),
+        ..Default::default()
+    };
+
+    if cursor.eat_punct('#') {
+        cursor.paren_group();
+    }
+    if let Some(header) = cursor.paren_group() {
+        parse_port_header(header, &mut module);
+    }
+    cursor.eat_punct(';');
+
+    loop {
+        let Some(token) = cursor.peek() else {
+            return Err(format!("Missing endmodule for module {}", module.name));
+        };
+        let Token::Ident(keyword) = token else {
+            cursor.skip_statement();
+            continue;
+        };
+        match keyword.as_str() {
+            "endmodule" => {
+                cursor.next();
+                return Ok(module);
+            }
+            "specify" => {
+                while let Some(token) = cursor.next() {
+                    if token == &Token::Ident("endspecify".to_string()) {
+                        break;
+                    }
+                }
+            }
+            k if DECLARATION_KEYWORDS.contains(&k) => {
+                cursor.next();
+                let is_port = matches!(k, "input" | "output" | "inout");
+                parse_declaration(cursor, &mut module, is_port);
+            }
+            k if SKIPPED_KEYWORDS.contains(&k) => cursor.skip_statement(),
+            _ => parse_instances(cursor, &mut module),
+        }
+    }
+}
+
+/// ANSI headers declare ports inline, e.g. `(input [3:0] a, output y)`
+fn parse_port_header(header: &[Token], module: &mut VerilogModule) {
+    let mut cursor = Tokens {
+        tokens: header,
+        pos: 0,
+    };
+    let mut range = None;
+    while let Some(token) = cursor.peek() {
+        match token {
+            Token::Ident(word) if DECLARATION_KEYWORDS.contains(&word.as_str()) => {
+                cursor.next();
+                range = None;
+            }
+            Token::Ident(word) if word == "reg" || word == "signed" => {
+                cursor.next();
+            }
+            Token::Punct('[') => range = cursor.range(),
+            Token::Ident(port) => {
+                cursor.next();
+                module.ports.insert(port.clone());
+                if let Some(range) = range {
+                    module.ranges.insert(port.clone(), range);
+                }
+            }
+            _ => {
+                cursor.next();
+            }
+        }
+    }
+}
+
+fn parse_declaration(cursor: &mut Tokens, module: &mut VerilogModule, is_port: bool) {
+    let mut range = None;
+    while let Some(token) = cursor.peek() {
+        match token {
+            Token::Punct(';') => {
+                cursor.next();
+                return;
+            }
+            Token::Punct('[') => range = cursor.range(),
+            Token::Punct('=') => {
+                // `wire a = b;` style assignment, nothing more to declare
+                cursor.skip_statement();
+                return;
+            }
+            Token::Ident(name) => {
+                cursor.next();
+                if is_port {
+                    module.ports.insert(name.clone());
+                }
+                if let Some(range) = range {
+                    module.ranges.insert(name.clone(), range);
+                }
+            }
+            _ => {
+                cursor.next();
+            }
+        }
+    }
+}
+
+/// Parse `CELL [#(...)] inst (...) [, inst2 (...)] ;`
+fn parse_instances(cursor: &mut Tokens, module: &mut VerilogModule) {
+    let Some(cell) = cursor.ident() else {
+        cursor.skip_statement();
+        return;
+    };
+    if cursor.eat_punct('#') {
+        cursor.paren_group();
+    }
+
+    loop {
+        let Some(name) = cursor.ident() else {
+            cursor.skip_statement();
+            return;
+        };
+        // Instance arrays are not expanded, the range is skipped
+        cursor.range();
+        let Some(connections) = cursor.paren_group() else {
+            cursor.skip_statement();
+            return;
+        };
+        let nets = connection_nets(connections, &module.ranges);
+        module.instances.push(VerilogInstance {
+            cell: cell.to_string(),
+            name: name.to_string(),
+            nets,
+        });
+
+        if !cursor.eat_punct(',') {
+            cursor.eat_punct(';');
+            return;
+        }
+    }
+}
+
+/// Net names referenced by an instance connection list, bit-blasted
+fn connection_nets(tokens: &[Token], ranges: &HashMap<String, (i64, i64)>) -> Vec<String> {
+    let mut cursor = Tokens { tokens, pos: 0 };
+    let mut nets = Vec::new();
+
+    while let Some(token) = cursor.next() {
+        match token {
+            // `.PIN(` - the pin name is not a net
+            Token::Punct('.') => {
+                cursor.ident();
+            }
+            Token::Ident(name) => match cursor.range() {
+                Some((msb, lsb)) => nets.extend(bit_names(name, msb, lsb)),
+                None => match ranges.get(name) {
+                    Some(&(msb, lsb)) => nets.extend(bit_names(name, msb, lsb)),
+                    None => nets.push(name.clone()),
+                },
+            },
+            _ => {}
+        }
+    }
+
+    nets
+}
+
+fn bit_names(name: &str, msb: i64, lsb: i64) -> impl Iterator<Item = String> + '_ {
+    let (low, high) = if msb <= lsb { (msb, lsb) } else { (lsb, msb) };
+    (low..=high).map(move |bit| format!("{name}[{bit}]"))
+}
+
+/// Limit on module nesting, guards against malformed recursive netlists
+const MAX_HIERARCHY_DEPTH: usize = 64;
+
+fn flatten_module(
+    module: &VerilogModule,
+    prefix: &str,
+    modules: &BTreeMap<String, VerilogModule>,
+    netlist: &mut LogicalNetlist,
+    depth: usize,
+) -> Result<(), String> {
+    if depth > MAX_HIERARCHY_DEPTH {
+        return Err(format!(
+            "Netlist hierarchy deeper than {MAX_HIERARCHY_DEPTH} levels at {prefix}"
+        ));
+    }
+
+    for instance in &module.instances {
+        let name = format!("{prefix}{}", instance.name);
+        for net in &instance.nets {
+            // Ports of a submodule are the nets of its parent
+            let base = net.split('[').next().unwrap_or(net);
+            if depth == 0 || !module.ports.contains(base) {
+                netlist.nets.insert(format!("{prefix}{net}"));
+            }
+        }
+
+        match modules.get(&instance.cell) {
+            Some(submodule) => {
+                flatten_module(submodule, &format!("{name}/"), modules, netlist, depth + 1)?
+            }
+            None => {
+                netlist.instances.insert(name, Some(instance.cell.clone()));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERILOG: &str = r"
+// Simple netlist
+module sub (input a, output y);
+  wire mid;
+  INV u0 (.A(a), .Y(mid));
+  BUF u1 (.A(mid), .Y(y));
+endmodule
+
+module top (clk, d, q);
+  input clk;
+  input [1:0] d;
+  output q;
+  wire \n[0] ;
+  /* two flops */
+  DFF #(.INIT(1'b0)) r0 (.CK(clk), .D(d[0]), .Q(\n[0] ));
+  AND2 g0 (.A(d), .Y(q)), g1 (.A(1'b1), .Y());
+  sub s0 (.a(\n[0] ), .y(q));
+endmodule
+";
+
+    #[test]
+    fn test_parse_verilog_flattens_hierarchy() {
+        let netlist = parse_verilog(VERILOG).unwrap();
+        let instances: Vec<&str> = netlist.instances.keys().map(|s| s.as_str()).collect();
+        assert_eq!(instances, ["g0", "g1", "r0", "s0/u0", "s0/u1"]);
+        assert_eq!(netlist.instances["r0"], Some("DFF".to_string()));
+
+        let nets: Vec<&str> = netlist.nets.iter().map(|s| s.as_str()).collect();
+        // Bus connection `d` is bit-blasted, sub ports alias parent nets
+        assert_eq!(nets, ["clk", "d[0]", "d[1]", r"n\[0\]", "q", "s0/mid"]);
+    }
+
+    #[test]
+    fn test_parse_spef_uses_name_map() {
+        let spef = r#"*SPEF "IEEE 1481-1998"
+*DELIMITER :
+*NAME_MAP
+*1 net_a
+*2 u_top/reg\[3\]
+*3 INV
+*D_NET *1 0.5
+*CONN
+*I *2:Q O *D DFF
+*I u5:A I
+*END
+"#;
+        let netlist = parse_spef(spef).unwrap();
+        assert!(netlist.nets.contains("net_a"));
+        assert_eq!(
+            netlist.instances[r"u_top/reg\[3\]"],
+            Some("DFF".to_string())
+        );
+        assert_eq!(netlist.instances["u5"], None);
+        assert!(parse_spef("*SPEF x").is_err());
+    }
+
+    #[test]
+    fn test_cross_check_reports_mismatches() {
+        use crate::def::DefComponent;
+
+        let component = |name: &str, macro_name: &str| DefComponent {
+            name: name.to_string(),
+            macro_name: macro_name.to_string(),
+            ..DefComponent::default()
+        };
+        let def = Def {
+            components: vec![
+                component("r0", "DFF"),
+                component("g0", "AND3"),
+                component("fill_1", "FILL1"),
+            ],
+            ..Default::default()
+        };
+        let netlist = parse_verilog(
+            "module top; DFF r0 (.Q(n1)); AND2 g0 (.Y(n1)); INV g1 (.A(n1)); endmodule",
+        )
+        .unwrap();
+
+        let report = cross_check(&def, &netlist);
+        assert_eq!(report.matched_instances, 2);
+        assert_eq!(report.missing_instances, ["g1"]);
+        assert_eq!(report.extra_instances, ["fill_1"]);
+        assert_eq!(
+            report.cell_mismatches,
+            [CellMismatch {
+                instance: "g0".to_string(),
+                netlist_cell: "AND2".to_string(),
+                def_cell: "AND3".to_string(),
+            }]
+        );
+        assert_eq!(report.missing_nets, ["n1"]);
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn test_normalize_matches_conventions() {
+        assert_eq!(normalize("data<3>"), "data[3]");
+        assert_eq!(normalize("data[3]"), "data[3]");
+        assert_eq!(normalize(r"reg\[3\]"), "reg[3]");
+    }
+}
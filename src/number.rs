@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Number tokens of LEF and DEF files
+//!
+//! Rust and nom parse `NaN`, `inf` and overflowing exponents such as
+//! `1e999` as floating point numbers. Coordinates like that come from
+//! corrupt files and would end up in the bounds and the camera, so the
+//! parsers reject them like any other malformed number: the statement is
+//! skipped or the value falls back to its default.
+
+use nom::combinator::verify;
+use nom::number::complete::double;
+use nom::{IResult, Parser};
+
+/// Token that is not a finite number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidNumber;
+
+impl std::fmt::Display for InvalidNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("not a finite number")
+    }
+}
+
+impl std::error::Error for InvalidNumber {}
+
+/// Parse a token as a finite `f64`
+pub trait ParseFinite {
+    fn parse_finite(&self) -> Result<f64, InvalidNumber>;
+}
+
+impl ParseFinite for str {
+    fn parse_finite(&self) -> Result<f64, InvalidNumber> {
+        self.parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite())
+            .ok_or(InvalidNumber)
+    }
+}
+
+/// nom parser of a finite floating point number
+pub fn finite_double(input: &str) -> IResult<&str, f64> {
+    verify(double, |value: &f64| value.is_finite()).parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_finite() {
+        assert_eq!("-1.25".parse_finite(), Ok(-1.25));
+        assert_eq!("2e3".parse_finite(), Ok(2000.0));
+        for token in ["NaN", "nan", "inf", "-infinity", "1e999", "", "1.2.3"] {
+            assert_eq!(token.parse_finite(), Err(InvalidNumber), "{token}");
+        }
+    }
+
+    #[test]
+    fn test_finite_double() {
+        assert_eq!(finite_double("0.5 ;"), Ok((" ;", 0.5)));
+        assert!(finite_double("nan ;").is_err());
+        assert!(finite_double("1e400").is_err());
+    }
+}
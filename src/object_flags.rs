@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Per-object visibility and lock flags
+//!
+//! Designs have far more objects than flagged ones, so flags live in a
+//! sparse map keyed by the object index (position in `Def::components` or
+//! `Def::pins`) and only flagged objects take any memory. Hidden objects
+//! are skipped by rendering and picking; locked objects are refused by
+//! operations that modify the design.
+
+use eframe::egui;
+use std::collections::HashMap;
+
+const HIDDEN: u8 = 1 << 0;
+const LOCKED: u8 = 1 << 1;
+
+/// Hidden and locked flags for one kind of object
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ObjectFlags {
+    flags: HashMap<u32, u8>,
+}
+
+impl ObjectFlags {
+    /// Create an empty flag map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the object is excluded from rendering and picking
+    pub fn is_hidden(&self, index: usize) -> bool {
+        self.get(index) & HIDDEN != 0
+    }
+
+    /// Whether the object must not be modified
+    pub fn is_locked(&self, index: usize) -> bool {
+        self.get(index) & LOCKED != 0
+    }
+
+    pub fn set_hidden(&mut self, index: usize, hidden: bool) {
+        self.set(index, HIDDEN, hidden);
+    }
+
+    pub fn set_locked(&mut self, index: usize, locked: bool) {
+        self.set(index, LOCKED, locked);
+    }
+
+    /// Number of hidden objects
+    pub fn hidden_count(&self) -> usize {
+        self.flags.values().filter(|f| **f & HIDDEN != 0).count()
+    }
+
+    /// Number of locked objects
+    pub fn locked_count(&self) -> usize {
+        self.flags.values().filter(|f| **f & LOCKED != 0).count()
+    }
+
+    /// Make every object visible again, keeping the locks
+    pub fn show_all(&mut self) {
+        self.flags.retain(|_, f| {
+            *f &= !HIDDEN;
+            *f != 0
+        });
+    }
+
+    /// Drop all flags, e.g. when a new design is loaded
+    pub fn clear(&mut self) {
+        self.flags.clear();
+    }
+
+    /// Eye and lock toggle buttons for one tree row
+    pub fn render_toggles(&mut self, ui: &mut egui::Ui, index: usize) {
+        let hidden = self.is_hidden(index);
+        let eye = if hidden { "◌" } else { "👁" };
+        if ui
+            .add(egui::Button::new(eye).small().frame(false))
+            .on_hover_text(if hidden { "Show" } else { "Hide" })
+            .clicked()
+        {
+            self.set_hidden(index, !hidden);
+        }
+
+        let locked = self.is_locked(index);
+        let lock = if locked { "🔒" } else { "🔓" };
+        if ui
+            .add(egui::Button::new(lock).small().frame(false))
+            .on_hover_text(if locked { "Unlock" } else { "Lock" })
+            .clicked()
+        {
+            self.set_locked(index, !locked);
+        }
+    }
+
+    fn get(&self, index: usize) -> u8 {
+        u32::try_from(index)
+            .ok()
+            .and_then(|index| self.flags.get(&index))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn set(&mut self, index: usize, flag: u8, on: bool) {
+        let Ok(index) = u32::try_from(index) else {
+            return;
+        };
+        let bits = self.flags.entry(index).or_default();
+        if on {
+            *bits |= flag;
+        } else {
+            *bits &= !flag;
+        }
+        if *bits == 0 {
+            self.flags.remove(&index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flags_are_independent() {
+        let mut flags = ObjectFlags::new();
+        flags.set_hidden(3, true);
+        flags.set_locked(3, true);
+        flags.set_locked(7, true);
+        assert!(flags.is_hidden(3) && flags.is_locked(3));
+        assert!(!flags.is_hidden(7) && flags.is_locked(7));
+        assert!(!flags.is_hidden(0));
+
+        flags.show_all();
+        assert_eq!(flags.hidden_count(), 0);
+        assert_eq!(flags.locked_count(), 2);
+    }
+
+    #[test]
+    fn test_cleared_flags_take_no_space() {
+        let mut flags = ObjectFlags::new();
+        flags.set_hidden(1, true);
+        flags.set_hidden(1, false);
+        assert_eq!(flags, ObjectFlags::new());
+    }
+}
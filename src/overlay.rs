@@ -0,0 +1,377 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Per-source overlay transforms
+//!
+//! Data from different sources (the DEF, each LEF file) may use different
+//! origins. A [`SourceTransform`] moves, rotates and scales one source so it
+//! lines up visually with the others. It is applied to the painted shapes,
+//! so the parsed data and all exports stay untouched.
+
+use eframe::egui;
+use std::sync::Arc;
+
+/// Offset, rotation and scale of one loaded source, in world units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceTransform {
+    /// Translation in microns
+    pub offset_x: f64,
+    pub offset_y: f64,
+    /// Counterclockwise rotation about the source origin, in degrees (0, 90, 180, 270)
+    pub rotation: u16,
+    /// Uniform scale about the source origin
+    pub scale: f64,
+}
+
+impl Default for SourceTransform {
+    fn default() -> Self {
+        Self {
+            offset_x: 0.0,
+            offset_y: 0.0,
+            rotation: 0,
+            scale: 1.0,
+        }
+    }
+}
+
+impl SourceTransform {
+    /// Whether the transform leaves the source where it is
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Screen-space version of this transform
+    ///
+    /// `pivot` is the screen position of the source origin and `zoom` the
+    /// current pixels per micron. Returns None for the identity so callers
+    /// can skip the extra work.
+    pub fn to_screen(self, pivot: egui::Pos2, zoom: f32) -> Option<ScreenTransform> {
+        if self.is_identity() {
+            return None;
+        }
+        Some(ScreenTransform {
+            pivot,
+            quarter_turns: ((self.rotation / 90) % 4) as u8,
+            scale: self.scale as f32,
+            // World Y points up, screen Y points down
+            translation: egui::vec2(self.offset_x as f32 * zoom, -(self.offset_y as f32) * zoom),
+        })
+    }
+}
+
+/// A [`SourceTransform`] resolved to screen coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenTransform {
+    pivot: egui::Pos2,
+    quarter_turns: u8,
+    scale: f32,
+    translation: egui::Vec2,
+}
+
+impl ScreenTransform {
+    /// Map a screen point of the untransformed source to its displayed position
+    pub fn apply(&self, point: egui::Pos2) -> egui::Pos2 {
+        let d = point - self.pivot;
+        // Counterclockwise in world space is clockwise on the Y-down screen
+        let rotated = match self.quarter_turns {
+            1 => egui::vec2(d.y, -d.x),
+            2 => -d,
+            3 => egui::vec2(-d.y, d.x),
+            _ => d,
+        };
+        self.pivot + rotated * self.scale + self.translation
+    }
+
+    /// Inverse of [`Self::apply`]
+    pub fn invert(&self, point: egui::Pos2) -> egui::Pos2 {
+        let d = (point - self.translation - self.pivot) / self.scale;
+        let unrotated = match self.quarter_turns {
+            1 => egui::vec2(-d.y, d.x),
+            2 => -d,
+            3 => egui::vec2(d.y, -d.x),
+            _ => d,
+        };
+        self.pivot + unrotated
+    }
+
+    /// Region of the untransformed source that ends up inside `rect`
+    ///
+    /// Used as the clip rect while painting, so viewport culling keeps
+    /// working on the untransformed coordinates.
+    pub fn source_region(&self, rect: egui::Rect) -> egui::Rect {
+        egui::Rect::from_points(&[
+            self.invert(rect.left_top()),
+            self.invert(rect.right_top()),
+            self.invert(rect.right_bottom()),
+            self.invert(rect.left_bottom()),
+        ])
+    }
+
+    /// Transform a painted shape in place
+    ///
+    /// Axis-aligned rectangles stay axis-aligned because rotation is limited
+    /// to quarter turns. Text is moved but kept upright, and marker sizes
+    /// (circle radii, stroke widths) are kept so they stay readable.
+    pub fn apply_shape(&self, shape: &mut egui::Shape) {
+        match shape {
+            egui::Shape::Vec(shapes) => {
+                for shape in shapes {
+                    self.apply_shape(shape);
+                }
+            }
+            egui::Shape::Circle(circle) => circle.center = self.apply(circle.center),
+            egui::Shape::Ellipse(ellipse) => {
+                ellipse.center = self.apply(ellipse.center);
+                if self.quarter_turns % 2 == 1 {
+                    ellipse.radius = egui::vec2(ellipse.radius.y, ellipse.radius.x);
+                }
+            }
+            egui::Shape::LineSegment { points, .. } => {
+                for point in points {
+                    *point = self.apply(*point);
+                }
+            }
+            egui::Shape::Path(path) => {
+                for point in &mut path.points {
+                    *point = self.apply(*point);
+                }
+            }
+            egui::Shape::Rect(rect_shape) => {
+                rect_shape.rect = egui::Rect::from_two_pos(
+                    self.apply(rect_shape.rect.min),
+                    self.apply(rect_shape.rect.max),
+                );
+            }
+            egui::Shape::Text(text) => {
+                let size = text.galley.size();
+                let center = self.apply(text.pos + size / 2.0);
+                text.pos = center - size / 2.0;
+            }
+            egui::Shape::Mesh(mesh) => {
+                for vertex in &mut Arc::make_mut(mesh).vertices {
+                    vertex.pos = self.apply(vertex.pos);
+                }
+            }
+            egui::Shape::QuadraticBezier(bezier) => {
+                for point in &mut bezier.points {
+                    *point = self.apply(*point);
+                }
+            }
+            egui::Shape::CubicBezier(bezier) => {
+                for point in &mut bezier.points {
+                    *point = self.apply(*point);
+                }
+            }
+            egui::Shape::Noop | egui::Shape::Callback(_) => {}
+        }
+    }
+}
+
+/// Shapes painted for one source, transformed when the paint is finished
+pub struct SourcePaint {
+    /// Painter to draw the source with
+    pub painter: egui::Painter,
+    transform: Option<ScreenTransform>,
+    canvas_clip: egui::Rect,
+    first_shape: egui::layers::ShapeIdx,
+}
+
+impl SourcePaint {
+    /// Start painting a source on top of `painter`
+    pub fn begin(painter: &egui::Painter, transform: Option<ScreenTransform>) -> Self {
+        let canvas_clip = painter.clip_rect();
+        let mut source_painter = painter.clone();
+        if let Some(transform) = &transform {
+            // Replace rather than intersect: the source may be moved into view
+            source_painter.set_clip_rect(transform.source_region(canvas_clip));
+        }
+        let first_shape = painter
+            .ctx()
+            .graphics_mut(|graphics| graphics.entry(painter.layer_id()).next_idx());
+
+        Self {
+            painter: source_painter,
+            transform,
+            canvas_clip,
+            first_shape,
+        }
+    }
+
+    /// Transform everything painted since [`Self::begin`]
+    pub fn finish(self) {
+        let Some(transform) = self.transform else {
+            return;
+        };
+
+        let layer_id = self.painter.layer_id();
+        self.painter.ctx().graphics_mut(|graphics| {
+            let paint_list = graphics.entry(layer_id);
+            let end = paint_list.next_idx().0;
+            for idx in self.first_shape.0..end {
+                paint_list.mutate_shape(egui::layers::ShapeIdx(idx), |clipped| {
+                    transform.apply_shape(&mut clipped.shape);
+                    clipped.clip_rect = self.canvas_clip;
+                });
+            }
+        });
+    }
+
+    /// Screen transform of this source, if any
+    pub fn transform(&self) -> Option<&ScreenTransform> {
+        self.transform.as_ref()
+    }
+}
+
+/// Dialog for editing the transform of each loaded source
+#[derive(Default)]
+pub struct OverlayTransformDialog {
+    /// Whether the dialog is currently shown
+    pub visible: bool,
+}
+
+impl OverlayTransformDialog {
+    /// Create a new overlay transform dialog
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render the dialog for the given (label, transform) sources
+    pub fn render(&mut self, ctx: &egui::Context, sources: &mut [(String, &mut SourceTransform)]) {
+        if !self.visible {
+            return;
+        }
+
+        let mut open = true;
+        egui::Window::new("Source Transforms")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                if sources.is_empty() {
+                    ui.label("No sources loaded");
+                    return;
+                }
+
+                ui.label("Offset (um), rotation about the source origin, and scale:");
+                ui.label("LEF offsets apply in the LEF view, the DEF offset in the DEF view.");
+                ui.separator();
+
+                egui::Grid::new("source_transform_grid")
+                    .num_columns(6)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Source");
+                        ui.strong("X");
+                        ui.strong("Y");
+                        ui.strong("Rotation");
+                        ui.strong("Scale");
+                        ui.label("");
+                        ui.end_row();
+
+                        for (idx, (label, transform)) in sources.iter_mut().enumerate() {
+                            ui.label(label.as_str());
+                            ui.add(egui::DragValue::new(&mut transform.offset_x).speed(0.1));
+                            ui.add(egui::DragValue::new(&mut transform.offset_y).speed(0.1));
+                            egui::ComboBox::from_id_salt(("source_rotation", idx))
+                                .selected_text(format!("{}°", transform.rotation))
+                                .show_ui(ui, |ui| {
+                                    for rotation in [0, 90, 180, 270] {
+                                        ui.selectable_value(
+                                            &mut transform.rotation,
+                                            rotation,
+                                            format!("{rotation}°"),
+                                        );
+                                    }
+                                });
+                            ui.add(
+                                egui::DragValue::new(&mut transform.scale)
+                                    .speed(0.01)
+                                    .range(0.01..=100.0),
+                            );
+                            if ui
+                                .add_enabled(!transform.is_identity(), egui::Button::new("Reset"))
+                                .clicked()
+                            {
+                                **transform = SourceTransform::default();
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if !open {
+            self.visible = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_is_skipped() {
+        let transform = SourceTransform::default();
+        assert!(transform.to_screen(egui::pos2(0.0, 0.0), 1.0).is_none());
+    }
+
+    #[test]
+    fn test_offset_uses_world_y_up() {
+        let transform = SourceTransform {
+            offset_x: 2.0,
+            offset_y: 3.0,
+            ..Default::default()
+        };
+        let screen = transform.to_screen(egui::pos2(0.0, 0.0), 10.0).unwrap();
+        assert_eq!(screen.apply(egui::pos2(1.0, 1.0)), egui::pos2(21.0, -29.0));
+    }
+
+    #[test]
+    fn test_rotation_is_counterclockwise_in_world() {
+        let transform = SourceTransform {
+            rotation: 90,
+            ..Default::default()
+        };
+        let pivot = egui::pos2(100.0, 100.0);
+        let screen = transform.to_screen(pivot, 1.0).unwrap();
+        // World +X becomes world +Y, i.e. screen up
+        assert_eq!(
+            screen.apply(egui::pos2(110.0, 100.0)),
+            egui::pos2(100.0, 90.0)
+        );
+    }
+
+    #[test]
+    fn test_invert_round_trip() {
+        for rotation in [90, 180, 270] {
+            let transform = SourceTransform {
+                offset_x: 1.5,
+                offset_y: -2.0,
+                rotation,
+                scale: 2.0,
+            };
+            let screen = transform.to_screen(egui::pos2(50.0, 40.0), 4.0).unwrap();
+            let point = egui::pos2(13.0, 27.0);
+            let back = screen.invert(screen.apply(point));
+            assert!((back - point).length() < 1e-4, "{rotation}");
+        }
+    }
+
+    #[test]
+    fn test_rect_stays_axis_aligned() {
+        let transform = SourceTransform {
+            rotation: 270,
+            ..Default::default()
+        };
+        let screen = transform.to_screen(egui::pos2(0.0, 0.0), 1.0).unwrap();
+        let mut shape = egui::Shape::rect_filled(
+            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(4.0, 2.0)),
+            0.0,
+            egui::Color32::RED,
+        );
+        screen.apply_shape(&mut shape);
+        let egui::Shape::Rect(rect_shape) = shape else {
+            panic!("expected a rect");
+        };
+        assert_eq!(rect_shape.rect.size(), egui::vec2(2.0, 4.0));
+    }
+}
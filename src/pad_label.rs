@@ -0,0 +1,134 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Text orientation of pad cells
+//!
+//! Pads along the die edge are labeled the way a pad ring is read from
+//! outside the die: upright on the bottom edge, reading upwards on the
+//! right edge, upside down on the top edge and reading downwards on the
+//! left edge. The edge is the one closest to the pad. Without a DIEAREA it
+//! follows the placement orientation, with the outer side of an N pad at
+//! the bottom as in most pad libraries.
+
+use crate::geometry::Bounds;
+use std::f32::consts::{FRAC_PI_2, PI};
+
+/// Die edge a pad sits on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DieEdge {
+    Bottom,
+    Right,
+    Top,
+    Left,
+}
+
+impl DieEdge {
+    /// Text rotation in radians on screen, clockwise with Y pointing down
+    pub fn text_angle(self) -> f32 {
+        match self {
+            DieEdge::Bottom => 0.0,
+            DieEdge::Right => -FRAC_PI_2,
+            DieEdge::Top => PI,
+            DieEdge::Left => FRAC_PI_2,
+        }
+    }
+
+    /// Outer side of a pad placed with `orientation`
+    fn from_orientation(orientation: &str) -> Self {
+        match orientation {
+            "S" | "FS" => DieEdge::Top,
+            "W" | "FE" => DieEdge::Right,
+            "E" | "FW" => DieEdge::Left,
+            _ => DieEdge::Bottom,
+        }
+    }
+}
+
+/// Whether a LEF macro CLASS is a pad, e.g. "PAD" or "PAD INOUT"
+pub fn is_pad_class(class: &str) -> bool {
+    class.split_whitespace().next() == Some("PAD")
+}
+
+/// Edge of the pad with bounds `pad` in the die `die`, both in microns
+pub fn pad_edge(pad: Bounds, die: Option<Bounds>, orientation: &str) -> DieEdge {
+    let Some((min_x, min_y, max_x, max_y)) = die.filter(|d| d.2 > d.0 && d.3 > d.1) else {
+        return DieEdge::from_orientation(orientation);
+    };
+    let (cx, cy) = ((pad.0 + pad.2) / 2.0, (pad.1 + pad.3) / 2.0);
+    [
+        (cy - min_y, DieEdge::Bottom),
+        (max_x - cx, DieEdge::Right),
+        (max_y - cy, DieEdge::Top),
+        (cx - min_x, DieEdge::Left),
+    ]
+    .into_iter()
+    .min_by(|a, b| a.0.total_cmp(&b.0))
+    .map_or(DieEdge::Bottom, |(_, edge)| edge)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::orient_point;
+
+    #[test]
+    fn test_pad_class() {
+        assert!(is_pad_class("PAD"));
+        assert!(is_pad_class("PAD INOUT"));
+        assert!(!is_pad_class("CORE"));
+        assert!(!is_pad_class("ENDCAP TOPLEFT"));
+    }
+
+    #[test]
+    fn test_nearest_die_edge() {
+        let die = Some((0.0, 0.0, 1000.0, 800.0));
+        assert_eq!(
+            pad_edge((400.0, 0.0, 460.0, 120.0), die, "N"),
+            DieEdge::Bottom
+        );
+        assert_eq!(
+            pad_edge((880.0, 300.0, 1000.0, 360.0), die, "N"),
+            DieEdge::Right
+        );
+        assert_eq!(
+            pad_edge((400.0, 680.0, 460.0, 800.0), die, "N"),
+            DieEdge::Top
+        );
+        assert_eq!(
+            pad_edge((0.0, 300.0, 120.0, 360.0), die, "N"),
+            DieEdge::Left
+        );
+        // Orientation decides without a die area
+        assert_eq!(pad_edge((0.0, 0.0, 1.0, 1.0), None, "FW"), DieEdge::Left);
+        assert_eq!(pad_edge((0.0, 0.0, 1.0, 1.0), None, "S"), DieEdge::Top);
+    }
+
+    #[test]
+    fn test_orientation_edge_follows_transform() {
+        // Where the middle of the bottom side of a 2 x 3 pad ends up
+        let size = (2.0, 3.0);
+        for orientation in ["N", "S", "E", "W", "FN", "FS", "FE", "FW"] {
+            let (x, y) = orient_point((1.0, 0.0), orientation, size);
+            let (width, height) = if matches!(orientation, "E" | "W" | "FE" | "FW") {
+                (size.1, size.0)
+            } else {
+                size
+            };
+            let expected = if y == 0.0 {
+                DieEdge::Bottom
+            } else if x == width {
+                DieEdge::Right
+            } else if y == height {
+                DieEdge::Top
+            } else {
+                assert_eq!(x, 0.0, "{orientation}");
+                DieEdge::Left
+            };
+            assert_eq!(
+                DieEdge::from_orientation(orientation),
+                expected,
+                "{orientation}"
+            );
+        }
+    }
+}
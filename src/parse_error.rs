@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Error of parsing LEF or DEF text
+//!
+//! [`crate::Lef::parse_str`] and [`crate::Def::parse_str`] parse text held
+//! in memory, for WASM builds and unit tests without files. Like the
+//! readers they skip statements they cannot read; when parsing stops
+//! altogether, the error tells in which line and at which text.
+
+use std::fmt;
+
+/// Characters of the offending text quoted in the message
+const SNIPPET_CHARS: usize = 40;
+
+/// LEF or DEF text that could not be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// "LEF" or "DEF"
+    pub format: &'static str,
+    /// Line the parser stopped in, starting at 1
+    pub line: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    /// Error of a nom parser that stopped in `input`
+    pub(crate) fn from_nom(
+        format: &'static str,
+        input: &str,
+        error: nom::Err<nom::error::Error<&str>>,
+    ) -> Self {
+        let (rest, kind) = match &error {
+            nom::Err::Error(e) | nom::Err::Failure(e) => (e.input, format!("{:?}", e.code)),
+            nom::Err::Incomplete(_) => ("", "Incomplete".to_string()),
+        };
+        // The parsers stop in a suffix of the input
+        let offset = input.len().saturating_sub(rest.len());
+        let line = input.as_bytes()[..offset]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count()
+            + 1;
+        let snippet: String = rest.trim_start().chars().take(SNIPPET_CHARS).collect();
+        let message = match snippet.lines().next() {
+            Some(text) if !text.is_empty() => format!("{kind} at `{text}`"),
+            _ => format!("{kind} at end of input"),
+        };
+        Self {
+            format,
+            line,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} parse error in line {}: {}",
+            self.format, self.line, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_nom_line_and_snippet() {
+        let input = "VERSION 5.8 ;\nMACRO A\n  SIZE x BY 1 ;\n";
+        let rest = &input[input.find("x BY").unwrap()..];
+        let error = nom::Err::Error(nom::error::Error::new(rest, nom::error::ErrorKind::Float));
+        let error = ParseError::from_nom("LEF", input, error);
+        assert_eq!(error.line, 3);
+        assert_eq!(
+            error.to_string(),
+            "LEF parse error in line 3: Float at `x BY 1 ;`"
+        );
+
+        let error = nom::Err::Failure(nom::error::Error::new("", nom::error::ErrorKind::Tag));
+        let error = ParseError::from_nom("DEF", "DESIGN top ;\n", error);
+        assert_eq!(error.line, 2);
+        assert_eq!(error.message, "Tag at end of input");
+    }
+}
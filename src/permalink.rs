@@ -0,0 +1,210 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Shareable view permalinks
+//!
+//! The camera, the layers panel toggles and the selections are packed into
+//! a short text blob: JSON, deflated and base64 encoded behind a `lefdef:`
+//! prefix. Files are referenced by their BLAKE3 hash, so a colleague who has
+//! loaded the same files can paste the blob and land on the exact same view,
+//! whatever the files are called on their machine.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use eframe::egui;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+/// Prefix of every blob, followed by the format version
+const PREFIX: &str = "lefdef:1:";
+
+/// Everything needed to restore a view
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ViewState {
+    /// BLAKE3 hashes of the LEF files that must be loaded
+    pub lef_hashes: Vec<String>,
+    /// BLAKE3 hash of the DEF file, None for a LEF-only view
+    pub def_hash: Option<String>,
+    pub def_mode: bool,
+    pub zoom: f32,
+    pub pan_x: f32,
+    pub pan_y: f32,
+    pub visible_layers: Vec<String>,
+    pub show_components: bool,
+    pub show_pins: bool,
+    pub show_nets: bool,
+    pub show_special_nets: bool,
+    pub show_diearea: bool,
+    pub show_pin_text: bool,
+    pub show_component_text: bool,
+    pub selected_cells: Vec<String>,
+    pub selected_lef_pins: Vec<String>,
+    pub selected_lef_obs: Vec<String>,
+    pub selected_components: Vec<String>,
+    pub selected_pins: Vec<String>,
+    pub selected_nets: Vec<String>,
+}
+
+/// Pack a view into a permalink blob
+pub fn encode(state: &ViewState) -> String {
+    let json = serde_json::to_vec(state).expect("view state serializes to JSON");
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    encoder
+        .write_all(&json)
+        .expect("deflating into memory does not fail");
+    let deflated = encoder
+        .finish()
+        .expect("deflating into memory does not fail");
+    format!("{PREFIX}{}", URL_SAFE_NO_PAD.encode(deflated))
+}
+
+/// Unpack a permalink blob, ignoring surrounding whitespace
+pub fn decode(blob: &str) -> Result<ViewState, String> {
+    let payload = blob
+        .trim()
+        .strip_prefix(PREFIX)
+        .ok_or("not a lefdef-viewer permalink")?;
+    let deflated = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|e| format!("invalid permalink encoding: {e}"))?;
+    let mut json = Vec::new();
+    DeflateDecoder::new(deflated.as_slice())
+        .read_to_end(&mut json)
+        .map_err(|e| format!("corrupted permalink: {e}"))?;
+    serde_json::from_slice(&json).map_err(|e| format!("invalid permalink content: {e}"))
+}
+
+/// Sorted copy of a set, so equal views give equal blobs
+pub fn sorted<'a>(items: impl IntoIterator<Item = &'a String>) -> Vec<String> {
+    let mut items: Vec<String> = items.into_iter().cloned().collect();
+    items.sort();
+    items
+}
+
+/// Permalink dialog state and rendering
+#[derive(Default)]
+pub struct PermalinkDialog {
+    /// Whether the dialog is currently shown
+    pub visible: bool,
+    /// Blob of the view when the dialog was opened
+    current: String,
+    /// Blob pasted by the user
+    pasted: String,
+    error: Option<String>,
+}
+
+impl PermalinkDialog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the dialog showing the blob of the current view
+    pub fn open(&mut self, state: &ViewState) {
+        self.visible = true;
+        self.current = encode(state);
+        self.error = None;
+    }
+
+    /// Show why the last pasted blob could not be restored
+    pub fn set_error(&mut self, error: String) {
+        self.visible = true;
+        self.error = Some(error);
+    }
+
+    /// Render the dialog, returning the view to restore
+    pub fn render(&mut self, ctx: &egui::Context) -> Option<ViewState> {
+        if !self.visible {
+            return None;
+        }
+
+        let mut open = true;
+        let mut restore = None;
+
+        egui::Window::new("Share View")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label("Permalink of the current view:");
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.current.as_str())
+                            .desired_width(320.0)
+                            .font(egui::TextStyle::Monospace),
+                    );
+                    if ui.button("Copy").clicked() {
+                        ui.ctx().copy_text(self.current.clone());
+                    }
+                });
+                ui.label(
+                    egui::RichText::new(format!("{} characters", self.current.len()))
+                        .small()
+                        .weak(),
+                );
+
+                ui.separator();
+                ui.label("Paste a permalink to restore its view:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.pasted)
+                        .hint_text("lefdef:1:...")
+                        .desired_width(f32::INFINITY)
+                        .font(egui::TextStyle::Monospace),
+                );
+                if ui
+                    .add_enabled(!self.pasted.trim().is_empty(), egui::Button::new("Restore"))
+                    .clicked()
+                {
+                    match decode(&self.pasted) {
+                        Ok(state) => {
+                            self.error = None;
+                            restore = Some(state);
+                        }
+                        Err(e) => self.error = Some(e),
+                    }
+                }
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 80, 80), error);
+                }
+            });
+
+        if !open {
+            self.visible = false;
+        }
+
+        restore
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let state = ViewState {
+            lef_hashes: vec!["ab".repeat(32)],
+            def_hash: Some("cd".repeat(32)),
+            def_mode: true,
+            zoom: 12.5,
+            pan_x: -340.25,
+            pan_y: 18.0,
+            visible_layers: vec!["M1.PIN".to_string(), "OUTLINE".to_string()],
+            show_components: true,
+            show_nets: true,
+            selected_nets: vec!["clk".to_string(), r"\data[3]".to_string()],
+            ..ViewState::default()
+        };
+        let blob = encode(&state);
+        assert!(blob.starts_with(PREFIX));
+        assert!(!blob.contains(char::is_whitespace));
+        assert_eq!(decode(&format!("  {blob}\n")).unwrap(), state);
+
+        assert!(decode("lefdef:1:!!!").is_err());
+        assert!(decode(&blob.replace(PREFIX, "other:")).is_err());
+        assert!(decode(&blob[..blob.len() / 2]).is_err());
+    }
+}
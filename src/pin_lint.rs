@@ -0,0 +1,162 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Macro pin naming convention linter
+//!
+//! Each rule binds a pin class, the LEF `USE` of the pin, to a regular
+//! expression the pin name must match in full. Pins without a `USE` are
+//! SIGNAL pins. Every pin of every macro is checked against the rules of its
+//! class and each mismatch is reported as a violation.
+
+use crate::lef::{Lef, LefMacro};
+use regex::Regex;
+
+/// Pin classes a rule can apply to, as written after LEF `USE`
+pub const PIN_CLASSES: [&str; 5] = ["SIGNAL", "CLOCK", "POWER", "GROUND", "ANALOG"];
+
+/// One naming rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintRule {
+    /// Pin class the rule applies to, e.g. "CLOCK"
+    pub class: String,
+    /// Regular expression the whole pin name must match
+    pub pattern: String,
+}
+
+impl LintRule {
+    pub fn new(class: &str, pattern: &str) -> Self {
+        Self {
+            class: class.to_string(),
+            pattern: pattern.to_string(),
+        }
+    }
+}
+
+/// Common library conventions: clocks start with CK, supplies with VDD/VSS
+pub fn default_rules() -> Vec<LintRule> {
+    vec![
+        LintRule::new("CLOCK", "CK.*|CLK.*"),
+        LintRule::new("POWER", "VDD.*"),
+        LintRule::new("GROUND", "VSS.*"),
+    ]
+}
+
+/// A pin whose name breaks a rule of its class
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// LEF file the macro was loaded from
+    pub file: String,
+    pub macro_name: String,
+    pub pin_name: String,
+    pub class: String,
+    /// Pattern of the broken rule
+    pub pattern: String,
+}
+
+/// Compiled rule set
+#[derive(Debug)]
+pub struct PinLinter {
+    rules: Vec<(LintRule, Regex)>,
+}
+
+impl PinLinter {
+    /// Compile `rules`, failing on the first invalid pattern
+    pub fn new(rules: &[LintRule]) -> Result<Self, String> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                Regex::new(&format!("^(?:{})$", rule.pattern))
+                    .map(|regex| (rule.clone(), regex))
+                    .map_err(|e| format!("Invalid pattern for {}: {e}", rule.class))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Check the pins of one macro
+    pub fn check_macro(&self, file: &str, macro_def: &LefMacro) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        for pin in &macro_def.pins {
+            let class = pin_class(&pin.use_type);
+            for (rule, regex) in &self.rules {
+                if rule.class.eq_ignore_ascii_case(class) && !regex.is_match(&pin.name) {
+                    violations.push(Violation {
+                        file: file.to_string(),
+                        macro_name: macro_def.name.clone(),
+                        pin_name: pin.name.clone(),
+                        class: class.to_string(),
+                        pattern: rule.pattern.clone(),
+                    });
+                }
+            }
+        }
+        violations
+    }
+
+    /// Check every macro of the given (file, LEF) pairs
+    pub fn check<'a>(
+        &self,
+        libraries: impl IntoIterator<Item = (&'a str, &'a Lef)>,
+    ) -> Vec<Violation> {
+        libraries
+            .into_iter()
+            .flat_map(|(file, lef)| {
+                lef.macros
+                    .iter()
+                    .flat_map(move |macro_def| self.check_macro(file, macro_def))
+            })
+            .collect()
+    }
+}
+
+/// Class of a pin from its LEF USE
+fn pin_class(use_type: &str) -> &str {
+    if use_type.is_empty() {
+        "SIGNAL"
+    } else {
+        use_type
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lef::LefPin;
+    use crate::test_fixtures;
+
+    fn pin(name: &str, use_type: &str) -> LefPin {
+        test_fixtures::pin(name, "INPUT", use_type)
+    }
+
+    fn cell(pins: Vec<LefPin>) -> LefMacro {
+        test_fixtures::cell("DFF_X1", 1.0, 1.0, pins)
+    }
+
+    #[test]
+    fn test_default_rules() {
+        let linter = PinLinter::new(&default_rules()).unwrap();
+        let macro_def = cell(vec![
+            pin("CK", "CLOCK"),
+            pin("GCLK", "CLOCK"),
+            pin("VDD", "POWER"),
+            pin("VSS", "GROUND"),
+            pin("GND", "GROUND"),
+            pin("D", ""),
+        ]);
+        let violations = linter.check_macro("lib.lef", &macro_def);
+        let names: Vec<&str> = violations.iter().map(|v| v.pin_name.as_str()).collect();
+        assert_eq!(names, vec!["GCLK", "GND"]);
+        assert_eq!(violations[1].class, "GROUND");
+        assert_eq!(violations[1].pattern, "VSS.*");
+    }
+
+    #[test]
+    fn test_patterns_match_whole_name() {
+        let linter = PinLinter::new(&[LintRule::new("SIGNAL", "[A-Z]+[0-9]*")]).unwrap();
+        let macro_def = cell(vec![pin("A1", "SIGNAL"), pin("a1", ""), pin("A_1", "")]);
+        let violations = linter.check_macro("lib.lef", &macro_def);
+        assert_eq!(violations.len(), 2);
+
+        assert!(PinLinter::new(&[LintRule::new("CLOCK", "CK(")]).is_err());
+    }
+}
@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Pin Naming Linter dialog
+//!
+//! Edits the naming rules, runs them over all loaded LEF files and lists the
+//! violations, which can be exported as CSV.
+
+use crate::lef::Lef;
+use crate::pin_lint::{self, LintRule, PinLinter, Violation, PIN_CLASSES};
+use eframe::egui;
+
+/// Violations listed before the rest is summarized
+const MAX_LISTED: usize = 1000;
+
+/// Pin naming linter state and rendering
+pub struct PinLintDialog {
+    /// Whether the dialog is currently shown
+    pub visible: bool,
+    rules: Vec<LintRule>,
+    /// Result of the last run, None before the first run
+    violations: Option<Vec<Violation>>,
+    error: Option<String>,
+}
+
+impl Default for PinLintDialog {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            rules: pin_lint::default_rules(),
+            violations: None,
+            error: None,
+        }
+    }
+}
+
+impl PinLintDialog {
+    /// Create a new linter dialog with the default rules
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Violations of the last run
+    pub fn violations(&self) -> &[Violation] {
+        self.violations.as_deref().unwrap_or_default()
+    }
+
+    /// Run the rules over `libraries`, given as (file path, LEF) pairs
+    pub fn run(&mut self, libraries: &[(&str, &Lef)]) {
+        match PinLinter::new(&self.rules) {
+            Ok(linter) => {
+                self.violations = Some(linter.check(libraries.iter().copied()));
+                self.error = None;
+            }
+            Err(e) => {
+                self.violations = None;
+                self.error = Some(e);
+            }
+        }
+    }
+
+    /// Forget the last result, e.g. when the loaded LEF files change
+    pub fn clear(&mut self) {
+        self.violations = None;
+    }
+
+    /// Render the dialog, `export_requested` is set when Export CSV is clicked
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        libraries: &[(&str, &Lef)],
+        export_requested: &mut bool,
+    ) {
+        if !self.visible {
+            return;
+        }
+
+        let mut open = true;
+        egui::Window::new("Pin Naming Linter")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(520.0)
+            .default_height(450.0)
+            .show(ctx, |ui| {
+                ui.label("Pin names must fully match the rules of their class (LEF USE):");
+                let mut removed = None;
+                egui::Grid::new("pin_lint_rules")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (i, rule) in self.rules.iter_mut().enumerate() {
+                            egui::ComboBox::from_id_salt(("pin_lint_class", i))
+                                .selected_text(rule.class.as_str())
+                                .show_ui(ui, |ui| {
+                                    for class in PIN_CLASSES {
+                                        ui.selectable_value(
+                                            &mut rule.class,
+                                            class.to_string(),
+                                            class,
+                                        );
+                                    }
+                                });
+                            ui.add(
+                                egui::TextEdit::singleline(&mut rule.pattern)
+                                    .desired_width(240.0)
+                                    .font(egui::TextStyle::Monospace),
+                            );
+                            if ui.small_button("🗑").on_hover_text("Remove rule").clicked() {
+                                removed = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if let Some(i) = removed {
+                    self.rules.remove(i);
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.button("Add Rule").clicked() {
+                        self.rules.push(LintRule::new("SIGNAL", ".*"));
+                    }
+                    if ui.button("Reset to Defaults").clicked() {
+                        self.rules = pin_lint::default_rules();
+                    }
+                    ui.separator();
+                    if ui
+                        .add_enabled(!libraries.is_empty(), egui::Button::new("Run"))
+                        .clicked()
+                    {
+                        self.run(libraries);
+                    }
+                    if ui
+                        .add_enabled(
+                            !self.violations().is_empty(),
+                            egui::Button::new("Export CSV"),
+                        )
+                        .clicked()
+                    {
+                        *export_requested = true;
+                    }
+                });
+
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::from_rgb(244, 67, 54), error);
+                }
+                if libraries.is_empty() {
+                    ui.label("Load LEF files to lint their pin names.");
+                }
+                ui.separator();
+
+                let Some(violations) = &self.violations else {
+                    return;
+                };
+                if violations.is_empty() {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(76, 175, 80),
+                        "All pin names follow the rules.",
+                    );
+                    return;
+                }
+                ui.label(format!("{} violation(s)", violations.len()));
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("pin_lint_violations")
+                        .num_columns(4)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Macro");
+                            ui.strong("Pin");
+                            ui.strong("Class");
+                            ui.strong("Rule");
+                            ui.end_row();
+                            for violation in violations.iter().take(MAX_LISTED) {
+                                ui.label(&violation.macro_name)
+                                    .on_hover_text(&violation.file);
+                                ui.label(&violation.pin_name);
+                                ui.label(&violation.class);
+                                ui.monospace(&violation.pattern);
+                                ui.end_row();
+                            }
+                        });
+                    if violations.len() > MAX_LISTED {
+                        ui.label(format!(
+                            "... and {} more, export CSV for the full list",
+                            violations.len() - MAX_LISTED
+                        ));
+                    }
+                });
+            });
+
+        if !open {
+            self.visible = false;
+        }
+    }
+}
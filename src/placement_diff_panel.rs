@@ -0,0 +1,227 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Placement Diff Panel
+//!
+//! Compares the loaded DEF against a reference DEF, e.g. the previous
+//! placement run, see [`crate::def::placement_diff`]. The panel summarizes
+//! the displacement per macro class and lists the moved, added and removed
+//! components; clicking one selects and inspects it. The canvas draws the
+//! displacement vectors while the panel is open.
+
+use crate::def::placement_diff::{self, ClassDisplacement, PlacementDiff};
+use crate::def::reader::DefReader;
+use crate::def::Def;
+use crate::units::UnitsCheck;
+use eframe::egui;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Rows shown per list before the rest is summarized
+const MAX_LISTED: usize = 500;
+
+/// Placement diff panel state and rendering
+#[derive(Default)]
+pub struct PlacementDiffPanel {
+    /// Whether the panel is currently shown
+    pub visible: bool,
+    /// Whether the canvas draws displacement vectors and removed components
+    pub show_vectors: bool,
+    reference_path: Option<String>,
+    reference: Option<Def>,
+    reference_db_units: f64,
+    diff: Option<PlacementDiff>,
+    summary: Vec<ClassDisplacement>,
+}
+
+impl PlacementDiffPanel {
+    /// Create a new placement diff panel
+    pub fn new() -> Self {
+        Self {
+            show_vectors: true,
+            ..Self::default()
+        }
+    }
+
+    /// Load the reference DEF, taking its database units from the DEF or
+    /// else `lef_units`
+    pub fn load_reference(&mut self, path: &str, lef_units: Option<f64>) -> Result<(), String> {
+        let reference = DefReader::new()
+            .read(path)
+            .map_err(|e| format!("Failed to load reference DEF {path}: {e}"))?;
+        self.reference_db_units =
+            UnitsCheck::new(reference.units_distance_microns, lef_units).db_units();
+        self.reference = Some(reference);
+        self.reference_path = Some(path.to_string());
+        self.visible = true;
+        Ok(())
+    }
+
+    /// Compare the reference against `current` again, e.g. after a new DEF
+    /// was loaded or components were moved
+    pub fn refresh<'a>(
+        &mut self,
+        current: Option<&Def>,
+        db_units: f64,
+        class_of: impl Fn(&str) -> Option<&'a str>,
+    ) {
+        self.diff = match (&self.reference, current) {
+            (Some(reference), Some(current)) => Some(placement_diff::diff_placements(
+                reference,
+                self.reference_db_units,
+                current,
+                db_units,
+            )),
+            _ => None,
+        };
+        self.summary = self
+            .diff
+            .as_ref()
+            .map(|diff| diff.class_summary(class_of))
+            .unwrap_or_default();
+    }
+
+    /// Diff to draw on the canvas, while the panel is shown
+    pub fn shown_diff(&self) -> Option<&PlacementDiff> {
+        self.diff
+            .as_ref()
+            .filter(|_| self.visible && self.show_vectors)
+    }
+
+    /// Render the panel
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        selected_components: &mut HashSet<String>,
+        inspected_component: &mut Option<String>,
+    ) {
+        if !self.visible {
+            return;
+        }
+
+        let mut open = true;
+        let mut close_reference = false;
+        egui::Window::new("Placement Diff")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(440.0)
+            .default_height(450.0)
+            .show(ctx, |ui| {
+                let Some(path) = &self.reference_path else {
+                    ui.label("Load a reference DEF from the File menu to compare placements.");
+                    return;
+                };
+                let name = Path::new(path)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or(path);
+                ui.horizontal(|ui| {
+                    ui.label(format!("Reference: {name}"));
+                    close_reference = ui.small_button("Close").clicked();
+                });
+                ui.checkbox(&mut self.show_vectors, "Show displacement vectors")
+                    .on_hover_text(
+                        "Arrows from the reference location, crosses where removed components were",
+                    );
+
+                let Some(diff) = &self.diff else {
+                    ui.label("Load a DEF file to compare it against the reference.");
+                    return;
+                };
+                ui.label(format!(
+                    "{} moved, {} added, {} removed, {} unchanged components",
+                    diff.moved.len(),
+                    diff.added.len(),
+                    diff.removed.len(),
+                    diff.unchanged
+                ));
+                ui.separator();
+
+                if !self.summary.is_empty() {
+                    egui::Grid::new("placement_diff_summary")
+                        .num_columns(4)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Class");
+                            ui.strong("Moved");
+                            ui.strong("Max (um)");
+                            ui.strong("Avg (um)");
+                            ui.end_row();
+                            for row in &self.summary {
+                                ui.label(&row.class);
+                                ui.label(row.moved.to_string());
+                                ui.monospace(format!("{:.3}", row.max));
+                                ui.monospace(format!("{:.3}", row.avg));
+                                ui.end_row();
+                            }
+                        });
+                    ui.separator();
+                }
+
+                let mut select = |name: &str| {
+                    selected_components.insert(name.to_string());
+                    *inspected_component = Some(name.to_string());
+                };
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        egui::CollapsingHeader::new(format!("Moved ({})", diff.moved.len()))
+                            .id_salt("placement_diff_moved")
+                            .show(ui, |ui| {
+                                for moved in diff.moved.iter().take(MAX_LISTED) {
+                                    let mut text = format!(
+                                        "{} ({}): {:.3} um",
+                                        moved.name,
+                                        moved.macro_name,
+                                        moved.displacement()
+                                    );
+                                    if moved.from_orientation != moved.to_orientation {
+                                        text.push_str(&format!(
+                                            ", {} -> {}",
+                                            moved.from_orientation, moved.to_orientation
+                                        ));
+                                    }
+                                    if ui.link(text).clicked() {
+                                        select(&moved.name);
+                                    }
+                                }
+                                more_label(ui, diff.moved.len());
+                            });
+                        egui::CollapsingHeader::new(format!("Added ({})", diff.added.len()))
+                            .id_salt("placement_diff_added")
+                            .show(ui, |ui| {
+                                for name in diff.added.iter().take(MAX_LISTED) {
+                                    if ui.link(name).clicked() {
+                                        select(name);
+                                    }
+                                }
+                                more_label(ui, diff.added.len());
+                            });
+                        egui::CollapsingHeader::new(format!("Removed ({})", diff.removed.len()))
+                            .id_salt("placement_diff_removed")
+                            .show(ui, |ui| {
+                                for removed in diff.removed.iter().take(MAX_LISTED) {
+                                    ui.label(format!("{} ({})", removed.name, removed.macro_name));
+                                }
+                                more_label(ui, diff.removed.len());
+                            });
+                    });
+            });
+
+        if close_reference {
+            self.reference = None;
+            self.reference_path = None;
+            self.diff = None;
+            self.summary.clear();
+        }
+        if !open {
+            self.visible = false;
+        }
+    }
+}
+
+fn more_label(ui: &mut egui::Ui, total: usize) {
+    if total > MAX_LISTED {
+        ui.label(format!("... and {} more", total - MAX_LISTED));
+    }
+}
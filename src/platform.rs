@@ -0,0 +1,134 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Platform layer of the background work and the file dialogs
+//!
+//! Parsing, file dialogs, tessellation and the precompute pass run off the
+//! UI thread and report back over channels. They are started through
+//! [`spawn`] rather than `std::thread` directly, so that a build without
+//! threads only has to change this module. Native builds run every job on
+//! a named thread of its own.
+//!
+//! wasm32-unknown-unknown has no threads: there a job runs to completion
+//! inside [`spawn`] and its messages are waiting in the channel at the next
+//! frame, with the UI blocked meanwhile. Workers that wait for more jobs
+//! are not started there, see [`THREADS`]. Browsers have no blocking file
+//! dialogs either: [`pick_files`] reads the picked files into memory, and
+//! the [`FileDialog`] of the web build cancels every dialog, so the exports
+//! that save to a path do nothing there.
+
+use std::io;
+
+/// Whether jobs started with [`spawn`] run next to the UI
+///
+/// Without threads a job runs to completion before [`spawn`] returns, so
+/// a worker that loops waiting for jobs would never return.
+pub const THREADS: bool = cfg!(not(target_arch = "wasm32"));
+
+/// Run `job` in the background, on a thread named `name`
+///
+/// Fails when the operating system cannot create the thread; `job` is
+/// dropped then, together with the senders it holds.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn spawn(name: &str, job: impl FnOnce() + Send + 'static) -> io::Result<()> {
+    std::thread::Builder::new()
+        .name(name.to_string())
+        .spawn(job)
+        .map(|_| ())
+}
+
+/// Run `job` to completion, the web has no threads to run it on
+#[cfg(target_arch = "wasm32")]
+pub fn spawn(_name: &str, job: impl FnOnce() + Send + 'static) -> io::Result<()> {
+    job();
+    Ok(())
+}
+
+/// Let other work run, between the items of a long background job
+pub fn yield_now() {
+    #[cfg(not(target_arch = "wasm32"))]
+    std::thread::yield_now();
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use rfd::FileDialog;
+
+#[cfg(target_arch = "wasm32")]
+pub use web::{pick_files, FileDialog};
+
+#[cfg(target_arch = "wasm32")]
+mod web {
+    use std::path::PathBuf;
+
+    /// Let the user pick files in the browser and hand their names and
+    /// contents to `done`, nothing when the dialog is cancelled
+    pub fn pick_files(
+        filter_name: &str,
+        extensions: &[&str],
+        done: impl FnOnce(Vec<(String, Vec<u8>)>) + 'static,
+    ) {
+        let dialog = rfd::AsyncFileDialog::new().add_filter(filter_name, extensions);
+        wasm_bindgen_futures::spawn_local(async move {
+            let mut files = Vec::new();
+            for handle in dialog.pick_files().await.unwrap_or_default() {
+                files.push((handle.file_name(), handle.read().await));
+            }
+            done(files);
+        });
+    }
+
+    /// The blocking dialogs of rfd as the browser has them: none at all
+    ///
+    /// Takes the same builder calls as `rfd::FileDialog` and returns
+    /// `None` for every dialog, as if it was cancelled.
+    #[derive(Debug, Default)]
+    pub struct FileDialog;
+
+    impl FileDialog {
+        pub fn new() -> Self {
+            Self
+        }
+
+        pub fn add_filter(self, _name: impl Into<String>, _extensions: &[impl ToString]) -> Self {
+            self
+        }
+
+        pub fn set_file_name(self, _file_name: impl Into<String>) -> Self {
+            self
+        }
+
+        pub fn pick_file(self) -> Option<PathBuf> {
+            unavailable()
+        }
+
+        pub fn pick_folder(self) -> Option<PathBuf> {
+            unavailable()
+        }
+
+        pub fn save_file(self) -> Option<PathBuf> {
+            unavailable()
+        }
+    }
+
+    fn unavailable<T>() -> Option<T> {
+        log::warn!("File dialogs by path are not available in the browser");
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_spawn_reports_over_channel() {
+        let (sender, receiver) = mpsc::channel();
+        spawn("test", move || {
+            let name = std::thread::current().name().map(str::to_string);
+            sender.send(name).unwrap();
+        })
+        .unwrap();
+        assert_eq!(receiver.recv().unwrap().as_deref(), Some("test"));
+    }
+}
@@ -0,0 +1,371 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! PNG export of rendered views
+//!
+//! Images are rendered off-screen, independent of the window: the design
+//! is painted again at the resolution of the image, and the shapes are
+//! tessellated by egui and filled into an image on the CPU, off the UI
+//! thread.
+
+use eframe::egui;
+use egui::epaint::{ClippedPrimitive, ClippedShape, FontImage, Primitive, Shape, Tessellator};
+use std::path::Path;
+
+/// Largest side of an off-screen render, in pixels
+pub const MAX_RENDER_SIZE: u32 = 8192;
+
+/// Shapes painted off-screen for an export, with what it takes to
+/// tessellate them away from the [`egui::Context`]
+pub struct OffscreenScene {
+    shapes: Vec<ClippedShape>,
+    tessellator: Tessellator,
+    font: FontImage,
+    pixels_per_point: f32,
+    size: [u32; 2],
+}
+
+impl OffscreenScene {
+    /// Take the shapes painted on `layer`, which is not shown on screen
+    ///
+    /// The painter covered `size` pixels from the origin, in points of the
+    /// current pixels per point.
+    pub fn capture(ctx: &egui::Context, layer: egui::LayerId, size: [u32; 2]) -> Self {
+        let paint_list = ctx.graphics_mut(|graphics| std::mem::take(graphics.entry(layer)));
+        let pixels_per_point = ctx.pixels_per_point();
+        let options = ctx.options(|options| options.tessellation_options);
+        let (font, font_size, prepared_discs) = ctx.fonts(|fonts| {
+            let atlas = fonts.texture_atlas();
+            let atlas = atlas.lock();
+            (atlas.image().clone(), atlas.size(), atlas.prepared_discs())
+        });
+        Self {
+            shapes: paint_list.all_entries().cloned().collect(),
+            tessellator: Tessellator::new(pixels_per_point, options, font_size, prepared_discs),
+            font,
+            pixels_per_point,
+            size,
+        }
+    }
+
+    /// Output size in pixels
+    pub fn size(&self) -> [u32; 2] {
+        self.size
+    }
+
+    /// Region of the shapes, in points
+    pub fn region(&self) -> egui::Rect {
+        offscreen_region(self.size, self.pixels_per_point)
+    }
+
+    pub fn shapes(&self) -> impl Iterator<Item = &Shape> {
+        self.shapes.iter().map(|clipped| &clipped.shape)
+    }
+
+    /// Tessellate the shapes and fill them into an image over `background`
+    pub fn render(mut self, background: egui::Color32) -> image::RgbaImage {
+        let primitives = self.tessellator.tessellate_shapes(self.shapes);
+        rasterize(
+            &primitives,
+            &self.font,
+            self.size,
+            self.pixels_per_point,
+            background,
+        )
+    }
+}
+
+/// Region in points of an off-screen render of `size` pixels
+pub fn offscreen_region(size: [u32; 2], pixels_per_point: f32) -> egui::Rect {
+    let [width, height] = size;
+    egui::Rect::from_min_size(
+        egui::Pos2::ZERO,
+        egui::vec2(width as f32, height as f32) / pixels_per_point,
+    )
+}
+
+/// File name of the image of a cell, with the characters that cannot be
+/// in a file name replaced by `_`
+///
+/// Names already in `taken` get a numbered suffix, so cells whose names
+/// only differ in such characters do not overwrite each other.
+pub fn cell_file_name(
+    cell: &str,
+    extension: &str,
+    taken: &mut std::collections::HashSet<String>,
+) -> String {
+    let mut stem: String = cell
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    if stem.is_empty() || stem.chars().all(|c| c == '.') {
+        stem = stem.replace('.', "_") + "_";
+    }
+    let mut name = format!("{stem}.{extension}");
+    let mut suffix = 2;
+    while !taken.insert(name.to_lowercase()) {
+        name = format!("{stem}_{suffix}.{extension}");
+        suffix += 1;
+    }
+    name
+}
+
+/// Linear blend weight of a byte
+fn unit(c: u8) -> f32 {
+    c as f32 / 255.0
+}
+
+/// Bilinear sample of the font coverage at normalized texture coordinates
+fn sample_coverage(font: &FontImage, uv: egui::Pos2) -> f32 {
+    let [width, height] = font.size;
+    if width == 0 || height == 0 {
+        return 1.0;
+    }
+    let x = (uv.x * width as f32 - 0.5).clamp(0.0, (width - 1) as f32);
+    let y = (uv.y * height as f32 - 0.5).clamp(0.0, (height - 1) as f32);
+    let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+    let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+    let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+    let at = |x: usize, y: usize| font.pixels[y * width + x];
+    let top = at(x0, y0) * (1.0 - fx) + at(x1, y0) * fx;
+    let bottom = at(x0, y1) * (1.0 - fx) + at(x1, y1) * fx;
+    top * (1.0 - fy) + bottom * fy
+}
+
+/// Fill tessellated meshes into an image
+///
+/// Vertex positions are in points and scaled by `pixels_per_point`. Colors
+/// blend premultiplied in gamma space into 8 bits per channel, like egui's
+/// own renderers into the frame buffer.
+pub fn rasterize(
+    primitives: &[ClippedPrimitive],
+    font: &FontImage,
+    size: [u32; 2],
+    pixels_per_point: f32,
+    background: egui::Color32,
+) -> image::RgbaImage {
+    let [width, height] = size;
+    // Premultiplied while drawing, unmultiplied at the end
+    let mut image = image::RgbaImage::from_pixel(width, height, image::Rgba(background.to_array()));
+    let font_texture = egui::TextureId::default();
+
+    for clipped in primitives {
+        let Primitive::Mesh(mesh) = &clipped.primitive else {
+            continue;
+        };
+        let clip = egui::Rect::from_min_max(
+            (clipped.clip_rect.min.to_vec2() * pixels_per_point).to_pos2(),
+            (clipped.clip_rect.max.to_vec2() * pixels_per_point).to_pos2(),
+        )
+        .intersect(egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            egui::vec2(width as f32, height as f32),
+        ));
+        if !clip.is_positive() {
+            continue;
+        }
+        // Only the font atlas is available here, so image textures such as
+        // the background underlay are left out
+        if mesh.texture_id != font_texture {
+            continue;
+        }
+
+        for triangle in mesh.indices.chunks_exact(3) {
+            let [Some(a), Some(b), Some(c)] =
+                [0, 1, 2].map(|k| mesh.vertices.get(triangle[k] as usize))
+            else {
+                continue;
+            };
+            let mut v = [a, b, c];
+            let mut p = v.map(|v| (v.pos.to_vec2() * pixels_per_point).to_pos2());
+            let area = (p[1] - p[0]).x * (p[2] - p[0]).y - (p[1] - p[0]).y * (p[2] - p[0]).x;
+            if area.abs() < f32::EPSILON {
+                continue;
+            }
+            if area < 0.0 {
+                p.swap(1, 2);
+                v.swap(1, 2);
+            }
+            let area = area.abs();
+            let edge = |a: egui::Pos2, b: egui::Pos2, x: f32, y: f32| {
+                (b.x - a.x) * (y - a.y) - (b.y - a.y) * (x - a.x)
+            };
+            // Top-left rule, so shared edges of translucent fills blend once
+            let owns = |a: egui::Pos2, b: egui::Pos2| a.y == b.y && b.x < a.x || b.y < a.y;
+            let edges = [(p[1], p[2]), (p[2], p[0]), (p[0], p[1])];
+            let colors = v.map(|vertex| vertex.color.to_array().map(unit));
+
+            let (min_x, max_x) = (
+                p[0].x.min(p[1].x).min(p[2].x),
+                p[0].x.max(p[1].x).max(p[2].x),
+            );
+            let (min_y, max_y) = (
+                p[0].y.min(p[1].y).min(p[2].y),
+                p[0].y.max(p[1].y).max(p[2].y),
+            );
+            let x_range =
+                (min_x.max(clip.min.x).floor() as u32)..(max_x.min(clip.max.x).ceil() as u32);
+            let y_range =
+                (min_y.max(clip.min.y).floor() as u32)..(max_y.min(clip.max.y).ceil() as u32);
+
+            for y in y_range {
+                let py = y as f32 + 0.5;
+                for x in x_range.clone() {
+                    let px = x as f32 + 0.5;
+                    let mut weights = [0.0; 3];
+                    let inside = edges.iter().enumerate().all(|(i, &(a, b))| {
+                        let w = edge(a, b, px, py);
+                        weights[i] = w / area;
+                        w > 0.0 || w == 0.0 && owns(a, b)
+                    });
+                    if !inside {
+                        continue;
+                    }
+
+                    let uv = egui::pos2(
+                        v[0].uv.x * weights[0] + v[1].uv.x * weights[1] + v[2].uv.x * weights[2],
+                        v[0].uv.y * weights[0] + v[1].uv.y * weights[1] + v[2].uv.y * weights[2],
+                    );
+                    let coverage = sample_coverage(font, uv);
+                    let src: [f32; 4] = std::array::from_fn(|i| {
+                        (colors[0][i] * weights[0]
+                            + colors[1][i] * weights[1]
+                            + colors[2][i] * weights[2])
+                            * coverage
+                    });
+
+                    let dst = image.get_pixel_mut(x, y);
+                    let keep = 1.0 - src[3];
+                    for (d, s) in dst.0.iter_mut().zip(src) {
+                        *d = ((s + unit(*d) * keep) * 255.0).round().clamp(0.0, 255.0) as u8;
+                    }
+                }
+            }
+        }
+    }
+
+    for pixel in image.pixels_mut() {
+        let [r, g, b, a] = pixel.0;
+        *pixel =
+            image::Rgba(egui::Color32::from_rgba_premultiplied(r, g, b, a).to_srgba_unmultiplied());
+    }
+    image
+}
+
+/// Size of an off-screen render of `region` at `scale` pixels per point
+pub fn render_size(region: egui::Rect, scale: f32) -> [u32; 2] {
+    let fit = |side: f32| (side * scale).round().clamp(1.0, MAX_RENDER_SIZE as f32) as u32;
+    let [width, height] = [fit(region.width()), fit(region.height())];
+    // Keep the aspect ratio when one side hits the limit
+    let aspect = region.width() / region.height().max(f32::EPSILON);
+    if width as f32 / height as f32 > aspect * 1.01 {
+        [((height as f32 * aspect).round() as u32).max(1), height]
+    } else if (width as f32 / height as f32) < aspect / 1.01 {
+        [width, ((width as f32 / aspect).round() as u32).max(1)]
+    } else {
+        [width, height]
+    }
+}
+
+/// Save an off-screen render as PNG
+pub fn save_rgba_png(
+    image: &image::RgbaImage,
+    path: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    image.save_with_format(path, image::ImageFormat::Png)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rasterize_fills_meshes_once() {
+        // A translucent 2x2 point square at 2 pixels per point, as two triangles
+        let color = egui::Color32::from_rgba_unmultiplied(255, 0, 0, 128);
+        let mut mesh = egui::Mesh::default();
+        mesh.add_colored_rect(
+            egui::Rect::from_min_max(egui::pos2(1.0, 1.0), egui::pos2(3.0, 3.0)),
+            color,
+        );
+        let primitives = [ClippedPrimitive {
+            clip_rect: egui::Rect::EVERYTHING,
+            primitive: Primitive::Mesh(mesh),
+        }];
+        // Untextured shapes sample the white texel of the font atlas
+        let font = FontImage {
+            size: [1, 1],
+            pixels: vec![1.0],
+        };
+        let image = rasterize(&primitives, &font, [8, 8], 2.0, egui::Color32::BLACK);
+
+        assert_eq!(image.dimensions(), (8, 8));
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 255]);
+        // Over black the result is the premultiplied color
+        assert_eq!(image.get_pixel(3, 3).0, [color.r(), 0, 0, 255]);
+        // The diagonal shared by both triangles is not blended twice
+        assert_eq!(image.get_pixel(4, 4).0, image.get_pixel(3, 3).0);
+        assert_eq!(image.get_pixel(5, 2).0, image.get_pixel(3, 3).0);
+        assert_eq!(image.get_pixel(6, 6).0, [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_offscreen_scene_renders_captured_layer() {
+        let ctx = egui::Context::default();
+        let layer = egui::LayerId::new(egui::Order::Background, egui::Id::new("test_export"));
+        let mut scene = None;
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            let region = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(4.0, 4.0));
+            let painter = egui::Painter::new(ctx.clone(), layer, region);
+            painter.rect_filled(
+                egui::Rect::from_min_max(egui::pos2(2.0, 0.0), egui::pos2(4.0, 4.0)),
+                0.0,
+                egui::Color32::RED,
+            );
+            scene = Some(OffscreenScene::capture(ctx, layer, [4, 4]));
+        });
+        let scene = scene.unwrap();
+        assert_eq!(scene.shapes().count(), 1);
+
+        let image = scene.render(egui::Color32::BLACK);
+        assert_eq!(image.dimensions(), (4, 4));
+        assert_eq!(image.get_pixel(0, 2).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(3, 2).0, [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_cell_file_name_sanitized() {
+        let mut taken = std::collections::HashSet::new();
+        assert_eq!(cell_file_name("INV_X1", "png", &mut taken), "INV_X1.png");
+        assert_eq!(
+            cell_file_name("../lib/a:b\tc", "png", &mut taken),
+            ".._lib_a_b_c.png"
+        );
+        assert_eq!(cell_file_name("..", "png", &mut taken), "___.png");
+        assert_eq!(cell_file_name("", "png", &mut taken), "_.png");
+    }
+
+    #[test]
+    fn test_cell_file_name_unique() {
+        let mut taken = std::collections::HashSet::new();
+        assert_eq!(cell_file_name("A/B", "png", &mut taken), "A_B.png");
+        assert_eq!(cell_file_name("A_B", "png", &mut taken), "A_B_2.png");
+        // Case-insensitive file systems would merge these
+        assert_eq!(cell_file_name("a_b", "png", &mut taken), "a_b_3.png");
+    }
+
+    #[test]
+    fn test_render_size_keeps_aspect() {
+        let region = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(400.0, 100.0));
+        assert_eq!(render_size(region, 2.0), [800, 200]);
+        assert_eq!(
+            render_size(region, 100.0),
+            [MAX_RENDER_SIZE, MAX_RENDER_SIZE / 4]
+        );
+    }
+}
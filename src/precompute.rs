@@ -0,0 +1,401 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Background precomputation after a design loads
+//!
+//! Once the LEF and DEF files are loaded, a low-priority worker thread
+//! computes what the viewer would otherwise build on first use: the
+//! instance count per macro, the nets of each component, the spatial index
+//! of the DEF and the thumbnails of the preview cards. The UI stays
+//! interactive meanwhile, and until a result arrives the features that
+//! need it fall back: the spatial index is built on the spot once the pass
+//! is gone, the preview card paints the macro live and leaves out what is
+//! still being counted.
+//!
+//! A pass works on a snapshot of the design. Loading or editing
+//! invalidates it, which stops the worker and drops its late results.
+
+use crate::def::Def;
+use crate::hit_test::DesignIndex;
+use crate::lef::LefMacro;
+use crate::macro_thumbnail;
+use crate::platform;
+use crate::status_bar::BackgroundTask;
+use eframe::egui;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+
+/// Thumbnails kept as textures, the cards of further macros paint live
+const MAX_THUMBNAILS: usize = 1024;
+/// Work items between yields to the other threads
+const ITEMS_PER_YIELD: usize = 256;
+/// Results taken per frame, so uploading thumbnails does not stall a frame
+const UPDATES_PER_FRAME: usize = 64;
+
+/// Design snapshot a pass works on
+pub struct PrecomputeInput {
+    pub def: Option<Def>,
+    /// One definition per macro name, the first loaded one
+    pub macros: Vec<LefMacro>,
+    pub db_units: f64,
+    /// Colors of the layer keys such as "M1.PIN" for the thumbnails
+    pub layer_colors: HashMap<String, egui::Color32>,
+}
+
+/// Stages of a pass, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    InstanceCounts,
+    Connectivity,
+    SpatialIndex,
+    Thumbnails,
+}
+
+impl Stage {
+    const COUNT: usize = 4;
+
+    fn label(self) -> &'static str {
+        match self {
+            Stage::InstanceCounts => "instance counts",
+            Stage::Connectivity => "connectivity",
+            Stage::SpatialIndex => "spatial index",
+            Stage::Thumbnails => "thumbnails",
+        }
+    }
+}
+
+enum Update {
+    Progress(Stage, usize, usize),
+    InstanceCounts(HashMap<String, usize>),
+    Connectivity(HashMap<String, Vec<usize>>),
+    DesignIndex(DesignIndex),
+    Thumbnail(String, egui::ColorImage),
+}
+
+/// Results of the background pass, and the pass that is running
+#[derive(Default)]
+pub struct Precompute {
+    /// Generation of the current pass, a worker of an older one stops
+    generation: Arc<AtomicU64>,
+    receiver: Option<mpsc::Receiver<(u64, Update)>>,
+    /// Whether the results are stale and a new pass should start
+    requested: bool,
+    with_def: bool,
+    progress: Option<(Stage, usize, usize)>,
+    instance_counts: Option<HashMap<String, usize>>,
+    connectivity: Option<HashMap<String, Vec<usize>>>,
+    thumbnails: HashMap<String, egui::TextureHandle>,
+}
+
+impl Precompute {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop the results and stop the running pass, a new one is requested
+    pub fn invalidate(&mut self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        self.receiver = None;
+        self.requested = true;
+        self.with_def = false;
+        self.progress = None;
+        self.instance_counts = None;
+        self.connectivity = None;
+        self.thumbnails.clear();
+    }
+
+    /// Whether a pass should be started
+    pub fn is_requested(&self) -> bool {
+        self.requested
+    }
+
+    /// Whether a pass is running
+    pub fn is_running(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    /// Start a pass on `input`, replacing the running one
+    pub fn start(&mut self, input: PrecomputeInput) {
+        self.invalidate();
+        self.requested = false;
+        if input.def.is_none() && input.macros.is_empty() {
+            return;
+        }
+        self.with_def = input.def.is_some();
+        let generation = self.generation.load(Ordering::Relaxed);
+        let current = Arc::clone(&self.generation);
+        let (sender, receiver) = mpsc::channel();
+        let spawned = platform::spawn("precompute", move || {
+            let send = |update| sender.send((generation, update)).is_ok();
+            let cancelled = || current.load(Ordering::Relaxed) != generation;
+            run_pass(input, send, cancelled);
+        });
+        match spawned {
+            Ok(()) => self.receiver = Some(receiver),
+            Err(e) => log::warn!("Failed to start the precompute thread: {e}"),
+        }
+    }
+
+    /// Take the results that arrived, returning the spatial index when it
+    /// is done
+    pub fn poll(&mut self, ctx: &egui::Context) -> Option<DesignIndex> {
+        let receiver = self.receiver.as_ref()?;
+        let generation = self.generation.load(Ordering::Relaxed);
+        let mut design_index = None;
+        let mut received = false;
+        let mut finished = false;
+        for _ in 0..UPDATES_PER_FRAME {
+            match receiver.try_recv() {
+                Ok((from, _)) if from != generation => {}
+                Ok((_, update)) => {
+                    received = true;
+                    match update {
+                        Update::Progress(stage, done, total) => {
+                            self.progress = Some((stage, done, total));
+                        }
+                        Update::InstanceCounts(counts) => self.instance_counts = Some(counts),
+                        Update::Connectivity(nets) => self.connectivity = Some(nets),
+                        Update::DesignIndex(index) => design_index = Some(index),
+                        Update::Thumbnail(name, image) => {
+                            let texture = ctx.load_texture(
+                                format!("thumbnail_{name}"),
+                                image,
+                                egui::TextureOptions::LINEAR,
+                            );
+                            self.thumbnails.insert(name, texture);
+                        }
+                    }
+                }
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    finished = true;
+                    break;
+                }
+            }
+        }
+        if finished {
+            self.receiver = None;
+            self.progress = None;
+        }
+        if received || finished {
+            ctx.request_repaint();
+        }
+        design_index
+    }
+
+    /// Status bar entry of the running pass
+    pub fn task(&self) -> Option<BackgroundTask> {
+        self.receiver.as_ref()?;
+        let (label, progress) = match self.progress {
+            Some((stage, done, total)) => {
+                let stage_done = if total > 0 {
+                    done as f32 / total as f32
+                } else {
+                    1.0
+                };
+                let index = stage as usize as f32;
+                (
+                    format!("Precomputing {}", stage.label()),
+                    (index + stage_done) / Stage::COUNT as f32,
+                )
+            }
+            None => ("Precomputing".to_string(), 0.0),
+        };
+        Some(BackgroundTask {
+            label,
+            queued: 0,
+            progress: Some(progress),
+        })
+    }
+
+    /// Instance count row of the preview card of `macro_name`
+    pub fn instances_label(&self, macro_name: &str) -> Option<String> {
+        if !self.with_def {
+            return None;
+        }
+        Some(match &self.instance_counts {
+            Some(counts) => counts.get(macro_name).copied().unwrap_or(0).to_string(),
+            None => "counting...".to_string(),
+        })
+    }
+
+    /// Indices of the nets connected to `component`, None until they are
+    /// known
+    pub fn connected_nets(&self, component: &str) -> Option<&[usize]> {
+        let connectivity = self.connectivity.as_ref()?;
+        Some(connectivity.get(component).map_or(&[], Vec::as_slice))
+    }
+
+    /// Rasterized preview card thumbnail of `macro_name`
+    pub fn thumbnail(&self, macro_name: &str) -> Option<&egui::TextureHandle> {
+        self.thumbnails.get(macro_name)
+    }
+}
+
+/// Run the stages, stopping when `send` fails or the pass is `cancelled`
+fn run_pass(input: PrecomputeInput, send: impl Fn(Update) -> bool, cancelled: impl Fn() -> bool) {
+    crate::profile_scope!("precompute_pass");
+    if let Some(def) = &input.def {
+        if !send(Update::Progress(Stage::InstanceCounts, 0, 1))
+            || !send(Update::InstanceCounts(instance_counts(def)))
+            || cancelled()
+        {
+            return;
+        }
+        platform::yield_now();
+        if !send(Update::Progress(Stage::Connectivity, 0, 1))
+            || !send(Update::Connectivity(component_nets(def)))
+            || cancelled()
+        {
+            return;
+        }
+        platform::yield_now();
+        let macros: HashMap<&str, &LefMacro> =
+            input.macros.iter().map(|m| (m.name.as_str(), m)).collect();
+        if !send(Update::Progress(Stage::SpatialIndex, 0, 1))
+            || !send(Update::DesignIndex(DesignIndex::build(
+                def,
+                input.db_units,
+                &macros,
+            )))
+            || cancelled()
+        {
+            return;
+        }
+    }
+
+    let layer_color = |layer: &str| {
+        input
+            .layer_colors
+            .get(layer)
+            .copied()
+            .unwrap_or(egui::Color32::GRAY)
+    };
+    let total = input.macros.len().min(MAX_THUMBNAILS);
+    for (done, macro_def) in input.macros.iter().take(total).enumerate() {
+        if cancelled() || !send(Update::Progress(Stage::Thumbnails, done, total)) {
+            return;
+        }
+        let image = macro_thumbnail::rasterize_macro(
+            macro_def,
+            &layer_color,
+            macro_thumbnail::THUMBNAIL_PIXELS,
+        );
+        if !send(Update::Thumbnail(macro_def.name.clone(), image)) {
+            return;
+        }
+        if done % ITEMS_PER_YIELD == 0 {
+            platform::yield_now();
+        }
+    }
+}
+
+/// Number of DEF components per macro
+fn instance_counts(def: &Def) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for component in &def.components {
+        *counts.entry(component.macro_name.clone()).or_default() += 1;
+    }
+    counts
+}
+
+/// Indices of the nets connected to each component, in net order
+fn component_nets(def: &Def) -> HashMap<String, Vec<usize>> {
+    let mut nets: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, net) in def.nets.iter().enumerate() {
+        for instance in &net.instances {
+            if instance == "PIN" {
+                continue;
+            }
+            let connected = nets.entry(instance.clone()).or_default();
+            // A net on several pins of the component is listed once
+            if connected.last() != Some(&index) {
+                connected.push(index);
+            }
+        }
+    }
+    nets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::def::{DefComponent, DefNet};
+
+    fn component(name: &str, macro_name: &str) -> DefComponent {
+        DefComponent {
+            name: name.to_string(),
+            macro_name: macro_name.to_string(),
+            ..DefComponent::default()
+        }
+    }
+
+    fn net(name: &str, connections: &[(&str, &str)]) -> DefNet {
+        DefNet {
+            name: name.to_string(),
+            connections: connections.len(),
+            pins: connections.len(),
+            use_type: String::new(),
+            weight: None,
+            source: String::new(),
+            pattern: String::new(),
+            shielded: false,
+            shield_nets: Vec::new(),
+            instances: connections.iter().map(|c| c.0.to_string()).collect(),
+            instance_pins: connections.iter().map(|c| c.1.to_string()).collect(),
+            routes: Vec::new(),
+        }
+    }
+
+    fn design() -> Def {
+        Def {
+            components: vec![
+                component("u1", "INV"),
+                component("u2", "INV"),
+                component("u3", "NAND2"),
+            ],
+            nets: vec![
+                net("a", &[("PIN", "a"), ("u1", "A")]),
+                net("b", &[("u1", "Y"), ("u3", "A"), ("u3", "B")]),
+            ],
+            ..Def::default()
+        }
+    }
+
+    #[test]
+    fn test_instance_counts_and_component_nets() {
+        let def = design();
+        let counts = instance_counts(&def);
+        assert_eq!(counts["INV"], 2);
+        assert_eq!(counts["NAND2"], 1);
+
+        let nets = component_nets(&def);
+        assert_eq!(nets["u1"], vec![0, 1]);
+        assert_eq!(nets["u3"], vec![1]);
+        assert!(!nets.contains_key("u2"));
+        assert!(!nets.contains_key("PIN"));
+    }
+
+    #[test]
+    fn test_cancelled_pass_stops() {
+        let updates = std::cell::RefCell::new(Vec::new());
+        let input = PrecomputeInput {
+            def: Some(design()),
+            macros: Vec::new(),
+            db_units: 1000.0,
+            layer_colors: HashMap::new(),
+        };
+        run_pass(
+            input,
+            |update| {
+                updates.borrow_mut().push(update);
+                true
+            },
+            || true,
+        );
+        let updates = updates.into_inner();
+        assert_eq!(updates.len(), 2);
+        assert!(matches!(updates[1], Update::InstanceCounts(_)));
+    }
+}
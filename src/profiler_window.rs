@@ -0,0 +1,141 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Profiler window
+//!
+//! Lists the profiler scopes of the latest or the slowest recorded frame,
+//! nested under their callers and merged over repeated calls, so the stage
+//! that makes a design slow (parsing, tessellation, culling or painting)
+//! stands out. Needs a build with the `profiling` feature.
+
+use crate::profiling;
+use eframe::egui;
+
+/// Profiler window state and rendering
+#[derive(Default)]
+pub struct ProfilerWindow {
+    /// Whether the window is currently shown
+    pub visible: bool,
+    #[cfg(feature = "profiling")]
+    show_slowest: bool,
+    /// Created on first use, as it keeps every frame reported after that
+    #[cfg(feature = "profiling")]
+    frame_view: Option<puffin::GlobalFrameView>,
+}
+
+impl ProfilerWindow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render the window; closing it stops recording
+    pub fn render(&mut self, ctx: &egui::Context) {
+        if !self.visible {
+            return;
+        }
+
+        let mut open = true;
+        egui::Window::new("Profiler")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(520.0)
+            .default_height(400.0)
+            .show(ctx, |ui| {
+                let mut recording = profiling::is_enabled();
+                let response = ui.add_enabled(
+                    profiling::AVAILABLE,
+                    egui::Checkbox::new(&mut recording, "Record"),
+                );
+                if response.changed() {
+                    profiling::set_enabled(recording);
+                }
+                #[cfg(feature = "profiling")]
+                self.render_frame(ui);
+                #[cfg(not(feature = "profiling"))]
+                ui.label("Rebuild with `--features profiling` to record profiler scopes.");
+            });
+
+        if !open {
+            self.visible = false;
+            profiling::set_enabled(false);
+        }
+    }
+
+    #[cfg(feature = "profiling")]
+    fn render_frame(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.show_slowest, false, "Latest Frame");
+            ui.selectable_value(&mut self.show_slowest, true, "Slowest Frame");
+        });
+        ui.separator();
+
+        let view = self
+            .frame_view
+            .get_or_insert_with(puffin::GlobalFrameView::default)
+            .lock();
+        let frame = if self.show_slowest {
+            view.slowest_frames_chronological()
+                .max_by_key(|f| f.duration_ns())
+                .cloned()
+        } else {
+            view.latest_frame()
+        };
+        let Some(unpacked) = frame.and_then(|f| f.unpacked().ok()) else {
+            ui.label("No frames recorded yet.");
+            return;
+        };
+        ui.label(format!(
+            "Frame {}: {:.2} ms",
+            unpacked.frame_index(),
+            unpacked.duration_ns() as f64 / 1e6
+        ));
+
+        let frames = [unpacked.clone()];
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("profiler_scopes")
+                .striped(true)
+                .num_columns(4)
+                .show(ui, |ui| {
+                    ui.strong("Scope");
+                    ui.strong("Calls");
+                    ui.strong("Total ms");
+                    ui.strong("Max ms");
+                    ui.end_row();
+
+                    for thread in unpacked.thread_streams.keys() {
+                        let Ok(scopes) = puffin::merge_scopes_for_thread(
+                            view.scope_collection(),
+                            &frames,
+                            thread,
+                        ) else {
+                            continue;
+                        };
+                        ui.label(egui::RichText::new(&thread.name).italics());
+                        ui.end_row();
+                        scope_rows(ui, view.scope_collection(), &scopes, 1);
+                    }
+                });
+        });
+    }
+}
+
+/// One grid row per merged scope, children indented below their parent
+#[cfg(feature = "profiling")]
+fn scope_rows(
+    ui: &mut egui::Ui,
+    collection: &puffin::ScopeCollection,
+    scopes: &[puffin::MergeScope<'_>],
+    depth: usize,
+) {
+    for scope in scopes {
+        let name = collection
+            .fetch_by_id(&scope.id)
+            .map_or_else(|| "?".to_string(), |details| details.name().to_string());
+        ui.label(format!("{}{name}", "  ".repeat(depth)));
+        ui.label(scope.num_pieces.to_string());
+        ui.label(format!("{:.3}", scope.total_duration_ns as f64 / 1e6));
+        ui.label(format!("{:.3}", scope.max_duration_ns as f64 / 1e6));
+        ui.end_row();
+        scope_rows(ui, collection, &scope.children, depth + 1);
+    }
+}
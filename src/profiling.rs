@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Profiler scopes
+//!
+//! With the `profiling` feature, [`profile_scope!`](crate::profile_scope)
+//! records the rest of the enclosing block as a puffin scope and the viewer's
+//! profiler window shows the recorded frames. Without the feature the scopes
+//! compile to nothing, so they can stay on hot paths. Recording is off until
+//! it is turned on with [`set_enabled`].
+
+/// Record the rest of the enclosing block as a profiler scope named `$name`
+#[macro_export]
+macro_rules! profile_scope {
+    ($name:expr) => {
+        #[cfg(feature = "profiling")]
+        puffin::profile_scope!($name);
+    };
+}
+
+/// Whether the binary was built with the `profiling` feature
+pub const AVAILABLE: bool = cfg!(feature = "profiling");
+
+/// Turn recording of scopes on or off
+pub fn set_enabled(enabled: bool) {
+    #[cfg(feature = "profiling")]
+    puffin::set_scopes_on(enabled);
+    #[cfg(not(feature = "profiling"))]
+    let _ = enabled;
+}
+
+/// Whether scopes are being recorded
+pub fn is_enabled() -> bool {
+    #[cfg(feature = "profiling")]
+    return puffin::are_scopes_on();
+    #[cfg(not(feature = "profiling"))]
+    false
+}
+
+/// Close the current profiler frame and start the next one
+///
+/// Called once per UI frame; scopes of background threads go into the frame
+/// during which they finished.
+pub fn new_frame() {
+    #[cfg(feature = "profiling")]
+    puffin::GlobalProfiler::lock().new_frame();
+}
@@ -0,0 +1,106 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Parser progress reporting
+//!
+//! [`crate::lef::reader::LefReader`] and [`crate::def::reader::DefReader`]
+//! take a [`ProgressCallback`] that is called while they parse, e.g. to show
+//! a progress bar. The callback runs on the parsing thread and is only
+//! called when the whole percentage or the section changes, not per item.
+
+/// Progress of a parser
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Progress {
+    /// Share of the input parsed, 0.0 to 1.0
+    pub fraction: f32,
+    /// Items parsed so far, macros for LEF and section items for DEF
+    pub items: usize,
+    /// Section or statement being parsed, e.g. `MACRO` or `NETS`
+    pub section: String,
+}
+
+impl Progress {
+    pub fn percent(&self) -> f32 {
+        self.fraction * 100.0
+    }
+}
+
+/// Called by the readers with the progress of the file being parsed
+pub type ProgressCallback = Box<dyn Fn(&Progress) + Send>;
+
+/// Passes progress on to a [`ProgressCallback`], leaving out the updates
+/// that would not change what it shows
+pub struct ProgressReporter<'a> {
+    callback: Option<&'a ProgressCallback>,
+    last: Option<(u32, String)>,
+}
+
+impl<'a> ProgressReporter<'a> {
+    pub fn new(callback: Option<&'a ProgressCallback>) -> Self {
+        Self {
+            callback,
+            last: None,
+        }
+    }
+
+    /// Report `fraction` of the input parsed and `items` in total while in
+    /// `section`
+    pub fn report(&mut self, fraction: f64, items: usize, section: &str) {
+        let Some(callback) = self.callback else {
+            return;
+        };
+        let fraction = if fraction.is_finite() {
+            fraction.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let percent = (fraction * 100.0) as u32;
+        if let Some((last_percent, last_section)) = &self.last {
+            if *last_percent == percent && last_section == section {
+                return;
+            }
+        }
+        self.last = Some((percent, section.to_string()));
+        callback(&Progress {
+            fraction: fraction as f32,
+            items,
+            section: section.to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_reporter_skips_unchanged_updates() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reports);
+        let callback: ProgressCallback = Box::new(move |p| sink.lock().unwrap().push(p.clone()));
+
+        let mut reporter = ProgressReporter::new(Some(&callback));
+        reporter.report(0.0, 0, "COMPONENTS");
+        reporter.report(0.004, 10, "COMPONENTS");
+        reporter.report(0.5, 20, "COMPONENTS");
+        reporter.report(0.5, 20, "NETS");
+        reporter.report(2.0, 30, "NETS");
+        ProgressReporter::new(None).report(0.7, 1, "NETS");
+
+        let reports = reports.lock().unwrap();
+        let seen: Vec<_> = reports
+            .iter()
+            .map(|p| (p.percent() as u32, p.items, p.section.as_str()))
+            .collect();
+        assert_eq!(
+            seen,
+            [
+                (0, 0, "COMPONENTS"),
+                (50, 20, "COMPONENTS"),
+                (50, 20, "NETS"),
+                (100, 30, "NETS")
+            ]
+        );
+    }
+}
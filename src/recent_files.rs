@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Recent files and file type detection
+//!
+//! Files opened from the dialog, dropped on the window or reopened from
+//! File -> Recent Files are remembered most recent first and kept with the
+//! session. Dropped files may come with any name, so their type is taken
+//! from the extension and, failing that, from the first keywords of the
+//! file: DESIGN, DIEAREA or COMPONENTS only occur in DEF, MACRO, LAYER or
+//! SITE statements only in LEF.
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Files listed in the Recent Files menu
+pub const MAX_RECENT: usize = 10;
+/// Bytes read from the start of a file to detect its type
+const HEADER_BYTES: u64 = 64 * 1024;
+
+/// Type of a layout file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    Lef,
+    Def,
+}
+
+impl FileKind {
+    /// Type of the file at `path` by extension, then by header keywords
+    pub fn detect(path: &Path) -> Option<Self> {
+        Self::from_extension(path).or_else(|| {
+            let mut header = String::new();
+            File::open(path)
+                .ok()?
+                .take(HEADER_BYTES)
+                .read_to_string(&mut header)
+                .ok()?;
+            Self::from_header(&header)
+        })
+    }
+
+    /// Type of a file handed over as `bytes` by its `name`, then by header
+    /// keywords
+    pub fn detect_bytes(name: &str, bytes: &[u8]) -> Option<Self> {
+        Self::from_extension(Path::new(name)).or_else(|| {
+            let header = &bytes[..bytes.len().min(HEADER_BYTES as usize)];
+            Self::from_header(&String::from_utf8_lossy(header))
+        })
+    }
+
+    fn from_extension(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "lef" | "tlef" => Some(Self::Lef),
+            "def" => Some(Self::Def),
+            _ => None,
+        }
+    }
+
+    /// Type from the first statement keyword that only one format has
+    pub fn from_header(header: &str) -> Option<Self> {
+        header
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default())
+            .filter_map(|line| line.split_whitespace().next())
+            .find_map(|keyword| match keyword {
+                "DESIGN" | "DIEAREA" | "COMPONENTS" | "ROW" | "TRACKS" | "NETS" => Some(Self::Def),
+                "MACRO" | "LAYER" | "SITE" | "MANUFACTURINGGRID" | "VIARULE" => Some(Self::Lef),
+                _ => None,
+            })
+    }
+}
+
+/// Recently opened files, most recent first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RecentFiles {
+    paths: Vec<String>,
+}
+
+impl RecentFiles {
+    /// Move `path` to the top of the list
+    pub fn add(&mut self, path: &str) {
+        self.paths.retain(|p| p != path);
+        self.paths.insert(0, path.to_string());
+        self.paths.truncate(MAX_RECENT);
+    }
+
+    pub fn remove(&mut self, path: &str) {
+        self.paths.retain(|p| p != path);
+    }
+
+    pub fn clear(&mut self) {
+        self.paths.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.paths.iter().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_file_kind() {
+        assert_eq!(
+            FileKind::from_extension(Path::new("tech.TLEF")),
+            Some(FileKind::Lef)
+        );
+        assert_eq!(
+            FileKind::from_extension(Path::new("top.def")),
+            Some(FileKind::Def)
+        );
+        assert_eq!(FileKind::from_extension(Path::new("top.txt")), None);
+
+        let def = "# routed\nVERSION 5.8 ;\nDIVIDERCHAR \"/\" ;\nDESIGN top ;\n";
+        assert_eq!(FileKind::from_header(def), Some(FileKind::Def));
+        let lef = "VERSION 5.8 ;\nBUSBITCHARS \"[]\" ;\n# MACRO in a comment\nLAYER M1\n";
+        assert_eq!(FileKind::from_header(lef), Some(FileKind::Lef));
+        assert_eq!(FileKind::from_header("VERSION 5.8 ;\n"), None);
+
+        assert_eq!(
+            FileKind::detect_bytes("cells.lef", b""),
+            Some(FileKind::Lef)
+        );
+        assert_eq!(
+            FileKind::detect_bytes("top.txt", def.as_bytes()),
+            Some(FileKind::Def)
+        );
+    }
+
+    #[test]
+    fn test_recent_files_order() {
+        let mut recent = RecentFiles::default();
+        for i in 0..12 {
+            recent.add(&format!("{i}.lef"));
+        }
+        recent.add("5.lef");
+        let paths: Vec<&str> = recent.iter().collect();
+        assert_eq!(paths.len(), MAX_RECENT);
+        assert_eq!(paths[..3], ["5.lef", "11.lef", "10.lef"]);
+        recent.remove("11.lef");
+        assert_eq!(recent.iter().nth(1), Some("10.lef"));
+    }
+}
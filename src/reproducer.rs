@@ -0,0 +1,482 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Minimized reproducers for parser bug reports
+//!
+//! A reproducer is the smallest part of a user's file that still shows a
+//! parsing problem: the file header plus one LEF block, or one DEF section
+//! cut down to the offending items. It is written next to an `.expect` file
+//! listing assertions on the parsed model, one `path = value` per line, so it
+//! can be dropped into `tests/fixtures` as a regression test.
+//!
+//! Paths walk the JSON form of the model from [`lef_summary`] or
+//! [`def_summary`]: `components.len = 3`, `components.0.name = "U1"`,
+//! `macros.0.pins.1.direction = "OUTPUT"`.
+
+use serde_json::{json, Value};
+
+use crate::def::def_parser::parse_def;
+use crate::def::Def;
+use crate::lef::lef_parser::{extract_block_source, parse_lef};
+use crate::lef::{Lef, LefPolygon, LefRect};
+
+/// LEF statements kept in front of the extracted block
+const LEF_HEADER: [&str; 5] = [
+    "VERSION",
+    "BUSBITCHARS",
+    "DIVIDERCHAR",
+    "NAMESCASESENSITIVE",
+    "MANUFACTURINGGRID",
+];
+
+/// DEF statements kept in front of the extracted section
+const DEF_HEADER: [&str; 8] = [
+    "VERSION",
+    "NAMESCASESENSITIVE",
+    "DIVIDERCHAR",
+    "BUSBITCHARS",
+    "DESIGN",
+    "TECHNOLOGY",
+    "UNITS",
+    "DIEAREA",
+];
+
+/// Whether a line ends a statement, ignoring quoted text and comments
+fn ends_statement(line: &str) -> bool {
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return false,
+            ';' if !quoted => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+fn first_token(line: &str) -> Option<&str> {
+    line.split_whitespace().next()
+}
+
+/// Lines of the statement starting at `lines[start]`, up to its `;`
+fn statement_end(lines: &[&str], start: usize) -> usize {
+    (start..lines.len())
+        .find(|&i| ends_statement(lines[i]))
+        .unwrap_or(lines.len() - 1)
+}
+
+/// Cut LEF content down to its header and one MACRO, LAYER, VIA or SITE
+pub fn minimize_lef(content: &str, name: &str) -> Result<String, String> {
+    let block = ["MACRO", "LAYER", "VIA", "SITE"]
+        .iter()
+        .find_map(|keyword| extract_block_source(content, keyword, name))
+        .ok_or_else(|| format!("No MACRO, LAYER, VIA or SITE named '{name}'"))?;
+
+    let lines: Vec<&str> = content.lines().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < lines.len() {
+        match first_token(lines[i]) {
+            Some(keyword) if LEF_HEADER.contains(&keyword) => {
+                let end = statement_end(&lines, i);
+                for line in &lines[i..=end] {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+                i = end;
+            }
+            Some("UNITS") => {
+                let end = (i..lines.len())
+                    .find(|&j| lines[j].split_whitespace().eq(["END", "UNITS"]))
+                    .unwrap_or(i);
+                for line in &lines[i..=end] {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+                i = end;
+            }
+            // The header ends with the first block
+            Some("MACRO" | "LAYER" | "VIA" | "SITE") => break,
+            _ => {}
+        }
+        i += 1;
+    }
+
+    out.push('\n');
+    out.push_str(block.trim_end());
+    out.push_str("\n\nEND LIBRARY\n");
+    Ok(out)
+}
+
+/// A section of DEF content: `NAME count ; items END NAME`
+struct DefSection<'a> {
+    name: &'a str,
+    /// Statements between the header and the END line, one per item
+    items: Vec<Vec<&'a str>>,
+}
+
+/// Split DEF content into its header statements and sections
+fn scan_def<'a>(lines: &[&'a str]) -> (Vec<&'a str>, Vec<DefSection<'a>>) {
+    let mut header = Vec::new();
+    let mut sections = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let Some(keyword) = first_token(lines[i]) else {
+            i += 1;
+            continue;
+        };
+        let end_line = |j: &usize| lines[*j].split_whitespace().eq(["END", keyword]);
+
+        if DEF_HEADER.contains(&keyword) {
+            let end = statement_end(lines, i);
+            header.extend_from_slice(&lines[i..=end]);
+            i = end + 1;
+        } else if let Some(end) = (i + 1..lines.len()).find(end_line) {
+            let mut section = DefSection {
+                name: keyword,
+                items: Vec::new(),
+            };
+            let mut j = statement_end(lines, i) + 1;
+            while j < end {
+                if lines[j].trim_start().starts_with('-') {
+                    let item_end = statement_end(&lines[..end], j);
+                    section.items.push(lines[j..=item_end].to_vec());
+                    j = item_end;
+                }
+                j += 1;
+            }
+            sections.push(section);
+            i = end + 1;
+        } else {
+            i += 1;
+        }
+    }
+
+    (header, sections)
+}
+
+/// Names of the sections of DEF content, in file order
+pub fn def_sections(content: &str) -> Vec<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let (_, sections) = scan_def(&lines);
+    sections.iter().map(|s| s.name.to_string()).collect()
+}
+
+/// Cut DEF content down to its header and one section
+///
+/// With `item`, only the items of that name are kept, e.g. one component.
+pub fn minimize_def(content: &str, section: &str, item: Option<&str>) -> Result<String, String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let (header, sections) = scan_def(&lines);
+    let found = sections
+        .iter()
+        .find(|s| s.name == section)
+        .ok_or_else(|| format!("No {section} section"))?;
+
+    let items: Vec<&Vec<&str>> = found
+        .items
+        .iter()
+        .filter(|lines| {
+            item.is_none_or(|item| {
+                lines[0]
+                    .trim_start()
+                    .trim_start_matches('-')
+                    .split_whitespace()
+                    .next()
+                    == Some(item)
+            })
+        })
+        .collect();
+    if let (Some(item), true) = (item, items.is_empty()) {
+        return Err(format!("No item '{item}' in the {section} section"));
+    }
+
+    let mut out = String::new();
+    for line in header {
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.push_str(&format!("\n{section} {} ;\n", items.len()));
+    for item_lines in items {
+        for line in item_lines {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out.push_str(&format!("END {section}\n\nEND DESIGN\n"));
+    Ok(out)
+}
+
+fn rect_summary(rect: &LefRect) -> Value {
+    json!({
+        "layer": rect.layer,
+        "xl": rect.xl,
+        "yl": rect.yl,
+        "xh": rect.xh,
+        "yh": rect.yh,
+    })
+}
+
+fn polygon_summary(polygon: &LefPolygon) -> Value {
+    json!({
+        "layer": polygon.layer,
+        "points": polygon.points,
+    })
+}
+
+/// JSON form of a parsed LEF, the model that expectation paths walk
+pub fn lef_summary(lef: &Lef) -> Value {
+    let layers: Vec<Value> = lef
+        .layers
+        .iter()
+        .map(|layer| {
+            json!({
+                "name": layer.name,
+                "type": layer.layer_type,
+                "direction": layer.direction,
+                "pitch": layer.pitch,
+                "width": layer.width,
+                "spacing": layer.spacing,
+            })
+        })
+        .collect();
+    let macros: Vec<Value> = lef
+        .macros
+        .iter()
+        .map(|m| {
+            let pins: Vec<Value> = m
+                .pins
+                .iter()
+                .map(|pin| {
+                    let ports: Vec<Value> = pin
+                        .ports
+                        .iter()
+                        .map(|port| {
+                            json!({
+                                "rects": port.rects.iter().map(rect_summary).collect::<Vec<_>>(),
+                                "polygons": port.polygons.iter().map(polygon_summary).collect::<Vec<_>>(),
+                            })
+                        })
+                        .collect();
+                    json!({
+                        "name": pin.name,
+                        "direction": pin.direction,
+                        "use": pin.use_type,
+                        "shape": pin.shape,
+                        "ports": ports,
+                    })
+                })
+                .collect();
+            let obs: Vec<Value> = m
+                .obs
+                .iter()
+                .map(|obs| {
+                    json!({
+                        "rects": obs.rects.iter().map(rect_summary).collect::<Vec<_>>(),
+                        "polygons": obs.polygons.iter().map(polygon_summary).collect::<Vec<_>>(),
+                    })
+                })
+                .collect();
+            json!({
+                "name": m.name,
+                "class": m.class,
+                "foreign": m.foreign,
+                "origin": [m.origin.0, m.origin.1],
+                "size": [m.size_x, m.size_y],
+                "symmetry": m.symmetry,
+                "site": m.site,
+                "pins": pins,
+                "obs": obs,
+                "density": m.density.iter().map(|d| json!({
+                    "layer": d.layer,
+                    "rect": [d.xl, d.yl, d.xh, d.yh],
+                    "density": d.density,
+                })).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+
+    json!({
+        "database_microns": lef.database_microns,
+        "manufacturing_grid": lef.manufacturing_grid,
+        "layers": layers,
+        "macros": macros,
+    })
+}
+
+/// JSON form of a parsed DEF, the model that expectation paths walk
+pub fn def_summary(def: &Def) -> Value {
+    serde_json::to_value(def).unwrap_or(Value::Null)
+}
+
+/// Starting `.expect` content for a model: list lengths and first names
+///
+/// `note` is kept as a comment describing the bug report.
+pub fn expectation_skeleton(summary: &Value, note: &str) -> String {
+    let mut out = String::new();
+    for line in note.lines().filter(|l| !l.trim().is_empty()) {
+        out.push_str(&format!("# {}\n", line.trim()));
+    }
+    out.push_str("# Review the values below and add the ones the bug got wrong\n");
+
+    let Some(fields) = summary.as_object() else {
+        return out;
+    };
+    for (key, value) in fields {
+        match value {
+            Value::Array(items) => {
+                out.push_str(&format!("{key}.len = {}\n", items.len()));
+                if let Some(name) = items.first().and_then(|first| first.get("name")) {
+                    out.push_str(&format!("{key}.0.name = {name}\n"));
+                }
+            }
+            Value::Null | Value::Object(_) => {}
+            scalar => out.push_str(&format!("{key} = {scalar}\n")),
+        }
+    }
+    out
+}
+
+/// A minimized source and the `.expect` skeleton of its parsed model
+pub struct Reproducer {
+    pub source: String,
+    pub expect: String,
+}
+
+/// Minimize LEF content to one block and start its expectations
+pub fn lef_reproducer(content: &str, name: &str, note: &str) -> Result<Reproducer, String> {
+    let source = minimize_lef(content, name)?;
+    let expect = match parse_lef(&source) {
+        Ok((_, lef)) => expectation_skeleton(&lef_summary(&lef), note),
+        Err(e) => expectation_skeleton(&Value::Null, &format!("{note}\nParse failed: {e:?}")),
+    };
+    Ok(Reproducer { source, expect })
+}
+
+/// Minimize DEF content to one section and start its expectations
+pub fn def_reproducer(
+    content: &str,
+    section: &str,
+    item: Option<&str>,
+    note: &str,
+) -> Result<Reproducer, String> {
+    let source = minimize_def(content, section, item)?;
+    let expect = match parse_def(&source) {
+        Ok((_, def)) => expectation_skeleton(&def_summary(&def), note),
+        Err(e) => expectation_skeleton(&Value::Null, &format!("{note}\nParse failed: {e:?}")),
+    };
+    Ok(Reproducer { source, expect })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEF: &str = "VERSION 5.8 ;
+BUSBITCHARS \"[]\" ;
+UNITS
+  DATABASE MICRONS 1000 ;
+END UNITS
+
+LAYER M1
+  TYPE ROUTING ;
+END M1
+
+MACRO INV
+  SIZE 1 BY 2 ;
+END INV
+
+MACRO BUF
+  SIZE 2 BY 2 ;
+END BUF
+
+END LIBRARY
+";
+
+    const DEF: &str = "VERSION 5.8 ;
+DESIGN top ;
+UNITS DISTANCE MICRONS 1000 ;
+DIEAREA ( 0 0 )
+  ( 1000 1000 ) ;
+ROW row0 core 0 0 N DO 10 BY 1 STEP 100 0 ;
+
+COMPONENTS 3 ;
+- U1 INV + PLACED ( 0 0 ) N ;
+- U2 BUF
+  + PROPERTY note \"a;b\"
+  + PLACED ( 100 0 ) FS ;
+- U3 INV + PLACED ( 200 0 ) N ;
+END COMPONENTS
+
+NETS 1 ;
+- n1 ( U1 Z ) ( U2 A ) ;
+END NETS
+
+END DESIGN
+";
+
+    #[test]
+    fn test_minimize_lef_keeps_header_and_block() {
+        let minimized = minimize_lef(LEF, "BUF").unwrap();
+        assert!(minimized.starts_with("VERSION 5.8 ;\nBUSBITCHARS \"[]\" ;\nUNITS\n"));
+        assert!(minimized.contains("END UNITS\n"));
+        assert!(minimized.contains("MACRO BUF\n  SIZE 2 BY 2 ;\nEND BUF\n"));
+        assert!(!minimized.contains("INV"));
+        assert!(!minimized.contains("LAYER"));
+        assert!(minimized.ends_with("END LIBRARY\n"));
+
+        assert!(minimize_lef(LEF, "M1").unwrap().contains("LAYER M1"));
+        assert!(minimize_lef(LEF, "NAND2").is_err());
+    }
+
+    #[test]
+    fn test_minimize_def_keeps_one_item() {
+        assert_eq!(def_sections(DEF), ["COMPONENTS", "NETS"]);
+
+        let minimized = minimize_def(DEF, "COMPONENTS", Some("U2")).unwrap();
+        assert_eq!(
+            minimized,
+            "VERSION 5.8 ;
+DESIGN top ;
+UNITS DISTANCE MICRONS 1000 ;
+DIEAREA ( 0 0 )
+  ( 1000 1000 ) ;
+
+COMPONENTS 1 ;
+- U2 BUF
+  + PROPERTY note \"a;b\"
+  + PLACED ( 100 0 ) FS ;
+END COMPONENTS
+
+END DESIGN
+"
+        );
+
+        let all = minimize_def(DEF, "COMPONENTS", None).unwrap();
+        assert!(all.contains("COMPONENTS 3 ;"));
+        assert!(minimize_def(DEF, "PINS", None).is_err());
+        assert!(minimize_def(DEF, "NETS", Some("n2")).is_err());
+    }
+
+    #[test]
+    fn test_expectation_skeleton() {
+        let summary = json!({
+            "design": "top",
+            "components": [{"name": "U1"}, {"name": "U2"}],
+            "rows": [],
+            "units_distance_microns": null,
+        });
+        assert_eq!(
+            expectation_skeleton(&summary, "COMPONENTS dropped\n"),
+            "# COMPONENTS dropped
+# Review the values below and add the ones the bug got wrong
+components.len = 2
+components.0.name = \"U1\"
+design = \"top\"
+rows.len = 0
+"
+        );
+    }
+}
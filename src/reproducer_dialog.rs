@@ -0,0 +1,228 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Parser bug reproducer dialog
+//!
+//! Extracts the offending block or section of a loaded file with
+//! [`crate::reproducer`] and previews it with its `.expect` skeleton before
+//! both are saved as a test fixture.
+
+use crate::reproducer::{self, Reproducer};
+use eframe::egui;
+
+/// A loaded file that a reproducer can be cut from
+pub struct ReproducerSource {
+    pub path: String,
+    pub is_def: bool,
+    /// LEF blocks or DEF sections to choose from
+    pub parts: Vec<String>,
+}
+
+/// Reproducer confirmed in the dialog
+pub struct ReproducerRequest {
+    pub reproducer: Reproducer,
+    /// Suggested fixture file name, without extension
+    pub name: String,
+    pub is_def: bool,
+}
+
+/// Reproducer dialog state and rendering
+#[derive(Default)]
+pub struct ReproducerDialog {
+    /// Whether the dialog is currently shown
+    pub visible: bool,
+    sources: Vec<ReproducerSource>,
+    source: usize,
+    part: String,
+    /// DEF item to keep, all items of the section when empty
+    item: String,
+    note: String,
+    preview: Option<Reproducer>,
+    error: Option<String>,
+}
+
+impl ReproducerDialog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn open(&mut self, sources: Vec<ReproducerSource>) {
+        self.visible = true;
+        self.sources = sources;
+        self.source = self.source.min(self.sources.len().saturating_sub(1));
+        self.preview = None;
+        self.error = None;
+    }
+
+    fn extract(&mut self) {
+        self.preview = None;
+        let Some(source) = self.sources.get(self.source) else {
+            return;
+        };
+        let content = match std::fs::read_to_string(&source.path) {
+            Ok(content) => content,
+            Err(e) => {
+                self.error = Some(format!("Failed to read {}: {e}", source.path));
+                return;
+            }
+        };
+        let item = self.item.trim();
+        let result = if source.is_def {
+            reproducer::def_reproducer(
+                &content,
+                &self.part,
+                (!item.is_empty()).then_some(item),
+                &self.note,
+            )
+        } else {
+            reproducer::lef_reproducer(&content, &self.part, &self.note)
+        };
+        match result {
+            Ok(preview) => {
+                self.error = None;
+                self.preview = Some(preview);
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    /// Render the dialog, returning the reproducer once Save is clicked
+    pub fn render(&mut self, ctx: &egui::Context) -> Option<ReproducerRequest> {
+        if !self.visible {
+            return None;
+        }
+
+        let mut open = true;
+        let mut request = None;
+
+        egui::Window::new("Report a Parser Bug")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(460.0)
+            .show(ctx, |ui| {
+                if self.sources.is_empty() {
+                    ui.label("Load the LEF or DEF file that is parsed wrongly first.");
+                    return;
+                }
+                ui.label(
+                    egui::RichText::new(
+                        "Cuts the file down to its header and the part that shows the bug, \
+                         with expected values for a regression test.",
+                    )
+                    .small()
+                    .weak(),
+                );
+
+                let selected = &self.sources[self.source];
+                let mut source = self.source;
+                egui::ComboBox::from_label("File")
+                    .selected_text(file_name(&selected.path))
+                    .show_ui(ui, |ui| {
+                        for (i, s) in self.sources.iter().enumerate() {
+                            ui.selectable_value(&mut source, i, file_name(&s.path));
+                        }
+                    });
+                if source != self.source {
+                    self.source = source;
+                    self.part.clear();
+                    self.preview = None;
+                }
+
+                let selected = &self.sources[self.source];
+                let label = if selected.is_def { "Section" } else { "Block" };
+                egui::ComboBox::from_label(label)
+                    .selected_text(&self.part)
+                    .height(300.0)
+                    .show_ui(ui, |ui| {
+                        for part in &selected.parts {
+                            ui.selectable_value(&mut self.part, part.clone(), part);
+                        }
+                    });
+                if selected.is_def {
+                    ui.horizontal(|ui| {
+                        ui.label("Item:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.item)
+                                .hint_text("all items of the section"),
+                        );
+                    });
+                }
+                ui.label("What is parsed wrongly:");
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.note)
+                        .desired_rows(2)
+                        .desired_width(f32::INFINITY),
+                );
+
+                if ui
+                    .add_enabled(!self.part.is_empty(), egui::Button::new("Extract"))
+                    .clicked()
+                {
+                    self.extract();
+                }
+
+                if let Some(preview) = &mut self.preview {
+                    ui.separator();
+                    ui.label(format!(
+                        "Reproducer, {} lines:",
+                        preview.source.lines().count()
+                    ));
+                    egui::ScrollArea::vertical()
+                        .id_salt("reproducer_source")
+                        .max_height(180.0)
+                        .show(ui, |ui| {
+                            ui.add(
+                                egui::TextEdit::multiline(&mut preview.source.as_str())
+                                    .code_editor()
+                                    .desired_width(f32::INFINITY),
+                            );
+                        });
+                    ui.label("Expected values:");
+                    egui::ScrollArea::vertical()
+                        .id_salt("reproducer_expect")
+                        .max_height(140.0)
+                        .show(ui, |ui| {
+                            ui.add(
+                                egui::TextEdit::multiline(&mut preview.expect)
+                                    .code_editor()
+                                    .desired_width(f32::INFINITY),
+                            );
+                        });
+                    if ui.button("Save...").clicked() {
+                        let selected = &self.sources[self.source];
+                        let item = self.item.trim();
+                        let name = if selected.is_def && !item.is_empty() {
+                            format!("{}_{item}", self.part)
+                        } else {
+                            self.part.clone()
+                        };
+                        request = Some(ReproducerRequest {
+                            reproducer: Reproducer {
+                                source: preview.source.clone(),
+                                expect: preview.expect.clone(),
+                            },
+                            name: name.to_lowercase(),
+                            is_def: selected.is_def,
+                        });
+                    }
+                }
+
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::from_rgb(220, 80, 80), error);
+                }
+            });
+
+        if !open || request.is_some() {
+            self.visible = false;
+        }
+
+        request
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    std::path::Path::new(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(path)
+}
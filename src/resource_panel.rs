@@ -0,0 +1,180 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Routing Resources Panel
+//!
+//! Shows the [`ResourceMap`] of the loaded design as a heatmap over the DEF
+//! canvas, one routing layer at a time, with the share of blocked gcells.
+//! The map is computed when the panel is opened and again after the design
+//! or its libraries change.
+
+use crate::routing_resources::{LayerResources, ResourceMap, PLACEMENT};
+use eframe::egui;
+
+/// Gcells at least this blocked count as congested in the summary
+const CONGESTED: f32 = 0.9;
+
+/// Heatmap color of a blocked fraction, green through yellow to red
+pub fn heat_color(fraction: f32, opacity: f32) -> egui::Color32 {
+    let fraction = fraction.clamp(0.0, 1.0);
+    let (r, g) = if fraction < 0.5 {
+        (fraction * 2.0, 1.0)
+    } else {
+        (1.0, (1.0 - fraction) * 2.0)
+    };
+    egui::Color32::from_rgba_unmultiplied(
+        (r * 255.0) as u8,
+        (g * 200.0) as u8,
+        40,
+        (opacity.clamp(0.0, 1.0) * 255.0) as u8,
+    )
+}
+
+/// Routing resources panel state and rendering
+pub struct ResourcePanel {
+    /// Whether the panel and its heatmap are currently shown
+    pub visible: bool,
+    map: Option<ResourceMap>,
+    /// The map no longer matches the loaded files
+    stale: bool,
+    layer: usize,
+    pub opacity: f32,
+}
+
+impl Default for ResourcePanel {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            map: None,
+            stale: true,
+            layer: 0,
+            opacity: 0.45,
+        }
+    }
+}
+
+impl ResourcePanel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop the map after the design or its libraries changed
+    pub fn invalidate(&mut self) {
+        self.map = None;
+        self.stale = true;
+    }
+
+    /// Whether the map has to be computed before the next frame
+    pub fn needs_map(&self) -> bool {
+        self.visible && self.stale
+    }
+
+    pub fn set_map(&mut self, map: Option<ResourceMap>) {
+        let previous = self.selected_name().map(str::to_string);
+        self.map = map;
+        self.stale = false;
+        // Keep the selected layer across recomputes
+        self.layer = previous
+            .and_then(|name| {
+                self.map
+                    .as_ref()?
+                    .layers
+                    .iter()
+                    .position(|l| l.layer == name)
+            })
+            .unwrap_or(0);
+    }
+
+    fn selected_name(&self) -> Option<&str> {
+        let map = self.map.as_ref()?;
+        map.layers.get(self.layer).map(|l| l.layer.as_str())
+    }
+
+    /// Map and layer to draw on the canvas, None while the panel is hidden
+    pub fn heatmap(&self) -> Option<(&ResourceMap, &LayerResources)> {
+        if !self.visible {
+            return None;
+        }
+        let map = self.map.as_ref()?;
+        Some((map, map.layers.get(self.layer)?))
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context) {
+        if !self.visible {
+            return;
+        }
+
+        let mut open = true;
+        egui::Window::new("Routing Resources")
+            .open(&mut open)
+            .resizable(false)
+            .default_width(280.0)
+            .show(ctx, |ui| {
+                let Some(map) = &self.map else {
+                    ui.label("Load a DEF with a die area and its LEF libraries.");
+                    return;
+                };
+                if map.layers.is_empty() {
+                    ui.label("No OBS or blockages on any routing layer.");
+                    return;
+                }
+
+                let selected = &map.layers[self.layer.min(map.layers.len() - 1)];
+                egui::ComboBox::from_label("Layer")
+                    .selected_text(&selected.layer)
+                    .show_ui(ui, |ui| {
+                        for (i, layer) in map.layers.iter().enumerate() {
+                            let label = if layer.layer == PLACEMENT {
+                                "Placement blockages"
+                            } else {
+                                layer.layer.as_str()
+                            };
+                            ui.selectable_value(&mut self.layer, i, label);
+                        }
+                    });
+                ui.add(egui::Slider::new(&mut self.opacity, 0.1..=1.0).text("Opacity"));
+
+                let layer = &map.layers[self.layer.min(map.layers.len() - 1)];
+                let cells = layer.blocked.len();
+                ui.separator();
+                ui.label(format!(
+                    "{} x {} gcells",
+                    map.grid.columns(),
+                    map.grid.rows()
+                ));
+                ui.label(format!("Average blocked: {:.1}%", layer.mean() * 100.0));
+                let congested = layer.count_at_least(CONGESTED);
+                ui.label(format!(
+                    "At least {:.0}% blocked: {congested} gcells ({:.1}%)",
+                    CONGESTED * 100.0,
+                    congested as f32 * 100.0 / cells.max(1) as f32
+                ));
+
+                // Legend
+                ui.horizontal(|ui| {
+                    ui.label("0%");
+                    let (rect, _) =
+                        ui.allocate_exact_size(egui::vec2(140.0, 12.0), egui::Sense::hover());
+                    let steps = 28;
+                    let width = rect.width() / steps as f32;
+                    for i in 0..steps {
+                        let x = rect.left() + i as f32 * width;
+                        let cell = egui::Rect::from_min_size(
+                            egui::pos2(x, rect.top()),
+                            egui::vec2(width + 0.5, rect.height()),
+                        );
+                        ui.painter().rect_filled(
+                            cell,
+                            0.0,
+                            heat_color(i as f32 / (steps - 1) as f32, 1.0),
+                        );
+                    }
+                    ui.label("100%");
+                });
+            });
+
+        if !open {
+            self.visible = false;
+        }
+    }
+}
@@ -0,0 +1,430 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Routing resource map
+//!
+//! Splits the die into gcells and computes, per routing layer, the fraction
+//! of every gcell covered by macro OBS and DEF BLOCKAGES. Heavily blocked
+//! gcells are where a router runs out of tracks first, so the map predicts
+//! congestion from LEF/DEF alone, before any routing has been done.
+//!
+//! Gcells come from the DEF GCELLGRID statements, or from a square grid
+//! over the die area when the DEF has none. Shape areas are summed per
+//! gcell and clamped to 1, so overlapping shapes are counted twice up to a
+//! fully blocked gcell. Polygons count with their bounding box. Placement
+//! blockages do not block a routing layer; they get a map of their own
+//! under [`PLACEMENT`].
+
+use crate::def::Def;
+use crate::geometry::MacroTransform;
+use crate::lef::LefMacro;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Layer name of the placement blockage map
+pub const PLACEMENT: &str = "PLACEMENT";
+
+/// Gcells along the longer die side when the DEF has no GCELLGRID
+pub const DEFAULT_GCELLS: usize = 64;
+
+/// Gcell boundaries in microns
+#[derive(Debug, Clone, PartialEq)]
+pub struct GcellGrid {
+    /// Vertical gcell boundaries from left to right
+    pub x_edges: Vec<f64>,
+    /// Horizontal gcell boundaries from bottom to top
+    pub y_edges: Vec<f64>,
+}
+
+impl GcellGrid {
+    /// Grid of the DEF GCELLGRID statements, or a square grid of
+    /// [`DEFAULT_GCELLS`] over the die area
+    pub fn from_def(def: &Def, db_units: f64) -> Option<Self> {
+        let edges = |grids: &[crate::def::DefGCellGrid]| {
+            let mut edges: Vec<f64> = grids
+                .iter()
+                .flat_map(|g| (0..g.count).map(move |i| (g.offset + i as f64 * g.step) / db_units))
+                .filter(|e| e.is_finite())
+                .collect();
+            edges.sort_by(f64::total_cmp);
+            edges.dedup();
+            edges
+        };
+        let (x_edges, y_edges) = (edges(&def.g_cell_grid_x), edges(&def.g_cell_grid_y));
+        if x_edges.len() >= 2 && y_edges.len() >= 2 {
+            return Some(Self { x_edges, y_edges });
+        }
+
+        let (min_x, min_y, max_x, max_y) = def.die_area_points.iter().fold(
+            (
+                f64::INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::NEG_INFINITY,
+            ),
+            |(x1, y1, x2, y2), &(x, y)| (x1.min(x), y1.min(y), x2.max(x), y2.max(y)),
+        );
+        let (width, height) = ((max_x - min_x) / db_units, (max_y - min_y) / db_units);
+        if !(width > 0.0 && height > 0.0) {
+            return None;
+        }
+        let step = width.max(height) / DEFAULT_GCELLS as f64;
+        let uniform = |start: f64, length: f64| {
+            let count = (length / step).ceil().max(1.0) as usize;
+            (0..=count)
+                .map(|i| (start + i as f64 * step).min(start + length))
+                .collect()
+        };
+        Some(Self {
+            x_edges: uniform(min_x / db_units, width),
+            y_edges: uniform(min_y / db_units, height),
+        })
+    }
+
+    pub fn columns(&self) -> usize {
+        self.x_edges.len() - 1
+    }
+
+    pub fn rows(&self) -> usize {
+        self.y_edges.len() - 1
+    }
+
+    /// Bounds of a gcell as (min_x, min_y, max_x, max_y)
+    pub fn cell_rect(&self, column: usize, row: usize) -> (f64, f64, f64, f64) {
+        (
+            self.x_edges[column],
+            self.y_edges[row],
+            self.x_edges[column + 1],
+            self.y_edges[row + 1],
+        )
+    }
+
+    /// Gcells along one axis overlapping `lo..hi`
+    fn span(edges: &[f64], lo: f64, hi: f64) -> Range<usize> {
+        let start = edges.partition_point(|&e| e <= lo).saturating_sub(1);
+        let end = edges.partition_point(|&e| e < hi).min(edges.len() - 1);
+        start..end.max(start)
+    }
+}
+
+/// Blocked fraction of every gcell on one layer
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerResources {
+    pub layer: String,
+    /// Fraction in 0..=1 per gcell, row by row from the bottom
+    pub blocked: Vec<f32>,
+}
+
+impl LayerResources {
+    /// Average blocked fraction over all gcells
+    pub fn mean(&self) -> f32 {
+        self.blocked.iter().sum::<f32>() / self.blocked.len().max(1) as f32
+    }
+
+    /// Gcells at least `threshold` blocked
+    pub fn count_at_least(&self, threshold: f32) -> usize {
+        self.blocked.iter().filter(|&&b| b >= threshold).count()
+    }
+}
+
+/// Routing resource map of a design
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceMap {
+    pub grid: GcellGrid,
+    /// One entry per routing layer in the given order, then [`PLACEMENT`]
+    /// when the design has placement blockages
+    pub layers: Vec<LayerResources>,
+}
+
+impl ResourceMap {
+    /// Compute the map of `def` with the OBS of `macros`
+    ///
+    /// `routing_layers` are usually the ROUTING layers of the technology
+    /// LEF; when empty, every layer with OBS or blockages is mapped.
+    pub fn compute(
+        def: &Def,
+        db_units: f64,
+        macros: &HashMap<&str, &LefMacro>,
+        routing_layers: &[String],
+    ) -> Option<Self> {
+        let grid = GcellGrid::from_def(def, db_units)?;
+        let mut accumulator = Accumulator {
+            grid: &grid,
+            areas: HashMap::new(),
+            order: routing_layers.to_vec(),
+            fixed_layers: !routing_layers.is_empty(),
+        };
+
+        for component in &def.components {
+            let (Some(placement), Some(macro_def)) = (
+                &component.placement,
+                macros.get(component.macro_name.as_str()),
+            ) else {
+                continue;
+            };
+            let transform = MacroTransform::new(
+                macro_def,
+                (placement.x / db_units, placement.y / db_units),
+                &placement.orientation,
+            );
+            for obs in &macro_def.obs {
+                for rect in &obs.rects {
+                    let bounds = transform.apply_rect(rect.xl, rect.yl, rect.xh, rect.yh);
+                    accumulator.add(&rect.layer, bounds);
+                }
+                for polygon in &obs.polygons {
+                    let points = polygon.points.iter().map(|&p| transform.apply(p));
+                    if let Some(bounds) = bounding_box(points) {
+                        accumulator.add(&polygon.layer, bounds);
+                    }
+                }
+            }
+        }
+
+        for blockage in &def.blockages {
+            let layer = blockage.layer.as_deref().unwrap_or(PLACEMENT);
+            for rect in &blockage.rects {
+                let bounds = (
+                    rect.xl / db_units,
+                    rect.yl / db_units,
+                    rect.xh / db_units,
+                    rect.yh / db_units,
+                );
+                accumulator.add(layer, bounds);
+            }
+            for polygon in &blockage.polygons {
+                let points = polygon
+                    .points
+                    .iter()
+                    .map(|&(x, y)| (x / db_units, y / db_units));
+                if let Some(bounds) = bounding_box(points) {
+                    accumulator.add(layer, bounds);
+                }
+            }
+        }
+
+        let Accumulator {
+            mut areas, order, ..
+        } = accumulator;
+        let cells = grid.columns() * grid.rows();
+        let mut layers: Vec<LayerResources> = order
+            .into_iter()
+            .map(|layer| {
+                let area = areas.remove(&layer).unwrap_or_else(|| vec![0.0; cells]);
+                LayerResources {
+                    blocked: grid.fractions(area),
+                    layer,
+                }
+            })
+            .collect();
+        if let Some(area) = areas.remove(PLACEMENT) {
+            layers.push(LayerResources {
+                layer: PLACEMENT.to_string(),
+                blocked: grid.fractions(area),
+            });
+        }
+        Some(Self { grid, layers })
+    }
+}
+
+impl GcellGrid {
+    /// Blocked areas per gcell divided by the gcell areas, clamped to 1
+    fn fractions(&self, areas: Vec<f64>) -> Vec<f32> {
+        let columns = self.columns();
+        areas
+            .into_iter()
+            .enumerate()
+            .map(|(i, area)| {
+                let (x1, y1, x2, y2) = self.cell_rect(i % columns, i / columns);
+                let cell = (x2 - x1) * (y2 - y1);
+                if cell > 0.0 {
+                    (area / cell).clamp(0.0, 1.0) as f32
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+}
+
+/// Blocked area per layer and gcell while shapes are added
+struct Accumulator<'a> {
+    grid: &'a GcellGrid,
+    areas: HashMap<String, Vec<f64>>,
+    /// Layers in map order
+    order: Vec<String>,
+    /// Only the given routing layers are mapped
+    fixed_layers: bool,
+}
+
+impl Accumulator<'_> {
+    fn add(&mut self, layer: &str, (min_x, min_y, max_x, max_y): (f64, f64, f64, f64)) {
+        if layer != PLACEMENT && !self.order.iter().any(|l| l == layer) {
+            if self.fixed_layers {
+                return;
+            }
+            self.order.push(layer.to_string());
+        }
+        let grid = self.grid;
+        let area = self
+            .areas
+            .entry(layer.to_string())
+            .or_insert_with(|| vec![0.0; grid.columns() * grid.rows()]);
+        for row in GcellGrid::span(&grid.y_edges, min_y, max_y) {
+            for column in GcellGrid::span(&grid.x_edges, min_x, max_x) {
+                let (x1, y1, x2, y2) = grid.cell_rect(column, row);
+                let width = max_x.min(x2) - min_x.max(x1);
+                let height = max_y.min(y2) - min_y.max(y1);
+                if width > 0.0 && height > 0.0 {
+                    area[row * grid.columns() + column] += width * height;
+                }
+            }
+        }
+    }
+}
+
+fn bounding_box(points: impl Iterator<Item = (f64, f64)>) -> Option<(f64, f64, f64, f64)> {
+    points.fold(None, |bounds, (x, y)| match bounds {
+        None => Some((x, y, x, y)),
+        Some((x1, y1, x2, y2)) => Some((x1.min(x), y1.min(y), x2.max(x), y2.max(y))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::def::{DefBlockage, DefGCellGrid, DefRect};
+    use crate::lef::{LefObstruction, LefRect};
+
+    fn sram() -> LefMacro {
+        LefMacro {
+            name: "SRAM".to_string(),
+            class: "BLOCK".to_string(),
+            foreign: String::new(),
+            origin: (0.0, 0.0),
+            size_x: 10.0,
+            size_y: 10.0,
+            symmetry: Vec::new(),
+            site: String::new(),
+            pins: Vec::new(),
+            obs: vec![LefObstruction {
+                rects: vec![
+                    LefRect {
+                        layer: "M1".to_string(),
+                        xl: 0.0,
+                        yl: 0.0,
+                        xh: 10.0,
+                        yh: 10.0,
+                    },
+                    LefRect {
+                        layer: "M2".to_string(),
+                        xl: 0.0,
+                        yl: 0.0,
+                        xh: 5.0,
+                        yh: 10.0,
+                    },
+                ],
+                polygons: Vec::new(),
+            }],
+            ..LefMacro::default()
+        }
+    }
+
+    fn design() -> Def {
+        let mut def: Def = serde_json::from_value(serde_json::json!({
+            "die_area_points": [[0.0, 0.0], [40000.0, 20000.0]],
+            "g_cell_grid_x": [], "g_cell_grid_y": [], "pins": [], "nets": [],
+            "special_nets": [], "rows": [], "tracks_x": [], "tracks_y": [], "vias": [],
+            "components": [{
+                "name": "ram0", "macro_name": "SRAM",
+                "placement": {"placement_type": "FIXED", "x": 0.0, "y": 0.0, "orientation": "N"},
+                "routing_halo": null, "source": null, "weight": null, "eeq": null,
+                "generate": null, "power": null, "ground": null, "properties": []
+            }]
+        }))
+        .unwrap();
+        // Four 10um gcells by two
+        def.g_cell_grid_x = vec![DefGCellGrid {
+            offset: 0.0,
+            count: 5,
+            step: 10000.0,
+        }];
+        def.g_cell_grid_y = vec![DefGCellGrid {
+            offset: 0.0,
+            count: 3,
+            step: 10000.0,
+        }];
+        def
+    }
+
+    fn layer<'a>(map: &'a ResourceMap, name: &str) -> &'a LayerResources {
+        map.layers.iter().find(|l| l.layer == name).unwrap()
+    }
+
+    #[test]
+    fn test_obs_and_blockages_per_gcell() {
+        let macro_def = sram();
+        let macros = HashMap::from([("SRAM", &macro_def)]);
+        let mut def = design();
+        def.blockages = vec![
+            DefBlockage {
+                layer: Some("M2".to_string()),
+                rects: vec![DefRect {
+                    layer: "M2".to_string(),
+                    xl: 15000.0,
+                    yl: 10000.0,
+                    xh: 25000.0,
+                    yh: 20000.0,
+                }],
+                ..DefBlockage::default()
+            },
+            DefBlockage {
+                rects: vec![DefRect {
+                    layer: String::new(),
+                    xl: 30000.0,
+                    yl: 0.0,
+                    xh: 40000.0,
+                    yh: 20000.0,
+                }],
+                ..DefBlockage::default()
+            },
+        ];
+
+        let layers = ["M1".to_string(), "M2".to_string(), "M3".to_string()];
+        let map = ResourceMap::compute(&def, 1000.0, &macros, &layers).unwrap();
+        assert_eq!((map.grid.columns(), map.grid.rows()), (4, 2));
+        let names: Vec<_> = map.layers.iter().map(|l| l.layer.as_str()).collect();
+        assert_eq!(names, ["M1", "M2", "M3", PLACEMENT]);
+
+        let m1 = layer(&map, "M1");
+        assert_eq!(m1.blocked, [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        // Half of the first gcell by OBS, halves of two upper gcells by the blockage
+        let m2 = layer(&map, "M2");
+        assert_eq!(m2.blocked, [0.5, 0.0, 0.0, 0.0, 0.0, 0.5, 0.5, 0.0]);
+        assert_eq!(m2.count_at_least(0.5), 3);
+        assert_eq!(layer(&map, "M3").mean(), 0.0);
+        let placement = layer(&map, PLACEMENT);
+        assert_eq!(placement.blocked, [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_default_grid_without_gcellgrid() {
+        let mut def = design();
+        def.g_cell_grid_x.clear();
+        def.g_cell_grid_y.clear();
+        let grid = GcellGrid::from_def(&def, 1000.0).unwrap();
+        assert_eq!(grid.columns(), DEFAULT_GCELLS);
+        assert_eq!(grid.rows(), DEFAULT_GCELLS / 2);
+        assert_eq!(*grid.x_edges.last().unwrap(), 40.0);
+
+        // Layers are taken from the shapes when no routing layers are given
+        let macro_def = sram();
+        let macros = HashMap::from([("SRAM", &macro_def)]);
+        let map = ResourceMap::compute(&def, 1000.0, &macros, &[]).unwrap();
+        let names: Vec<_> = map.layers.iter().map(|l| l.layer.as_str()).collect();
+        assert_eq!(names, ["M1", "M2"]);
+
+        def.die_area_points.clear();
+        assert!(GcellGrid::from_def(&def, 1000.0).is_none());
+    }
+}
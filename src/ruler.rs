@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Ruler
+//!
+//! In ruler mode the first click on the canvas starts a measurement and the
+//! second one ends it; the dimension line follows the cursor in between. A
+//! third click starts over. Points optionally snap to the nearest shape edge
+//! or routing track within a few screen pixels, each axis on its own, so a
+//! measurement from edge to edge reads the exact spacing.
+//!
+//! All coordinates are in microns with Y pointing up.
+
+use crate::def::DefTrack;
+use crate::geometry::Bounds;
+
+/// Distance in screen points within which a point snaps
+pub const SNAP_DISTANCE: f32 = 8.0;
+
+/// Ruler mode state
+pub struct Ruler {
+    /// Whether clicks on the canvas place ruler points
+    pub active: bool,
+    pub snap_edges: bool,
+    pub snap_tracks: bool,
+    start: Option<(f64, f64)>,
+    end: Option<(f64, f64)>,
+}
+
+impl Default for Ruler {
+    fn default() -> Self {
+        Self {
+            active: false,
+            snap_edges: true,
+            snap_tracks: false,
+            start: None,
+            end: None,
+        }
+    }
+}
+
+impl Ruler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Turn ruler mode on or off, dropping the measurement when turned off
+    pub fn toggle(&mut self) {
+        self.active = !self.active;
+        if !self.active {
+            self.clear();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.start = None;
+        self.end = None;
+    }
+
+    /// Place the next point of the measurement
+    pub fn click(&mut self, point: (f64, f64)) {
+        match (self.start, self.end) {
+            (Some(_), None) => self.end = Some(point),
+            _ => {
+                self.start = Some(point);
+                self.end = None;
+            }
+        }
+    }
+
+    /// Ends of the measurement, the second one following `cursor` until it
+    /// is placed
+    pub fn segment(&self, cursor: Option<(f64, f64)>) -> Option<((f64, f64), (f64, f64))> {
+        Some((self.start?, self.end.or(cursor)?))
+    }
+}
+
+/// Distance between two points
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub dx: f64,
+    pub dy: f64,
+    pub distance: f64,
+}
+
+impl Measurement {
+    pub fn between(start: (f64, f64), end: (f64, f64)) -> Self {
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        Self {
+            dx,
+            dy,
+            distance: dx.hypot(dy),
+        }
+    }
+}
+
+/// Move `point` onto the nearest shape edge or track within `tolerance`,
+/// separately for X and Y
+///
+/// Tracks are DEF TRACKS in database units; `tracks_x` holds the vertical
+/// tracks that give X positions.
+pub fn snap_point(
+    point: (f64, f64),
+    shapes: &[Bounds],
+    tracks: (&[DefTrack], &[DefTrack]),
+    db_units: f64,
+    tolerance: f64,
+) -> (f64, f64) {
+    let edges_x = shapes.iter().flat_map(|b| [b.0, b.2]);
+    let edges_y = shapes.iter().flat_map(|b| [b.1, b.3]);
+    let tracks_x = tracks
+        .0
+        .iter()
+        .filter_map(|t| nearest_track(point.0, t, db_units));
+    let tracks_y = tracks
+        .1
+        .iter()
+        .filter_map(|t| nearest_track(point.1, t, db_units));
+    (
+        snap_value(point.0, edges_x.chain(tracks_x), tolerance),
+        snap_value(point.1, edges_y.chain(tracks_y), tolerance),
+    )
+}
+
+/// Nearest candidate within `tolerance` of `value`, `value` if there is none
+fn snap_value(value: f64, candidates: impl Iterator<Item = f64>, tolerance: f64) -> f64 {
+    candidates
+        .filter(|c| (c - value).abs() <= tolerance)
+        .min_by(|a, b| (a - value).abs().total_cmp(&(b - value).abs()))
+        .unwrap_or(value)
+}
+
+/// Coordinate in microns of the track of `track` nearest to `value`
+fn nearest_track(value: f64, track: &DefTrack, db_units: f64) -> Option<f64> {
+    if track.num <= 0 || track.step <= 0.0 {
+        return None;
+    }
+    let step = track.step / db_units;
+    let offset = track.offset / db_units;
+    let index = ((value - offset) / step)
+        .round()
+        .clamp(0.0, (track.num - 1) as f64);
+    Some(offset + index * step)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(offset: f64, num: i32, step: f64) -> DefTrack {
+        DefTrack {
+            layer: "M1".to_string(),
+            offset,
+            num,
+            step,
+        }
+    }
+
+    #[test]
+    fn test_ruler_clicks() {
+        let mut ruler = Ruler::new();
+        assert_eq!(ruler.segment(Some((1.0, 1.0))), None);
+        ruler.click((0.0, 0.0));
+        assert_eq!(
+            ruler.segment(Some((3.0, 4.0))),
+            Some(((0.0, 0.0), (3.0, 4.0)))
+        );
+        ruler.click((3.0, 4.0));
+        assert_eq!(ruler.segment(None), Some(((0.0, 0.0), (3.0, 4.0))));
+        let measurement = Measurement::between((0.0, 0.0), (3.0, -4.0));
+        assert_eq!(
+            (measurement.dx, measurement.dy, measurement.distance),
+            (3.0, -4.0, 5.0)
+        );
+
+        // A third click starts over
+        ruler.click((10.0, 10.0));
+        assert_eq!(ruler.segment(None), None);
+    }
+
+    #[test]
+    fn test_snap_point() {
+        let shapes = [(1.0, 2.0, 3.0, 4.0)];
+        let no_tracks: (&[DefTrack], &[DefTrack]) = (&[], &[]);
+        assert_eq!(
+            snap_point((1.05, 3.95), &shapes, no_tracks, 1000.0, 0.1),
+            (1.0, 4.0)
+        );
+        // Each axis snaps on its own
+        assert_eq!(
+            snap_point((2.0, 2.05), &shapes, no_tracks, 1000.0, 0.1),
+            (2.0, 2.0)
+        );
+
+        // Vertical tracks every 0.2 um from 0.1 um, ten of them
+        let tracks_x = [track(100.0, 10, 200.0)];
+        let tracks = (&tracks_x[..], &[][..]);
+        let (x, y) = snap_point((0.68, 9.0), &[], tracks, 1000.0, 0.05);
+        assert!((x - 0.7).abs() < 1e-9);
+        assert_eq!(y, 9.0);
+        // Beyond the last track
+        assert_eq!(snap_point((5.0, 0.0), &[], tracks, 1000.0, 0.05).0, 5.0);
+    }
+}
@@ -0,0 +1,504 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Rhai scripts over the loaded design
+//!
+//! A script reads the design through `components()`, `nets()` and
+//! `macros()`, arrays of object maps with coordinates in microns, and acts
+//! on the viewer through the functions below. Scripts do not touch the
+//! viewer while they run: every call is recorded as a [`ScriptAction`] that
+//! the viewer applies once the script finished without error.
+//!
+//! | Function | Action |
+//! |---|---|
+//! | `select_components(list)` | add components to the selection |
+//! | `select_nets(list)` | add nets to the selection |
+//! | `clear_selection()` | deselect everything |
+//! | `color_components(list, "#rrggbb")` | fill components with a color |
+//! | `clear_colors()` | remove the colors of earlier scripts |
+//! | `export_placement_csv(list)` | save the placement of components as CSV |
+//!
+//! A list holds names or the object maps of `components()` and `nets()`.
+//! [`SNIPPETS`] are the examples of the snippet library.
+
+use crate::def::Def;
+use crate::lef::LefMacro;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Operations after which a script is stopped, so an endless loop does not
+/// hang the viewer
+const MAX_OPERATIONS: u64 = 50_000_000;
+
+/// What a script asked the viewer to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptAction {
+    SelectComponents(Vec<String>),
+    SelectNets(Vec<String>),
+    ClearSelection,
+    /// Components and their fill color
+    ColorComponents(Vec<String>, [u8; 3]),
+    ClearColors,
+    /// Components whose placement is saved as CSV
+    ExportPlacementCsv(Vec<String>),
+}
+
+/// Result of a script that ran to the end
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScriptOutput {
+    /// Lines of `print` and `debug`
+    pub printed: Vec<String>,
+    pub actions: Vec<ScriptAction>,
+}
+
+/// Placed component as scripts see it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptComponent {
+    pub name: String,
+    pub cell: String,
+    /// Location in microns, 0 when unplaced
+    pub x: f64,
+    pub y: f64,
+    pub orient: String,
+    pub status: String,
+}
+
+/// Net as scripts see it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptNet {
+    pub name: String,
+    /// USE of the net, empty when not given
+    pub use_type: String,
+    pub connections: usize,
+}
+
+/// LEF macro as scripts see it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptMacro {
+    pub name: String,
+    pub class: String,
+    /// Size in microns
+    pub width: f64,
+    pub height: f64,
+    pub pins: usize,
+}
+
+/// Snapshot of the loaded design a script runs on
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScriptDesign {
+    pub components: Vec<ScriptComponent>,
+    pub nets: Vec<ScriptNet>,
+    pub macros: Vec<ScriptMacro>,
+}
+
+impl ScriptDesign {
+    /// Snapshot of `def`, with `db_units` per micron, and the LEF `macros`
+    pub fn new<'a>(
+        def: Option<&Def>,
+        db_units: f64,
+        macros: impl IntoIterator<Item = &'a LefMacro>,
+    ) -> Self {
+        let components = def
+            .map(|def| {
+                def.components
+                    .iter()
+                    .map(|component| {
+                        let placement = component.placement.as_ref();
+                        ScriptComponent {
+                            name: component.name.clone(),
+                            cell: component.macro_name.clone(),
+                            x: placement.map_or(0.0, |p| p.x / db_units),
+                            y: placement.map_or(0.0, |p| p.y / db_units),
+                            orient: placement.map(|p| p.orientation.clone()).unwrap_or_default(),
+                            status: placement
+                                .map_or("UNPLACED", |p| p.placement_type.as_str())
+                                .to_string(),
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let nets = def
+            .map(|def| {
+                def.nets
+                    .iter()
+                    .map(|net| ScriptNet {
+                        name: net.name.clone(),
+                        use_type: net.use_type.clone(),
+                        connections: net.connections,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let macros = macros
+            .into_iter()
+            .map(|macro_def| ScriptMacro {
+                name: macro_def.name.clone(),
+                class: macro_def.class.clone(),
+                width: macro_def.size_x,
+                height: macro_def.size_y,
+                pins: macro_def.pins.len(),
+            })
+            .collect();
+        Self {
+            components,
+            nets,
+            macros,
+        }
+    }
+}
+
+/// Run `code` on `design`
+///
+/// The error names the line and position the script failed at.
+pub fn run(code: &str, design: &ScriptDesign) -> Result<ScriptOutput, String> {
+    let output = Rc::new(RefCell::new(ScriptOutput::default()));
+    let design = Rc::new(design.clone());
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+
+    let printed = Rc::clone(&output);
+    engine.on_print(move |text| printed.borrow_mut().printed.push(text.to_string()));
+    let printed = Rc::clone(&output);
+    engine.on_debug(move |text, _, _| printed.borrow_mut().printed.push(text.to_string()));
+
+    let source = Rc::clone(&design);
+    engine.register_fn("components", move || -> Array {
+        source.components.iter().map(component_map).collect()
+    });
+    let source = Rc::clone(&design);
+    engine.register_fn("nets", move || -> Array {
+        source.nets.iter().map(net_map).collect()
+    });
+    let source = Rc::clone(&design);
+    engine.register_fn("macros", move || -> Array {
+        source.macros.iter().map(macro_map).collect()
+    });
+
+    let actions = Rc::clone(&output);
+    engine.register_fn("select_components", move |list: Array| -> ScriptResult {
+        let names = names(list)?;
+        push(&actions, ScriptAction::SelectComponents(names))
+    });
+    let actions = Rc::clone(&output);
+    engine.register_fn("select_nets", move |list: Array| -> ScriptResult {
+        let names = names(list)?;
+        push(&actions, ScriptAction::SelectNets(names))
+    });
+    let actions = Rc::clone(&output);
+    engine.register_fn("clear_selection", move || -> ScriptResult {
+        push(&actions, ScriptAction::ClearSelection)
+    });
+    let actions = Rc::clone(&output);
+    engine.register_fn(
+        "color_components",
+        move |list: Array, color: &str| -> ScriptResult {
+            let names = names(list)?;
+            let color =
+                parse_color(color).ok_or_else(|| format!("Not a #rrggbb color: {color}"))?;
+            push(&actions, ScriptAction::ColorComponents(names, color))
+        },
+    );
+    let actions = Rc::clone(&output);
+    engine.register_fn("clear_colors", move || -> ScriptResult {
+        push(&actions, ScriptAction::ClearColors)
+    });
+    let actions = Rc::clone(&output);
+    engine.register_fn("export_placement_csv", move |list: Array| -> ScriptResult {
+        let names = names(list)?;
+        push(&actions, ScriptAction::ExportPlacementCsv(names))
+    });
+
+    engine.run(code).map_err(|e| e.to_string())?;
+    drop(engine);
+    let output = output.borrow().clone();
+    Ok(output)
+}
+
+type ScriptResult = Result<(), Box<EvalAltResult>>;
+
+fn push(output: &RefCell<ScriptOutput>, action: ScriptAction) -> ScriptResult {
+    output.borrow_mut().actions.push(action);
+    Ok(())
+}
+
+/// Names of a list of names or of object maps with a `name`
+fn names(list: Array) -> Result<Vec<String>, Box<EvalAltResult>> {
+    list.into_iter()
+        .map(|item| {
+            if item.is_map() {
+                let map = item.cast::<Map>();
+                map.get("name")
+                    .and_then(|name| name.clone().into_string().ok())
+                    .ok_or_else(|| "Object map without a name".into())
+            } else {
+                item.into_string()
+                    .map_err(|kind| format!("Expected a name or an object map, got {kind}").into())
+            }
+        })
+        .collect()
+}
+
+/// `#rrggbb` as red, green and blue
+fn parse_color(color: &str) -> Option<[u8; 3]> {
+    let hex = color.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+fn component_map(component: &ScriptComponent) -> Dynamic {
+    let mut map = Map::new();
+    map.insert("name".into(), component.name.clone().into());
+    map.insert("cell".into(), component.cell.clone().into());
+    map.insert("x".into(), component.x.into());
+    map.insert("y".into(), component.y.into());
+    map.insert("orient".into(), component.orient.clone().into());
+    map.insert("status".into(), component.status.clone().into());
+    map.into()
+}
+
+fn net_map(net: &ScriptNet) -> Dynamic {
+    let mut map = Map::new();
+    map.insert("name".into(), net.name.clone().into());
+    map.insert("use_type".into(), net.use_type.clone().into());
+    map.insert("connections".into(), (net.connections as i64).into());
+    map.into()
+}
+
+fn macro_map(macro_def: &ScriptMacro) -> Dynamic {
+    let mut map = Map::new();
+    map.insert("name".into(), macro_def.name.clone().into());
+    map.insert("class".into(), macro_def.class.clone().into());
+    map.insert("width".into(), macro_def.width.into());
+    map.insert("height".into(), macro_def.height.into());
+    map.insert("pins".into(), (macro_def.pins as i64).into());
+    map.into()
+}
+
+/// Example of the snippet library
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snippet {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub code: &'static str,
+}
+
+/// Examples shipped with the viewer, runnable as they are
+pub const SNIPPETS: &[Snippet] = &[
+    Snippet {
+        name: "Select clock nets",
+        description: "Nets with USE CLOCK or a clock-like name",
+        code: r#"let clocks = nets().filter(|net| {
+    let name = net.name.to_lower();
+    net.use_type == "CLOCK" || name.contains("clk") || name.contains("clock")
+});
+select_nets(clocks);
+print(`${clocks.len()} clock nets selected`);
+"#,
+    },
+    Snippet {
+        name: "Export filtered CSV",
+        description: "Placement of the flip-flops, by cell name, as CSV",
+        code: r#"let flops = components().filter(|c| {
+    let cell = c.cell.to_upper();
+    cell.contains("DFF") || cell.contains("LATCH")
+});
+print(`${flops.len()} sequential cells`);
+export_placement_csv(flops);
+"#,
+    },
+    Snippet {
+        name: "Color by hierarchy",
+        description: "One color per top-level hierarchy of the instance names",
+        code: r##"let palette = ["#e6194b", "#3cb44b", "#4363d8", "#f58231", "#911eb4",
+               "#46f0f0", "#f032e6", "#bcf60c", "#fabebe", "#008080"];
+let groups = #{};
+for c in components() {
+    let parts = c.name.split("/");
+    let top = if parts.len() > 1 { parts[0] } else { "(top)" };
+    if top in groups {
+        groups[top].push(c.name);
+    } else {
+        groups[top] = [c.name];
+    }
+}
+clear_colors();
+let i = 0;
+for top in groups.keys() {
+    let color = palette[i % palette.len()];
+    color_components(groups[top], color);
+    print(`${top}: ${groups[top].len()} instances in ${color}`);
+    i += 1;
+}
+"##,
+    },
+    Snippet {
+        name: "Count cells by macro",
+        description: "Print how often each macro is placed, most used first",
+        code: r#"let counts = #{};
+for c in components() {
+    counts[c.cell] = (counts[c.cell] ?? 0) + 1;
+}
+let cells = counts.keys();
+cells.sort(|a, b| counts[b] - counts[a]);
+for cell in cells {
+    print(`${cell}: ${counts[cell]}`);
+}
+"#,
+    },
+    Snippet {
+        name: "Select large macros",
+        description: "Components of BLOCK macros or macros over 100 um on a side",
+        code: r#"let large = #{};
+for m in macros() {
+    if m.class.starts_with("BLOCK") || m.width > 100.0 || m.height > 100.0 {
+        large[m.name] = true;
+    }
+}
+let picked = components().filter(|c| c.cell in large);
+select_components(picked);
+print(`${picked.len()} components of ${large.len()} large macros`);
+"#,
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn design() -> ScriptDesign {
+        let component = |name: &str, cell: &str| ScriptComponent {
+            name: name.to_string(),
+            cell: cell.to_string(),
+            x: 1.0,
+            y: 2.0,
+            orient: "N".to_string(),
+            status: "PLACED".to_string(),
+        };
+        let net = |name: &str, use_type: &str| ScriptNet {
+            name: name.to_string(),
+            use_type: use_type.to_string(),
+            connections: 2,
+        };
+        ScriptDesign {
+            components: vec![
+                component("core/u1", "DFFX1"),
+                component("core/u2", "INVX1"),
+                component("io/u3", "DFFX1"),
+                component("u4", "RAM64"),
+            ],
+            nets: vec![
+                net("core_clk", ""),
+                net("ref", "CLOCK"),
+                net("data", "SIGNAL"),
+            ],
+            macros: vec![ScriptMacro {
+                name: "RAM64".to_string(),
+                class: "BLOCK".to_string(),
+                width: 120.0,
+                height: 80.0,
+                pins: 40,
+            }],
+        }
+    }
+
+    fn snippet(name: &str) -> &'static str {
+        SNIPPETS.iter().find(|s| s.name == name).unwrap().code
+    }
+
+    fn strings(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_actions_and_print() {
+        let output = run(
+            r#"
+            print(components().len());
+            select_components(["u4"]);
+            select_nets(nets().filter(|n| n.use_type == "SIGNAL"));
+            clear_selection();
+            "#,
+            &design(),
+        )
+        .unwrap();
+        assert_eq!(output.printed, ["4"]);
+        assert_eq!(
+            output.actions,
+            [
+                ScriptAction::SelectComponents(strings(&["u4"])),
+                ScriptAction::SelectNets(strings(&["data"])),
+                ScriptAction::ClearSelection,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_errors_name_the_line() {
+        let error = run("let a = 1;\nselect_nets([1]);", &design()).unwrap_err();
+        assert!(error.contains("line 2"), "{error}");
+        let error = run("color_components([], \"red\");", &design()).unwrap_err();
+        assert!(error.contains("#rrggbb"), "{error}");
+        assert!(run("loop {}", &design()).is_err());
+    }
+
+    #[test]
+    fn test_snippets_run() {
+        for snippet in SNIPPETS {
+            run(snippet.code, &design()).unwrap_or_else(|e| panic!("{}: {e}", snippet.name));
+            run(snippet.code, &ScriptDesign::default())
+                .unwrap_or_else(|e| panic!("{} without a design: {e}", snippet.name));
+        }
+
+        let clocks = run(snippet("Select clock nets"), &design()).unwrap();
+        assert_eq!(
+            clocks.actions,
+            [ScriptAction::SelectNets(strings(&["core_clk", "ref"]))]
+        );
+
+        let csv = run(snippet("Export filtered CSV"), &design()).unwrap();
+        assert_eq!(
+            csv.actions,
+            [ScriptAction::ExportPlacementCsv(strings(&[
+                "core/u1", "io/u3"
+            ]))]
+        );
+
+        let colors = run(snippet("Color by hierarchy"), &design()).unwrap();
+        assert_eq!(colors.actions[0], ScriptAction::ClearColors);
+        let mut groups: Vec<Vec<String>> = colors.actions[1..]
+            .iter()
+            .map(|action| match action {
+                ScriptAction::ColorComponents(names, _) => names.clone(),
+                other => panic!("unexpected {other:?}"),
+            })
+            .collect();
+        groups.sort();
+        assert_eq!(
+            groups,
+            [
+                strings(&["core/u1", "core/u2"]),
+                strings(&["io/u3"]),
+                strings(&["u4"])
+            ]
+        );
+
+        let large = run(snippet("Select large macros"), &design()).unwrap();
+        assert_eq!(
+            large.actions,
+            [ScriptAction::SelectComponents(strings(&["u4"]))]
+        );
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("#ff8000"), Some([255, 128, 0]));
+        assert_eq!(parse_color("ff8000"), None);
+        assert_eq!(parse_color("#ff80"), None);
+        assert_eq!(parse_color("#gg8000"), None);
+    }
+}
@@ -0,0 +1,181 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Scripting Console
+//!
+//! Docked editor for Rhai scripts over the loaded design, see
+//! [`crate::script`], with the output of the last run below it. The snippet
+//! library lists the examples shipped with the viewer: each runs as it is,
+//! opens in the editor to be changed, or is copied to the clipboard. The
+//! panel docks to the right or to the bottom of the window.
+
+use crate::script::{ScriptOutput, SNIPPETS};
+use eframe::egui;
+
+/// Where the console is docked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConsoleDock {
+    #[default]
+    Right,
+    Bottom,
+}
+
+/// Scripting console state and rendering
+#[derive(Default)]
+pub struct ScriptConsole {
+    /// Whether the console is currently shown
+    pub visible: bool,
+    dock: ConsoleDock,
+    code: String,
+    /// Printed lines of the last run
+    printed: Vec<String>,
+    /// Summary or error of the last run
+    status: Option<Result<String, String>>,
+}
+
+impl ScriptConsole {
+    /// Create a new console with an empty editor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show the result of the script the console asked to run
+    pub fn set_result(&mut self, result: Result<ScriptOutput, String>) {
+        match result {
+            Ok(output) => {
+                self.status = Some(Ok(format!(
+                    "Finished, {} actions applied",
+                    output.actions.len()
+                )));
+                self.printed = output.printed;
+            }
+            Err(error) => {
+                self.status = Some(Err(error));
+                self.printed.clear();
+            }
+        }
+    }
+
+    /// Render the docked console, returning the code to run when Run was
+    /// clicked in the editor or the snippet library
+    ///
+    /// Must be called before the central panel is shown.
+    pub fn render(&mut self, ctx: &egui::Context) -> Option<String> {
+        if !self.visible {
+            return None;
+        }
+
+        let mut run = None;
+        match self.dock {
+            ConsoleDock::Right => {
+                egui::SidePanel::right("script_console")
+                    .resizable(true)
+                    .default_width(380.0)
+                    .show(ctx, |ui| run = self.render_contents(ui));
+            }
+            ConsoleDock::Bottom => {
+                egui::TopBottomPanel::bottom("script_console_bottom")
+                    .resizable(true)
+                    .default_height(260.0)
+                    .show(ctx, |ui| run = self.render_contents(ui));
+            }
+        }
+        run
+    }
+
+    fn render_contents(&mut self, ui: &mut egui::Ui) -> Option<String> {
+        let mut run = None;
+        ui.horizontal(|ui| {
+            ui.heading("Scripting Console");
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.small_button("X").on_hover_text("Close").clicked() {
+                    self.visible = false;
+                }
+                let (label, other) = match self.dock {
+                    ConsoleDock::Right => ("Dock Bottom", ConsoleDock::Bottom),
+                    ConsoleDock::Bottom => ("Dock Right", ConsoleDock::Right),
+                };
+                if ui.small_button(label).clicked() {
+                    self.dock = other;
+                }
+            });
+        });
+
+        egui::CollapsingHeader::new("Snippet Library")
+            .default_open(true)
+            .show(ui, |ui| {
+                for snippet in SNIPPETS {
+                    ui.horizontal(|ui| {
+                        if ui.small_button("Run").clicked() {
+                            run = Some(snippet.code.to_string());
+                        }
+                        if ui
+                            .small_button("Edit")
+                            .on_hover_text("Open in the editor")
+                            .clicked()
+                        {
+                            self.code = snippet.code.to_string();
+                        }
+                        if ui.small_button("Copy").clicked() {
+                            ui.ctx().copy_text(snippet.code.to_string());
+                        }
+                        ui.label(snippet.name).on_hover_text(snippet.description);
+                    });
+                }
+            });
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!self.code.trim().is_empty(), egui::Button::new("Run"))
+                .on_hover_text("Ctrl+Enter in the editor")
+                .clicked()
+            {
+                run = Some(self.code.clone());
+            }
+            if ui.button("Clear").clicked() {
+                self.code.clear();
+            }
+        });
+        let editor = egui::ScrollArea::vertical()
+            .id_salt("script_editor")
+            .max_height(240.0)
+            .show(ui, |ui| {
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.code)
+                        .code_editor()
+                        .desired_rows(10)
+                        .desired_width(f32::INFINITY)
+                        .hint_text("select_nets(nets().filter(|n| n.use_type == \"CLOCK\"));"),
+                )
+            })
+            .inner;
+        if editor.has_focus()
+            && ui.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::Enter))
+        {
+            run = Some(self.code.clone());
+        }
+
+        ui.separator();
+        match &self.status {
+            Some(Ok(summary)) => {
+                ui.label(summary);
+            }
+            Some(Err(error)) => {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+            None => {
+                ui.label("Run a script or a snippet to see its output here.");
+            }
+        }
+        egui::ScrollArea::vertical()
+            .id_salt("script_output")
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                for line in &self.printed {
+                    ui.monospace(line);
+                }
+            });
+        run
+    }
+}
@@ -0,0 +1,311 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Search Bar
+//!
+//! Ctrl+F focuses a search box in the menu bar. An `x,y` pair in microns
+//! centers the canvas on that point. Anything else is matched against the
+//! names of the go to dialog: plain text as a case-insensitive substring,
+//! text with `*` or `?` as a wildcard over the whole name and `/text/` as a
+//! regular expression.
+
+use crate::goto_dialog::GotoTarget;
+use crate::identifier;
+use eframe::egui;
+use regex::Regex;
+
+/// Results listed below the box
+const MAX_RESULTS: usize = 50;
+
+/// Parsed search text
+#[derive(Debug, Clone)]
+pub enum SearchQuery {
+    /// Coordinate in microns
+    Point(f64, f64),
+    Substring(String),
+    Wildcard(String),
+    Regex(Regex),
+}
+
+impl SearchQuery {
+    /// Parse the search text, None when it is empty
+    pub fn parse(text: &str) -> Result<Option<Self>, String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        if let Some(point) = parse_point(text) {
+            return Ok(Some(SearchQuery::Point(point.0, point.1)));
+        }
+        if let Some(pattern) = text
+            .strip_prefix('/')
+            .and_then(|rest| rest.strip_suffix('/'))
+            .filter(|pattern| !pattern.is_empty())
+        {
+            return Regex::new(pattern)
+                .map(|regex| Some(SearchQuery::Regex(regex)))
+                .map_err(|e| format!("Invalid regular expression: {e}"));
+        }
+        if text.contains(['*', '?']) {
+            return Ok(Some(SearchQuery::Wildcard(text.to_lowercase())));
+        }
+        Ok(Some(SearchQuery::Substring(text.to_string())))
+    }
+
+    /// Whether an object name matches, false for a coordinate
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            SearchQuery::Point(..) => false,
+            SearchQuery::Substring(query) => identifier::matches_query(name, query),
+            SearchQuery::Wildcard(pattern) => {
+                identifier::wildcard_match(pattern, &identifier::unescape(name).to_lowercase())
+            }
+            SearchQuery::Regex(regex) => regex.is_match(&identifier::unescape(name)),
+        }
+    }
+}
+
+/// `x,y` with optional parentheses and spaces
+fn parse_point(text: &str) -> Option<(f64, f64)> {
+    let inner = text
+        .strip_prefix('(')
+        .and_then(|rest| rest.strip_suffix(')'))
+        .unwrap_or(text);
+    let (x, y) = inner.split_once(',')?;
+    let x: f64 = x.trim().parse().ok()?;
+    let y: f64 = y.trim().parse().ok()?;
+    (x.is_finite() && y.is_finite()).then_some((x, y))
+}
+
+/// What the viewer should jump to
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchAction {
+    /// Center on a point in microns
+    Point(f64, f64),
+    Object(GotoTarget),
+}
+
+/// Search box state and rendering
+#[derive(Default)]
+pub struct SearchBar {
+    text: String,
+    query: Option<SearchQuery>,
+    error: Option<String>,
+    matches: Vec<GotoTarget>,
+    /// Matches beyond the listed ones
+    more: usize,
+    highlighted: usize,
+    results_open: bool,
+    focus_requested: bool,
+}
+
+impl SearchBar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move the keyboard focus into the box on the next frame
+    pub fn focus(&mut self) {
+        self.focus_requested = true;
+    }
+
+    fn update_matches(&mut self, targets: Vec<GotoTarget>) {
+        self.matches.clear();
+        self.more = 0;
+        self.highlighted = 0;
+        match SearchQuery::parse(&self.text) {
+            Ok(query) => {
+                self.error = None;
+                self.query = query;
+            }
+            Err(e) => {
+                self.error = Some(e);
+                self.query = None;
+            }
+        }
+        let Some(query) = &self.query else {
+            return;
+        };
+        let mut matches: Vec<GotoTarget> = targets
+            .into_iter()
+            .filter(|t| query.matches(&t.name))
+            .collect();
+        // Exact names first, then shorter and alphabetical names
+        let exact = self.text.trim().to_lowercase();
+        matches.sort_by(|a, b| {
+            let rank = |t: &GotoTarget| identifier::unescape(&t.name).to_lowercase() != exact;
+            rank(a)
+                .cmp(&rank(b))
+                .then(a.name.len().cmp(&b.name.len()))
+                .then(a.name.cmp(&b.name))
+        });
+        self.more = matches.len().saturating_sub(MAX_RESULTS);
+        matches.truncate(MAX_RESULTS);
+        self.matches = matches;
+    }
+
+    /// Render the box into the menu bar, `targets` lists the searchable
+    /// objects and is only called when the text changes
+    pub fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        targets: impl FnOnce() -> Vec<GotoTarget>,
+    ) -> Option<SearchAction> {
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut self.text)
+                .hint_text("Search name or x,y (Ctrl+F)")
+                .desired_width(220.0),
+        );
+        if std::mem::take(&mut self.focus_requested) {
+            response.request_focus();
+        }
+        if response.changed() {
+            self.update_matches(targets());
+            self.results_open = true;
+        }
+        if response.gained_focus() {
+            self.results_open = true;
+        }
+
+        let mut action = None;
+        if response.has_focus() {
+            let (up, down) = ui.input(|i| {
+                (
+                    i.key_pressed(egui::Key::ArrowUp),
+                    i.key_pressed(egui::Key::ArrowDown),
+                )
+            });
+            if down && self.highlighted + 1 < self.matches.len() {
+                self.highlighted += 1;
+            }
+            if up {
+                self.highlighted = self.highlighted.saturating_sub(1);
+            }
+        }
+        if response.lost_focus() {
+            let (enter, escape) = ui.input(|i| {
+                (
+                    i.key_pressed(egui::Key::Enter),
+                    i.key_pressed(egui::Key::Escape),
+                )
+            });
+            if enter {
+                action = match &self.query {
+                    Some(SearchQuery::Point(x, y)) => Some(SearchAction::Point(*x, *y)),
+                    _ => self
+                        .matches
+                        .get(self.highlighted)
+                        .cloned()
+                        .map(SearchAction::Object),
+                };
+            }
+            if enter || escape {
+                self.results_open = false;
+            }
+        }
+
+        if self.results_open && !self.text.trim().is_empty() {
+            let area = egui::Area::new(ui.id().with("search_results"))
+                .order(egui::Order::Foreground)
+                .fixed_pos(response.rect.left_bottom())
+                .show(ui.ctx(), |ui| {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.set_width(response.rect.width().max(280.0));
+                        self.render_results(ui, &mut action);
+                    });
+                });
+            if response.clicked_elsewhere() && area.response.clicked_elsewhere() {
+                self.results_open = false;
+            }
+        }
+        if action.is_some() {
+            self.results_open = false;
+        }
+        action
+    }
+
+    fn render_results(&self, ui: &mut egui::Ui, action: &mut Option<SearchAction>) {
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::from_rgb(244, 67, 54), error);
+            return;
+        }
+        if let Some(SearchQuery::Point(x, y)) = &self.query {
+            if ui
+                .selectable_label(true, format!("Go to ({x:.3}, {y:.3}) um"))
+                .clicked()
+            {
+                *action = Some(SearchAction::Point(*x, *y));
+            }
+            return;
+        }
+        if self.matches.is_empty() {
+            ui.label("No matching objects");
+            return;
+        }
+        egui::ScrollArea::vertical()
+            .max_height(320.0)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                for (row, target) in self.matches.iter().enumerate() {
+                    let (letter, color) = target.kind.icon();
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(letter)
+                                .monospace()
+                                .strong()
+                                .color(egui::Color32::WHITE)
+                                .background_color(color),
+                        );
+                        let label = identifier::unescape(&target.name);
+                        let response = ui.selectable_label(row == self.highlighted, label.as_ref());
+                        if row == self.highlighted {
+                            response.scroll_to_me(None);
+                        }
+                        if response.clicked() {
+                            *action = Some(SearchAction::Object(target.clone()));
+                        }
+                    });
+                }
+                if self.more > 0 {
+                    ui.label(egui::RichText::new(format!("... and {} more", self.more)).weak());
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        assert!(matches!(
+            SearchQuery::parse(" 12.5, -3 "),
+            Ok(Some(SearchQuery::Point(x, y))) if x == 12.5 && y == -3.0
+        ));
+        assert!(matches!(
+            SearchQuery::parse("(100,200)"),
+            Ok(Some(SearchQuery::Point(..)))
+        ));
+        assert!(matches!(SearchQuery::parse("  "), Ok(None)));
+        assert!(SearchQuery::parse("/reg[/").is_err());
+
+        let substring = SearchQuery::parse("REG[3]").unwrap().unwrap();
+        assert!(substring.matches(r"\u_top/reg\[3\]"));
+        assert!(!substring.matches("u_top/reg_4"));
+
+        let wildcard = SearchQuery::parse("U_TOP/*_3").unwrap().unwrap();
+        assert!(wildcard.matches("u_top/inv_3"));
+        assert!(!wildcard.matches("u_top/inv_30"));
+
+        let regex = SearchQuery::parse(r"/^clk_\d+$/").unwrap().unwrap();
+        assert!(regex.matches("clk_12"));
+        assert!(!regex.matches("clk_12_buf"));
+
+        // A comma in a name is not a coordinate
+        assert!(matches!(
+            SearchQuery::parse("a,b"),
+            Ok(Some(SearchQuery::Substring(_)))
+        ));
+    }
+}
@@ -0,0 +1,234 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Session state kept between runs
+//!
+//! On exit, and every few seconds while running, the viewer stores the
+//! paths of the loaded files, the view as in [`crate::permalink`], which
+//! panels are open and how wide, the mouse bindings and the recent files
+//! through eframe's persistence. The open states of collapsing headers are
+//! kept by egui in the same file.
+//! eframe writes them as RON to the platform data directory, for example
+//! `~/.local/share/lefdef-viewer/app.ron` on Linux.
+//!
+//! On the next start the files are loaded again, LEF before DEF so that
+//! components find their macros, and the view is applied once the last
+//! file is in. Files that were moved or deleted are skipped.
+
+use crate::export::{SpiceStubOptions, VerilogStubOptions};
+use crate::mouse_bindings::MouseBindings;
+use crate::permalink::ViewState;
+use crate::recent_files::RecentFiles;
+use crate::tcl_selection::TclDialect;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// eframe storage key of the session
+pub const STORAGE_KEY: &str = "session";
+
+/// Open panels and windows
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PanelLayout {
+    pub layers_panel: bool,
+    pub lef_details: bool,
+    pub def_details: bool,
+    pub component_table: bool,
+    pub checks_panel: bool,
+    pub issues_panel: bool,
+    pub charts_panel: bool,
+    pub resource_panel: bool,
+}
+
+impl Default for PanelLayout {
+    fn default() -> Self {
+        Self {
+            layers_panel: true,
+            lef_details: false,
+            def_details: false,
+            component_table: false,
+            checks_panel: false,
+            issues_panel: false,
+            charts_panel: false,
+            resource_panel: false,
+        }
+    }
+}
+
+/// Sizes of the docked panels and table columns, in points
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkspaceLayout {
+    pub left_panel_width: f32,
+    pub layers_panel_width: f32,
+    /// Component table column widths by column label
+    pub component_columns: BTreeMap<String, f32>,
+}
+
+impl Default for WorkspaceLayout {
+    fn default() -> Self {
+        Self {
+            left_panel_width: 300.0,
+            layers_panel_width: 250.0,
+            component_columns: BTreeMap::new(),
+        }
+    }
+}
+
+/// Everything restored on the next start
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// Whether to restore the files and view on the next start, the panel
+    /// layout and mouse bindings are restored either way
+    pub restore_files: bool,
+    pub lef_paths: Vec<String>,
+    pub def_path: Option<String>,
+    /// Camera, layers and selections, without file hashes
+    pub view: ViewState,
+    pub panels: PanelLayout,
+    pub workspace: WorkspaceLayout,
+    pub mouse_bindings: MouseBindings,
+    pub recent_files: RecentFiles,
+    /// Shell the selection is copied as Tcl for
+    pub tcl_dialect: TclDialect,
+    pub verilog_stub: VerilogStubOptions,
+    pub spice_stub: SpiceStubOptions,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            restore_files: true,
+            lef_paths: Vec::new(),
+            def_path: None,
+            view: ViewState::default(),
+            panels: PanelLayout::default(),
+            workspace: WorkspaceLayout::default(),
+            mouse_bindings: MouseBindings::default(),
+            recent_files: RecentFiles::default(),
+            tcl_dialect: TclDialect::default(),
+            verilog_stub: VerilogStubOptions::default(),
+            spice_stub: SpiceStubOptions::default(),
+        }
+    }
+}
+
+/// Next step of restoring a session
+#[derive(Debug, Clone, PartialEq)]
+pub enum RestoreStep {
+    LoadLefs(Vec<String>),
+    LoadDef(String),
+    ApplyView(Box<ViewState>),
+}
+
+/// Files and view of a session still to be restored
+#[derive(Debug)]
+pub struct SessionRestore {
+    lef_paths: Vec<String>,
+    def_path: Option<String>,
+    view: Option<ViewState>,
+    /// Paths that no longer exist
+    pub missing: Vec<String>,
+}
+
+impl SessionRestore {
+    /// Restore of the files and view of `session`, None when it has no
+    /// files or restoring files is turned off
+    pub fn new(session: &Session) -> Option<Self> {
+        if !session.restore_files || (session.lef_paths.is_empty() && session.def_path.is_none()) {
+            return None;
+        }
+        let mut missing = Vec::new();
+        let mut existing = |path: &String| {
+            let exists = Path::new(path).is_file();
+            if !exists {
+                missing.push(path.clone());
+            }
+            exists
+        };
+        let lef_paths = session
+            .lef_paths
+            .iter()
+            .filter(|p| existing(p))
+            .cloned()
+            .collect();
+        let def_path = session.def_path.clone().filter(|p| existing(p));
+        Some(Self {
+            lef_paths,
+            def_path,
+            view: Some(session.view.clone()),
+            missing,
+        })
+    }
+
+    /// Step to take once the previous one finished loading, None when the
+    /// session is restored
+    pub fn next_step(&mut self) -> Option<RestoreStep> {
+        if !self.lef_paths.is_empty() {
+            return Some(RestoreStep::LoadLefs(std::mem::take(&mut self.lef_paths)));
+        }
+        if let Some(path) = self.def_path.take() {
+            return Some(RestoreStep::LoadDef(path));
+        }
+        self.view
+            .take()
+            .map(|view| RestoreStep::ApplyView(Box::new(view)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_order() {
+        let dir = std::env::temp_dir().join("lefdef_viewer_session_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let lef = dir.join("cells.lef");
+        let def = dir.join("top.def");
+        std::fs::write(&lef, "").unwrap();
+        std::fs::write(&def, "").unwrap();
+        let lef = lef.to_string_lossy().to_string();
+        let def = def.to_string_lossy().to_string();
+        let gone = dir.join("gone.lef").to_string_lossy().to_string();
+
+        let session = Session {
+            lef_paths: vec![lef.clone(), gone.clone()],
+            def_path: Some(def.clone()),
+            view: ViewState {
+                zoom: 4.0,
+                ..ViewState::default()
+            },
+            ..Session::default()
+        };
+        let mut restore = SessionRestore::new(&session).unwrap();
+        assert_eq!(restore.missing, [gone]);
+        assert_eq!(restore.next_step(), Some(RestoreStep::LoadLefs(vec![lef])));
+        assert_eq!(restore.next_step(), Some(RestoreStep::LoadDef(def)));
+        assert!(matches!(
+            restore.next_step(),
+            Some(RestoreStep::ApplyView(view)) if view.zoom == 4.0
+        ));
+        assert_eq!(restore.next_step(), None);
+
+        // Nothing to restore
+        assert!(SessionRestore::new(&Session::default()).is_none());
+        let off = Session {
+            restore_files: false,
+            ..session
+        };
+        assert!(SessionRestore::new(&off).is_none());
+    }
+
+    #[test]
+    fn test_session_defaults_for_missing_fields() {
+        let session: Session = serde_json::from_str(r#"{"lef_paths":["a.lef"]}"#).unwrap();
+        assert_eq!(session.lef_paths, ["a.lef"]);
+        assert!(session.restore_files);
+        assert!(session.panels.layers_panel);
+        assert_eq!(session.workspace.left_panel_width, 300.0);
+        assert_eq!(session.mouse_bindings, MouseBindings::default());
+    }
+}
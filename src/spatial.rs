@@ -0,0 +1,207 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Static R-tree over bounding boxes
+//!
+//! The tree is bulk-loaded once with Sort-Tile-Recursive packing: boxes
+//! are sorted into vertical slabs by X, each slab into leaves by Y, and the
+//! leaves are packed the same way level by level. It is never modified, so
+//! a design that changes is indexed again from scratch.
+
+use crate::geometry::Bounds;
+
+/// Children per node
+const NODE_CAPACITY: usize = 16;
+
+#[derive(Debug, Clone)]
+struct Node {
+    bounds: Bounds,
+    /// First entry in `items` for leaves, in `children` otherwise
+    first: usize,
+    count: usize,
+    leaf: bool,
+}
+
+/// Packed R-tree answering box and point queries
+#[derive(Debug, Clone, Default)]
+pub struct RTree {
+    nodes: Vec<Node>,
+    children: Vec<usize>,
+    /// Boxes with their id, grouped by leaf
+    items: Vec<(Bounds, usize)>,
+    root: Option<usize>,
+}
+
+fn intersects(a: Bounds, b: Bounds) -> bool {
+    a.0 <= b.2 && b.0 <= a.2 && a.1 <= b.3 && b.1 <= a.3
+}
+
+fn union(entries: &[(Bounds, usize)]) -> Bounds {
+    entries.iter().fold(
+        (
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ),
+        |(ax, ay, bx, by), &((min_x, min_y, max_x, max_y), _)| {
+            (ax.min(min_x), ay.min(min_y), bx.max(max_x), by.max(max_y))
+        },
+    )
+}
+
+/// Sort-Tile-Recursive grouping of entries into nodes of `NODE_CAPACITY`
+fn tile(mut entries: Vec<(Bounds, usize)>) -> Vec<Vec<(Bounds, usize)>> {
+    let center_x = |b: &Bounds| b.0 + b.2;
+    let center_y = |b: &Bounds| b.1 + b.3;
+    let nodes = entries.len().div_ceil(NODE_CAPACITY);
+    let slabs = (nodes as f64).sqrt().ceil() as usize;
+    let slab_size = slabs.max(1) * NODE_CAPACITY;
+
+    entries.sort_by(|a, b| center_x(&a.0).total_cmp(&center_x(&b.0)));
+    let mut groups = Vec::with_capacity(nodes);
+    for slab in entries.chunks_mut(slab_size) {
+        slab.sort_by(|a, b| center_y(&a.0).total_cmp(&center_y(&b.0)));
+        groups.extend(slab.chunks(NODE_CAPACITY).map(<[_]>::to_vec));
+    }
+    groups
+}
+
+impl RTree {
+    /// Index `bounds`, each box identified by its position in the list
+    ///
+    /// Boxes with NaN coordinates are left out.
+    pub fn new(bounds: impl IntoIterator<Item = Bounds>) -> Self {
+        let entries: Vec<(Bounds, usize)> = bounds
+            .into_iter()
+            .enumerate()
+            .filter(|(_, b)| ![b.0, b.1, b.2, b.3].iter().any(|v| v.is_nan()))
+            .map(|(id, b)| (b, id))
+            .collect();
+        let mut tree = Self::default();
+        if entries.is_empty() {
+            return tree;
+        }
+
+        // Leaves, as (bounds, node index) entries of the next level
+        let mut level: Vec<(Bounds, usize)> = tile(entries)
+            .into_iter()
+            .map(|group| {
+                let bounds = union(&group);
+                let node = Node {
+                    bounds,
+                    first: tree.items.len(),
+                    count: group.len(),
+                    leaf: true,
+                };
+                tree.items.extend(group);
+                tree.nodes.push(node);
+                (bounds, tree.nodes.len() - 1)
+            })
+            .collect();
+
+        while level.len() > 1 {
+            level = tile(level)
+                .into_iter()
+                .map(|group| {
+                    let bounds = union(&group);
+                    let node = Node {
+                        bounds,
+                        first: tree.children.len(),
+                        count: group.len(),
+                        leaf: false,
+                    };
+                    tree.children.extend(group.iter().map(|&(_, index)| index));
+                    tree.nodes.push(node);
+                    (bounds, tree.nodes.len() - 1)
+                })
+                .collect();
+        }
+        tree.root = level.first().map(|&(_, index)| index);
+        tree
+    }
+
+    /// Ids of the boxes touching `area`, in ascending order
+    pub fn query(&self, area: Bounds) -> Vec<usize> {
+        let mut found = Vec::new();
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !intersects(node.bounds, area) {
+                continue;
+            }
+            let range = node.first..node.first + node.count;
+            if node.leaf {
+                found.extend(
+                    self.items[range]
+                        .iter()
+                        .filter(|(b, _)| intersects(*b, area))
+                        .map(|&(_, id)| id),
+                );
+            } else {
+                stack.extend_from_slice(&self.children[range]);
+            }
+        }
+        found.sort_unstable();
+        found
+    }
+
+    /// Ids of the boxes containing a point, in ascending order
+    pub fn query_point(&self, (x, y): (f64, f64)) -> Vec<usize> {
+        self.query((x, y, x, y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Brute-force reference for the queries
+    fn scan(boxes: &[Bounds], area: Bounds) -> Vec<usize> {
+        (0..boxes.len())
+            .filter(|&i| intersects(boxes[i], area))
+            .collect()
+    }
+
+    #[test]
+    fn test_query_matches_scan() {
+        // Rows of standard cells with a few large macros on top
+        let mut boxes = Vec::new();
+        for row in 0..40 {
+            for column in 0..50 {
+                let x = column as f64 * 2.0 + (row % 3) as f64 * 0.3;
+                let y = row as f64 * 1.5;
+                boxes.push((x, y, x + 1.8, y + 1.4));
+            }
+        }
+        boxes.push((10.0, 10.0, 40.0, 30.0));
+        boxes.push((60.0, 5.0, 95.0, 50.0));
+
+        let tree = RTree::new(boxes.clone());
+        assert_eq!(tree.items.len(), boxes.len());
+        for area in [
+            (0.0, 0.0, 5.0, 5.0),
+            (15.0, 12.0, 15.0, 12.0),
+            (33.3, 20.1, 70.0, 44.4),
+            (-10.0, -10.0, -1.0, -1.0),
+            (-1.0, -1.0, 200.0, 200.0),
+        ] {
+            assert_eq!(tree.query(area), scan(&boxes, area), "{area:?}");
+        }
+        assert_eq!(
+            tree.query_point((61.0, 10.0)),
+            scan(&boxes, (61.0, 10.0, 61.0, 10.0))
+        );
+    }
+
+    #[test]
+    fn test_empty_and_small_trees() {
+        let empty = RTree::new(Vec::new());
+        assert!(empty.query((0.0, 0.0, 1.0, 1.0)).is_empty());
+
+        let tree = RTree::new([(0.0, 0.0, 1.0, 1.0), (f64::NAN, 0.0, 1.0, 1.0)]);
+        assert_eq!(tree.items.len(), 1);
+        assert_eq!(tree.query_point((1.0, 1.0)), [0]);
+        assert!(tree.query_point((1.5, 1.0)).is_empty());
+    }
+}
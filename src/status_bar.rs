@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Status bar
+//!
+//! A strip at the bottom of the window with the current mode, the
+//! selection, the cursor position in microns and the background tasks that
+//! are running. Short notices from loading and rebuilding are shown here for
+//! a few seconds instead of being printed to the terminal.
+
+use eframe::egui;
+use std::time::Duration;
+use web_time::Instant;
+
+/// How long a hint stays in the status bar
+const HINT_DURATION: Duration = Duration::from_secs(6);
+
+/// A background task shown with a spinner, or a progress bar when its
+/// progress is known
+pub struct BackgroundTask {
+    pub label: String,
+    /// Work items still waiting, not shown when zero
+    pub queued: usize,
+    /// Fraction done, 0 to 1
+    pub progress: Option<f32>,
+}
+
+/// What the status bar shows this frame
+pub struct StatusInfo {
+    /// Current view or tool mode
+    pub mode: &'static str,
+    pub selection: String,
+    /// Cursor position in microns while it is over the canvas
+    pub cursor: Option<(f64, f64)>,
+    pub tasks: Vec<BackgroundTask>,
+}
+
+/// Status bar state and rendering
+#[derive(Default)]
+pub struct StatusBar {
+    hint: Option<(String, Instant)>,
+}
+
+impl StatusBar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show a short notice, replacing the previous one
+    pub fn hint(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        log::info!("{message}");
+        self.hint = Some((message, Instant::now()));
+    }
+
+    /// Render the bar at the bottom of the window
+    ///
+    /// Call before the side and central panels so it spans the full width.
+    pub fn render(&mut self, ctx: &egui::Context, info: StatusInfo) {
+        if self
+            .hint
+            .as_ref()
+            .is_some_and(|(_, shown)| shown.elapsed() > HINT_DURATION)
+        {
+            self.hint = None;
+        }
+
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.strong(info.mode);
+                ui.separator();
+                ui.label(&info.selection);
+                ui.separator();
+                let cursor = match info.cursor {
+                    Some((x, y)) => format!("X {x:.3}  Y {y:.3} um"),
+                    None => "X -  Y -".to_string(),
+                };
+                ui.monospace(cursor);
+                if let Some((hint, _)) = &self.hint {
+                    ui.separator();
+                    ui.label(egui::RichText::new(hint).weak());
+                }
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    for task in &info.tasks {
+                        let label = if task.queued > 0 {
+                            format!("{} ({} queued)", task.label, task.queued)
+                        } else {
+                            task.label.clone()
+                        };
+                        ui.label(label);
+                        match task.progress {
+                            Some(progress) => {
+                                ui.add(egui::ProgressBar::new(progress).desired_width(80.0));
+                            }
+                            None => {
+                                ui.spinner();
+                            }
+                        }
+                    }
+                });
+            });
+        });
+
+        if !info.tasks.is_empty() || self.hint.is_some() {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+    }
+}
@@ -0,0 +1,268 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! SVG snapshot of the canvas
+//!
+//! The shapes painted on the canvas during one frame are read back from the
+//! paint list and converted to [`SvgElement`]s, so the snapshot shows exactly
+//! the layers, components, pins and labels on screen without a second
+//! renderer to keep in sync.
+
+use crate::export::{Rgba, SvgElement, SvgView};
+use eframe::egui;
+use egui::epaint::{ColorMode, Shape};
+
+fn rgba(color: egui::Color32) -> Option<Rgba> {
+    (color.a() > 0).then(|| color.to_srgba_unmultiplied())
+}
+
+fn stroke(stroke: egui::Stroke) -> Option<(Rgba, f32)> {
+    Some((rgba(stroke.color)?, stroke.width)).filter(|(_, width)| *width > 0.0)
+}
+
+fn path_stroke(stroke: &egui::epaint::PathStroke) -> Option<(Rgba, f32)> {
+    match &stroke.color {
+        ColorMode::Solid(color) => self::stroke(egui::Stroke::new(stroke.width, *color)),
+        // Gradients are not used on the canvas
+        ColorMode::UV(_) => None,
+    }
+}
+
+/// Converts canvas shapes to SVG elements relative to the canvas origin
+struct Converter {
+    origin: egui::Pos2,
+    elements: Vec<SvgElement>,
+}
+
+impl Converter {
+    fn point(&self, pos: egui::Pos2) -> (f32, f32) {
+        (pos.x - self.origin.x, pos.y - self.origin.y)
+    }
+
+    fn points(&self, points: &[egui::Pos2]) -> Vec<(f32, f32)> {
+        points.iter().map(|p| self.point(*p)).collect()
+    }
+
+    fn add(&mut self, shape: &Shape) {
+        match shape {
+            Shape::Noop | Shape::Callback(_) => {}
+            Shape::Vec(shapes) => {
+                for shape in shapes {
+                    self.add(shape);
+                }
+            }
+            Shape::Circle(circle) => {
+                self.elements.push(SvgElement::Circle {
+                    center: self.point(circle.center),
+                    radius: circle.radius,
+                    fill: rgba(circle.fill),
+                    stroke: stroke(circle.stroke),
+                });
+            }
+            Shape::Ellipse(ellipse) => {
+                let points: Vec<egui::Pos2> = (0..48)
+                    .map(|i| {
+                        let t = i as f32 / 48.0 * std::f32::consts::TAU;
+                        ellipse.center
+                            + egui::vec2(ellipse.radius.x * t.cos(), ellipse.radius.y * t.sin())
+                    })
+                    .collect();
+                self.elements.push(SvgElement::Polygon {
+                    points: self.points(&points),
+                    fill: rgba(ellipse.fill),
+                    stroke: stroke(ellipse.stroke),
+                });
+            }
+            Shape::LineSegment { points, stroke: s } => {
+                if let Some(s) = stroke(*s) {
+                    self.elements.push(SvgElement::Polyline {
+                        points: self.points(points),
+                        stroke: s,
+                    });
+                }
+            }
+            Shape::Path(path) => {
+                if path.closed {
+                    self.elements.push(SvgElement::Polygon {
+                        points: self.points(&path.points),
+                        fill: rgba(path.fill),
+                        stroke: path_stroke(&path.stroke),
+                    });
+                } else if let Some(s) = path_stroke(&path.stroke) {
+                    self.elements.push(SvgElement::Polyline {
+                        points: self.points(&path.points),
+                        stroke: s,
+                    });
+                }
+            }
+            Shape::Rect(rect) => {
+                let r = rect.rect;
+                let corners = [
+                    r.left_top(),
+                    r.right_top(),
+                    r.right_bottom(),
+                    r.left_bottom(),
+                ];
+                self.elements.push(SvgElement::Polygon {
+                    points: self.points(&corners),
+                    fill: rgba(rect.fill),
+                    stroke: stroke(rect.stroke),
+                });
+            }
+            Shape::Text(text) => self.add_text(text),
+            Shape::Mesh(mesh) => {
+                for triangle in mesh.indices.chunks_exact(3) {
+                    let vertices: Vec<_> = triangle
+                        .iter()
+                        .filter_map(|i| mesh.vertices.get(*i as usize))
+                        .collect();
+                    if vertices.len() != 3 {
+                        continue;
+                    }
+                    let points: Vec<egui::Pos2> = vertices.iter().map(|v| v.pos).collect();
+                    self.elements.push(SvgElement::Polygon {
+                        points: self.points(&points),
+                        fill: rgba(vertices[0].color),
+                        stroke: None,
+                    });
+                }
+            }
+            Shape::QuadraticBezier(bezier) => {
+                self.add(&Shape::Path(bezier.to_path_shape(None)));
+            }
+            Shape::CubicBezier(bezier) => {
+                for path in bezier.to_path_shapes(None, None) {
+                    self.add(&Shape::Path(path));
+                }
+            }
+        }
+    }
+
+    /// One text element per row, anchored at the start of its baseline
+    fn add_text(&mut self, text: &egui::epaint::TextShape) {
+        let galley = &text.galley;
+        let rotation = egui::emath::Rot2::from_angle(text.angle);
+        for row in &galley.rows {
+            let Some(first) = row.glyphs.first() else {
+                continue;
+            };
+            let content: String = row.glyphs.iter().map(|g| g.chr).collect();
+            if content.trim().is_empty() {
+                continue;
+            }
+            let section = galley.job.sections.get(first.section_index as usize);
+            let color = text
+                .override_text_color
+                .or_else(|| section.map(|s| s.format.color))
+                .unwrap_or(text.fallback_color)
+                .gamma_multiply(text.opacity_factor);
+            let Some(color) = rgba(color) else {
+                continue;
+            };
+            let size = section.map_or(first.font_height, |s| s.format.font_id.size);
+            let offset = egui::vec2(row.rect.min.x + first.pos.x, first.pos.y);
+            self.elements.push(SvgElement::Text {
+                pos: self.point(text.pos + rotation * offset),
+                text: content,
+                size,
+                color,
+                angle: text.angle,
+            });
+        }
+    }
+}
+
+/// Convert shapes painted on a canvas to an SVG snapshot of it
+///
+/// Shapes outside `canvas` are left out.
+pub fn shapes_to_svg<'a>(
+    shapes: impl IntoIterator<Item = &'a Shape>,
+    canvas: egui::Rect,
+) -> SvgView {
+    let mut converter = Converter {
+        origin: canvas.min,
+        elements: Vec::new(),
+    };
+    for shape in shapes {
+        if shape.visual_bounding_rect().intersects(canvas) {
+            converter.add(shape);
+        }
+    }
+    SvgView {
+        width: canvas.width(),
+        height: canvas.height(),
+        output_size: None,
+        background: None,
+        elements: converter.elements,
+    }
+}
+
+/// Snapshot of everything painted on `layer_id` since `first_shape`
+pub fn capture(
+    ctx: &egui::Context,
+    layer_id: egui::LayerId,
+    first_shape: egui::layers::ShapeIdx,
+    canvas: egui::Rect,
+) -> SvgView {
+    ctx.graphics(|graphics| {
+        let Some(paint_list) = graphics.get(layer_id) else {
+            return shapes_to_svg([], canvas);
+        };
+        shapes_to_svg(
+            paint_list
+                .all_entries()
+                .skip(first_shape.0)
+                .map(|clipped| &clipped.shape),
+            canvas,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shapes_to_svg_is_relative_to_canvas() {
+        let canvas = egui::Rect::from_min_size(egui::pos2(100.0, 50.0), egui::vec2(200.0, 100.0));
+        let shapes = [
+            Shape::rect_filled(
+                egui::Rect::from_min_max(egui::pos2(110.0, 60.0), egui::pos2(120.0, 70.0)),
+                0.0,
+                egui::Color32::from_rgba_unmultiplied(255, 0, 0, 128),
+            ),
+            Shape::line_segment(
+                [egui::pos2(100.0, 50.0), egui::pos2(300.0, 150.0)],
+                egui::Stroke::new(2.0, egui::Color32::WHITE),
+            ),
+            // Fully outside the canvas
+            Shape::circle_filled(egui::pos2(500.0, 500.0), 3.0, egui::Color32::GREEN),
+            // Nothing to draw
+            Shape::line_segment(
+                [egui::pos2(100.0, 50.0), egui::pos2(300.0, 150.0)],
+                egui::Stroke::NONE,
+            ),
+        ];
+
+        let view = shapes_to_svg(&shapes, canvas);
+        assert_eq!((view.width, view.height), (200.0, 100.0));
+        assert_eq!(view.elements.len(), 2);
+        match &view.elements[0] {
+            SvgElement::Polygon { points, fill, .. } => {
+                assert_eq!(points[0], (10.0, 10.0));
+                assert_eq!(points[2], (20.0, 20.0));
+                let [r, g, b, a] = fill.unwrap();
+                assert_eq!((r, g, b), (255, 0, 0));
+                assert!((127..=129).contains(&a));
+            }
+            other => panic!("expected a polygon, got {other:?}"),
+        }
+        assert_eq!(
+            view.elements[1],
+            SvgElement::Polyline {
+                points: vec![(0.0, 0.0), (200.0, 100.0)],
+                stroke: ([255, 255, 255, 255], 2.0),
+            }
+        );
+    }
+}
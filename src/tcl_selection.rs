@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Selection as Tcl command fragments
+//!
+//! The selected components, nets and DEF pins are written as `get_cells`,
+//! `get_nets` and `get_ports` collections that paste into a P&R or STA
+//! shell. The dialect decides whether the collections are only queried or
+//! also selected in the GUI of the tool.
+//!
+//! Names are Tcl list elements in braces: brackets and `$` stay literal,
+//! braces, backslashes and white space are escaped with a backslash.
+
+use serde::{Deserialize, Serialize};
+
+/// Shell the fragments are written for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TclDialect {
+    /// Plain SDC collections, for PrimeTime, OpenSTA, OpenROAD and Tempus
+    #[default]
+    Sdc,
+    /// `select_obj` of the collections in Innovus
+    Innovus,
+    /// `change_selection` of the collections in IC Compiler II and Fusion
+    /// Compiler
+    Icc2,
+}
+
+impl TclDialect {
+    pub const ALL: [TclDialect; 3] = [TclDialect::Sdc, TclDialect::Innovus, TclDialect::Icc2];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TclDialect::Sdc => "SDC (get_cells)",
+            TclDialect::Innovus => "Innovus (select_obj)",
+            TclDialect::Icc2 => "ICC2 (change_selection)",
+        }
+    }
+}
+
+/// Names of the selected objects, in display form
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TclSelection {
+    pub cells: Vec<String>,
+    pub nets: Vec<String>,
+    pub ports: Vec<String>,
+}
+
+impl TclSelection {
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty() && self.nets.is_empty() && self.ports.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len() + self.nets.len() + self.ports.len()
+    }
+
+    /// One command per kind of object, names sorted
+    pub fn to_tcl(&self, dialect: TclDialect) -> String {
+        let mut out = String::new();
+        let kinds = [
+            ("get_cells", &self.cells),
+            ("get_nets", &self.nets),
+            ("get_ports", &self.ports),
+        ];
+        for (command, names) in kinds {
+            if names.is_empty() {
+                continue;
+            }
+            let mut names: Vec<&str> = names.iter().map(String::as_str).collect();
+            names.sort_unstable();
+            names.dedup();
+            let collection = format!("{command} {}", tcl_list(&names));
+            let line = match dialect {
+                TclDialect::Sdc => collection,
+                TclDialect::Innovus => format!("select_obj [{collection}]"),
+                // The first command replaces the selection, the others add to it
+                TclDialect::Icc2 if out.is_empty() => format!("change_selection [{collection}]"),
+                TclDialect::Icc2 => format!("change_selection -add [{collection}]"),
+            };
+            out.push_str(&line);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Braced Tcl list of `names`
+fn tcl_list(names: &[&str]) -> String {
+    let elements: Vec<String> = names.iter().map(|name| tcl_list_element(name)).collect();
+    format!("{{{}}}", elements.join(" "))
+}
+
+fn tcl_list_element(name: &str) -> String {
+    let mut element = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(c, '{' | '}' | '\\') || c.is_whitespace() {
+            element.push('\\');
+        }
+        element.push(c);
+    }
+    element
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selection() -> TclSelection {
+        TclSelection {
+            cells: vec!["u2".to_string(), "core/u1".to_string()],
+            nets: vec!["data[3]".to_string()],
+            ports: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_dialects() {
+        let selection = selection();
+        assert_eq!(selection.len(), 3);
+        assert_eq!(
+            selection.to_tcl(TclDialect::Sdc),
+            "get_cells {core/u1 u2}\nget_nets {data[3]}\n"
+        );
+        assert_eq!(
+            selection.to_tcl(TclDialect::Innovus),
+            "select_obj [get_cells {core/u1 u2}]\nselect_obj [get_nets {data[3]}]\n"
+        );
+        assert_eq!(
+            selection.to_tcl(TclDialect::Icc2),
+            "change_selection [get_cells {core/u1 u2}]\n\
+             change_selection -add [get_nets {data[3]}]\n"
+        );
+        assert_eq!(TclSelection::default().to_tcl(TclDialect::Sdc), "");
+    }
+
+    #[test]
+    fn test_special_characters_are_escaped() {
+        let selection = TclSelection {
+            ports: vec!["a b".to_string(), "c{1}".to_string(), "d\\e".to_string()],
+            ..TclSelection::default()
+        };
+        assert_eq!(
+            selection.to_tcl(TclDialect::Sdc),
+            "get_ports {a\\ b c\\{1\\} d\\\\e}\n"
+        );
+    }
+}
@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Design objects shared by the unit tests
+//!
+//! The factories fill in the fields most tests care about and leave the
+//! rest at their defaults; a test that needs more sets it with struct
+//! update syntax, e.g. `LefMacro { obs, ..cell("INV", 1.0, 1.8, pins) }`.
+
+use crate::def::{DefComponent, DefPlacement};
+use crate::lef::{LefMacro, LefPin, LefPort, LefRect};
+
+/// Rectangle on `layer`
+pub fn rect(layer: &str, xl: f64, yl: f64, xh: f64, yh: f64) -> LefRect {
+    LefRect {
+        layer: layer.to_string(),
+        xl,
+        yl,
+        xh,
+        yh,
+    }
+}
+
+/// Pin without any ports
+pub fn pin(name: &str, direction: &str, use_type: &str) -> LefPin {
+    LefPin {
+        name: name.to_string(),
+        direction: direction.to_string(),
+        use_type: use_type.to_string(),
+        ..LefPin::default()
+    }
+}
+
+/// Pin with a single port made of `rects`
+pub fn pin_with_rects(name: &str, direction: &str, rects: Vec<LefRect>) -> LefPin {
+    LefPin {
+        ports: vec![LefPort {
+            rects,
+            polygons: Vec::new(),
+        }],
+        ..pin(name, direction, "SIGNAL")
+    }
+}
+
+/// CORE macro of the given size with its origin at (0, 0)
+pub fn cell(name: &str, size_x: f64, size_y: f64, pins: Vec<LefPin>) -> LefMacro {
+    LefMacro {
+        name: name.to_string(),
+        class: "CORE".to_string(),
+        size_x,
+        size_y,
+        pins,
+        ..LefMacro::default()
+    }
+}
+
+/// PLACED component, in DEF database units
+pub fn component(name: &str, macro_name: &str, x: f64, y: f64, orientation: &str) -> DefComponent {
+    DefComponent {
+        name: name.to_string(),
+        macro_name: macro_name.to_string(),
+        placement: Some(DefPlacement {
+            placement_type: "PLACED".to_string(),
+            x,
+            y,
+            orientation: orientation.to_string(),
+        }),
+        ..DefComponent::default()
+    }
+}
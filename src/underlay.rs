@@ -0,0 +1,211 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Background image under the DEF view
+//!
+//! A raster image, such as a congestion or IR-drop map exported by another
+//! tool or a die photo, is stretched between two corners given in design
+//! microns and painted below the components. It follows the DEF source
+//! transform like the rest of the design, so it stays aligned when the
+//! design is moved.
+
+use crate::geometry::Bounds;
+use eframe::egui;
+use std::path::Path;
+
+/// Loaded underlay image and its placement
+pub struct Underlay {
+    pub name: String,
+    texture: egui::TextureHandle,
+    /// Image size in pixels
+    pub image_size: [usize; 2],
+    /// Lower-left and upper-right corners in microns
+    pub bounds: Bounds,
+    pub opacity: f32,
+    pub visible: bool,
+}
+
+/// Decode a PNG into an egui image
+pub fn decode_image(bytes: &[u8]) -> Result<egui::ColorImage, String> {
+    let image = image::load_from_memory(bytes)
+        .map_err(|e| format!("Unsupported image: {e}"))?
+        .to_rgba8();
+    let size = [image.width() as usize, image.height() as usize];
+    Ok(egui::ColorImage::from_rgba_unmultiplied(
+        size,
+        image.as_flat_samples().as_slice(),
+    ))
+}
+
+impl Underlay {
+    /// Load an image file, covering `bounds` in microns
+    ///
+    /// Without bounds, one pixel covers one micron from the origin.
+    pub fn load(ctx: &egui::Context, path: &Path, bounds: Option<Bounds>) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let image = decode_image(&bytes)?;
+        let image_size = image.size;
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let texture = ctx.load_texture(
+            format!("underlay:{}", path.display()),
+            image,
+            egui::TextureOptions::LINEAR,
+        );
+        Ok(Self {
+            name,
+            texture,
+            image_size,
+            bounds: bounds.unwrap_or((0.0, 0.0, image_size[0] as f64, image_size[1] as f64)),
+            opacity: 0.5,
+            visible: true,
+        })
+    }
+
+    /// Screen rectangle of the image, `origin` being the screen position of
+    /// the design origin and `zoom` points per micron
+    pub fn screen_rect(&self, origin: egui::Pos2, zoom: f32) -> egui::Rect {
+        let (x0, y0, x1, y1) = self.bounds;
+        let to_screen = |x: f64, y: f64| origin + egui::vec2(x as f32, -(y as f32)) * zoom;
+        egui::Rect::from_two_pos(to_screen(x0, y1), to_screen(x1, y0))
+    }
+
+    /// Paint the image, as a mesh so that source transforms apply to it
+    pub fn paint(&self, painter: &egui::Painter, origin: egui::Pos2, zoom: f32) {
+        if !self.visible || self.opacity <= 0.0 {
+            return;
+        }
+        let rect = self.screen_rect(origin, zoom);
+        if !rect.intersects(painter.clip_rect()) {
+            return;
+        }
+        let mut mesh = egui::Mesh::with_texture(self.texture.id());
+        mesh.add_rect_with_uv(
+            rect,
+            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+            egui::Color32::WHITE.gamma_multiply(self.opacity),
+        );
+        painter.add(egui::Shape::mesh(mesh));
+    }
+}
+
+/// What the user asked for in the underlay dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnderlayAction {
+    Load,
+    Remove,
+}
+
+/// Underlay dialog state and rendering
+#[derive(Default)]
+pub struct UnderlayDialog {
+    /// Whether the dialog is currently shown
+    pub visible: bool,
+}
+
+impl UnderlayDialog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render the dialog; `die` is the die area in microns, if any
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        underlay: Option<&mut Underlay>,
+        die: Option<Bounds>,
+    ) -> Option<UnderlayAction> {
+        if !self.visible {
+            return None;
+        }
+
+        let mut open = true;
+        let mut action = None;
+        egui::Window::new("Background Image")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let Some(underlay) = underlay else {
+                    ui.label("No image loaded");
+                    ui.label(
+                        egui::RichText::new("PNG, for example a congestion map or a die photo.")
+                            .small()
+                            .weak(),
+                    );
+                    if ui.button("Load Image...").clicked() {
+                        action = Some(UnderlayAction::Load);
+                    }
+                    return;
+                };
+
+                ui.label(format!(
+                    "{} ({} x {} px)",
+                    underlay.name, underlay.image_size[0], underlay.image_size[1]
+                ));
+                ui.checkbox(&mut underlay.visible, "Show under the design");
+                ui.add(egui::Slider::new(&mut underlay.opacity, 0.0..=1.0).text("Opacity"));
+
+                ui.separator();
+                ui.label("Corners (um):");
+                let (x0, y0, x1, y1) = &mut underlay.bounds;
+                egui::Grid::new("underlay_corners_grid")
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        ui.label("Lower left");
+                        ui.add(egui::DragValue::new(x0).speed(0.1).prefix("X "));
+                        ui.add(egui::DragValue::new(y0).speed(0.1).prefix("Y "));
+                        ui.end_row();
+                        ui.label("Upper right");
+                        ui.add(egui::DragValue::new(x1).speed(0.1).prefix("X "));
+                        ui.add(egui::DragValue::new(y1).speed(0.1).prefix("Y "));
+                        ui.end_row();
+                    });
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(die.is_some(), egui::Button::new("Fit to Die Area"))
+                        .clicked()
+                    {
+                        if let Some(die) = die {
+                            underlay.bounds = die;
+                        }
+                    }
+                    if ui.button("Replace...").clicked() {
+                        action = Some(UnderlayAction::Load);
+                    }
+                    if ui.button("Remove").clicked() {
+                        action = Some(UnderlayAction::Remove);
+                    }
+                });
+            });
+
+        if !open {
+            self.visible = false;
+        }
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_png() {
+        let mut image = image::RgbaImage::new(3, 2);
+        image.put_pixel(2, 1, image::Rgba([255, 0, 0, 255]));
+        let mut bytes = Vec::new();
+        image
+            .write_to(
+                &mut std::io::Cursor::new(&mut bytes),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+
+        let decoded = decode_image(&bytes).unwrap();
+        assert_eq!(decoded.size, [3, 2]);
+        assert_eq!(decoded.pixels[5], egui::Color32::RED);
+        assert!(decode_image(b"not an image").is_err());
+    }
+}
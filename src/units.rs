@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Database unit reconciliation between LEF and DEF
+//!
+//! DEF coordinates are integers in database units (DBU). The DEF declares
+//! its DBU per micron with `UNITS DISTANCE MICRONS`, the tech LEF with
+//! `UNITS DATABASE MICRONS`. When the DEF declares nothing, the LEF value
+//! is used, and 1000 when neither does.
+
+/// DBU per micron assumed when neither file declares UNITS
+pub const DEFAULT_DB_UNITS: f64 = 1000.0;
+
+/// Declared and effective database units of a loaded design
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitsCheck {
+    /// UNITS DISTANCE MICRONS of the DEF
+    pub def_units: Option<f64>,
+    /// UNITS DATABASE MICRONS of the tech LEF
+    pub lef_units: Option<f64>,
+}
+
+impl UnitsCheck {
+    /// Reconcile the units declared by the DEF and the tech LEF
+    pub fn new(def_units: Option<f64>, lef_units: Option<f64>) -> Self {
+        let valid = |units: Option<f64>| units.filter(|u| u.is_finite() && *u > 0.0);
+        Self {
+            def_units: valid(def_units),
+            lef_units: valid(lef_units),
+        }
+    }
+
+    /// DBU per micron to convert DEF coordinates with
+    pub fn db_units(&self) -> f64 {
+        self.def_units
+            .or(self.lef_units)
+            .unwrap_or(DEFAULT_DB_UNITS)
+    }
+
+    /// Where [`Self::db_units`] comes from, for display
+    pub fn source(&self) -> &'static str {
+        match (self.def_units, self.lef_units) {
+            (Some(_), _) => "DEF UNITS DISTANCE MICRONS",
+            (None, Some(_)) => "tech LEF UNITS DATABASE MICRONS",
+            (None, None) => "default, no UNITS declared",
+        }
+    }
+
+    /// Whether both files declare UNITS and the values differ
+    pub fn is_mismatch(&self) -> bool {
+        matches!((self.def_units, self.lef_units), (Some(d), Some(l)) if d != l)
+    }
+
+    /// Warning text for the banner, None when the units agree
+    pub fn warning(&self) -> Option<String> {
+        if !self.is_mismatch() {
+            return None;
+        }
+        let (def_units, lef_units) = (self.def_units?, self.lef_units?);
+        Some(format!(
+            "DEF UNITS DISTANCE MICRONS {def_units} differs from tech LEF UNITS DATABASE \
+             MICRONS {lef_units}. DEF coordinates are scaled by the DEF units \
+             (1 um = {def_units} DBU)."
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_db_units_precedence() {
+        assert_eq!(UnitsCheck::new(None, None).db_units(), DEFAULT_DB_UNITS);
+        assert_eq!(UnitsCheck::new(None, Some(2000.0)).db_units(), 2000.0);
+        assert_eq!(UnitsCheck::new(Some(100.0), Some(2000.0)).db_units(), 100.0);
+        // Nonsense declarations are ignored
+        assert_eq!(UnitsCheck::new(Some(0.0), Some(2000.0)).db_units(), 2000.0);
+        assert_eq!(
+            UnitsCheck::new(None, Some(100.0)).source(),
+            "tech LEF UNITS DATABASE MICRONS"
+        );
+    }
+
+    #[test]
+    fn test_mismatch_warning() {
+        assert!(UnitsCheck::new(Some(1000.0), Some(1000.0))
+            .warning()
+            .is_none());
+        assert!(UnitsCheck::new(Some(1000.0), None).warning().is_none());
+
+        let check = UnitsCheck::new(Some(2000.0), Some(1000.0));
+        assert!(check.is_mismatch());
+        let warning = check.warning().unwrap();
+        assert!(warning.contains("2000"));
+        assert!(warning.contains("1000"));
+    }
+}
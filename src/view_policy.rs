@@ -0,0 +1,152 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Initial view policy after a file load
+//!
+//! Decides whether a load fits the view, keeps the current one, or restores
+//! the view last used for the same design. Designs are keyed by name, the
+//! DEF DESIGN statement or the LEF file name, so reloading a file or opening
+//! another revision of the same design returns to where the user was.
+
+use crate::def::Def;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// When to fit the view to the content after a load
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AutoFit {
+    /// Fit after every load
+    #[default]
+    Always,
+    /// Fit the first time a design is loaded, keep the view on reloads
+    FirstLoad,
+    /// Never fit automatically, F and Fit to View still work
+    Never,
+}
+
+impl AutoFit {
+    pub const ALL: [AutoFit; 3] = [AutoFit::Always, AutoFit::FirstLoad, AutoFit::Never];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AutoFit::Always => "Always",
+            AutoFit::FirstLoad => "First Load Only",
+            AutoFit::Never => "Never",
+        }
+    }
+}
+
+/// Zoom and pan of the canvas
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SavedView {
+    pub zoom: f32,
+    pub pan_x: f32,
+    pub pan_y: f32,
+}
+
+/// What the canvas should do after a load
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoadView {
+    Fit,
+    Keep,
+    Restore(SavedView),
+}
+
+/// Auto-fit preferences and the views saved per design
+#[derive(Debug, Default)]
+pub struct ViewPolicy {
+    pub auto_fit: AutoFit,
+    /// Restore the view saved for a design when it is loaded again
+    pub preserve_view: bool,
+    saved: HashMap<String, SavedView>,
+    seen: HashSet<String>,
+}
+
+impl ViewPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Save the view of the design that is being replaced or reloaded
+    pub fn remember(&mut self, design: &str, view: SavedView) {
+        if !design.is_empty() {
+            self.saved.insert(design.to_string(), view);
+        }
+    }
+
+    /// Decide the view after `design` was loaded
+    pub fn on_load(&mut self, design: &str) -> LoadView {
+        let first_load = self.seen.insert(design.to_string());
+        if self.preserve_view {
+            if let Some(view) = self.saved.get(design) {
+                return LoadView::Restore(*view);
+            }
+        }
+        match self.auto_fit {
+            AutoFit::Always => LoadView::Fit,
+            AutoFit::FirstLoad if first_load => LoadView::Fit,
+            AutoFit::FirstLoad | AutoFit::Never => LoadView::Keep,
+        }
+    }
+}
+
+/// View key of a DEF: its DESIGN name, the file name when it has none
+pub fn def_view_key(def: &Def, path: &str) -> String {
+    if def.design.is_empty() {
+        format!("DEF file {}", file_name(path))
+    } else {
+        format!("DEF {}", def.design)
+    }
+}
+
+/// View key of a LEF library: its file name
+pub fn lef_view_key(path: &str) -> String {
+    format!("LEF file {}", file_name(path))
+}
+
+fn file_name(path: &str) -> &str {
+    Path::new(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VIEW: SavedView = SavedView {
+        zoom: 2.0,
+        pan_x: 10.0,
+        pan_y: -5.0,
+    };
+
+    #[test]
+    fn test_auto_fit_modes() {
+        let mut policy = ViewPolicy::new();
+        assert_eq!(policy.on_load("top"), LoadView::Fit);
+        assert_eq!(policy.on_load("top"), LoadView::Fit);
+
+        policy.auto_fit = AutoFit::FirstLoad;
+        assert_eq!(policy.on_load("top"), LoadView::Keep);
+        assert_eq!(policy.on_load("other"), LoadView::Fit);
+
+        policy.auto_fit = AutoFit::Never;
+        assert_eq!(policy.on_load("new"), LoadView::Keep);
+    }
+
+    #[test]
+    fn test_preserve_view_by_design() {
+        let mut policy = ViewPolicy::new();
+        policy.preserve_view = true;
+        assert_eq!(policy.on_load("top"), LoadView::Fit);
+
+        policy.remember("top", VIEW);
+        assert_eq!(policy.on_load("top"), LoadView::Restore(VIEW));
+        // Other designs are unaffected
+        assert_eq!(policy.on_load("block"), LoadView::Fit);
+
+        policy.preserve_view = false;
+        assert_eq!(policy.on_load("top"), LoadView::Fit);
+    }
+}
@@ -6,7 +6,8 @@
 //! This module provides a comprehensive voltage configuration dialog for Liberty export
 //! with support for thousands of pins, batch selection, and per-pin power/ground configuration.
 
-use crate::export::{PinCsvRecord, VoltageConfig};
+use crate::export::{PinCsvRecord, TimingArcTemplate, VoltageConfig};
+use crate::identifier;
 use crate::lef::{Lef, LefPin};
 use eframe::egui;
 use egui_extras::{Column, TableBuilder};
@@ -15,33 +16,10 @@ use std::collections::BTreeMap;
 /// Extract bus information from pin name
 /// Returns Some((base_name, index)) if pin follows bus pattern, None otherwise
 fn extract_bus_info(pin_name: &str) -> Option<(String, usize)> {
-    // Try pattern with square brackets: base_name[index]
-    if let Some(bracket_start) = pin_name.rfind('[') {
-        if let Some(bracket_end) = pin_name.rfind(']') {
-            if bracket_start < bracket_end {
-                let base_name = pin_name[..bracket_start].to_string();
-                let index_str = &pin_name[bracket_start + 1..bracket_end];
-                if let Ok(index) = index_str.parse::<usize>() {
-                    return Some((base_name, index));
-                }
-            }
-        }
-    }
-
-    // Try pattern with angle brackets: base_name<index>
-    if let Some(angle_start) = pin_name.rfind('<') {
-        if let Some(angle_end) = pin_name.rfind('>') {
-            if angle_start < angle_end {
-                let base_name = pin_name[..angle_start].to_string();
-                let index_str = &pin_name[angle_start + 1..angle_end];
-                if let Ok(index) = index_str.parse::<usize>() {
-                    return Some((base_name, index));
-                }
-            }
-        }
-    }
-
-    None
+    // Accepts both base_name[index] and base_name<index>; escaped
+    // delimiters such as reg\[3\] are part of the name, not a bus bit
+    identifier::split_any_bus_bit(pin_name)
+        .map(|(base_name, index)| (identifier::unescape(base_name).into_owned(), index))
 }
 
 /// Clean pin name by removing special characters like '!'
@@ -124,7 +102,7 @@ mod tests {
     #[test]
     fn test_smart_power_pin_selection() {
         // Test exact match VDD
-        let pins = vec![
+        let pins = [
             "POWER_1".to_string(),
             "VDD".to_string(),
             "POWER_2".to_string(),
@@ -135,7 +113,7 @@ mod tests {
         );
 
         // Test exact match vdd
-        let pins = vec![
+        let pins = [
             "POWER_1".to_string(),
             "vdd".to_string(),
             "POWER_2".to_string(),
@@ -146,7 +124,7 @@ mod tests {
         );
 
         // Test contains VDD
-        let pins = vec![
+        let pins = [
             "POWER_1".to_string(),
             "VDD_CORE".to_string(),
             "POWER_2".to_string(),
@@ -157,7 +135,7 @@ mod tests {
         );
 
         // Test contains vdd (case insensitive)
-        let pins = vec![
+        let pins = [
             "POWER_1".to_string(),
             "vdd_io".to_string(),
             "POWER_2".to_string(),
@@ -168,7 +146,7 @@ mod tests {
         );
 
         // Test fallback to first pin
-        let pins = vec!["POWER_A".to_string(), "POWER_B".to_string()];
+        let pins = ["POWER_A".to_string(), "POWER_B".to_string()];
         assert_eq!(
             select_default_power_pin(pins.iter()),
             Some("POWER_A".to_string())
@@ -182,21 +160,21 @@ mod tests {
     #[test]
     fn test_smart_ground_pin_selection() {
         // Test exact match VSS
-        let pins = vec!["GND_1".to_string(), "VSS".to_string(), "GND_2".to_string()];
+        let pins = ["GND_1".to_string(), "VSS".to_string(), "GND_2".to_string()];
         assert_eq!(
             select_default_ground_pin(pins.iter()),
             Some("VSS".to_string())
         );
 
         // Test exact match vss
-        let pins = vec!["GND_1".to_string(), "vss".to_string(), "GND_2".to_string()];
+        let pins = ["GND_1".to_string(), "vss".to_string(), "GND_2".to_string()];
         assert_eq!(
             select_default_ground_pin(pins.iter()),
             Some("vss".to_string())
         );
 
         // Test contains VSS
-        let pins = vec![
+        let pins = [
             "GND_1".to_string(),
             "VSS_CORE".to_string(),
             "GND_2".to_string(),
@@ -207,7 +185,7 @@ mod tests {
         );
 
         // Test contains vss (case insensitive)
-        let pins = vec![
+        let pins = [
             "GND_1".to_string(),
             "vss_io".to_string(),
             "GND_2".to_string(),
@@ -218,7 +196,7 @@ mod tests {
         );
 
         // Test fallback to first pin
-        let pins = vec!["GND_A".to_string(), "GND_B".to_string()];
+        let pins = ["GND_A".to_string(), "GND_B".to_string()];
         assert_eq!(
             select_default_ground_pin(pins.iter()),
             Some("GND_A".to_string())
@@ -262,7 +240,12 @@ mod tests {
                     },
                 ],
                 obs: Vec::new(),
+                ..LefMacro::default()
             }],
+            database_microns: None,
+            manufacturing_grid: None,
+            layers: Vec::new(),
+            sites: Vec::new(),
         };
 
         let mut config = VoltageConfig::default();
@@ -546,6 +529,51 @@ impl VoltageDialog {
                 }
                 ui.separator();
 
+                // Electrical defaults of the signal pins
+                ui.horizontal(|ui| {
+                    ui.label("Input Capacitance:");
+                    ui.add(
+                        egui::DragValue::new(&mut voltage_config.input_capacitance)
+                            .speed(0.001)
+                            .range(0.0..=10.0)
+                            .max_decimals(4)
+                            .suffix(" pF"),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    optional_limit(ui, "Max Transition", &mut voltage_config.max_transition, 0.5, " ns");
+                    optional_limit(ui, "Max Capacitance", &mut voltage_config.max_capacitance, 0.2, " pF");
+                });
+                ui.horizontal(|ui| {
+                    let mut with_arcs = voltage_config.timing_arc.is_some();
+                    if ui
+                        .checkbox(&mut with_arcs, "Timing Arcs")
+                        .on_hover_text("Scalar arc from all inputs to each output pin, for early synthesis trials")
+                        .changed()
+                    {
+                        voltage_config.timing_arc = with_arcs.then(TimingArcTemplate::default);
+                    }
+                    if let Some(arc) = &mut voltage_config.timing_arc {
+                        ui.label("Delay:");
+                        ui.add(
+                            egui::DragValue::new(&mut arc.delay)
+                                .speed(0.001)
+                                .range(0.0..=100.0)
+                                .max_decimals(4)
+                                .suffix(" ns"),
+                        );
+                        ui.label("Transition:");
+                        ui.add(
+                            egui::DragValue::new(&mut arc.transition)
+                                .speed(0.001)
+                                .range(0.0..=100.0)
+                                .max_decimals(4)
+                                .suffix(" ns"),
+                        );
+                    }
+                });
+                ui.separator();
+
                 // Power pins configuration
                 if !voltage_config.power_pins.is_empty() {
                     ui.label("Power Pins:");
@@ -680,11 +708,7 @@ impl VoltageDialog {
                 let filtered_pin_groups: Vec<&PinCsvRecord> = compressed_pin_groups
                     .iter()
                     .filter(|pin_group| {
-                        voltage_config.pin_filter.is_empty()
-                            || pin_group
-                                .name
-                                .to_lowercase()
-                                .contains(&voltage_config.pin_filter.to_lowercase())
+                        identifier::matches_query(&pin_group.name, &voltage_config.pin_filter)
                     })
                     .collect();
 
@@ -1026,3 +1050,26 @@ impl VoltageDialog {
             });
     }
 }
+
+/// Checkbox with a value for a limit that is only written when enabled
+fn optional_limit(
+    ui: &mut egui::Ui,
+    label: &str,
+    limit: &mut Option<f32>,
+    default: f32,
+    unit: &str,
+) {
+    let mut enabled = limit.is_some();
+    if ui.checkbox(&mut enabled, label).changed() {
+        *limit = enabled.then_some(default);
+    }
+    if let Some(value) = limit {
+        ui.add(
+            egui::DragValue::new(value)
+                .speed(0.001)
+                .range(0.0..=100.0)
+                .max_decimals(4)
+                .suffix(unit),
+        );
+    }
+}
@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Waived check violations
+//!
+//! A violation that was reviewed and accepted, such as an intentional
+//! overlap of two macros, is waived with a comment saying why. Waivers are
+//! kept in a JSON file next to the design, `top.waivers.json` for
+//! `top.def`, so they travel with it and the batch runner finds them too.
+//! Waived findings move out of the violations of their check: they no
+//! longer count and no longer fail a batch run, but reports still list
+//! them with their comment.
+//!
+//! A waiver matches a finding by check name, object and message, so a
+//! violation that changes, e.g. an overlap with another instance, shows up
+//! again.
+
+use crate::checks::{CheckReport, Finding, WaivedFinding};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Accepted violation of a check
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Waiver {
+    pub check: String,
+    pub object: String,
+    pub message: String,
+    /// Why the violation is accepted
+    pub comment: String,
+}
+
+impl Waiver {
+    fn matches(&self, check: &str, finding: &Finding) -> bool {
+        self.check == check && self.object == finding.object && self.message == finding.message
+    }
+}
+
+/// Waivers of a design
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Waivers {
+    pub waivers: Vec<Waiver>,
+}
+
+impl Waivers {
+    /// Waiver file of a design or library file, e.g. `top.waivers.json`
+    /// next to `top.def`
+    pub fn path_for(design_file: &str) -> PathBuf {
+        let path = Path::new(design_file);
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("design");
+        path.with_file_name(format!("{stem}.waivers.json"))
+    }
+
+    /// Read a waiver file, no waivers when it does not exist
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .map_err(|e| format!("invalid waiver file {}: {e}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("failed to read {}: {e}", path.display())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = serde_json::to_string_pretty(self).expect("waivers serialize to JSON");
+        std::fs::write(path, text).map_err(|e| format!("failed to write {}: {e}", path.display()))
+    }
+
+    /// Waive a finding of `check`, replacing the comment of an earlier
+    /// waiver of it
+    pub fn waive(&mut self, check: &str, finding: &Finding, comment: &str) {
+        self.unwaive(check, finding);
+        self.waivers.push(Waiver {
+            check: check.to_string(),
+            object: finding.object.clone(),
+            message: finding.message.clone(),
+            comment: comment.to_string(),
+        });
+    }
+
+    pub fn unwaive(&mut self, check: &str, finding: &Finding) {
+        self.waivers.retain(|w| !w.matches(check, finding));
+    }
+
+    /// Waiver of a finding of `check`, if any
+    pub fn find(&self, check: &str, finding: &Finding) -> Option<&Waiver> {
+        self.waivers.iter().find(|w| w.matches(check, finding))
+    }
+
+    /// Move the waived findings of `report` out of its violations
+    pub fn apply(&self, report: &mut CheckReport) {
+        for result in &mut report.results {
+            let (waived, findings) = std::mem::take(&mut result.findings)
+                .into_iter()
+                .partition::<Vec<_>, _>(|f| self.find(result.name, f).is_some());
+            result.findings = findings;
+            result.waived.extend(waived.into_iter().map(|finding| {
+                let comment = self
+                    .find(result.name, &finding)
+                    .map(|w| w.comment.clone())
+                    .unwrap_or_default();
+                WaivedFinding { finding, comment }
+            }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::{CheckResult, OVERLAPS};
+
+    fn finding(object: &str, message: &str) -> Finding {
+        Finding {
+            object: object.to_string(),
+            message: message.to_string(),
+            bounds: None,
+        }
+    }
+
+    #[test]
+    fn test_waivers() {
+        assert_eq!(
+            Waivers::path_for("/work/top.def"),
+            Path::new("/work/top.waivers.json")
+        );
+
+        let mut report = CheckReport {
+            design: "top".to_string(),
+            results: vec![CheckResult {
+                name: OVERLAPS,
+                findings: vec![finding("u1", "overlaps u2"), finding("u3", "overlaps u4")],
+                waived: Vec::new(),
+                skipped: None,
+            }],
+        };
+        let mut waivers = Waivers::default();
+        waivers.waive(OVERLAPS, &finding("u1", "overlaps u2"), "first try");
+        waivers.waive(OVERLAPS, &finding("u1", "overlaps u2"), "abutted halo");
+        // Same object, another violation
+        waivers.waive(OVERLAPS, &finding("u3", "overlaps u5"), "stale");
+        assert_eq!(waivers.waivers.len(), 2);
+
+        waivers.apply(&mut report);
+        let result = &report.results[0];
+        assert_eq!(result.findings, [finding("u3", "overlaps u4")]);
+        assert_eq!(
+            result.waived,
+            [WaivedFinding {
+                finding: finding("u1", "overlaps u2"),
+                comment: "abutted halo".to_string()
+            }]
+        );
+        assert_eq!(report.failure_count(), 1);
+
+        waivers.unwaive(OVERLAPS, &finding("u3", "overlaps u5"));
+        let text = serde_json::to_string(&waivers).unwrap();
+        let loaded: Waivers = serde_json::from_str(&text).unwrap();
+        assert_eq!(loaded, waivers);
+        assert_eq!(loaded.waivers[0].comment, "abutted halo");
+    }
+}
@@ -11,6 +11,7 @@
 use lefdef_viewer::def::def_parser;
 use lefdef_viewer::def::parser::DefItemParser;
 use lefdef_viewer::def::reader::DefReader;
+use lefdef_viewer::def::utilization::utilization;
 use std::fs;
 
 #[test]
@@ -45,6 +46,7 @@ END DESIGN
     assert!(result.is_ok(), "Failed to parse basic DEF: {:?}", result);
 
     let (_, def) = result.unwrap();
+    assert_eq!(def.design, "simple_design");
     assert_eq!(def.components.len(), 3);
     assert_eq!(def.pins.len(), 3);
 
@@ -374,3 +376,592 @@ fn test_parse_component_with_routinghalo() {
     parser.parse_continuation(&mut context, "+ ROUTINGHALO 10 20 30 40 ;");
     assert_eq!(context.routing_halo, Some((10.0, 20.0, 30.0, 40.0)));
 }
+
+#[test]
+fn test_escaped_names_and_bus_bit_chars() {
+    let def_content = r#"
+VERSION 5.8 ;
+DIVIDERCHAR "/" ;
+BUSBITCHARS "<>" ;
+DESIGN escaped ;
+UNITS DISTANCE MICRONS 1000 ;
+
+COMPONENTS 2 ;
+    - \u_top/reg\[3\] DFFX1 + PLACED ( 1000 2000 ) N ;
+    - u_top/mem<0> RAM1 + PLACED ( 3000 2000 ) FS ;
+END COMPONENTS
+
+PINS 1 ;
+    - data<7> + NET data<7> + DIRECTION INPUT + FIXED ( 0 0 ) N + LAYER M1 ( 0 0 ) ( 10 10 ) ;
+END PINS
+
+NETS 1 ;
+    - \n\$12 ( \u_top/reg\[3\] Q ) ( PIN data<7> ) ;
+END NETS
+
+END DESIGN
+"#;
+
+    let (_, def) = def_parser::parse_def(def_content).unwrap();
+    assert_eq!(def.bus_bit_chars.open, '<');
+    assert_eq!(def.bus_bit_chars.close, '>');
+
+    // Names are kept exactly as written
+    assert_eq!(def.components.len(), 2);
+    assert_eq!(def.components[0].name, r"\u_top/reg\[3\]");
+    assert_eq!(def.components[0].macro_name, "DFFX1");
+    assert_eq!(def.pins[0].name, "data<7>");
+    assert_eq!(def.nets[0].name, r"\n\$12");
+    assert!(def.nets[0]
+        .instances
+        .contains(&r"\u_top/reg\[3\]".to_string()));
+
+    // Display uses unescaped names and canonical bus bits
+    use lefdef_viewer::identifier::{display_name, matches_query};
+    assert_eq!(
+        display_name(&def.components[0].name, def.bus_bit_chars),
+        "u_top/reg[3]"
+    );
+    assert_eq!(
+        display_name(&def.pins[0].name, def.bus_bit_chars),
+        "data[7]"
+    );
+    assert!(matches_query(&def.components[0].name, "reg[3]"));
+}
+
+#[test]
+fn test_section_filter_skips_sections() {
+    let def_content = r#"
+VERSION 5.8 ;
+DESIGN filtered ;
+COMPONENTS 1 ;
+    - u1 INVX1 + PLACED ( 0 0 ) N ;
+END COMPONENTS
+SPECIALNETS 1 ;
+    - VDD ( * VDD ) + USE POWER ;
+END SPECIALNETS
+NETS 1 ;
+    - n1 ( u1 Y ) ( PIN OUT ) ;
+END NETS
+END DESIGN
+"#;
+
+    let options = lefdef_viewer::def::DefParseOptions::default().skip("NETS");
+    let (_, def) = def_parser::parse_def_with_options(def_content, &options).unwrap();
+    assert_eq!(def.components.len(), 1);
+    assert_eq!(def.special_nets.len(), 1);
+    assert!(def.nets.is_empty());
+
+    let (_, def) = def_parser::parse_def(def_content).unwrap();
+    assert_eq!(def.nets.len(), 1);
+}
+
+#[test]
+fn test_units_distance_microns() {
+    let def_content = r#"
+VERSION 5.8 ;
+DESIGN scaled ;
+UNITS DISTANCE MICRONS 2000 ;
+DIEAREA ( 0 0 ) ( 20000 10000 ) ;
+END DESIGN
+"#;
+
+    let (_, def) = def_parser::parse_def(def_content).unwrap();
+    assert_eq!(def.units_distance_microns, Some(2000.0));
+
+    let (_, def) = def_parser::parse_def("VERSION 5.8 ;\nEND DESIGN\n").unwrap();
+    assert_eq!(def.units_distance_microns, None);
+}
+
+#[test]
+fn test_component_and_pin_snippets_reparse() {
+    let def_content = r#"
+DESIGN snippet ;
+UNITS DISTANCE MICRONS 1000 ;
+COMPONENTS 1 ;
+    - u1 INVX1 + SOURCE NETLIST + FIXED ( 10000 20000 ) FS + HALO 10 20 30 40 ;
+END COMPONENTS
+PINS 1 ;
+    - IN1 + NET n1 + DIRECTION INPUT + USE SIGNAL
+      + LAYER M1 ( -70 0 ) ( 70 140 )
+      + PLACED ( 5000 0 ) N ;
+END PINS
+END DESIGN
+"#;
+    let (_, def) = def_parser::parse_def(def_content).unwrap();
+    let component = lefdef_viewer::def::writer::write_component(&def.components[0]);
+    let pin = lefdef_viewer::def::writer::write_pin(&def.pins[0]);
+    assert!(component.contains("+ FIXED ( 10000 20000 ) FS"));
+    assert!(pin.contains("+ LAYER M1 ( -70 0 ) ( 70 140 )"));
+
+    let reparsed = format!(
+        "DESIGN snippet ;\nCOMPONENTS 1 ;\n{component}END COMPONENTS\nPINS 1 ;\n{pin}END PINS\nEND DESIGN\n"
+    );
+    let (_, copy) = def_parser::parse_def(&reparsed).unwrap();
+    let (original, copied) = (&def.components[0], &copy.components[0]);
+    assert_eq!(copied.name, original.name);
+    assert_eq!(copied.macro_name, original.macro_name);
+    assert_eq!(copied.source, original.source);
+    assert_eq!(copied.routing_halo, original.routing_halo);
+    let placement = copied.placement.as_ref().unwrap();
+    assert_eq!((placement.x, placement.y), (10000.0, 20000.0));
+    assert_eq!(placement.orientation, "FS");
+
+    let (original, copied) = (&def.pins[0], &copy.pins[0]);
+    assert_eq!(copied.net, original.net);
+    assert_eq!(copied.direction, original.direction);
+    assert_eq!(copied.use_type, original.use_type);
+    assert_eq!((copied.x, copied.y), (original.x, original.y));
+    assert_eq!(copied.rects.len(), 1);
+    assert_eq!(copied.rects[0].xl, -70.0);
+}
+
+#[test]
+fn test_bump_array_cover_components() {
+    let content = fs::read_to_string("tests/test_samples/test_bumps.def")
+        .expect("Could not read tests/test_samples/test_bumps.def");
+    let (_, def) = def_parser::parse_def(&content).unwrap();
+    assert_eq!(def.components.len(), 19);
+
+    let covers: Vec<&str> = def
+        .components
+        .iter()
+        .filter(|c| c.is_cover())
+        .map(|c| c.name.as_str())
+        .collect();
+    assert_eq!(covers.len(), 17);
+    assert!(covers.contains(&"bump_3_3") && covers.contains(&"pfill_0"));
+    assert!(!covers.contains(&"core_buf"));
+
+    // 400 x 400 um die, 50 x 50 um bumps, 10 x 10 um pad filler, 2 x 1 um cells
+    let macro_area = |name: &str| match name {
+        "BUMP_50" => Some(2500.0),
+        "PADFILL" => Some(100.0),
+        "INVX1" | "BUFX1" => Some(2.0),
+        _ => None,
+    };
+    let is_physical_only = |name: &str| name == "PADFILL";
+
+    let core = utilization(&def, 1000.0, macro_area, is_physical_only, false).unwrap();
+    assert_eq!(core.cover_excluded, 17);
+    assert!((core.total - 4.0 / 160_000.0).abs() < 1e-12);
+    assert_eq!(core.total, core.logical);
+
+    let all = utilization(&def, 1000.0, macro_area, is_physical_only, true).unwrap();
+    assert_eq!(all.cover_excluded, 0);
+    assert!((all.total - 40_104.0 / 160_000.0).abs() < 1e-12);
+    assert!((all.logical - 40_004.0 / 160_000.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_pin_with_multiple_ports() {
+    let def_content = r#"
+VERSION 5.8 ;
+DESIGN ports ;
+UNITS DISTANCE MICRONS 1000 ;
+PINS 2 ;
+    - VDD + NET VDD + DIRECTION INOUT + USE POWER
+      + PORT
+        + LAYER M4 ( -500 0 ) ( 500 1000 )
+        + FIXED ( 10000 0 ) N
+      + PORT
+        + LAYER M4 ( -500 0 ) ( 500 1000 )
+        + VIA via34 ( 0 500 )
+        + FIXED ( 0 20000 ) E ;
+    - IN1 + NET n1 + DIRECTION INPUT
+      + LAYER M1 ( -70 0 ) ( 70 140 )
+      + PLACED ( 5000 0 ) S ;
+END PINS
+END DESIGN
+"#;
+    let (_, def) = def_parser::parse_def(def_content).unwrap();
+    let vdd = &def.pins[0];
+    assert_eq!(vdd.ports.len(), 2);
+    assert!(vdd.rects.is_empty());
+    assert_eq!(
+        (vdd.ports[1].x, vdd.ports[1].y, vdd.ports[1].orient.as_str()),
+        (0.0, 20000.0, "E")
+    );
+    assert_eq!(vdd.ports[1].vias, [("via34".to_string(), 0.0, 500.0)]);
+    // The pin takes the placement of its first port
+    assert_eq!((vdd.x, vdd.y, vdd.status.as_str()), (10000.0, 0.0, "FIXED"));
+
+    // Each port shape lands at its own placement, rotated by its orientation
+    let rects = vdd.placed_rects();
+    assert_eq!(rects.len(), 2);
+    assert_eq!(
+        (rects[0].xl, rects[0].yl, rects[0].xh),
+        (9500.0, 0.0, 10500.0)
+    );
+    assert_eq!(
+        (rects[1].xl, rects[1].yl, rects[1].xh, rects[1].yh),
+        (0.0, 20000.0 - 500.0 - 0.0, 1000.0, 20500.0)
+    );
+
+    let in1 = &def.pins[1];
+    assert!(in1.ports.is_empty());
+    let rects = in1.placed_rects();
+    assert_eq!((rects[0].yl, rects[0].yh), (-140.0, 0.0));
+
+    // Ports survive a write and reparse
+    let snippet = lefdef_viewer::def::writer::write_pin(vdd);
+    let reparsed = format!("DESIGN snippet ;\nPINS 1 ;\n{snippet}END PINS\nEND DESIGN\n");
+    let (_, copy) = def_parser::parse_def(&reparsed).unwrap();
+    assert_eq!(copy.pins[0].ports.len(), 2);
+    assert_eq!(copy.pins[0].ports[1].vias, vdd.ports[1].vias);
+    assert_eq!(copy.pins[0].ports[1].orient, "E");
+}
+
+#[test]
+fn test_write_def_round_trip() {
+    let def_content = r#"
+VERSION 5.8 ;
+DIVIDERCHAR "/" ;
+BUSBITCHARS "<>" ;
+DESIGN round_trip ;
+UNITS DISTANCE MICRONS 1000 ;
+DIEAREA ( 0 0 ) ( 20000 20000 ) ;
+ROW core_0 core 0 0 N DO 100 BY 1 STEP 190 0 ;
+ROW core_1 core 0 1400 FS DO 100 BY 1 STEP 190 0 ;
+TRACKS X 95 DO 105 STEP 190 LAYER metal2 ;
+TRACKS Y 70 DO 142 STEP 140 LAYER metal1 ;
+GCELLGRID X 0 DO 11 STEP 2000 ;
+GCELLGRID Y 0 DO 11 STEP 2000 ;
+
+VIAS 1 ;
+- via12
+  + RECT metal1 ( -100 -100 ) ( 100 100 )
+  + RECT metal2 ( -120 -80 ) ( 120 80 ) ;
+END VIAS
+
+COMPONENTS 2 ;
+- u1 INVX1 + PLACED ( 1000 2000 ) N ;
+- u2 BUFX2 + FIXED ( 5000 2000 ) FS + HALO 10 20 30 40 ;
+END COMPONENTS
+
+PINS 1 ;
+- a + NET a + DIRECTION INPUT + USE SIGNAL
+  + LAYER metal2 ( -70 0 ) ( 70 140 )
+  + PLACED ( 0 3000 ) E ;
+END PINS
+
+REGIONS 1 ;
+- fence1 ( 0 0 ) ( 8000 8000 ) ( 8000 0 ) ( 12000 4000 ) + TYPE FENCE ;
+END REGIONS
+
+BLOCKAGES 2 ;
+- LAYER metal2 + COMPONENT u1 RECT ( 0 0 ) ( 400 400 ) ;
+- PLACEMENT + PARTIAL 60 RECT ( 1000 1000 ) ( 3000 3000 ) ;
+END BLOCKAGES
+
+FILLS 2 ;
+- LAYER metal1 + OPC RECT ( 100 100 ) ( 200 200 ) ;
+- VIA via12 ( 500 500 ) ( 700 500 ) ;
+END FILLS
+
+SPECIALNETS 1 ;
+- VDD ( * VDD ) + USE POWER
+  + ROUTED metal1 340 + SHAPE FOLLOWPIN ( 0 0 ) ( 20000 0 ) via12
+    NEW metal2 200 ( 100 0 ) ( 100 20000 )
+  + RECT metal3 ( 0 0 ) ( 400 400 ) ;
+END SPECIALNETS
+
+NETS 1 ;
+- a ( PIN a ) ( u1 A )
+  + ROUTED metal2 ( 0 3000 ) ( 1000 * 35 ) via12
+    NEW metal1 ( 1000 3000 ) ( 1000 2100 )
+  + USE SIGNAL ;
+END NETS
+
+SCANCHAINS 1 ;
+- chain1 + START PIN scan_in
+  + FLOATING u1 ( IN SI ) ( OUT Q )
+  + ORDERED u2 ( BITS 4 ) u1
+  + STOP PIN scan_out ;
+END SCANCHAINS
+
+GROUPS 1 ;
+- grp1 u* + REGION fence1 ;
+END GROUPS
+END DESIGN
+"#;
+    let (_, def) = def_parser::parse_def(def_content).unwrap();
+    let text = lefdef_viewer::def::writer::write_def(&def);
+    let (_, copy) = def_parser::parse_def(&text).unwrap();
+
+    // Everything the parser reads survives a write and reparse
+    assert_eq!(
+        serde_json::to_value(&copy).unwrap(),
+        serde_json::to_value(&def).unwrap(),
+        "written DEF:\n{text}"
+    );
+    assert_eq!(copy.nets[0].routes[0].vias.len(), 1);
+    assert_eq!(copy.nets[0].routes[0].points[1].ext, Some(35.0));
+    assert_eq!(copy.special_nets[0].routes[0].vias.len(), 1);
+    assert_eq!(copy.special_nets[0].rects.len(), 1);
+    assert_eq!(copy.regions[0].rects.len(), 2);
+    assert_eq!(copy.groups[0].region.as_deref(), Some("fence1"));
+    assert_eq!(copy.fills[1].via_points.len(), 2);
+    assert_eq!(copy.scan_chains[0].sequence(), ["u2", "u1", "u1"]);
+    assert!(text.ends_with("END DESIGN\n"));
+
+    // Sample designs read back with the same contents
+    let content = fs::read_to_string("tests/test_samples/test_complex.def").unwrap();
+    let (_, def) = def_parser::parse_def(&content).unwrap();
+    assert_eq!(def.components[4].properties.len(), 2);
+    let (_, copy) = def_parser::parse_def(&lefdef_viewer::def::writer::write_def(&def)).unwrap();
+    assert_eq!(
+        serde_json::to_value(&copy).unwrap(),
+        serde_json::to_value(&def).unwrap()
+    );
+}
+
+#[test]
+fn test_gcellgrid_and_blockages() {
+    let def_content = r#"
+DESIGN blocked ;
+UNITS DISTANCE MICRONS 1000 ;
+DIEAREA ( 0 0 ) ( 100000 50000 ) ;
+GCELLGRID X 0 DO 21 STEP 5000 ;
+GCELLGRID Y 0 DO 6 STEP 10000 ;
+GCELLGRID Y 50000 DO 1 STEP 0 ;
+
+BLOCKAGES 3 ;
+- LAYER metal1 + SPACING 100
+    RECT ( 500 600 ) ( 100 200 ) ;
+- LAYER metal3 + COMPONENT ram0 + PUSHDOWN
+    RECT ( 0 0 ) ( 10000 10000 )
+    POLYGON ( 20000 0 ) ( 30000 0 ) ( 30000 10000 ) ;
+- PLACEMENT + SOFT RECT ( 40000 0 ) ( 60000 50000 ) ;
+END BLOCKAGES
+END DESIGN
+"#;
+    let (_, def) = def_parser::parse_def(def_content).unwrap();
+    assert_eq!(def.g_cell_grid_x.len(), 1);
+    assert_eq!(def.g_cell_grid_y.len(), 2);
+    assert_eq!(def.g_cell_grid_x[0].count, 21);
+    assert_eq!(def.g_cell_grid_y[0].step, 10000.0);
+
+    assert_eq!(def.blockages.len(), 3);
+    let metal1 = &def.blockages[0];
+    assert_eq!(metal1.layer.as_deref(), Some("metal1"));
+    // Corners are normalized
+    assert_eq!(
+        (
+            metal1.rects[0].xl,
+            metal1.rects[0].yl,
+            metal1.rects[0].xh,
+            metal1.rects[0].yh
+        ),
+        (100.0, 200.0, 500.0, 600.0)
+    );
+    let metal3 = &def.blockages[1];
+    assert_eq!(metal3.component.as_deref(), Some("ram0"));
+    assert_eq!(metal3.rects.len(), 1);
+    assert_eq!(metal3.polygons[0].points.len(), 3);
+    let placement = &def.blockages[2];
+    assert!(placement.layer.is_none() && placement.soft);
+    assert_eq!(placement.rects[0].xh, 60000.0);
+}
+
+#[test]
+fn test_regions_and_groups() {
+    let def_content = r#"
+DESIGN grouped ;
+UNITS DISTANCE MICRONS 1000 ;
+DIEAREA ( 0 0 ) ( 100000 50000 ) ;
+
+REGIONS 2 ;
+- core_fence ( 10000 10000 ) ( 0 0 )
+    ( 20000 0 ) ( 30000 5000 ) + TYPE FENCE ;
+- io_guide ( 40000 0 ) ( 50000 50000 ) + TYPE GUIDE + PROPERTY owner "top" ;
+END REGIONS
+
+COMPONENTS 3 ;
+- alu/u1 INVX1 + PLACED ( 1000 2000 ) N ;
+- alu/u22 INVX1 + PLACED ( 3000 2000 ) N ;
+- io_a PAD + FIXED ( 41000 0 ) N ;
+END COMPONENTS
+
+GROUPS 2 ;
+- alu_group alu/u?
+    + REGION core_fence ;
+- io io_* + PROPERTY kind pads ;
+END GROUPS
+END DESIGN
+"#;
+    let (_, def) = def_parser::parse_def(def_content).unwrap();
+
+    assert_eq!(def.regions.len(), 2);
+    let fence = &def.regions[0];
+    assert_eq!(fence.name, "core_fence");
+    assert_eq!(fence.region_type.as_deref(), Some("FENCE"));
+    assert_eq!(fence.rects.len(), 2);
+    // Corners are normalized
+    assert_eq!((fence.rects[0].xl, fence.rects[0].xh), (0.0, 10000.0));
+    assert_eq!(def.regions[1].properties.len(), 1);
+
+    assert_eq!(def.groups.len(), 2);
+    let alu = &def.groups[0];
+    assert_eq!(alu.patterns, ["alu/u?"]);
+    assert_eq!(alu.region.as_deref(), Some("core_fence"));
+    assert!(alu.contains("alu/u1"));
+    assert!(!alu.contains("alu/u22"));
+    let io = &def.groups[1];
+    assert!(io.region.is_none());
+    assert_eq!(io.properties, [("kind".to_string(), "pads".to_string())]);
+    assert!(io.contains("io_a"));
+    assert!(!io.contains("alu/u1"));
+}
+
+#[test]
+fn test_fills_and_scan_chains() {
+    let def_content = r#"
+DESIGN scanned ;
+UNITS DISTANCE MICRONS 1000 ;
+DIEAREA ( 0 0 ) ( 100000 50000 ) ;
+
+FILLS 3 ;
+- LAYER metal2 + MASK 1 RECT ( 500 600 ) ( 100 200 )
+    RECT ( 1000 1000 ) ( 2000 2000 ) ;
+- LAYER metal3 + OPC POLYGON ( 0 0 ) ( 3000 0 ) ( 3000 1000 ) ;
+- VIA via23 + MASK 031 ( 5000 5000 ) ( 6000 5000 ) ;
+END FILLS
+
+SCANCHAINS 2 ;
+- chain1
+  + PARTITION clk_part MAXBITS 100
+  + COMMONSCANPINS ( IN SI ) ( OUT SO )
+  + START ff0 Q
+  + FLOATING ff1 ff2 ( IN D ) ( BITS 2 )
+  + ORDERED ff3 ( OUT QN ) ff4
+  + ORDERED ff5 ff6
+  + STOP ff7 SI ;
+- chain2 + START PIN si + STOP PIN so ;
+END SCANCHAINS
+END DESIGN
+"#;
+    let (_, def) = def_parser::parse_def(def_content).unwrap();
+
+    assert_eq!(def.fills.len(), 3);
+    let metal2 = &def.fills[0];
+    assert_eq!(metal2.layer.as_deref(), Some("metal2"));
+    assert_eq!(metal2.rects.len(), 2);
+    assert_eq!((metal2.rects[0].xl, metal2.rects[0].yh), (100.0, 600.0));
+    assert!(def.fills[1].opc);
+    assert_eq!(def.fills[1].polygons[0].points.len(), 3);
+    let via = &def.fills[2];
+    assert_eq!(via.via.as_deref(), Some("via23"));
+    assert_eq!(via.via_points, [(5000.0, 5000.0), (6000.0, 5000.0)]);
+
+    assert_eq!(def.scan_chains.len(), 2);
+    let chain = &def.scan_chains[0];
+    assert_eq!(chain.partition.as_deref(), Some("clk_part"));
+    assert_eq!(chain.common_in.as_deref(), Some("SI"));
+    assert_eq!(chain.common_out.as_deref(), Some("SO"));
+    assert_eq!(
+        chain.start,
+        Some(("ff0".to_string(), Some("Q".to_string())))
+    );
+    assert_eq!(
+        chain.stop,
+        Some(("ff7".to_string(), Some("SI".to_string())))
+    );
+    assert_eq!(chain.floating.len(), 2);
+    assert_eq!(chain.floating[1].in_pin.as_deref(), Some("D"));
+    assert_eq!(chain.floating[1].bits, Some(2));
+    assert_eq!(chain.ordered.len(), 2);
+    assert_eq!(chain.ordered[0][0].out_pin.as_deref(), Some("QN"));
+    assert_eq!(
+        chain.sequence(),
+        ["ff0", "ff3", "ff4", "ff5", "ff6", "ff1", "ff2", "ff7"]
+    );
+
+    // IO pin ends are not components
+    let chain2 = &def.scan_chains[1];
+    assert_eq!(
+        chain2.start,
+        Some(("PIN".to_string(), Some("si".to_string())))
+    );
+    assert!(chain2.sequence().is_empty());
+}
+
+#[test]
+fn test_rows() {
+    let def_content = r#"
+DESIGN rows ;
+UNITS DISTANCE MICRONS 1000 ;
+DIEAREA ( 0 0 ) ( 20000 20000 ) ;
+ROW row_0 core 1000 2000 N DO 50 BY 1 STEP 190 0 ;
+ROW row_1 core 1000 3400 FS DO 50 BY 1 STEP 190 0
+  + PROPERTY rowkind "std" ;
+ROW col_0 io 0 0 W DO 1 BY 8 STEP 0 500 ;
+ROW single pad 100 100 N ;
+END DESIGN
+"#;
+    let (_, def) = def_parser::parse_def(def_content).unwrap();
+    assert_eq!(def.rows.len(), 4);
+
+    let row = &def.rows[1];
+    assert_eq!(
+        (row.name.as_str(), row.macro_name.as_str()),
+        ("row_1", "core")
+    );
+    assert_eq!((row.x, row.y), (1000.0, 3400.0));
+    assert_eq!(row.orientation, "FS");
+    assert_eq!((row.num_x, row.num_y), (50, 1));
+    assert_eq!((row.step_x, row.step_y), (190.0, 0.0));
+
+    // Sites of 0.19 x 1.4 um at 1000 database units per micron
+    assert_eq!(
+        row.bounds((190.0, 1400.0)),
+        (1000.0, 3400.0, 10500.0, 4800.0)
+    );
+    assert_eq!(
+        def.rows[2].bounds((300.0, 500.0)),
+        (0.0, 0.0, 300.0, 4000.0)
+    );
+
+    let single = &def.rows[3];
+    assert_eq!((single.num_x, single.num_y), (1, 1));
+    assert_eq!(single.bounds((50.0, 60.0)), (100.0, 100.0, 150.0, 160.0));
+}
+
+#[test]
+fn test_tracks() {
+    let def_content = r#"
+DESIGN tracks ;
+UNITS DISTANCE MICRONS 1000 ;
+DIEAREA ( 0 0 ) ( 20000 20000 ) ;
+TRACKS X 95 DO 105 STEP 190 LAYER metal2 metal4 ;
+TRACKS Y 70 DO 142 STEP 140 MASK 2 SAMEMASK LAYER metal1 ;
+TRACKS Y 100 DO 10 STEP 2000 ;
+END DESIGN
+"#;
+    let (_, def) = def_parser::parse_def(def_content).unwrap();
+
+    let layers: Vec<&str> = def.tracks_x.iter().map(|t| t.layer.as_str()).collect();
+    assert_eq!(layers, ["metal2", "metal4"]);
+    let track = &def.tracks_x[1];
+    assert_eq!((track.offset, track.num, track.step), (95.0, 105, 190.0));
+
+    // Tracks without a layer give no pattern
+    assert_eq!(def.tracks_y.len(), 1);
+    assert_eq!(def.tracks_y[0].layer, "metal1");
+    assert_eq!(def.tracks_y[0].step, 140.0);
+}
+
+#[test]
+fn test_parse_str() {
+    let def = lefdef_viewer::Def::parse_str(
+        "VERSION 5.8 ;\nDESIGN top ;\nUNITS DISTANCE MICRONS 1000 ;\n\
+         COMPONENTS 1 ;\n- u1 INV + PLACED ( 100 200 ) N ;\nEND COMPONENTS\nEND DESIGN\n",
+    )
+    .unwrap();
+    assert_eq!(def.design, "top");
+    assert_eq!(def.components.len(), 1);
+
+    // Like in files, statements that cannot be read are skipped
+    let def = lefdef_viewer::Def::parse_str("COMPONENTS x ;\n- u1 ( ( ;\n").unwrap();
+    assert!(def.components.is_empty());
+}
@@ -1,8 +1,16 @@
 // SPDX-License-Identifier: MIT
 // SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
 
-use lefdef_viewer::export::export_verilog_stub;
-use lefdef_viewer::lef::{Lef, LefMacro, LefPin};
+use lefdef_viewer::def::reader::DefReader;
+use lefdef_viewer::def::{DefComponent, DefPlacement};
+use lefdef_viewer::export::{
+    export_def_netlist_json, export_def_placement_to_csv, export_labels_to_csv, export_lef_to_csv,
+    export_lib_stub_with_voltage_config, export_pin_lint_to_csv, export_spice_stub,
+    export_verilog_stub, export_verilog_stub_with_options, export_view_to_svg, PlacedLabel,
+    SpiceStubOptions, SvgElement, SvgView, TimingArcTemplate, VerilogStubOptions, VoltageConfig,
+};
+use lefdef_viewer::lef::{Lef, LefDensity, LefMacro, LefPin};
+use lefdef_viewer::pin_lint::{default_rules, PinLinter};
 use std::env;
 use std::fs;
 
@@ -28,12 +36,13 @@ fn create_test_macro(name: &str) -> LefMacro {
         site: String::new(),
         pins: Vec::new(),
         obs: Vec::new(),
+        ..LefMacro::default()
     }
 }
 
 #[test]
 fn test_verilog_generation_single_pin() {
-    let mut lef_data = Lef { macros: Vec::new() };
+    let mut lef_data = Lef::default();
 
     // Create a simple macro with a single pin
     let mut macro_def = create_test_macro("TEST_CELL");
@@ -62,7 +71,7 @@ fn test_verilog_generation_single_pin() {
 
 #[test]
 fn test_verilog_generation_only_signal_pins() {
-    let mut lef_data = Lef { macros: Vec::new() };
+    let mut lef_data = Lef::default();
 
     // Create a macro with only signal pins
     let mut macro_def = create_test_macro("TEST_SIGNAL_ONLY");
@@ -106,7 +115,7 @@ fn test_verilog_generation_only_signal_pins() {
 
 #[test]
 fn test_verilog_generation_bus_pins() {
-    let mut lef_data = Lef { macros: Vec::new() };
+    let mut lef_data = Lef::default();
 
     // Create a macro with bus pins
     let mut macro_def = create_test_macro("TEST_BUS");
@@ -142,9 +151,76 @@ fn test_verilog_generation_bus_pins() {
     fs::remove_file(&temp_file).unwrap();
 }
 
+#[test]
+fn test_verilog_generation_flat_bus_pins() {
+    let mut lef_data = Lef::default();
+    let mut macro_def = create_test_macro("TEST_FLAT_BUS");
+    for i in 0..2 {
+        let pin = create_test_pin(&format!("DATA[{}]", i), "INPUT", "SIGNAL");
+        macro_def.pins.push(pin);
+    }
+    macro_def
+        .pins
+        .push(create_test_pin("OUT", "OUTPUT", "SIGNAL"));
+    lef_data.macros.push(macro_def);
+
+    let temp_file = env::temp_dir().join("test_flat_bus.v");
+    let options = VerilogStubOptions { group_buses: false };
+    export_verilog_stub_with_options(
+        &lef_data,
+        temp_file.to_str().unwrap(),
+        "test_flat_bus",
+        &options,
+    )
+    .unwrap();
+    let content = fs::read_to_string(&temp_file).unwrap();
+
+    // Bus bits are escaped identifiers, the space ends the name
+    assert!(content.contains("    input \\DATA[0] ,       /**< DATA[0] */"));
+    assert!(content.contains("    input \\DATA[1] ,       /**< DATA[1] */"));
+    assert!(content.contains("    output OUT       /**< OUT */"));
+    assert!(!content.contains("[1:0]"));
+
+    fs::remove_file(&temp_file).unwrap();
+}
+
+#[test]
+fn test_spice_stub_pin_order() {
+    let mut lef_data = Lef::default();
+    let mut macro_def = create_test_macro("NAND2");
+    macro_def
+        .pins
+        .push(create_test_pin("VDD", "INOUT", "POWER"));
+    macro_def
+        .pins
+        .push(create_test_pin("VSS", "INOUT", "GROUND"));
+    macro_def.pins.push(create_test_pin("A", "INPUT", "SIGNAL"));
+    macro_def
+        .pins
+        .push(create_test_pin("Y", "OUTPUT", "SIGNAL"));
+    macro_def.pins.push(create_test_pin("B", "INPUT", "SIGNAL"));
+    lef_data.macros.push(macro_def);
+
+    let temp_file = env::temp_dir().join("test_spice_stub.sp");
+    let path = temp_file.to_str().unwrap();
+    export_spice_stub(&lef_data, path, "cells", &SpiceStubOptions::default()).unwrap();
+    let content = fs::read_to_string(&temp_file).unwrap();
+    assert!(content.contains(".SUBCKT NAND2 A Y B VDD VSS\n"));
+    assert!(content.contains("*.PININFO A:I Y:O B:I VDD:P VSS:G\n"));
+    assert!(content.contains(".ENDS NAND2\n"));
+    assert!(content.ends_with(".END\n"));
+
+    let options = SpiceStubOptions { pg_pins: false };
+    export_spice_stub(&lef_data, path, "cells", &options).unwrap();
+    let content = fs::read_to_string(&temp_file).unwrap();
+    assert!(content.contains(".SUBCKT NAND2 A Y B\n"));
+
+    fs::remove_file(&temp_file).unwrap();
+}
+
 #[test]
 fn test_verilog_generation_power_pins() {
-    let mut lef_data = Lef { macros: Vec::new() };
+    let mut lef_data = Lef::default();
 
     // Create a macro with power pins
     let mut macro_def = create_test_macro("TEST_POWER");
@@ -186,7 +262,7 @@ fn test_verilog_generation_power_pins() {
 
 #[test]
 fn test_verilog_generation_pg_exist_mixed_pins() {
-    let mut lef_data = Lef { macros: Vec::new() };
+    let mut lef_data = Lef::default();
 
     // Create a macro with both signal and power pins
     let mut macro_def = create_test_macro("TEST_PG_EXIST_MIXED");
@@ -238,7 +314,7 @@ fn test_verilog_generation_pg_exist_mixed_pins() {
 
 #[test]
 fn test_verilog_generation_only_power_pins() {
-    let mut lef_data = Lef { macros: Vec::new() };
+    let mut lef_data = Lef::default();
 
     // Create a macro with only power pins (like a power switch cell)
     let mut macro_def = create_test_macro("POWER_SWITCH");
@@ -279,7 +355,7 @@ fn test_verilog_generation_only_power_pins() {
 fn test_iverilog_syntax_validation() {
     use std::process::Command;
 
-    let mut lef_data = Lef { macros: Vec::new() };
+    let mut lef_data = Lef::default();
 
     // Create a comprehensive test case
     let mut macro_def = create_test_macro("SYNTAX_TEST");
@@ -362,8 +438,8 @@ fn test_iverilog_syntax_validation() {
 
     // Clean up
     fs::remove_file(&temp_file).unwrap();
-    let _ = fs::remove_file(temp_dir.join("test_syntax_no_pg.vvp")).unwrap_or(());
-    let _ = fs::remove_file(temp_dir.join("test_syntax_with_pg.vvp")).unwrap_or(());
+    let _ = fs::remove_file(temp_dir.join("test_syntax_no_pg.vvp"));
+    let _ = fs::remove_file(temp_dir.join("test_syntax_with_pg.vvp"));
 }
 
 #[test]
@@ -371,7 +447,7 @@ fn test_iverilog_syntax_validation() {
 fn test_iverilog_validation_only_power() {
     use std::process::Command;
 
-    let mut lef_data = Lef { macros: Vec::new() };
+    let mut lef_data = Lef::default();
 
     // Create test case: only PG pins
     let mut macro_def = create_test_macro("POWER_SWITCH");
@@ -446,8 +522,8 @@ fn test_iverilog_validation_only_power() {
 
     // Clean up
     fs::remove_file(&temp_file).unwrap();
-    let _ = fs::remove_file(temp_dir.join("test_only_power_no_pg.vvp")).unwrap_or(());
-    let _ = fs::remove_file(temp_dir.join("test_only_power_with_pg.vvp")).unwrap_or(());
+    let _ = fs::remove_file(temp_dir.join("test_only_power_no_pg.vvp"));
+    let _ = fs::remove_file(temp_dir.join("test_only_power_with_pg.vvp"));
 }
 
 #[test]
@@ -455,7 +531,7 @@ fn test_iverilog_validation_only_power() {
 fn test_iverilog_validation_only_signal() {
     use std::process::Command;
 
-    let mut lef_data = Lef { macros: Vec::new() };
+    let mut lef_data = Lef::default();
 
     // Create test case: only signal pins
     let mut macro_def = create_test_macro("TEST_SIGNAL_ONLY");
@@ -506,12 +582,12 @@ fn test_iverilog_validation_only_signal() {
 
     // Clean up
     fs::remove_file(&temp_file).unwrap();
-    let _ = fs::remove_file(temp_dir.join("test_only_signal.vvp")).unwrap_or(());
+    let _ = fs::remove_file(temp_dir.join("test_only_signal.vvp"));
 }
 
 #[test]
 fn test_verilog_generation_pg_and_signal_pins() {
-    let mut lef_data = Lef { macros: Vec::new() };
+    let mut lef_data = Lef::default();
 
     // Create a macro with both PG and signal pins (PG first, then signal)
     let mut macro_def = create_test_macro("current_source_1to8");
@@ -568,7 +644,7 @@ fn test_verilog_generation_pg_and_signal_pins() {
 fn test_iverilog_validation_pg_and_signal() {
     use std::process::Command;
 
-    let mut lef_data = Lef { macros: Vec::new() };
+    let mut lef_data = Lef::default();
 
     // Create test case: PG pins + signal pins
     let mut macro_def = create_test_macro("current_source_1to8");
@@ -651,6 +727,309 @@ fn test_iverilog_validation_pg_and_signal() {
 
     // Clean up
     fs::remove_file(&temp_file).unwrap();
-    let _ = fs::remove_file(temp_dir.join("test_pg_signal_no_pg.vvp")).unwrap_or(());
-    let _ = fs::remove_file(temp_dir.join("test_pg_signal_with_pg.vvp")).unwrap_or(());
+    let _ = fs::remove_file(temp_dir.join("test_pg_signal_no_pg.vvp"));
+    let _ = fs::remove_file(temp_dir.join("test_pg_signal_with_pg.vvp"));
+}
+
+#[test]
+fn test_pin_lint_csv_export() {
+    let mut lef_data = Lef::default();
+    let mut macro_def = create_test_macro("TEST_CELL");
+    macro_def.pins.push(create_test_pin("CK", "INPUT", "CLOCK"));
+    macro_def
+        .pins
+        .push(create_test_pin("GCLK", "INPUT", "CLOCK"));
+    macro_def
+        .pins
+        .push(create_test_pin("VDD", "INOUT", "POWER"));
+    macro_def
+        .pins
+        .push(create_test_pin("GND", "INOUT", "GROUND"));
+    lef_data.macros.push(macro_def);
+
+    let linter = PinLinter::new(&default_rules()).unwrap();
+    let violations = linter.check([("cells.lef", &lef_data)]);
+    assert_eq!(violations.len(), 2);
+
+    let temp_file = env::temp_dir().join("test_pin_lint.csv");
+    export_pin_lint_to_csv(&violations, temp_file.to_str().unwrap()).unwrap();
+
+    let content = fs::read_to_string(&temp_file).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines[0], "File,Macro,Pin,Class,Rule");
+    assert_eq!(lines[1], "cells.lef,TEST_CELL,GCLK,CLOCK,CK.*|CLK.*");
+    assert_eq!(lines[2], "cells.lef,TEST_CELL,GND,GROUND,VSS.*");
+
+    fs::remove_file(&temp_file).unwrap();
+}
+
+#[test]
+fn test_view_svg_export() {
+    let view = SvgView {
+        width: 200.0,
+        height: 100.0,
+        output_size: Some((400.0, 200.0)),
+        background: Some([0, 0, 0, 255]),
+        elements: vec![
+            SvgElement::Polygon {
+                points: vec![(10.0, 10.0), (20.0, 10.0), (20.0, 20.0), (10.0, 20.0)],
+                fill: Some([255, 0, 0, 128]),
+                stroke: Some(([255, 255, 255, 255], 1.5)),
+            },
+            SvgElement::Polyline {
+                points: vec![(0.0, 0.0), (200.0, 100.0)],
+                stroke: ([0, 255, 0, 255], 2.0),
+            },
+            SvgElement::Circle {
+                center: (50.0, 50.0),
+                radius: 4.0,
+                fill: Some([0, 0, 255, 255]),
+                stroke: None,
+            },
+            SvgElement::Text {
+                pos: (30.0, 40.0),
+                text: "A<0> & B".to_string(),
+                size: 14.0,
+                color: [255, 255, 255, 255],
+                angle: std::f32::consts::FRAC_PI_2,
+            },
+        ],
+    };
+
+    let temp_file = env::temp_dir().join("test_view.svg");
+    export_view_to_svg(&view, temp_file.to_str().unwrap()).unwrap();
+
+    let content = fs::read_to_string(&temp_file).unwrap();
+    assert!(content.starts_with("<?xml"));
+    assert!(content.contains("width=\"400\" height=\"200\" viewBox=\"0 0 200.00 100.00\""));
+    assert!(content.contains(
+        "<polygon points=\"10.00,10.00 20.00,10.00 20.00,20.00 10.00,20.00\" \
+         fill=\"#ff0000\" fill-opacity=\"0.502\" stroke=\"#ffffff\" stroke-width=\"1.50\"/>"
+    ));
+    assert!(content.contains("<polyline points=\"0.00,0.00 200.00,100.00\" fill=\"none\""));
+    assert!(content.contains("<circle cx=\"50.00\" cy=\"50.00\" r=\"4.00\" fill=\"#0000ff\"/>"));
+    assert!(content.contains("rotate(90.00 30.00 40.00)"));
+    assert!(content.contains(">A&lt;0&gt; &amp; B</text>"));
+    assert!(content.trim_end().ends_with("</svg>"));
+
+    fs::remove_file(&temp_file).unwrap();
+}
+
+#[test]
+fn test_label_csv_export() {
+    let labels = vec![
+        PlacedLabel {
+            text: "u1".to_string(),
+            x: 12.5,
+            y: 40.0,
+            rotation: 0.0,
+            anchor: "CENTER_CENTER".to_string(),
+            size: 12.0,
+        },
+        PlacedLabel {
+            text: "pad, \"A\"".to_string(),
+            x: 1000.0,
+            y: 250.125,
+            rotation: 90.0,
+            anchor: "LEFT_CENTER".to_string(),
+            size: 9.5,
+        },
+    ];
+
+    let temp_file = env::temp_dir().join("test_labels.csv");
+    export_labels_to_csv(&labels, temp_file.to_str().unwrap()).unwrap();
+
+    let content = fs::read_to_string(&temp_file).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "Text,X (um),Y (um),Rotation (deg),Anchor,Size (pt)",
+            "u1,12.500,40.000,0.0,CENTER_CENTER,12.0",
+            "\"pad, \"\"A\"\"\",1000.000,250.125,90.0,LEFT_CENTER,9.5",
+        ]
+    );
+
+    fs::remove_file(&temp_file).unwrap();
+}
+
+#[test]
+fn test_lef_csv_density_columns() {
+    let window = |layer: &str, xh: f64, density: f64| LefDensity {
+        layer: layer.to_string(),
+        xl: 0.0,
+        yl: 0.0,
+        xh,
+        yh: 1.0,
+        density,
+    };
+    let mut dense = create_test_macro("DENSE");
+    dense.density = vec![window("metal1", 1.0, 20.0), window("metal2", 3.0, 60.0)];
+    let lef = Lef {
+        macros: vec![dense, create_test_macro("PLAIN")],
+        ..Lef::default()
+    };
+
+    let temp_file = env::temp_dir().join("test_lef_density.csv");
+    export_lef_to_csv(&lef, temp_file.to_str().unwrap()).unwrap();
+
+    let content = fs::read_to_string(&temp_file).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(
+        lines[0],
+        "Micro,Class,Size,Pins,Area,Pinlist,Density Windows,Min Density (%),Max Density (%),Avg Density (%)"
+    );
+    assert!(lines[1].ends_with(",2,20.00,60.00,50.00"));
+    assert!(lines[2].ends_with(",0,,,"));
+
+    fs::remove_file(&temp_file).unwrap();
+}
+
+#[test]
+fn test_def_placement_csv_export() {
+    let component = |name: &str, placement: Option<DefPlacement>| DefComponent {
+        name: name.to_string(),
+        macro_name: "INVX1".to_string(),
+        placement,
+        ..DefComponent::default()
+    };
+    let components = vec![
+        component(
+            "u1",
+            Some(DefPlacement {
+                placement_type: "FIXED".to_string(),
+                x: 1500.0,
+                y: 2000.0,
+                orientation: "FS".to_string(),
+            }),
+        ),
+        component("u2", None),
+    ];
+
+    let temp_file = env::temp_dir().join("test_def_placement.csv");
+    export_def_placement_to_csv(&components, 1000.0, temp_file.to_str().unwrap()).unwrap();
+
+    let content = fs::read_to_string(&temp_file).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines[0], "Instance,Macro,X (um),Y (um),Orient,Status");
+    assert_eq!(lines[1], "u1,INVX1,1.500,2.000,FS,FIXED");
+    assert_eq!(lines[2], "u2,INVX1,,,,UNPLACED");
+
+    fs::remove_file(&temp_file).unwrap();
+}
+
+#[test]
+fn test_def_placement_csv_export_keeps_fine_units() {
+    let placed = |name: &str, x: f64| DefComponent {
+        name: name.to_string(),
+        macro_name: "INVX1".to_string(),
+        placement: Some(DefPlacement {
+            placement_type: "PLACED".to_string(),
+            x,
+            y: 2001.0,
+            orientation: "N".to_string(),
+        }),
+        ..DefComponent::default()
+    };
+    // 0.0005 um apart at 2000 units, merged when rounded to 0.001
+    let components = vec![placed("u1", 3001.0), placed("u2", 3002.0)];
+
+    let temp_file = env::temp_dir().join("test_def_placement_2000.csv");
+    export_def_placement_to_csv(&components, 2000.0, temp_file.to_str().unwrap()).unwrap();
+
+    let content = fs::read_to_string(&temp_file).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines[1], "u1,INVX1,1.5005,1.0005,N,PLACED");
+    assert_eq!(lines[2], "u2,INVX1,1.5010,1.0005,N,PLACED");
+
+    fs::remove_file(&temp_file).unwrap();
+}
+
+#[test]
+fn test_def_netlist_json_export() {
+    let def_file = env::temp_dir().join("test_netlist_export.def");
+    fs::write(
+        &def_file,
+        "VERSION 5.8 ;\nDESIGN top ;\nUNITS DISTANCE MICRONS 1000 ;\n\
+         NETS 2 ;\n\
+         - n1 ( u1 A ) ( u2 Y ) + USE SIGNAL\n  + ROUTED metal1 ( 0 0 ) ( 2000 0 ) via12\n    NEW metal2 ( 2000 0 ) ( 2000 1000 ) ;\n\
+         - n2 ( PIN in ) ( u1 B ) ;\n\
+         END NETS\nEND DESIGN\n",
+    )
+    .unwrap();
+    let def = DefReader::new().read(def_file.to_str().unwrap()).unwrap();
+
+    let json_file = env::temp_dir().join("test_netlist_export.json");
+    let layer_order = vec!["metal1".to_string(), "metal2".to_string()];
+    export_def_netlist_json(&def, 1000.0, &layer_order, json_file.to_str().unwrap()).unwrap();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&json_file).unwrap()).unwrap();
+    assert_eq!(json["version"], 1);
+    assert_eq!(json["design"], "top");
+    let nets = json["nets"].as_array().unwrap();
+    assert_eq!(nets.len(), 2);
+    assert_eq!(nets[0]["name"], "n1");
+    assert_eq!(nets[0]["use"], "SIGNAL");
+    assert_eq!(nets[0]["connections"][1]["instance"], "u2");
+    assert_eq!(nets[0]["connections"][1]["pin"], "Y");
+    assert_eq!(nets[0]["routing"]["length"], 3.0);
+    assert_eq!(nets[0]["routing"]["vias"], 1);
+    assert_eq!(nets[0]["routing"]["layers"][1]["layer"], "metal2");
+    assert_eq!(nets[1]["connections"][0]["instance"], "PIN");
+    assert_eq!(nets[1]["routing"]["length"], 0.0);
+    assert!(nets[1].get("use").is_none());
+
+    fs::remove_file(&def_file).unwrap();
+    fs::remove_file(&json_file).unwrap();
+}
+
+#[test]
+fn test_lib_stub_pin_limits_and_timing_arcs() {
+    let mut macro_def = create_test_macro("NAND2");
+    macro_def.pins = vec![
+        create_test_pin("A", "INPUT", "SIGNAL"),
+        create_test_pin("B", "INPUT", "SIGNAL"),
+        create_test_pin("Y", "OUTPUT", "SIGNAL"),
+    ];
+    let lef = Lef {
+        macros: vec![macro_def],
+        ..Lef::default()
+    };
+    let config = VoltageConfig {
+        input_capacitance: 0.005,
+        max_transition: Some(0.4),
+        max_capacitance: Some(0.15),
+        timing_arc: Some(TimingArcTemplate {
+            delay: 0.08,
+            transition: 0.03,
+        }),
+        ..VoltageConfig::default()
+    };
+
+    let temp_file = env::temp_dir().join("test_lib_timing_arcs.lib");
+    export_lib_stub_with_voltage_config(&lef, temp_file.to_str().unwrap(), &config).unwrap();
+    let content = fs::read_to_string(&temp_file).unwrap();
+
+    assert_eq!(content.matches("capacitance : 0.005;").count(), 3);
+    assert_eq!(content.matches("max_transition : 0.4 ;").count(), 1);
+    assert_eq!(content.matches("max_capacitance : 0.15 ;").count(), 1);
+    assert_eq!(content.matches("timing () {").count(), 1);
+    assert!(content.contains("related_pin : \"A B\" ;"));
+    assert!(content.contains("cell_rise (scalar) { values (\"0.08\") ; }"));
+    assert!(content.contains("fall_transition (scalar) { values (\"0.03\") ; }"));
+
+    // The limits and arcs are opt-in
+    export_lib_stub_with_voltage_config(
+        &lef,
+        temp_file.to_str().unwrap(),
+        &VoltageConfig::default(),
+    )
+    .unwrap();
+    let content = fs::read_to_string(&temp_file).unwrap();
+    assert_eq!(content.matches("capacitance : 0.02;").count(), 3);
+    assert!(!content.contains("max_transition"));
+    assert!(!content.contains("timing ()"));
+
+    fs::remove_file(&temp_file).unwrap();
 }
@@ -0,0 +1,149 @@
+// SPDX-License-Identifier: MIT
+// SPDX-FileCopyrightText: 2025 Huang Rui <vowstar@gmail.com>
+
+//! Regression tests from parser bug reports
+//!
+//! Every `tests/fixtures/<name>.lef` or `<name>.def` is a minimized input
+//! from a bug report, usually saved with Help > Report a Parser Bug. It is
+//! parsed and checked against `<name>.expect`, one `path = value` assertion
+//! per line with `#` comments. Paths walk the JSON model from
+//! `lefdef_viewer::reproducer`, `len` gives the length of a list, and values
+//! are JSON literals or bare strings.
+//!
+//! Set `FIXTURE_DUMP=1` to print the parsed model of every fixture, which
+//! helps with writing the assertions of a new one.
+
+use lefdef_viewer::def::def_parser::parse_def;
+use lefdef_viewer::lef::lef_parser::parse_lef;
+use lefdef_viewer::reproducer::{def_summary, lef_summary};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// Follow a dotted path through the model
+fn lookup(model: &Value, path: &str) -> Option<Value> {
+    let mut current = model;
+    let mut segments = path.split('.').peekable();
+    while let Some(segment) = segments.next() {
+        if segment == "len" && segments.peek().is_none() {
+            return match current {
+                Value::Array(items) => Some(Value::from(items.len())),
+                Value::Object(fields) => Some(Value::from(fields.len())),
+                _ => None,
+            };
+        }
+        current = match current {
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            Value::Object(fields) => fields.get(segment)?,
+            _ => return None,
+        };
+    }
+    Some(current.clone())
+}
+
+fn matches(actual: &Value, expected: &Value) -> bool {
+    match (actual.as_f64(), expected.as_f64()) {
+        (Some(a), Some(e)) => (a - e).abs() <= 1e-6 * e.abs().max(1.0),
+        _ => actual == expected,
+    }
+}
+
+/// Check one fixture, returning a message per failed assertion
+fn check_fixture(source: &Path) -> Vec<String> {
+    let name = source.file_name().unwrap().to_string_lossy().to_string();
+    let content = fs::read_to_string(source).unwrap();
+    let model = match source.extension().and_then(|e| e.to_str()) {
+        Some("lef") => match parse_lef(&content) {
+            Ok((_, lef)) => lef_summary(&lef),
+            Err(e) => return vec![format!("{name}: parse failed: {e:?}")],
+        },
+        _ => match parse_def(&content) {
+            Ok((_, def)) => def_summary(&def),
+            Err(e) => return vec![format!("{name}: parse failed: {e:?}")],
+        },
+    };
+    if std::env::var_os("FIXTURE_DUMP").is_some() {
+        println!("{name}:\n{}", serde_json::to_string_pretty(&model).unwrap());
+    }
+
+    let Ok(expect) = fs::read_to_string(source.with_extension("expect")) else {
+        return vec![format!("{name}: missing .expect file")];
+    };
+    let mut failures = Vec::new();
+    let mut assertions = 0;
+    for (number, line) in expect.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((path, value)) = line.split_once('=') else {
+            failures.push(format!(
+                "{name}.expect:{}: expected `path = value`",
+                number + 1
+            ));
+            continue;
+        };
+        let (path, value) = (path.trim(), value.trim());
+        let expected =
+            serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()));
+        assertions += 1;
+        match lookup(&model, path) {
+            Some(actual) if matches(&actual, &expected) => {}
+            Some(actual) => failures.push(format!(
+                "{name}.expect:{}: {path} is {actual}, expected {expected}",
+                number + 1
+            )),
+            None => failures.push(format!(
+                "{name}.expect:{}: {path} does not exist",
+                number + 1
+            )),
+        }
+    }
+    if assertions == 0 {
+        failures.push(format!("{name}.expect: no assertions"));
+    }
+    failures
+}
+
+#[test]
+fn test_parser_fixtures() {
+    let mut sources: Vec<PathBuf> = fs::read_dir(fixtures_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("lef" | "def")
+            )
+        })
+        .collect();
+    sources.sort();
+    assert!(!sources.is_empty(), "no fixtures found");
+
+    let failures: Vec<String> = sources.iter().flat_map(|s| check_fixture(s)).collect();
+    assert!(
+        failures.is_empty(),
+        "{} fixture assertions failed:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_lookup_paths() {
+    let model = serde_json::json!({
+        "macros": [{"name": "INV", "size": [1.5, 2.0], "pins": []}],
+    });
+    assert_eq!(lookup(&model, "macros.len"), Some(Value::from(1)));
+    assert_eq!(lookup(&model, "macros.0.name"), Some(Value::from("INV")));
+    assert_eq!(lookup(&model, "macros.0.size.1"), Some(Value::from(2.0)));
+    assert_eq!(lookup(&model, "macros.0.pins.len"), Some(Value::from(0)));
+    assert_eq!(lookup(&model, "macros.1.name"), None);
+    assert_eq!(lookup(&model, "macros.0.name.len"), None);
+    assert!(matches(&Value::from(0.1 + 0.2), &Value::from(0.3)));
+    assert!(!matches(&Value::from("N"), &Value::from("FN")));
+}
@@ -354,6 +354,7 @@ END LIBRARY
 }
 
 #[test]
+#[allow(clippy::assertions_on_constants, clippy::useless_vec)]
 fn test_winding_direction_calculation() {
     // This test checks the winding direction calculation for polygons
     // Clockwise points (positive area - solid shape)
@@ -369,6 +370,85 @@ fn test_winding_direction_calculation() {
     );
 }
 
+#[test]
+fn test_polygon_winding_sets_hole_flag() {
+    // Counter-clockwise outline is solid, clockwise outline is a hole
+    let lef_content = r#"
+MACRO WINDING
+   CLASS CORE ;
+   SIZE 2.0 BY 2.0 ;
+   PIN A
+      DIRECTION INPUT ;
+      PORT
+         LAYER M1 ;
+         POLYGON 0.0 0.0 1.0 0.0 1.0 1.0 0.0 1.0 ;
+         POLYGON 0.0 0.0 0.0 1.0 1.0 1.0 1.0 0.0 ;
+      END
+   END A
+END WINDING
+"#;
+
+    let (_, lef) = lef_parser::parse_lef(lef_content).unwrap();
+    let polygons = &lef.macros[0].pins[0].ports[0].polygons;
+    assert_eq!(polygons.len(), 2);
+    assert!(!polygons[0].is_hole);
+    assert!(polygons[1].is_hole);
+}
+
+#[test]
+fn test_geometry_hash_shared_by_identical_macros() {
+    let lef_content = r#"
+MACRO INV_X1
+   CLASS CORE ;
+   SIZE 1.0 BY 1.0 ;
+   PIN A
+      DIRECTION INPUT ;
+      PORT
+         LAYER M1 ;
+         RECT 0.0 0.4 0.2 0.6 ;
+      END
+   END A
+END INV_X1
+
+MACRO INV_X1_ALT
+   CLASS CORE ;
+   SIZE 1.2 BY 1.0 ;
+   PIN A
+      DIRECTION INPUT ;
+      PORT
+         LAYER M1 ;
+         RECT 0.0 0.4 0.2 0.6 ;
+      END
+   END A
+END INV_X1_ALT
+
+MACRO INV_X2
+   CLASS CORE ;
+   SIZE 1.0 BY 1.0 ;
+   PIN A
+      DIRECTION INPUT ;
+      PORT
+         LAYER M2 ;
+         RECT 0.0 0.4 0.2 0.6 ;
+      END
+   END A
+END INV_X2
+"#;
+
+    let (_, lef) = lef_parser::parse_lef(lef_content).unwrap();
+    assert_eq!(lef.macros.len(), 3);
+
+    // Same shapes on the same layers share a hash regardless of name and SIZE
+    assert_eq!(lef.macros[0].geometry_hash(), lef.macros[1].geometry_hash());
+    // A different layer changes the hash
+    assert_ne!(lef.macros[0].geometry_hash(), lef.macros[2].geometry_hash());
+
+    // Meshes are cached in LEF coordinates before ORIGIN is applied
+    let mut shifted = lef.macros[0].clone();
+    shifted.origin = (0.1, 0.0);
+    assert_eq!(lef.macros[0].geometry_hash(), shifted.geometry_hash());
+}
+
 #[cfg(test)]
 mod integration_tests {
     use super::*;
@@ -402,3 +482,451 @@ mod integration_tests {
         }
     }
 }
+
+#[test]
+fn test_macro_names_with_special_characters() {
+    let lef_content = r#"
+VERSION 5.8 ;
+BUSBITCHARS "[]" ;
+
+MACRO IN
+  CLASS CORE ;
+  SIZE 1.0 BY 2.0 ;
+  PIN INV
+    DIRECTION INPUT ;
+    PORT
+      LAYER M1 ;
+        RECT 0.1 0.1 0.2 0.2 ;
+    END
+  END INV
+  PIN Y
+    DIRECTION OUTPUT ;
+    PORT
+      LAYER M1 ;
+        RECT 0.5 0.5 0.6 0.6 ;
+    END
+  END Y
+END IN
+
+MACRO \cell$1\[0\]
+  CLASS CORE ;
+  SIZE 1.0 BY 2.0 ;
+END \cell$1\[0\]
+
+END LIBRARY
+"#;
+
+    let (_, lef) = lef_parser::parse_lef(lef_content).unwrap();
+    assert_eq!(lef.macros.len(), 2);
+
+    // "END INV" closes the pin, not the macro named "IN"
+    assert_eq!(lef.macros[0].name, "IN");
+    assert_eq!(lef.macros[0].pins.len(), 2);
+
+    assert_eq!(lef.macros[1].name, r"\cell$1\[0\]");
+}
+
+#[test]
+fn test_extract_and_reparse_macro_source() {
+    let lef_content = r#"
+VERSION 5.8 ;
+
+MACRO INVX1
+  CLASS CORE ;
+  SIZE 1.0 BY 2.0 ;
+  PIN A
+    DIRECTION INPUT ;
+    PORT
+      LAYER M1 ;
+        RECT 0.1 0.1 0.2 0.2 ;
+    END
+  END A
+END INVX1
+
+MACRO INVX1_HV
+  CLASS CORE ;
+  SIZE 2.0 BY 2.0 ;
+END INVX1_HV
+
+END LIBRARY
+"#;
+
+    let source = lef_parser::extract_macro_source(lef_content, "INVX1").unwrap();
+    assert!(source.starts_with("MACRO INVX1\n"));
+    assert!(source.trim_end().ends_with("END INVX1"));
+    assert!(!source.contains("INVX1_HV"));
+    assert!(lef_parser::extract_macro_source(lef_content, "MISSING").is_none());
+
+    // Edit the abstract and reparse just this macro
+    let edited = source
+        .replace("SIZE 1.0 BY 2.0", "SIZE 1.5 BY 2.0")
+        .replace("RECT 0.1 0.1 0.2 0.2", "RECT 0.1 0.1 0.4 0.2");
+    let macro_def = lef_parser::parse_macro_source(&edited).unwrap();
+    assert_eq!(macro_def.name, "INVX1");
+    assert_eq!(macro_def.size_x, 1.5);
+    assert_eq!(macro_def.pins[0].ports[0].rects[0].xh, 0.4);
+
+    // Unterminated or multiple macros are rejected
+    assert!(lef_parser::parse_macro_source("MACRO BROKEN\n  SIZE 1 BY 1 ;\n").is_err());
+    assert!(lef_parser::parse_macro_source(lef_content).is_err());
+}
+
+#[test]
+fn test_physical_only_cells() {
+    let lef_content = r#"
+MACRO FILLER_A
+  CLASS CORE SPACER ;
+  SIZE 0.2 BY 2.0 ;
+END FILLER_A
+
+MACRO WT
+  CLASS CORE WELLTAP ;
+  SIZE 0.4 BY 2.0 ;
+END WT
+
+MACRO NAND2
+  CLASS CORE ;
+  SIZE 1.0 BY 2.0 ;
+END NAND2
+
+MACRO DECAPX4
+  CLASS CORE ;
+  SIZE 2.0 BY 2.0 ;
+END DECAPX4
+
+END LIBRARY
+"#;
+
+    let (_, lef) = lef_parser::parse_lef(lef_content).unwrap();
+    assert_eq!(lef.macros[0].class, "CORE SPACER");
+
+    let physical: Vec<bool> = lef.macros.iter().map(|m| m.is_physical_only()).collect();
+    // WT has no telling name, its CLASS identifies it; DECAPX4 only by name
+    assert_eq!(physical, [true, true, false, true]);
+
+    assert!(lefdef_viewer::lef::is_physical_only_cell(
+        "sky130_fd_sc_hd__tapvpwrvgnd_1",
+        None
+    ));
+    assert!(!lefdef_viewer::lef::is_physical_only_cell(
+        "INVX1",
+        Some("CORE")
+    ));
+}
+
+#[test]
+fn test_units_database_microns() {
+    let lef_content = r#"
+VERSION 5.8 ;
+UNITS
+  DATABASE MICRONS 2000 ;
+  CAPACITANCE PICOFARADS 1 ;
+END UNITS
+MANUFACTURINGGRID 0.005 ;
+
+MACRO INVX1
+  CLASS CORE ;
+  SIZE 1.0 BY 2.0 ;
+END INVX1
+
+END LIBRARY
+"#;
+
+    let (_, lef) = lef_parser::parse_lef(lef_content).unwrap();
+    assert_eq!(lef.database_microns, Some(2000.0));
+    assert_eq!(lef.manufacturing_grid, Some(0.005));
+    assert_eq!(lef.macros.len(), 1);
+
+    let units = lefdef_viewer::units::UnitsCheck::new(Some(1000.0), lef.database_microns);
+    assert!(units.is_mismatch());
+    assert_eq!(units.db_units(), 1000.0);
+}
+
+#[test]
+fn test_macro_snippet_reparses() {
+    let lef_content = r#"
+MACRO NAND2X1
+  CLASS CORE ;
+  SIZE 1.2 BY 2.4 ;
+  SITE core ;
+  PIN A
+    DIRECTION INPUT ;
+    USE SIGNAL ;
+    PORT
+      LAYER M1 ;
+        RECT 0.1 0.2 0.3 0.4 ;
+        POLYGON 0.5 0.5 0.9 0.5 0.9 0.9 ;
+    END
+  END A
+  OBS
+    LAYER M2 ;
+      RECT 0 0 1.2 0.1 ;
+  END
+END NAND2X1
+
+END LIBRARY
+"#;
+    let (_, lef) = lef_parser::parse_lef(lef_content).unwrap();
+    let snippet = lefdef_viewer::lef::writer::write_macro(&lef.macros[0]);
+    assert!(snippet.contains("SIZE 1.2 BY 2.4 ;"));
+    assert!(lefdef_viewer::lef::writer::write_pin(&lef.macros[0].pins[0]).starts_with("PIN A\n"));
+
+    let (_, copy) = lef_parser::parse_lef(&format!("{snippet}\nEND LIBRARY\n")).unwrap();
+    let (original, copied) = (&lef.macros[0], &copy.macros[0]);
+    assert_eq!(copied.name, original.name);
+    assert_eq!(copied.class, original.class);
+    assert_eq!(
+        (copied.size_x, copied.size_y),
+        (original.size_x, original.size_y)
+    );
+    assert_eq!(copied.pins.len(), 1);
+    assert_eq!(copied.pins[0].direction, "INPUT");
+    assert_eq!(copied.pins[0].ports[0].rects.len(), 1);
+    assert_eq!(copied.pins[0].ports[0].polygons.len(), 1);
+    assert_eq!(copied.obs[0].rects[0].layer, "M2");
+    assert_eq!(copied.geometry_hash(), original.geometry_hash());
+}
+
+#[test]
+fn test_technology_layers() {
+    let lef_content = r#"
+VERSION 5.8 ;
+UNITS
+  DATABASE MICRONS 1000 ;
+END UNITS
+
+LAYER poly
+  TYPE MASTERSLICE ;
+END poly
+
+LAYER contact
+  TYPE CUT ;
+  SPACING 0.075 ;
+  RESISTANCE 10.5 ;
+END contact
+
+LAYER metal1
+  TYPE ROUTING ;
+  DIRECTION HORIZONTAL ;
+  PITCH 0.14 0.14 ;
+  WIDTH 0.07 ;
+  AREA 0.0115 ;
+  SPACING 0.13 RANGE 0.3 10 ;
+  SPACING 0.065 ;
+  RESISTANCE RPERSQ 0.38 ;
+  CAPACITANCE CPERSQDIST 7.7161e-05 ;
+END metal1
+
+VIA via1_def DEFAULT
+  LAYER via1 ;
+    RECT -0.035 -0.035 0.035 0.035 ;
+END via1_def
+
+SITE core
+  CLASS CORE ;
+  SYMMETRY Y ;
+  SIZE 0.19 BY 1.4 ;
+END core
+
+SITE io
+  SIZE 60 BY 200 ;
+  CLASS PAD ;
+END io
+
+MACRO INVX1
+  CLASS CORE ;
+  SIZE 1.0 BY 2.0 ;
+  SITE core ;
+  OBS
+    LAYER metal1 ;
+      RECT 0 0 1 0.1 ;
+  END
+END INVX1
+
+END LIBRARY
+"#;
+
+    let (_, lef) = lef_parser::parse_lef(lef_content).unwrap();
+    let names: Vec<&str> = lef.layers.iter().map(|l| l.name.as_str()).collect();
+    assert_eq!(names, ["poly", "contact", "metal1"]);
+
+    let contact = &lef.layers[1];
+    assert!(contact.is_cut());
+    assert_eq!(contact.resistance, Some(10.5));
+
+    let metal1 = &lef.layers[2];
+    assert!(metal1.is_routing());
+    assert_eq!(metal1.direction, "HORIZONTAL");
+    assert_eq!(metal1.pitch, Some(0.14));
+    assert_eq!(metal1.width, Some(0.07));
+    assert_eq!(metal1.spacing, Some(0.065));
+    assert_eq!(metal1.resistance, Some(0.38));
+    assert_eq!(metal1.capacitance, Some(7.7161e-05));
+    assert_eq!(metal1.min_area, Some(0.0115));
+    assert_eq!(contact.min_area, None);
+    assert_eq!(lef.macros.len(), 1);
+
+    // The SITE reference of a macro is not a definition
+    assert_eq!(lef.sites.len(), 2);
+    let core = &lef.sites[0];
+    assert_eq!(core.name, "core");
+    assert_eq!(core.class, "CORE");
+    assert_eq!((core.size_x, core.size_y), (0.19, 1.4));
+    assert_eq!(core.symmetry, ["Y"]);
+    assert_eq!(lef.sites[1].class, "PAD");
+    assert_eq!((lef.sites[1].size_x, lef.sites[1].size_y), (60.0, 200.0));
+}
+
+#[test]
+fn test_write_lef_round_trip() {
+    let lef_content = r#"
+VERSION 5.8 ;
+UNITS
+  DATABASE MICRONS 2000 ;
+END UNITS
+MANUFACTURINGGRID 0.005 ;
+
+LAYER via1
+  TYPE CUT ;
+  RESISTANCE 4.5 ;
+END via1
+
+LAYER metal2
+  TYPE ROUTING ;
+  DIRECTION VERTICAL ;
+  PITCH 0.19 ;
+  WIDTH 0.07 ;
+  RESISTANCE RPERSQ 0.25 ;
+END metal2
+
+SITE core
+  CLASS CORE ;
+  SYMMETRY X Y ;
+  SIZE 0.19 BY 1.71 ;
+END core
+
+MACRO NAND2X1
+  CLASS CORE ;
+  ORIGIN 0 0 ;
+  SIZE 1.14 BY 1.71 ;
+  SYMMETRY X Y ;
+  SITE core ;
+  PIN A
+    DIRECTION INPUT ;
+    USE SIGNAL ;
+    PORT
+      LAYER metal1 ;
+        RECT 0.1 0.5 0.2 0.7 ;
+        POLYGON 0.3 0.3 0.5 0.3 0.5 0.6 0.3 0.6 ;
+    END
+  END A
+  PIN VDD
+    DIRECTION INOUT ;
+    USE POWER ;
+    SHAPE ABUTMENT ;
+    PORT
+      LAYER metal1 ;
+        RECT 0 1.6 1.14 1.8 ;
+    END
+  END VDD
+  OBS
+    LAYER metal1 ;
+      RECT 0.6 0.2 0.9 1.2 ;
+    LAYER metal2 ;
+      RECT 0.6 0.2 0.7 1.2 ;
+  END
+END NAND2X1
+
+END LIBRARY
+"#;
+    let (_, lef) = lef_parser::parse_lef(lef_content).unwrap();
+    let path = std::env::temp_dir().join("test_write_lef_round_trip.lef");
+    let path = path.to_str().unwrap();
+    lefdef_viewer::lef::writer::write_lef(&lef, path).unwrap();
+    let copy = lefdef_viewer::lef::reader::LefReader::new()
+        .read(path)
+        .unwrap();
+
+    assert_eq!(copy.database_microns, Some(2000.0));
+    assert_eq!(copy.manufacturing_grid, Some(0.005));
+    assert_eq!(copy.layers, lef.layers);
+    assert_eq!(copy.sites, lef.sites);
+
+    let (original, written) = (&lef.macros[0], &copy.macros[0]);
+    assert_eq!(written.name, "NAND2X1");
+    assert_eq!((written.size_x, written.size_y), (1.14, 1.71));
+    assert_eq!(written.symmetry, original.symmetry);
+    assert_eq!(written.site, "core");
+    assert_eq!(written.pins.len(), 2);
+    assert_eq!(written.pins[1].shape, "ABUTMENT");
+    assert_eq!(written.pins[0].ports[0].polygons[0].points.len(), 4);
+    assert_eq!(written.obs[0].rects.len(), 2);
+    assert_eq!(written.geometry_hash(), original.geometry_hash());
+
+    // Writing the reread library again gives the same text
+    assert_eq!(
+        lefdef_viewer::lef::writer::lef_to_string(&copy),
+        lefdef_viewer::lef::writer::lef_to_string(&lef)
+    );
+}
+
+#[test]
+fn test_json_round_trip() {
+    let lef_content = r#"
+VERSION 5.8 ;
+UNITS
+  DATABASE MICRONS 1000 ;
+END UNITS
+
+LAYER metal1
+  TYPE ROUTING ;
+  DIRECTION HORIZONTAL ;
+  PITCH 0.14 ;
+END metal1
+
+MACRO INVX1
+  CLASS CORE ;
+  SIZE 0.42 BY 1.4 ;
+  PIN A
+    DIRECTION INPUT ;
+    PORT
+      LAYER metal1 ;
+        RECT 0.05 0.5 0.15 0.7 ;
+    END
+  END A
+  OBS
+    LAYER metal1 ;
+      POLYGON 0.2 0.2 0.3 0.2 0.3 0.4 0.2 0.4 ;
+  END
+END INVX1
+
+END LIBRARY
+"#;
+    let (_, lef) = lef_parser::parse_lef(lef_content).unwrap();
+    let json = lef.to_json().unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["database_microns"], 1000.0);
+    assert_eq!(value["macros"][0]["name"], "INVX1");
+    assert_eq!(
+        value["macros"][0]["pins"][0]["ports"][0]["rects"][0]["xh"],
+        0.15
+    );
+
+    let copy = lefdef_viewer::lef::Lef::from_json(&json).unwrap();
+    assert_eq!(copy.to_json().unwrap(), json);
+    assert_eq!(copy.layers, lef.layers);
+}
+
+#[test]
+fn test_parse_str() {
+    let lef = lefdef_viewer::Lef::parse_str(
+        "VERSION 5.8 ;\nMACRO BUF\n  CLASS CORE ;\n  SIZE 1 BY 2 ;\nEND BUF\nEND LIBRARY\n",
+    )
+    .unwrap();
+    assert_eq!(lef.macros.len(), 1);
+    assert_eq!((lef.macros[0].size_x, lef.macros[0].size_y), (1.0, 2.0));
+
+    // Like in files, a broken MACRO is skipped rather than failing the library
+    let lef = lefdef_viewer::Lef::parse_str("VERSION 5.8 ;\nMACRO BUF\n  SIZE 1 BY\n").unwrap();
+    assert!(lef.macros.is_empty());
+}
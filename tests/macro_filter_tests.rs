@@ -17,6 +17,7 @@ fn create_test_lef_data() -> Lef {
                 site: String::new(),
                 pins: Vec::new(),
                 obs: Vec::new(),
+                ..LefMacro::default()
             },
             LefMacro {
                 name: "NAND_X2".to_string(),
@@ -29,6 +30,7 @@ fn create_test_lef_data() -> Lef {
                 site: String::new(),
                 pins: Vec::new(),
                 obs: Vec::new(),
+                ..LefMacro::default()
             },
             LefMacro {
                 name: "BUFFER_X4".to_string(),
@@ -41,6 +43,7 @@ fn create_test_lef_data() -> Lef {
                 site: String::new(),
                 pins: Vec::new(),
                 obs: Vec::new(),
+                ..LefMacro::default()
             },
             LefMacro {
                 name: "AND_X1".to_string(),
@@ -53,6 +56,7 @@ fn create_test_lef_data() -> Lef {
                 site: String::new(),
                 pins: Vec::new(),
                 obs: Vec::new(),
+                ..LefMacro::default()
             },
             LefMacro {
                 name: "CURRENT_SOURCE_1TO8".to_string(),
@@ -65,15 +69,20 @@ fn create_test_lef_data() -> Lef {
                 site: String::new(),
                 pins: Vec::new(),
                 obs: Vec::new(),
+                ..LefMacro::default()
             },
         ],
+        database_microns: None,
+        manufacturing_grid: None,
+        layers: Vec::new(),
+        sites: Vec::new(),
     }
 }
 
 #[test]
 fn test_macro_filter_empty() {
     let lef_data = create_test_lef_data();
-    let filter = "";
+    let filter = String::from("");
 
     let filtered: Vec<&LefMacro> = lef_data
         .macros
@@ -101,7 +110,7 @@ fn test_macro_filter_empty() {
 #[test]
 fn test_macro_filter_inverter() {
     let lef_data = create_test_lef_data();
-    let filter = "INVERTER";
+    let filter = String::from("INVERTER");
 
     let filtered: Vec<&LefMacro> = lef_data
         .macros
@@ -125,7 +134,7 @@ fn test_macro_filter_inverter() {
 #[test]
 fn test_macro_filter_case_insensitive() {
     let lef_data = create_test_lef_data();
-    let filter = "current";
+    let filter = String::from("current");
 
     let filtered: Vec<&LefMacro> = lef_data
         .macros
@@ -149,7 +158,7 @@ fn test_macro_filter_case_insensitive() {
 #[test]
 fn test_macro_filter_x1() {
     let lef_data = create_test_lef_data();
-    let filter = "X1";
+    let filter = String::from("X1");
 
     let filtered: Vec<&LefMacro> = lef_data
         .macros
@@ -174,7 +183,7 @@ fn test_macro_filter_x1() {
 #[test]
 fn test_macro_filter_no_match() {
     let lef_data = create_test_lef_data();
-    let filter = "NONEXISTENT";
+    let filter = String::from("NONEXISTENT");
 
     let filtered: Vec<&LefMacro> = lef_data
         .macros
@@ -197,7 +206,7 @@ fn test_macro_filter_no_match() {
 #[test]
 fn test_macro_filter_partial_match() {
     let lef_data = create_test_lef_data();
-    let filter = "X";
+    let filter = String::from("X");
 
     let filtered: Vec<&LefMacro> = lef_data
         .macros